@@ -0,0 +1,23 @@
+-- Positions and strategies, one row each. `data` holds the full serialized
+-- record; the other columns are copies of its fields for indexed lookups.
+
+CREATE TABLE IF NOT EXISTS positions (
+    id            TEXT PRIMARY KEY NOT NULL,
+    token_address TEXT NOT NULL,
+    strategy_id   TEXT NOT NULL,
+    status        TEXT NOT NULL,
+    entry_time    TEXT NOT NULL,
+    exit_time     TEXT,
+    data          TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_positions_token ON positions (token_address);
+CREATE INDEX IF NOT EXISTS idx_positions_strategy ON positions (strategy_id);
+CREATE INDEX IF NOT EXISTS idx_positions_status ON positions (status);
+
+CREATE TABLE IF NOT EXISTS strategies (
+    id      TEXT PRIMARY KEY NOT NULL,
+    name    TEXT NOT NULL,
+    enabled INTEGER NOT NULL,
+    data    TEXT NOT NULL
+);
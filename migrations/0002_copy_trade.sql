@@ -0,0 +1,29 @@
+-- Copy-trade state: registered copiers, trade signals, copy positions and
+-- source wallets. As with positions, `data` holds the full serialized record.
+
+CREATE TABLE IF NOT EXISTS copy_traders (
+    wallet_address TEXT PRIMARY KEY NOT NULL,
+    data           TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS trade_signals (
+    id        TEXT PRIMARY KEY NOT NULL,
+    timestamp TEXT NOT NULL,
+    data      TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS copy_positions (
+    id              TEXT PRIMARY KEY NOT NULL,
+    copier_wallet   TEXT NOT NULL,
+    bot_position_id TEXT NOT NULL,
+    status          TEXT NOT NULL,
+    data            TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_copy_positions_copier ON copy_positions (copier_wallet);
+CREATE INDEX IF NOT EXISTS idx_copy_positions_bot_position ON copy_positions (bot_position_id);
+
+CREATE TABLE IF NOT EXISTS copy_sources (
+    address TEXT PRIMARY KEY NOT NULL,
+    data    TEXT NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Store-level markers, e.g. that the JSON files were imported, so one-off steps
+-- don't repeat on later startups.
+
+CREATE TABLE IF NOT EXISTS meta (
+    key   TEXT PRIMARY KEY NOT NULL,
+    value TEXT NOT NULL
+);
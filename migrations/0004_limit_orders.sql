@@ -0,0 +1,12 @@
+-- Limit orders, pending and finished. As with positions, `data` holds the full
+-- serialized record.
+
+CREATE TABLE IF NOT EXISTS limit_orders (
+    id            TEXT PRIMARY KEY NOT NULL,
+    token_address TEXT NOT NULL,
+    status        TEXT NOT NULL,
+    created_at    TEXT NOT NULL,
+    data          TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_limit_orders_status ON limit_orders (status);
@@ -0,0 +1,15 @@
+-- Closed positions moved out of `positions` after POSITION_ARCHIVE_AFTER_DAYS.
+-- Same layout as `positions`; rowid keeps the order they were archived in.
+
+CREATE TABLE IF NOT EXISTS archived_positions (
+    id            TEXT PRIMARY KEY NOT NULL,
+    token_address TEXT NOT NULL,
+    strategy_id   TEXT NOT NULL,
+    status        TEXT NOT NULL,
+    entry_time    TEXT NOT NULL,
+    exit_time     TEXT,
+    data          TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_archived_positions_token ON archived_positions (token_address);
+CREATE INDEX IF NOT EXISTS idx_archived_positions_strategy ON archived_positions (strategy_id);
@@ -1,10 +1,12 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
+use crate::api::rate_limit::RateLimitBackoff;
+
 // Verified base URL
 const BIRDEYE_BASE_URL: &str = "https://public-api.birdeye.so";
 
@@ -86,6 +88,7 @@ pub struct TradeData {
 struct CachedValue {
     value: f64,
     fetched_at: Instant,
+    is_fallback: bool, // The $150 stand-in, not a real quote
 }
 
 pub struct BirdeyeClient {
@@ -93,6 +96,8 @@ pub struct BirdeyeClient {
     client: Client,
     /// Cached SOL price to avoid rate limit hits (TTL: 60 seconds)
     sol_price_cache: Mutex<Option<CachedValue>>,
+    /// Cooldown after rate-limited responses, shared with the other analysis APIs
+    rate_limit: Arc<RateLimitBackoff>,
 }
 
 // --- Response Structs ---
@@ -148,6 +153,51 @@ struct PriceData {
     // liquidity field might exist here too, but we only need value for SOL
 }
 
+// Structure for the /defi/ohlcv endpoint response
+#[derive(Debug, Deserialize)]
+struct OhlcvResponse {
+    data: Option<OhlcvData>,
+    success: bool,
+}
+#[derive(Debug, Deserialize)]
+struct OhlcvData {
+    items: Vec<Candle>,
+}
+
+/// One OHLCV candle from /defi/ohlcv
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Candle {
+    #[serde(rename = "unixTime")]
+    pub unix_time: i64,
+    #[serde(rename = "o")]
+    pub open: f64,
+    #[serde(rename = "h")]
+    pub high: f64,
+    #[serde(rename = "l")]
+    pub low: f64,
+    #[serde(rename = "c")]
+    pub close: f64,
+    #[serde(rename = "v", default)]
+    pub volume: f64,
+}
+
+// Structure for the /defi/history_price endpoint response
+#[derive(Debug, Deserialize)]
+struct PriceHistoryResponse {
+    data: Option<PriceHistoryData>,
+    success: bool,
+}
+#[derive(Debug, Deserialize)]
+struct PriceHistoryData {
+    items: Vec<PriceHistoryItem>,
+}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PriceHistoryItem {
+    unix_time: i64,
+    value: f64,
+}
+
 
 // --- Birdeye Client Implementation ---
 
@@ -160,9 +210,20 @@ impl BirdeyeClient {
                 .build()
                 .expect("Failed to create HTTP client for Birdeye"),
             sol_price_cache: Mutex::new(None),
+            rate_limit: Arc::new(RateLimitBackoff::default()),
         }
     }
 
+    /// Share a rate-limit backoff with other API clients
+    pub fn with_rate_limit_backoff(mut self, backoff: Arc<RateLimitBackoff>) -> Self {
+        self.rate_limit = backoff;
+        self
+    }
+
+    pub fn rate_limit_backoff(&self) -> &RateLimitBackoff {
+        &self.rate_limit
+    }
+
     /// Fetches the full token overview from the /defi/token_overview endpoint.
     pub async fn get_token_overview(&self, token_address: &str) -> Result<Option<TokenOverviewData>> {
         let endpoint = "/defi/token_overview";
@@ -182,8 +243,10 @@ impl BirdeyeClient {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
             warn!("Birdeye Token Overview API error for token {}: {} - {}", token_address, status, error_text);
+            self.rate_limit.record_response(status, &error_text, "Birdeye");
             return Ok(None);
         }
+        self.rate_limit.record_response(response.status(), "", "Birdeye");
 
         let response_data: TokenOverviewResponse = match response.json().await {
             Ok(data) => data,
@@ -234,9 +297,10 @@ impl BirdeyeClient {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
             warn!("Birdeye SOL Price API error: {} - {}; using fallback $150", status, error_text);
+            self.rate_limit.record_response(status, &error_text, "Birdeye");
             // Cache the fallback to avoid repeated failed API calls
             let mut cache = self.sol_price_cache.lock().unwrap();
-            *cache = Some(CachedValue { value: 150.0, fetched_at: Instant::now() });
+            *cache = Some(CachedValue { value: 150.0, fetched_at: Instant::now(), is_fallback: true });
             return Ok(150.0);
         }
 
@@ -244,10 +308,13 @@ impl BirdeyeClient {
             Ok(data) => data,
             Err(e) => {
                 warn!("Failed to parse Birdeye SOL Price API response: {:?}; using fallback $150", e);
+                let mut cache = self.sol_price_cache.lock().unwrap();
+                *cache = Some(CachedValue { value: 150.0, fetched_at: Instant::now(), is_fallback: true });
                 return Ok(150.0);
             }
         };
 
+        let is_fallback = response_data.data.is_none();
         let price = response_data.data.map(|d| d.value).unwrap_or(150.0);
 
         // Update cache
@@ -256,6 +323,7 @@ impl BirdeyeClient {
             *cache = Some(CachedValue {
                 value: price,
                 fetched_at: Instant::now(),
+                is_fallback,
             });
         }
         info!("SOL price updated: ${:.2}", price);
@@ -263,6 +331,117 @@ impl BirdeyeClient {
         Ok(price)
     }
 
+    /// SOL price in USD, or None while only the $150 fallback is available. For
+    /// values that get stored (USD cost basis), where a made-up price would stick.
+    pub async fn get_live_sol_price_usd(&self) -> Option<f64> {
+        let price = self.get_sol_price_usd().await.ok()?;
+        let live = self.sol_price_cache.lock().unwrap().as_ref().is_some_and(|c| !c.is_fallback);
+        live.then_some(price)
+    }
+
+    /// Percent price change of a token over the last `lookback_minutes`, from
+    /// the /defi/history_price endpoint. Returns None if there isn't enough history.
+    pub async fn get_price_change_percent(&self, token_address: &str, lookback_minutes: u64) -> Result<Option<f64>> {
+        let endpoint = "/defi/history_price";
+        let url = format!("{}{}", BIRDEYE_BASE_URL, endpoint);
+
+        let time_to = chrono::Utc::now().timestamp();
+        let time_from = time_to - (lookback_minutes as i64) * 60;
+        // 1m candles for short windows, 5m beyond an hour to keep the response small
+        let interval = if lookback_minutes <= 60 { "1m" } else { "5m" };
+
+        debug!("Fetching {}m price history from Birdeye for {}", lookback_minutes, token_address);
+
+        let response = self.client
+            .get(&url)
+            .header("X-API-KEY", &self.api_key)
+            .header("x-chain", "solana")
+            .query(&[
+                ("address", token_address),
+                ("address_type", "token"),
+                ("type", interval),
+                ("time_from", &time_from.to_string()),
+                ("time_to", &time_to.to_string()),
+            ])
+            .send()
+            .await
+            .context("Failed to send request to Birdeye Price History API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            warn!("Birdeye Price History API error for {}: {} - {}", token_address, status, error_text);
+            self.rate_limit.record_response(status, &error_text, "Birdeye");
+            return Ok(None);
+        }
+        self.rate_limit.record_response(response.status(), "", "Birdeye");
+
+        let response_data: PriceHistoryResponse = match response.json().await {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("Failed to parse Birdeye Price History response for {}: {:?}", token_address, e);
+                return Ok(None);
+            }
+        };
+
+        if !response_data.success {
+            warn!("Birdeye Price History API reported failure for {}", token_address);
+            return Ok(None);
+        }
+
+        let mut items = response_data.data.map(|d| d.items).unwrap_or_default();
+        items.sort_by_key(|i| i.unix_time);
+        match (items.first(), items.last()) {
+            (Some(first), Some(last)) if items.len() >= 2 && first.value > 0.0 => {
+                Ok(Some((last.value - first.value) / first.value * 100.0))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// OHLCV candles for a token between two unix times, oldest first, priced in SOL
+    /// (`currency=native`). Birdeye returns at most 1000 candles per request, so a
+    /// long range needs a wider interval.
+    pub async fn get_ohlcv(&self, token_address: &str, interval: &str, time_from: i64, time_to: i64) -> Result<Vec<Candle>> {
+        let endpoint = "/defi/ohlcv";
+        let url = format!("{}{}", BIRDEYE_BASE_URL, endpoint);
+
+        debug!("Fetching {} candles from Birdeye for {} ({}..{})", interval, token_address, time_from, time_to);
+
+        let response = self.client
+            .get(&url)
+            .header("X-API-KEY", &self.api_key)
+            .header("x-chain", "solana")
+            .query(&[
+                ("address", token_address),
+                ("type", interval),
+                ("currency", "native"),
+                ("time_from", &time_from.to_string()),
+                ("time_to", &time_to.to_string()),
+            ])
+            .send()
+            .await
+            .context("Failed to send request to Birdeye OHLCV API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            self.rate_limit.record_response(status, &error_text, "Birdeye");
+            return Err(anyhow!("Birdeye OHLCV API error for {}: {} - {}", token_address, status, error_text));
+        }
+        self.rate_limit.record_response(response.status(), "", "Birdeye");
+
+        let response_data: OhlcvResponse = response.json().await
+            .context("Failed to parse Birdeye OHLCV response")?;
+        if !response_data.success {
+            return Err(anyhow!("Birdeye OHLCV API reported failure for {}", token_address));
+        }
+
+        let mut candles = response_data.data.map(|d| d.items).unwrap_or_default();
+        candles.sort_by_key(|c| c.unix_time);
+        Ok(candles)
+    }
+
     // ========================================================================
     // V3 API Methods (for Final Stretch / Migrated strategies)
     // ========================================================================
@@ -286,6 +465,7 @@ impl BirdeyeClient {
         // Check for rate limiting
         if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
             warn!("Birdeye API rate limit hit for market-data");
+            self.rate_limit.record_response(response.status(), "", "Birdeye");
             return Ok(None);
         }
 
@@ -293,8 +473,10 @@ impl BirdeyeClient {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
             warn!("Birdeye Market Data API error for {}: {} - {}", mint, status, error_text);
+            self.rate_limit.record_response(status, &error_text, "Birdeye");
             return Ok(None);
         }
+        self.rate_limit.record_response(response.status(), "", "Birdeye");
 
         let response_data: MarketDataResponse = match response.json().await {
             Ok(data) => data,
@@ -343,6 +525,7 @@ impl BirdeyeClient {
                     continue;
                 } else {
                     warn!("Birdeye API rate limit hit for trade-data after {} retries", max_retries);
+                    self.rate_limit.record_response(response.status(), "", "Birdeye");
                     return Ok(None);
                 }
             }
@@ -358,8 +541,10 @@ impl BirdeyeClient {
                     continue;
                 }
                 warn!("Birdeye Trade Data API error for {}: {} - {}", mint, status, error_text);
+                self.rate_limit.record_response(status, &error_text, "Birdeye");
                 return Ok(None);
             }
+            self.rate_limit.record_response(response.status(), "", "Birdeye");
 
             let response_data: TradeDataResponse = match response.json().await {
                 Ok(data) => data,
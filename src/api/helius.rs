@@ -1,9 +1,11 @@
 use anyhow::{Context, Result};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, error, info};
 
+use crate::api::rate_limit::RateLimitBackoff;
 use crate::models::token::TokenMetadata;
 
 const HELIUS_RPC_URL: &str = "https://mainnet.helius-rpc.com";
@@ -12,6 +14,8 @@ const HELIUS_RPC_URL: &str = "https://mainnet.helius-rpc.com";
 pub struct HeliusClient {
     api_key: String,
     client: Client,
+    /// Cooldown after rate-limited responses, shared with the other analysis APIs
+    rate_limit: Arc<RateLimitBackoff>,
 }
 
 /// JSON-RPC request wrapper for Helius DAS API
@@ -196,16 +200,32 @@ impl HeliusClient {
                 .timeout(Duration::from_secs(30))
                 .build()
                 .expect("Failed to create HTTP client"),
+            rate_limit: Arc::new(RateLimitBackoff::default()),
         }
     }
+
+    /// Share a rate-limit backoff with other API clients
+    pub fn with_rate_limit_backoff(mut self, backoff: Arc<RateLimitBackoff>) -> Self {
+        self.rate_limit = backoff;
+        self
+    }
     
     pub async fn search_assets(&self, owner_address: Option<&str>, limit: Option<u32>) -> Result<Vec<DasAsset>> {
+        self.search(owner_address, None, limit).await
+    }
+
+    /// Assets whose verified creators include `creator_address`, newest first
+    pub async fn search_assets_by_creator(&self, creator_address: &str, limit: u32) -> Result<Vec<DasAsset>> {
+        self.search(None, Some(creator_address), Some(limit)).await
+    }
+
+    async fn search(&self, owner_address: Option<&str>, creator_address: Option<&str>, limit: Option<u32>) -> Result<Vec<DasAsset>> {
         // Use JSON-RPC format for Helius DAS API
         let url = format!("{}/?api-key={}", HELIUS_RPC_URL, self.api_key);
 
         let params = SearchAssetsRequest {
             ownerAddress: owner_address.map(String::from),
-            creatorAddress: None,
+            creatorAddress: creator_address.map(String::from),
             limit: Some(limit.unwrap_or(100)),
             page: Some(1),
             before: None,
@@ -242,8 +262,10 @@ impl HeliusClient {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
             error!("Helius DAS API error: {} - {}", status, error_text);
+            self.rate_limit.record_response(status, &error_text, "Helius");
             anyhow::bail!("Helius DAS API error: {} - {}", status, error_text);
         }
+        self.rate_limit.record_response(response.status(), "", "Helius");
 
         // JSON-RPC response format
         #[derive(Debug, Deserialize)]
@@ -309,6 +331,7 @@ impl HeliusClient {
                         supply: asset.supply.map(|s| s.print_current_supply as u64), // Placeholder
                         logo_uri: asset.content.as_ref()?.links.as_ref()?.image.clone(), // Placeholder
                         creation_time: None, // Placeholder: Needs actual data
+                        creator: asset.creators.first().map(|c| c.address.clone()),
                     })
                 } else {
                     None
@@ -323,11 +346,41 @@ impl HeliusClient {
     
     /// Gets detailed token metadata for a specific token address
     pub async fn get_token_metadata(&self, token_address: &str) -> Result<TokenMetadata> {
+        debug!("Fetching token metadata for: {}", token_address);
+        let asset = self.get_asset(token_address).await?;
+
+        // Convert DAS asset to TokenMetadata
+        let metadata = asset.content.as_ref()
+            .and_then(|c| c.metadata.as_ref())
+            .ok_or_else(|| anyhow::anyhow!("No metadata found for token {}", token_address))?;
+
+        let token_name = metadata.name.clone().unwrap_or_else(|| "Unknown Token".to_string());
+        let token_symbol = metadata.symbol.clone().unwrap_or_else(|| "UNK".to_string());
+
+        // For SPL tokens, we need to get additional info like decimals and supply
+        // This might require additional API calls or we use defaults
+        let decimals = 9; // Default for most SPL tokens
+        let supply = asset.supply.as_ref().map(|s| s.print_current_supply as u64);
+
+        Ok(TokenMetadata {
+            address: asset.id.clone(),
+            name: token_name,
+            symbol: token_symbol,
+            decimals,
+            supply,
+            logo_uri: asset.content.as_ref()
+                .and_then(|c| c.links.as_ref())
+                .and_then(|l| l.image.clone()),
+            creation_time: None, // Would need additional logic to determine creation time
+            creator: asset.creators.first().map(|c| c.address.clone()),
+        })
+    }
+
+    /// The raw DAS asset for a mint (creators, authorities, supply, metadata)
+    pub async fn get_asset(&self, token_address: &str) -> Result<DasAsset> {
         // Use JSON-RPC format for Helius DAS API
         let url = format!("{}/?api-key={}", HELIUS_RPC_URL, self.api_key);
 
-        debug!("Fetching token metadata for: {}", token_address);
-
         #[derive(Serialize)]
         struct GetAssetParams {
             id: String,
@@ -353,8 +406,10 @@ impl HeliusClient {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
             error!("Helius getAsset API error: {} - {}", status, error_text);
+            self.rate_limit.record_response(status, &error_text, "Helius");
             anyhow::bail!("Helius getAsset API error: {} - {}", status, error_text);
         }
+        self.rate_limit.record_response(response.status(), "", "Helius");
 
         #[derive(Debug, Deserialize)]
         struct JsonRpcAssetResponse {
@@ -366,32 +421,7 @@ impl HeliusClient {
             .await
             .context("Failed to parse Helius getAsset API response")?;
 
-        let asset = asset_response_wrapper.result;
-
-        // Convert DAS asset to TokenMetadata
-        let metadata = asset.content.as_ref()
-            .and_then(|c| c.metadata.as_ref())
-            .ok_or_else(|| anyhow::anyhow!("No metadata found for token {}", token_address))?;
-
-        let token_name = metadata.name.clone().unwrap_or_else(|| "Unknown Token".to_string());
-        let token_symbol = metadata.symbol.clone().unwrap_or_else(|| "UNK".to_string());
-
-        // For SPL tokens, we need to get additional info like decimals and supply
-        // This might require additional API calls or we use defaults
-        let decimals = 9; // Default for most SPL tokens
-        let supply = asset.supply.map(|s| s.print_current_supply as u64);
-
-        Ok(TokenMetadata {
-            address: asset.id,
-            name: token_name,
-            symbol: token_symbol,
-            decimals,
-            supply,
-            logo_uri: asset.content.as_ref()
-                .and_then(|c| c.links.as_ref())
-                .and_then(|l| l.image.clone()),
-            creation_time: None, // Would need additional logic to determine creation time
-        })
+        Ok(asset_response_wrapper.result)
     }
 
     // TODO: Implement methods for:
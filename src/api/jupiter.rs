@@ -11,22 +11,72 @@ use solana_transaction_status::{
 };
 use std::{
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
     str::FromStr,
 };
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tracing::{debug, error, info, warn};
 
+use crate::solana::jito::{JitoClient, JitoTip};
 use crate::solana::wallet::WalletManager;
 use crate::error::TraderbotError;
 use crate::solana::client::SolanaClient;
+use crate::api::raydium::{PoolSnapshot, RaydiumClient};
+use crate::api::swap_breaker::SwapCircuitBreaker;
+use crate::api::swap_error::{SwapError, SwapFailureStats};
+use crate::trading::pumpfun_swap;
+use crate::trading::strategy::ExecutionVenue;
 
 const JUPITER_BASE_URL: &str = "https://quote-api.jup.ag/v6";
+/// Trigger (limit order) API: orders sit on-chain and fill without the bot running
+const JUPITER_TRIGGER_URL: &str = "https://api.jup.ag/trigger/v1";
+/// How many times a stale quote is re-fetched before we give up and send anyway.
+const MAX_QUOTE_REFRESHES: u32 = 2;
 pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+/// Base signature fee per transaction
+const BASE_TX_FEE_LAMPORTS: u64 = 5_000;
+/// Typical compute units used by a routed swap, for priority fee estimates
+const ESTIMATED_SWAP_COMPUTE_UNITS: u64 = 200_000;
+
+/// Where and how a swap is sent: the venue to try first and an optional Jito tip
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SwapRoute {
+    pub venue: ExecutionVenue,
+    pub jito_tip_lamports: Option<u64>, // Send as a Jito bundle with this tip
+    pub bonding_curve: bool,            // Trade on the pump.fun curve even if curve trading is off globally
+}
+
+impl SwapRoute {
+    pub fn new(venue: ExecutionVenue, jito_tip_lamports: Option<u64>) -> Self {
+        Self { venue, jito_tip_lamports, bonding_curve: false }
+    }
+
+    pub fn with_bonding_curve(mut self, enabled: bool) -> Self {
+        self.bonding_curve = enabled;
+        self
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct JupiterClient {
     client: Client,
     api_key: Option<String>,
+    /// Maximum age of a quote when its swap transaction is sent. None disables the guard.
+    quote_max_age: Option<Duration>,
+    /// Failed swaps counted by category (shared across clones)
+    failure_stats: Arc<SwapFailureStats>,
+    /// In-flight buy swaps allowed at once (None = unlimited); excess buys queue
+    buy_permits: Option<Arc<Semaphore>>,
+    /// Separate budget for exit swaps so buys can't starve a panic-close
+    exit_permits: Option<Arc<Semaphore>>,
+    /// Trade pre-graduation pump.fun tokens on their bonding curve
+    bonding_curve_trading: bool,
+    /// Pauses new buys after a run of failed swaps (buys and exits alike)
+    swap_breaker: Arc<SwapCircuitBreaker>,
+    /// Block engine for swaps sent as Jito bundles (None = always send through the RPC)
+    jito: Option<Arc<JitoClient>>,
+    /// Pool discovery for swaps routed straight to Raydium (shared across clones)
+    raydium: Arc<RaydiumClient>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -116,6 +166,69 @@ pub struct SwapResponse {
     pub prioritization_fee_lamports: Option<u64>,
 }
 
+/// What a SOL -> token buy would return right now, without sending anything
+#[derive(Debug, Clone, Serialize)]
+pub struct BuyPreview {
+    pub expected_tokens: f64,
+    pub min_tokens: f64, // After worst-case slippage
+    pub price_impact_pct: f64,
+    pub slippage_bps: u32,
+    pub estimated_fee_sol: f64, // Base fee + priority fee at a typical swap compute budget
+    pub route: String,
+}
+
+/// What a token -> SOL sell would return right now, without sending anything
+#[derive(Debug, Clone, Serialize)]
+pub struct SellPreview {
+    pub expected_sol: f64,
+    pub min_sol: f64, // After worst-case slippage
+    pub price_impact_pct: f64,
+    pub slippage_bps: u32,
+    pub estimated_fee_sol: f64, // Base fee + priority fee at a typical swap compute budget
+    pub route: String,
+}
+
+/// Which way a previewed swap goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SwapDirection {
+    Buy,  // SOL -> token
+    Sell, // token -> SOL
+}
+
+/// A preview quote in UI units of the output side, shared by `BuyPreview` and `SellPreview`
+struct SwapPreview {
+    expected_out: f64,
+    min_out: f64,
+    price_impact_pct: f64,
+    slippage_bps: u32,
+    estimated_fee_sol: f64,
+    route: String,
+}
+
+/// Buy quote for a size and the sell quote for the tokens it would get back
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RoundTripQuote {
+    pub sol_in: f64,
+    pub sol_back: f64,
+    pub token_amount_raw: u64,
+}
+
+/// Unsigned transaction from the Trigger API (create or cancel)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TriggerOrderResponse {
+    #[serde(default)]
+    order: Option<String>, // Order account (createOrder only)
+    transaction: String,   // Base64 VersionedTransaction for the maker to sign
+}
+
+/// A limit sell placed on-chain, and the signature of the transaction that placed it
+#[derive(Debug, Clone)]
+pub struct PlacedTriggerOrder {
+    pub order: String,
+    pub signature: Signature,
+}
+
 #[derive(Debug, Clone)]
 pub struct SwapResult {
     pub input_mint: String,
@@ -128,13 +241,117 @@ pub struct SwapResult {
 }
 
 impl JupiterClient {
-    pub fn new(api_key: Option<String>) -> Self {
+    pub fn new(api_key: Option<String>, quote_max_age_ms: u64) -> Self {
         Self {
             client: Client::builder()
                 .timeout(Duration::from_secs(30))
                 .build()
                 .expect("Failed to create HTTP client"),
             api_key,
+            quote_max_age: (quote_max_age_ms > 0).then(|| Duration::from_millis(quote_max_age_ms)),
+            failure_stats: Arc::new(SwapFailureStats::default()),
+            buy_permits: None,
+            exit_permits: None,
+            bonding_curve_trading: false,
+            swap_breaker: Arc::new(SwapCircuitBreaker::default()),
+            jito: None,
+            raydium: Arc::new(RaydiumClient::new()),
+        }
+    }
+
+    /// Send swaps that ask for a Jito tip as bundles through this block engine
+    pub fn with_jito(mut self, jito: Arc<JitoClient>) -> Self {
+        self.jito = Some(jito);
+        self
+    }
+
+    /// The bundle tip for a swap asking for `tip_lamports`, if Jito is set up
+    fn jito_tip(&self, tip_lamports: Option<u64>) -> Option<JitoTip> {
+        let lamports = tip_lamports.filter(|l| *l > 0)?;
+        match &self.jito {
+            Some(client) => Some(JitoTip { client: client.clone(), lamports }),
+            None => {
+                warn!("Jito tip requested but no block engine is configured; sending through the RPC");
+                None
+            }
+        }
+    }
+
+    /// Trip a circuit breaker on consecutive swap failures
+    pub fn with_swap_breaker(mut self, breaker: Arc<SwapCircuitBreaker>) -> Self {
+        self.swap_breaker = breaker;
+        self
+    }
+
+    pub fn swap_breaker(&self) -> &Arc<SwapCircuitBreaker> {
+        &self.swap_breaker
+    }
+
+    /// Route swaps of pump.fun tokens that haven't graduated to their bonding curve
+    pub fn with_bonding_curve_trading(mut self, enabled: bool) -> Self {
+        self.bonding_curve_trading = enabled;
+        self
+    }
+
+    /// The token's bonding curve, if curve trading is on (globally or for this
+    /// route) and it hasn't graduated
+    async fn curve_route(&self, route: SwapRoute, token_mint: &str, wallet_manager: &WalletManager) -> Option<pumpfun_swap::ActiveCurve> {
+        if !self.bonding_curve_trading && !route.bonding_curve {
+            return None;
+        }
+        pumpfun_swap::active_curve(&wallet_manager.solana_client(), token_mint).await
+    }
+
+    /// The token's Raydium pool when the route asks for Raydium; None falls back to Jupiter
+    async fn raydium_route(&self, route: SwapRoute, token_mint: &str, wallet_manager: &WalletManager) -> Option<PoolSnapshot> {
+        if route.venue != ExecutionVenue::Raydium {
+            return None;
+        }
+        let pool = self.raydium.pool_for(&wallet_manager.solana_client(), token_mint).await;
+        if pool.is_none() {
+            debug!("No swappable Raydium pool for {}, routing through Jupiter", token_mint);
+        }
+        pool
+    }
+
+    /// Cap concurrent in-flight buy and exit swaps (0 = unlimited)
+    pub fn with_swap_limits(mut self, max_buys: usize, max_exits: usize) -> Self {
+        self.buy_permits = (max_buys > 0).then(|| Arc::new(Semaphore::new(max_buys)));
+        self.exit_permits = (max_exits > 0).then(|| Arc::new(Semaphore::new(max_exits)));
+        self
+    }
+
+    /// Wait for a swap slot; the permit is released when dropped
+    async fn acquire_swap_permit(permits: &Option<Arc<Semaphore>>, side: &str, token_mint: &str) -> Option<OwnedSemaphorePermit> {
+        let permits = permits.as_ref()?;
+        if permits.available_permits() == 0 {
+            info!("Max concurrent {} swaps in flight, queueing {}", side, token_mint);
+        }
+        permits.clone().acquire_owned().await.ok()
+    }
+
+    /// Swap failure counts by category since startup
+    pub fn failure_stats(&self) -> &SwapFailureStats {
+        &self.failure_stats
+    }
+
+    /// Categorize and count a failed swap, tagging the error with its category
+    fn record_failure(&self, err: anyhow::Error) -> anyhow::Error {
+        let kind = SwapError::classify(&err);
+        self.failure_stats.record(kind);
+        self.swap_breaker.record_failure(kind);
+        warn!("Swap failed [{}]: {:#}", kind, err);
+        err.context(format!("Swap failed [{}]", kind))
+    }
+
+    /// Count a finished swap toward the stats and the circuit breaker
+    fn record_outcome(&self, result: Result<SwapResult>) -> Result<SwapResult> {
+        match result {
+            Ok(swap) => {
+                self.swap_breaker.record_success();
+                Ok(swap)
+            }
+            Err(e) => Err(self.record_failure(e)),
         }
     }
 
@@ -190,6 +407,114 @@ impl JupiterClient {
         Ok(quote)
     }
 
+    /// Quote a swap between SOL and a token and estimate its fees, without building a
+    /// transaction. `amount_in_ui` is in UI units of the input side.
+    async fn preview_swap(
+        &self,
+        direction: SwapDirection,
+        token_mint: &str,
+        token_decimals: u8,
+        amount_in_ui: f64,
+        slippage_bps: u32,
+        priority_fee_micro_lamports: Option<u64>,
+    ) -> Result<SwapPreview> {
+        let token_scale = 10f64.powi(token_decimals as i32);
+        let (input_mint, output_mint, in_scale, out_scale) = match direction {
+            SwapDirection::Buy => (SOL_MINT, token_mint, 1_000_000_000.0, token_scale),
+            SwapDirection::Sell => (token_mint, SOL_MINT, token_scale, 1_000_000_000.0),
+        };
+        let amount_raw = (amount_in_ui * in_scale) as u64;
+        if amount_raw == 0 {
+            return Err(match direction {
+                SwapDirection::Buy => anyhow!("Input SOL amount is too small or zero"),
+                SwapDirection::Sell => anyhow!("Token amount is too small or zero"),
+            });
+        }
+
+        let quote = self.get_quote(input_mint, output_mint, amount_raw, slippage_bps).await?;
+        let expected_out = quote.out_amount.parse::<u64>().context("Failed to parse quote out_amount")? as f64 / out_scale;
+        let min_out = quote.other_amount_threshold.parse::<u64>().unwrap_or(0) as f64 / out_scale;
+        let price_impact_pct = quote.price_impact_pct.as_deref().unwrap_or("0.0").parse::<f64>().unwrap_or(0.0);
+        let priority_lamports = priority_fee_micro_lamports.unwrap_or(0) * ESTIMATED_SWAP_COMPUTE_UNITS / 1_000_000;
+        let route = quote.route_plan.iter()
+            .map(|r| r.swap_info.label.as_str())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+
+        Ok(SwapPreview {
+            expected_out,
+            min_out,
+            price_impact_pct,
+            slippage_bps: quote.slippage_bps,
+            estimated_fee_sol: (BASE_TX_FEE_LAMPORTS + priority_lamports) as f64 / 1_000_000_000.0,
+            route,
+        })
+    }
+
+    /// Quote a SOL -> token buy and estimate its fees, without building a transaction
+    pub async fn preview_buy(
+        &self,
+        token_mint: &str,
+        token_decimals: u8,
+        amount_sol: f64,
+        slippage_bps: u32,
+        priority_fee_micro_lamports: Option<u64>,
+    ) -> Result<BuyPreview> {
+        let preview = self.preview_swap(
+            SwapDirection::Buy, token_mint, token_decimals, amount_sol, slippage_bps, priority_fee_micro_lamports,
+        ).await?;
+        Ok(BuyPreview {
+            expected_tokens: preview.expected_out,
+            min_tokens: preview.min_out,
+            price_impact_pct: preview.price_impact_pct,
+            slippage_bps: preview.slippage_bps,
+            estimated_fee_sol: preview.estimated_fee_sol,
+            route: preview.route,
+        })
+    }
+
+    /// Quote buying `amount_sol` of a token and selling the quoted amount straight back.
+    /// A sell that routes but returns far less than went in is a soft honeypot.
+    pub async fn quote_round_trip(&self, token_mint: &str, amount_sol: f64, slippage_bps: u32) -> Result<RoundTripQuote> {
+        let lamports_in = (amount_sol * 1_000_000_000.0) as u64;
+        if lamports_in == 0 { return Err(anyhow!("Input SOL amount is too small or zero")); }
+
+        let buy = self.get_quote(SOL_MINT, token_mint, lamports_in, slippage_bps).await
+            .context("Round-trip buy quote failed")?;
+        let token_amount_raw = buy.out_amount.parse::<u64>().context("Failed to parse quote out_amount")?;
+        let sell = self.get_quote(token_mint, SOL_MINT, token_amount_raw, slippage_bps).await
+            .context("Round-trip sell quote failed")?;
+        let lamports_back = sell.out_amount.parse::<u64>().context("Failed to parse quote out_amount")?;
+
+        Ok(RoundTripQuote {
+            sol_in: lamports_in as f64 / 1_000_000_000.0,
+            sol_back: lamports_back as f64 / 1_000_000_000.0,
+            token_amount_raw,
+        })
+    }
+
+    /// Quote selling `token_amount_ui` of a token for SOL; the exit-side `preview_buy`
+    pub async fn preview_sell(
+        &self,
+        token_mint: &str,
+        token_decimals: u8,
+        token_amount_ui: f64,
+        slippage_bps: u32,
+        priority_fee_micro_lamports: Option<u64>,
+    ) -> Result<SellPreview> {
+        let preview = self.preview_swap(
+            SwapDirection::Sell, token_mint, token_decimals, token_amount_ui, slippage_bps, priority_fee_micro_lamports,
+        ).await?;
+        Ok(SellPreview {
+            expected_sol: preview.expected_out,
+            min_sol: preview.min_out,
+            price_impact_pct: preview.price_impact_pct,
+            slippage_bps: preview.slippage_bps,
+            estimated_fee_sol: preview.estimated_fee_sol,
+            route: preview.route,
+        })
+    }
+
     pub async fn get_swap_transaction(
         &self,
         quote: &QuoteResponse,
@@ -230,6 +555,46 @@ impl JupiterClient {
         Ok(swap_response)
     }
 
+    /// Fetches a quote and its swap transaction, re-fetching the quote if it aged past
+    /// `quote_max_age` while the swap transaction was being built. Returns the quote the
+    /// transaction was actually built from.
+    async fn get_fresh_quote_and_swap(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount_lamports: u64,
+        slippage_bps: u32,
+        user_public_key: &str,
+        priority_fee_micro_lamports: Option<u64>,
+    ) -> Result<(QuoteResponse, SwapResponse)> {
+        let mut refreshes = 0;
+        loop {
+            let quote = self.get_quote(input_mint, output_mint, amount_lamports, slippage_bps).await
+                .context("Failed to get quote")?;
+            let quoted_at = Instant::now();
+            let swap_response = self.get_swap_transaction(&quote, user_public_key, priority_fee_micro_lamports).await
+                .context("Failed to get swap transaction")?;
+            let quote_age = quoted_at.elapsed();
+
+            match self.quote_max_age {
+                Some(max_age) if quote_age > max_age => {
+                    if refreshes >= MAX_QUOTE_REFRESHES {
+                        warn!("Quote still stale after {} refreshes (age {}ms > {}ms), sending anyway",
+                              refreshes, quote_age.as_millis(), max_age.as_millis());
+                        return Ok((quote, swap_response));
+                    }
+                    refreshes += 1;
+                    warn!("Quote is stale (age {}ms > {}ms), re-fetching ({}/{})",
+                          quote_age.as_millis(), max_age.as_millis(), refreshes, MAX_QUOTE_REFRESHES);
+                }
+                _ => {
+                    info!("Quote age at send: {}ms", quote_age.as_millis());
+                    return Ok((quote, swap_response));
+                }
+            }
+        }
+    }
+
     pub async fn swap_sol_to_token(
         &self,
         token_mint: &str,
@@ -237,14 +602,41 @@ impl JupiterClient {
         amount_sol: f64,
         slippage_bps: u32,
         priority_fee_micro_lamports: Option<u64>,
+        route: SwapRoute,
+        wallet_manager: Arc<WalletManager>,
+    ) -> Result<SwapResult> {
+        let jito = self.jito_tip(route.jito_tip_lamports);
+        let _permit = Self::acquire_swap_permit(&self.buy_permits, "buy", token_mint).await;
+        if let Some(curve) = self.curve_route(route, token_mint, &wallet_manager).await {
+            let result = pumpfun_swap::buy_on_curve(&curve, token_decimals, amount_sol, slippage_bps, priority_fee_micro_lamports, jito.as_ref(), wallet_manager).await;
+            return self.record_outcome(result);
+        }
+        if let Some(pool) = self.raydium_route(route, token_mint, &wallet_manager).await {
+            let result = pool.buy(token_decimals, amount_sol, slippage_bps, priority_fee_micro_lamports, jito.as_ref(), wallet_manager).await;
+            return self.record_outcome(result);
+        }
+        let result = self.execute_sol_to_token(token_mint, token_decimals, amount_sol, slippage_bps, priority_fee_micro_lamports, jito.as_ref(), wallet_manager).await;
+        self.record_outcome(result)
+    }
+
+    async fn execute_sol_to_token(
+        &self,
+        token_mint: &str,
+        token_decimals: u8,
+        amount_sol: f64,
+        slippage_bps: u32,
+        priority_fee_micro_lamports: Option<u64>,
+        jito: Option<&JitoTip>,
         wallet_manager: Arc<WalletManager>,
     ) -> Result<SwapResult> {
         info!("Initiating swap: {:.6} SOL to Token {}", amount_sol, token_mint);
         let lamports_in = (amount_sol * 1_000_000_000.0) as u64;
         if lamports_in == 0 { return Err(anyhow!("Input SOL amount is too small or zero")); }
 
-        let quote = self.get_quote(SOL_MINT, token_mint, lamports_in, slippage_bps).await
-            .context("Failed to get quote for SOL to token swap")?;
+        let user_public_key = wallet_manager.get_public_key().to_string();
+        let (quote, swap_response) = self.get_fresh_quote_and_swap(
+            SOL_MINT, token_mint, lamports_in, slippage_bps, &user_public_key, priority_fee_micro_lamports
+        ).await.context("Failed to prepare SOL to token swap")?;
         let estimated_out_lamports = quote.out_amount.parse::<u64>()
             .context("Failed to parse quote out_amount")?;
         let estimated_out_ui = estimated_out_lamports as f64 / 10f64.powi(token_decimals as i32);
@@ -252,19 +644,16 @@ impl JupiterClient {
         info!("Quote received: {:.6} SOL -> {:.6} {} (Price Impact: {:.4}%)", 
               amount_sol, estimated_out_ui, token_mint, price_impact);
 
-        let user_public_key = wallet_manager.get_public_key().to_string();
-        let swap_response = self.get_swap_transaction(&quote, &user_public_key, priority_fee_micro_lamports).await
-            .context("Failed to get swap transaction")?;
-
         let transaction_bytes = STANDARD.decode(&swap_response.swap_transaction)
             .context("Failed to decode swap transaction")?;
         let versioned_tx: VersionedTransaction = bincode::deserialize(&transaction_bytes)
             .context("Failed to deserialize VersionedTransaction")?;
 
         info!("Sending swap transaction...");
-        let signature = wallet_manager.sign_and_send_versioned_transaction(
-            versioned_tx, 
-            swap_response.last_valid_block_height
+        let signature = wallet_manager.send_swap_transaction(
+            versioned_tx,
+            swap_response.last_valid_block_height,
+            jito,
         ).await.context("Failed to sign and send swap transaction")?;
         info!("Swap transaction sent: {}", signature);
 
@@ -294,14 +683,41 @@ impl JupiterClient {
         token_amount_ui: f64,
         slippage_bps: u32,
         priority_fee_micro_lamports: Option<u64>,
+        route: SwapRoute,
+        wallet_manager: Arc<WalletManager>,
+    ) -> Result<SwapResult> {
+        let jito = self.jito_tip(route.jito_tip_lamports);
+        let _permit = Self::acquire_swap_permit(&self.exit_permits, "exit", token_mint).await;
+        if let Some(curve) = self.curve_route(route, token_mint, &wallet_manager).await {
+            let result = pumpfun_swap::sell_on_curve(&curve, token_decimals, token_amount_ui, slippage_bps, priority_fee_micro_lamports, jito.as_ref(), wallet_manager).await;
+            return self.record_outcome(result);
+        }
+        if let Some(pool) = self.raydium_route(route, token_mint, &wallet_manager).await {
+            let result = pool.sell(token_decimals, token_amount_ui, slippage_bps, priority_fee_micro_lamports, jito.as_ref(), wallet_manager).await;
+            return self.record_outcome(result);
+        }
+        let result = self.execute_token_to_sol(token_mint, token_decimals, token_amount_ui, slippage_bps, priority_fee_micro_lamports, jito.as_ref(), wallet_manager).await;
+        self.record_outcome(result)
+    }
+
+    async fn execute_token_to_sol(
+        &self,
+        token_mint: &str,
+        token_decimals: u8,
+        token_amount_ui: f64,
+        slippage_bps: u32,
+        priority_fee_micro_lamports: Option<u64>,
+        jito: Option<&JitoTip>,
         wallet_manager: Arc<WalletManager>,
     ) -> Result<SwapResult> {
         info!("Initiating swap: {:.6} Token {} to SOL", token_amount_ui, token_mint);
         let token_amount_lamports = (token_amount_ui * 10f64.powi(token_decimals as i32)) as u64;
         if token_amount_lamports == 0 { return Err(anyhow!("Input token amount is too small or zero")); }
 
-        let quote = self.get_quote(token_mint, SOL_MINT, token_amount_lamports, slippage_bps).await
-            .context("Failed to get quote for token to SOL swap")?;
+        let user_public_key = wallet_manager.get_public_key().to_string();
+        let (quote, swap_response) = self.get_fresh_quote_and_swap(
+            token_mint, SOL_MINT, token_amount_lamports, slippage_bps, &user_public_key, priority_fee_micro_lamports
+        ).await.context("Failed to prepare token to SOL swap")?;
         let estimated_out_lamports = quote.out_amount.parse::<u64>()
             .context("Failed to parse quote out_amount")?;
         let estimated_out_ui = estimated_out_lamports as f64 / 1_000_000_000.0;
@@ -309,19 +725,16 @@ impl JupiterClient {
         info!("Quote received: {:.6} {} -> {:.6} SOL (Price Impact: {:.4}%)", 
               token_amount_ui, token_mint, estimated_out_ui, price_impact);
 
-        let user_public_key = wallet_manager.get_public_key().to_string();
-        let swap_response = self.get_swap_transaction(&quote, &user_public_key, priority_fee_micro_lamports).await
-            .context("Failed to get swap transaction")?;
-
         let transaction_bytes = STANDARD.decode(&swap_response.swap_transaction)
             .context("Failed to decode swap transaction")?;
         let versioned_tx: VersionedTransaction = bincode::deserialize(&transaction_bytes)
             .context("Failed to deserialize VersionedTransaction")?;
 
         info!("Sending swap transaction...");
-        let signature = wallet_manager.sign_and_send_versioned_transaction(
-            versioned_tx, 
-            swap_response.last_valid_block_height
+        let signature = wallet_manager.send_swap_transaction(
+            versioned_tx,
+            swap_response.last_valid_block_height,
+            jito,
         ).await.context("Failed to sign and send swap transaction")?;
         info!("Swap transaction sent: {}", signature);
 
@@ -415,6 +828,87 @@ impl JupiterClient {
         Ok(None)
     }
 
+    /// Place an on-chain limit sell of `token_amount_ui` tokens for at least
+    /// `min_sol_out` SOL. The tokens are escrowed by the order until it fills or is
+    /// cancelled. The returned signature still needs confirming.
+    pub async fn place_limit_sell(
+        &self,
+        token_mint: &str,
+        token_decimals: u8,
+        token_amount_ui: f64,
+        min_sol_out: f64,
+        wallet_manager: Arc<WalletManager>,
+    ) -> Result<PlacedTriggerOrder> {
+        let making_amount = (token_amount_ui * 10f64.powi(token_decimals as i32)) as u64;
+        let taking_amount = (min_sol_out * 1_000_000_000.0) as u64;
+        if making_amount == 0 || taking_amount == 0 {
+            return Err(anyhow!("Limit sell amounts are too small or zero"));
+        }
+        let maker = wallet_manager.get_public_key().to_string();
+        let body = serde_json::json!({
+            "inputMint": token_mint,
+            "outputMint": SOL_MINT,
+            "maker": maker,
+            "payer": maker,
+            "params": {
+                "makingAmount": making_amount.to_string(),
+                "takingAmount": taking_amount.to_string(),
+            },
+            "computeUnitPrice": "auto",
+            "wrapAndUnwrapSol": true,
+        });
+        let response = self.post_trigger("createOrder", &body).await?;
+        let order = response.order.clone()
+            .ok_or_else(|| anyhow!("Jupiter Trigger API returned no order account"))?;
+        let signature = Self::send_trigger_transaction(&response, &wallet_manager).await?;
+        info!("Limit sell {} placed: {:.6} {} for >= {:.6} SOL ({})", order, token_amount_ui, token_mint, min_sol_out, signature);
+        Ok(PlacedTriggerOrder { order, signature })
+    }
+
+    /// Cancel an open trigger order, returning its tokens to the wallet. The returned
+    /// signature still needs confirming.
+    pub async fn cancel_trigger_order(&self, order: &str, wallet_manager: Arc<WalletManager>) -> Result<Signature> {
+        let body = serde_json::json!({
+            "maker": wallet_manager.get_public_key().to_string(),
+            "order": order,
+            "computeUnitPrice": "auto",
+        });
+        let response = self.post_trigger("cancelOrder", &body).await?;
+        let signature = Self::send_trigger_transaction(&response, &wallet_manager).await?;
+        info!("Cancelling limit order {} ({})", order, signature);
+        Ok(signature)
+    }
+
+    async fn post_trigger(&self, endpoint: &str, body: &serde_json::Value) -> Result<TriggerOrderResponse> {
+        let url = format!("{}/{}", JUPITER_TRIGGER_URL, endpoint);
+        let mut request_builder = self.client.post(&url).json(body);
+        if let Some(key) = &self.api_key {
+            request_builder = request_builder.header("x-api-key", key);
+        }
+        let response = request_builder
+            .send()
+            .await
+            .with_context(|| format!("Failed to send {} request to Jupiter Trigger API", endpoint))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(TraderbotError::ApiError(format!(
+                "Jupiter Trigger API {} failed with status {}: {}", endpoint, status, error_text
+            )).into());
+        }
+        response.json().await
+            .with_context(|| format!("Failed to parse Jupiter Trigger API {} response", endpoint))
+    }
+
+    async fn send_trigger_transaction(response: &TriggerOrderResponse, wallet_manager: &WalletManager) -> Result<Signature> {
+        let transaction_bytes = STANDARD.decode(&response.transaction)
+            .context("Failed to decode trigger order transaction")?;
+        let versioned_tx: VersionedTransaction = bincode::deserialize(&transaction_bytes)
+            .context("Failed to deserialize trigger order transaction")?;
+        wallet_manager.send_swap_transaction(versioned_tx, 0, None).await
+            .context("Failed to sign and send trigger order transaction")
+    }
+
     pub async fn get_price(
         &self,
         input_mint: &str,
@@ -2,4 +2,9 @@ pub mod birdeye;
 pub mod helius;
 pub mod jupiter;
 pub mod moralis;
+pub mod price_cache;
+pub mod raydium;
+pub mod rate_limit;
+pub mod swap_breaker;
+pub mod swap_error;
 pub mod telegram;
@@ -0,0 +1,130 @@
+//! Short-lived per-token cache in front of the Jupiter price and Birdeye overview
+//! lookups
+//!
+//! With many open positions, the scanner and the rug/exit-liquidity checks all
+//! running, the same mint is priced several times a second. Lookups for a mint
+//! within `ttl` of the last one reuse its result, and concurrent lookups for a mint
+//! share one request instead of racing each other to the API. Errors are not
+//! cached, so the next caller retries.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::api::birdeye::{BirdeyeClient, TokenOverviewData};
+use crate::api::jupiter::{JupiterClient, SOL_MINT};
+
+/// Mints kept before idle entries are pruned
+const MAX_CACHED_MINTS: usize = 1_000;
+
+type Slot<V> = Arc<tokio::sync::Mutex<Option<(V, Instant)>>>;
+
+/// Values by key, each fetched at most once per `ttl`
+struct TtlCache<V> {
+    ttl: Duration,
+    slots: Mutex<HashMap<String, Slot<V>>>,
+}
+
+impl<V: Clone> TtlCache<V> {
+    fn new(ttl: Duration) -> Self {
+        Self { ttl, slots: Mutex::new(HashMap::new()) }
+    }
+
+    /// The cached value for `key`, or `fetch`'s result if it is missing or stale.
+    /// Callers arriving while a fetch is running wait for it and share its result.
+    async fn get_or_fetch<F, Fut>(&self, key: &str, fetch: F) -> Result<V>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V>>,
+    {
+        if self.ttl.is_zero() {
+            return fetch().await;
+        }
+        let slot = {
+            let mut slots = self.slots.lock().unwrap();
+            if slots.len() >= MAX_CACHED_MINTS && !slots.contains_key(key) {
+                let ttl = self.ttl;
+                slots.retain(|_, slot| {
+                    // Keep slots in use or still fresh
+                    slot.try_lock().map_or(true, |entry| entry.as_ref().is_some_and(|(_, at)| at.elapsed() < ttl))
+                });
+            }
+            slots.entry(key.to_string()).or_default().clone()
+        };
+
+        let mut entry = slot.lock().await;
+        if let Some((value, fetched_at)) = entry.as_ref() {
+            if fetched_at.elapsed() < self.ttl {
+                return Ok(value.clone());
+            }
+        }
+        let value = fetch().await?;
+        *entry = Some((value.clone(), Instant::now()));
+        Ok(value)
+    }
+}
+
+/// Token prices and overviews shared by the risk analyzer, position manager and
+/// limit order monitor
+pub struct PriceCache {
+    jupiter_client: Arc<JupiterClient>,
+    birdeye_client: Arc<BirdeyeClient>,
+    prices: TtlCache<f64>,
+    overviews: TtlCache<Option<TokenOverviewData>>,
+}
+
+impl PriceCache {
+    /// `ttl` of zero turns caching off (every lookup goes to the API)
+    pub fn new(jupiter_client: Arc<JupiterClient>, birdeye_client: Arc<BirdeyeClient>, ttl: Duration) -> Self {
+        Self {
+            jupiter_client,
+            birdeye_client,
+            prices: TtlCache::new(ttl),
+            overviews: TtlCache::new(ttl),
+        }
+    }
+
+    /// Price of `token_mint` in SOL, from a Jupiter quote
+    pub async fn price_sol(&self, token_mint: &str, token_decimals: u8) -> Result<f64> {
+        self.prices.get_or_fetch(token_mint, || {
+            self.jupiter_client.get_price(SOL_MINT, token_mint, token_decimals)
+        }).await
+    }
+
+    /// Birdeye token overview (price, liquidity, metadata)
+    pub async fn token_overview(&self, token_mint: &str) -> Result<Option<TokenOverviewData>> {
+        self.overviews.get_or_fetch(token_mint, || self.birdeye_client.get_token_overview(token_mint)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn lookups_within_ttl_share_one_fetch() {
+        let cache = TtlCache::new(Duration::from_secs(60));
+        let fetches = AtomicUsize::new(0);
+        let fetch = || async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(1.5)
+        };
+
+        let (a, b) = tokio::join!(cache.get_or_fetch("mint", fetch), cache.get_or_fetch("mint", fetch));
+        assert_eq!((a.unwrap(), b.unwrap()), (1.5, 1.5));
+        assert_eq!(cache.get_or_fetch("mint", fetch).await.unwrap(), 1.5);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        cache.get_or_fetch("other", fetch).await.unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+        // Failures aren't cached
+        assert!(cache.get_or_fetch("failing", || async { Err(anyhow::anyhow!("rate limited")) }).await.is_err());
+        assert_eq!(cache.get_or_fetch("failing", fetch).await.unwrap(), 1.5);
+    }
+}
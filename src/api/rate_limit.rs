@@ -0,0 +1,150 @@
+//! Shared backoff for the analysis APIs (Birdeye, Helius) after rate-limit responses
+//!
+//! A 429 used to be logged like any other failure while scanning carried on, which
+//! only kept the limit tripped and turned every missing response into a risk
+//! penalty. Each rate-limited response now doubles a global cooldown (capped) that
+//! the scan loops wait out; the first successful response resets it.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::StatusCode;
+use tracing::{info, warn};
+
+/// Whether a failed response means "slow down" rather than a real error.
+/// Birdeye also reports exhausted compute units in the body of non-429 errors.
+pub fn is_rate_limited(status: StatusCode, body: &str) -> bool {
+    let body = body.to_lowercase();
+    status == StatusCode::TOO_MANY_REQUESTS
+        || body.contains("rate limit")
+        || body.contains("too many requests")
+        || body.contains("compute units")
+}
+
+#[derive(Debug, Default)]
+struct BackoffState {
+    consecutive: u32,
+    until: Option<Instant>,
+    last_hit: Option<Instant>,
+}
+
+#[derive(Debug)]
+pub struct RateLimitBackoff {
+    base: Duration,
+    max: Duration,
+    state: Mutex<BackoffState>,
+}
+
+impl Default for RateLimitBackoff {
+    fn default() -> Self {
+        Self::new(5, 300)
+    }
+}
+
+impl RateLimitBackoff {
+    pub fn new(base_secs: u64, max_secs: u64) -> Self {
+        Self {
+            base: Duration::from_secs(base_secs.max(1)),
+            max: Duration::from_secs(max_secs.max(base_secs.max(1))),
+            state: Mutex::new(BackoffState::default()),
+        }
+    }
+
+    /// Cooldown after `consecutive` rate-limited responses in a row
+    fn delay_for(&self, consecutive: u32) -> Duration {
+        let factor = 1u32 << consecutive.saturating_sub(1).min(16);
+        self.base.saturating_mul(factor).min(self.max)
+    }
+
+    /// Record a rate-limited response; returns the cooldown now in force
+    pub fn record_rate_limited(&self, source: &str) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        state.last_hit = Some(now);
+        // Responses already in flight when the cooldown started don't escalate it
+        if state.until.is_some_and(|until| until > now) {
+            return state.until.map_or(Duration::ZERO, |until| until - now);
+        }
+        state.consecutive += 1;
+        let delay = self.delay_for(state.consecutive);
+        state.until = Some(now + delay);
+        warn!("⏳ {} rate limited, backing off for {}s (hit {} in a row)", source, delay.as_secs(), state.consecutive);
+        delay
+    }
+
+    /// Feed a response status into the backoff: rate limits extend it, successes clear it
+    pub fn record_response(&self, status: StatusCode, body: &str, source: &str) {
+        if status.is_success() {
+            self.record_success();
+        } else if is_rate_limited(status, body) {
+            self.record_rate_limited(source);
+        }
+    }
+
+    /// A successful response ends the escalation
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.consecutive > 0 && !state.until.is_some_and(|until| until > Instant::now()) {
+            info!("✅ Analysis APIs responding again, rate-limit backoff cleared");
+            state.consecutive = 0;
+        }
+    }
+
+    /// Time left in the current cooldown, if any
+    pub fn remaining(&self) -> Option<Duration> {
+        let until = self.state.lock().unwrap().until?;
+        until.checked_duration_since(Instant::now()).filter(|d| !d.is_zero())
+    }
+
+    /// Whether a rate-limited response arrived since `since` - results gathered in
+    /// that window are incomplete and shouldn't be judged
+    pub fn hit_since(&self, since: Instant) -> bool {
+        self.state.lock().unwrap().last_hit.is_some_and(|hit| hit >= since)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_rate_limit_responses() {
+        assert!(is_rate_limited(StatusCode::TOO_MANY_REQUESTS, ""));
+        assert!(is_rate_limited(StatusCode::BAD_REQUEST, "Compute units usage limit exceeded"));
+        assert!(!is_rate_limited(StatusCode::INTERNAL_SERVER_ERROR, "upstream error"));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_cap() {
+        let backoff = RateLimitBackoff::new(5, 30);
+        assert_eq!(backoff.delay_for(1), Duration::from_secs(5));
+        assert_eq!(backoff.delay_for(2), Duration::from_secs(10));
+        assert_eq!(backoff.delay_for(3), Duration::from_secs(20));
+        assert_eq!(backoff.delay_for(4), Duration::from_secs(30));
+        assert_eq!(backoff.delay_for(40), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn hits_during_cooldown_do_not_escalate() {
+        let backoff = RateLimitBackoff::new(60, 600);
+        let started = Instant::now();
+        assert!(!backoff.hit_since(started));
+        assert_eq!(backoff.record_rate_limited("Test"), Duration::from_secs(60));
+        backoff.record_rate_limited("Test");
+        assert_eq!(backoff.state.lock().unwrap().consecutive, 1);
+        assert!(backoff.remaining().is_some());
+        assert!(backoff.hit_since(started));
+    }
+
+    #[test]
+    fn responses_drive_the_backoff() {
+        let backoff = RateLimitBackoff::new(60, 600);
+        backoff.record_response(StatusCode::INTERNAL_SERVER_ERROR, "upstream error", "Test");
+        assert!(backoff.remaining().is_none());
+        backoff.record_response(StatusCode::TOO_MANY_REQUESTS, "", "Test");
+        assert_eq!(backoff.state.lock().unwrap().consecutive, 1);
+        backoff.state.lock().unwrap().until = None; // Cooldown over
+        backoff.record_response(StatusCode::OK, "", "Test");
+        assert_eq!(backoff.state.lock().unwrap().consecutive, 0);
+    }
+}
@@ -0,0 +1,393 @@
+//! Direct swaps on Raydium AMM v4 pools
+//!
+//! On a fresh pool Raydium is usually the only venue, and going through Jupiter
+//! adds a quote and a swap-build round trip that a snipe can't afford. These swaps
+//! build the AMM's `swap_base_in_v2` instruction (the variant without OpenBook
+//! market accounts) against the token's deepest SOL pool: the expected output comes
+//! from the pool's constant-product reserves, and the instruction's minimum output
+//! carries the slippage protection. SOL goes in and out through a temporary wrapped
+//! SOL account that is closed in the same transaction.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::{Message, VersionedMessage};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::VersionedTransaction;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token::state::Account as TokenAccount;
+use tracing::{debug, info};
+
+use crate::api::jupiter::{SwapResult, SOL_MINT};
+use crate::solana::client::SolanaClient;
+use crate::solana::jito::JitoTip;
+use crate::solana::wallet::WalletManager;
+use crate::trading::pool_discovery::{
+    read_pubkey, PoolDiscovery, PoolKind, RAYDIUM_AMM_V4_PROGRAM_ID, RAYDIUM_BASE_MINT_OFFSET,
+    RAYDIUM_BASE_VAULT_OFFSET, RAYDIUM_QUOTE_MINT_OFFSET, RAYDIUM_QUOTE_VAULT_OFFSET,
+};
+use crate::trading::pumpfun_swap::less_slippage;
+
+/// PDA that owns every AMM v4 pool's vaults
+const RAYDIUM_AMM_AUTHORITY: &str = "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1";
+const SWAP_BASE_IN_V2: u8 = 16;
+
+const STATUS_OFFSET: usize = 0;
+const SWAP_FEE_NUMERATOR_OFFSET: usize = 176;
+const SWAP_FEE_DENOMINATOR_OFFSET: usize = 184;
+const NEED_TAKE_PNL_BASE_OFFSET: usize = 192;
+const NEED_TAKE_PNL_QUOTE_OFFSET: usize = 200;
+const POOL_OPEN_TIME_OFFSET: usize = 224;
+
+/// Pool statuses that accept swaps: Initialized, SwapOnly, WaitingTrade (once open)
+const SWAPPABLE_STATUSES: [u64; 3] = [1, 6, 7];
+
+/// How long "no pool" is remembered; short, since a snipe target's pool may be
+/// created seconds after we first look
+const NO_POOL_RECHECK_SECS: u64 = 30;
+
+/// An AMM v4 pool pairing a token with SOL. Base/quote follow the pool's own
+/// (coin/pc) order, which is also the vault order the swap instruction expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RaydiumPool {
+    pub address: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    status: u64,
+    swap_fee_numerator: u64,
+    swap_fee_denominator: u64,
+    need_take_pnl_base: u64, // Protocol fees still sitting in the vaults, not tradable
+    need_take_pnl_quote: u64,
+    open_time: u64,
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8).map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap_or_default()))
+}
+
+impl RaydiumPool {
+    /// Decode an AMM v4 pool account; None unless it pairs `mint` with SOL
+    pub fn parse(address: Pubkey, data: &[u8], mint: &Pubkey) -> Option<Self> {
+        let sol = Pubkey::from_str(SOL_MINT).ok()?;
+        let pool = Self {
+            address,
+            base_mint: read_pubkey(data, RAYDIUM_BASE_MINT_OFFSET)?,
+            quote_mint: read_pubkey(data, RAYDIUM_QUOTE_MINT_OFFSET)?,
+            base_vault: read_pubkey(data, RAYDIUM_BASE_VAULT_OFFSET)?,
+            quote_vault: read_pubkey(data, RAYDIUM_QUOTE_VAULT_OFFSET)?,
+            status: read_u64(data, STATUS_OFFSET)?,
+            swap_fee_numerator: read_u64(data, SWAP_FEE_NUMERATOR_OFFSET)?,
+            swap_fee_denominator: read_u64(data, SWAP_FEE_DENOMINATOR_OFFSET)?,
+            need_take_pnl_base: read_u64(data, NEED_TAKE_PNL_BASE_OFFSET)?,
+            need_take_pnl_quote: read_u64(data, NEED_TAKE_PNL_QUOTE_OFFSET)?,
+            open_time: read_u64(data, POOL_OPEN_TIME_OFFSET)?,
+        };
+        let pairs_sol = (pool.base_mint == *mint && pool.quote_mint == sol)
+            || (pool.base_mint == sol && pool.quote_mint == *mint);
+        pairs_sol.then_some(pool)
+    }
+
+    fn token_is_base(&self) -> bool {
+        self.quote_mint.to_string() == SOL_MINT
+    }
+
+    fn token_mint(&self) -> Pubkey {
+        if self.token_is_base() { self.base_mint } else { self.quote_mint }
+    }
+
+    fn is_swappable(&self, now_unix: u64) -> bool {
+        SWAPPABLE_STATUSES.contains(&self.status) && self.open_time <= now_unix
+    }
+
+    /// Output for `amount_in` after the pool's swap fee (constant product)
+    fn quote_out(&self, reserve_in: u64, reserve_out: u64, amount_in: u64) -> u64 {
+        if self.swap_fee_denominator == 0 || reserve_in == 0 {
+            return 0;
+        }
+        let fee = (amount_in as u128 * self.swap_fee_numerator as u128).div_ceil(self.swap_fee_denominator as u128);
+        let in_after_fee = (amount_in as u128).saturating_sub(fee);
+        (reserve_out as u128 * in_after_fee / (reserve_in as u128 + in_after_fee)) as u64
+    }
+
+    fn swap_instruction(&self, source: Pubkey, destination: Pubkey, owner: Pubkey, amount_in: u64, min_out: u64) -> Result<Instruction> {
+        let mut data = Vec::with_capacity(17);
+        data.push(SWAP_BASE_IN_V2);
+        data.extend_from_slice(&amount_in.to_le_bytes());
+        data.extend_from_slice(&min_out.to_le_bytes());
+        Ok(Instruction {
+            program_id: Pubkey::from_str(RAYDIUM_AMM_V4_PROGRAM_ID).context("Invalid Raydium program id")?,
+            accounts: vec![
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new(self.address, false),
+                AccountMeta::new_readonly(Pubkey::from_str(RAYDIUM_AMM_AUTHORITY).context("Invalid Raydium authority")?, false),
+                AccountMeta::new(self.base_vault, false),
+                AccountMeta::new(self.quote_vault, false),
+                AccountMeta::new(source, false),
+                AccountMeta::new(destination, false),
+                AccountMeta::new_readonly(owner, true),
+            ],
+            data,
+        })
+    }
+}
+
+/// A pool with its tradable reserves, read just before swapping
+#[derive(Debug, Clone, Copy)]
+pub struct PoolSnapshot {
+    pub pool: RaydiumPool,
+    pub token_reserve: u64,
+    pub sol_reserve: u64,
+}
+
+impl PoolSnapshot {
+    /// Price impact of a trade against the pool's spot price, in percent
+    fn price_impact_pct(&self, lamports: u64, tokens: u64) -> f64 {
+        if tokens == 0 || self.token_reserve == 0 {
+            return 0.0;
+        }
+        let spot = self.sol_reserve as f64 / self.token_reserve as f64;
+        let execution = lamports as f64 / tokens as f64;
+        ((execution - spot).abs() / spot) * 100.0
+    }
+
+    /// Buy with `amount_sol`, requiring at least the pool quote less `slippage_bps`
+    pub async fn buy(
+        &self,
+        token_decimals: u8,
+        amount_sol: f64,
+        slippage_bps: u32,
+        priority_fee_micro_lamports: Option<u64>,
+        jito: Option<&JitoTip>,
+        wallet_manager: Arc<WalletManager>,
+    ) -> Result<SwapResult> {
+        let lamports_in = (amount_sol * 1_000_000_000.0) as u64;
+        let expected_tokens = self.pool.quote_out(self.sol_reserve, self.token_reserve, lamports_in);
+        let min_tokens = less_slippage(expected_tokens, slippage_bps);
+        if min_tokens == 0 {
+            return Err(anyhow!("Raydium quote for {} SOL of {} is zero", amount_sol, self.pool.token_mint()));
+        }
+        let scale = 10f64.powi(token_decimals as i32);
+        info!(
+            "Raydium buy: {:.6} SOL -> {:.6} {} (min {:.6}, pool {})",
+            amount_sol, expected_tokens as f64 / scale, self.pool.token_mint(), min_tokens as f64 / scale, self.pool.address
+        );
+
+        let user = wallet_manager.get_public_key();
+        let wsol = spl_token::native_mint::id();
+        let wsol_account = get_associated_token_address(&user, &wsol);
+        let token_account = get_associated_token_address(&user, &self.pool.token_mint());
+        let instructions = vec![
+            create_associated_token_account_idempotent(&user, &user, &wsol, &spl_token::id()),
+            system_instruction::transfer(&user, &wsol_account, lamports_in),
+            spl_token::instruction::sync_native(&spl_token::id(), &wsol_account)?,
+            create_associated_token_account_idempotent(&user, &user, &self.pool.token_mint(), &spl_token::id()),
+            self.pool.swap_instruction(wsol_account, token_account, user, lamports_in, min_tokens)?,
+            spl_token::instruction::close_account(&spl_token::id(), &wsol_account, &user, &user, &[])?,
+        ];
+        let signature = send_swap_instructions(&wallet_manager, instructions, priority_fee_micro_lamports, jito).await?;
+        info!("Raydium buy sent: {}", signature);
+
+        Ok(SwapResult {
+            input_mint: SOL_MINT.to_string(),
+            output_mint: self.pool.token_mint().to_string(),
+            in_amount_ui: amount_sol,
+            // swap_base_in fills at the pool quote; min_tokens is only the slippage floor
+            out_amount_ui: expected_tokens as f64 / scale,
+            actual_out_amount_ui: None,
+            price_impact_pct: self.price_impact_pct(lamports_in, expected_tokens),
+            transaction_signature: signature.to_string(),
+        })
+    }
+
+    /// Sell `token_amount_ui` tokens, requiring at least the pool quote less `slippage_bps`
+    pub async fn sell(
+        &self,
+        token_decimals: u8,
+        token_amount_ui: f64,
+        slippage_bps: u32,
+        priority_fee_micro_lamports: Option<u64>,
+        jito: Option<&JitoTip>,
+        wallet_manager: Arc<WalletManager>,
+    ) -> Result<SwapResult> {
+        let raw_tokens = (token_amount_ui * 10f64.powi(token_decimals as i32)) as u64;
+        if raw_tokens == 0 {
+            return Err(anyhow!("Input token amount is too small or zero"));
+        }
+        let expected_lamports = self.pool.quote_out(self.token_reserve, self.sol_reserve, raw_tokens);
+        let min_lamports = less_slippage(expected_lamports, slippage_bps);
+        if min_lamports == 0 {
+            return Err(anyhow!("Raydium quote for {} {} is zero", token_amount_ui, self.pool.token_mint()));
+        }
+        info!(
+            "Raydium sell: {:.6} {} -> {:.6} SOL (min {:.6}, pool {})",
+            token_amount_ui, self.pool.token_mint(), expected_lamports as f64 / 1e9, min_lamports as f64 / 1e9, self.pool.address
+        );
+
+        let user = wallet_manager.get_public_key();
+        let wsol = spl_token::native_mint::id();
+        let wsol_account = get_associated_token_address(&user, &wsol);
+        let token_account = get_associated_token_address(&user, &self.pool.token_mint());
+        let instructions = vec![
+            create_associated_token_account_idempotent(&user, &user, &wsol, &spl_token::id()),
+            self.pool.swap_instruction(token_account, wsol_account, user, raw_tokens, min_lamports)?,
+            spl_token::instruction::close_account(&spl_token::id(), &wsol_account, &user, &user, &[])?,
+        ];
+        let signature = send_swap_instructions(&wallet_manager, instructions, priority_fee_micro_lamports, jito).await?;
+        info!("Raydium sell sent: {}", signature);
+
+        Ok(SwapResult {
+            input_mint: self.pool.token_mint().to_string(),
+            output_mint: SOL_MINT.to_string(),
+            in_amount_ui: token_amount_ui,
+            out_amount_ui: expected_lamports as f64 / 1_000_000_000.0,
+            actual_out_amount_ui: None,
+            price_impact_pct: self.price_impact_pct(expected_lamports, raw_tokens),
+            transaction_signature: signature.to_string(),
+        })
+    }
+}
+
+async fn send_swap_instructions(
+    wallet_manager: &WalletManager,
+    mut instructions: Vec<Instruction>,
+    priority_fee_micro_lamports: Option<u64>,
+    jito: Option<&JitoTip>,
+) -> Result<Signature> {
+    if let Some(fee) = priority_fee_micro_lamports.filter(|f| *f > 0) {
+        instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_price(fee));
+    }
+    let message = Message::new(&instructions, Some(&wallet_manager.get_public_key()));
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+        message: VersionedMessage::Legacy(message),
+    };
+    // The blockhash is set when signing
+    wallet_manager.send_swap_transaction(transaction, 0, jito).await
+        .context("Failed to send Raydium swap transaction")
+}
+
+/// Finds and caches each token's deepest Raydium SOL pool
+#[derive(Debug)]
+pub struct RaydiumClient {
+    discovery: PoolDiscovery,
+}
+
+impl RaydiumClient {
+    pub fn new() -> Self {
+        Self {
+            discovery: PoolDiscovery::new(&[PoolKind::RaydiumAmm], NO_POOL_RECHECK_SECS),
+        }
+    }
+
+    /// The token's pool with current reserves, or None if it has no swappable AMM v4
+    /// SOL pool (the caller falls back to Jupiter)
+    pub async fn pool_for(&self, solana_client: &SolanaClient, token_mint: &str) -> Option<PoolSnapshot> {
+        let mint = Pubkey::from_str(token_mint).ok()?;
+        let address = match self.discovery.deepest_sol_pool(solana_client, &mint).await {
+            Ok(pool) => pool?.address,
+            Err(e) => {
+                debug!("Raydium pool lookup for {} failed: {:?}", token_mint, e);
+                return None;
+            }
+        };
+        match snapshot(solana_client, address, &mint).await {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                debug!("Raydium pool {} for {} isn't usable: {:?}", address, token_mint, e);
+                self.discovery.forget(&mint).await;
+                None
+            }
+        }
+    }
+}
+
+impl Default for RaydiumClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read the pool and both vaults in one call
+async fn snapshot(solana_client: &SolanaClient, address: Pubkey, mint: &Pubkey) -> Result<PoolSnapshot> {
+    let rpc = solana_client.get_rpc();
+    let pool_account = rpc.get_account(&address).await.context("Failed to read Raydium pool")?;
+    let pool = RaydiumPool::parse(address, &pool_account.data, mint)
+        .ok_or_else(|| anyhow!("Account {} is not a SOL pool for {}", address, mint))?;
+    if !pool.is_swappable(chrono::Utc::now().timestamp().max(0) as u64) {
+        return Err(anyhow!("Pool status {} doesn't allow swaps yet", pool.status));
+    }
+
+    let vaults = rpc.get_multiple_accounts(&[pool.base_vault, pool.quote_vault]).await
+        .context("Failed to read Raydium pool vaults")?;
+    let balance = |i: usize| -> Result<u64> {
+        let account = vaults.get(i).cloned().flatten().ok_or_else(|| anyhow!("Pool vault missing"))?;
+        Ok(TokenAccount::unpack(&account.data).context("Failed to unpack pool vault")?.amount)
+    };
+    let base = balance(0)?.saturating_sub(pool.need_take_pnl_base);
+    let quote = balance(1)?.saturating_sub(pool.need_take_pnl_quote);
+    let (token_reserve, sol_reserve) = if pool.token_is_base() { (base, quote) } else { (quote, base) };
+    if token_reserve == 0 || sol_reserve == 0 {
+        return Err(anyhow!("Pool {} is empty", address));
+    }
+    Ok(PoolSnapshot { pool, token_reserve, sol_reserve })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::pool_discovery::RAYDIUM_AMM_V4_SIZE;
+
+    fn pool_data(mint: &Pubkey, token_is_base: bool) -> Vec<u8> {
+        let sol = Pubkey::from_str(SOL_MINT).unwrap();
+        let (base, quote) = if token_is_base { (*mint, sol) } else { (sol, *mint) };
+        let mut data = vec![0u8; RAYDIUM_AMM_V4_SIZE as usize];
+        data[STATUS_OFFSET..STATUS_OFFSET + 8].copy_from_slice(&6u64.to_le_bytes());
+        data[SWAP_FEE_NUMERATOR_OFFSET..SWAP_FEE_NUMERATOR_OFFSET + 8].copy_from_slice(&25u64.to_le_bytes());
+        data[SWAP_FEE_DENOMINATOR_OFFSET..SWAP_FEE_DENOMINATOR_OFFSET + 8].copy_from_slice(&10_000u64.to_le_bytes());
+        data[RAYDIUM_BASE_MINT_OFFSET..RAYDIUM_BASE_MINT_OFFSET + 32].copy_from_slice(base.as_ref());
+        data[RAYDIUM_QUOTE_MINT_OFFSET..RAYDIUM_QUOTE_MINT_OFFSET + 32].copy_from_slice(quote.as_ref());
+        data
+    }
+
+    #[test]
+    fn parses_sol_pools_and_quotes_after_fee() {
+        let mint = Pubkey::new_unique();
+        let pool = RaydiumPool::parse(Pubkey::new_unique(), &pool_data(&mint, true), &mint).unwrap();
+        assert!(pool.token_is_base());
+        assert_eq!(pool.token_mint(), mint);
+        assert!(pool.is_swappable(0));
+
+        let flipped = RaydiumPool::parse(Pubkey::new_unique(), &pool_data(&mint, false), &mint).unwrap();
+        assert!(!flipped.token_is_base());
+        assert_eq!(flipped.token_mint(), mint);
+
+        // Not this token's pool
+        assert!(RaydiumPool::parse(Pubkey::new_unique(), &pool_data(&mint, true), &Pubkey::new_unique()).is_none());
+
+        // 0.25% fee: 10_000 in leaves 9_975, against 1M/1M reserves
+        assert_eq!(pool.quote_out(1_000_000, 1_000_000, 10_000), 9_876);
+        assert_eq!(pool.quote_out(0, 1_000_000, 10_000), 0);
+    }
+
+    #[test]
+    fn swap_instruction_uses_v2_layout() {
+        let mint = Pubkey::new_unique();
+        let pool = RaydiumPool::parse(Pubkey::new_unique(), &pool_data(&mint, true), &mint).unwrap();
+        let owner = Pubkey::new_unique();
+        let ix = pool.swap_instruction(Pubkey::new_unique(), Pubkey::new_unique(), owner, 500, 400).unwrap();
+        assert_eq!(ix.data[0], SWAP_BASE_IN_V2);
+        assert_eq!(u64::from_le_bytes(ix.data[1..9].try_into().unwrap()), 500);
+        assert_eq!(u64::from_le_bytes(ix.data[9..17].try_into().unwrap()), 400);
+        assert_eq!(ix.accounts.len(), 8);
+        assert!(ix.accounts[7].is_signer && ix.accounts[7].pubkey == owner);
+    }
+}
@@ -0,0 +1,172 @@
+//! Circuit breaker for runs of failed swaps
+//!
+//! When buys and exits keep failing back to back the cause is usually the
+//! execution layer - RPC down, a misconfigured key, an empty fee wallet - not the
+//! tokens. Retrying every candidate just burns fees and buries the real problem in
+//! the logs. After `threshold` consecutive failures (each within `window` of the
+//! previous one) new buys are paused for `cooldown`, or until an operator resets
+//! the breaker. Any successful swap ends the run.
+//!
+//! No-route and slippage failures are about the token and the market, not the
+//! infrastructure, so they neither count nor break the run.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tracing::{error, info};
+
+use crate::api::swap_error::SwapError;
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive: u32,
+    last_failure: Option<Instant>,
+    last_error: Option<SwapError>,
+    paused_until: Option<Instant>,
+}
+
+/// Breaker state for the status endpoints
+#[derive(Debug, Clone, Serialize)]
+pub struct SwapBreakerStatus {
+    pub enabled: bool,
+    pub consecutive_failures: u32,
+    pub threshold: u32,
+    pub paused: bool,
+    pub paused_secs_remaining: Option<u64>,
+    pub last_error: Option<SwapError>,
+}
+
+#[derive(Debug)]
+pub struct SwapCircuitBreaker {
+    threshold: u32,
+    window: Duration,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl Default for SwapCircuitBreaker {
+    /// Never trips
+    fn default() -> Self {
+        Self::new(0, 300, 900)
+    }
+}
+
+impl SwapCircuitBreaker {
+    /// `threshold` of 0 disables the breaker
+    pub fn new(threshold: u32, window_secs: u64, cooldown_secs: u64) -> Self {
+        Self {
+            threshold,
+            window: Duration::from_secs(window_secs.max(1)),
+            cooldown: Duration::from_secs(cooldown_secs.max(1)),
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Whether a failure of this kind points at the execution layer
+    fn counts(kind: SwapError) -> bool {
+        !matches!(kind, SwapError::NoRoute | SwapError::SlippageExceeded)
+    }
+
+    /// Count a failed swap; returns true if this failure tripped the breaker
+    pub fn record_failure(&self, kind: SwapError) -> bool {
+        if self.threshold == 0 || !Self::counts(kind) {
+            return false;
+        }
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if state.last_failure.is_some_and(|last| now.duration_since(last) > self.window) {
+            state.consecutive = 0;
+        }
+        state.consecutive += 1;
+        state.last_failure = Some(now);
+        state.last_error = Some(kind);
+        let already_paused = state.paused_until.is_some_and(|until| until > now);
+        if state.consecutive < self.threshold || already_paused {
+            return false;
+        }
+        state.paused_until = Some(now + self.cooldown);
+        error!(
+            "🛑 {} swaps failed in a row (last: {}); pausing new buys for {}s",
+            state.consecutive, kind, self.cooldown.as_secs()
+        );
+        true
+    }
+
+    /// A swap went through: the run of failures is over
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive = 0;
+        state.last_failure = None;
+    }
+
+    /// Time left before buys resume, while tripped
+    pub fn paused_for(&self) -> Option<Duration> {
+        let until = self.state.lock().unwrap().paused_until?;
+        until.checked_duration_since(Instant::now()).filter(|d| !d.is_zero())
+    }
+
+    /// Resume buys now; returns false if the breaker wasn't tripped
+    pub fn reset(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let was_paused = state.paused_until.is_some_and(|until| until > Instant::now());
+        *state = BreakerState::default();
+        if was_paused {
+            info!("Swap circuit breaker reset, buys resumed");
+        }
+        was_paused
+    }
+
+    pub fn status(&self) -> SwapBreakerStatus {
+        let paused_for = self.paused_for();
+        let state = self.state.lock().unwrap();
+        SwapBreakerStatus {
+            enabled: self.threshold > 0,
+            consecutive_failures: state.consecutive,
+            threshold: self.threshold,
+            paused: paused_for.is_some(),
+            paused_secs_remaining: paused_for.map(|d| d.as_secs()),
+            last_error: state.last_error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trips_after_consecutive_infrastructure_failures() {
+        let breaker = SwapCircuitBreaker::new(3, 300, 600);
+        assert!(!breaker.record_failure(SwapError::RpcTimeout));
+        assert!(!breaker.record_failure(SwapError::BlockhashExpired));
+        // Token-specific failures don't count
+        assert!(!breaker.record_failure(SwapError::NoRoute));
+        assert!(breaker.paused_for().is_none());
+        assert!(breaker.record_failure(SwapError::InsufficientFunds));
+        assert!(breaker.paused_for().is_some());
+        assert!(breaker.status().paused);
+
+        assert!(breaker.reset());
+        assert!(breaker.paused_for().is_none());
+        assert!(!breaker.reset());
+    }
+
+    #[test]
+    fn success_ends_the_run() {
+        let breaker = SwapCircuitBreaker::new(2, 300, 600);
+        breaker.record_failure(SwapError::Other);
+        breaker.record_success();
+        assert!(!breaker.record_failure(SwapError::Other));
+        assert_eq!(breaker.status().consecutive_failures, 1);
+    }
+
+    #[test]
+    fn disabled_breaker_never_trips() {
+        let breaker = SwapCircuitBreaker::default();
+        for _ in 0..10 {
+            assert!(!breaker.record_failure(SwapError::RpcTimeout));
+        }
+        assert!(!breaker.status().enabled);
+    }
+}
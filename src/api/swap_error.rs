@@ -0,0 +1,144 @@
+//! Swap failure categorization
+//!
+//! Jupiter and RPC failures arrive as free-form error strings. This module maps
+//! them onto a small set of categories and counts them, so operators can see
+//! which failure mode dominates (e.g. expired blockhashes -> raise priority fees).
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+/// Category of a failed swap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapError {
+    SlippageExceeded,
+    BlockhashExpired,
+    InsufficientFunds,
+    NoRoute,
+    RpcTimeout,
+    SimulationFailed,
+    Other,
+}
+
+impl SwapError {
+    pub const ALL: [SwapError; 7] = [
+        SwapError::SlippageExceeded,
+        SwapError::BlockhashExpired,
+        SwapError::InsufficientFunds,
+        SwapError::NoRoute,
+        SwapError::RpcTimeout,
+        SwapError::SimulationFailed,
+        SwapError::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapError::SlippageExceeded => "slippage_exceeded",
+            SwapError::BlockhashExpired => "blockhash_expired",
+            SwapError::InsufficientFunds => "insufficient_funds",
+            SwapError::NoRoute => "no_route",
+            SwapError::RpcTimeout => "rpc_timeout",
+            SwapError::SimulationFailed => "simulation_failed",
+            SwapError::Other => "other",
+        }
+    }
+
+    /// Categorize an error by inspecting its whole context chain.
+    /// Order matters: a failed simulation usually carries the program error that
+    /// caused it (e.g. Jupiter's slippage error 0x1771), which is the more useful category.
+    pub fn classify(err: &anyhow::Error) -> SwapError {
+        Self::classify_message(&format!("{:#}", err))
+    }
+
+    pub fn classify_message(message: &str) -> SwapError {
+        let msg = message.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|n| msg.contains(n));
+
+        if has(&["slippage", "0x1771", "custom program error: 6001", "custom(6001)"]) {
+            SwapError::SlippageExceeded
+        } else if has(&["blockhash not found", "blockhashnotfound", "block height exceeded", "blockhash expired"]) {
+            SwapError::BlockhashExpired
+        } else if has(&["insufficient funds", "insufficient lamports", "insufficientfunds", "insufficient balance"]) {
+            SwapError::InsufficientFunds
+        } else if has(&["no route", "no_routes_found", "could not find any route", "could_not_find_any_route", "route not found", "token_not_tradable"]) {
+            SwapError::NoRoute
+        } else if has(&["timed out", "timeout", "deadline has elapsed"]) {
+            SwapError::RpcTimeout
+        } else if has(&["simulation failed", "simulate", "preflight"]) {
+            SwapError::SimulationFailed
+        } else {
+            SwapError::Other
+        }
+    }
+}
+
+impl fmt::Display for SwapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Running count of swap failures per category
+#[derive(Debug, Default)]
+pub struct SwapFailureStats {
+    counts: Mutex<HashMap<SwapError, u64>>,
+}
+
+impl SwapFailureStats {
+    pub fn record(&self, kind: SwapError) {
+        *self.counts.lock().unwrap().entry(kind).or_insert(0) += 1;
+    }
+
+    /// Count for every category (including zeros), in a stable order
+    pub fn snapshot(&self) -> Vec<(SwapError, u64)> {
+        let counts = self.counts.lock().unwrap();
+        SwapError::ALL.iter()
+            .map(|kind| (*kind, counts.get(kind).copied().unwrap_or(0)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn classifies_common_failures() {
+        let cases = [
+            ("Transaction simulation failed: Error processing Instruction 3: custom program error: 0x1771", SwapError::SlippageExceeded),
+            ("Transaction failed: InstructionError(3, Custom(6001))", SwapError::SlippageExceeded),
+            ("RPC response error -32002: Blockhash not found", SwapError::BlockhashExpired),
+            ("Transaction simulation failed: Attempt to debit an account but found no record of a prior credit; insufficient funds", SwapError::InsufficientFunds),
+            ("Jupiter Quote API failed with status 400: {\"errorCode\":\"COULD_NOT_FIND_ANY_ROUTE\"}", SwapError::NoRoute),
+            ("error sending request: operation timed out", SwapError::RpcTimeout),
+            ("Transaction simulation failed: Error processing Instruction 2: invalid account data", SwapError::SimulationFailed),
+            ("Failed to decode swap transaction", SwapError::Other),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(SwapError::classify_message(message), expected, "{}", message);
+        }
+    }
+
+    #[test]
+    fn classify_looks_through_context_chain() {
+        let err = anyhow!("Blockhash not found").context("Failed to sign and send swap transaction");
+        assert_eq!(SwapError::classify(&err), SwapError::BlockhashExpired);
+    }
+
+    #[test]
+    fn snapshot_includes_every_category() {
+        let stats = SwapFailureStats::default();
+        stats.record(SwapError::NoRoute);
+        stats.record(SwapError::NoRoute);
+        stats.record(SwapError::RpcTimeout);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.len(), SwapError::ALL.len());
+        assert!(snapshot.contains(&(SwapError::NoRoute, 2)));
+        assert!(snapshot.contains(&(SwapError::RpcTimeout, 1)));
+        assert!(snapshot.contains(&(SwapError::Other, 0)));
+    }
+}
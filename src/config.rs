@@ -1,13 +1,84 @@
 use anyhow::{Context, Result};
+use chrono::{NaiveTime, Weekday};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock, RwLock};
+use tracing::debug;
+
+/// What to do about tokens the bot recently stopped out of at a loss
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LossRebuyGuard {
+    /// Buy them again like any other token
+    #[default]
+    Off,
+    /// Skip them for `loss_rebuy_cooldown_minutes` after the losing exit
+    Cooldown,
+    /// Skip them until the bot restarts
+    Session,
+}
+
+impl FromStr for LossRebuyGuard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "off" | "false" | "" => Ok(LossRebuyGuard::Off),
+            "cooldown" => Ok(LossRebuyGuard::Cooldown),
+            "session" | "session_blacklist" | "blacklist" => Ok(LossRebuyGuard::Session),
+            other => Err(format!("unknown loss rebuy guard '{}'", other)),
+        }
+    }
+}
+
+/// Where positions and strategies are persisted
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    /// data/positions.json and data/strategies.json
+    #[default]
+    Json,
+    /// A SQLite database at `database_url`
+    Sqlite,
+}
+
+impl FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "json" | "file" | "" => Ok(StorageBackend::Json),
+            "sqlite" | "sql" | "db" => Ok(StorageBackend::Sqlite),
+            other => Err(format!("unknown storage backend '{}'", other)),
+        }
+    }
+}
+
+/// Comma-separated addresses from an env var (empty when unset)
+fn address_list(vars: &EnvVars, var: &str) -> Vec<String> {
+    vars.get(var)
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Config {
     // Solana Configuration
     pub solana_rpc_url: String,
     pub solana_ws_url: String,
+    pub solana_rpc_headers: Vec<(String, String)>, // extra headers sent with every RPC request
+    pub solana_rpc_auth_token: Option<String>,      // sent as "Authorization: Bearer <token>"
+    pub solana_rpc_failover_urls: Vec<String>,      // switched to when the active RPC lags
+    pub max_rpc_slot_lag: u64,                      // default 150 (0 = don't check)
+    pub rpc_slot_check_secs: u64,                   // default 30
+    pub rpc_failover_after_errors: u32,             // default 3: failed health probes in a row before failing over (0 = lag only)
     pub solana_private_key: String,
+    pub additional_wallet_private_keys: Vec<String>, // extra wallets; buys rotate round-robin
     pub network: String,
 
     // API Keys
@@ -15,6 +86,8 @@ pub struct Config {
     pub jupiter_api_key: Option<String>,
     pub birdeye_api_key: Option<String>,
     pub moralis_api_key: Option<String>,
+    pub rate_limit_backoff_base_secs: u64,  // default 5: first pause after Birdeye/Helius rate-limit us, doubled per repeat
+    pub rate_limit_backoff_max_secs: u64,   // default 300: cap on that pause
 
     // Telegram Sniper Configuration
     pub tg_api_id: Option<i32>,
@@ -25,8 +98,6 @@ pub struct Config {
 
     // Snipe Execution
     pub snipe_amount_sol: f64,              // default 0.25
-    pub snipe_slippage_bps: u32,            // default 1500 (15%)
-    pub snipe_priority_fee_micro_lamports: u64,  // default 1_000_000 (1M μlamports = high priority)
     pub snipe_exit_delay_ms: u64,           // default 3000 (3 seconds)
     pub snipe_exit_percent: u32,            // default 90
 
@@ -34,16 +105,31 @@ pub struct Config {
     pub api_host: Option<String>,
     pub api_port: Option<u16>,
     pub cors_origins: Vec<String>,
+    pub api_request_timeout_secs: u64,      // default 30: read-only requests running longer get 504 (0 = no limit)
     pub auto_start_trading: bool,
+    pub notification_max_chars: usize,      // default 4096 (Telegram's limit); 0 = no cap
+    pub startup_digest_enabled: bool,       // default true
+    pub pnl_report_time: Option<NaiveTime>, // UTC time the daily PnL report is sent (unset = no scheduled reports)
+    pub pnl_report_weekday: Option<Weekday>, // default Mon: day the weekly report follows the daily one ("off" = none)
+    pub ws_channel_capacity: usize,         // default 100: WebSocket updates buffered per client before it must resync
+    pub notification_queue_max: usize,      // default 200: alerts held while no client is connected (0 = don't queue)
+    pub strategy_capacity_alerts: bool,     // default true: notify once when a strategy's budget or position limit stops its buys
 
     // Copy Trade Configuration
     pub treasury_wallet: Option<String>,
     pub copy_trade_fee_percent: f64,
+    pub copy_source_poll_secs: u64,         // default 15: how often source wallets are polled (0 = off)
+    pub copy_source_min_sol: f64,           // default 0.01: smaller source-wallet swaps are ignored
+    pub profit_fee_percent: f64,            // default 0 (off): share of each profitable exit's net profit sent to profit_fee_wallet
+    pub profit_fee_wallet: Option<String>,
 
     // Trading Configuration
     pub demo_mode: bool,
     pub dry_run_mode: bool,  // Scans real tokens, simulates trades without execution
+    pub confirm_real_mode: bool,            // CONFIRM_REAL_MODE=yes: operator acknowledged trading real funds
+    pub real_mode_confirm_above_sol: f64,   // default 0.1: wallets holding more need confirm_real_mode to auto-trade
     pub max_position_size_sol: f64,
+    pub max_allocation_per_token_sol: Option<f64>, // cap on open entry value per token, across all buys
     pub total_budget_sol: f64,
     pub default_stop_loss_percent: u32,
     pub default_take_profit_percent: u32,
@@ -54,16 +140,182 @@ pub struct Config {
     pub min_liquidity_sol: u32,
     pub max_risk_level: u32,
     pub min_holders: u32,
+    pub transfer_tax_warn_percent: f64,     // default 5.0: warn before buying tokens taxed at least this much
+    pub min_exit_liquidity_sol: Option<f64>, // hold exits (and escalate) while pool liquidity is below this
+    pub rug_monitor: bool,                  // default false: emergency-sell held tokens on liquidity pulls, whale dumps or new mints
+    pub rug_check_interval_secs: u64,       // default 30
+    pub rug_liquidity_drop_percent: f64,    // default 80: pool liquidity fall since the first check that counts as a pull
+    pub rug_holder_dump_percent: f64,       // default 50: share of its holding a tracked whale must sell
+    pub rug_whale_min_percent: f64,         // default 5: wallets holding at least this % of supply are tracked
+    pub onchain_take_profit: bool,          // default false: place take-profits as Jupiter limit orders that fill while the bot is down
+    pub max_position_fraction_of_liquidity: Option<f64>, // cap buys at this fraction of pool liquidity
+    pub min_liquidity_capped_buy_sol: f64,  // default 0.01: skip the buy if the liquidity cap leaves less than this
+    pub max_roundtrip_loss_percent: Option<f64>, // skip buys whose buy+sell-back quotes lose more than this (adds two quotes per buy)
+    pub onchain_liquidity_fallback: bool,   // default true: read pool reserves on-chain when Birdeye has no liquidity
+    pub deployer_check_enabled: bool,       // default false: score the mint's deployer wallet history in risk analysis
+    pub deployer_check_launches: usize,     // default 5: earlier launches of the deployer checked for dead liquidity
+    pub metadata_check_enabled: bool,       // default false: score image, description and socials in risk analysis
+    pub usd_pnl_tracking: bool,             // default true: record USD value at entry/exit and report PnL in USD too
+    pub sol_downtrend_pause_percent: Option<f64>, // pause new buys while SOL has fallen this much over the lookback
+    pub sol_trend_lookback_minutes: u64,    // default 60
+    pub blocklist_source: Option<String>,   // URL or local file of known-scam mints/creators
+    pub blocklist_refresh_minutes: u64,     // default 30
+    pub mint_blacklist: Vec<String>,        // mints no strategy or manual snipe may buy
+    pub mint_whitelist: Vec<String>,        // when set, only these mints may be bought
+    pub creator_blacklist: Vec<String>,     // creator wallets whose tokens are always rejected
+    pub creator_whitelist: Vec<String>,     // when set, only tokens from these creators may be bought
+
+    // Dust Sweep
+    pub dust_sweep_enabled: bool,           // default false: periodically sell leftover token dust to SOL
+    pub dust_sweep_interval_minutes: u64,   // default 360
+    pub dust_sweep_max_value_sol: f64,      // default 0.01: holdings worth less than this count as dust
+    pub dust_sweep_min_value_sol: f64,      // default 0.0005: dust worth less than this isn't worth the swap fee
+    pub dust_sweep_sell_orphans: bool,      // default false: also sell untracked holdings above the dust ceiling
+    pub dust_sweep_orphan_max_value_sol: f64, // default 0.5: orphans worth this much or more are never sold
+    pub dust_sweep_burn_worthless: bool,    // default false: burn holdings not worth the swap fee and close their accounts
+    pub dust_sweep_close_empty_accounts: bool, // default true: close empty token accounts to reclaim their rent
+    pub dust_sweep_keep_mints: Vec<String>, // Never swept, burned or closed
+
+    // Limit Orders
+    pub limit_order_check_secs: u64,        // default 15: how often pending limit orders are priced
+    pub limit_order_default_expiry_minutes: u64, // default 60: expiry for manual orders that don't give one
+
+    // Strategy Changelog
+    pub strategy_changelog_max_entries: usize, // default 100: recorded edits kept per strategy
+
+    // Test Swap
+    pub test_swap_enabled: bool,            // default false: allow POST /api/test/swap (real round-trip swap)
+    pub test_swap_amount_sol: f64,          // default 0.005
+    pub test_swap_token_mint: String,       // default USDC
 
     // Transaction Parameters
-    pub default_slippage_bps: u32,
-    pub default_priority_fee_micro_lamports: u64,
+    pub jito_block_engine_url: String,      // default https://mainnet.block-engine.jito.wtf
+    pub jito_tip_lamports: u64,             // default 100_000 (0.0001 SOL): bundle tip for strategies with use_jito
+    pub priority_fee_auto: bool,            // default true: price swaps from recent prioritization fees (static default as fallback)
+    pub priority_fee_min_micro_lamports: u64, // default 10_000
+    pub priority_fee_max_micro_lamports: u64, // default 2_000_000
+    pub priority_fee_cache_secs: u64,       // default 10: reuse a fee sample this long
+    pub token_slippage_max_bps: u32,        // default 3000: cap on per-token exit slippage from risk analysis (0 = always use the default)
+    pub quote_max_age_ms: u64,              // default 2000 (0 disables the staleness guard)
+    pub confirm_timeout_secs: u64,          // default 60
+    pub post_timeout_verify_attempts: u32,  // default 3: re-checks of a buy after confirmation times out
+    pub post_timeout_verify_delay_ms: u64,  // default 5000
+    pub verify_bought_mint: bool,           // default true: check a confirmed buy delivered the intended mint
+    pub sell_mismatched_mint: bool,         // default true: sell back a look-alike token a buy delivered instead
+    pub verify_buy_balance: bool,           // default true: check the wallet holds the bought tokens before opening a position
+    pub buy_balance_min_percent: f64,       // default 50.0: held balance below this share of the swap's output is a mismatch
+    pub reconcile_buy_fills: bool,          // default true: set a new position's tokens/entry price from the wallet's actual token delta
+    pub entry_retry_max_window_ms: u64,     // default 10000: cap on time spent retrying a failed entry
+    pub swap_retry_attempts: u32,           // default 2: re-quotes after a slippage/expired-blockhash failure (0 = off)
+    pub swap_retry_slippage_step_bps: u32,  // default 300: slippage added per retry
+    pub swap_retry_max_slippage_bps: u32,   // default 2500
+    pub swap_retry_fee_multiplier: f64,     // default 2.0: priority fee multiplier per retry
+    pub swap_retry_max_priority_fee_micro_lamports: u64, // default 3_000_000
+    pub max_concurrent_swaps: usize,        // default 3: in-flight buy swaps at once (0 = unlimited)
+    pub max_concurrent_exit_swaps: usize,   // default 5: in-flight exit swaps at once, separate budget (0 = unlimited)
+    pub pumpfun_curve_trading: bool,        // default true: trade pre-graduation pump.fun tokens on their bonding curve
+    pub swap_breaker_failures: u32,         // default 5: consecutive failed swaps that pause new buys (0 = off)
+    pub swap_breaker_window_secs: u64,      // default 300: max gap between failures for them to count as consecutive
+    pub swap_breaker_cooldown_secs: u64,    // default 900: how long buys stay paused unless reset via the API
+    pub loss_breaker_consecutive_losses: u32, // default 0 (off): losing closes in a row that pause a strategy's buys
+    pub loss_breaker_daily_drawdown_sol: Option<f64>, // realized loss in a UTC day that pauses every strategy's buys
+    pub loss_breaker_cooldown_minutes: u64, // default 60: how long a loss pause lasts unless reset via the API
+
+    // Wallet
+    pub balance_cache_ttl_ms: u64,          // default 5000 (0 disables the cache)
+    pub price_cache_ttl_ms: u64,            // default 3000: token price/overview lookups reused for this long (0 = off)
+
+    // Manual Trades
+    pub require_confirmation_above_sol: Option<f64>,  // manual snipes above this need a confirm step
+    pub manual_snipe_max_risk_level: u32,   // default 70: manual snipes of riskier tokens are refused (100 = no check)
+    pub enrich_token_metadata: bool,        // default true: look up real name/symbol for manual buys
+    pub hide_positions_below_sol: f64,      // default 0.001: dust positions hidden from listings (0 = show all)
+
+    // Position Persistence
+    pub position_load_retries: u32,         // default 3: retries for an unreadable positions file at startup
+    pub position_load_retry_delay_ms: u64,  // default 500, doubled after each retry
+    pub position_archive_after_days: u64,   // default 30: closed positions older than this move to the archive (0 = never)
+    pub storage_backend: StorageBackend,    // default json: "sqlite" keeps positions (and their archive), strategies, limit orders and copy-trade state in database_url
+    pub database_url: String,               // default sqlite://data/traderbot.db
+
+    // Token Scan
+    pub scan_interval_secs: u64,            // default 60: how often the Helius scan looks for new tokens
+    pub scan_token_age_minutes: u64,        // default 60: how far back each scan looks for new tokens
+
+    // Position Monitor
+    pub position_monitor_min_secs: u64,     // default 5: fastest re-check, for volatile positions near a trigger
+    pub position_monitor_max_secs: u64,     // default 30: slowest re-check, for quiet positions far from triggers
+    pub position_price_stream_secs: u64,    // default 5: how often live prices/PnL of open positions go out over /ws (0 = off)
+    pub exit_retry_attempts: u32,           // default 5: failed sells retried before a position is marked Failed (0 = fail at once)
+    pub exit_retry_grace_minutes: u64,      // default 10: give up retrying a failed exit after this long
+    pub unroutable_exit_attempts: u32,      // default 3: consecutive no-route sells before a position is marked Unsellable (0 = never)
+    pub hold_sl_tighten_window_percent: f64, // default 0 (off): tighten the SL over this last share of the max hold window
+    pub hold_sl_tighten_curve: f64,         // default 1.0: exponent of the tightening curve (1 = linear, >1 = late, <1 = early)
+    pub hold_sl_tighten_final_gap_percent: f64, // default 2.0: SL distance below the price when the deadline is reached
+
+    // Loss Rebuy Guard
+    pub loss_rebuy_guard: LossRebuyGuard,   // default off: skip tokens stopped out at a loss ("cooldown" or "session")
+    pub loss_rebuy_cooldown_minutes: u64,   // default 60: how long "cooldown" skips such a token
+
+    // Strategy Stats History
+    pub strategy_stats_snapshot_minutes: u64, // default 60: periodic per-strategy snapshots (0 = only on close)
+
+    // Escalation
+    pub escalation_enabled: bool,           // default true
+    pub escalation_repeat_minutes: u64,     // default 15 (0 = notify once)
+    pub escalation_rpc_down_secs: u64,      // default 300
+
+    // Real-time Discovery
+    pub realtime_discovery_enabled: bool,   // default false: logsSubscribe for new pump.fun tokens in real mode
+    pub realtime_discovery_ws_url: Option<String>, // defaults to the Helius WebSocket endpoint
+    pub realtime_pool_discovery: bool,      // default true: also stream new Raydium AMM v4/CPMM pools (with realtime discovery)
+
+    // Logging
+    pub log_dir: Option<String>,            // rotated log files are written here (stdout logging stays on)
+    pub log_file: Option<String>,           // log file name prefix, or a full path; default "trader-tony.log"
+    pub log_retention_days: usize,          // default 7: daily log files kept before deletion
+
+    // Instance Lock
+    pub instance_lock_enabled: bool,        // default true: refuse to start while another instance uses data/
+    pub instance_lock_stale_secs: u64,      // default 120: a lock whose heartbeat is older than this is taken over
+
+    // Slippage, priority fees and API credentials; replaced on reload (see `live()`)
+    #[serde(flatten)]
+    pub live: LiveSettings,
 }
 
 impl Config {
+    /// Whether trading must stay off until CONFIRM_REAL_MODE=yes: real mode with a funded
+    /// wallet, or one whose balance couldn't be checked
+    pub fn real_mode_needs_confirmation(&self, wallet_balance_sol: Option<f64>) -> bool {
+        !self.demo_mode
+            && !self.dry_run_mode
+            && !self.confirm_real_mode
+            && wallet_balance_sol.is_none_or(|b| b > self.real_mode_confirm_above_sol)
+    }
+
     pub fn load() -> Result<Self> {
+        Self::load_from(&EnvVars::process())
+    }
+
+    /// Config from the minimum required variables plus `extra`, ignoring the process env
+    #[cfg(test)]
+    pub(crate) fn for_tests(extra: &[(&str, &str)]) -> Self {
+        let vars: HashMap<String, String> = [
+            ("SOLANA_RPC_URL", "https://rpc.example"),
+            ("WALLET_PRIVATE_KEY", "test"),
+            ("HELIUS_API_KEY", "test"),
+        ]
+        .into_iter()
+        .chain(extra.iter().copied())
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        Self::load_from(&EnvVars::reloaded(vars)).unwrap()
+    }
+
+    fn load_from(vars: &EnvVars) -> Result<Self> {
         // Parse CORS origins from comma-separated string
-        let cors_origins: Vec<String> = env::var("CORS_ORIGINS")
+        let cors_origins: Vec<String> = vars.get("CORS_ORIGINS")
             .unwrap_or_else(|_| "*".to_string())
             .split(',')
             .map(|s| s.trim().to_string())
@@ -72,119 +324,626 @@ impl Config {
 
         Ok(Self {
             // Solana Configuration
-            solana_rpc_url: env::var("SOLANA_RPC_URL")
+            solana_rpc_url: vars.get("SOLANA_RPC_URL")
                 .context("SOLANA_RPC_URL not set in environment")?,
-            solana_ws_url: env::var("SOLANA_WS_URL")
+            solana_ws_url: vars.get("SOLANA_WS_URL")
                 .unwrap_or_else(|_| {
                     // Derive WebSocket URL from RPC URL if not provided
-                    let rpc = env::var("SOLANA_RPC_URL").unwrap_or_default();
+                    let rpc = vars.get("SOLANA_RPC_URL").unwrap_or_default();
                     rpc.replace("https://", "wss://").replace("http://", "ws://")
                 }),
-            solana_private_key: env::var("WALLET_PRIVATE_KEY")
-                .or_else(|_| env::var("SOLANA_PRIVATE_KEY"))
+            solana_rpc_headers: vars.get("SOLANA_RPC_HEADERS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|pair| {
+                    pair.split_once(':')
+                        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                        .with_context(|| format!("Invalid SOLANA_RPC_HEADERS entry '{}', expected Name:Value", pair))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            solana_rpc_auth_token: vars.get("SOLANA_RPC_AUTH_TOKEN").ok().filter(|v| !v.is_empty()),
+            solana_rpc_failover_urls: vars.get("SOLANA_RPC_FAILOVER_URLS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            max_rpc_slot_lag: vars.get("MAX_RPC_SLOT_LAG")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(150),
+            rpc_slot_check_secs: vars.get("RPC_SLOT_CHECK_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(30),
+            rpc_failover_after_errors: vars.get("RPC_FAILOVER_AFTER_ERRORS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(3),
+            solana_private_key: vars.get("WALLET_PRIVATE_KEY")
+                .or_else(|_| vars.get("SOLANA_PRIVATE_KEY"))
                 .context("WALLET_PRIVATE_KEY or SOLANA_PRIVATE_KEY not set in environment")?,
-            network: env::var("NETWORK").unwrap_or_else(|_| "mainnet".to_string()),
+            additional_wallet_private_keys: vars.get("ADDITIONAL_WALLET_PRIVATE_KEYS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            network: vars.get("NETWORK").unwrap_or_else(|_| "mainnet".to_string()),
 
             // API Keys
-            helius_api_key: env::var("HELIUS_API_KEY")
+            helius_api_key: vars.get("HELIUS_API_KEY")
                 .context("HELIUS_API_KEY not set in environment")?,
-            jupiter_api_key: env::var("JUPITER_API_KEY").ok(),
-            birdeye_api_key: env::var("BIRDEYE_API_KEY").ok(),
-            moralis_api_key: env::var("MORALIS_API_KEY").ok(),
+            jupiter_api_key: vars.get("JUPITER_API_KEY").ok(),
+            birdeye_api_key: vars.get("BIRDEYE_API_KEY").ok(),
+            moralis_api_key: vars.get("MORALIS_API_KEY").ok(),
+            rate_limit_backoff_base_secs: vars.get("RATE_LIMIT_BACKOFF_BASE_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(5),
+            rate_limit_backoff_max_secs: vars.get("RATE_LIMIT_BACKOFF_MAX_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(300),
 
             // Telegram Sniper
-            tg_api_id: env::var("TG_API_ID").ok().and_then(|v| v.parse().ok()),
-            tg_api_hash: env::var("TG_API_HASH").ok(),
-            tg_phone: env::var("TG_PHONE").ok(),
-            tg_channel: env::var("TG_CHANNEL").ok(),
-            tg_session_path: env::var("TG_SESSION_PATH")
+            tg_api_id: vars.get("TG_API_ID").ok().and_then(|v| v.parse().ok()),
+            tg_api_hash: vars.get("TG_API_HASH").ok(),
+            tg_phone: vars.get("TG_PHONE").ok(),
+            tg_channel: vars.get("TG_CHANNEL").ok(),
+            tg_session_path: vars.get("TG_SESSION_PATH")
                 .unwrap_or_else(|_| "data/tg_session.session".to_string()),
 
             // Snipe Execution
-            snipe_amount_sol: env::var("SNIPE_AMOUNT_SOL")
+            snipe_amount_sol: vars.get("SNIPE_AMOUNT_SOL")
                 .ok().and_then(|v| v.parse().ok()).unwrap_or(0.25),
-            snipe_slippage_bps: env::var("SNIPE_SLIPPAGE_BPS")
-                .ok().and_then(|v| v.parse().ok()).unwrap_or(1500),
-            snipe_priority_fee_micro_lamports: env::var("SNIPE_PRIORITY_FEE_MICRO_LAMPORTS")
-                .ok().and_then(|v| v.parse().ok()).unwrap_or(1_000_000),
-            snipe_exit_delay_ms: env::var("SNIPE_EXIT_DELAY_MS")
+            snipe_exit_delay_ms: vars.get("SNIPE_EXIT_DELAY_MS")
                 .ok().and_then(|v| v.parse().ok()).unwrap_or(3000),
-            snipe_exit_percent: env::var("SNIPE_EXIT_PERCENT")
+            snipe_exit_percent: vars.get("SNIPE_EXIT_PERCENT")
                 .ok().and_then(|v| v.parse().ok()).unwrap_or(90),
 
             // Web API Configuration
-            api_host: env::var("API_HOST").ok(),
-            api_port: env::var("API_PORT")
+            api_host: vars.get("API_HOST").ok(),
+            api_port: vars.get("API_PORT")
                 .ok()
                 .and_then(|v| v.parse().ok())
-                .or_else(|| env::var("PORT").ok().and_then(|v| v.parse().ok())), // Railway uses PORT
+                .or_else(|| vars.get("PORT").ok().and_then(|v| v.parse().ok())), // Railway uses PORT
             cors_origins,
-            auto_start_trading: env::var("AUTO_START_TRADING")
+            api_request_timeout_secs: vars.get("API_REQUEST_TIMEOUT_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(30),
+            auto_start_trading: vars.get("AUTO_START_TRADING")
                 .map(|v| v.to_lowercase() == "true")
                 .unwrap_or(false),
+            notification_max_chars: vars.get("NOTIFICATION_MAX_CHARS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(4096),
+            ws_channel_capacity: vars.get("WS_CHANNEL_CAPACITY")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(100),
+            notification_queue_max: vars.get("NOTIFICATION_QUEUE_MAX")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(200),
+            strategy_capacity_alerts: vars.get("STRATEGY_CAPACITY_ALERTS")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            startup_digest_enabled: vars.get("STARTUP_DIGEST_ENABLED")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            pnl_report_time: vars.get("PNL_REPORT_TIME")
+                .ok().and_then(|v| NaiveTime::parse_from_str(v.trim(), "%H:%M").ok()),
+            pnl_report_weekday: vars.get("PNL_REPORT_WEEKDAY")
+                .map(|v| v.trim().parse().ok())
+                .unwrap_or(Some(Weekday::Mon)),
 
             // Copy Trade Configuration
-            treasury_wallet: env::var("TREASURY_WALLET").ok(),
-            copy_trade_fee_percent: env::var("COPY_TRADE_FEE_PERCENT")
+            treasury_wallet: vars.get("TREASURY_WALLET").ok(),
+            copy_trade_fee_percent: vars.get("COPY_TRADE_FEE_PERCENT")
                 .unwrap_or_else(|_| "10.0".to_string())
                 .parse()
                 .unwrap_or(10.0),
+            copy_source_poll_secs: vars.get("COPY_SOURCE_POLL_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(15),
+            copy_source_min_sol: vars.get("COPY_SOURCE_MIN_SOL")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(0.01),
+            profit_fee_percent: vars.get("PROFIT_FEE_PERCENT")
+                .ok().and_then(|v| v.parse().ok())
+                .filter(|v: &f64| (0.0..=100.0).contains(v))
+                .unwrap_or(0.0),
+            profit_fee_wallet: vars.get("PROFIT_FEE_WALLET").ok().filter(|v| !v.trim().is_empty()),
 
             // Trading Configuration
-            demo_mode: env::var("DEMO_MODE")
+            demo_mode: vars.get("DEMO_MODE")
                 .map(|v| v.to_lowercase() == "true")
                 .unwrap_or(true), // Default to demo mode
-            dry_run_mode: env::var("DRY_RUN_MODE")
+            dry_run_mode: vars.get("DRY_RUN_MODE")
                 .map(|v| v.to_lowercase() == "true")
                 .unwrap_or(false), // Default to false
-            max_position_size_sol: env::var("MAX_POSITION_SIZE_SOL")
+            confirm_real_mode: vars.get("CONFIRM_REAL_MODE")
+                .map(|v| v.trim().eq_ignore_ascii_case("yes"))
+                .unwrap_or(false),
+            real_mode_confirm_above_sol: vars.get("REAL_MODE_CONFIRM_ABOVE_SOL")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(0.1),
+            max_position_size_sol: vars.get("MAX_POSITION_SIZE_SOL")
                 .unwrap_or_else(|_| "0.01".to_string())
                 .parse()
                 .unwrap_or(0.01),
-            total_budget_sol: env::var("TOTAL_BUDGET_SOL")
+            max_allocation_per_token_sol: vars.get("MAX_ALLOCATION_PER_TOKEN_SOL")
+                .ok().and_then(|v| v.parse().ok()).filter(|v: &f64| *v > 0.0),
+            total_budget_sol: vars.get("TOTAL_BUDGET_SOL")
                 .unwrap_or_else(|_| "0.1".to_string())
                 .parse()
                 .unwrap_or(0.1),
-            default_stop_loss_percent: env::var("DEFAULT_STOP_LOSS_PERCENT")
+            default_stop_loss_percent: vars.get("DEFAULT_STOP_LOSS_PERCENT")
                 .unwrap_or_else(|_| "10".to_string())
                 .parse()
                 .unwrap_or(10),
-            default_take_profit_percent: env::var("DEFAULT_TAKE_PROFIT_PERCENT")
+            default_take_profit_percent: vars.get("DEFAULT_TAKE_PROFIT_PERCENT")
                 .unwrap_or_else(|_| "50".to_string())
                 .parse()
                 .unwrap_or(50),
-            default_trailing_stop_percent: env::var("DEFAULT_TRAILING_STOP_PERCENT")
+            default_trailing_stop_percent: vars.get("DEFAULT_TRAILING_STOP_PERCENT")
                 .unwrap_or_else(|_| "5".to_string())
                 .parse()
                 .unwrap_or(5),
-            max_hold_time_minutes: env::var("MAX_HOLD_TIME_MINUTES")
+            max_hold_time_minutes: vars.get("MAX_HOLD_TIME_MINUTES")
                 .unwrap_or_else(|_| "240".to_string())
                 .parse()
                 .unwrap_or(240),
 
             // Risk Parameters
-            min_liquidity_sol: env::var("MIN_LIQUIDITY_SOL")
+            min_liquidity_sol: vars.get("MIN_LIQUIDITY_SOL")
                 .unwrap_or_else(|_| "10".to_string())
                 .parse()
                 .unwrap_or(10),
-            max_risk_level: env::var("MAX_RISK_LEVEL")
+            max_risk_level: vars.get("MAX_RISK_LEVEL")
                 .unwrap_or_else(|_| "50".to_string())
                 .parse()
                 .unwrap_or(50),
-            min_holders: env::var("MIN_HOLDERS")
+            min_holders: vars.get("MIN_HOLDERS")
                 .unwrap_or_else(|_| "50".to_string())
                 .parse()
                 .unwrap_or(50),
+            transfer_tax_warn_percent: vars.get("TRANSFER_TAX_WARN_PERCENT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5.0),
+            min_exit_liquidity_sol: vars.get("MIN_EXIT_LIQUIDITY_SOL")
+                .ok().and_then(|v| v.parse().ok()).filter(|v: &f64| *v > 0.0),
+            rug_monitor: vars.get("RUG_MONITOR")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            rug_check_interval_secs: vars.get("RUG_CHECK_INTERVAL_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(30),
+            rug_liquidity_drop_percent: vars.get("RUG_LIQUIDITY_DROP_PERCENT")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(80.0),
+            rug_holder_dump_percent: vars.get("RUG_HOLDER_DUMP_PERCENT")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(50.0),
+            rug_whale_min_percent: vars.get("RUG_WHALE_MIN_PERCENT")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(5.0),
+            onchain_take_profit: vars.get("ONCHAIN_TAKE_PROFIT")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            max_position_fraction_of_liquidity: vars.get("MAX_POSITION_FRACTION_OF_LIQUIDITY")
+                .ok().and_then(|v| v.parse().ok()).filter(|v: &f64| *v > 0.0),
+            min_liquidity_capped_buy_sol: vars.get("MIN_LIQUIDITY_CAPPED_BUY_SOL")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(0.01),
+            max_roundtrip_loss_percent: vars.get("MAX_ROUNDTRIP_LOSS_PERCENT")
+                .ok().and_then(|v| v.parse().ok()).filter(|v: &f64| *v > 0.0),
+            onchain_liquidity_fallback: vars.get("ONCHAIN_LIQUIDITY_FALLBACK")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            deployer_check_enabled: vars.get("DEPLOYER_CHECK_ENABLED")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            deployer_check_launches: vars.get("DEPLOYER_CHECK_LAUNCHES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(5),
+            metadata_check_enabled: vars.get("METADATA_CHECK_ENABLED")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            usd_pnl_tracking: vars.get("USD_PNL_TRACKING")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            sol_downtrend_pause_percent: vars.get("SOL_DOWNTREND_PAUSE_PERCENT")
+                .ok().and_then(|v| v.parse().ok()).filter(|v: &f64| *v > 0.0),
+            sol_trend_lookback_minutes: vars.get("SOL_TREND_LOOKBACK_MINUTES")
+                .ok().and_then(|v| v.parse().ok()).filter(|v: &u64| *v > 0).unwrap_or(60),
+            blocklist_source: vars.get("BLOCKLIST_SOURCE").ok().filter(|v| !v.trim().is_empty()),
+            blocklist_refresh_minutes: vars.get("BLOCKLIST_REFRESH_MINUTES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(30),
+            mint_blacklist: address_list(vars, "MINT_BLACKLIST"),
+            mint_whitelist: address_list(vars, "MINT_WHITELIST"),
+            creator_blacklist: address_list(vars, "CREATOR_BLACKLIST"),
+            creator_whitelist: address_list(vars, "CREATOR_WHITELIST"),
+
+            // Dust Sweep
+            dust_sweep_enabled: vars.get("DUST_SWEEP_ENABLED")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            dust_sweep_interval_minutes: vars.get("DUST_SWEEP_INTERVAL_MINUTES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(360),
+            dust_sweep_max_value_sol: vars.get("DUST_SWEEP_MAX_VALUE_SOL")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(0.01),
+            dust_sweep_min_value_sol: vars.get("DUST_SWEEP_MIN_VALUE_SOL")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(0.0005),
+            dust_sweep_sell_orphans: vars.get("DUST_SWEEP_SELL_ORPHANS")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            dust_sweep_orphan_max_value_sol: vars.get("DUST_SWEEP_ORPHAN_MAX_VALUE_SOL")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(0.5),
+            dust_sweep_burn_worthless: vars.get("DUST_SWEEP_BURN_WORTHLESS")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            dust_sweep_close_empty_accounts: vars.get("DUST_SWEEP_CLOSE_EMPTY_ACCOUNTS")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            dust_sweep_keep_mints: address_list(vars, "DUST_SWEEP_KEEP_MINTS"),
+
+            // Limit Orders
+            limit_order_check_secs: vars.get("LIMIT_ORDER_CHECK_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(15),
+            limit_order_default_expiry_minutes: vars.get("LIMIT_ORDER_DEFAULT_EXPIRY_MINUTES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(60),
+
+            // Strategy Changelog
+            strategy_changelog_max_entries: vars.get("STRATEGY_CHANGELOG_MAX_ENTRIES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(100),
+
+            // Test Swap
+            test_swap_enabled: vars.get("TEST_SWAP_ENABLED")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            test_swap_amount_sol: vars.get("TEST_SWAP_AMOUNT_SOL")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(0.005),
+            test_swap_token_mint: vars.get("TEST_SWAP_TOKEN_MINT")
+                .ok().filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string()),
 
             // Transaction Parameters
-            default_slippage_bps: env::var("DEFAULT_SLIPPAGE_BPS")
+            jito_block_engine_url: vars.get("JITO_BLOCK_ENGINE_URL")
+                .unwrap_or_else(|_| "https://mainnet.block-engine.jito.wtf".to_string()),
+            jito_tip_lamports: vars.get("JITO_TIP_LAMPORTS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(100_000),
+            priority_fee_auto: vars.get("PRIORITY_FEE_AUTO")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            priority_fee_min_micro_lamports: vars.get("PRIORITY_FEE_MIN_MICRO_LAMPORTS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(10_000),
+            priority_fee_max_micro_lamports: vars.get("PRIORITY_FEE_MAX_MICRO_LAMPORTS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(2_000_000),
+            priority_fee_cache_secs: vars.get("PRIORITY_FEE_CACHE_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(10),
+            token_slippage_max_bps: vars.get("TOKEN_SLIPPAGE_MAX_BPS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(3000),
+            quote_max_age_ms: vars.get("QUOTE_MAX_AGE_MS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(2000),
+            confirm_timeout_secs: vars.get("CONFIRM_TIMEOUT_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(60),
+            post_timeout_verify_attempts: vars.get("POST_TIMEOUT_VERIFY_ATTEMPTS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(3),
+            post_timeout_verify_delay_ms: vars.get("POST_TIMEOUT_VERIFY_DELAY_MS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(5000),
+            verify_bought_mint: vars.get("VERIFY_BOUGHT_MINT")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            sell_mismatched_mint: vars.get("SELL_MISMATCHED_MINT")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            verify_buy_balance: vars.get("VERIFY_BUY_BALANCE")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            buy_balance_min_percent: vars.get("BUY_BALANCE_MIN_PERCENT")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(50.0),
+            reconcile_buy_fills: vars.get("RECONCILE_BUY_FILLS")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            entry_retry_max_window_ms: vars.get("ENTRY_RETRY_MAX_WINDOW_MS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(10000),
+            swap_retry_attempts: vars.get("SWAP_RETRY_ATTEMPTS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(2),
+            swap_retry_slippage_step_bps: vars.get("SWAP_RETRY_SLIPPAGE_STEP_BPS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(300),
+            swap_retry_max_slippage_bps: vars.get("SWAP_RETRY_MAX_SLIPPAGE_BPS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(2500),
+            swap_retry_fee_multiplier: vars.get("SWAP_RETRY_FEE_MULTIPLIER")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(2.0),
+            swap_retry_max_priority_fee_micro_lamports: vars.get("SWAP_RETRY_MAX_PRIORITY_FEE_MICRO_LAMPORTS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(3_000_000),
+            max_concurrent_swaps: vars.get("MAX_CONCURRENT_SWAPS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(3),
+            max_concurrent_exit_swaps: vars.get("MAX_CONCURRENT_EXIT_SWAPS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(5),
+            pumpfun_curve_trading: vars.get("PUMPFUN_CURVE_TRADING")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            swap_breaker_failures: vars.get("SWAP_BREAKER_FAILURES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(5),
+            swap_breaker_window_secs: vars.get("SWAP_BREAKER_WINDOW_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(300),
+            swap_breaker_cooldown_secs: vars.get("SWAP_BREAKER_COOLDOWN_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(900),
+            loss_breaker_consecutive_losses: vars.get("LOSS_BREAKER_CONSECUTIVE_LOSSES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(0),
+            loss_breaker_daily_drawdown_sol: vars.get("LOSS_BREAKER_DAILY_DRAWDOWN_SOL")
+                .ok().and_then(|v| v.parse().ok()).filter(|v: &f64| *v > 0.0),
+            loss_breaker_cooldown_minutes: vars.get("LOSS_BREAKER_COOLDOWN_MINUTES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(60),
+
+            // Wallet
+            balance_cache_ttl_ms: vars.get("BALANCE_CACHE_TTL_MS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(5000),
+            price_cache_ttl_ms: vars.get("PRICE_CACHE_TTL_MS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(3000),
+
+            // Manual Trades
+            require_confirmation_above_sol: vars.get("REQUIRE_CONFIRMATION_ABOVE_SOL")
+                .ok().and_then(|v| v.parse().ok()).filter(|v: &f64| *v > 0.0),
+            manual_snipe_max_risk_level: vars.get("MANUAL_SNIPE_MAX_RISK_LEVEL")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(70),
+            enrich_token_metadata: vars.get("ENRICH_TOKEN_METADATA")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            hide_positions_below_sol: vars.get("HIDE_POSITIONS_BELOW_SOL")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(0.001),
+
+            // Position Persistence
+            position_load_retries: vars.get("POSITION_LOAD_RETRIES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(3),
+            position_load_retry_delay_ms: vars.get("POSITION_LOAD_RETRY_DELAY_MS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(500),
+            position_archive_after_days: vars.get("POSITION_ARCHIVE_AFTER_DAYS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(30),
+            storage_backend: vars.get("STORAGE_BACKEND")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or_default(),
+            database_url: vars.get("DATABASE_URL")
+                .unwrap_or_else(|_| "sqlite://data/traderbot.db".to_string()),
+
+            // Token Scan
+            scan_interval_secs: vars.get("SCAN_INTERVAL_SECS")
+                .ok().and_then(|v| v.parse().ok()).filter(|v: &u64| *v > 0).unwrap_or(60),
+            scan_token_age_minutes: vars.get("SCAN_TOKEN_AGE_MINUTES")
+                .ok().and_then(|v| v.parse().ok()).filter(|v: &u64| *v > 0).unwrap_or(60),
+
+            // Position Monitor
+            position_monitor_min_secs: vars.get("POSITION_MONITOR_MIN_SECS")
+                .ok().and_then(|v| v.parse().ok()).filter(|v: &u64| *v > 0).unwrap_or(5),
+            position_monitor_max_secs: vars.get("POSITION_MONITOR_MAX_SECS")
+                .ok().and_then(|v| v.parse().ok()).filter(|v: &u64| *v > 0).unwrap_or(30),
+            position_price_stream_secs: vars.get("POSITION_PRICE_STREAM_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(5),
+            exit_retry_attempts: vars.get("EXIT_RETRY_ATTEMPTS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(5),
+            exit_retry_grace_minutes: vars.get("EXIT_RETRY_GRACE_MINUTES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(10),
+            unroutable_exit_attempts: vars.get("UNROUTABLE_EXIT_ATTEMPTS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(3),
+            hold_sl_tighten_window_percent: vars.get("HOLD_SL_TIGHTEN_WINDOW_PERCENT")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(0.0),
+            hold_sl_tighten_curve: vars.get("HOLD_SL_TIGHTEN_CURVE")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(1.0),
+            hold_sl_tighten_final_gap_percent: vars.get("HOLD_SL_TIGHTEN_FINAL_GAP_PERCENT")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(2.0),
+
+            // Loss Rebuy Guard
+            loss_rebuy_guard: vars.get("LOSS_REBUY_GUARD")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or_default(),
+            loss_rebuy_cooldown_minutes: vars.get("LOSS_REBUY_COOLDOWN_MINUTES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(60),
+
+            // Strategy Stats History
+            strategy_stats_snapshot_minutes: vars.get("STRATEGY_STATS_SNAPSHOT_MINUTES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(60),
+
+            // Escalation
+            escalation_enabled: vars.get("ESCALATION_ENABLED")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            escalation_repeat_minutes: vars.get("ESCALATION_REPEAT_MINUTES")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(15),
+            escalation_rpc_down_secs: vars.get("ESCALATION_RPC_DOWN_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(300),
+
+            // Real-time Discovery
+            realtime_discovery_enabled: vars.get("REALTIME_DISCOVERY_ENABLED")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            realtime_discovery_ws_url: vars.get("REALTIME_DISCOVERY_WS_URL").ok().filter(|v| !v.is_empty()),
+            realtime_pool_discovery: vars.get("REALTIME_POOL_DISCOVERY")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+
+            // Logging
+            log_dir: vars.get("LOG_DIR").ok().filter(|v| !v.trim().is_empty()),
+            log_file: vars.get("LOG_FILE").ok().filter(|v| !v.trim().is_empty()),
+            log_retention_days: vars.get("LOG_RETENTION_DAYS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(7),
+
+            // Instance Lock
+            instance_lock_enabled: vars.get("INSTANCE_LOCK_ENABLED")
+                .map(|v| v.to_lowercase() != "false")
+                .unwrap_or(true),
+            instance_lock_stale_secs: vars.get("INSTANCE_LOCK_STALE_SECS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(120),
+
+            live: LiveSettings::new(ReloadableSettings::load_from(vars)?),
+        })
+    }
+
+    /// Current slippage, priority fees and API credentials. Read these through here
+    /// rather than caching them, so a reload reaches every component.
+    pub fn live(&self) -> ReloadableSettings {
+        self.live.get()
+    }
+
+    /// Re-read `.env` and the environment and apply the reloadable settings. Returns
+    /// the freshly loaded config (for settings owned elsewhere, e.g. scan settings) and
+    /// which settings changed. Variables set in the process environment keep
+    /// precedence over `.env`, as at startup; ones removed from `.env` are unset.
+    pub fn reload(&self) -> Result<(Config, ConfigReload)> {
+        let vars = match dotenv::dotenv_iter() {
+            Ok(iter) => EnvVars::reloaded(
+                iter.collect::<std::result::Result<_, _>>().context("Failed to parse .env")?,
+            ),
+            Err(e) => {
+                debug!("No .env to reload ({}), using the process environment", e);
+                EnvVars::process()
+            }
+        };
+        let fresh = Config::load_from(&vars)?;
+        let reload = ConfigReload::between(self, &fresh)?;
+        self.live.replace(fresh.live());
+        Ok((fresh, reload))
+    }
+}
+
+/// Variables present before `.env` was read. dotenv never overrides them, so a
+/// reload must not either.
+static PROCESS_ENV: OnceLock<HashSet<String>> = OnceLock::new();
+
+/// Where config values are read from. A reload reads a freshly parsed `.env` rather
+/// than the process environment, which still holds the values `.env` had at
+/// startup, so a variable removed from `.env` (e.g. a revoked API token) is unset.
+struct EnvVars {
+    dotenv: Option<HashMap<String, String>>, // None: the process environment only
+}
+
+impl EnvVars {
+    fn process() -> Self {
+        Self { dotenv: None }
+    }
+
+    fn reloaded(dotenv: HashMap<String, String>) -> Self {
+        Self { dotenv: Some(dotenv) }
+    }
+
+    fn get(&self, key: &str) -> std::result::Result<String, env::VarError> {
+        match &self.dotenv {
+            Some(_) if PROCESS_ENV.get().is_some_and(|vars| vars.contains(key)) => env::var(key),
+            Some(dotenv) => dotenv.get(key).cloned().ok_or(env::VarError::NotPresent),
+            None => env::var(key),
+        }
+    }
+}
+
+/// Load `.env` into the environment, remembering which variables were already set
+pub fn load_env_file() {
+    PROCESS_ENV.get_or_init(|| env::vars().map(|(key, _)| key).collect());
+    dotenv::dotenv().ok();
+}
+
+/// Settings that take effect without a restart when the config is reloaded
+/// (SIGHUP or POST /api/config/reload)
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ReloadableSettings {
+    pub default_slippage_bps: u32,
+    pub default_priority_fee_micro_lamports: u64,
+    pub snipe_slippage_bps: u32,            // default 1500 (15%)
+    pub snipe_priority_fee_micro_lamports: u64,  // default 1_000_000 (1M μlamports = high priority)
+    pub emergency_exit_slippage_bps: u32,   // default 5000
+    pub emergency_priority_fee_micro_lamports: u64, // default 5_000_000
+    pub api_admin_token: Option<String>,     // full access; unset disables auth
+    pub api_observer_token: Option<String>,  // read-only access
+    pub api_jwt_secret: Option<String>,      // HS256 secret for JWT bearer tokens with a "read"/"trade" scope; unset = API keys only
+}
+
+impl ReloadableSettings {
+    fn load_from(vars: &EnvVars) -> Result<Self> {
+        Ok(Self {
+            default_slippage_bps: vars.get("DEFAULT_SLIPPAGE_BPS")
                 .unwrap_or_else(|_| "100".to_string())
                 .parse()
                 .context("Failed to parse DEFAULT_SLIPPAGE_BPS")?,
-            default_priority_fee_micro_lamports: env::var("DEFAULT_PRIORITY_FEE_MICRO_LAMPORTS")
+            default_priority_fee_micro_lamports: vars.get("DEFAULT_PRIORITY_FEE_MICRO_LAMPORTS")
                 .unwrap_or_else(|_| "50000".to_string())
                 .parse()
                 .context("Failed to parse DEFAULT_PRIORITY_FEE_MICRO_LAMPORTS")?,
+            snipe_slippage_bps: vars.get("SNIPE_SLIPPAGE_BPS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(1500),
+            snipe_priority_fee_micro_lamports: vars.get("SNIPE_PRIORITY_FEE_MICRO_LAMPORTS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(1_000_000),
+            emergency_exit_slippage_bps: vars.get("EMERGENCY_EXIT_SLIPPAGE_BPS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(5000),
+            emergency_priority_fee_micro_lamports: vars.get("EMERGENCY_PRIORITY_FEE_MICRO_LAMPORTS")
+                .ok().and_then(|v| v.parse().ok()).unwrap_or(5_000_000),
+            api_admin_token: vars.get("API_ADMIN_TOKEN").ok().filter(|v| !v.is_empty()),
+            api_observer_token: vars.get("API_OBSERVER_TOKEN").ok().filter(|v| !v.is_empty()),
+            api_jwt_secret: vars.get("API_JWT_SECRET").ok().filter(|v| !v.is_empty()),
         })
     }
 }
+
+/// The reloadable settings, shared by every clone of a Config
+#[derive(Clone, Debug)]
+pub struct LiveSettings(Arc<RwLock<ReloadableSettings>>);
+
+impl LiveSettings {
+    pub fn new(settings: ReloadableSettings) -> Self {
+        Self(Arc::new(RwLock::new(settings)))
+    }
+
+    pub fn get(&self) -> ReloadableSettings {
+        self.0.read().unwrap().clone()
+    }
+
+    fn replace(&self, settings: ReloadableSettings) {
+        *self.0.write().unwrap() = settings;
+    }
+}
+
+impl Serialize for LiveSettings {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.get().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LiveSettings {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        ReloadableSettings::deserialize(deserializer).map(LiveSettings::new)
+    }
+}
+
+/// Setting names changed by a reload. Values are left out, as some are secrets.
+#[derive(Debug, Default, Serialize)]
+pub struct ConfigReload {
+    pub applied: Vec<String>,          // Reloadable settings now in effect
+    pub restart_required: Vec<String>, // Changed settings that only take effect after a restart
+}
+
+impl ConfigReload {
+    /// Settings of `fresh` that differ from `running`. The live settings are flattened
+    /// into the serialized config, so they're told apart by name.
+    fn between(running: &Config, fresh: &Config) -> Result<Self> {
+        let current_live = serde_json::to_value(running.live())?;
+        let fresh_live = serde_json::to_value(fresh.live())?;
+        let running = serde_json::to_value(running)?;
+        let loaded = serde_json::to_value(fresh)?;
+        let mut reload = ConfigReload::default();
+        if let (Some(running), Some(loaded)) = (running.as_object(), loaded.as_object()) {
+            for (name, value) in loaded {
+                if fresh_live.get(name).is_some() {
+                    if current_live.get(name) != Some(value) {
+                        reload.applied.push(name.clone());
+                    }
+                } else if running.get(name) != Some(value) {
+                    reload.restart_required.push(name.clone());
+                }
+            }
+        }
+        Ok(reload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reload_separates_live_settings_from_restart_only_ones() {
+        let running = Config::for_tests(&[("DEFAULT_SLIPPAGE_BPS", "100"), ("MAX_RPC_SLOT_LAG", "150")]);
+        let fresh = Config::for_tests(&[("DEFAULT_SLIPPAGE_BPS", "250"), ("MAX_RPC_SLOT_LAG", "300"), ("API_ADMIN_TOKEN", "secret")]);
+        let reload = ConfigReload::between(&running, &fresh).unwrap();
+        assert_eq!(reload.applied, vec!["api_admin_token", "default_slippage_bps"]);
+        assert_eq!(reload.restart_required, vec!["max_rpc_slot_lag"]);
+
+        assert!(ConfigReload::between(&running, &running).unwrap().applied.is_empty());
+    }
+
+    #[test]
+    fn reload_revokes_credentials_removed_from_env_file() {
+        let running = Config::for_tests(&[("API_ADMIN_TOKEN", "secret"), ("API_JWT_SECRET", "jwt")]);
+        let fresh = Config::for_tests(&[]);
+        assert_eq!(fresh.live().api_admin_token, None);
+        assert_eq!(fresh.live().api_jwt_secret, None);
+        let reload = ConfigReload::between(&running, &fresh).unwrap();
+        assert_eq!(reload.applied, vec!["api_admin_token", "api_jwt_secret"]);
+        assert!(reload.restart_required.is_empty());
+    }
+}
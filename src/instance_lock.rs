@@ -0,0 +1,182 @@
+//! Single-instance lock on the data directory
+//!
+//! Two bots sharing one wallet and `data/positions.json` double-trade and overwrite
+//! each other's state. At startup a lock file holding the owner's PID and host is
+//! created in the data directory; a second instance finding a live lock refuses to
+//! start. The owner refreshes a heartbeat in the file, so a lock left behind by a
+//! crashed process is taken over once its PID is gone (same host) or its heartbeat
+//! has gone stale (any host, e.g. a redeployed container on the same volume).
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+const LOCK_FILE: &str = "data/trader.lock";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub host: String,
+    pub started_at: DateTime<Utc>,
+    pub heartbeat_at: DateTime<Utc>,
+}
+
+/// Whether a lock found on disk belongs to no running instance
+pub fn lock_is_stale(
+    lock: &LockInfo,
+    own_pid: u32,
+    own_host: &str,
+    now: DateTime<Utc>,
+    stale_after_secs: u64,
+    pid_alive: impl Fn(u32) -> bool,
+) -> bool {
+    if (now - lock.heartbeat_at).num_seconds() > stale_after_secs as i64 {
+        return true;
+    }
+    // PIDs can only be checked on the host that wrote the lock. A lock carrying our own
+    // PID was left by an earlier run (containers restart as the same PID).
+    lock.host == own_host && (lock.pid == own_pid || !pid_alive(lock.pid))
+}
+
+/// Held for the life of the process; removes the lock file when dropped
+pub struct InstanceLock {
+    path: PathBuf,
+    info: LockInfo,
+    heartbeat: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl InstanceLock {
+    /// Take the lock or fail with the PID/host of the instance holding it
+    pub fn acquire(stale_after_secs: u64) -> Result<Self> {
+        let path = PathBuf::from(LOCK_FILE);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create data directory")?;
+        }
+
+        let now = Utc::now();
+        let info = LockInfo { pid: std::process::id(), host: hostname(), started_at: now, heartbeat_at: now };
+
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(serde_json::to_string(&info)?.as_bytes())
+                        .context("Failed to write instance lock")?;
+                    info!("🔒 Acquired instance lock {} (pid {})", path.display(), info.pid);
+                    return Ok(Self { path, info, heartbeat: None });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let existing = read_lock(&path);
+                    match &existing {
+                        Some(lock) if !lock_is_stale(lock, info.pid, &info.host, Utc::now(), stale_after_secs, pid_alive) => {
+                            bail!(
+                                "Another TraderTony instance (pid {} on {}, running since {}) is using the data directory. \
+                                 Stop it first; running two instances against the same wallet and positions double-trades. \
+                                 If it is definitely gone, delete {}.",
+                                lock.pid, lock.host, lock.started_at, path.display()
+                            );
+                        }
+                        Some(lock) => warn!(
+                            "Removing stale instance lock from pid {} on {} (last heartbeat {})",
+                            lock.pid, lock.host, lock.heartbeat_at
+                        ),
+                        None => warn!("Removing unreadable instance lock {}", path.display()),
+                    }
+                    std::fs::remove_file(&path).context("Failed to remove stale instance lock")?;
+                }
+                Err(e) => return Err(e).context("Failed to create instance lock"),
+            }
+        }
+        bail!("Another instance took the lock at {} while starting", path.display())
+    }
+
+    /// Refresh the heartbeat every `interval_secs` so other hosts can tell the lock is live
+    pub fn spawn_heartbeat(&mut self, interval_secs: u64) {
+        let path = self.path.clone();
+        let mut info = self.info.clone();
+        self.heartbeat = Some(tokio::spawn(async move {
+            let mut timer = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+            timer.tick().await;
+            loop {
+                timer.tick().await;
+                info.heartbeat_at = Utc::now();
+                // Only rewrite a lock that is still ours
+                if read_lock(&path).is_some_and(|l| l.pid == info.pid && l.started_at == info.started_at) {
+                    if let Ok(data) = serde_json::to_string(&info) {
+                        if let Err(e) = tokio::fs::write(&path, data).await {
+                            warn!("Failed to refresh instance lock heartbeat: {:?}", e);
+                        }
+                    }
+                } else {
+                    warn!("Instance lock {} is no longer held by this process", path.display());
+                }
+            }
+        }));
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        if let Some(heartbeat) = self.heartbeat.take() {
+            heartbeat.abort();
+        }
+        if read_lock(&self.path).is_some_and(|l| l.pid == self.info.pid && l.started_at == self.info.started_at) {
+            match std::fs::remove_file(&self.path) {
+                Ok(()) => info!("🔓 Released instance lock {}", self.path.display()),
+                Err(e) => warn!("Failed to remove instance lock {}: {:?}", self.path.display(), e),
+            }
+        }
+    }
+}
+
+fn read_lock(path: &Path) -> Option<LockInfo> {
+    std::fs::read_to_string(path).ok().and_then(|data| serde_json::from_str(&data).ok())
+}
+
+fn hostname() -> String {
+    std::env::var("HOSTNAME").ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(unix)]
+fn pid_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(true) // Can't tell: assume it's running
+}
+
+#[cfg(not(unix))]
+fn pid_alive(_pid: u32) -> bool {
+    true // No portable check; rely on the heartbeat going stale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    #[test]
+    fn stale_when_owner_gone_or_heartbeat_old() {
+        let now = Utc::now();
+        let lock = LockInfo { pid: 42, host: "box".to_string(), started_at: now, heartbeat_at: now };
+
+        assert!(!lock_is_stale(&lock, 7, "box", now, 120, |_| true));
+        assert!(lock_is_stale(&lock, 7, "box", now, 120, |_| false));
+        assert!(lock_is_stale(&lock, 42, "box", now, 120, |_| true));
+        // Another host's PID can't be checked, only its heartbeat
+        assert!(!lock_is_stale(&lock, 7, "other", now, 120, |_| false));
+        assert!(lock_is_stale(&lock, 7, "other", now + ChronoDuration::seconds(121), 120, |_| true));
+    }
+}
@@ -1,23 +1,32 @@
 use anyhow::{Context, Result};
-use dotenv::dotenv;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tracing::{info, warn, Level};
-use tracing_subscriber::FmtSubscriber;
+use tracing::{info, warn};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
 
 mod api;
 mod config;
 mod error;
+mod instance_lock;
 mod models;
 mod solana;
+mod storage;
 mod trading;
 mod web;
 
 use crate::config::Config;
 use crate::solana::client::SolanaClient;
 use crate::solana::wallet::WalletManager;
+use crate::solana::wallet_pool::WalletPool;
 use crate::trading::autotrader::AutoTrader;
+use crate::trading::loss_breaker::LossBreakerReason;
+use crate::trading::position::{CapacityLimit, PositionLifecycle};
 use crate::web::AppState;
+use crate::web::websocket::WsMessage;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -28,46 +37,105 @@ async fn main() -> Result<()> {
         eprintln!("=== PANIC === {}", info);
     }));
 
-    // Initialize logging
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)?;
-
     // Load environment variables
-    dotenv().ok();
+    config::load_env_file();
 
     // Load configuration
     let config = Arc::new(Config::load()?);
+
+    // Initialize logging. The guard flushes the file writer on shutdown, so keep it alive.
+    let _log_guard = init_logging(&config)?;
+
+    // Refuse to run alongside another instance on the same data directory.
+    // Held until main returns; dropping it removes the lock file.
+    let _instance_lock = if config.instance_lock_enabled {
+        let mut lock = instance_lock::InstanceLock::acquire(config.instance_lock_stale_secs)?;
+        lock.spawn_heartbeat(config.instance_lock_stale_secs / 4);
+        Some(lock)
+    } else {
+        warn!("INSTANCE_LOCK_ENABLED=false - nothing stops a second instance from using the same data directory");
+        None
+    };
+
     info!("Configuration loaded successfully (v4.1.0 - multi-strategy)");
     info!("Demo mode: {}", config.demo_mode);
     info!("Dry run mode: {}", config.dry_run_mode);
+    if config.live().api_admin_token.is_none() && config.live().api_jwt_secret.is_none() {
+        tracing::warn!("Neither API_ADMIN_TOKEN nor API_JWT_SECRET set - web API is unauthenticated");
+    }
 
     // Initialize Solana client
-    let solana_client = Arc::new(SolanaClient::new(&config.solana_rpc_url)?);
+    let solana_client = Arc::new(SolanaClient::new(
+        &config.solana_rpc_url,
+        &config.solana_rpc_headers,
+        config.solana_rpc_auth_token.as_deref(),
+    )?.with_failover_urls(&config.solana_rpc_failover_urls)
+        .with_error_failover(config.rpc_failover_after_errors));
     // Don't block startup on RPC connection check - just log warning if it fails
     match solana_client.check_connection().await {
         Ok(_) => info!("Solana RPC connection verified"),
         Err(e) => tracing::warn!("Solana RPC connection check failed (will retry later): {}", e),
     }
     info!("Solana client initialized");
+    if config.max_rpc_slot_lag > 0 || solana_client.has_failover() {
+        solana_client.clone().spawn_health_monitor(config.rpc_slot_check_secs, config.max_rpc_slot_lag);
+    }
 
     // Initialize wallet manager
     let wallet_manager = WalletManager::new(
         &config.solana_private_key,
         solana_client.clone(),
         config.demo_mode,
+        config.balance_cache_ttl_ms,
     )?;
     info!("Wallet initialized with address: {}", wallet_manager.get_public_key());
 
+    // Additional wallets (if any) join the primary in a round-robin pool
+    let wallet_pool = WalletPool::new(
+        wallet_manager,
+        &config.additional_wallet_private_keys,
+        solana_client.clone(),
+        config.demo_mode,
+        config.balance_cache_ttl_ms,
+    )?;
+
     // Initialize AutoTrader
     let auto_trader = AutoTrader::new(
-        wallet_manager.clone(),
+        wallet_pool.clone(),
         solana_client.clone(),
         config.clone(),
     ).await?;
     info!("AutoTrader initialized");
 
+    // Escalation monitor (RPC health, swap breaker + repeat notifications for open incidents)
+    let escalation_manager = auto_trader.escalation_manager.clone();
+    escalation_manager.clone().start_monitoring(solana_client.clone(), auto_trader.swap_breaker());
+    let mut drawdown_alert_rx = auto_trader.position_manager.subscribe_drawdown_alerts();
+    let mut capacity_alert_rx = auto_trader.position_manager.subscribe_capacity_alerts();
+    let mut loss_breaker_rx = auto_trader.position_manager.subscribe_loss_breaker();
+    let mut price_tick_rx = auto_trader.position_manager.subscribe_price_ticks();
+    let mut dust_sweep_rx = auto_trader.dust_sweeper.subscribe();
+    let mut lifecycle_rx = auto_trader.position_manager.subscribe_lifecycle();
+    let mut limit_order_rx = auto_trader.limit_orders.subscribe();
+    let store = auto_trader.store();
+
+    // Don't auto-trade a funded wallet in REAL mode unless the operator confirmed it
+    let wallet_balance = wallet_pool.total_sol_balance().await.ok();
+    if config.real_mode_needs_confirmation(wallet_balance) {
+        let balance = wallet_balance.map_or("unknown".to_string(), |b| format!("{:.4} SOL", b));
+        tracing::error!("==================================================================");
+        tracing::error!("⚠️  REAL MODE NOT CONFIRMED - TRADING IS DISABLED");
+        tracing::error!("DEMO_MODE=false and the wallets hold {} (threshold {} SOL).", balance, config.real_mode_confirm_above_sol);
+        tracing::error!("Set CONFIRM_REAL_MODE=yes to trade with real funds.");
+        tracing::error!("==================================================================");
+        auto_trader.hold_trading(format!(
+            "REAL mode with {} in the wallets is not confirmed; set CONFIRM_REAL_MODE=yes",
+            balance
+        )).await;
+    } else if !config.demo_mode && !config.dry_run_mode {
+        warn!("⚠️  REAL MODE: trades use real funds");
+    }
+
     // Wrap AutoTrader in Arc<Mutex> for shared access
     let auto_trader = Arc::new(Mutex::new(auto_trader));
 
@@ -151,18 +219,347 @@ async fn main() -> Result<()> {
     // Create application state for web server
     let app_state = AppState::new(
         auto_trader,
-        wallet_manager,
+        wallet_pool,
         solana_client,
+        store,
         config.clone(),
     );
 
+    // Forward escalation incidents to WebSocket clients
+    {
+        let mut incident_rx = escalation_manager.subscribe();
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            loop {
+                let incident = match incident_rx.recv().await {
+                    Ok(incident) => incident,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                app_state.broadcast(WsMessage::Escalation {
+                    incident_id: incident.id,
+                    kind: format!("{:?}", incident.kind),
+                    message: incident.message,
+                    notify_count: incident.notify_count,
+                    first_seen: incident.first_seen,
+                    timestamp: chrono::Utc::now(),
+                });
+            }
+        });
+    }
+
+    // Forward peak-drawdown warnings to WebSocket clients
+    {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            loop {
+                let alert = match drawdown_alert_rx.recv().await {
+                    Ok(alert) => alert,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                app_state.broadcast(WsMessage::DrawdownAlert {
+                    position_id: alert.position_id,
+                    token_address: alert.token_address,
+                    token_symbol: alert.token_symbol,
+                    drawdown_percent: alert.drawdown_percent,
+                    highest_price_sol: alert.highest_price_sol,
+                    price_sol: alert.price_sol,
+                    sold_percent: alert.sold_percent,
+                    sold_value_sol: alert.sold_value_sol,
+                    timestamp: alert.timestamp,
+                });
+            }
+        });
+    }
+
+    // Stream live prices/PnL of open positions to WebSocket clients
+    {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            loop {
+                let positions = match price_tick_rx.recv().await {
+                    Ok(positions) => positions,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                app_state.broadcast(WsMessage::PositionPrices {
+                    positions,
+                    timestamp: chrono::Utc::now(),
+                });
+            }
+        });
+    }
+
+    // Forward "strategy maxed out" alerts to WebSocket clients
+    {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            loop {
+                let alert = match capacity_alert_rx.recv().await {
+                    Ok(alert) => alert,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                let limit = match alert.limit {
+                    CapacityLimit::MaxConcurrentPositions => "max_concurrent_positions",
+                    CapacityLimit::Budget => "budget",
+                };
+                app_state.broadcast(WsMessage::StrategyCapacity {
+                    strategy_id: alert.strategy_id,
+                    strategy_name: alert.strategy_name,
+                    limit: limit.to_string(),
+                    open_positions: alert.open_positions,
+                    max_concurrent_positions: alert.max_concurrent_positions,
+                    deployed_sol: alert.deployed_sol,
+                    budget_sol: alert.budget_sol,
+                    timestamp: alert.timestamp,
+                });
+            }
+        });
+    }
+
+    // Forward loss circuit breaker pauses and resumes to WebSocket clients
+    {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            loop {
+                let event = match loss_breaker_rx.recv().await {
+                    Ok(event) => event,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                let reason = match event.reason {
+                    LossBreakerReason::ConsecutiveLosses => "consecutive_losses",
+                    LossBreakerReason::DailyDrawdown => "daily_drawdown",
+                };
+                app_state.broadcast(WsMessage::LossBreaker {
+                    strategy_id: event.strategy_id,
+                    reason: reason.to_string(),
+                    tripped: event.tripped,
+                    consecutive_losses: event.consecutive_losses,
+                    daily_pnl_sol: event.daily_pnl_sol,
+                    paused_until: event.paused_until,
+                    timestamp: event.timestamp,
+                });
+            }
+        });
+    }
+
+    // Forward dust sweep results to WebSocket clients
+    {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            loop {
+                let report = match dust_sweep_rx.recv().await {
+                    Ok(report) => report,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                app_state.broadcast(WsMessage::DustSwept {
+                    swept_tokens: report.swept_tokens,
+                    reclaimed_sol: report.reclaimed_sol,
+                    skipped_tokens: report.skipped_tokens,
+                    burned_tokens: report.burned_tokens,
+                    closed_accounts: report.closed_accounts,
+                    reclaimed_rent_sol: report.reclaimed_rent_sol,
+                    timestamp: report.timestamp,
+                });
+            }
+        });
+    }
+
+    // Forward limit order fills/expiries to WebSocket clients
+    {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            loop {
+                let order = match limit_order_rx.recv().await {
+                    Ok(order) => order,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                app_state.broadcast(WsMessage::LimitOrderUpdate {
+                    status: format!("{:?}", order.status).to_lowercase(),
+                    id: order.id,
+                    token_address: order.token_address,
+                    token_symbol: order.token_symbol,
+                    target_price_sol: order.target_price_sol,
+                    amount_sol: order.amount_sol,
+                    signature: order.signature,
+                    error: order.error,
+                    timestamp: order.closed_at.unwrap_or_else(chrono::Utc::now),
+                });
+            }
+        });
+    }
+
+    // Forward position opens/closes to WebSocket clients, as each strategy's notify_trades allows
+    {
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            loop {
+                let event = match lifecycle_rx.recv().await {
+                    Ok(event) => event,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(missed)) => {
+                        warn!("Position lifecycle forwarder lagged and dropped {} open/close notifications", missed);
+                        continue;
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                if !event.should_notify() {
+                    continue;
+                }
+                app_state.broadcast(match event {
+                    PositionLifecycle::Opened(p, _) => WsMessage::PositionOpened {
+                        id: p.id,
+                        token_address: p.token_address,
+                        token_symbol: p.token_symbol,
+                        entry_value_sol: p.entry_value_sol,
+                        token_amount: p.entry_token_amount,
+                        strategy_id: p.strategy_id,
+                        timestamp: p.entry_time,
+                    },
+                    PositionLifecycle::Closed(p, _) => WsMessage::PositionClosed {
+                        exit_reason: p.exit_reason(),
+                        id: p.id,
+                        token_address: p.token_address,
+                        token_symbol: p.token_symbol,
+                        exit_value_sol: p.exit_value_sol.unwrap_or(0.0),
+                        pnl_sol: p.pnl_sol.unwrap_or(0.0),
+                        pnl_percent: p.pnl_percent.unwrap_or(0.0),
+                        timestamp: p.exit_time.unwrap_or_else(chrono::Utc::now),
+                    },
+                });
+            }
+        });
+    }
+
     // Initialize async components (copy trade manager, etc.)
     app_state.init().await.context("Failed to initialize app state")?;
     info!("Copy trade manager initialized");
 
-    // Start the web server
+    if config.startup_digest_enabled {
+        app_state.send_startup_digest().await;
+    }
+
+    spawn_reload_on_sighup(app_state.clone());
+
+    // Start the web server; return on SIGINT/SIGTERM so the instance lock is released
     info!("Starting TraderTony V4 API server...");
-    web::server::start_server(app_state, config).await?;
+    tokio::select! {
+        result = web::server::start_server(app_state, config) => result?,
+        _ = shutdown_signal() => info!("Shutdown signal received, exiting"),
+    }
 
     Ok(())
 }
+
+/// Reload the config on every SIGHUP, as POST /api/config/reload does
+#[cfg(unix)]
+fn spawn_reload_on_sighup(app_state: AppState) {
+    let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+        Ok(signal) => signal,
+        Err(e) => {
+            warn!("Failed to listen for SIGHUP, config reload only via the API: {:?}", e);
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            info!("SIGHUP received, reloading config");
+            if let Err(e) = app_state.reload_config().await {
+                warn!("Config reload failed, keeping the current settings: {:#}", e);
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn spawn_reload_on_sighup(_app_state: AppState) {}
+
+/// Resolves on Ctrl+C, or SIGTERM (what container platforms send on stop/redeploy)
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Failed to listen for Ctrl+C: {:?}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {:?}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Set up stdout logging, plus daily-rotated file logging when LOG_DIR or LOG_FILE is set.
+/// File writes go through a non-blocking worker so rotation never stalls the runtime.
+fn init_logging(config: &Config) -> Result<Option<WorkerGuard>> {
+    let stdout_layer = tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO);
+
+    if config.log_dir.is_none() && config.log_file.is_none() {
+        tracing_subscriber::registry().with(stdout_layer).try_init()?;
+        return Ok(None);
+    }
+
+    let (dir, prefix) = log_file_location(config);
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create log directory {}", dir.display()))?;
+
+    let mut builder = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(prefix.clone());
+    if config.log_retention_days > 0 {
+        builder = builder.max_log_files(config.log_retention_days);
+    }
+    let appender = builder.build(&dir)
+        .with_context(|| format!("Failed to open log file in {}", dir.display()))?;
+    let (file_writer, guard) = tracing_appender::non_blocking(appender);
+
+    let file_layer = tracing_subscriber::fmt::layer()
+        .with_writer(file_writer)
+        .with_ansi(false)
+        .with_filter(LevelFilter::INFO);
+
+    tracing_subscriber::registry().with(stdout_layer).with(file_layer).try_init()?;
+    info!(
+        "Logging to {}/{}.<date> (keeping {} days)",
+        dir.display(), prefix, config.log_retention_days
+    );
+    Ok(Some(guard))
+}
+
+/// Directory and file-name prefix for rotated logs. LOG_FILE may be a full path,
+/// in which case its parent directory takes precedence over LOG_DIR.
+fn log_file_location(config: &Config) -> (PathBuf, String) {
+    let default_dir = || PathBuf::from(config.log_dir.as_deref().unwrap_or("logs"));
+    match config.log_file.as_deref().map(Path::new) {
+        Some(path) => {
+            let prefix = path.file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "trader-tony.log".to_string());
+            let dir = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                _ => default_dir(),
+            };
+            (dir, prefix)
+        }
+        None => (default_dir(), "trader-tony.log".to_string()),
+    }
+}
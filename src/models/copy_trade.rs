@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -29,6 +31,9 @@ pub struct TradeSignal {
     pub current_price_sol: Option<f64>,
     /// Current PnL percentage (for active positions)
     pub current_pnl_percent: Option<f64>,
+    /// External wallet whose on-chain trade produced this signal (None = the bot)
+    #[serde(default)]
+    pub source_wallet: Option<String>,
 }
 
 impl TradeSignal {
@@ -54,6 +59,7 @@ impl TradeSignal {
             is_active: true,
             current_price_sol: Some(price_sol),
             current_pnl_percent: Some(0.0),
+            source_wallet: None,
         }
     }
 
@@ -80,8 +86,15 @@ impl TradeSignal {
             is_active: false, // Sell signals are immediately inactive
             current_price_sol: Some(price_sol),
             current_pnl_percent: Some(pnl_percent),
+            source_wallet: None,
         }
     }
+
+    /// Key standing in for `bot_position_id` on signals from a source wallet,
+    /// so a source's sell deactivates its earlier buy of the same token
+    pub fn source_position_key(source_wallet: &str, token_address: &str) -> String {
+        format!("source:{}:{}", source_wallet, token_address)
+    }
 }
 
 /// Trade action type
@@ -101,6 +114,33 @@ impl std::fmt::Display for TradeAction {
     }
 }
 
+/// An external wallet whose on-chain buys/sells are turned into trade signals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceWallet {
+    /// Wallet address (Solana public key)
+    pub address: String,
+    /// Optional display name
+    pub label: Option<String>,
+    /// When the wallet was added
+    pub added_at: DateTime<Utc>,
+    /// Newest transaction already processed (polling resumes after it)
+    pub last_seen_signature: Option<String>,
+    /// Signals generated from this wallet
+    pub signals_generated: u32,
+}
+
+impl SourceWallet {
+    pub fn new(address: &str, label: Option<String>) -> Self {
+        Self {
+            address: address.to_string(),
+            label,
+            added_at: Utc::now(),
+            last_seen_signature: None,
+            signals_generated: 0,
+        }
+    }
+}
+
 /// A registered copy trader (user who wants to copy trades)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CopyTrader {
@@ -367,3 +407,19 @@ impl Default for CopyTradeStats {
         }
     }
 }
+
+/// Snapshot of all persisted copy-trade data, as stored and in state export/import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CopyTradeState {
+    pub traders: Vec<CopyTrader>,
+    pub signals: Vec<TradeSignal>,
+    /// Copy positions by copier wallet
+    pub copy_positions: HashMap<String, Vec<CopyPosition>>,
+    pub source_wallets: Vec<SourceWallet>,
+}
+
+impl CopyTradeState {
+    pub fn is_empty(&self) -> bool {
+        self.traders.is_empty() && self.signals.is_empty() && self.copy_positions.is_empty() && self.source_wallets.is_empty()
+    }
+}
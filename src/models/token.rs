@@ -10,6 +10,8 @@ pub struct TokenMetadata {
     pub supply: Option<u64>,                 // Total supply (use u64 for lamports/raw units)
     pub logo_uri: Option<String>,            // Logo URL
     pub creation_time: Option<DateTime<Utc>>, // Token creation time (if available)
+    #[serde(default)]
+    pub creator: Option<String>,             // Creator/dev wallet (if known)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
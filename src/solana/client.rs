@@ -1,7 +1,8 @@
 use anyhow::{anyhow, Context, Result};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::str::FromStr;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::future::Future;
 use tracing::{info, warn, debug, error};
 use solana_sdk::{
@@ -13,10 +14,14 @@ use solana_sdk::{
 };
 use solana_client::{
     nonblocking::rpc_client::RpcClient,
+    http_sender::HttpSender,
+    rpc_client::RpcClientConfig,
     client_error::ClientError,
     rpc_config::{RpcTransactionConfig, RpcSimulateTransactionConfig, RpcSendTransactionConfig},
-    rpc_response::{RpcSimulateTransactionResult, RpcTokenAccountBalance},
+    rpc_request::TokenAccountsFilter,
+    rpc_response::{RpcKeyedAccount, RpcSimulateTransactionResult, RpcTokenAccountBalance},
 };
+use solana_account_decoder::UiAccountData;
 use solana_transaction_status::{UiTransactionEncoding, EncodedConfirmedTransactionWithStatusMeta};
 use spl_token::state::{Account as TokenAccount, Mint};
 use spl_associated_token_account::get_associated_token_address;
@@ -74,22 +79,371 @@ where
     }
 }
 
+/// Nominal Solana slot time, used to estimate lag when there's no reference RPC
+const SLOT_TIME_MS: u128 = 400;
+
+/// A token balance held by a wallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenHolding {
+    pub mint: String,
+    pub raw_amount: u64,
+    pub ui_amount: f64,
+    pub decimals: u8,
+    pub account: Pubkey,    // Token account holding the balance
+    pub program_id: Pubkey, // SPL Token or Token-2022
+    pub lamports: u64,      // Rent held by the account, returned when it's closed
+}
+
+/// The balance of a jsonParsed token account (possibly empty)
+fn parse_token_holding(keyed: &RpcKeyedAccount) -> Option<TokenHolding> {
+    let UiAccountData::Json(parsed) = &keyed.account.data else {
+        return None;
+    };
+    let info = &parsed.parsed["info"];
+    let amount = &info["tokenAmount"];
+    let mint = info["mint"].as_str()?;
+    let raw_amount = amount["amount"].as_str().and_then(|a| a.parse::<u64>().ok())?;
+    let decimals = amount["decimals"].as_u64()? as u8;
+    Some(TokenHolding {
+        mint: mint.to_string(),
+        raw_amount,
+        ui_amount: spl_token::amount_to_ui_amount(raw_amount, decimals),
+        decimals,
+        account: Pubkey::from_str(&keyed.pubkey).ok()?,
+        program_id: Pubkey::from_str(&keyed.account.owner).ok()?,
+        lamports: keyed.account.lamports,
+    })
+}
+
+/// Result of the latest slot-lag check
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SlotLagStatus {
+    pub slot: u64,
+    pub lag_slots: u64,
+    pub lagging: bool,
+}
+
+#[derive(Debug, Default)]
+struct SlotLagState {
+    /// Last slot seen on the active endpoint and when, for the rate-based estimate
+    last_observation: Option<(u64, Instant)>,
+    status: Option<SlotLagStatus>,
+}
+
+/// Slots the active endpoint is behind: the best reference slot if any reference
+/// answered, otherwise how far it fell short of the expected slot rate since the
+/// previous observation.
+fn estimate_slot_lag(slot: u64, reference_slot: Option<u64>, previous: Option<(u64, u128)>) -> u64 {
+    if let Some(reference) = reference_slot {
+        return reference.saturating_sub(slot);
+    }
+    match previous {
+        Some((prev_slot, elapsed_ms)) => {
+            let expected = (elapsed_ms / SLOT_TIME_MS) as u64;
+            expected.saturating_sub(slot.saturating_sub(prev_slot))
+        }
+        None => 0,
+    }
+}
+
+/// Weight of the newest sample in an endpoint's latency moving average
+const LATENCY_EWMA_WEIGHT: f64 = 0.3;
+/// Score penalty for each consecutive failed probe, in milliseconds of latency
+const PROBE_ERROR_PENALTY_MS: f64 = 5_000.0;
+
+/// Probe history of one RPC endpoint
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EndpointHealth {
+    pub host: String,               // Host only: RPC URLs often carry an API key
+    pub active: bool,
+    pub latency_ms: Option<f64>,    // Moving average over successful probes
+    pub probes: u64,
+    pub errors: u64,
+    pub consecutive_errors: u32,
+    pub last_slot: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+impl EndpointHealth {
+    fn new(url: &str) -> Self {
+        let host = reqwest::Url::parse(url).ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| "unknown".to_string());
+        Self { host, ..Default::default() }
+    }
+
+    fn record(&mut self, probe: &Result<(u64, Duration), String>) {
+        self.probes += 1;
+        match probe {
+            Ok((slot, latency)) => {
+                let sample = latency.as_secs_f64() * 1000.0;
+                self.latency_ms = Some(match self.latency_ms {
+                    Some(avg) => avg + LATENCY_EWMA_WEIGHT * (sample - avg),
+                    None => sample,
+                });
+                self.consecutive_errors = 0;
+                self.last_slot = Some(*slot);
+            }
+            Err(e) => {
+                self.errors += 1;
+                self.consecutive_errors += 1;
+                self.last_error = Some(e.clone());
+            }
+        }
+    }
+
+    /// Lower is healthier: average latency plus a penalty per consecutive failure
+    fn score(&self) -> f64 {
+        self.latency_ms.unwrap_or(PROBE_ERROR_PENALTY_MS) + self.consecutive_errors as f64 * PROBE_ERROR_PENALTY_MS
+    }
+}
+
+/// An RPC error message with any URL cut down to its host, so API keys in paths or
+/// query strings don't end up in the health report
+fn redact_urls(message: &str) -> String {
+    static URL: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    URL.get_or_init(|| regex::Regex::new(r"(https?://[^/\s)?]+)[^\s)]*").unwrap())
+        .replace_all(message, "$1")
+        .into_owned()
+}
+
+/// The best-scoring endpoint other than `exclude` whose last probe succeeded
+fn healthiest_endpoint(health: &[EndpointHealth], exclude: usize) -> Option<usize> {
+    health.iter()
+        .enumerate()
+        .filter(|(i, h)| *i != exclude && h.probes > 0 && h.consecutive_errors == 0)
+        .min_by(|(_, a), (_, b)| a.score().total_cmp(&b.score()))
+        .map(|(i, _)| i)
+}
+
 /// Wrapper around Solana's RpcClient that adds retry logic and error handling.
 pub struct SolanaClient {
-    rpc_client: Arc<RpcClient>,
+    /// Primary RPC first, then failovers; requests go to the active one
+    endpoints: Vec<Arc<RpcClient>>,
+    active_endpoint: AtomicUsize,
+    slot_lag: Mutex<SlotLagState>,
+    health: Mutex<Vec<EndpointHealth>>, // Per-endpoint probe results, same order as `endpoints`
+    failover_after_errors: u32,         // Failed probes in a row before leaving the active endpoint (0 = never)
 }
 
 impl SolanaClient {
-    pub fn new(rpc_url: &str) -> Result<Self> {
+    /// Create a client for `rpc_url`. `headers` are sent with every RPC request and
+    /// `auth_token` as `Authorization: Bearer <token>`, for providers that
+    /// authenticate by header instead of (or in addition to) a key in the URL.
+    pub fn new(rpc_url: &str, headers: &[(String, String)], auth_token: Option<&str>) -> Result<Self> {
         let commitment_config = CommitmentConfig::confirmed();
-        let rpc_client = RpcClient::new_with_commitment(rpc_url.to_string(), commitment_config);
-        Ok(Self {
-            rpc_client: Arc::new(rpc_client),
-        })
+        if headers.is_empty() && auth_token.is_none() {
+            let rpc_client = RpcClient::new_with_commitment(rpc_url.to_string(), commitment_config);
+            return Ok(Self::with_endpoint(rpc_client, rpc_url));
+        }
+
+        let mut header_map = reqwest::header::HeaderMap::new();
+        for (name, value) in headers {
+            let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid RPC header name '{}'", name))?;
+            let value = reqwest::header::HeaderValue::from_str(value)
+                .with_context(|| format!("Invalid value for RPC header '{}'", name))?;
+            header_map.insert(name, value);
+        }
+        if let Some(token) = auth_token {
+            let mut value = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
+                .context("Invalid RPC auth token")?;
+            value.set_sensitive(true);
+            header_map.insert(reqwest::header::AUTHORIZATION, value);
+        }
+
+        let http_client = reqwest::Client::builder()
+            .default_headers(header_map)
+            .timeout(Duration::from_secs(30))
+            .build()
+            .context("Failed to build HTTP client for Solana RPC")?;
+        let sender = HttpSender::new_with_client(rpc_url, http_client);
+        let rpc_client = RpcClient::new_sender(sender, RpcClientConfig::with_commitment(commitment_config));
+        info!(
+            "Solana RPC client configured with {} custom header(s){}",
+            headers.len(),
+            if auth_token.is_some() { " and bearer auth" } else { "" }
+        );
+        Ok(Self::with_endpoint(rpc_client, rpc_url))
+    }
+
+    fn with_endpoint(rpc_client: RpcClient, rpc_url: &str) -> Self {
+        Self {
+            endpoints: vec![Arc::new(rpc_client)],
+            active_endpoint: AtomicUsize::new(0),
+            slot_lag: Mutex::new(SlotLagState::default()),
+            health: Mutex::new(vec![EndpointHealth::new(rpc_url)]),
+            failover_after_errors: 3,
+        }
+    }
+
+    /// Add failover endpoints to switch to when the active one lags. Custom headers
+    /// and the auth token are only sent to the primary, so failover URLs must carry
+    /// their own credentials.
+    pub fn with_failover_urls(mut self, urls: &[String]) -> Self {
+        for url in urls {
+            self.endpoints.push(Arc::new(RpcClient::new_with_commitment(url.clone(), CommitmentConfig::confirmed())));
+            self.health.get_mut().unwrap().push(EndpointHealth::new(url));
+        }
+        if !urls.is_empty() {
+            info!("Solana RPC configured with {} failover endpoint(s)", urls.len());
+        }
+        self
+    }
+
+    /// Fail over after this many failed health probes in a row on the active endpoint
+    pub fn with_error_failover(mut self, failed_probes: u32) -> Self {
+        self.failover_after_errors = failed_probes;
+        self
+    }
+
+    /// Whether there are failover endpoints to switch between
+    pub fn has_failover(&self) -> bool {
+        self.endpoints.len() > 1
+    }
+
+    /// Every endpoint's probe history, primary first
+    pub fn endpoint_health(&self) -> Vec<EndpointHealth> {
+        let active = self.active_endpoint.load(Ordering::Relaxed);
+        let mut health = self.health.lock().unwrap().clone();
+        for (index, endpoint) in health.iter_mut().enumerate() {
+            endpoint.active = index == active;
+        }
+        health
+    }
+
+    /// Time a getSlot on every endpoint at once and record each outcome. Returns
+    /// each endpoint's slot, None where the probe failed.
+    async fn probe_endpoints(&self) -> Vec<Option<u64>> {
+        let probes = self.endpoints.iter().map(|endpoint| async move {
+            let started = Instant::now();
+            endpoint.get_slot().await
+                .map(|slot| (slot, started.elapsed()))
+                .map_err(|e| redact_urls(&e.to_string()))
+        });
+        let results = futures::future::join_all(probes).await;
+        let mut health = self.health.lock().unwrap();
+        results.iter().zip(health.iter_mut())
+            .map(|(probe, endpoint)| {
+                endpoint.record(probe);
+                probe.as_ref().ok().map(|(slot, _)| *slot)
+            })
+            .collect()
+    }
+
+    /// Leave the active endpoint once it has failed `failover_after_errors` probes in
+    /// a row, for the healthiest endpoint that answered its last probe
+    fn fail_over_if_erroring(&self, active: usize) {
+        if self.failover_after_errors == 0 {
+            return;
+        }
+        let health = self.health.lock().unwrap();
+        let failures = health[active].consecutive_errors;
+        if failures < self.failover_after_errors {
+            return;
+        }
+        match healthiest_endpoint(&health, active) {
+            Some(index) => {
+                warn!(
+                    "Solana RPC {} failed {} health probes in a row; failing over to {} ({:.0}ms)",
+                    health[active].host, failures, health[index].host, health[index].latency_ms.unwrap_or_default()
+                );
+                self.active_endpoint.store(index, Ordering::Relaxed);
+            }
+            None => warn!("Solana RPC {} failed {} health probes in a row and no healthy endpoint is available", health[active].host, failures),
+        }
+    }
+
+    /// Latest slot-lag check result, if a check has run
+    pub fn slot_lag_status(&self) -> Option<SlotLagStatus> {
+        self.slot_lag.lock().unwrap().status
+    }
+
+    /// Whether the active RPC was behind by more than the limit at the last check
+    pub fn is_lagging(&self) -> bool {
+        self.slot_lag_status().is_some_and(|s| s.lagging)
+    }
+
+    /// Probe every endpoint and measure how far the active RPC is behind, using the
+    /// other endpoints as references. When it's more than `max_lag` slots behind and
+    /// a failover is available, switch to the endpoint with the highest slot; when
+    /// its probes keep failing, switch to the healthiest endpoint.
+    pub async fn check_slot_lag(&self, max_lag: u64) -> Result<SlotLagStatus> {
+        let slots = self.probe_endpoints().await;
+        let active = self.active_endpoint.load(Ordering::Relaxed);
+        let Some(slot) = slots[active] else {
+            self.fail_over_if_erroring(active);
+            let error = self.health.lock().unwrap()[active].last_error.clone().unwrap_or_default();
+            return Err(anyhow!("Failed to get slot from active RPC: {}", error));
+        };
+
+        let best_reference: Option<(usize, u64)> = slots.iter()
+            .enumerate()
+            .filter(|(i, _)| *i != active)
+            .filter_map(|(i, s)| s.map(|s| (i, s)))
+            .fold(None, |best, (i, s)| match best {
+                Some((_, best_slot)) if s <= best_slot => best,
+                _ => Some((i, s)),
+            });
+
+        let now = Instant::now();
+        let previous = self.slot_lag.lock().unwrap().last_observation
+            .map(|(prev_slot, at)| (prev_slot, now.duration_since(at).as_millis()));
+        let lag_slots = estimate_slot_lag(slot, best_reference.map(|(_, s)| s), previous);
+        let lagging = max_lag > 0 && lag_slots > max_lag;
+
+        let mut switched = false;
+        if lagging {
+            match best_reference {
+                Some((index, reference_slot)) if reference_slot > slot => {
+                    warn!(
+                        "Solana RPC is {} slots behind (limit {}); failing over to endpoint {}",
+                        lag_slots, max_lag, index
+                    );
+                    self.active_endpoint.store(index, Ordering::Relaxed);
+                    switched = true;
+                }
+                _ => warn!("Solana RPC is {} slots behind (limit {}) and no healthier endpoint is available", lag_slots, max_lag),
+            }
+        }
+
+        // After a failover the new endpoint is assumed current until its own check
+        let status = if switched {
+            SlotLagStatus { slot: best_reference.map_or(slot, |(_, s)| s), lag_slots: 0, lagging: false }
+        } else {
+            SlotLagStatus { slot, lag_slots, lagging }
+        };
+        let mut state = self.slot_lag.lock().unwrap();
+        state.last_observation = Some((status.slot, now));
+        state.status = Some(status);
+        Ok(status)
+    }
+
+    /// Probe the endpoints and check slot lag every `check_secs` until the process exits
+    pub fn spawn_health_monitor(self: Arc<Self>, check_secs: u64, max_lag: u64) {
+        info!(
+            "Starting RPC health monitor for {} endpoint(s) (every {}s, slot lag limit {})",
+            self.endpoints.len(), check_secs, max_lag
+        );
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(check_secs.max(1)));
+            loop {
+                ticker.tick().await;
+                match self.check_slot_lag(max_lag).await {
+                    Ok(status) => debug!("RPC slot {} ({} behind)", status.slot, status.lag_slots),
+                    Err(e) => debug!("Slot lag check failed: {}", e),
+                }
+            }
+        });
     }
 
     pub async fn check_connection(&self) -> Result<()> {
-        self.rpc_client.get_latest_blockhash().await
+        // getHealth is cheap and surfaces auth failures (401/403) clearly; some
+        // providers don't implement it, so it only warns and the blockhash call decides.
+        if let Err(e) = self.get_rpc().get_health().await {
+            warn!("Solana RPC health check failed: {}", e);
+        }
+        self.get_rpc().get_latest_blockhash().await
             .map(|_| info!("Successfully connected to Solana RPC"))
             .map_err(|e| {
                 error!("Failed to connect to Solana RPC: {}", e);
@@ -106,7 +460,7 @@ impl SolanaClient {
     {
         with_retries(
             || { // The operation closure passed to with_retries
-                let client = self.rpc_client.clone();
+                let client = self.get_rpc();
                 let f_clone = f.clone();
                 async move { // The future returned by the operation closure
                     tokio::task::spawn_blocking(move || f_clone(client))
@@ -122,7 +476,7 @@ impl SolanaClient {
 
 
     pub async fn get_sol_balance(&self, pubkey: &Pubkey) -> Result<f64> {
-        let lamports = self.rpc_client.get_balance(pubkey).await?;
+        let lamports = self.get_rpc().get_balance(pubkey).await?;
         let sol_balance = lamports as f64 / 1_000_000_000.0;
         Ok(sol_balance)
     }
@@ -141,8 +495,38 @@ impl SolanaClient {
         Ok(spl_token::amount_to_ui_amount(amount, decimals))
     }
 
+    /// Every non-empty SPL / Token-2022 balance held by `owner`
+    pub async fn get_token_holdings(&self, owner: &Pubkey) -> Result<Vec<TokenHolding>> {
+        let mut holdings = self.get_token_accounts(owner).await?;
+        holdings.retain(|h| h.raw_amount > 0);
+        Ok(holdings)
+    }
+
+    /// Every SPL / Token-2022 account of `owner`, empty ones included
+    pub async fn get_token_accounts(&self, owner: &Pubkey) -> Result<Vec<TokenHolding>> {
+        let mut holdings = Vec::new();
+        for program_id in [spl_token::id(), spl_token_2022::id()] {
+            let accounts = self.get_rpc()
+                .get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(program_id))
+                .await
+                .context(format!("Failed to list token accounts of {}", owner))?;
+            holdings.extend(accounts.iter().filter_map(parse_token_holding));
+        }
+        Ok(holdings)
+    }
+
+    /// Total UI balance of `mint` across all of `owner`'s token accounts (ATA or not,
+    /// SPL or Token-2022); 0 if it holds none
+    pub async fn get_mint_balance_ui(&self, owner: &Pubkey, mint: &Pubkey) -> Result<f64> {
+        let accounts = self.get_rpc()
+            .get_token_accounts_by_owner(owner, TokenAccountsFilter::Mint(*mint))
+            .await
+            .context(format!("Failed to list {} token accounts of {}", mint, owner))?;
+        Ok(accounts.iter().filter_map(parse_token_holding).map(|h| h.ui_amount).sum())
+    }
+
     pub async fn get_token_supply(&self, mint_pubkey: &Pubkey) -> Result<u64> {
-        let ui_amount = self.rpc_client.get_token_supply(mint_pubkey).await.context("Failed to get token supply RPC response")?;
+        let ui_amount = self.get_rpc().get_token_supply(mint_pubkey).await.context("Failed to get token supply RPC response")?;
         ui_amount.amount.parse::<u64>().context(format!(
             "Failed to parse token supply amount '{}' into u64",
             ui_amount.amount
@@ -150,12 +534,12 @@ impl SolanaClient {
     }
 
     pub async fn get_token_largest_accounts(&self, mint_pubkey: &Pubkey) -> Result<Vec<RpcTokenAccountBalance>> {
-        let result = self.rpc_client.get_token_largest_accounts(mint_pubkey).await.context("Failed to get token largest accounts")?;
+        let result = self.get_rpc().get_token_largest_accounts(mint_pubkey).await.context("Failed to get token largest accounts")?;
         Ok(result)
     }
 
     pub async fn get_account_data(&self, pubkey: &Pubkey) -> Result<Vec<u8>> {
-        let account = self.rpc_client.get_account(pubkey).await.context(format!("Failed to get account data for {}", pubkey))?;
+        let account = self.get_rpc().get_account(pubkey).await.context(format!("Failed to get account data for {}", pubkey))?;
         Ok(account.data)
     }
 
@@ -189,7 +573,7 @@ impl SolanaClient {
         
         // Clone to pass into the retry function
         let transaction_clone = transaction.clone();
-        let rpc_client = self.rpc_client.clone();
+        let rpc_client = self.get_rpc();
         
         with_retries(
             move || {
@@ -238,7 +622,7 @@ impl SolanaClient {
         
         // Clone to pass into the retry function
         let transaction_clone = transaction.clone();
-        let rpc_client = self.rpc_client.clone();
+        let rpc_client = self.get_rpc();
         
         let simulation_response = with_retries(
             move || {
@@ -289,7 +673,7 @@ impl SolanaClient {
     }
 
      pub fn get_rpc(&self) -> Arc<RpcClient> {
-        self.rpc_client.clone()
+        self.endpoints[self.active_endpoint.load(Ordering::Relaxed)].clone()
     }
 
     // Enhanced with better retry and error handling
@@ -314,7 +698,7 @@ impl SolanaClient {
             let statuses_result = with_retries(
                 move || {
                     let sig = signature_copy;
-                    let client = self.rpc_client.clone();
+                    let client = self.get_rpc();
                     
                     async move {
                         match client.get_signature_statuses(&[sig]).await {
@@ -379,6 +763,19 @@ impl SolanaClient {
         }
     }
 
+    /// One-off status lookup that also searches transaction history, for re-checking
+    /// a signature after `confirm_transaction` gave up.
+    /// Returns Some(true) if it landed successfully, Some(false) if it failed on-chain,
+    /// and None if the cluster doesn't know about it.
+    pub async fn get_signature_outcome(&self, signature: &Signature) -> Result<Option<bool>> {
+        let response = self.get_rpc()
+            .get_signature_statuses_with_history(&[*signature])
+            .await
+            .map_err(|e| TraderbotError::SolanaError(format!("Status lookup failed: {}", e)))?;
+
+        Ok(response.value.into_iter().next().flatten().map(|status| status.err.is_none()))
+    }
+
     pub async fn get_transaction(
         &self,
         signature: &Signature,
@@ -391,7 +788,7 @@ impl SolanaClient {
         };
         
         // Clone for async move
-        let rpc_client = self.rpc_client.clone();
+        let rpc_client = self.get_rpc();
         let signature_copy = *signature;
         
         with_retries(
@@ -456,3 +853,48 @@ impl SolanaClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_lag_prefers_reference_then_rate() {
+        // A reference RPC ahead of us gives the lag directly
+        assert_eq!(estimate_slot_lag(1_000, Some(1_150), Some((990, 4_000))), 150);
+        assert_eq!(estimate_slot_lag(1_000, Some(990), None), 0);
+        // Without one, a node stalled for 20s is ~50 slots behind the expected rate
+        assert_eq!(estimate_slot_lag(1_000, None, Some((1_000, 20_000))), 50);
+        assert_eq!(estimate_slot_lag(1_050, None, Some((1_000, 20_000))), 0);
+        assert_eq!(estimate_slot_lag(1_000, None, None), 0);
+    }
+
+    #[test]
+    fn healthiest_endpoint_skips_failing_ones() {
+        let mut health = vec![
+            EndpointHealth::new("https://primary.example.com/?api-key=secret"),
+            EndpointHealth::new("https://slow.example.com"),
+            EndpointHealth::new("https://fast.example.com"),
+        ];
+        assert_eq!(health[0].host, "primary.example.com");
+        // Nothing probed yet: nowhere to go
+        assert_eq!(healthiest_endpoint(&health, 0), None);
+
+        health[0].record(&Err("timeout".to_string()));
+        health[1].record(&Ok((100, Duration::from_millis(400))));
+        health[2].record(&Ok((100, Duration::from_millis(200))));
+        health[2].record(&Ok((101, Duration::from_millis(300))));
+        assert!((health[2].latency_ms.unwrap() - 230.0).abs() < 1e-9);
+        assert_eq!(healthiest_endpoint(&health, 0), Some(2));
+
+        health[2].record(&Err("connection refused".to_string()));
+        assert_eq!(health[2].consecutive_errors, 1);
+        assert_eq!(healthiest_endpoint(&health, 0), Some(1));
+        assert!(health[2].score() > health[1].score());
+
+        assert_eq!(
+            redact_urls("error sending request for url (https://rpc.example.com/v1/KEY?api-key=secret): timed out"),
+            "error sending request for url (https://rpc.example.com): timed out"
+        );
+    }
+}
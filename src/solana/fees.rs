@@ -0,0 +1,222 @@
+//! Priority fee estimation
+//!
+//! A fixed priority fee overpays while the network is quiet and fails to land
+//! when it's congested. The estimator asks the RPC what transactions touching
+//! the traded token have recently paid: Helius' `getPriorityFeeEstimate` where
+//! available, otherwise a percentile of `getRecentPrioritizationFees` over the
+//! last ~150 slots. The result is clamped to the configured bounds.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use crate::config::Config;
+use crate::solana::client::SolanaClient;
+
+/// How hard a swap should compete for block space
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeUrgency {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Max,
+}
+
+impl FeeUrgency {
+    /// Percentile of recent per-slot fees paid at this urgency
+    pub fn percentile(self) -> f64 {
+        match self {
+            FeeUrgency::Low => 25.0,
+            FeeUrgency::Medium => 50.0,
+            FeeUrgency::High => 75.0,
+            FeeUrgency::Max => 95.0,
+        }
+    }
+}
+
+/// Nearest-rank percentile of `fees` (sorted in place); None when empty
+pub fn fee_percentile(fees: &mut [u64], percentile: f64) -> Option<u64> {
+    if fees.is_empty() {
+        return None;
+    }
+    fees.sort_unstable();
+    let rank = (percentile.clamp(0.0, 100.0) / 100.0 * fees.len() as f64).ceil() as usize;
+    Some(fees[rank.saturating_sub(1).min(fees.len() - 1)])
+}
+
+/// `priorityFeeLevels` of a Helius `getPriorityFeeEstimate` response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PriorityFeeLevels {
+    low: f64,
+    medium: f64,
+    high: f64,
+    very_high: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PriorityFeeEstimate {
+    priority_fee_levels: PriorityFeeLevels,
+}
+
+/// Fees recently paid around a set of accounts
+#[derive(Debug, Clone)]
+enum FeeSample {
+    Levels(FeeLevels), // Helius estimate
+    Recent(Vec<u64>),  // Per-slot fees from getRecentPrioritizationFees
+}
+
+/// Helius' fee levels mapped onto urgencies (veryHigh as max)
+#[derive(Debug, Clone, Copy)]
+struct FeeLevels {
+    low: u64,
+    medium: u64,
+    high: u64,
+    max: u64,
+}
+
+impl FeeSample {
+    fn fee(&self, urgency: FeeUrgency) -> Option<u64> {
+        match self {
+            FeeSample::Levels(levels) => Some(match urgency {
+                FeeUrgency::Low => levels.low,
+                FeeUrgency::Medium => levels.medium,
+                FeeUrgency::High => levels.high,
+                FeeUrgency::Max => levels.max,
+            }),
+            FeeSample::Recent(fees) => fee_percentile(&mut fees.clone(), urgency.percentile()),
+        }
+    }
+}
+
+pub struct PriorityFeeEstimator {
+    solana_client: Arc<SolanaClient>,
+    config: Arc<Config>,
+    samples: Mutex<HashMap<Vec<Pubkey>, (Instant, FeeSample)>>, // Latest sample per account set
+}
+
+impl PriorityFeeEstimator {
+    pub fn new(solana_client: Arc<SolanaClient>, config: Arc<Config>) -> Self {
+        Self {
+            solana_client,
+            config,
+            samples: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Priority fee (micro-lamports per CU) for a swap touching `accounts` at
+    /// `urgency`, or `fallback` when auto-estimation is off or no sample is available
+    pub async fn estimate(&self, urgency: FeeUrgency, accounts: &[Pubkey], fallback: u64) -> u64 {
+        if !self.config.priority_fee_auto {
+            return fallback;
+        }
+        let sample = match self.sample(accounts).await {
+            Ok(sample) => sample,
+            Err(e) => {
+                warn!("Priority fee estimation failed, using {} μlamports: {:?}", fallback, e);
+                return fallback;
+            }
+        };
+        match sample.fee(urgency) {
+            Some(fee) => {
+                let fee = fee.clamp(
+                    self.config.priority_fee_min_micro_lamports,
+                    self.config.priority_fee_max_micro_lamports.max(self.config.priority_fee_min_micro_lamports),
+                );
+                debug!("Priority fee at {:?} urgency: {} μlamports", urgency, fee);
+                fee
+            }
+            None => fallback,
+        }
+    }
+
+    /// Recent fees around `accounts`, reused for `priority_fee_cache_secs`
+    async fn sample(&self, accounts: &[Pubkey]) -> Result<FeeSample> {
+        let mut key = accounts.to_vec();
+        key.sort();
+        let max_age = Duration::from_secs(self.config.priority_fee_cache_secs);
+
+        let mut samples = self.samples.lock().await;
+        samples.retain(|_, (at, _)| at.elapsed() < max_age);
+        if let Some((_, sample)) = samples.get(&key) {
+            return Ok(sample.clone());
+        }
+
+        let sample = match self.helius_estimate(&key).await {
+            Ok(levels) => FeeSample::Levels(levels),
+            Err(e) => {
+                debug!("getPriorityFeeEstimate unavailable ({}), sampling recent prioritization fees", e);
+                FeeSample::Recent(self.recent_fees(&key).await?)
+            }
+        };
+        samples.insert(key, (Instant::now(), sample.clone()));
+        Ok(sample)
+    }
+
+    async fn helius_estimate(&self, accounts: &[Pubkey]) -> Result<FeeLevels> {
+        let account_keys: Vec<String> = accounts.iter().map(|a| a.to_string()).collect();
+        let estimate: PriorityFeeEstimate = self.solana_client.get_rpc()
+            .send(
+                RpcRequest::Custom { method: "getPriorityFeeEstimate" },
+                json!([{ "accountKeys": account_keys, "options": { "includeAllPriorityFeeLevels": true } }]),
+            )
+            .await
+            .context("getPriorityFeeEstimate failed")?;
+        let levels = estimate.priority_fee_levels;
+        Ok(FeeLevels {
+            low: levels.low.round() as u64,
+            medium: levels.medium.round() as u64,
+            high: levels.high.round() as u64,
+            max: levels.very_high.round() as u64,
+        })
+    }
+
+    async fn recent_fees(&self, accounts: &[Pubkey]) -> Result<Vec<u64>> {
+        Ok(self.solana_client.get_rpc()
+            .get_recent_prioritization_fees(accounts)
+            .await
+            .context("Failed to get recent prioritization fees")?
+            .into_iter()
+            .map(|f| f.prioritization_fee)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_nearest_rank_percentile() {
+        let mut fees = vec![500, 0, 100, 300, 200, 0, 400, 1000, 700, 600];
+        assert_eq!(fee_percentile(&mut fees, FeeUrgency::Low.percentile()), Some(100));
+        assert_eq!(fee_percentile(&mut fees, FeeUrgency::Medium.percentile()), Some(300));
+        assert_eq!(fee_percentile(&mut fees, FeeUrgency::High.percentile()), Some(600));
+        assert_eq!(fee_percentile(&mut fees, FeeUrgency::Max.percentile()), Some(1000));
+        assert_eq!(fee_percentile(&mut fees, 0.0), Some(0));
+        assert_eq!(fee_percentile(&mut [], 50.0), None);
+    }
+
+    #[test]
+    fn parses_helius_fee_levels() {
+        let estimate: PriorityFeeEstimate = serde_json::from_value(json!({
+            "priorityFeeLevels": {
+                "min": 0.0, "low": 1200.5, "medium": 25000.0,
+                "high": 150000.0, "veryHigh": 900000.0, "unsafeMax": 5000000.0
+            }
+        })).unwrap();
+        assert_eq!(estimate.priority_fee_levels.medium, 25000.0);
+        assert_eq!(estimate.priority_fee_levels.very_high, 900000.0);
+    }
+}
@@ -0,0 +1,116 @@
+//! Jito bundle submission
+//!
+//! Swaps sent through the RPC are visible to searchers before they land, so snipes
+//! on fresh pump.fun launches get frontrun. A Jito bundle goes straight to the block
+//! engine and executes atomically, in order: here it's the swap followed by a SOL
+//! tip to one of Jito's tip accounts. The tip is what buys inclusion, and because
+//! the bundle is all-or-nothing it's only paid if the swap lands.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rand::seq::SliceRandom;
+use reqwest::Client;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use tracing::debug;
+
+/// Jito's mainnet tip accounts; one is picked at random per bundle to spread
+/// write-lock contention
+const TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// A swap to send as a bundle, and what to tip for it
+#[derive(Debug, Clone)]
+pub struct JitoTip {
+    pub client: Arc<JitoClient>,
+    pub lamports: u64,
+}
+
+#[derive(Debug)]
+pub struct JitoClient {
+    client: Client,
+    block_engine_url: String,
+}
+
+impl JitoClient {
+    pub fn new(block_engine_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            block_engine_url: block_engine_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// A tip account to pay this bundle's tip to
+    pub fn random_tip_account() -> Pubkey {
+        let account = TIP_ACCOUNTS.choose(&mut rand::thread_rng()).unwrap_or(&TIP_ACCOUNTS[0]);
+        Pubkey::from_str(account).expect("Jito tip accounts are valid pubkeys")
+    }
+
+    /// Submit signed transactions as one bundle, returning the bundle id. Acceptance
+    /// only means the block engine took it; whether it landed shows up as the swap
+    /// signature confirming (or not).
+    pub async fn send_bundle(&self, transactions: &[VersionedTransaction]) -> Result<String> {
+        let request = bundle_request(transactions)?;
+        let url = format!("{}/api/v1/bundles", self.block_engine_url);
+        let response: Value = self.client.post(&url)
+            .json(&request)
+            .send()
+            .await
+            .context("Failed to reach Jito block engine")?
+            .json()
+            .await
+            .context("Failed to parse Jito block engine response")?;
+
+        if let Some(error) = response.get("error") {
+            return Err(anyhow!("Jito rejected bundle: {}", error));
+        }
+        let bundle_id = response.get("result").and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("Jito response has no bundle id: {}", response))?;
+        debug!("Jito accepted bundle {} ({} transactions)", bundle_id, transactions.len());
+        Ok(bundle_id.to_string())
+    }
+}
+
+/// The `sendBundle` JSON-RPC request for base64-encoded transactions
+fn bundle_request(transactions: &[VersionedTransaction]) -> Result<Value> {
+    let encoded = transactions.iter()
+        .map(|tx| bincode::serialize(tx).map(|bytes| STANDARD.encode(bytes)))
+        .collect::<Result<Vec<_>, _>>()
+        .context("Failed to serialize bundle transaction")?;
+    Ok(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "sendBundle",
+        "params": [encoded, { "encoding": "base64" }],
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundle_request_encodes_every_transaction() {
+        let request = bundle_request(&[VersionedTransaction::default(), VersionedTransaction::default()]).unwrap();
+        assert_eq!(request["method"], "sendBundle");
+        let encoded = request["params"][0].as_array().unwrap();
+        assert_eq!(encoded.len(), 2);
+        let bytes = STANDARD.decode(encoded[0].as_str().unwrap()).unwrap();
+        assert!(bincode::deserialize::<VersionedTransaction>(&bytes).is_ok());
+        assert_eq!(request["params"][1]["encoding"], "base64");
+
+        assert!(TIP_ACCOUNTS.contains(&JitoClient::random_tip_account().to_string().as_str()));
+    }
+}
@@ -1,3 +1,6 @@
 pub mod client;
+pub mod fees;
+pub mod jito;
 pub mod wallet;
+pub mod wallet_pool;
 // Potentially add transaction helpers, account parsing, etc. here later
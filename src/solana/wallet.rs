@@ -1,14 +1,21 @@
 use anyhow::{Context, Result};
 use solana_sdk::{
+    commitment_config::CommitmentLevel,
+    instruction::Instruction,
+    native_token::sol_to_lamports,
     pubkey::Pubkey,
+    system_instruction,
     signature::{Keypair, Signature}, // Removed Signer here, will add below
     signer::Signer, // Import the Signer trait explicitly
     transaction::{Transaction, VersionedTransaction}, // Added VersionedTransaction
 };
 use std::sync::Arc;
-use tracing::{error, info, warn}; // Removed unused debug
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, warn};
 
 use crate::solana::client::SolanaClient;
+use crate::solana::jito::{JitoClient, JitoTip};
 use crate::error::TraderbotError; // Assuming TraderbotError exists
 
 #[derive(Clone)] // Removed Debug
@@ -16,6 +23,9 @@ pub struct WalletManager {
     keypair: Arc<Keypair>,
     solana_client: Arc<SolanaClient>,
     demo_mode: bool,
+    // Last fetched SOL balance and when it was fetched (shared across clones)
+    balance_cache: Arc<RwLock<Option<(f64, Instant)>>>,
+    balance_cache_ttl: Duration,
 }
 
 impl WalletManager {
@@ -24,6 +34,7 @@ impl WalletManager {
         private_key_bs58: &str, // Expecting base58 private key string
         solana_client: Arc<SolanaClient>,
         demo_mode: bool,
+        balance_cache_ttl_ms: u64,
     ) -> Result<Arc<Self>> {
         // Decode base58 private key
         let bytes = bs58::decode(private_key_bs58)
@@ -49,6 +60,8 @@ impl WalletManager {
             keypair: Arc::new(keypair),
             solana_client,
             demo_mode,
+            balance_cache: Arc::new(RwLock::new(None)),
+            balance_cache_ttl: Duration::from_millis(balance_cache_ttl_ms),
         };
 
         Ok(Arc::new(wallet_manager))
@@ -58,11 +71,36 @@ impl WalletManager {
         self.keypair.pubkey()
     }
 
+    /// Returns the SOL balance, reusing a recently fetched value if it is
+    /// younger than the configured TTL.
     pub async fn get_sol_balance(&self) -> Result<f64> {
-        self.solana_client
+        self.get_sol_balance_with_refresh(false).await
+    }
+
+    /// Returns the SOL balance. With `force_refresh` the cache is bypassed
+    /// (use after a trade when the absolute latest balance matters).
+    pub async fn get_sol_balance_with_refresh(&self, force_refresh: bool) -> Result<f64> {
+        if !force_refresh && !self.balance_cache_ttl.is_zero() {
+            if let Some((balance, fetched_at)) = *self.balance_cache.read().await {
+                if fetched_at.elapsed() < self.balance_cache_ttl {
+                    debug!("Using cached SOL balance ({:.6} SOL, {}ms old)", balance, fetched_at.elapsed().as_millis());
+                    return Ok(balance);
+                }
+            }
+        }
+
+        let balance = self.solana_client
             .get_sol_balance(&self.get_public_key())
             .await
-            .context("Failed to get SOL balance from SolanaClient")
+            .context("Failed to get SOL balance from SolanaClient")?;
+
+        *self.balance_cache.write().await = Some((balance, Instant::now()));
+        Ok(balance)
+    }
+
+    /// Drops the cached SOL balance so the next read goes to the RPC.
+    pub async fn invalidate_balance_cache(&self) {
+        *self.balance_cache.write().await = None;
     }
 
     // Returns the UI amount (f64)
@@ -107,11 +145,32 @@ impl WalletManager {
         // Fetch recent blockhash just before signing (important!)
         let recent_blockhash = self.solana_client.get_rpc().get_latest_blockhash().await?; // Use Arc<RpcClient> directly
         transaction.message.set_recent_blockhash(recent_blockhash);
+        self.sign_versioned_transaction(&mut transaction)?;
 
-        // Sign the VersionedTransaction using the keypair
-        // The `sign` method takes a slice of signers.
-        // It modifies the transaction in place and returns a Result.
-        // Sign the transaction message bytes using the keypair
+        // Send the transaction (without confirmation here)
+        let signature = self
+            .solana_client
+            .send_versioned_transaction(&transaction)
+            .await
+            .context("Failed to send signed versioned transaction")?;
+
+        info!(
+            "Transaction sent. Signature: {}, Pubkey: {}",
+            signature,
+            self.get_public_key()
+        );
+
+        // Balance is about to change - don't serve the pre-trade value
+        self.invalidate_balance_cache().await;
+
+        // Confirmation should ideally happen elsewhere (e.g., in the calling function or a dedicated task)
+        // Example: self.solana_client.confirm_transaction(&signature, CommitmentLevel::Confirmed, 60).await?;
+
+        Ok(signature)
+    }
+
+    // Signs the message and puts the signature in the first (payer) slot
+    fn sign_versioned_transaction(&self, transaction: &mut VersionedTransaction) -> Result<()> {
         let message_bytes = transaction.message.serialize();
         let signature = self.keypair.try_sign_message(&message_bytes)
              .map_err(|e| {
@@ -119,33 +178,56 @@ impl WalletManager {
                  TraderbotError::WalletError(format!("Signing failed: {}", e))
              })?;
 
-        // Replace the first (payer) signature placeholder with the actual signature
         if transaction.signatures.is_empty() {
              // This shouldn't happen for transactions created by Jupiter API, but handle defensively
              error!("Transaction has no signature slots to place signature.");
              return Err(TraderbotError::WalletError("Transaction has no signature slots".to_string()).into());
         }
         transaction.signatures[0] = signature;
+        tracing::debug!("Signed versioned transaction with blockhash: {}", transaction.message.recent_blockhash());
+        Ok(())
+    }
 
-        // Removed warning: warn!("Transaction signing is currently commented out due to compilation issues!");
-        tracing::debug!("Signed versioned transaction with blockhash: {}", transaction.message.recent_blockhash()); // Re-enabled debug log
+    /// Signs and sends a swap transaction: as a Jito bundle with a tip when `jito` is
+    /// set, otherwise through the RPC. Demo mode always takes the RPC path (simulated).
+    pub async fn send_swap_transaction(
+        &self,
+        transaction: VersionedTransaction,
+        last_valid_block_height: u64,
+        jito: Option<&JitoTip>,
+    ) -> Result<Signature> {
+        match jito {
+            Some(tip) if !self.demo_mode => self.sign_and_send_jito_bundle(transaction, tip).await,
+            _ => self.sign_and_send_versioned_transaction(transaction, last_valid_block_height).await,
+        }
+    }
 
-        // Send the transaction (without confirmation here)
-        let signature = self
-            .solana_client
-            .send_versioned_transaction(&transaction)
-            .await
-            .context("Failed to send signed versioned transaction")?;
+    // Sends the transaction followed by a tip transfer as one Jito bundle, returning
+    // the transaction's signature
+    async fn sign_and_send_jito_bundle(&self, mut transaction: VersionedTransaction, tip: &JitoTip) -> Result<Signature> {
+        let recent_blockhash = self.solana_client.get_rpc().get_latest_blockhash().await?;
+        transaction.message.set_recent_blockhash(recent_blockhash);
+        self.sign_versioned_transaction(&mut transaction)?;
+        let signature = transaction.signatures[0];
 
-        info!(
-            "Transaction sent. Signature: {}, Pubkey: {}",
-            signature,
-            self.get_public_key()
+        let tip_account = JitoClient::random_tip_account();
+        let tip_transaction = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(&self.get_public_key(), &tip_account, tip.lamports)],
+            Some(&self.get_public_key()),
+            &[&*self.keypair],
+            recent_blockhash,
         );
 
-        // Confirmation should ideally happen elsewhere (e.g., in the calling function or a dedicated task)
-        // Example: self.solana_client.confirm_transaction(&signature, CommitmentLevel::Confirmed, 60).await?;
+        let bundle_id = tip.client
+            .send_bundle(&[transaction, VersionedTransaction::from(tip_transaction)])
+            .await
+            .context("Failed to send Jito bundle")?;
+        info!(
+            "Transaction sent as Jito bundle {} (tip {} lamports to {}). Signature: {}",
+            bundle_id, tip.lamports, tip_account, signature
+        );
 
+        self.invalidate_balance_cache().await;
         Ok(signature)
     }
 
@@ -162,6 +244,65 @@ impl WalletManager {
         Ok(transaction)
     }
 
+    /// Sends `amount_sol` SOL from this wallet to `destination` and waits for it to confirm
+    pub async fn transfer_sol(&self, destination: &Pubkey, amount_sol: f64, confirm_timeout_secs: u64) -> Result<Signature> {
+        if self.demo_mode {
+            info!("[DEMO MODE] Simulating transfer of {:.6} SOL to {}", amount_sol, destination);
+            return Ok(Signature::default());
+        }
+
+        let instruction = system_instruction::transfer(&self.get_public_key(), destination, sol_to_lamports(amount_sol));
+        let recent_blockhash = self.solana_client.get_rpc().get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.get_public_key()),
+            &[&*self.keypair],
+            recent_blockhash,
+        );
+
+        let signature = self
+            .solana_client
+            .send_versioned_transaction(&VersionedTransaction::from(transaction))
+            .await
+            .context("Failed to send SOL transfer")?;
+        self.invalidate_balance_cache().await;
+
+        self.solana_client
+            .confirm_transaction(&signature, CommitmentLevel::Confirmed, confirm_timeout_secs)
+            .await
+            .context(format!("SOL transfer {} failed confirmation", signature))?;
+        Ok(signature)
+    }
+
+    /// Send `instructions` in one transaction signed and paid for by this wallet
+    pub async fn send_instructions(&self, instructions: &[Instruction], confirm_timeout_secs: u64) -> Result<Signature> {
+        if self.demo_mode {
+            info!("[DEMO MODE] Simulating transaction with {} instruction(s)", instructions.len());
+            return Ok(Signature::default());
+        }
+
+        let recent_blockhash = self.solana_client.get_rpc().get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.get_public_key()),
+            &[&*self.keypair],
+            recent_blockhash,
+        );
+
+        let signature = self
+            .solana_client
+            .send_versioned_transaction(&VersionedTransaction::from(transaction))
+            .await
+            .context("Failed to send transaction")?;
+        self.invalidate_balance_cache().await;
+
+        self.solana_client
+            .confirm_transaction(&signature, CommitmentLevel::Confirmed, confirm_timeout_secs)
+            .await
+            .context(format!("Transaction {} failed confirmation", signature))?;
+        Ok(signature)
+    }
+
     // Provide access to the underlying keypair if needed (e.g., for specific signing needs)
     pub fn keypair(&self) -> Arc<Keypair> {
         self.keypair.clone()
@@ -0,0 +1,85 @@
+use anyhow::{Context, Result};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::solana::client::SolanaClient;
+use crate::solana::wallet::WalletManager;
+
+/// A set of trading wallets. Buys rotate through them round-robin; positions
+/// remember which wallet holds them so exits sell from the same one.
+/// The first wallet is the primary (`WALLET_PRIVATE_KEY`).
+pub struct WalletPool {
+    wallets: Vec<Arc<WalletManager>>,
+    next: AtomicUsize,
+}
+
+impl WalletPool {
+    /// Build the pool from the primary wallet plus any additional base58 private keys
+    pub fn new(
+        primary: Arc<WalletManager>,
+        additional_private_keys: &[String],
+        solana_client: Arc<SolanaClient>,
+        demo_mode: bool,
+        balance_cache_ttl_ms: u64,
+    ) -> Result<Arc<Self>> {
+        let mut wallets = vec![primary];
+        for (i, key) in additional_private_keys.iter().enumerate() {
+            let wallet = WalletManager::new(key, solana_client.clone(), demo_mode, balance_cache_ttl_ms)
+                .with_context(|| format!("Invalid additional wallet key #{}", i + 1))?;
+            if wallets.iter().any(|w| w.get_public_key() == wallet.get_public_key()) {
+                warn!("Skipping duplicate wallet {} in pool", wallet.get_public_key());
+                continue;
+            }
+            wallets.push(wallet);
+        }
+
+        if wallets.len() > 1 {
+            info!("Wallet pool initialized with {} wallets (round-robin)", wallets.len());
+        }
+
+        Ok(Arc::new(Self {
+            wallets,
+            next: AtomicUsize::new(0),
+        }))
+    }
+
+    /// The primary wallet
+    pub fn primary(&self) -> Arc<WalletManager> {
+        self.wallets[0].clone()
+    }
+
+    /// Pick the wallet for the next buy (round-robin)
+    pub fn next_wallet(&self) -> Arc<WalletManager> {
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.wallets.len();
+        self.wallets[i].clone()
+    }
+
+    /// Every wallet in the pool, primary first
+    pub fn all(&self) -> &[Arc<WalletManager>] {
+        &self.wallets
+    }
+
+    /// Find the wallet with the given public key
+    pub fn get(&self, address: &str) -> Option<Arc<WalletManager>> {
+        self.wallets.iter()
+            .find(|w| w.get_public_key().to_string() == address)
+            .cloned()
+    }
+
+    /// SOL balance of every wallet, as (address, balance)
+    pub async fn balances(&self) -> Result<Vec<(String, f64)>> {
+        let mut balances = Vec::with_capacity(self.wallets.len());
+        for wallet in &self.wallets {
+            let balance = wallet.get_sol_balance().await
+                .with_context(|| format!("Failed to get balance for wallet {}", wallet.get_public_key()))?;
+            balances.push((wallet.get_public_key().to_string(), balance));
+        }
+        Ok(balances)
+    }
+
+    /// Combined SOL balance across all wallets
+    pub async fn total_sol_balance(&self) -> Result<f64> {
+        Ok(self.balances().await?.iter().map(|(_, b)| b).sum())
+    }
+}
@@ -0,0 +1,262 @@
+//! The original JSON-file store: data/positions.json, data/strategies.json and the
+//! limit-order and copy-trade files beside them, each rewritten whole through a temp
+//! file and rename. Archived positions go to an append-only JSON-lines file.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use super::Store;
+use crate::models::copy_trade::{CopyPosition, CopyTradeState, CopyTrader, SourceWallet, TradeSignal};
+use crate::trading::limit_orders::LimitOrder;
+use crate::trading::position::Position;
+use crate::trading::strategy::Strategy;
+
+const POSITIONS_FILE: &str = "data/positions.json";
+const STRATEGIES_FILE: &str = "data/strategies.json";
+// Archive, limit-order and copy-trade files, in the positions file's directory
+const POSITIONS_ARCHIVE_FILE: &str = "positions_archive.jsonl";
+const LIMIT_ORDERS_FILE: &str = "limit_orders.json";
+const COPY_TRADERS_FILE: &str = "copy_traders.json";
+const SIGNALS_FILE: &str = "signals.json";
+const COPY_POSITIONS_FILE: &str = "copy_positions.json";
+const SOURCE_WALLETS_FILE: &str = "copy_sources.json";
+
+pub struct JsonStore {
+    positions_path: PathBuf,
+    strategies_path: PathBuf,
+    data_dir: PathBuf,
+    write_lock: Mutex<()>, // Two saves sharing a temp file would interleave
+}
+
+impl JsonStore {
+    pub fn new() -> Self {
+        Self::with_paths(PathBuf::from(POSITIONS_FILE), PathBuf::from(STRATEGIES_FILE))
+    }
+
+    pub fn with_paths(positions_path: PathBuf, strategies_path: PathBuf) -> Self {
+        let data_dir = positions_path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Self {
+            positions_path,
+            strategies_path,
+            data_dir,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// File contents, or None if it doesn't exist or is empty
+    async fn read(path: &Path) -> Result<Option<String>> {
+        match fs::read_to_string(path).await {
+            Ok(d) if d.trim().is_empty() => Ok(None),
+            Ok(d) => Ok(Some(d)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).context(format!("Failed to read {:?}", path)),
+        }
+    }
+
+    /// Parsed file contents, or the default if it doesn't exist or is empty
+    async fn read_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+        match Self::read(path).await? {
+            Some(data) => serde_json::from_str(&data).context(format!("Failed to parse {:?}", path)),
+            None => Ok(T::default()),
+        }
+    }
+
+    async fn write<T: Serialize + ?Sized>(&self, path: &Path, value: &T) -> Result<()> {
+        let data = serde_json::to_string_pretty(value).context("Failed to serialize")?;
+        let _guard = self.write_lock.lock().await;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await.context("Failed to create data directory")?;
+        }
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, data).await
+            .context(format!("Failed to write temporary file: {:?}", temp_path))?;
+        fs::rename(&temp_path, path).await
+            .context(format!("Failed to rename temporary file to {:?}", path))?;
+        Ok(())
+    }
+}
+
+impl Default for JsonStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Store for JsonStore {
+    async fn load_positions(&self) -> Result<Vec<Position>> {
+        let Some(data) = Self::read(&self.positions_path).await? else {
+            info!("Positions file {:?} not found or empty, starting with empty state.", self.positions_path);
+            return Ok(Vec::new());
+        };
+        // A corrupt file is an error, not an empty book: its tokens may still be in the wallet
+        serde_json::from_str(&data).context("Failed to parse positions file")
+    }
+
+    async fn save_positions(&self, positions: &[&Position]) -> Result<()> {
+        self.write(&self.positions_path, positions).await
+            .context("Failed to save positions")
+    }
+
+    async fn load_archived_positions(&self) -> Result<Vec<Position>> {
+        let path = self.data_dir.join(POSITIONS_ARCHIVE_FILE);
+        let Some(data) = Self::read(&path).await? else {
+            return Ok(Vec::new());
+        };
+        // Malformed lines are skipped; a position appended twice is read once
+        let mut positions = Vec::new();
+        let mut seen = HashSet::new();
+        for line in data.lines().filter(|l| !l.trim().is_empty()) {
+            match serde_json::from_str::<Position>(line) {
+                Ok(position) if seen.insert(position.id.clone()) => positions.push(position),
+                Ok(_) => {}
+                Err(e) => warn!("Skipping malformed positions archive line: {}", e),
+            }
+        }
+        Ok(positions)
+    }
+
+    async fn archive_positions(&self, positions: &[Position]) -> Result<()> {
+        if positions.is_empty() {
+            return Ok(());
+        }
+        let mut lines = String::new();
+        for position in positions {
+            lines.push_str(&serde_json::to_string(position).context("Failed to serialize archived position")?);
+            lines.push('\n');
+        }
+
+        let path = self.data_dir.join(POSITIONS_ARCHIVE_FILE);
+        let _guard = self.write_lock.lock().await;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await.context("Failed to create data directory")?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .context(format!("Failed to open positions archive: {:?}", path))?;
+        file.write_all(lines.as_bytes()).await
+            .context(format!("Failed to append to positions archive: {:?}", path))
+    }
+
+    async fn load_strategies(&self) -> Result<HashMap<String, Strategy>> {
+        let Some(data) = Self::read(&self.strategies_path).await? else {
+            return Ok(HashMap::new());
+        };
+        serde_json::from_str(&data).context("Failed to parse strategies file")
+    }
+
+    async fn save_strategies(&self, strategies: &HashMap<String, Strategy>) -> Result<()> {
+        self.write(&self.strategies_path, strategies).await
+            .context("Failed to save strategies")
+    }
+
+    async fn load_copy_trade_state(&self) -> Result<CopyTradeState> {
+        let dir = &self.data_dir;
+        Ok(CopyTradeState {
+            traders: Self::read_json::<Vec<CopyTrader>>(&dir.join(COPY_TRADERS_FILE)).await?,
+            signals: Self::read_json::<Vec<TradeSignal>>(&dir.join(SIGNALS_FILE)).await?,
+            copy_positions: Self::read_json::<HashMap<String, Vec<CopyPosition>>>(&dir.join(COPY_POSITIONS_FILE)).await?,
+            source_wallets: Self::read_json::<Vec<SourceWallet>>(&dir.join(SOURCE_WALLETS_FILE)).await?,
+        })
+    }
+
+    async fn save_copy_trade_state(&self, state: &CopyTradeState) -> Result<()> {
+        let dir = &self.data_dir;
+        self.write(&dir.join(COPY_TRADERS_FILE), &state.traders).await.context("Failed to save copy traders")?;
+        self.write(&dir.join(SIGNALS_FILE), &state.signals).await.context("Failed to save trade signals")?;
+        self.write(&dir.join(COPY_POSITIONS_FILE), &state.copy_positions).await.context("Failed to save copy positions")?;
+        self.write(&dir.join(SOURCE_WALLETS_FILE), &state.source_wallets).await.context("Failed to save source wallets")
+    }
+
+    async fn load_limit_orders(&self) -> Result<Vec<LimitOrder>> {
+        let orders = Self::read_json::<HashMap<String, LimitOrder>>(&self.data_dir.join(LIMIT_ORDERS_FILE)).await?;
+        Ok(orders.into_values().collect())
+    }
+
+    async fn save_limit_orders(&self, orders: &[&LimitOrder]) -> Result<()> {
+        // Keyed by id, as the file has always been
+        let orders: HashMap<&str, &LimitOrder> = orders.iter().map(|o| (o.id.as_str(), *o)).collect();
+        self.write(&self.data_dir.join(LIMIT_ORDERS_FILE), &orders).await
+            .context("Failed to save limit orders")
+    }
+
+    fn describe(&self) -> String {
+        format!("{:?} and {:?}", self.positions_path, self.strategies_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn strategies_round_trip_and_concurrent_saves_stay_valid() {
+        let dir = std::env::temp_dir().join(format!("json_store_{}", Uuid::new_v4()));
+        let store = Arc::new(JsonStore::with_paths(dir.join("positions.json"), dir.join("strategies.json")));
+        assert!(store.load_positions().await.unwrap().is_empty());
+        assert!(store.load_strategies().await.unwrap().is_empty());
+
+        let strategy = Strategy::default("Round Trip");
+        let strategies = HashMap::from([(strategy.id.clone(), strategy.clone())]);
+        let saves: Vec<_> = (0..8).map(|_| {
+            let (store, strategies) = (store.clone(), strategies.clone());
+            tokio::spawn(async move { store.save_strategies(&strategies).await })
+        }).collect();
+        for save in saves {
+            save.await.unwrap().unwrap();
+        }
+
+        let loaded = store.load_strategies().await.unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[&strategy.id].name, "Round Trip");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn corrupt_positions_file_is_an_error() {
+        let dir = std::env::temp_dir().join(format!("json_store_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("positions.json"), "[{\"id\": ").unwrap();
+        let store = JsonStore::with_paths(dir.join("positions.json"), dir.join("strategies.json"));
+        assert!(store.load_positions().await.is_err());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn copy_trade_state_is_stored_beside_positions() {
+        let dir = std::env::temp_dir().join(format!("json_store_{}", Uuid::new_v4()));
+        let store = JsonStore::with_paths(dir.join("positions.json"), dir.join("strategies.json"));
+        assert!(store.load_copy_trade_state().await.unwrap().is_empty());
+
+        let state = CopyTradeState {
+            traders: vec![CopyTrader::new("copier", 0.1)],
+            source_wallets: vec![SourceWallet::new("source", None)],
+            ..CopyTradeState::default()
+        };
+        store.save_copy_trade_state(&state).await.unwrap();
+        assert!(dir.join(COPY_TRADERS_FILE).exists());
+
+        let loaded = store.load_copy_trade_state().await.unwrap();
+        assert_eq!(loaded.traders[0].wallet_address, "copier");
+        assert_eq!(loaded.source_wallets[0].address, "source");
+        assert!(loaded.signals.is_empty() && loaded.copy_positions.is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
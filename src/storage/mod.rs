@@ -0,0 +1,126 @@
+//! Persistence for positions, strategies, limit orders and copy-trade state
+//!
+//! PositionManager, AutoTrader, LimitOrderBook and CopyTradeManager load and save their whole state
+//! through a [`Store`], so the backend is a config choice: the original JSON files,
+//! or a SQLite database with one row per record and indexes on the columns lookups
+//! filter by. Switching to SQLite imports the JSON files once, so existing
+//! positions and copiers aren't forgotten; the import is recorded in the database
+//! and never repeated, even if a table is emptied later.
+
+mod json;
+mod sqlite;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::config::{Config, StorageBackend};
+use crate::models::copy_trade::CopyTradeState;
+use crate::trading::limit_orders::LimitOrder;
+use crate::trading::position::Position;
+use crate::trading::strategy::Strategy;
+
+pub use json::JsonStore;
+pub use sqlite::SqliteStore;
+
+#[async_trait]
+pub trait Store: Send + Sync {
+    /// Every stored position. Missing storage is an empty list; a read error is
+    /// returned so the caller can retry rather than start with empty state.
+    async fn load_positions(&self) -> Result<Vec<Position>>;
+
+    /// Replace the stored positions with `positions`
+    async fn save_positions(&self, positions: &[&Position]) -> Result<()>;
+
+    /// Closed positions moved out of the live set, oldest archived first, each once
+    async fn load_archived_positions(&self) -> Result<Vec<Position>>;
+
+    /// Add positions to the archive. Archiving one that is already there is a no-op,
+    /// so a retry after a failed positions save doesn't duplicate it.
+    async fn archive_positions(&self, positions: &[Position]) -> Result<()>;
+
+    /// Every stored strategy, by id
+    async fn load_strategies(&self) -> Result<HashMap<String, Strategy>>;
+
+    /// Replace the stored strategies with `strategies`
+    async fn save_strategies(&self, strategies: &HashMap<String, Strategy>) -> Result<()>;
+
+    /// Copy traders, signals, copy positions and source wallets
+    async fn load_copy_trade_state(&self) -> Result<CopyTradeState>;
+
+    /// Replace the stored copy-trade state with `state`
+    async fn save_copy_trade_state(&self, state: &CopyTradeState) -> Result<()>;
+
+    /// Every stored limit order, pending and finished
+    async fn load_limit_orders(&self) -> Result<Vec<LimitOrder>>;
+
+    /// Replace the stored limit orders with `orders`
+    async fn save_limit_orders(&self, orders: &[&LimitOrder]) -> Result<()>;
+
+    /// Human-readable location, for logs
+    fn describe(&self) -> String;
+}
+
+/// Open the store `config.storage_backend` selects
+pub async fn open(config: &Config) -> Result<Arc<dyn Store>> {
+    match config.storage_backend {
+        StorageBackend::Json => Ok(Arc::new(JsonStore::new())),
+        StorageBackend::Sqlite => {
+            let store = SqliteStore::open(&config.database_url).await?;
+            import_json_once(&store, &JsonStore::new()).await?;
+            Ok(Arc::new(store))
+        }
+    }
+}
+
+/// `meta` key recording that the JSON files were imported
+const JSON_IMPORTED_KEY: &str = "json_imported_at";
+
+/// Copy the JSON files into tables that are still empty, once per database. Without
+/// the marker, deleting every strategy (or closing out every position) would pull
+/// the stale JSON files back in on the next restart.
+async fn import_json_once(store: &SqliteStore, json: &JsonStore) -> Result<()> {
+    if store.meta(JSON_IMPORTED_KEY).await?.is_some() {
+        return Ok(());
+    }
+    if store.load_positions().await?.is_empty() {
+        let positions = json.load_positions().await?;
+        if !positions.is_empty() {
+            store.save_positions(&positions.iter().collect::<Vec<_>>()).await?;
+            info!("Imported {} positions from {} into {}", positions.len(), json.describe(), store.describe());
+        }
+    }
+    if store.load_archived_positions().await?.is_empty() {
+        let archived = json.load_archived_positions().await?;
+        if !archived.is_empty() {
+            store.archive_positions(&archived).await?;
+            info!("Imported {} archived positions from {} into {}", archived.len(), json.describe(), store.describe());
+        }
+    }
+    if store.load_strategies().await?.is_empty() {
+        let strategies = json.load_strategies().await?;
+        if !strategies.is_empty() {
+            store.save_strategies(&strategies).await?;
+            info!("Imported {} strategies from {} into {}", strategies.len(), json.describe(), store.describe());
+        }
+    }
+    if store.load_copy_trade_state().await?.is_empty() {
+        let copy_trade = json.load_copy_trade_state().await?;
+        if !copy_trade.is_empty() {
+            store.save_copy_trade_state(&copy_trade).await?;
+            info!("Imported {} copy traders from {} into {}", copy_trade.traders.len(), json.describe(), store.describe());
+        }
+    }
+    if store.load_limit_orders().await?.is_empty() {
+        let orders = json.load_limit_orders().await?;
+        if !orders.is_empty() {
+            store.save_limit_orders(&orders.iter().collect::<Vec<_>>()).await?;
+            info!("Imported {} limit orders from {} into {}", orders.len(), json.describe(), store.describe());
+        }
+    }
+    store.set_meta(JSON_IMPORTED_KEY, &chrono::Utc::now().to_rfc3339()).await?;
+    Ok(())
+}
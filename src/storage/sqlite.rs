@@ -0,0 +1,368 @@
+//! SQLite store. Each position (live or archived), strategy, limit order and
+//! copy-trade record is a row holding its serialized JSON plus indexed copies of
+//! the fields history is queried by (token, strategy, status, times), so trade
+//! history can be queried with plain SQL. Saves replace the table contents inside
+//! one transaction, so a crash mid-save leaves the previous state intact and
+//! concurrent saves are serialized by SQLite. Positions (saved on every monitor
+//! tick) and strategies are upserted, so only changed and removed rows are written.
+//! Archived positions are only ever inserted.
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+use sqlx::{Sqlite, Transaction};
+use tracing::{info, warn};
+
+use super::Store;
+use crate::models::copy_trade::{CopyPosition, CopyTradeState};
+use crate::trading::limit_orders::LimitOrder;
+use crate::trading::position::Position;
+use crate::trading::strategy::Strategy;
+
+pub struct SqliteStore {
+    pool: SqlitePool,
+    database_url: String,
+}
+
+impl SqliteStore {
+    /// Open (creating if needed) the database and run pending migrations
+    pub async fn open(database_url: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(database_url)
+            .with_context(|| format!("Invalid DATABASE_URL: {}", database_url))?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal);
+        if let Some(dir) = options.get_filename().parent().filter(|d| !d.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await.context("Failed to create database directory")?;
+        }
+
+        // Every connection to an in-memory database gets its own, empty one
+        let in_memory = database_url.contains(":memory:");
+        let mut pool_options = SqlitePoolOptions::new().max_connections(if in_memory { 1 } else { 4 });
+        if in_memory {
+            pool_options = pool_options.idle_timeout(None).max_lifetime(None);
+        }
+        let pool = pool_options
+            .connect_with(options)
+            .await
+            .with_context(|| format!("Failed to open database {}", database_url))?;
+        sqlx::migrate!("./migrations").run(&pool).await
+            .context("Failed to run database migrations")?;
+
+        info!("Using SQLite store at {}", database_url);
+        Ok(Self { pool, database_url: database_url.to_string() })
+    }
+
+    /// (id, data) rows of a query
+    async fn fetch_rows(&self, sql: &str) -> Result<Vec<(String, String)>> {
+        Ok(sqlx::query_as(sql).fetch_all(&self.pool).await?)
+    }
+
+    /// A value from the `meta` table
+    pub async fn meta(&self, key: &str) -> Result<Option<String>> {
+        let value: Option<(String,)> = sqlx::query_as("SELECT value FROM meta WHERE key = ?")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await
+            .with_context(|| format!("Failed to read meta {}", key))?;
+        Ok(value.map(|(v,)| v))
+    }
+
+    pub async fn set_meta(&self, key: &str, value: &str) -> Result<()> {
+        sqlx::query("INSERT INTO meta (key, value) VALUES (?, ?) ON CONFLICT(key) DO UPDATE SET value = excluded.value")
+            .bind(key)
+            .bind(value)
+            .execute(&self.pool)
+            .await
+            .with_context(|| format!("Failed to write meta {}", key))?;
+        Ok(())
+    }
+}
+
+/// Delete the rows of `table` whose id isn't in `keep`
+async fn delete_missing(tx: &mut Transaction<'_, Sqlite>, table: &str, keep: &HashSet<&str>) -> Result<()> {
+    let ids: Vec<(String,)> = sqlx::query_as(&format!("SELECT id FROM {}", table))
+        .fetch_all(&mut **tx)
+        .await
+        .with_context(|| format!("Failed to list {}", table))?;
+    for (id,) in ids.into_iter().filter(|(id,)| !keep.contains(id.as_str())) {
+        sqlx::query(&format!("DELETE FROM {} WHERE id = ?", table))
+            .bind(&id)
+            .execute(&mut **tx)
+            .await
+            .with_context(|| format!("Failed to delete {} row {}", table, id))?;
+    }
+    Ok(())
+}
+
+/// Parse each row's JSON, skipping (and logging) rows that no longer deserialize
+fn parse_rows<T: serde::de::DeserializeOwned>(rows: Vec<(String, String)>, kind: &str) -> Vec<(String, T)> {
+    rows.into_iter()
+        .filter_map(|(id, data)| match serde_json::from_str(&data) {
+            Ok(value) => Some((id, value)),
+            Err(e) => {
+                warn!("Skipping malformed {} row {}: {}", kind, id, e);
+                None
+            }
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Store for SqliteStore {
+    async fn load_positions(&self) -> Result<Vec<Position>> {
+        let rows: Vec<(String, String)> = sqlx::query_as("SELECT id, data FROM positions ORDER BY entry_time")
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to read positions from database")?;
+        Ok(parse_rows(rows, "position").into_iter().map(|(_, p)| p).collect())
+    }
+
+    async fn save_positions(&self, positions: &[&Position]) -> Result<()> {
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+        let keep: HashSet<&str> = positions.iter().map(|p| p.id.as_str()).collect();
+        delete_missing(&mut tx, "positions", &keep).await?;
+        for position in positions {
+            let data = serde_json::to_string(position).context("Failed to serialize position")?;
+            sqlx::query(
+                "INSERT INTO positions (id, token_address, strategy_id, status, entry_time, exit_time, data) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?) \
+                 ON CONFLICT(id) DO UPDATE SET token_address = excluded.token_address, \
+                 strategy_id = excluded.strategy_id, status = excluded.status, entry_time = excluded.entry_time, \
+                 exit_time = excluded.exit_time, data = excluded.data \
+                 WHERE positions.data != excluded.data",
+            )
+            .bind(&position.id)
+            .bind(&position.token_address)
+            .bind(&position.strategy_id)
+            .bind(position.status.to_string())
+            .bind(position.entry_time.to_rfc3339())
+            .bind(position.exit_time.map(|t| t.to_rfc3339()))
+            .bind(data)
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Failed to save position {}", position.id))?;
+        }
+        tx.commit().await.context("Failed to commit positions")?;
+        Ok(())
+    }
+
+    async fn load_archived_positions(&self) -> Result<Vec<Position>> {
+        let rows = self.fetch_rows("SELECT id, data FROM archived_positions ORDER BY rowid").await
+            .context("Failed to read archived positions from database")?;
+        Ok(parse_rows(rows, "archived position").into_iter().map(|(_, p)| p).collect())
+    }
+
+    async fn archive_positions(&self, positions: &[Position]) -> Result<()> {
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+        for position in positions {
+            let data = serde_json::to_string(position).context("Failed to serialize archived position")?;
+            sqlx::query(
+                "INSERT INTO archived_positions (id, token_address, strategy_id, status, entry_time, exit_time, data) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?) ON CONFLICT(id) DO NOTHING",
+            )
+            .bind(&position.id)
+            .bind(&position.token_address)
+            .bind(&position.strategy_id)
+            .bind(position.status.to_string())
+            .bind(position.entry_time.to_rfc3339())
+            .bind(position.exit_time.map(|t| t.to_rfc3339()))
+            .bind(data)
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Failed to archive position {}", position.id))?;
+        }
+        tx.commit().await.context("Failed to commit archived positions")?;
+        Ok(())
+    }
+
+    async fn load_strategies(&self) -> Result<HashMap<String, Strategy>> {
+        let rows: Vec<(String, String)> = sqlx::query_as("SELECT id, data FROM strategies")
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to read strategies from database")?;
+        Ok(parse_rows(rows, "strategy").into_iter().collect())
+    }
+
+    async fn save_strategies(&self, strategies: &HashMap<String, Strategy>) -> Result<()> {
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+        let keep: HashSet<&str> = strategies.keys().map(String::as_str).collect();
+        delete_missing(&mut tx, "strategies", &keep).await?;
+        for (id, strategy) in strategies {
+            let data = serde_json::to_string(strategy).context("Failed to serialize strategy")?;
+            sqlx::query(
+                "INSERT INTO strategies (id, name, enabled, data) VALUES (?, ?, ?, ?) \
+                 ON CONFLICT(id) DO UPDATE SET name = excluded.name, enabled = excluded.enabled, data = excluded.data \
+                 WHERE strategies.data != excluded.data",
+            )
+                .bind(id)
+                .bind(&strategy.name)
+                .bind(strategy.enabled)
+                .bind(data)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to save strategy {}", id))?;
+        }
+        tx.commit().await.context("Failed to commit strategies")?;
+        Ok(())
+    }
+
+    async fn load_copy_trade_state(&self) -> Result<CopyTradeState> {
+        let traders = self.fetch_rows("SELECT wallet_address, data FROM copy_traders").await
+            .context("Failed to read copy traders from database")?;
+        let signals = self.fetch_rows("SELECT id, data FROM trade_signals ORDER BY timestamp").await
+            .context("Failed to read trade signals from database")?;
+        let positions = self.fetch_rows("SELECT id, data FROM copy_positions ORDER BY rowid").await
+            .context("Failed to read copy positions from database")?;
+        let sources = self.fetch_rows("SELECT address, data FROM copy_sources").await
+            .context("Failed to read source wallets from database")?;
+
+        let mut copy_positions: HashMap<String, Vec<CopyPosition>> = HashMap::new();
+        for (_, position) in parse_rows::<CopyPosition>(positions, "copy position") {
+            copy_positions.entry(position.copier_wallet.clone()).or_default().push(position);
+        }
+        Ok(CopyTradeState {
+            traders: parse_rows(traders, "copy trader").into_iter().map(|(_, t)| t).collect(),
+            signals: parse_rows(signals, "trade signal").into_iter().map(|(_, s)| s).collect(),
+            copy_positions,
+            source_wallets: parse_rows(sources, "source wallet").into_iter().map(|(_, s)| s).collect(),
+        })
+    }
+
+    async fn save_copy_trade_state(&self, state: &CopyTradeState) -> Result<()> {
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+        for table in ["copy_traders", "trade_signals", "copy_positions", "copy_sources"] {
+            sqlx::query(&format!("DELETE FROM {}", table)).execute(&mut *tx).await
+                .with_context(|| format!("Failed to clear {}", table))?;
+        }
+        for trader in &state.traders {
+            let data = serde_json::to_string(trader).context("Failed to serialize copy trader")?;
+            sqlx::query("INSERT INTO copy_traders (wallet_address, data) VALUES (?, ?)")
+                .bind(&trader.wallet_address)
+                .bind(data)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to save copy trader {}", trader.wallet_address))?;
+        }
+        for signal in &state.signals {
+            let data = serde_json::to_string(signal).context("Failed to serialize trade signal")?;
+            sqlx::query("INSERT INTO trade_signals (id, timestamp, data) VALUES (?, ?, ?)")
+                .bind(&signal.id)
+                .bind(signal.timestamp.to_rfc3339())
+                .bind(data)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to save trade signal {}", signal.id))?;
+        }
+        for position in state.copy_positions.values().flatten() {
+            let data = serde_json::to_string(position).context("Failed to serialize copy position")?;
+            sqlx::query(
+                "INSERT INTO copy_positions (id, copier_wallet, bot_position_id, status, data) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(&position.id)
+            .bind(&position.copier_wallet)
+            .bind(&position.bot_position_id)
+            .bind(position.status.to_string())
+            .bind(data)
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Failed to save copy position {}", position.id))?;
+        }
+        for source in &state.source_wallets {
+            let data = serde_json::to_string(source).context("Failed to serialize source wallet")?;
+            sqlx::query("INSERT INTO copy_sources (address, data) VALUES (?, ?)")
+                .bind(&source.address)
+                .bind(data)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to save source wallet {}", source.address))?;
+        }
+        tx.commit().await.context("Failed to commit copy-trade state")?;
+        Ok(())
+    }
+
+    async fn load_limit_orders(&self) -> Result<Vec<LimitOrder>> {
+        let rows = self.fetch_rows("SELECT id, data FROM limit_orders ORDER BY created_at").await
+            .context("Failed to read limit orders from database")?;
+        Ok(parse_rows(rows, "limit order").into_iter().map(|(_, o)| o).collect())
+    }
+
+    async fn save_limit_orders(&self, orders: &[&LimitOrder]) -> Result<()> {
+        let mut tx = self.pool.begin().await.context("Failed to start transaction")?;
+        let keep: HashSet<&str> = orders.iter().map(|o| o.id.as_str()).collect();
+        delete_missing(&mut tx, "limit_orders", &keep).await?;
+        for order in orders {
+            let data = serde_json::to_string(order).context("Failed to serialize limit order")?;
+            sqlx::query(
+                "INSERT INTO limit_orders (id, token_address, status, created_at, data) VALUES (?, ?, ?, ?, ?) \
+                 ON CONFLICT(id) DO UPDATE SET token_address = excluded.token_address, status = excluded.status, \
+                 created_at = excluded.created_at, data = excluded.data \
+                 WHERE limit_orders.data != excluded.data",
+            )
+            .bind(&order.id)
+            .bind(&order.token_address)
+            .bind(format!("{:?}", order.status))
+            .bind(order.created_at.to_rfc3339())
+            .bind(data)
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Failed to save limit order {}", order.id))?;
+        }
+        tx.commit().await.context("Failed to commit limit orders")?;
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        self.database_url.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::position::PositionStatus;
+
+    fn position(id: &str) -> Position {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "token_address": "mint", "token_name": "Token", "token_symbol": "TKN",
+            "token_decimals": 6, "strategy_id": "s", "entry_time": chrono::Utc::now(), "exit_time": null,
+            "entry_value_sol": 0.1, "entry_token_amount": 1000.0,
+            "expected_token_amount": 1000.0, "fill_percent": 1.0, "exit_value_sol": null,
+            "entry_price_sol": 0.0001, "current_price_sol": 0.0001, "exit_price_sol": null, "pnl_sol": null,
+            "pnl_percent": null, "stop_loss_price": null, "take_profit_price": null, "trailing_stop_price": null,
+            "trailing_stop_percent": null, "highest_price": 0.0001, "status": "Active", "entry_tx_signature": "tx",
+            "exit_tx_signature": null, "is_demo": false, "max_hold_time_minutes": null,
+            "stop_loss_percent": null, "take_profit_percent": null
+        })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn positions_and_strategies_round_trip_through_upserts() {
+        let store = SqliteStore::open("sqlite::memory:").await.unwrap();
+        assert!(store.load_positions().await.unwrap().is_empty());
+
+        let (a, mut b) = (position("a"), position("b"));
+        store.save_positions(&[&a, &b]).await.unwrap();
+        b.status = PositionStatus::Closed;
+        store.save_positions(&[&b]).await.unwrap();
+        let loaded = store.load_positions().await.unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].id, "b");
+        assert_eq!(loaded[0].status, PositionStatus::Closed);
+
+        let strategy = Strategy::default("Upsert");
+        let mut strategies = HashMap::from([(strategy.id.clone(), strategy.clone())]);
+        store.save_strategies(&strategies).await.unwrap();
+        strategies.get_mut(&strategy.id).unwrap().enabled = !strategy.enabled;
+        store.save_strategies(&strategies).await.unwrap();
+        assert_eq!(store.load_strategies().await.unwrap()[&strategy.id].enabled, !strategy.enabled);
+        store.save_strategies(&HashMap::new()).await.unwrap();
+        assert!(store.load_strategies().await.unwrap().is_empty());
+
+        assert_eq!(store.meta("json_imported_at").await.unwrap(), None);
+        store.set_meta("json_imported_at", "now").await.unwrap();
+        assert_eq!(store.meta("json_imported_at").await.unwrap().as_deref(), Some("now"));
+    }
+}
@@ -0,0 +1,447 @@
+//! FIFO lot accounting over every buy and sell the bot has made
+//!
+//! Each position records its confirmed fills: the entry buy, scale-in buys, partial
+//! sells and the final sell. Per token, buys become lots and sells consume the oldest
+//! lots first, giving per-lot cost basis, proceeds and realized gain in SOL. Demo
+//! positions are excluded. Positions recorded before fills were tracked are rebuilt
+//! from their entry and exit totals and counted in `reconstructed_positions`.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::trading::position::{Position, PositionStatus};
+
+/// Token amounts below this are treated as fully consumed (float dust)
+const AMOUNT_EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FillSide {
+    Buy,
+    Sell,
+}
+
+/// One confirmed buy or sell within a position
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TradeFill {
+    pub timestamp: DateTime<Utc>,
+    pub side: FillSide,
+    pub token_amount: f64,
+    pub sol_amount: f64,
+    pub signature: String,
+}
+
+/// A sold portion of one lot
+#[derive(Debug, Clone, Serialize)]
+pub struct RealizedLot {
+    pub token_address: String,
+    pub token_symbol: String,
+    pub position_id: String,        // Position that bought the lot
+    pub acquired_at: DateTime<Utc>,
+    pub disposed_at: DateTime<Utc>,
+    pub token_amount: f64,
+    pub cost_basis_sol: f64,
+    pub proceeds_sol: f64,
+    pub gain_sol: f64,
+    pub buy_signature: String,
+    pub sell_signature: String,
+}
+
+/// Tokens still held from a buy
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenLot {
+    pub token_address: String,
+    pub token_symbol: String,
+    pub position_id: String,
+    pub acquired_at: DateTime<Utc>,
+    pub token_amount: f64,
+    pub cost_basis_sol: f64,
+    pub buy_signature: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FifoReport {
+    pub generated_at: DateTime<Utc>,
+    pub token: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub realized: Vec<RealizedLot>,  // Disposals within [from, to)
+    pub open_lots: Vec<OpenLot>,     // Holdings as of now
+    pub total_proceeds_sol: f64,
+    pub total_cost_basis_sol: f64,
+    pub total_realized_gain_sol: f64,
+    pub reconstructed_positions: usize, // Positions without recorded fills, approximated from totals
+}
+
+/// A position's fills, rebuilt from its entry/exit totals if none were recorded
+pub fn position_fills(position: &Position) -> (Vec<TradeFill>, bool) {
+    if !position.fills.is_empty() {
+        return (position.fills.clone(), false);
+    }
+    let mut fills = vec![TradeFill {
+        timestamp: position.entry_time,
+        side: FillSide::Buy,
+        token_amount: position.entry_token_amount,
+        sol_amount: position.entry_value_sol,
+        signature: position.entry_tx_signature.clone(),
+    }];
+    if let (Some(exit_time), Some(exit_value)) = (position.exit_time, position.exit_value_sol) {
+        if !matches!(position.status, PositionStatus::Failed | PositionStatus::Unsellable) {
+            fills.push(TradeFill {
+                timestamp: exit_time,
+                side: FillSide::Sell,
+                token_amount: position.entry_token_amount,
+                sol_amount: exit_value,
+                signature: position.exit_tx_signature.clone().unwrap_or_default(),
+            });
+        }
+    }
+    (fills, true)
+}
+
+struct LotEntry {
+    position_id: String,
+    acquired_at: DateTime<Utc>,
+    token_amount: f64,
+    cost_per_token: f64,
+    buy_signature: String,
+}
+
+/// Match sells to the oldest buys per token. Disposals outside `from`..`to` are left out
+/// of `realized`, but every sell still consumes lots so later periods stay correct.
+pub fn fifo_report(
+    positions: &[Position],
+    token: Option<&str>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> FifoReport {
+    let mut reconstructed_positions = 0;
+    // token -> (symbol, fills tagged with their position)
+    let mut by_token: BTreeMap<&str, (&str, Vec<(&str, TradeFill)>)> = BTreeMap::new();
+    for position in positions {
+        if position.is_demo || token.is_some_and(|t| position.token_address != t) {
+            continue;
+        }
+        let (fills, reconstructed) = position_fills(position);
+        if reconstructed {
+            reconstructed_positions += 1;
+        }
+        let entry = by_token.entry(&position.token_address).or_insert((&position.token_symbol, Vec::new()));
+        entry.1.extend(fills.into_iter().map(|f| (position.id.as_str(), f)));
+    }
+
+    let in_period = |at: DateTime<Utc>| !from.is_some_and(|f| at < f) && !to.is_some_and(|t| at >= t);
+    let mut realized = Vec::new();
+    let mut open_lots = Vec::new();
+
+    for (token_address, (symbol, mut fills)) in by_token {
+        // Buys first when a buy and sell share a timestamp
+        fills.sort_by(|a, b| a.1.timestamp.cmp(&b.1.timestamp).then((a.1.side == FillSide::Sell).cmp(&(b.1.side == FillSide::Sell))));
+
+        let mut lots: VecDeque<LotEntry> = VecDeque::new();
+        for (position_id, fill) in fills {
+            if fill.token_amount <= AMOUNT_EPSILON {
+                continue;
+            }
+            match fill.side {
+                FillSide::Buy => lots.push_back(LotEntry {
+                    position_id: position_id.to_string(),
+                    acquired_at: fill.timestamp,
+                    token_amount: fill.token_amount,
+                    cost_per_token: fill.sol_amount / fill.token_amount,
+                    buy_signature: fill.signature.clone(),
+                }),
+                FillSide::Sell => {
+                    let proceeds_per_token = fill.sol_amount / fill.token_amount;
+                    let mut remaining = fill.token_amount;
+                    // Sells beyond the tokens held (rounding) have no lot to match and are dropped
+                    while remaining > AMOUNT_EPSILON {
+                        let Some(lot) = lots.front_mut() else { break };
+                        let taken = remaining.min(lot.token_amount);
+                        if in_period(fill.timestamp) {
+                            let cost_basis_sol = taken * lot.cost_per_token;
+                            let proceeds_sol = taken * proceeds_per_token;
+                            realized.push(RealizedLot {
+                                token_address: token_address.to_string(),
+                                token_symbol: symbol.to_string(),
+                                position_id: lot.position_id.clone(),
+                                acquired_at: lot.acquired_at,
+                                disposed_at: fill.timestamp,
+                                token_amount: taken,
+                                cost_basis_sol,
+                                proceeds_sol,
+                                gain_sol: proceeds_sol - cost_basis_sol,
+                                buy_signature: lot.buy_signature.clone(),
+                                sell_signature: fill.signature.clone(),
+                            });
+                        }
+                        lot.token_amount -= taken;
+                        remaining -= taken;
+                        if lot.token_amount <= AMOUNT_EPSILON {
+                            lots.pop_front();
+                        }
+                    }
+                }
+            }
+        }
+
+        open_lots.extend(lots.into_iter().map(|lot| OpenLot {
+            token_address: token_address.to_string(),
+            token_symbol: symbol.to_string(),
+            position_id: lot.position_id,
+            acquired_at: lot.acquired_at,
+            token_amount: lot.token_amount,
+            cost_basis_sol: lot.token_amount * lot.cost_per_token,
+            buy_signature: lot.buy_signature,
+        }));
+    }
+
+    realized.sort_by(|a, b| a.disposed_at.cmp(&b.disposed_at));
+    let total_proceeds_sol = realized.iter().map(|l| l.proceeds_sol).sum();
+    let total_cost_basis_sol = realized.iter().map(|l| l.cost_basis_sol).sum();
+    FifoReport {
+        generated_at: Utc::now(),
+        token: token.map(|t| t.to_string()),
+        from,
+        to,
+        realized,
+        open_lots,
+        total_proceeds_sol,
+        total_cost_basis_sol,
+        total_realized_gain_sol: total_proceeds_sol - total_cost_basis_sol,
+        reconstructed_positions,
+    }
+}
+
+/// Realized lots as CSV in the usual disposal-report layout (one row per lot sold)
+pub fn realized_csv(report: &FifoReport) -> String {
+    let mut csv = String::from(
+        "Description,Token Address,Date Acquired,Date Sold,Amount,Proceeds (SOL),Cost Basis (SOL),Gain (SOL),Buy Tx,Sell Tx\n",
+    );
+    for lot in &report.realized {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{:.9},{:.9},{:.9},{},{}\n",
+            csv_field(&lot.token_symbol),
+            lot.token_address,
+            lot.acquired_at.to_rfc3339(),
+            lot.disposed_at.to_rfc3339(),
+            lot.token_amount,
+            lot.proceeds_sol,
+            lot.cost_basis_sol,
+            lot.gain_sol,
+            lot.buy_signature,
+            lot.sell_signature,
+        ));
+    }
+    csv
+}
+
+/// One closed position as a row of the trade history export
+#[derive(Debug, Clone, Serialize)]
+pub struct ClosedTrade {
+    pub position_id: String,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub strategy_id: String,
+    pub entry_time: DateTime<Utc>,
+    pub exit_time: DateTime<Utc>,
+    pub entry_price_sol: f64,
+    pub exit_price_sol: Option<f64>,
+    pub token_amount: f64,
+    pub entry_value_sol: f64,
+    pub exit_value_sol: f64,            // All sell proceeds, partial sells included (0 if written off)
+    pub transfer_tax_percent: f64,      // Token-2022 fee withheld on sell (already out of exit_value_sol)
+    pub profit_fee_sol: Option<f64>,    // Profit share paid to the fee wallet
+    pub pnl_sol: Option<f64>,
+    pub pnl_percent: Option<f64>,
+    pub pnl_usd: Option<f64>,
+    pub exit_reason: String,
+    pub entry_tx: String,
+    pub exit_tx: Option<String>,
+}
+
+/// Closed non-demo positions whose exit falls in `from`..`to`, oldest exit first
+pub fn closed_trades(positions: &[Position], from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Vec<ClosedTrade> {
+    let mut trades: Vec<ClosedTrade> = positions.iter()
+        .filter(|p| !p.is_demo)
+        .filter_map(|p| p.exit_time.map(|exit_time| (p, exit_time)))
+        .filter(|(_, at)| !from.is_some_and(|f| *at < f) && !to.is_some_and(|t| *at >= t))
+        .map(|(p, exit_time)| ClosedTrade {
+            position_id: p.id.clone(),
+            token_address: p.token_address.clone(),
+            token_symbol: p.token_symbol.clone(),
+            strategy_id: p.strategy_id.clone(),
+            entry_time: p.entry_time,
+            exit_time,
+            entry_price_sol: p.entry_price_sol,
+            exit_price_sol: p.exit_price_sol,
+            token_amount: p.entry_token_amount,
+            entry_value_sol: p.entry_value_sol,
+            exit_value_sol: p.exit_value_sol.unwrap_or(0.0),
+            transfer_tax_percent: p.transfer_tax_percent,
+            profit_fee_sol: p.profit_fee_sol,
+            pnl_sol: p.pnl_sol,
+            pnl_percent: p.pnl_percent,
+            pnl_usd: p.pnl_usd,
+            exit_reason: p.exit_reason(),
+            entry_tx: p.entry_tx_signature.clone(),
+            exit_tx: p.exit_tx_signature.clone(),
+        })
+        .collect();
+    trades.sort_by(|a, b| a.exit_time.cmp(&b.exit_time));
+    trades
+}
+
+/// Closed trades as CSV, one row per position
+pub fn closed_trades_csv(trades: &[ClosedTrade]) -> String {
+    let mut csv = String::from(
+        "Position ID,Token,Token Address,Strategy,Entry Time,Exit Time,Entry Price (SOL),Exit Price (SOL),Amount,Entry Value (SOL),Exit Value (SOL),Transfer Tax (%),Profit Fee (SOL),PnL (SOL),PnL (%),PnL (USD),Exit Reason,Entry Tx,Exit Tx\n",
+    );
+    let opt = |v: Option<f64>, precision: usize| v.map(|v| format!("{:.*}", precision, v)).unwrap_or_default();
+    for t in trades {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{:.12},{},{},{:.9},{:.9},{},{},{},{},{},{},{},{}\n",
+            t.position_id,
+            csv_field(&t.token_symbol),
+            t.token_address,
+            csv_field(&t.strategy_id),
+            t.entry_time.to_rfc3339(),
+            t.exit_time.to_rfc3339(),
+            t.entry_price_sol,
+            opt(t.exit_price_sol, 12),
+            t.token_amount,
+            t.entry_value_sol,
+            t.exit_value_sol,
+            t.transfer_tax_percent,
+            opt(t.profit_fee_sol, 9),
+            opt(t.pnl_sol, 9),
+            opt(t.pnl_percent, 2),
+            opt(t.pnl_usd, 2),
+            csv_field(&t.exit_reason),
+            t.entry_tx,
+            t.exit_tx.as_deref().unwrap_or_default(),
+        ));
+    }
+    csv
+}
+
+/// Quote a field if it contains separators or quotes
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn fill(minutes: i64, side: FillSide, tokens: f64, sol: f64) -> TradeFill {
+        TradeFill {
+            timestamp: DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap() + Duration::minutes(minutes),
+            side,
+            token_amount: tokens,
+            sol_amount: sol,
+            signature: format!("tx{}", minutes),
+        }
+    }
+
+    fn position(id: &str, fills: Vec<TradeFill>) -> Position {
+        let mut p: Position = serde_json::from_value(serde_json::json!({
+            "id": id, "token_address": "mint", "token_name": "Token", "token_symbol": "TKN",
+            "token_decimals": 6, "strategy_id": "s", "entry_time": fills[0].timestamp, "exit_time": null,
+            "entry_value_sol": fills[0].sol_amount, "entry_token_amount": fills[0].token_amount,
+            "expected_token_amount": fills[0].token_amount, "fill_percent": 1.0, "exit_value_sol": null,
+            "entry_price_sol": 0.0, "current_price_sol": 0.0, "exit_price_sol": null, "pnl_sol": null,
+            "pnl_percent": null, "stop_loss_price": null, "take_profit_price": null, "trailing_stop_price": null,
+            "trailing_stop_percent": null, "highest_price": 0.0, "status": "Active", "entry_tx_signature": "tx",
+            "exit_tx_signature": null, "is_demo": false, "max_hold_time_minutes": null,
+            "stop_loss_percent": null, "take_profit_percent": null
+        })).unwrap();
+        p.fills = fills;
+        p
+    }
+
+    #[test]
+    fn sells_consume_oldest_lots_first() {
+        let positions = vec![
+            // 100 @ 0.01, scale-in 100 @ 0.02, sell 150 for 4.5 SOL (0.03 each)
+            position("a", vec![
+                fill(0, FillSide::Buy, 100.0, 1.0),
+                fill(10, FillSide::Buy, 100.0, 2.0),
+                fill(20, FillSide::Sell, 150.0, 4.5),
+            ]),
+            // Later position in the same token: its lot queues behind a's remainder
+            position("b", vec![
+                fill(30, FillSide::Buy, 50.0, 2.0),
+                fill(40, FillSide::Sell, 80.0, 1.6),
+            ]),
+        ];
+        let report = fifo_report(&positions, Some("mint"), None, None);
+
+        let sold: Vec<(f64, f64, f64)> = report.realized.iter()
+            .map(|l| (l.token_amount, l.cost_basis_sol, l.proceeds_sol))
+            .collect();
+        assert_eq!(sold.len(), 4);
+        assert!((sold[0].0 - 100.0).abs() < 1e-9 && (sold[0].1 - 1.0).abs() < 1e-9 && (sold[0].2 - 3.0).abs() < 1e-9);
+        assert!((sold[1].0 - 50.0).abs() < 1e-9 && (sold[1].1 - 1.0).abs() < 1e-9);
+        assert!((sold[2].0 - 50.0).abs() < 1e-9 && (sold[2].1 - 1.0).abs() < 1e-9 && (sold[2].2 - 1.0).abs() < 1e-9);
+        assert_eq!(report.realized[3].position_id, "b");
+        assert!((sold[3].0 - 30.0).abs() < 1e-9 && (sold[3].1 - 1.2).abs() < 1e-9);
+
+        assert_eq!(report.open_lots.len(), 1);
+        assert!((report.open_lots[0].token_amount - 20.0).abs() < 1e-9);
+        assert!((report.total_realized_gain_sol - (6.1 - 4.2)).abs() < 1e-9);
+
+        // A period filter keeps only disposals in range, matched against the same lots
+        let from = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap() + Duration::minutes(35);
+        let later = fifo_report(&positions, None, Some(from), None);
+        assert_eq!(later.realized.len(), 2);
+        assert!((later.total_cost_basis_sol - 2.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn closed_trades_filter_by_exit_time_and_skip_demo() {
+        let base = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let closed = |id: &str, exit_minutes: i64, demo: bool| {
+            let mut p = position(id, vec![fill(0, FillSide::Buy, 100.0, 1.0)]);
+            p.exit_time = Some(base + Duration::minutes(exit_minutes));
+            p.exit_value_sol = Some(1.5);
+            p.pnl_sol = Some(0.75);
+            p.status = PositionStatus::TakeProfitHit;
+            p.is_demo = demo;
+            p
+        };
+        let positions = vec![
+            closed("late", 30, false),
+            closed("early", 10, false),
+            closed("demo", 20, true),
+            position("open", vec![fill(0, FillSide::Buy, 100.0, 1.0)]),
+        ];
+
+        let all = closed_trades(&positions, None, None);
+        let ids: Vec<&str> = all.iter().map(|t| t.position_id.as_str()).collect();
+        assert_eq!(ids, vec!["early", "late"]);
+        assert!((all[0].exit_value_sol - 1.5).abs() < 1e-9);
+
+        let windowed = closed_trades(&positions, Some(base + Duration::minutes(15)), Some(base + Duration::minutes(60)));
+        assert_eq!(windowed.len(), 1);
+        assert_eq!(windowed[0].position_id, "late");
+
+        let csv = closed_trades_csv(&windowed);
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.lines().nth(1).unwrap().starts_with("late,TKN,mint,s,"));
+    }
+
+    #[test]
+    fn csv_quotes_awkward_symbols() {
+        assert_eq!(csv_field("TKN"), "TKN");
+        assert_eq!(csv_field("A,B"), "\"A,B\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -1,29 +1,48 @@
 use anyhow::{anyhow, Context, Result};
 use borsh::BorshDeserialize;
 use std::collections::HashMap;
-use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::str::FromStr;
-use std::time::Duration;
-use tokio::sync::{mpsc, Mutex, RwLock};
-use tokio::time::interval;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex, Notify, RwLock};
+use tokio::time::{interval, interval_at, sleep};
 use chrono::Utc;
 use tracing::{debug, error, info, warn};
 use solana_client::nonblocking::rpc_client::RpcClient as SolanaRpcClient;
 
 use crate::api::birdeye::BirdeyeClient;
 use crate::api::helius::HeliusClient;
-use crate::api::jupiter::{JupiterClient, SwapResult};
+use crate::api::jupiter::{BuyPreview, JupiterClient, SwapResult, SwapRoute, SOL_MINT};
+use crate::api::swap_breaker::{SwapBreakerStatus, SwapCircuitBreaker};
+use crate::api::swap_error::SwapError;
 use crate::api::moralis::MoralisClient;
+use crate::api::price_cache::PriceCache;
+use crate::api::rate_limit::RateLimitBackoff;
 use crate::solana::client::SolanaClient;
+use crate::solana::fees::FeeUrgency;
+use crate::solana::jito::JitoClient;
+use crate::storage::Store;
 use crate::solana::wallet::WalletManager;
+use crate::solana::wallet_pool::WalletPool;
 use crate::config::Config;
-use crate::trading::position::PositionManager;
-use crate::trading::risk::{RiskAnalysis, RiskAnalyzer};
-use crate::trading::strategy::Strategy;
+use crate::trading::position::{CapacityLimit, Position, PositionManager, StrategyCapacityAlert};
+use crate::trading::escalation::{self, EscalationManager, IncidentKind};
+use crate::trading::blocklist::Blocklist;
+use crate::trading::dust_sweep::DustSweeper;
+use crate::trading::limit_orders::{order_action, LimitOrder, LimitOrderBook, LimitOrderStatus, OrderAction};
+use crate::trading::mint_check::{balance_shortfall, verify_received_mint, ReceivedMint};
+use crate::trading::sol_trend::SolTrendFilter;
+use crate::trading::swap_retry::SwapRetryLadder;
+use crate::trading::test_swap::{run_test_swap, TestSwapReport};
+use crate::trading::risk::{
+    break_even_gain_percent, fetch_transfer_tax_percent, liquidity_capped_size, round_trip_loss_percent, RiskAnalysis, RiskAnalyzer,
+};
+use crate::trading::strategy::{ExecutionVenue, StopLossType, Strategy, StrategyType, TokenLists, TradeNotify, DEFAULT_ENTRY_RETRY_DELAY_MS};
+use crate::trading::strategy_changelog::StrategyChangelog;
 use crate::trading::simulation::SimulationManager;
 use crate::trading::pumpfun::{PumpfunToken, BondingCurveState};
+use crate::trading::pool_monitor::{NewPool, PoolMonitor};
 use crate::trading::pumpfun_monitor::PumpfunMonitor;
 use crate::trading::graduation_monitor::{GraduationMonitor, GraduationEvent};
 use crate::trading::sniper::{CallSignal, Sniper};
@@ -35,25 +54,31 @@ use solana_sdk::pubkey::Pubkey;
 // --- Standalone Task Functions ---
 
 /// The main cycle executed by the background task.
+#[allow(clippy::too_many_arguments)]
 async fn run_scan_cycle(
     strategies_arc: Arc<RwLock<HashMap<String, Strategy>>>,
     helius_client: Arc<HeliusClient>,
     risk_analyzer: Arc<RiskAnalyzer>,
     position_manager: Arc<PositionManager>,
     config: Arc<Config>,
-    wallet_manager: Arc<WalletManager>,
+    wallet_pool: Arc<WalletPool>,
     jupiter_client: Arc<JupiterClient>,
     simulation_manager: Option<Arc<SimulationManager>>,
+    sol_trend_filter: Arc<SolTrendFilter>,
+    blocklist: Arc<Blocklist>,
+    limit_orders: Arc<LimitOrderBook>,
+    token_age_minutes: u64,
     // solana_client is implicitly used by risk_analyzer/position_manager/wallet_manager
 ) -> Result<()> {
     debug!("Scanning for trading opportunities...");
 
+    // Macro filter: don't buy launches into a market-wide SOL sell-off
+    if !config.demo_mode && !sol_trend_filter.allows_buys().await {
+        return Ok(());
+    }
+
     let strategies_guard = strategies_arc.read().await;
-    let enabled_strategies: Vec<_> = strategies_guard
-        .values()
-        .filter(|s| s.enabled)
-        .cloned()
-        .collect();
+    let enabled_strategies = enabled_strategies_in_order(&strategies_guard);
     drop(strategies_guard); // Release read lock
 
     if enabled_strategies.is_empty() {
@@ -62,7 +87,7 @@ async fn run_scan_cycle(
     }
 
     if config.demo_mode {
-        run_simulated_scan_cycle(&enabled_strategies, &position_manager, &config).await?;
+        run_simulated_scan_cycle(&enabled_strategies, &position_manager, &config, &blocklist).await?;
         return Ok(());
     }
 
@@ -73,7 +98,7 @@ async fn run_scan_cycle(
     } else {
         info!("Scanning for new tokens using Helius...");
     }
-    match helius_client.get_recent_tokens(60).await { // TODO: Make age configurable
+    match helius_client.get_recent_tokens(token_age_minutes).await {
         Ok(tokens) => {
             if tokens.is_empty() {
                 debug!("No new tokens found in this scan cycle.");
@@ -81,9 +106,19 @@ async fn run_scan_cycle(
             }
             info!("Found {} potential new tokens via Helius.", tokens.len());
 
+            let rate_limit = risk_analyzer.rate_limit_backoff();
             for token in tokens {
+                if let Some(wait) = rate_limit.remaining() {
+                    info!("⏳ Analysis APIs rate limited, backing off; ending scan early ({}s left)", wait.as_secs());
+                    break;
+                }
                 debug!("Processing potential token: {} ({})", token.name, token.address);
+                let analysis_started = std::time::Instant::now();
                 match risk_analyzer.analyze_token(&token.address).await {
+                    // A rate-limited lookup leaves gaps that would read as risk; don't judge the token on it
+                    Ok(_) if rate_limit.hit_since(analysis_started) => {
+                        info!("⏳ Skipping {}: rate limited during analysis", token.symbol);
+                    }
                     Ok(risk_analysis) => {
                         info!(
                             "Analyzed token {}: Risk Level {}, Liquidity {:.2} SOL, Holders {}",
@@ -91,7 +126,7 @@ async fn run_scan_cycle(
                         );
 
                         for strategy in &enabled_strategies {
-                            if meets_strategy_criteria(&token, &risk_analysis, strategy) {
+                            if meets_strategy_criteria(&token, &risk_analysis, strategy, &blocklist) {
                                 info!("✅ [CANDIDATE] Token {} meets criteria for strategy '{}' - Risk: {}/100",
                                     token.symbol, strategy.name, risk_analysis.risk_level);
 
@@ -120,22 +155,7 @@ async fn run_scan_cycle(
                                     }
                                 } else {
                                     // REAL MODE: Execute actual trade
-                                    if should_execute_buy_task(&token, strategy, &position_manager).await? {
-                                        match execute_buy_task(
-                                            &token,
-                                            strategy,
-                                            &position_manager,
-                                            &jupiter_client,
-                                            &wallet_manager,
-                                            &config,
-                                            None,
-                                        ).await {
-                                            Ok(_) => info!("Successfully executed buy and confirmed for {} via strategy '{}'", token.symbol, strategy.name),
-                                            Err(e) => error!("Failed to execute buy for {}: {:?}", token.symbol, e),
-                                        }
-                                    } else {
-                                        debug!("Buy condition not met for token {} and strategy '{}'", token.symbol, strategy.name);
-                                    }
+                                    try_buy_token(&token, strategy, &risk_analysis, &position_manager, &jupiter_client, &wallet_pool, &config, &limit_orders).await?;
                                 }
                             } else {
                                 // Enhanced logging for rejected tokens
@@ -168,11 +188,175 @@ async fn run_scan_cycle(
     Ok(())
 }
 
+/// Buys `token` for `strategy` if position/budget limits allow (real mode).
+/// Buy failures are logged, not returned; only the limit check can error.
+/// A strategy with `limit_entry` places a limit order below the current price instead.
+/// The size is capped to `max_position_fraction_of_liquidity` of the pool's liquidity.
+#[allow(clippy::too_many_arguments)]
+async fn try_buy_token(
+    token: &TokenMetadata,
+    strategy: &Strategy,
+    risk_analysis: &RiskAnalysis,
+    position_manager: &PositionManager,
+    jupiter_client: &JupiterClient,
+    wallet_pool: &WalletPool,
+    config: &Config,
+    limit_orders: &LimitOrderBook,
+) -> Result<()> {
+    if !should_execute_buy_task(token, strategy, position_manager).await? {
+        debug!("Buy condition not met for token {} and strategy '{}'", token.symbol, strategy.name);
+        return Ok(());
+    }
+    if let Some(wait) = jupiter_client.swap_breaker().paused_for() {
+        debug!("Skipping buy for {}: buys paused after repeated swap failures ({}s left)", token.symbol, wait.as_secs());
+        return Ok(());
+    }
+    let liquidity_sol = risk_analysis.liquidity_sol;
+    let capped_strategy;
+    let strategy = match config.max_position_fraction_of_liquidity {
+        Some(max_fraction) => {
+            let size = liquidity_capped_size(strategy.max_position_size_sol, liquidity_sol, max_fraction);
+            if size < strategy.max_position_size_sol {
+                if size < config.min_liquidity_capped_buy_sol {
+                    info!(
+                        "Skipping buy for {}: pool liquidity {:.2} SOL allows only {:.4} SOL ({:.0}% of liquidity), below the {:.4} SOL minimum",
+                        token.symbol, liquidity_sol, size, max_fraction * 100.0, config.min_liquidity_capped_buy_sol
+                    );
+                    return Ok(());
+                }
+                info!(
+                    "📉 Capping buy of {} from {:.4} to {:.4} SOL ({:.0}% of {:.2} SOL liquidity) for strategy '{}'",
+                    token.symbol, strategy.max_position_size_sol, size, max_fraction * 100.0, liquidity_sol, strategy.name
+                );
+                capped_strategy = Strategy { max_position_size_sol: size, ..strategy.clone() };
+                &capped_strategy
+            } else {
+                strategy
+            }
+        }
+        None => strategy,
+    };
+    if let Some(max_loss) = config.max_roundtrip_loss_percent.filter(|_| !config.demo_mode) {
+        let slippage_bps = strategy.slippage_bps.unwrap_or(config.live().default_slippage_bps);
+        match jupiter_client.quote_round_trip(&token.address, strategy.max_position_size_sol, slippage_bps).await {
+            Ok(quote) => {
+                let loss = round_trip_loss_percent(quote.sol_in, quote.sol_back, risk_analysis.transfer_tax_percent);
+                if loss > max_loss {
+                    info!(
+                        "Skipping buy for {}: buying {:.4} SOL and selling straight back returns {:.4} SOL, a {:.1}% round-trip loss (max {:.1}%) - likely a soft honeypot",
+                        token.symbol, quote.sol_in, quote.sol_back, loss, max_loss
+                    );
+                    return Ok(());
+                }
+                debug!("Round-trip cost of {} at {:.4} SOL: {:.1}%", token.symbol, quote.sol_in, loss);
+            }
+            // Bonding-curve tokens have no Jupiter route yet; the sellability check still applies
+            Err(e) => debug!("No round-trip quote for {}, not checking its spread: {:#}", token.symbol, e),
+        }
+    }
+    if let Some(limit_entry) = &strategy.limit_entry {
+        if limit_orders.has_pending(&token.address).await {
+            debug!("Limit order for {} already pending, not placing another", token.symbol);
+            return Ok(());
+        }
+        let price = match jupiter_client.get_price(SOL_MINT, &token.address, token.decimals).await {
+            Ok(price) => price,
+            Err(e) => {
+                warn!("No price for {}, cannot place limit entry for strategy '{}': {:?}", token.symbol, strategy.name, e);
+                return Ok(());
+            }
+        };
+        let now = Utc::now();
+        let order = LimitOrder {
+            id: uuid::Uuid::new_v4().to_string(),
+            token_address: token.address.clone(),
+            token_name: token.name.clone(),
+            token_symbol: token.symbol.clone(),
+            token_decimals: token.decimals,
+            target_price_sol: limit_entry.target_price(price),
+            amount_sol: strategy.max_position_size_sol,
+            strategy_id: Some(strategy.id.clone()),
+            created_at: now,
+            expires_at: now + chrono::Duration::minutes(limit_entry.expiry_minutes as i64),
+            status: LimitOrderStatus::Pending,
+            last_price_sol: Some(price),
+            closed_at: None,
+            signature: None,
+            error: None,
+        };
+        if let Err(e) = limit_orders.place(order).await {
+            warn!("Failed to place limit entry for {} via strategy '{}': {:?}", token.symbol, strategy.name, e);
+        }
+        return Ok(());
+    }
+    position_manager.set_exit_slippage_hint(&token.address, risk_analysis.recommended_slippage_bps).await;
+    match execute_buy_with_entry_retry(
+        token,
+        strategy,
+        position_manager,
+        jupiter_client,
+        &wallet_pool.next_wallet(),
+        config,
+        Some(risk_analysis.transfer_tax_percent),
+    ).await {
+        Ok(_) => info!("Successfully executed buy and confirmed for {} via strategy '{}'", token.symbol, strategy.name),
+        Err(e) => error!("Failed to execute buy for {} [{}]: {:?}", token.symbol, SwapError::classify(&e), e),
+    }
+    position_manager.clear_exit_slippage_hint(&token.address).await;
+    Ok(())
+}
+
+/// Evaluates a token pushed by the real-time discovery stream against the enabled
+/// strategies and buys it if one matches - the same pipeline as `run_scan_cycle`,
+/// for a single token, without waiting for the next scan.
+#[allow(clippy::too_many_arguments)]
+async fn process_realtime_token(
+    token: TokenMetadata,
+    strategies_arc: Arc<RwLock<HashMap<String, Strategy>>>,
+    risk_analyzer: Arc<RiskAnalyzer>,
+    position_manager: Arc<PositionManager>,
+    config: Arc<Config>,
+    wallet_pool: Arc<WalletPool>,
+    jupiter_client: Arc<JupiterClient>,
+    sol_trend_filter: Arc<SolTrendFilter>,
+    blocklist: Arc<Blocklist>,
+    limit_orders: Arc<LimitOrderBook>,
+) -> Result<()> {
+    let enabled_strategies = enabled_strategies_in_order(&strategies_arc.read().await);
+    if enabled_strategies.is_empty() || !sol_trend_filter.allows_buys().await {
+        return Ok(());
+    }
+
+    if let Some(wait) = risk_analyzer.rate_limit_backoff().remaining() {
+        debug!("⚡ [REALTIME] Skipping {}: analysis APIs rate limited ({}s left)", token.symbol, wait.as_secs());
+        return Ok(());
+    }
+    let analysis_started = std::time::Instant::now();
+    let risk_analysis = risk_analyzer.analyze_token(&token.address).await?;
+    if risk_analyzer.rate_limit_backoff().hit_since(analysis_started) {
+        info!("⏳ [REALTIME] Skipping {}: rate limited during analysis", token.symbol);
+        return Ok(());
+    }
+    info!(
+        "⚡ [REALTIME] Analyzed {}: Risk Level {}, Liquidity {:.2} SOL, Holders {}",
+        token.symbol, risk_analysis.risk_level, risk_analysis.liquidity_sol, risk_analysis.holder_count
+    );
+
+    for strategy in &enabled_strategies {
+        if meets_strategy_criteria(&token, &risk_analysis, strategy, &blocklist) {
+            info!("✅ [REALTIME] Token {} meets criteria for strategy '{}'", token.symbol, strategy.name);
+            try_buy_token(&token, strategy, &risk_analysis, &position_manager, &jupiter_client, &wallet_pool, &config, &limit_orders).await?;
+        }
+    }
+    Ok(())
+}
+
 /// Simulates the scanning process in demo mode.
 async fn run_simulated_scan_cycle(
     enabled_strategies: &[Strategy],
     position_manager: &PositionManager, // Pass Arc<PositionManager>
     _config: &Config, // Pass Arc<Config> - Prefixed as unused for now
+    blocklist: &Blocklist,
 ) -> Result<()> {
     info!("[DEMO MODE] Simulating scan for opportunities...");
     // Simulate finding a token occasionally
@@ -186,6 +370,7 @@ async fn run_simulated_scan_cycle(
             supply: Some(1_000_000_000 * 10u64.pow(9)), // Example supply
             logo_uri: None,
             creation_time: Some(Utc::now()),
+            creator: None,
         };
         info!("[DEMO MODE] Simulated finding token: {} ({})", demo_token.name, demo_token.symbol);
 
@@ -201,13 +386,15 @@ async fn run_simulated_scan_cycle(
              transfer_tax_percent: if rand::random::<f64>() < 0.1 { rand::random::<f64>() * 10.0 } else { 0.0 },
              can_sell: rand::random::<f64>() > 0.1, // 90% chance can sell
              concentration_percent: rand::random::<f64>() * 50.0, // 0-50%
+             recommended_slippage_bps: 300,
+             deployer: None,
              details: vec!["Simulated analysis".to_string()],
         };
          info!("[DEMO MODE] Simulated analysis for {}: Risk {}, Liquidity {:.2}", demo_token.symbol, risk_analysis.risk_level, risk_analysis.liquidity_sol);
 
 
         for strategy in enabled_strategies {
-            if meets_strategy_criteria(&demo_token, &risk_analysis, strategy) {
+            if meets_strategy_criteria(&demo_token, &risk_analysis, strategy, blocklist) {
                 info!("[DEMO MODE] Token {} meets criteria for strategy '{}'", demo_token.symbol, strategy.name);
                  if should_execute_buy_task(&demo_token, strategy, position_manager).await? {
                      info!("[DEMO MODE] Executing simulated buy for {} via strategy '{}'", demo_token.symbol, strategy.name);
@@ -230,12 +417,61 @@ async fn run_simulated_scan_cycle(
     Ok(())
 }
 
+/// How often the Helius scan runs and how far back it looks. Starts from config and
+/// can be changed at runtime through `PATCH /api/autotrader/settings`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct ScanSettings {
+    pub scan_interval_secs: u64,
+    pub token_age_minutes: u64,
+}
+
+impl ScanSettings {
+    /// These settings with the enabled strategies' overrides applied: the shortest
+    /// interval and the longest lookback any of them asks for
+    fn with_overrides(self, strategies: &[Strategy]) -> Self {
+        Self {
+            scan_interval_secs: strategies.iter()
+                .filter_map(|s| s.scan_interval_secs)
+                .map(u64::from)
+                .fold(self.scan_interval_secs, u64::min),
+            token_age_minutes: strategies.iter()
+                .filter_map(|s| s.scan_token_age_minutes)
+                .map(u64::from)
+                .fold(self.token_age_minutes, u64::max),
+        }
+    }
+}
+
+/// Scan settings in force right now, strategy overrides included
+async fn effective_scan_settings(
+    scan_settings: &RwLock<ScanSettings>,
+    strategies: &RwLock<HashMap<String, Strategy>>,
+) -> ScanSettings {
+    let base = *scan_settings.read().await;
+    base.with_overrides(&enabled_strategies_in_order(&*strategies.read().await))
+}
+
+/// Enabled strategies in the order they get a chance to buy (oldest first).
+/// The first matching strategy with room in its budget claims the token.
+fn enabled_strategies_in_order(strategies: &HashMap<String, Strategy>) -> Vec<Strategy> {
+    let mut enabled: Vec<Strategy> = strategies.values().filter(|s| s.enabled).cloned().collect();
+    enabled.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.name.cmp(&b.name)));
+    enabled
+}
+
 /// Checks if a token meets the criteria defined by a strategy based on risk analysis.
 fn meets_strategy_criteria(
     token: &TokenMetadata,
     risk_analysis: &RiskAnalysis,
     strategy: &Strategy,
+    blocklist: &Blocklist,
 ) -> bool {
+    if let Some(reason) = blocklist.block_reason(&token.address, token.creator.as_deref())
+        .or_else(|| strategy.token_lists.rejection_reason(&token.address, token.creator.as_deref()))
+    {
+        debug!("Token {} rejected by strategy '{}': {}", token.symbol, strategy.name, reason);
+        return false;
+    }
     if risk_analysis.risk_level > strategy.max_risk_level {
         debug!("Token {} rejected by strategy '{}': Risk level {} > {}", token.symbol, strategy.name, risk_analysis.risk_level, strategy.max_risk_level);
         return false;
@@ -245,9 +481,11 @@ fn meets_strategy_criteria(
         return false;
     }
     if let Some(creation_time) = token.creation_time {
-        let age_minutes = Utc::now().signed_duration_since(creation_time).num_minutes();
-        if age_minutes > 0 && age_minutes as u32 > strategy.max_token_age_minutes { // Check age > 0 to avoid issues with clock sync
-             debug!("Token {} rejected by strategy '{}': Age {} mins > {}", token.symbol, strategy.name, age_minutes, strategy.max_token_age_minutes);
+        // max_token_age_seconds, when set, replaces the minute limit (see Strategy::max_token_age_secs)
+        let age_secs = Utc::now().signed_duration_since(creation_time).num_seconds();
+        let max_age_secs = strategy.max_token_age_secs();
+        if age_secs > 0 && age_secs as u64 > max_age_secs { // Check age > 0 to avoid issues with clock sync
+             debug!("Token {} rejected by strategy '{}': Age {}s > {}s", token.symbol, strategy.name, age_secs, max_age_secs);
             return false;
         }
     } else {
@@ -260,6 +498,12 @@ fn meets_strategy_criteria(
         return false;
     }
     // Add more checks based on RiskAnalysis fields (mint/freeze authority, tax, etc.) if needed
+    if let Some(max_tax) = strategy.max_transfer_tax_percent {
+        if risk_analysis.transfer_tax_percent > max_tax {
+            debug!("Token {} rejected by strategy '{}': Transfer tax {:.1}% > {:.1}%", token.symbol, strategy.name, risk_analysis.transfer_tax_percent, max_tax);
+            return false;
+        }
+    }
     if !risk_analysis.can_sell && strategy.require_can_sell {
          debug!("Token {} rejected by strategy '{}': Cannot sell and strategy requires it", token.symbol, strategy.name);
         return false;
@@ -286,30 +530,358 @@ async fn should_execute_buy_task(
         return Ok(false);
     }
 
+    // Don't keep re-entering a token that already stopped us out at a loss
+    if let Some(exit) = position_manager.rebuy_blocked_by_loss(&token.address).await {
+        info!("Skipping buy for {}: stopped out at a loss ({:.4} SOL) at {} (loss rebuy guard).",
+             token.symbol, exit.pnl_sol, exit.exited_at.format("%H:%M:%S"));
+        return Ok(false);
+    }
+
+    // Losing streak or daily drawdown cooldown
+    if let Some(pause) = position_manager.loss_breaker().buy_paused(&strategy.id, Utc::now()) {
+        info!("Skipping buy for {}: loss circuit breaker ({:?}) pauses strategy '{}' until {}.",
+             token.symbol, pause.reason, strategy.name, pause.until.format("%H:%M:%S"));
+        return Ok(false);
+    }
+
     // Check strategy-specific limits (concurrent positions, budget)
     let strategy_positions = position_manager.get_active_positions_by_strategy(&strategy.id).await;
+    let used_budget: f64 = strategy_positions.iter().map(|p| p.entry_value_sol).sum(); // Use entry value
+    let capacity_alert = |limit| StrategyCapacityAlert {
+        strategy_id: strategy.id.clone(),
+        strategy_name: strategy.name.clone(),
+        limit,
+        open_positions: strategy_positions.len(),
+        max_concurrent_positions: strategy.max_concurrent_positions,
+        deployed_sol: used_budget,
+        budget_sol: strategy.total_budget_sol,
+        timestamp: Utc::now(),
+    };
 
     if strategy_positions.len() >= strategy.max_concurrent_positions as usize {
         info!("Skipping buy for {}: Max concurrent positions ({}) reached for strategy '{}'.",
              token.symbol, strategy.max_concurrent_positions, strategy.name);
+        position_manager.strategy_maxed_out(capacity_alert(CapacityLimit::MaxConcurrentPositions)).await;
         return Ok(false);
     }
 
-    let used_budget: f64 = strategy_positions.iter().map(|p| p.entry_value_sol).sum(); // Use entry value
     let position_size = strategy.max_position_size_sol; // Determine intended size first
     let remaining_budget = strategy.total_budget_sol - used_budget;
 
     if position_size > remaining_budget {
         warn!("Skipping buy for {}: Required size {:.4} SOL exceeds remaining budget {:.4} SOL for strategy '{}'.",
              token.symbol, position_size, remaining_budget, strategy.name);
+        position_manager.strategy_maxed_out(capacity_alert(CapacityLimit::Budget)).await;
         return Ok(false);
     }
+    position_manager.strategy_has_capacity(&strategy.id).await;
 
     // Check overall wallet balance? Maybe not here, rely on swap failing if insufficient.
 
     Ok(true)
 }
 
+/// Confirms a buy, re-checking the signature after a timeout so a buy that lands late
+/// is still treated as successful (otherwise the position would go untracked and the
+/// token could be bought again).
+async fn confirm_buy_transaction(
+    solana_client: &SolanaClient,
+    signature: &Signature,
+    config: &Config,
+) -> Result<()> {
+    let err = match solana_client.confirm_transaction(
+        signature,
+        solana_sdk::commitment_config::CommitmentLevel::Confirmed,
+        config.confirm_timeout_secs,
+    ).await {
+        Ok(()) => return Ok(()),
+        Err(e) => e,
+    };
+
+    for attempt in 1..=config.post_timeout_verify_attempts {
+        tokio::time::sleep(Duration::from_millis(config.post_timeout_verify_delay_ms)).await;
+        match solana_client.get_signature_outcome(signature).await {
+            Ok(Some(true)) => {
+                warn!("Buy {} confirmed late (post-timeout check {}/{}) - treating as successful",
+                      signature, attempt, config.post_timeout_verify_attempts);
+                return Ok(());
+            }
+            Ok(Some(false)) => {
+                debug!("Buy {} failed on-chain (post-timeout check)", signature);
+                break;
+            }
+            Ok(None) => debug!("Buy {} still not found (post-timeout check {}/{})",
+                               signature, attempt, config.post_timeout_verify_attempts),
+            Err(e) => warn!("Post-timeout status check for {} failed: {}", signature, e),
+        }
+    }
+
+    Err(err)
+}
+
+/// Checks a confirmed buy delivered `token` itself rather than a look-alike mint.
+/// On a mismatch the wrong tokens are optionally sold straight back and an error is
+/// returned so no position is created. A buy that can't be checked is let through.
+/// Returns the amount of `token` received, when the transaction showed it.
+async fn check_bought_mint(
+    token: &TokenMetadata,
+    signature: &Signature,
+    jupiter_client: &JupiterClient,
+    wallet_manager: &WalletManager,
+    config: &Config,
+) -> Result<Option<f64>> {
+    let owner = wallet_manager.get_public_key().to_string();
+    let received = match verify_received_mint(&wallet_manager.solana_client(), signature, &owner, &token.address).await {
+        Ok(ReceivedMint::Mismatch { received }) => received,
+        Ok(ReceivedMint::Matches { amount_ui }) => return Ok(Some(amount_ui)),
+        Ok(ReceivedMint::Unknown) => {
+            warn!("Could not verify the mint received by buy {} for {}: no token balance change found", signature, token.symbol);
+            return Ok(None);
+        }
+        Err(e) => {
+            warn!("Could not verify the mint received by buy {} for {}: {}", signature, token.symbol, e);
+            return Ok(None);
+        }
+    };
+
+    let received_mints: Vec<&str> = received.iter().map(|t| t.mint.as_str()).collect();
+    error!(
+        "🚨 MINT MISMATCH: buy {} for {} ({}) delivered {:?} instead - possible spoofed route, not opening a position",
+        signature, token.symbol, token.address, received_mints
+    );
+
+    if config.sell_mismatched_mint {
+        for wrong in &received {
+            match jupiter_client.swap_token_to_sol(
+                &wrong.mint,
+                wrong.decimals,
+                wrong.amount_ui,
+                config.live().default_slippage_bps,
+                Some(config.live().default_priority_fee_micro_lamports),
+                SwapRoute::default(),
+                wallet_manager.clone().into(),
+            ).await {
+                Ok(result) => warn!("Sold mismatched token {} ({:.4} SOL back): {}", wrong.mint, result.out_amount_ui, result.transaction_signature),
+                Err(e) => error!("Failed to sell mismatched token {} - sell it manually: {:?}", wrong.mint, e),
+            }
+        }
+    }
+
+    Err(anyhow!(
+        "Buy {} for {} delivered the wrong mint ({}), expected {}",
+        signature, token.symbol, received_mints.join(", "), token.address
+    ))
+}
+
+/// Attempts at reading the post-buy balance; RPC nodes can trail the confirmation briefly
+const BUY_BALANCE_CHECK_ATTEMPTS: u32 = 3;
+const BUY_BALANCE_CHECK_RETRY_MS: u64 = 1_500;
+
+/// Read post-buy state up to `attempts` times, `retry_delay` apart, until a read is
+/// `done`. Returns the last successful read (None if every read failed).
+async fn read_after_buy<T, F, Fut>(attempts: u32, retry_delay: Duration, mut read: F, done: impl Fn(&T) -> bool) -> Option<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Option<T>>,
+{
+    let mut last = None;
+    for attempt in 0..attempts {
+        if attempt > 0 {
+            sleep(retry_delay).await;
+        }
+        if let Some(value) = read().await {
+            if done(&value) {
+                return Some(value);
+            }
+            last = Some(value);
+        }
+    }
+    last
+}
+
+/// The wallet's balance of `token` before a buy, so the check after it sees only
+/// what the buy added (scale-ins, re-buys and moonbags leave tokens already held).
+/// None if it can't be read.
+async fn balance_before_buy(token: &TokenMetadata, wallet_manager: &WalletManager) -> Option<f64> {
+    let mint = Pubkey::from_str(&token.address).ok()?;
+    wallet_manager.solana_client().get_mint_balance_ui(&wallet_manager.get_public_key(), &mint).await
+        .map_err(|e| warn!("Could not read {} balance before buying: {}", token.symbol, e))
+        .ok()
+}
+
+/// Confirm the buy added at least `buy_balance_min_percent` of the bought tokens to
+/// the wallet's `balance_before`. On a shortfall an incident carrying the signature is
+/// raised and no position opens; if the balance can't be read after the buy it is
+/// let through.
+async fn check_bought_balance(
+    token: &TokenMetadata,
+    signature: &Signature,
+    expected_ui: f64,
+    balance_before: Option<f64>,
+    wallet_manager: &WalletManager,
+    position_manager: &PositionManager,
+    config: &Config,
+) -> Result<()> {
+    let mint = Pubkey::from_str(&token.address).context("Invalid token mint address")?;
+    let owner = wallet_manager.get_public_key();
+    let solana_client = wallet_manager.solana_client();
+    // Without a pre-buy reading, fall back to checking the whole balance
+    let balance_before = balance_before.unwrap_or(0.0);
+    let shortfall = |received: &f64| balance_shortfall(expected_ui, *received, config.buy_balance_min_percent);
+    let received = read_after_buy(
+        BUY_BALANCE_CHECK_ATTEMPTS,
+        Duration::from_millis(BUY_BALANCE_CHECK_RETRY_MS),
+        || async {
+            solana_client.get_mint_balance_ui(&owner, &mint).await
+                .map(|balance| balance - balance_before)
+                .map_err(|e| warn!("Could not read {} balance after buy {}: {}", token.symbol, signature, e))
+                .ok()
+        },
+        |balance| !shortfall(balance),
+    ).await;
+
+    let received = match received {
+        Some(received) if !shortfall(&received) => return Ok(()),
+        Some(received) => received,
+        None => {
+            warn!("Could not verify the balance received by buy {} for {}; opening the position anyway", signature, token.symbol);
+            return Ok(());
+        }
+    };
+    let message = format!(
+        "Buy {} for {} ({}) confirmed but wallet {} received only {:.4} of the ~{:.4} tokens swapped - no position opened, investigate manually",
+        signature, token.symbol, token.address, owner, received, expected_ui
+    );
+    error!("🚨 BALANCE MISMATCH: {}", message);
+    position_manager.escalation()
+        .raise(&escalation::missing_tokens_key(&signature.to_string()), IncidentKind::MissingBuyTokens, message)
+        .await;
+
+    Err(anyhow!(
+        "Buy {} for {} added {:.4} tokens to the wallet, expected ~{:.4}",
+        signature, token.symbol, received, expected_ui
+    ))
+}
+
+/// Correct a new position's token amount, and so its entry price, to what the wallet
+/// actually gained in the buy (read from the confirmed transaction) rather than the
+/// swap's estimate
+async fn reconcile_buy_fill(
+    position: &Position,
+    signature: &Signature,
+    wallet_manager: &WalletManager,
+    position_manager: &PositionManager,
+) {
+    let owner = wallet_manager.get_public_key().to_string();
+    let solana_client = wallet_manager.solana_client();
+    let received = read_after_buy(
+        BUY_BALANCE_CHECK_ATTEMPTS,
+        Duration::from_millis(BUY_BALANCE_CHECK_RETRY_MS),
+        || async {
+            match verify_received_mint(&solana_client, signature, &owner, &position.token_address).await {
+                Ok(ReceivedMint::Matches { amount_ui }) => Some(amount_ui),
+                Ok(_) => {
+                    debug!("Buy {} shows no {} balance change yet", signature, position.token_symbol);
+                    None
+                }
+                Err(e) => {
+                    debug!("Could not read the fill of buy {}: {}", signature, e);
+                    None
+                }
+            }
+        },
+        |_| true,
+    ).await;
+
+    let Some(amount) = received.filter(|a| *a > 0.0) else {
+        warn!(
+            "Could not read the fill of buy {} for {}; keeping the estimated {:.4} tokens",
+            signature, position.token_symbol, position.entry_token_amount
+        );
+        return;
+    };
+    if let Err(e) = position_manager.update_position_fill_amount(&position.id, amount).await {
+        warn!("Failed to reconcile the fill of position {} ({}): {:?}", position.id, position.token_symbol, e);
+    }
+}
+
+/// Buy a triggered limit order with its strategy (manual orders use the "default"
+/// strategy), sized to the order's amount
+async fn execute_limit_order(
+    order: &LimitOrder,
+    strategies: &RwLock<HashMap<String, Strategy>>,
+    position_manager: &PositionManager,
+    jupiter_client: &JupiterClient,
+    wallet_pool: &WalletPool,
+    config: &Config,
+) -> Result<SwapResult> {
+    let mut strategy = {
+        let strategies = strategies.read().await;
+        match &order.strategy_id {
+            Some(id) => strategies.get(id).cloned()
+                .ok_or_else(|| anyhow!("Strategy {} that placed the order no longer exists", id))?,
+            None => strategies.values().find(|s| s.name.to_lowercase() == "default").cloned()
+                .unwrap_or_else(|| Strategy::default("Default")),
+        }
+    };
+    strategy.max_position_size_sol = order.amount_sol;
+
+    if position_manager.has_active_position(&order.token_address).await {
+        return Err(anyhow!("Already have an active position in token {}", order.token_address));
+    }
+
+    let token = TokenMetadata {
+        address: order.token_address.clone(),
+        name: order.token_name.clone(),
+        symbol: order.token_symbol.clone(),
+        decimals: order.token_decimals,
+        supply: None,
+        logo_uri: None,
+        creation_time: None,
+        creator: None,
+    };
+    execute_buy_with_entry_retry(&token, &strategy, position_manager, jupiter_client, &wallet_pool.next_wallet(), config, None).await
+}
+
+/// Runs `execute_buy_task`, retrying up to `strategy.entry_retry_attempts` times when the
+/// buy failed before anything was sent (no route yet, or the swap failed simulation).
+/// Just-launched pools often become tradable a second or two after detection.
+/// Retries stop once `entry_retry_max_window_ms` has elapsed.
+async fn execute_buy_with_entry_retry(
+    token: &TokenMetadata,
+    strategy: &Strategy,
+    position_manager: &PositionManager,
+    jupiter_client: &JupiterClient,
+    wallet_manager: &WalletManager,
+    config: &Config,
+    transfer_tax_percent: Option<f64>,
+) -> Result<SwapResult> {
+    let started = Instant::now();
+    let max_window = Duration::from_millis(config.entry_retry_max_window_ms);
+    let delay = Duration::from_millis(strategy.entry_retry_delay_ms);
+    let mut attempt = 0;
+
+    loop {
+        let err = match execute_buy_task(token, strategy, position_manager, jupiter_client, wallet_manager, config, transfer_tax_percent, None).await {
+            Ok(result) => return Ok(result),
+            Err(e) => e,
+        };
+
+        let kind = SwapError::classify(&err);
+        let retryable = matches!(kind, SwapError::NoRoute | SwapError::SimulationFailed);
+        if !retryable || attempt >= strategy.entry_retry_attempts || started.elapsed() + delay > max_window {
+            return Err(err);
+        }
+
+        attempt += 1;
+        warn!(
+            "Entry for {} failed [{}], retrying in {}ms ({}/{})",
+            token.symbol, kind, delay.as_millis(), attempt, strategy.entry_retry_attempts
+        );
+        sleep(delay).await;
+    }
+}
+
 /// Executes the buy swap via Jupiter, confirms the transaction, and creates a position entry.
 async fn execute_buy_task(
     token: &TokenMetadata,
@@ -318,6 +890,7 @@ async fn execute_buy_task(
     jupiter_client: &JupiterClient, // Pass Arc<JupiterClient>
     wallet_manager: &WalletManager, // Pass Arc<WalletManager> (holds SolanaClient)
     config: &Config, // Pass Arc<Config>
+    transfer_tax_percent: Option<f64>, // From the risk analysis, if the caller ran one
     _notification_tx: Option<()>, // Placeholder for future WebSocket notification channel
 ) -> Result<SwapResult> { // Return SwapResult
     info!(
@@ -326,7 +899,12 @@ async fn execute_buy_task(
     );
 
     // Determine position size based on strategy (consider risk adjustment?)
-    let position_size_sol = strategy.max_position_size_sol; // Simple for now
+    // A scale-in or DCA strategy only buys its first tranche here; the monitor adds the rest
+    let entry_tranches = strategy.entry_tranches();
+    let position_size_sol = match &entry_tranches {
+        Some(scale_in) => scale_in.initial_size_sol(strategy.max_position_size_sol),
+        None => strategy.max_position_size_sol,
+    };
     // TODO: Add risk-adjusted position sizing?
     // position_size_sol = position_size_sol * risk_adjustment_factor;
 
@@ -335,17 +913,186 @@ async fn execute_buy_task(
         return Err(anyhow!("Calculated position size is zero or negative for token {}", token.symbol));
     }
 
+    position_manager.check_token_allocation(&token.address, position_size_sol).await?;
+
+    // Don't buy off stale prices from a node that has fallen behind
+    if let Some(status) = wallet_manager.solana_client().slot_lag_status().filter(|s| s.lagging) {
+        return Err(anyhow!(
+            "Solana RPC is {} slots behind; not buying {} until it catches up",
+            status.lag_slots, token.symbol
+        ));
+    }
+
+    // Curve strategies only buy tokens still on their curve and far enough along it
+    if strategy.strategy_type == StrategyType::PumpfunBondingCurve {
+        let curve = crate::trading::pumpfun_swap::active_curve(&wallet_manager.solana_client(), &token.address).await
+            .ok_or_else(|| anyhow!("{} is not on an active pump.fun bonding curve", token.symbol))?;
+        let progress = curve.state.get_progress_percent();
+        let min_progress = strategy.min_bonding_progress.unwrap_or(0.0);
+        if progress < min_progress {
+            return Err(anyhow!(
+                "{} bonding curve is {:.1}% complete, below the strategy's {:.1}% minimum",
+                token.symbol, progress, min_progress
+            ));
+        }
+    }
+
+    // Warn when a transfer tax makes a profitable exit hard (taxed on the way in and out)
+    let transfer_tax_percent = match transfer_tax_percent {
+        Some(tax) => tax,
+        None => match Pubkey::from_str(&token.address) {
+            Ok(mint) => fetch_transfer_tax_percent(&wallet_manager.solana_client(), &mint).await.unwrap_or(0.0),
+            Err(_) => 0.0,
+        },
+    };
+    if transfer_tax_percent > 0.0 && transfer_tax_percent >= config.transfer_tax_warn_percent {
+        warn!(
+            "⚠️ {} has a {:.1}% transfer tax - price must rise {:.1}% just to break even",
+            token.symbol, transfer_tax_percent, break_even_gain_percent(transfer_tax_percent)
+        );
+    }
+
     // Fetch token decimals if not already known (needed for Jupiter swap)
     // Assuming TokenMetadata now includes decimals correctly populated by Helius/RiskAnalyzer
     let token_decimals = token.decimals;
 
+    // Strategy priority fee, else estimated from recent blocks at the strategy's urgency
+    let mut priority_fee = match strategy.priority_fee_micro_lamports {
+        Some(fee) => fee,
+        None => position_manager.priority_fee(&token.address, strategy.fee_urgency, config.live().default_priority_fee_micro_lamports).await,
+    };
+
+    // Held until the position is created, so the dust sweep leaves the tokens alone
+    let _buy_in_flight = position_manager.begin_buy(&token.address);
+    let verify_balance = config.verify_buy_balance && !config.demo_mode;
+    let balance_before = match verify_balance {
+        true => balance_before_buy(token, wallet_manager).await,
+        false => None,
+    };
+
     // --- Execute Swap ---
-    let swap_result = jupiter_client.swap_sol_to_token(
+    // Re-quoted with more slippage and a higher fee if it fails on slippage or an expired blockhash
+    let ladder = SwapRetryLadder::from_config(config);
+    let mut slippage_bps = strategy.slippage_bps.unwrap_or(config.live().default_slippage_bps); // Use strategy slippage or default
+    let mut attempt = 0;
+    let (swap_result, signature) = loop {
+        let err = match send_and_confirm_buy(token, position_size_sol, slippage_bps, priority_fee, strategy, jupiter_client, wallet_manager, config).await {
+            Ok(sent) => break sent,
+            Err(e) => e,
+        };
+        let kind = SwapError::classify(&err);
+        if !SwapRetryLadder::retries(kind) || attempt >= ladder.attempts {
+            return Err(err);
+        }
+        attempt += 1;
+        (slippage_bps, priority_fee) = ladder.escalate(slippage_bps, priority_fee);
+        warn!(
+            "Buy for {} failed [{}], re-quoting at {} bps slippage, {} μlamports priority ({}/{})",
+            token.symbol, kind, slippage_bps, priority_fee, attempt, ladder.attempts
+        );
+    };
+
+    let received = if config.verify_bought_mint {
+        check_bought_mint(token, &signature, jupiter_client, wallet_manager, config).await?
+    } else {
+        None
+    };
+
+    // --- Create Position Entry (Only after confirmation) ---
+    // The wallet's token delta when the mint check read it, else the swap's estimate
+    // (reconciled below once the confirmed transaction can be read)
+    let received = received.filter(|_| config.reconcile_buy_fills);
+    let actual_out_amount = received.unwrap_or(swap_result.actual_out_amount_ui.unwrap_or(swap_result.out_amount_ui));
+
+    if verify_balance {
+        check_bought_balance(token, &signature, actual_out_amount, balance_before, wallet_manager, position_manager, config).await?;
+    }
+    
+    // Check fill rate - if it's too low, warn the user
+    let fill_rate = if swap_result.out_amount_ui > 0.0 {
+        (actual_out_amount / swap_result.out_amount_ui) * 100.0
+    } else {
+        100.0 // Default to 100% if expected is 0
+    };
+    
+    // Log warning if fill rate is low
+    if fill_rate < 95.0 {
+        warn!(
+            "Low fill rate detected: Received {:.4} tokens ({:.1}% of expected {:.4})",
+            actual_out_amount, fill_rate, swap_result.out_amount_ui
+        );
+
+        // TODO: Send notification via WebSocket when implemented
+        if fill_rate < 50.0 {
+            warn!(
+                "Very low fill rate in trade: only {:.1}% filled for {}",
+                fill_rate, token.symbol
+            );
+        }
+    }
+
+    let position = position_manager.create_position(
         &token.address,
+        &token.name,
+        &token.symbol,
         token_decimals,
+        &strategy.id,
+        position_size_sol, // Entry value in SOL
+        actual_out_amount, // Amount of token received
+        Some(swap_result.out_amount_ui), // Expected amount as a separate parameter
+        swap_result.price_impact_pct,
+        &swap_result.transaction_signature,
+        // Pass SL/TP/Trailing settings from strategy
+        strategy.stop_loss_percent,
+        strategy.take_profit_percent,
+        strategy.trailing_stop_percent,
+        strategy.peak_drawdown_alert_percent,
+        strategy.peak_drawdown_sell_percent,
+        Some(strategy.max_hold_time_minutes), // Wrap in Some()
+        strategy.force_close_at,
+        strategy.momentum_tp,
+        strategy.stop_loss_type.volatility(),
+        entry_tranches,
+        Some(&wallet_manager.get_public_key().to_string()),
+        strategy.swap_route(config.jito_tip_lamports),
+        Some(transfer_tax_percent),
+        strategy.notify_trades,
+    ).await.context("Failed to create position entry after successful swap confirmation")?;
+
+    info!(
+        "Position created for {} ({}) with {:.4} SOL entry value.",
+        token.name, token.symbol, position_size_sol
+    );
+
+    // Nothing to reconcile when the mint check or the swap itself already read the fill
+    if config.reconcile_buy_fills && !config.demo_mode && received.is_none() && swap_result.actual_out_amount_ui.is_none() {
+        reconcile_buy_fill(&position, &signature, wallet_manager, position_manager).await;
+    }
+
+    // TODO: Send notification (Telegram?)
+
+    Ok(swap_result) // Return original swap result on success
+}
+
+/// Sends the buy swap and waits for it to confirm
+#[allow(clippy::too_many_arguments)]
+async fn send_and_confirm_buy(
+    token: &TokenMetadata,
+    position_size_sol: f64,
+    slippage_bps: u32,
+    priority_fee: u64,
+    strategy: &Strategy,
+    jupiter_client: &JupiterClient,
+    wallet_manager: &WalletManager,
+    config: &Config,
+) -> Result<(SwapResult, Signature)> {
+    let swap_result = jupiter_client.swap_sol_to_token(
+        &token.address,
+        token.decimals,
         position_size_sol,
-        strategy.slippage_bps.unwrap_or(config.default_slippage_bps), // Use strategy slippage or default
-        strategy.priority_fee_micro_lamports.or(Some(config.default_priority_fee_micro_lamports)), // Use strategy priority fee or default
+        slippage_bps,
+        Some(priority_fee),
+        strategy.swap_route(config.jito_tip_lamports), // Strategy's venue, plus a Jito bundle if it asks for one
         wallet_manager.clone().into(), // Convert &WalletManager to Arc<WalletManager>
     ).await.context(format!("Failed to execute SOL to {} swap", token.symbol))?;
 
@@ -360,78 +1107,129 @@ async fn execute_buy_task(
         .context("Failed to parse buy transaction signature")?;
 
     // Use the SolanaClient from WalletManager to confirm
-    // TODO: Make confirmation timeout configurable
-    match wallet_manager.solana_client().confirm_transaction(&signature, solana_sdk::commitment_config::CommitmentLevel::Confirmed, 60).await { // Use getter method
+    match confirm_buy_transaction(&wallet_manager.solana_client(), &signature, config).await {
         Ok(_) => {
             info!("Buy transaction {} confirmed successfully.", signature);
+            Ok((swap_result, signature))
+        }
+        Err(e) => {
+            error!("Failed to confirm buy transaction {}: {:?}", signature, e);
+            // Don't create a position if confirmation fails
+            Err(e).context(format!("Buy transaction {} failed confirmation", signature))
+        }
+    }
+}
 
-            // --- Create Position Entry (Only after confirmation) ---
-            // TODO: Get actual out amount after confirmation if possible (requires parsing tx details)
-            let actual_out_amount = swap_result.actual_out_amount_ui.unwrap_or(swap_result.out_amount_ui); // Use estimate for now
-            
-            // Check fill rate - if it's too low, warn the user
-            let fill_rate = if swap_result.out_amount_ui > 0.0 {
-                (actual_out_amount / swap_result.out_amount_ui) * 100.0
-            } else {
-                100.0 // Default to 100% if expected is 0
-            };
-            
-            // Log warning if fill rate is low
-            if fill_rate < 95.0 {
-                warn!(
-                    "Low fill rate detected: Received {:.4} tokens ({:.1}% of expected {:.4})",
-                    actual_out_amount, fill_rate, swap_result.out_amount_ui
-                );
 
-                // TODO: Send notification via WebSocket when implemented
-                if fill_rate < 50.0 {
-                    warn!(
-                        "Very low fill rate in trade: only {:.1}% filled for {}",
-                        fill_rate, token.symbol
-                    );
-                }
-            }
+/// Name/symbol/decimals for manually traded tokens: Helius first, then Birdeye's
+/// overview, cached per mint once resolved
+#[derive(Clone)]
+pub struct TokenMetadataResolver {
+    helius_client: Arc<HeliusClient>,
+    risk_analyzer: Arc<RiskAnalyzer>,
+    config: Arc<Config>,
+    cache: Arc<RwLock<HashMap<String, TokenMetadata>>>,
+}
 
-            position_manager.create_position(
-                &token.address,
-                &token.name,
-                &token.symbol,
-                token_decimals,
-                &strategy.id,
-                position_size_sol, // Entry value in SOL
-                actual_out_amount, // Amount of token received
-                Some(swap_result.out_amount_ui), // Expected amount as a separate parameter
-                swap_result.price_impact_pct,
-                &swap_result.transaction_signature,
-                // Pass SL/TP/Trailing settings from strategy
-                strategy.stop_loss_percent,
-                strategy.take_profit_percent,
-                strategy.trailing_stop_percent,
-                Some(strategy.max_hold_time_minutes), // Wrap in Some()
-            ).await.context("Failed to create position entry after successful swap confirmation")?;
-
-            info!(
-                "Position created for {} ({}) with {:.4} SOL entry value.",
-                token.name, token.symbol, position_size_sol
-            );
+impl TokenMetadataResolver {
+    fn new(helius_client: Arc<HeliusClient>, risk_analyzer: Arc<RiskAnalyzer>, config: Arc<Config>) -> Self {
+        Self { helius_client, risk_analyzer, config, cache: Arc::new(RwLock::new(HashMap::new())) }
+    }
+
+    /// Gets token metadata for a given address.
+    /// Tries Helius, then Birdeye, and only falls back to a truncated-address
+    /// placeholder if neither returns a real name/symbol.
+    pub async fn resolve(&self, token_address: &str) -> Result<TokenMetadata> {
+        if let Some(cached) = self.cache.read().await.get(token_address) {
+            return Ok(cached.clone());
+        }
 
-            // TODO: Send notification (Telegram?)
+        let short_address: String = token_address.chars().take(6).collect();
+        let mut metadata = TokenMetadata {
+            address: token_address.to_string(),
+            name: format!("Token {}", short_address),
+            symbol: short_address.clone(),
+            decimals: 9,
+            supply: None,
+            logo_uri: None,
+            creation_time: None,
+            creator: None,
+        };
 
-            Ok(swap_result) // Return original swap result on success
+        if !self.config.enrich_token_metadata {
+            return Ok(metadata);
         }
-        Err(e) => {
-            error!("Failed to confirm buy transaction {}: {:?}", signature, e);
-            // Don't create a position if confirmation fails
-            Err(e).context(format!("Buy transaction {} failed confirmation", signature))
+
+        let mut resolved = false;
+        match self.helius_client.get_token_metadata(token_address).await {
+            // Helius fills in "Unknown Token"/"UNK" when the asset has no metadata
+            Ok(helius) if helius.symbol != "UNK" => {
+                metadata = helius;
+                resolved = true;
+            }
+            Ok(helius) => metadata.supply = helius.supply,
+            Err(e) => debug!("Helius metadata lookup failed for {}: {}", token_address, e),
+        }
+
+        if !resolved {
+            match self.risk_analyzer.price_cache().token_overview(token_address).await {
+                Ok(Some(overview)) if overview.symbol.is_some() => {
+                    metadata.symbol = overview.symbol.unwrap_or(metadata.symbol);
+                    metadata.name = overview.name.unwrap_or_else(|| metadata.symbol.clone());
+                    metadata.decimals = overview.decimals.unwrap_or(metadata.decimals);
+                    metadata.logo_uri = overview.logo_uri;
+                    resolved = true;
+                }
+                Ok(_) => {}
+                Err(e) => debug!("Birdeye metadata lookup failed for {}: {}", token_address, e),
+            }
+        }
+
+        if resolved {
+            self.cache.write().await.insert(token_address.to_string(), metadata.clone());
+        } else {
+            warn!("Could not resolve name/symbol for {}, using placeholder {}", token_address, short_address);
         }
+
+        Ok(metadata)
     }
 }
 
+/// What a manual buy preview needs, cloned out of the AutoTrader so the metadata
+/// lookup and Jupiter quote run without holding its lock
+pub struct ManualBuyPreviewer {
+    jupiter_client: Arc<JupiterClient>,
+    position_manager: Arc<PositionManager>,
+    token_metadata: TokenMetadataResolver,
+    slippage_bps: u32,
+    priority_fee: Option<u64>, // The default strategy's fixed fee, else estimated per token
+    fee_urgency: FeeUrgency,
+    default_priority_fee: u64,
+}
+
+impl ManualBuyPreviewer {
+    /// Quote a manual buy with the same slippage/priority fee `execute_manual_buy` would use
+    pub async fn preview(&self, token_address: &str, amount_sol: f64) -> Result<BuyPreview> {
+        let priority_fee = match self.priority_fee {
+            Some(fee) => fee,
+            None => self.position_manager.priority_fee(token_address, self.fee_urgency, self.default_priority_fee).await,
+        };
+        let token_metadata = self.token_metadata.resolve(token_address).await?;
+
+        self.jupiter_client.preview_buy(
+            token_address,
+            token_metadata.decimals,
+            amount_sol,
+            self.slippage_bps,
+            Some(priority_fee),
+        ).await
+    }
+}
 
 // Removed Clone derive, manual implementation was problematic
 // Removed Debug derive as SolanaClient doesn't implement it
 pub struct AutoTrader {
-    wallet_manager: Arc<WalletManager>,
+    wallet_pool: Arc<WalletPool>, // All trading wallets; buys rotate round-robin
     solana_client: Arc<SolanaClient>,
     helius_client: Arc<HeliusClient>,
     jupiter_client: Arc<JupiterClient>,
@@ -441,13 +1239,20 @@ pub struct AutoTrader {
     pub position_manager: Arc<PositionManager>, // Expose for references
     pub risk_analyzer: Arc<RiskAnalyzer>, // Expose for /analyze commands
     pub simulation_manager: Option<Arc<SimulationManager>>, // For DRY_RUN_MODE
+    pub escalation_manager: Arc<EscalationManager>, // Stuck-position / RPC-down incidents
+    sol_trend_filter: Arc<SolTrendFilter>, // Pauses buys while SOL is in a sharp downtrend
+    blocklist: Arc<Blocklist>, // Known-scam mints/creators from an external feed
+    pub dust_sweeper: Arc<DustSweeper>, // Periodic sale of leftover token dust
+    pub limit_orders: Arc<LimitOrderBook>, // Entries waiting for a target price
+    pub strategy_changelog: Arc<StrategyChangelog>, // Who changed which strategy field, and when
+    trading_hold: Arc<RwLock<Option<String>>>, // Why start() is refused (unconfirmed REAL mode)
     is_running: Arc<AtomicBool>,
     // notification_tx will be used for WebSocket broadcasts in future
     // notification_tx: Option<broadcast::Sender<WsMessage>>,
     strategies: Arc<RwLock<HashMap<String, Strategy>>>, // Use Arc<RwLock<..>> for shared mutable state
     running: Arc<RwLock<bool>>, // Use Arc<RwLock<..>>
     task_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
-    strategies_path: PathBuf,
+    store: Arc<dyn Store>, // Where strategies are persisted (JSON file or SQLite)
 
     // Pump.fun real-time discovery (for DRY_RUN_MODE)
     pumpfun_token_rx: Arc<Mutex<Option<mpsc::Receiver<PumpfunToken>>>>,
@@ -455,6 +1260,10 @@ pub struct AutoTrader {
     pumpfun_monitor: Arc<Mutex<Option<PumpfunMonitor>>>,
     graduation_monitor: Arc<Mutex<Option<GraduationMonitor>>>,
 
+    // Raydium new-pool stream (real-time discovery, REAL mode)
+    new_pool_rx: Arc<Mutex<Option<mpsc::Receiver<NewPool>>>>,
+    pool_monitor: Arc<Mutex<Option<PoolMonitor>>>,
+
     // Multi-strategy support (NewPairs, FinalStretch, Migrated)
     active_strategy_type: Arc<RwLock<crate::trading::strategy::StrategyType>>,
     watchlist: Arc<crate::trading::watchlist::Watchlist>,
@@ -462,23 +1271,40 @@ pub struct AutoTrader {
 
     // Telegram sniper signal receiver (for TelegramCall strategy)
     tg_signal_rx: Arc<Mutex<Option<mpsc::Receiver<CallSignal>>>>,
+
+    // Resolved name/symbol for manually traded tokens
+    token_metadata: TokenMetadataResolver,
+
+    // Helius scan cadence/lookback, and a wake-up for the scan loop when they change
+    scan_settings: Arc<RwLock<ScanSettings>>,
+    scan_settings_changed: Arc<Notify>,
 }
 
 impl AutoTrader {
     // FIXED VERSION: Changed to async to avoid block_on issues
     pub async fn new(
-        wallet_manager: Arc<WalletManager>,
+        wallet_pool: Arc<WalletPool>,
         solana_client: Arc<SolanaClient>,
         config: Arc<Config>, // Keep Arc<Config>
     ) -> Result<Self> { // Return Result<Self>
+        let wallet_manager = wallet_pool.primary();
         // Initialize clients and analyzers potentially shared via Arc
-        let helius_client = Arc::new(HeliusClient::new(&config.helius_api_key));
-        let jupiter_client = Arc::new(JupiterClient::new(config.jupiter_api_key.clone())); // Clone Option<String>
+        let api_backoff = Arc::new(RateLimitBackoff::new(config.rate_limit_backoff_base_secs, config.rate_limit_backoff_max_secs));
+        let helius_client = Arc::new(HeliusClient::new(&config.helius_api_key).with_rate_limit_backoff(api_backoff.clone()));
+        let jupiter_client = Arc::new(
+            JupiterClient::new(config.jupiter_api_key.clone(), config.quote_max_age_ms) // Clone Option<String>
+                .with_swap_limits(config.max_concurrent_swaps, config.max_concurrent_exit_swaps)
+                .with_bonding_curve_trading(config.pumpfun_curve_trading)
+                .with_jito(Arc::new(JitoClient::new(&config.jito_block_engine_url)))
+                .with_swap_breaker(Arc::new(SwapCircuitBreaker::new(
+                    config.swap_breaker_failures, config.swap_breaker_window_secs, config.swap_breaker_cooldown_secs,
+                ))),
+        );
 
         // Initialize BirdeyeClient - require the API key for now
         let birdeye_api_key = config.birdeye_api_key.as_ref()
             .context("BIRDEYE_API_KEY is required but missing in config")?;
-        let birdeye_client = Arc::new(BirdeyeClient::new(birdeye_api_key));
+        let birdeye_client = Arc::new(BirdeyeClient::new(birdeye_api_key).with_rate_limit_backoff(api_backoff));
 
         // Initialize MoralisClient if API key is available
         let moralis_client = config.moralis_api_key.as_ref().map(|key| {
@@ -495,13 +1321,33 @@ impl AutoTrader {
             jupiter_client.clone(),
             birdeye_client.clone(), // Pass BirdeyeClient
             wallet_manager.clone(), // Pass WalletManager to RiskAnalyzer::new
-        ));
+        ).with_onchain_liquidity_fallback(config.onchain_liquidity_fallback)
+        .with_deployer_reputation(config.deployer_check_enabled, config.deployer_check_launches)
+        .with_metadata_check(config.metadata_check_enabled)
+        .with_price_cache(Arc::new(PriceCache::new(
+            jupiter_client.clone(),
+            birdeye_client.clone(),
+            Duration::from_millis(config.price_cache_ttl_ms),
+        ))));
+        let escalation_manager = Arc::new(EscalationManager::new(config.clone()));
+        let sol_trend_filter = Arc::new(SolTrendFilter::new(birdeye_client.clone(), config.clone()));
+        let blocklist = Arc::new(Blocklist::new(config.clone()));
+        let store = crate::storage::open(&config).await?;
         let position_manager = Arc::new(PositionManager::new(
-            wallet_manager.clone(),
+            wallet_pool.clone(),
             jupiter_client.clone(),
             solana_client.clone(),
             config.clone(),
+            escalation_manager.clone(),
+            risk_analyzer.clone(),
+            store.clone(),
         )); // Corrected syntax: Ensure this parenthesis closes Arc::new
+        let dust_sweeper = Arc::new(DustSweeper::new(
+            wallet_pool.clone(),
+            jupiter_client.clone(),
+            position_manager.clone(),
+            config.clone(),
+        ));
 
         // Initialize SimulationManager if dry_run_mode is enabled
         let simulation_manager = if config.dry_run_mode {
@@ -516,8 +1362,15 @@ impl AutoTrader {
             None
         };
 
-        // Set the default path for strategy persistence
-        let strategies_path = PathBuf::from("data/strategies.json");
+        let limit_orders = Arc::new(LimitOrderBook::new(store.clone()));
+        if let Err(e) = limit_orders.load().await {
+            warn!("Failed to load limit orders: {}", e);
+        }
+
+        let strategy_changelog = Arc::new(StrategyChangelog::new(config.strategy_changelog_max_entries));
+        if let Err(e) = strategy_changelog.load().await {
+            warn!("Failed to load strategy changelog: {}", e);
+        }
 
         // Initialize watchlist and load existing tokens
         let watchlist = Arc::new(crate::trading::watchlist::Watchlist::new());
@@ -526,8 +1379,9 @@ impl AutoTrader {
         }
 
         // Create AutoTrader instance
+        let token_metadata = TokenMetadataResolver::new(helius_client.clone(), risk_analyzer.clone(), config.clone());
         let autotrader = Self {
-            wallet_manager,
+            wallet_pool,
             solana_client: solana_client.clone(),
             helius_client,
             jupiter_client,
@@ -537,22 +1391,37 @@ impl AutoTrader {
             position_manager,
             risk_analyzer,
             simulation_manager,
+            escalation_manager,
+            sol_trend_filter,
+            blocklist,
+            dust_sweeper,
+            limit_orders,
+            strategy_changelog,
+            trading_hold: Arc::new(RwLock::new(None)),
             is_running: Arc::new(AtomicBool::new(false)),
             strategies: Arc::new(RwLock::new(HashMap::new())), // Start with empty map, will load in init
             running: Arc::new(RwLock::new(false)),
             task_handle: Arc::new(Mutex::new(None)),
-            strategies_path,
+            store,
             // Pump.fun discovery initialized to None - will be set up in init_pumpfun_discovery()
             pumpfun_token_rx: Arc::new(Mutex::new(None)),
             graduation_rx: Arc::new(Mutex::new(None)),
             pumpfun_monitor: Arc::new(Mutex::new(None)),
             graduation_monitor: Arc::new(Mutex::new(None)),
+            new_pool_rx: Arc::new(Mutex::new(None)),
+            pool_monitor: Arc::new(Mutex::new(None)),
             // Multi-strategy support
             active_strategy_type: Arc::new(RwLock::new(crate::trading::strategy::StrategyType::NewPairs)),
             watchlist,
             scanner: Arc::new(Mutex::new(None)), // Scanner initialized in start() when needed
             // Telegram sniper signal receiver — injected later by main.rs
             tg_signal_rx: Arc::new(Mutex::new(None)),
+            token_metadata,
+            scan_settings: Arc::new(RwLock::new(ScanSettings {
+                scan_interval_secs: config.scan_interval_secs,
+                token_age_minutes: config.scan_token_age_minutes,
+            })),
+            scan_settings_changed: Arc::new(Notify::new()),
         };
         
         // Initialize by loading strategies - use await directly since we're in an async function
@@ -570,35 +1439,18 @@ impl AutoTrader {
 
     // --- Strategy Management ---
     
-    /// Loads strategies from disk
+    /// Loads strategies from the store
     async fn load_strategies(&self) -> Result<()> {
-        info!("Loading strategies from {:?}", self.strategies_path);
-        
-        let loaded_strategies = if self.strategies_path.exists() {
-            match tokio::fs::read_to_string(&self.strategies_path).await {
-                Ok(data) => {
-                    if data.is_empty() {
-                        HashMap::new()
-                    } else {
-                        match serde_json::from_str::<HashMap<String, Strategy>>(&data) {
-                            Ok(strategies) => strategies,
-                            Err(e) => {
-                                error!("Failed to parse strategies file: {}", e);
-                                HashMap::new()
-                            }
-                        }
-                    }
-                },
-                Err(e) => {
-                    error!("Failed to read strategies file: {}", e);
-                    HashMap::new()
-                }
+        info!("Loading strategies from {}", self.store.describe());
+
+        let loaded_strategies = match self.store.load_strategies().await {
+            Ok(strategies) => strategies,
+            Err(e) => {
+                error!("Failed to load strategies: {:#}", e);
+                HashMap::new()
             }
-        } else {
-            // File doesn't exist yet
-            HashMap::new()
         };
-        
+
         // Update the in-memory HashMap
         let mut strategies = self.strategies.write().await;
         *strategies = loaded_strategies;
@@ -675,34 +1527,16 @@ impl AutoTrader {
             "finalstretch" | "final_stretch" | "bonding" => StrategyType::FinalStretch,
             "migrated" | "graduated" => StrategyType::Migrated,
             "telegramcall" | "telegram_call" | "telegram" => StrategyType::TelegramCall,
+            "pumpfunbondingcurve" | "pumpfun_bonding_curve" | "bonding_curve" | "curve" => StrategyType::PumpfunBondingCurve,
             _ => StrategyType::FinalStretch,
         }
     }
     
-    /// Saves strategies to disk
+    /// Saves strategies to the store
     async fn save_strategies(&self) -> Result<()> {
-        debug!("Saving strategies to {:?}", self.strategies_path);
-        
-        // Get the current strategies
         let strategies = self.strategies.read().await;
-        
-        // Ensure directory exists
-        if let Some(parent) = self.strategies_path.parent() {
-            if !parent.exists() {
-                tokio::fs::create_dir_all(parent).await
-                    .context("Failed to create directory for strategies file")?;
-            }
-        }
-        
-        // Serialize to JSON
-        let json = serde_json::to_string_pretty(&*strategies)
-            .context("Failed to serialize strategies")?;
-        
-        // Write to file
-        tokio::fs::write(&self.strategies_path, json).await
-            .context("Failed to write strategies file")?;
-        
-        debug!("Saved {} strategies to disk", strategies.len());
+        self.store.save_strategies(&strategies).await?;
+        debug!("Saved {} strategies to {}", strategies.len(), self.store.describe());
         Ok(())
     }
 
@@ -725,8 +1559,8 @@ impl AutoTrader {
         Ok(())
     }
     
-    /// Updates an existing strategy
-    pub async fn update_strategy(&self, strategy: Strategy) -> Result<()> {
+    /// Updates an existing strategy, recording the changed fields under `actor`
+    pub async fn update_strategy(&self, strategy: Strategy, actor: &str) -> Result<()> {
         // Validate the strategy first
         if let Err(validation_error) = strategy.validate() {
             return Err(anyhow!("Invalid strategy: {}", validation_error));
@@ -734,35 +1568,39 @@ impl AutoTrader {
         
         // Check if the strategy exists before updating
         let mut strategies = self.strategies.write().await;
-        if !strategies.contains_key(&strategy.id) {
+        let Some(previous) = strategies.get(&strategy.id).cloned() else {
             return Err(anyhow!("Strategy with ID {} not found", strategy.id));
-        }
+        };
         
         // Update the strategy
         info!("Updating strategy: {} ({})", strategy.name, strategy.id);
-        strategies.insert(strategy.id.clone(), strategy);
+        strategies.insert(strategy.id.clone(), strategy.clone());
         drop(strategies); // Release lock before saving
         
         // Save strategies to disk
         self.save_strategies().await?;
+        self.strategy_changelog.record(&previous, &strategy, actor, "update").await;
         
         Ok(())
     }
     
-    /// Toggles a strategy's enabled state
-    pub async fn toggle_strategy(&self, strategy_id: &str) -> Result<bool> {
+    /// Toggles a strategy's enabled state, recording the change under `actor`
+    pub async fn toggle_strategy(&self, strategy_id: &str, actor: &str) -> Result<bool> {
         // Get the strategy
         let mut strategies = self.strategies.write().await;
         let strategy = strategies.get_mut(strategy_id)
             .ok_or_else(|| anyhow!("Strategy not found: {}", strategy_id))?;
+        let previous = strategy.clone();
         
         // Toggle the enabled flag
         strategy.enabled = !strategy.enabled;
         let new_status = strategy.enabled;
+        let toggled = strategy.clone();
         drop(strategies);
         
         // Save changes to disk
         self.save_strategies().await?;
+        self.strategy_changelog.record(&previous, &toggled, actor, "toggle").await;
         
         info!("Strategy {} {} status: {}", strategy_id, 
             if new_status { "enabled" } else { "disabled" },
@@ -797,6 +1635,51 @@ impl AutoTrader {
         strategies.values().cloned().collect()
     }
 
+    /// Replaces every strategy with `strategies` (state import). Nothing changes
+    /// unless all of them validate.
+    pub async fn replace_strategies(&self, strategies: Vec<Strategy>) -> Result<()> {
+        for strategy in &strategies {
+            if let Err(validation_error) = strategy.validate() {
+                return Err(anyhow!("Invalid strategy {}: {}", strategy.id, validation_error));
+            }
+        }
+
+        let mut current = self.strategies.write().await;
+        *current = strategies.into_iter().map(|s| (s.id.clone(), s)).collect();
+        info!("Replaced strategies with {} imported strategies", current.len());
+        drop(current); // Release lock before saving
+
+        self.save_strategies().await
+    }
+
+    // --- Scan Settings ---
+
+    /// Global scan settings (before strategy overrides)
+    pub async fn scan_settings(&self) -> ScanSettings {
+        *self.scan_settings.read().await
+    }
+
+    /// Change the scan interval and/or lookback without a restart. A running scan
+    /// loop picks the new interval up immediately.
+    pub async fn update_scan_settings(&self, scan_interval_secs: Option<u64>, token_age_minutes: Option<u64>) -> Result<ScanSettings> {
+        if scan_interval_secs == Some(0) || token_age_minutes == Some(0) {
+            return Err(anyhow!("Scan interval and token age must be greater than 0"));
+        }
+        let updated = {
+            let mut settings = self.scan_settings.write().await;
+            if let Some(secs) = scan_interval_secs {
+                settings.scan_interval_secs = secs;
+            }
+            if let Some(minutes) = token_age_minutes {
+                settings.token_age_minutes = minutes;
+            }
+            *settings
+        };
+        info!("Scan settings updated: every {}s, tokens up to {}m old", updated.scan_interval_secs, updated.token_age_minutes);
+        self.scan_settings_changed.notify_one();
+        Ok(updated)
+    }
+
     // --- Active Strategy Type Management ---
 
     /// Get the currently active strategy type
@@ -854,6 +1737,11 @@ impl AutoTrader {
 
     // Changed to take &self
     pub async fn start(&self) -> Result<()> {
+        if let Some(reason) = self.trading_hold.read().await.as_ref() {
+            warn!("AutoTrader start refused: {}", reason);
+            return Err(anyhow!("AutoTrader start refused: {}", reason));
+        }
+
         // Check if already running *before* acquiring write lock if possible
         if *self.running.read().await {
              warn!("AutoTrader start requested but already running.");
@@ -871,6 +1759,9 @@ impl AutoTrader {
         // Ensure PositionManager::start_monitoring takes &self or Arc<Self> appropriately
         // Assuming it takes Arc<Self> based on previous implementation attempt
         self.position_manager.clone().start_monitoring().await?;
+        self.blocklist.clone().spawn_refresh();
+        self.dust_sweeper.clone().spawn();
+        self.spawn_limit_order_monitor();
 
         // Initialize and start Pump.fun discovery ONLY for NewPairs strategy in dry run mode
         // FinalStretch and Migrated use the Moralis scanner instead
@@ -884,6 +1775,12 @@ impl AutoTrader {
             }
         } else if self.config.dry_run_mode {
             info!("📡 [DRY RUN] Strategy is {:?} - skipping Pump.fun WebSocket, using Moralis scanner", current_strategy);
+        } else if self.config.realtime_discovery_enabled && !self.config.demo_mode
+            && current_strategy == crate::trading::strategy::StrategyType::NewPairs
+        {
+            if let Err(e) = self.init_realtime_discovery().await {
+                warn!("Failed to start real-time discovery, using Helius polling only: {:?}", e);
+            }
         }
 
         // Set running flag to true
@@ -900,19 +1797,25 @@ impl AutoTrader {
         let risk_analyzer = self.risk_analyzer.clone();
         let position_manager = self.position_manager.clone();
         let config = self.config.clone();
-        let wallet_manager = self.wallet_manager.clone();
+        let wallet_pool = self.wallet_pool.clone();
         let jupiter_client = self.jupiter_client.clone();
         let simulation_manager = self.simulation_manager.clone();
+        let sol_trend_filter = self.sol_trend_filter.clone();
+        let blocklist = self.blocklist.clone();
         let moralis_client = self.moralis_client.clone();
+        let limit_orders = self.limit_orders.clone();
+        let scan_settings = self.scan_settings.clone();
+        let scan_settings_changed = self.scan_settings_changed.clone();
 
 
-        // Take the Pump.fun token receiver for use in the task (if in dry run mode)
-        let pumpfun_token_rx = if config.dry_run_mode {
+        // Take the Pump.fun token receiver for use in the task (dry run, or real-time discovery)
+        let pumpfun_token_rx = {
             let mut rx_guard = self.pumpfun_token_rx.lock().await;
             rx_guard.take()
-        } else {
-            None
         };
+        let realtime_monitor = self.pumpfun_monitor.clone();
+        let new_pool_rx = self.new_pool_rx.lock().await.take();
+        let pool_monitor = self.pool_monitor.clone();
 
         // Take the Telegram signal receiver if present
         let tg_signal_rx = {
@@ -931,7 +1834,8 @@ impl AutoTrader {
 
         let handle = tokio::spawn(async move {
             // Main scanning loop
-            let mut scan_interval = interval(Duration::from_secs(60)); // Scan every 60 seconds
+            let initial_scan = effective_scan_settings(&scan_settings, &strategies).await;
+            let mut scan_interval = interval(Duration::from_secs(initial_scan.scan_interval_secs));
             let mut moralis_scan_interval = interval(Duration::from_secs(30)); // Moralis scan every 30 seconds (reduced from 15 to avoid Birdeye rate limits)
             let mut price_update_counter: u32 = 0;
 
@@ -956,6 +1860,7 @@ impl AutoTrader {
 
             // Wrap the receiver in an Option so we can use it in the select!
             let mut token_rx = pumpfun_token_rx;
+            let mut pool_rx = new_pool_rx;
             let mut tg_rx = tg_signal_rx;
 
             loop {
@@ -979,6 +1884,35 @@ impl AutoTrader {
                         if let Some(token) = token {
                             info!("📥 Received token from WebSocket channel: {} ({})", token.symbol, token.mint);
 
+                            // REAL MODE: straight into the buy pipeline
+                            if !config.dry_run_mode {
+                                let token_meta = TokenMetadata {
+                                    address: token.mint.clone(),
+                                    name: token.name.clone(),
+                                    symbol: token.symbol.clone(),
+                                    decimals: crate::trading::pumpfun::DEFAULT_DECIMALS,
+                                    supply: None,
+                                    logo_uri: None,
+                                    creation_time: chrono::DateTime::from_timestamp(token.discovered_at, 0),
+                                    creator: Some(token.creator.clone()),
+                                };
+                                let (strategies, risk_analyzer, position_manager, config, wallet_pool, jupiter_client, sol_trend_filter, blocklist, limit_orders) = (
+                                    strategies.clone(), risk_analyzer.clone(), position_manager.clone(), config.clone(),
+                                    wallet_pool.clone(), jupiter_client.clone(), sol_trend_filter.clone(), blocklist.clone(),
+                                    limit_orders.clone(),
+                                );
+                                tokio::spawn(async move {
+                                    let symbol = token_meta.symbol.clone();
+                                    if let Err(e) = process_realtime_token(
+                                        token_meta, strategies, risk_analyzer, position_manager,
+                                        config, wallet_pool, jupiter_client, sol_trend_filter, blocklist, limit_orders,
+                                    ).await {
+                                        warn!("⚡ [REALTIME] Failed to process {}: {:?}", symbol, e);
+                                    }
+                                });
+                                continue;
+                            }
+
                             // Check active strategy type to determine if we should evaluate for trading
                             let current_strategy_type = active_strategy_type.read().await.clone();
                             let evaluate_for_trading = current_strategy_type == crate::trading::strategy::StrategyType::NewPairs;
@@ -1019,6 +1953,55 @@ impl AutoTrader {
                         }
                     }
 
+                    // New Raydium pool from the real-time stream (REAL mode)
+                    pool = async {
+                        if let Some(ref mut rx) = pool_rx {
+                            rx.recv().await
+                        } else {
+                            std::future::pending::<Option<NewPool>>().await
+                        }
+                    } => {
+                        let Some(pool) = pool else {
+                            warn!("Pool channel closed - no more new pools will be received");
+                            pool_rx = None;
+                            continue;
+                        };
+                        let (strategies, helius_client, risk_analyzer, position_manager, config, wallet_pool, jupiter_client, sol_trend_filter, blocklist, limit_orders) = (
+                            strategies.clone(), helius_client.clone(), risk_analyzer.clone(), position_manager.clone(), config.clone(),
+                            wallet_pool.clone(), jupiter_client.clone(), sol_trend_filter.clone(), blocklist.clone(),
+                            limit_orders.clone(),
+                        );
+                        tokio::spawn(async move {
+                            let mut token_meta = helius_client.get_token_metadata(&pool.token_mint).await.unwrap_or_else(|e| {
+                                debug!("No metadata yet for new pool token {}: {:?}", pool.token_mint, e);
+                                let short: String = pool.token_mint.chars().take(6).collect();
+                                TokenMetadata {
+                                    address: pool.token_mint.clone(),
+                                    name: format!("Unknown ({})", short),
+                                    symbol: short,
+                                    decimals: 9,
+                                    supply: None,
+                                    logo_uri: None,
+                                    creation_time: None,
+                                    creator: None,
+                                }
+                            });
+                            if let Some(decimals) = pool.token_decimals {
+                                token_meta.decimals = decimals;
+                            }
+                            token_meta.creation_time = token_meta.creation_time.or(Some(pool.discovered_at));
+                            info!("📥 New {:?} pool {} for {} ({}) in tx {}", pool.program, pool.pool, token_meta.symbol, pool.token_mint, pool.signature);
+
+                            let symbol = token_meta.symbol.clone();
+                            if let Err(e) = process_realtime_token(
+                                token_meta, strategies, risk_analyzer, position_manager,
+                                config, wallet_pool, jupiter_client, sol_trend_filter, blocklist, limit_orders,
+                            ).await {
+                                warn!("⚡ [REALTIME] Failed to process pool token {}: {:?}", symbol, e);
+                            }
+                        });
+                    }
+
                     // Telegram call signal (TelegramCall strategy only)
                     signal = async {
                         if let Some(ref mut rx) = tg_rx {
@@ -1042,11 +2025,19 @@ impl AutoTrader {
                                 .unwrap_or_else(|| crate::trading::strategy::Strategy::telegram_call("default-tg"));
                             drop(strats);
 
+                            // Calls carry no creator; only the mint lists can be checked without delaying the snipe
+                            if let Some(reason) = blocklist.block_reason(&signal.mint, None)
+                                .or_else(|| strategy.token_lists.rejection_reason(&signal.mint, None))
+                            {
+                                info!("⛔ Ignoring TG call for {}: {}", signal.mint, reason);
+                                continue;
+                            }
+
                             // Build a one-shot Sniper and run the snipe inline (spawned).
                             let sniper = std::sync::Arc::new(Sniper::new(
                                 config.clone(),
                                 jupiter_client.clone(),
-                                wallet_manager.clone(),
+                                wallet_pool.next_wallet(),
                                 position_manager.clone(),
                                 strategy,
                             ));
@@ -1059,13 +2050,42 @@ impl AutoTrader {
                         }
                     }
 
+                    // Scan interval changed through the API: reschedule from now
+                    _ = scan_settings_changed.notified() => {
+                        let period = Duration::from_secs(effective_scan_settings(&scan_settings, &strategies).await.scan_interval_secs);
+                        if period != scan_interval.period() {
+                            scan_interval = interval_at(tokio::time::Instant::now() + period, period);
+                        }
+                    }
+
                     // Regular scan cycle timer (Helius DAS - only for NewPairs strategy)
                     _ = scan_interval.tick() => {
                         let current_strategy_for_scan = active_strategy_type.read().await.clone();
+                        let scan = effective_scan_settings(&scan_settings, &strategies).await;
+                        // Strategy overrides change with strategy edits; follow them from the next tick
+                        let period = Duration::from_secs(scan.scan_interval_secs);
+                        if period != scan_interval.period() {
+                            debug!("Scan interval now {}s", scan.scan_interval_secs);
+                            scan_interval = interval_at(tokio::time::Instant::now() + period, period);
+                        }
+
+                        // Real-time discovery replaces polling while its stream is up;
+                        // if it has given up reconnecting, fall back to the Helius scan
+                        let realtime_active = match realtime_monitor.lock().await.as_ref() {
+                            Some(monitor) if !config.dry_run_mode => monitor.is_running().await,
+                            _ => false,
+                        } || match pool_monitor.lock().await.as_ref() {
+                            Some(monitor) if !config.dry_run_mode => monitor.is_running().await,
+                            _ => false,
+                        };
 
                         // Only run Helius DAS scan for NewPairs strategy and when not in dry_run mode
                         // FinalStretch and Migrated use the Moralis scanner (separate timer below)
-                        if !config.dry_run_mode && current_strategy_for_scan == crate::trading::strategy::StrategyType::NewPairs {
+                        if realtime_active {
+                            debug!("Skipping Helius scan - real-time discovery is active");
+                        } else if let Some(wait) = risk_analyzer.rate_limit_backoff().remaining() {
+                            info!("⏳ Analysis APIs rate limited, backing off; skipping scan ({}s left)", wait.as_secs());
+                        } else if !config.dry_run_mode && current_strategy_for_scan == crate::trading::strategy::StrategyType::NewPairs {
                             // Run the regular scan cycle (uses Helius DAS for new token discovery)
                             if let Err(e) = run_scan_cycle(
                                 strategies.clone(),
@@ -1073,9 +2093,13 @@ impl AutoTrader {
                                 risk_analyzer.clone(),
                                 position_manager.clone(),
                                 config.clone(),
-                                wallet_manager.clone(),
+                                wallet_pool.clone(),
                                 jupiter_client.clone(),
                                 simulation_manager.clone(),
+                                sol_trend_filter.clone(),
+                                blocklist.clone(),
+                                limit_orders.clone(),
+                                scan.token_age_minutes,
                             ).await {
                                 error!("Error in scan cycle: {:?}", e);
                                 // Continue running even if one cycle fails
@@ -1129,6 +2153,7 @@ impl AutoTrader {
                         if let Some(ref sc) = scanner {
                             match current_strategy_type {
                                 crate::trading::strategy::StrategyType::FinalStretch |
+                                crate::trading::strategy::StrategyType::PumpfunBondingCurve |
                                 crate::trading::strategy::StrategyType::Migrated => {
                                     // Get strategy for scanning
                                     let strats = strategies.read().await;
@@ -1153,6 +2178,13 @@ impl AutoTrader {
 
                                                     // Process each candidate
                                                     for candidate in candidates {
+                                                        if let Some(reason) = blocklist.block_reason(&candidate.token_address, None)
+                                                            .or_else(|| strategy.token_lists.rejection_reason(&candidate.token_address, None))
+                                                        {
+                                                            info!("⛔ Skipping scanner candidate {}: {}", candidate.symbol, reason);
+                                                            continue;
+                                                        }
+
                                                         // Convert USD price to SOL price for accurate simulation
                                                         let price_sol = if sol_price_usd > 0.0 {
                                                             candidate.price_usd / sol_price_usd
@@ -1165,8 +2197,10 @@ impl AutoTrader {
                                                             if let Some(ref sim_mgr) = simulation_manager {
                                                                 if !sim_mgr.has_open_position(&candidate.token_address).await {
                                                                     let entry_reason = match current_strategy_type {
-                                                                        crate::trading::strategy::StrategyType::FinalStretch =>
-                                                                            format!("Final Stretch: Progress {:.1}%, MCap ${:.0}, Holders {}",
+                                                                        crate::trading::strategy::StrategyType::FinalStretch |
+                                                                        crate::trading::strategy::StrategyType::PumpfunBondingCurve =>
+                                                                            format!("{}: Progress {:.1}%, MCap ${:.0}, Holders {}",
+                                                                                current_strategy_type.display_name(),
                                                                                 candidate.bonding_progress.unwrap_or(0.0),
                                                                                 candidate.market_cap_usd,
                                                                                 candidate.holders),
@@ -1203,6 +2237,7 @@ impl AutoTrader {
                                                                 supply: None,
                                                                 logo_uri: None,
                                                                 creation_time: None,
+                                                                creator: None,
                                                             };
 
                                                             match should_execute_buy_task(&token_meta, &strategy, &position_manager).await {
@@ -1210,12 +2245,12 @@ impl AutoTrader {
                                                                     info!("🚀 [LIVE] Executing {:?} buy for {} ({}) - MCap ${:.0}, Holders {}",
                                                                         current_strategy_type, candidate.symbol, candidate.token_address,
                                                                         candidate.market_cap_usd, candidate.holders);
-                                                                    match execute_buy_task(
+                                                                    match execute_buy_with_entry_retry(
                                                                         &token_meta,
                                                                         &strategy,
                                                                         &position_manager,
                                                                         &jupiter_client,
-                                                                        &wallet_manager,
+                                                                        &wallet_pool.next_wallet(),
                                                                         &config,
                                                                         None,
                                                                     ).await {
@@ -1252,27 +2287,46 @@ impl AutoTrader {
                                             max_position_size_sol: 0.1,
                                             total_budget_sol: 1.0,
                                             stop_loss_percent: Some(20),
+                                            stop_loss_type: StopLossType::FixedPercent,
                                             take_profit_percent: Some(50),
                                             trailing_stop_percent: Some(10),
+                                            peak_drawdown_alert_percent: None,
+                                            peak_drawdown_sell_percent: None,
                                             max_hold_time_minutes: 60,
+                                            force_close_at: None,
+                                            momentum_tp: None,
+                                            scale_in: None,
+                                            dca: None,
+                                            scan_interval_secs: None,
+                                            scan_token_age_minutes: None,
+                                            limit_entry: None,
+                                            notify_trades: TradeNotify::Both,
                                             min_liquidity_sol: 1,
                                             max_risk_level: 70,
-                                            min_holders: if current_strategy_type == crate::trading::strategy::StrategyType::FinalStretch { 50 } else { 75 },
-                                            max_token_age_minutes: if current_strategy_type == crate::trading::strategy::StrategyType::FinalStretch { 60 } else { 1440 },
+                                            min_holders: if current_strategy_type.scans_bonding_tokens() { 50 } else { 75 },
+                                            max_token_age_minutes: if current_strategy_type.scans_bonding_tokens() { 60 } else { 1440 },
+                                            max_token_age_seconds: None,
                                             require_lp_burned: current_strategy_type == crate::trading::strategy::StrategyType::Migrated,
                                             reject_if_mint_authority: true,
                                             reject_if_freeze_authority: true,
                                             require_can_sell: true,
                                             max_transfer_tax_percent: Some(5.0),
                                             max_concentration_percent: Some(40.0),
-                                            min_volume_usd: if current_strategy_type == crate::trading::strategy::StrategyType::FinalStretch { Some(15_000.0) } else { Some(40_000.0) },
-                                            min_market_cap_usd: if current_strategy_type == crate::trading::strategy::StrategyType::FinalStretch { Some(15_000.0) } else { Some(40_000.0) },
-                                            min_bonding_progress: if current_strategy_type == crate::trading::strategy::StrategyType::FinalStretch { Some(20.0) } else { None },
+                                            min_volume_usd: if current_strategy_type.scans_bonding_tokens() { Some(15_000.0) } else { Some(40_000.0) },
+                                            min_market_cap_usd: if current_strategy_type.scans_bonding_tokens() { Some(15_000.0) } else { Some(40_000.0) },
+                                            min_bonding_progress: if current_strategy_type.scans_bonding_tokens() { Some(20.0) } else { None },
                                             require_migrated: if current_strategy_type == crate::trading::strategy::StrategyType::Migrated { Some(true) } else { None },
                                             min_buy_ratio_percent: 55.0,
                                             min_unique_wallets_24h: Some(20),
+                                            token_lists: TokenLists::default(),
                                             slippage_bps: None,
                                             priority_fee_micro_lamports: None,
+                                            fee_urgency: FeeUrgency::Medium,
+                                            use_jito: false,
+                                            jito_tip_lamports: None,
+                                            execution_venue: ExecutionVenue::Jupiter,
+                                            entry_retry_attempts: 0,
+                                            entry_retry_delay_ms: DEFAULT_ENTRY_RETRY_DELAY_MS,
                                             created_at: chrono::Utc::now(),
                                             updated_at: chrono::Utc::now(),
                                         };
@@ -1348,11 +2402,16 @@ impl AutoTrader {
         drop(running_guard);
 
         // Stop Pump.fun monitors if running
-        if self.config.dry_run_mode {
+        if self.config.dry_run_mode || self.config.realtime_discovery_enabled {
             if let Err(e) = self.stop_pumpfun_discovery().await {
                 warn!("Error stopping Pump.fun discovery: {:?}", e);
             }
         }
+        if let Some(monitor) = self.pool_monitor.lock().await.take() {
+            if let Err(e) = monitor.stop().await {
+                warn!("Error stopping pool discovery: {:?}", e);
+            }
+        }
 
         // Wait for the task to finish
         let mut task_handle_guard = self.task_handle.lock().await;
@@ -1368,10 +2427,68 @@ impl AutoTrader {
         Ok(())
     }
 
+    /// Swap failures since startup, counted per category
+    pub fn swap_failure_counts(&self) -> Vec<(SwapError, u64)> {
+        self.jupiter_client.failure_stats().snapshot()
+    }
+
+    /// The breaker that pauses buys after consecutive swap failures
+    pub fn swap_breaker(&self) -> Arc<SwapCircuitBreaker> {
+        self.jupiter_client.swap_breaker().clone()
+    }
+
+    pub fn swap_breaker_status(&self) -> SwapBreakerStatus {
+        self.jupiter_client.swap_breaker().status()
+    }
+
+    /// Refuse to start trading, with `reason` reported to every start attempt
+    pub async fn hold_trading(&self, reason: String) {
+        *self.trading_hold.write().await = Some(reason);
+    }
+
     pub async fn get_status(&self) -> bool {
         *self.running.read().await
     }
 
+    /// Manual buy settings and clients for quoting a manual buy without the AutoTrader lock
+    pub async fn manual_buy_previewer(&self) -> ManualBuyPreviewer {
+        let strategies = self.strategies.read().await;
+        let default_strategy = strategies.values().find(|s| s.name.to_lowercase() == "default");
+        ManualBuyPreviewer {
+            jupiter_client: self.jupiter_client.clone(),
+            position_manager: self.position_manager.clone(),
+            token_metadata: self.token_metadata.clone(),
+            slippage_bps: default_strategy.and_then(|s| s.slippage_bps).unwrap_or(self.config.live().default_slippage_bps),
+            priority_fee: default_strategy.and_then(|s| s.priority_fee_micro_lamports),
+            fee_urgency: default_strategy.map(|s| s.fee_urgency).unwrap_or_default(),
+            default_priority_fee: self.config.live().default_priority_fee_micro_lamports,
+        }
+    }
+
+    /// Slippage for manual buys: the "default" strategy's, else the global default
+    pub async fn manual_slippage_bps(&self) -> u32 {
+        let strategies = self.strategies.read().await;
+        strategies.values()
+            .find(|s| s.name.to_lowercase() == "default")
+            .and_then(|s| s.slippage_bps)
+            .unwrap_or(self.config.live().default_slippage_bps)
+    }
+
+    /// Store positions and strategies are persisted to, shared with copy trading
+    pub fn store(&self) -> Arc<dyn Store> {
+        self.store.clone()
+    }
+
+    /// Shared Jupiter client, for handlers that quote without holding the AutoTrader lock
+    pub fn jupiter_client(&self) -> Arc<JupiterClient> {
+        self.jupiter_client.clone()
+    }
+
+    /// Shared Birdeye client, for handlers that fetch history without holding the AutoTrader lock
+    pub fn birdeye_client(&self) -> Arc<BirdeyeClient> {
+        self.birdeye_client.clone()
+    }
+
     /// Executes a manual buy for a specific token address
     pub async fn execute_manual_buy(
         &self,
@@ -1380,6 +2497,22 @@ impl AutoTrader {
     ) -> Result<SwapResult> {
         info!("Executing manual buy for token: {} with amount: {} SOL", token_address, amount_sol);
 
+        // Global and default-strategy blacklists/whitelists apply to manual snipes too
+        let token_metadata = self.get_token_metadata(token_address).await?;
+        let creator = token_metadata.creator.as_deref();
+        let default_lists = self.strategies.read().await.values()
+            .find(|s| s.name.to_lowercase() == "default")
+            .map(|s| s.token_lists.clone());
+        if let Some(reason) = self.blocklist.block_reason(token_address, creator)
+            .or_else(|| default_lists.and_then(|lists| lists.rejection_reason(token_address, creator)))
+        {
+            return Err(anyhow!("Manual buy of {} rejected: {}", token_metadata.symbol, reason));
+        }
+
+        if self.config.dry_run_mode {
+            return self.simulate_manual_buy(&token_metadata, amount_sol).await;
+        }
+
         // Use the default strategy for manual buys
         let strategies = self.strategies.read().await;
         let default_strategy = strategies.values().find(|s| s.name.to_lowercase() == "default").cloned();
@@ -1400,21 +2533,61 @@ impl AutoTrader {
             return Err(anyhow!("Already have an active position in token {}", token_address));
         }
 
-        // Get token metadata
-        let token_metadata = self.get_token_metadata(token_address).await?;
-
         // Execute the buy using the existing execute_buy_task function
         execute_buy_task(
             &token_metadata,
             &strategy,
             &self.position_manager,
             &self.jupiter_client,
-            &self.wallet_manager,
+            &self.wallet_pool.next_wallet(),
             &self.config,
+            None,
             None, // TODO: Pass WebSocket tx when implemented
         ).await
     }
 
+    /// Dry-run manual buy: a simulated position at the current price instead of a swap.
+    /// The returned signature is a `DRY_RUN_` placeholder.
+    async fn simulate_manual_buy(&self, token_metadata: &TokenMetadata, amount_sol: f64) -> Result<SwapResult> {
+        let simulation_manager = self.simulation_manager.as_ref()
+            .ok_or_else(|| anyhow!("Dry-run mode is on but no simulation manager is running"))?;
+        let token_address = token_metadata.address.as_str();
+        let price_sol = self.risk_analyzer.price_cache().price_sol(token_address, token_metadata.decimals).await
+            .context(format!("No price for {}, cannot simulate the buy", token_metadata.symbol))?;
+        if price_sol <= 0.0 {
+            return Err(anyhow!("Invalid price for {}, cannot simulate the buy", token_metadata.symbol));
+        }
+        let (risk_score, risk_details) = match self.risk_analyzer.analyze_token(token_address).await {
+            Ok(analysis) => (analysis.risk_level, analysis.details),
+            Err(e) => (0, vec![format!("Risk analysis failed: {}", e)]),
+        };
+        let strategy_id = self.strategies.read().await.values()
+            .find(|s| s.name.to_lowercase() == "default")
+            .map_or_else(|| "manual".to_string(), |s| s.id.clone());
+
+        let position = simulation_manager.simulate_buy(
+            token_address,
+            &token_metadata.symbol,
+            &token_metadata.name,
+            price_sol,
+            amount_sol,
+            risk_score,
+            risk_details,
+            "Manual snipe".to_string(),
+            strategy_id,
+        ).await?;
+
+        Ok(SwapResult {
+            input_mint: SOL_MINT.to_string(),
+            output_mint: token_address.to_string(),
+            in_amount_ui: amount_sol,
+            out_amount_ui: amount_sol / price_sol,
+            actual_out_amount_ui: None,
+            price_impact_pct: 0.0,
+            transaction_signature: format!("DRY_RUN_{}", position.id),
+        })
+    }
+
     /// Creates a default strategy and executes a manual buy
     async fn create_default_strategy_and_buy(
         &self,
@@ -1431,13 +2604,25 @@ impl AutoTrader {
             max_position_size_sol: amount_sol,
             total_budget_sol: amount_sol * 2.0,
             stop_loss_percent: Some(15),
+            stop_loss_type: StopLossType::FixedPercent,
             take_profit_percent: Some(50),
             trailing_stop_percent: Some(5),
+            peak_drawdown_alert_percent: None,
+            peak_drawdown_sell_percent: None,
             max_hold_time_minutes: 240,
+            force_close_at: None,
+            momentum_tp: None,
+            scale_in: None,
+            dca: None,
+            scan_interval_secs: None,
+            scan_token_age_minutes: None,
+            limit_entry: None,
+            notify_trades: TradeNotify::Both,
             min_liquidity_sol: 1,
             max_risk_level: 80,
             min_holders: 10,
             max_token_age_minutes: 1440, // 24 hours
+            max_token_age_seconds: None,
             require_lp_burned: false,
             reject_if_mint_authority: true,
             reject_if_freeze_authority: true,
@@ -1450,8 +2635,15 @@ impl AutoTrader {
             require_migrated: None,
             min_buy_ratio_percent: 0.0,
             min_unique_wallets_24h: None,
+            token_lists: TokenLists::default(),
             slippage_bps: None,
             priority_fee_micro_lamports: None,
+            fee_urgency: FeeUrgency::Medium,
+            use_jito: false,
+            jito_tip_lamports: None,
+            execution_venue: ExecutionVenue::Jupiter,
+            entry_retry_attempts: 0,
+            entry_retry_delay_ms: DEFAULT_ENTRY_RETRY_DELAY_MS,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -1468,30 +2660,136 @@ impl AutoTrader {
             &default_strategy,
             &self.position_manager,
             &self.jupiter_client,
-            &self.wallet_manager,
+            &self.wallet_pool.next_wallet(),
             &self.config,
+            None,
             None, // TODO: Pass WebSocket tx when implemented
         ).await
     }
 
-    /// Gets token metadata for a given address
-    async fn get_token_metadata(&self, token_address: &str) -> Result<TokenMetadata> {
-        // Try to get from Helius first
-        match self.helius_client.get_token_metadata(token_address).await {
-            Ok(metadata) => Ok(metadata),
-            Err(_) => {
-                // If Helius fails, create basic metadata
-                Ok(TokenMetadata {
-                    address: token_address.to_string(),
-                    name: format!("Token {}", token_address),
-                    symbol: "UNKNOWN".to_string(),
-                    decimals: 9,
-                    supply: None,
-                    logo_uri: None,
-                    creation_time: None,
-                })
-            }
+    /// Place a manual limit buy that executes once the price falls to `target_price_sol`
+    pub async fn place_limit_order(
+        &self,
+        token_address: &str,
+        target_price_sol: f64,
+        amount_sol: f64,
+        expires_in_minutes: u64,
+    ) -> Result<LimitOrder> {
+        if self.position_manager.has_active_position(token_address).await {
+            return Err(anyhow!("Already have an active position in token {}", token_address));
         }
+        let token_metadata = self.get_token_metadata(token_address).await?;
+        let now = Utc::now();
+        self.limit_orders.place(LimitOrder {
+            id: uuid::Uuid::new_v4().to_string(),
+            token_address: token_address.to_string(),
+            token_name: token_metadata.name,
+            token_symbol: token_metadata.symbol,
+            token_decimals: token_metadata.decimals,
+            target_price_sol,
+            amount_sol,
+            strategy_id: None,
+            created_at: now,
+            expires_at: now + chrono::Duration::minutes(expires_in_minutes as i64),
+            status: LimitOrderStatus::Pending,
+            last_price_sol: None,
+            closed_at: None,
+            signature: None,
+            error: None,
+        }).await
+    }
+
+    /// Round-trip `test_swap_amount_sol` through the configured test token on the primary
+    /// wallet, reporting each step. Refused unless TEST_SWAP_ENABLED is set, and in demo mode.
+    pub async fn run_test_swap(&self) -> Result<TestSwapReport> {
+        if !self.config.test_swap_enabled {
+            return Err(anyhow!("Test swaps are disabled (set TEST_SWAP_ENABLED=true)"));
+        }
+        if self.config.demo_mode {
+            return Err(anyhow!("Test swaps send real transactions and can't run in demo mode"));
+        }
+        let token_metadata = self.get_token_metadata(&self.config.test_swap_token_mint).await?;
+        info!(
+            "🧪 Running test swap: {} SOL -> {} -> SOL",
+            self.config.test_swap_amount_sol, token_metadata.symbol
+        );
+        Ok(run_test_swap(
+            &self.jupiter_client,
+            self.wallet_pool.primary(),
+            &self.config.test_swap_token_mint,
+            token_metadata.decimals,
+            self.config.test_swap_amount_sol,
+            self.config.live().default_slippage_bps,
+            self.config.live().default_priority_fee_micro_lamports,
+        ).await)
+    }
+
+    /// Price pending limit orders every `limit_order_check_secs` while trading is running,
+    /// buying those at or below target and expiring the rest once they lapse
+    fn spawn_limit_order_monitor(&self) {
+        if !self.limit_orders.claim_monitor() {
+            return;
+        }
+        let limit_orders = self.limit_orders.clone();
+        let running = self.running.clone();
+        let strategies = self.strategies.clone();
+        let position_manager = self.position_manager.clone();
+        let jupiter_client = self.jupiter_client.clone();
+        let price_cache = self.risk_analyzer.price_cache();
+        let wallet_pool = self.wallet_pool.clone();
+        let config = self.config.clone();
+
+        tokio::spawn(async move {
+            let mut timer = interval(Duration::from_secs(config.limit_order_check_secs.max(1)));
+            loop {
+                timer.tick().await;
+                if !*running.read().await {
+                    continue;
+                }
+                for order in limit_orders.pending().await {
+                    let now = Utc::now();
+                    let price = if now >= order.expires_at {
+                        None
+                    } else {
+                        price_cache.price_sol(&order.token_address, order.token_decimals).await
+                            .map_err(|e| debug!("No price for limit order {} ({}): {:?}", order.id, order.token_symbol, e))
+                            .ok()
+                    };
+
+                    match order_action(&order, price, now) {
+                        OrderAction::Wait => {
+                            if let Some(price) = price {
+                                limit_orders.record_price(&order.id, price).await;
+                            }
+                        }
+                        OrderAction::Expire => {
+                            let _ = limit_orders.finish(
+                                &order.id, LimitOrderStatus::Expired, None,
+                                Some(format!("Target {:.9} SOL not reached", order.target_price_sol)),
+                            ).await;
+                        }
+                        OrderAction::Fill => {
+                            info!(
+                                "📌 Limit order {} triggered: {} at {:.9} SOL (target {:.9})",
+                                order.id, order.token_symbol, price.unwrap_or(0.0), order.target_price_sol
+                            );
+                            let result = execute_limit_order(
+                                &order, &strategies, &position_manager, &jupiter_client, &wallet_pool, &config,
+                            ).await;
+                            let _ = match result {
+                                Ok(swap) => limit_orders.finish(&order.id, LimitOrderStatus::Filled, Some(swap.transaction_signature), None).await,
+                                Err(e) => limit_orders.finish(&order.id, LimitOrderStatus::Failed, None, Some(e.to_string())).await,
+                            };
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Gets token metadata for a given address (see `TokenMetadataResolver`)
+    async fn get_token_metadata(&self, token_address: &str) -> Result<TokenMetadata> {
+        self.token_metadata.resolve(token_address).await
     }
 
     // =========================================================================
@@ -1554,6 +2852,36 @@ impl AutoTrader {
         Ok(())
     }
 
+    /// Start real-time pump.fun discovery for REAL mode: a logsSubscribe stream whose
+    /// tokens go straight into the buy pipeline. The Helius scan takes over if the
+    /// stream exhausts its reconnect attempts.
+    async fn init_realtime_discovery(&self) -> Result<()> {
+        let ws_url = self.config.realtime_discovery_ws_url.clone().unwrap_or_else(|| {
+            format!("wss://mainnet.helius-rpc.com/?api-key={}", self.config.helius_api_key)
+        });
+
+        let (token_tx, token_rx) = mpsc::channel::<PumpfunToken>(100);
+        let monitor = PumpfunMonitor::with_websocket_url(&ws_url, token_tx);
+        monitor.start().await?;
+
+        *self.pumpfun_monitor.lock().await = Some(monitor);
+        *self.pumpfun_token_rx.lock().await = Some(token_rx);
+
+        if self.config.realtime_pool_discovery {
+            let (pool_tx, pool_rx) = mpsc::channel::<NewPool>(100);
+            let pool_monitor = PoolMonitor::new(&ws_url, self.solana_client.clone(), pool_tx);
+            match pool_monitor.start().await {
+                Ok(()) => {
+                    *self.pool_monitor.lock().await = Some(pool_monitor);
+                    *self.new_pool_rx.lock().await = Some(pool_rx);
+                }
+                Err(e) => warn!("Failed to start Raydium pool discovery: {:?}", e),
+            }
+        }
+        info!("⚡ Real-time discovery started (Helius scan is the fallback)");
+        Ok(())
+    }
+
     /// Start the Pump.fun monitors (call after init_pumpfun_discovery and start).
     pub async fn start_pumpfun_discovery(&self) -> Result<()> {
         if !self.config.dry_run_mode {
@@ -1738,24 +3066,101 @@ impl AutoTrader {
         Ok(())
     }
 
-    /// Gets performance statistics for the trading bot
-    pub async fn get_performance_stats(&self) -> Result<PerformanceStats> {
-        let positions = self.position_manager.get_all_positions().await;
+    /// Explains which enabled strategy would claim a token: analyzes it, evaluates every
+    /// enabled strategy in scan order and reports the first one that would actually buy.
+    pub async fn match_strategies(&self, token_address: &str) -> Result<StrategyMatchReport> {
+        let token = self.get_token_metadata(token_address).await?;
+        let risk_analysis = self.risk_analyzer.analyze_token(token_address).await
+            .context("Failed to analyze token")?;
+
+        let strategies = enabled_strategies_in_order(&*self.strategies.read().await);
+
+        let mut matches = Vec::with_capacity(strategies.len());
+        let mut claimed_by = None;
+        for (order, strategy) in strategies.iter().enumerate() {
+            let meets_criteria = meets_strategy_criteria(&token, &risk_analysis, strategy, &self.blocklist);
+            let can_execute = if meets_criteria {
+                Some(should_execute_buy_task(&token, strategy, &self.position_manager).await?)
+            } else {
+                None
+            };
+            if claimed_by.is_none() && can_execute == Some(true) {
+                claimed_by = Some(strategy.id.clone());
+            }
+            matches.push(StrategyMatch {
+                order: order + 1,
+                strategy_id: strategy.id.clone(),
+                strategy_name: strategy.name.clone(),
+                strategy_type: format!("{:?}", strategy.strategy_type),
+                meets_criteria,
+                can_execute,
+            });
+        }
+
+        Ok(StrategyMatchReport {
+            token_address: token_address.to_string(),
+            token_symbol: token.symbol,
+            risk_level: risk_analysis.risk_level,
+            liquidity_sol: risk_analysis.liquidity_sol,
+            holder_count: risk_analysis.holder_count,
+            strategies: matches,
+            claimed_by,
+        })
+    }
+
+    /// Gets performance statistics for the trading bot: aggregate stats over closed
+    /// positions. Dust positions (below `hide_positions_below_sol`) are skipped unless
+    /// `include_dust` is set; `include_archived` adds archived positions.
+    pub async fn get_performance_stats(&self, include_dust: bool, include_archived: bool) -> Result<PerformanceStats> {
+        let dust_threshold = if include_dust { 0.0 } else { self.config.hide_positions_below_sol };
+        let positions = if include_archived {
+            self.position_manager.get_all_positions_with_archive().await?
+        } else {
+            self.position_manager.get_all_positions().await
+        };
+        let positions: Vec<_> = positions
+            .into_iter()
+            .filter(|p| !p.is_dust(dust_threshold))
+            .collect();
+        Ok(PerformanceStats::from_closed_trades(positions.into_iter().filter_map(|position| {
+            position.exit_value_sol.map(|exit_value| (position.entry_value_sol, exit_value, position.pnl_usd))
+        })))
+    }
+}
+
+/// Performance statistics structure
+#[derive(Debug, serde::Serialize)]
+pub struct PerformanceStats {
+    pub total_trades: u32,
+    pub winning_trades: u32,
+    pub total_pnl: f64,
+    pub total_pnl_usd: Option<f64>,   // Over trades with a USD basis (USD_PNL_TRACKING)
+    pub win_rate: f64,
+    pub avg_roi: f64,
+    pub total_entry_value: f64,
+}
+
+impl PerformanceStats {
+    /// Aggregate closed trades given as (entry SOL, exit SOL, USD PnL if known).
+    /// Shared by live stats and backtests so the two are directly comparable.
+    pub fn from_closed_trades(trades: impl IntoIterator<Item = (f64, f64, Option<f64>)>) -> Self {
         let mut total_pnl = 0.0;
+        let mut total_pnl_usd: Option<f64> = None;
         let mut total_trades = 0;
         let mut winning_trades = 0;
         let mut total_entry_value = 0.0;
 
-        for position in positions {
-            if let Some(exit_value) = position.exit_value_sol {
-                let pnl = exit_value - position.entry_value_sol;
-                total_pnl += pnl;
-                total_entry_value += position.entry_value_sol;
-                total_trades += 1;
+        for (entry_value, exit_value, pnl_usd) in trades {
+            let pnl = exit_value - entry_value;
+            total_pnl += pnl;
+            if let Some(pnl_usd) = pnl_usd {
+                *total_pnl_usd.get_or_insert(0.0) += pnl_usd;
+            }
+            total_entry_value += entry_value;
+            total_trades += 1;
 
-                if pnl > 0.0 {
-                    winning_trades += 1;
-                }
+            if pnl > 0.0 {
+                winning_trades += 1;
             }
         }
 
@@ -1771,26 +3176,39 @@ impl AutoTrader {
             0.0
         };
 
-        Ok(PerformanceStats {
+        PerformanceStats {
             total_trades,
             winning_trades,
             total_pnl,
+            total_pnl_usd,
             win_rate,
             avg_roi,
             total_entry_value,
-        })
+        }
     }
 }
 
-/// Performance statistics structure
+/// One strategy's verdict on a token (see `AutoTrader::match_strategies`)
 #[derive(Debug, serde::Serialize)]
-pub struct PerformanceStats {
-    pub total_trades: u32,
-    pub winning_trades: u32,
-    pub total_pnl: f64,
-    pub win_rate: f64,
-    pub avg_roi: f64,
-    pub total_entry_value: f64,
+pub struct StrategyMatch {
+    pub order: usize,                 // Position in scan order (1 = evaluated first)
+    pub strategy_id: String,
+    pub strategy_name: String,
+    pub strategy_type: String,
+    pub meets_criteria: bool,
+    pub can_execute: Option<bool>,    // Budget/concurrency check; None if criteria not met
+}
+
+/// Which strategies match a token and which one would buy it
+#[derive(Debug, serde::Serialize)]
+pub struct StrategyMatchReport {
+    pub token_address: String,
+    pub token_symbol: String,
+    pub risk_level: u32,
+    pub liquidity_sol: f64,
+    pub holder_count: u32,
+    pub strategies: Vec<StrategyMatch>,
+    pub claimed_by: Option<String>,   // ID of the strategy that would execute the buy
 }
 
 // ============================================================================
@@ -1883,3 +3301,29 @@ fn calculate_pumpfun_risk_score(progress_percent: f64, liquidity_sol: f64) -> u3
     // Clamp to 0-100 range
     risk_score.clamp(0.0, 100.0) as u32
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn read_after_buy_retries_until_done() {
+        let reads = AtomicU32::new(0);
+        // Fails once, reads short once, then reads the full fill
+        let read = || async {
+            match reads.fetch_add(1, Ordering::SeqCst) {
+                0 => None,
+                1 => Some(40.0),
+                _ => Some(100.0),
+            }
+        };
+        assert_eq!(read_after_buy(5, Duration::ZERO, read, |v: &f64| *v >= 95.0).await, Some(100.0));
+        assert_eq!(reads.load(Ordering::SeqCst), 3);
+
+        // Out of attempts: the last successful read, or None if none succeeded
+        reads.store(0, Ordering::SeqCst);
+        assert_eq!(read_after_buy(2, Duration::ZERO, read, |v: &f64| *v >= 95.0).await, Some(40.0));
+        assert_eq!(read_after_buy(1, Duration::ZERO, || async { None::<f64> }, |_| true).await, None);
+    }
+}
@@ -0,0 +1,273 @@
+//! Replay historical candles against a strategy's exit settings
+//!
+//! Each token is bought once at the open of its first candle for
+//! `max_position_size_sol`, then walked candle by candle through the strategy's
+//! stop-loss, take-profit, trailing stop and max hold time, the way the position
+//! monitor would. Candles don't say whether the high or the low came first, so a
+//! candle that touches both the stop and the target counts as a stop (the
+//! pessimistic reading). Entry filters (risk, liquidity, holders) aren't replayed:
+//! the caller picks the tokens.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::api::birdeye::Candle;
+use crate::trading::autotrader::PerformanceStats;
+use crate::trading::strategy::Strategy;
+
+/// Swap fees and price impact charged on each side of a trade when the caller
+/// doesn't give a cost
+pub const DEFAULT_COST_BPS: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BacktestExit {
+    StopLoss,
+    TakeProfit,
+    TrailingStop,
+    MaxHoldTime,
+    EndOfData, // Still open at the last candle; valued at its close
+}
+
+/// One simulated round trip
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestTrade {
+    pub token_address: String,
+    pub entry_time: DateTime<Utc>,
+    pub exit_time: DateTime<Utc>,
+    pub entry_price_sol: f64,
+    pub exit_price_sol: f64,
+    pub entry_value_sol: f64,
+    pub exit_value_sol: f64,
+    pub pnl_sol: f64,
+    pub pnl_percent: f64,
+    pub exit_reason: BacktestExit,
+}
+
+/// Account value after each candle: starting budget plus realized PnL plus the
+/// open trade marked at the candle's close
+#[derive(Debug, Clone, Serialize)]
+pub struct EquityPoint {
+    pub timestamp: DateTime<Utc>,
+    pub equity_sol: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BacktestReport {
+    pub strategy_id: String,
+    pub strategy_name: String,
+    pub starting_equity_sol: f64,
+    pub final_equity_sol: f64,
+    pub stats: PerformanceStats,
+    pub max_drawdown_sol: f64,
+    pub max_drawdown_percent: f64,
+    pub trades: Vec<BacktestTrade>,
+    pub skipped_tokens: Vec<String>, // No usable candles in the range
+    pub equity_curve: Vec<EquityPoint>,
+}
+
+/// Replay `candles` (per token, oldest first) against `strategy`, trading the
+/// tokens one after another. `cost_bps` is taken off both the buy and the sell.
+pub fn run(strategy: &Strategy, tokens: &[(String, Vec<Candle>)], cost_bps: u32) -> Result<BacktestReport> {
+    if strategy.max_position_size_sol <= 0.0 {
+        return Err(anyhow!("Strategy {} has no position size", strategy.name));
+    }
+    let starting_equity = strategy.total_budget_sol.max(strategy.max_position_size_sol);
+    let cost = cost_bps as f64 / 10_000.0;
+
+    let mut equity = starting_equity;
+    let mut trades = Vec::new();
+    let mut skipped_tokens = Vec::new();
+    let mut equity_curve = Vec::new();
+    for (token_address, candles) in tokens {
+        let Some(trade) = replay_token(strategy, token_address, candles, cost, equity, &mut equity_curve) else {
+            skipped_tokens.push(token_address.clone());
+            continue;
+        };
+        equity += trade.pnl_sol;
+        trades.push(trade);
+    }
+
+    let (max_drawdown_sol, max_drawdown_percent) = max_drawdown(starting_equity, &equity_curve);
+    Ok(BacktestReport {
+        strategy_id: strategy.id.clone(),
+        strategy_name: strategy.name.clone(),
+        starting_equity_sol: starting_equity,
+        final_equity_sol: equity,
+        stats: PerformanceStats::from_closed_trades(trades.iter().map(|t| (t.entry_value_sol, t.exit_value_sol, None))),
+        max_drawdown_sol,
+        max_drawdown_percent,
+        trades,
+        skipped_tokens,
+        equity_curve,
+    })
+}
+
+/// Buy at the first candle's open and hold until an exit triggers
+fn replay_token(
+    strategy: &Strategy,
+    token_address: &str,
+    candles: &[Candle],
+    cost: f64,
+    equity_before: f64,
+    equity_curve: &mut Vec<EquityPoint>,
+) -> Option<BacktestTrade> {
+    let candles: Vec<&Candle> = candles.iter().filter(|c| c.open > 0.0 && c.low > 0.0).collect();
+    let first = candles.first()?;
+    let entry_time = candle_time(first);
+    let entry_price = first.open;
+    let entry_value = strategy.max_position_size_sol;
+    let tokens = entry_value * (1.0 - cost) / entry_price;
+
+    let stop_loss = strategy.stop_loss_percent.map(|p| entry_price * (1.0 - p as f64 / 100.0));
+    let take_profit = strategy.take_profit_percent.map(|p| entry_price * (1.0 + p as f64 / 100.0));
+    let deadline = (strategy.max_hold_time_minutes > 0)
+        .then(|| entry_time + chrono::Duration::minutes(strategy.max_hold_time_minutes as i64));
+    let mut highest = entry_price;
+
+    for candle in &candles {
+        let time = candle_time(candle);
+        // The trailing level is set by highs already seen: this candle's own high
+        // may have come after its low
+        let trailing = strategy.trailing_stop_percent.map(|p| highest * (1.0 - p as f64 / 100.0));
+        let exit = match (stop_loss, trailing) {
+            (Some(sl), Some(ts)) if ts > sl && candle.low <= ts => Some((BacktestExit::TrailingStop, ts)),
+            (Some(sl), _) if candle.low <= sl => Some((BacktestExit::StopLoss, sl)),
+            (None, Some(ts)) if candle.low <= ts => Some((BacktestExit::TrailingStop, ts)),
+            _ => None,
+        }
+        // A candle that opens through the level fills at the open, not the level
+        .map(|(reason, level)| (reason, level.min(candle.open)))
+        .or_else(|| take_profit.filter(|tp| candle.high >= *tp).map(|tp| (BacktestExit::TakeProfit, tp.max(candle.open))))
+        .or_else(|| deadline.filter(|d| time >= *d).map(|_| (BacktestExit::MaxHoldTime, candle.close)));
+
+        if let Some((exit_reason, exit_price)) = exit {
+            let trade = close_trade(token_address, entry_time, entry_price, entry_value, tokens, time, exit_price, cost, exit_reason);
+            equity_curve.push(EquityPoint { timestamp: time, equity_sol: equity_before + trade.pnl_sol });
+            return Some(trade);
+        }
+
+        highest = highest.max(candle.high);
+        let marked = tokens * candle.close * (1.0 - cost);
+        equity_curve.push(EquityPoint { timestamp: time, equity_sol: equity_before + marked - entry_value });
+    }
+
+    let last = candles.last()?;
+    Some(close_trade(token_address, entry_time, entry_price, entry_value, tokens, candle_time(last), last.close, cost, BacktestExit::EndOfData))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn close_trade(
+    token_address: &str,
+    entry_time: DateTime<Utc>,
+    entry_price: f64,
+    entry_value: f64,
+    tokens: f64,
+    exit_time: DateTime<Utc>,
+    exit_price: f64,
+    cost: f64,
+    exit_reason: BacktestExit,
+) -> BacktestTrade {
+    let exit_value = tokens * exit_price * (1.0 - cost);
+    let pnl_sol = exit_value - entry_value;
+    BacktestTrade {
+        token_address: token_address.to_string(),
+        entry_time,
+        exit_time,
+        entry_price_sol: entry_price,
+        exit_price_sol: exit_price,
+        entry_value_sol: entry_value,
+        exit_value_sol: exit_value,
+        pnl_sol,
+        pnl_percent: pnl_sol / entry_value * 100.0,
+        exit_reason,
+    }
+}
+
+/// Largest peak-to-trough fall of the equity curve, in SOL and percent of the peak
+fn max_drawdown(starting_equity: f64, curve: &[EquityPoint]) -> (f64, f64) {
+    let mut peak = starting_equity;
+    let (mut worst_sol, mut worst_percent) = (0.0_f64, 0.0_f64);
+    for point in curve {
+        peak = peak.max(point.equity_sol);
+        let drawdown = peak - point.equity_sol;
+        worst_sol = worst_sol.max(drawdown);
+        if peak > 0.0 {
+            worst_percent = worst_percent.max(drawdown / peak * 100.0);
+        }
+    }
+    (worst_sol, worst_percent)
+}
+
+fn candle_time(candle: &Candle) -> DateTime<Utc> {
+    Utc.timestamp_opt(candle.unix_time, 0).single().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(minute: i64, open: f64, high: f64, low: f64, close: f64) -> Candle {
+        Candle { unix_time: 1_700_000_000 + minute * 60, open, high, low, close, volume: 0.0 }
+    }
+
+    fn strategy() -> Strategy {
+        let mut strategy = Strategy::default("Backtest");
+        strategy.max_position_size_sol = 1.0;
+        strategy.total_budget_sol = 10.0;
+        strategy.stop_loss_percent = Some(20);
+        strategy.take_profit_percent = Some(50);
+        strategy.trailing_stop_percent = None;
+        strategy.max_hold_time_minutes = 0;
+        strategy
+    }
+
+    #[test]
+    fn exits_on_take_profit_then_stop_loss_with_drawdown() {
+        let tokens = vec![
+            ("WIN".to_string(), vec![candle(0, 1.0, 1.2, 0.9, 1.1), candle(1, 1.1, 1.6, 1.05, 1.5)]),
+            ("LOSE".to_string(), vec![candle(2, 1.0, 1.05, 0.95, 1.0), candle(3, 1.0, 1.0, 0.7, 0.75)]),
+            ("EMPTY".to_string(), Vec::new()),
+        ];
+        let report = run(&strategy(), &tokens, 0).unwrap();
+
+        assert_eq!(report.trades.len(), 2);
+        assert_eq!(report.trades[0].exit_reason, BacktestExit::TakeProfit);
+        assert!((report.trades[0].pnl_sol - 0.5).abs() < 1e-9);
+        assert_eq!(report.trades[1].exit_reason, BacktestExit::StopLoss);
+        assert!((report.trades[1].pnl_sol + 0.2).abs() < 1e-9);
+        assert_eq!(report.skipped_tokens, vec!["EMPTY".to_string()]);
+
+        assert_eq!(report.stats.total_trades, 2);
+        assert_eq!(report.stats.winning_trades, 1);
+        assert!((report.final_equity_sol - 10.3).abs() < 1e-9);
+        // Peak 10.5 after the win, trough 10.3 after the stop
+        assert!((report.max_drawdown_sol - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn trailing_stop_follows_earlier_highs_and_gaps_fill_at_open() {
+        let mut strategy = strategy();
+        strategy.take_profit_percent = None;
+        strategy.trailing_stop_percent = Some(10);
+        let tokens = vec![("TRAIL".to_string(), vec![
+            candle(0, 1.0, 2.0, 1.0, 2.0),
+            // Gaps below the 1.8 trailing level: filled at the 1.5 open
+            candle(1, 1.5, 1.5, 1.4, 1.45),
+        ])];
+        let report = run(&strategy, &tokens, 0).unwrap();
+        let trade = &report.trades[0];
+        assert_eq!(trade.exit_reason, BacktestExit::TrailingStop);
+        assert_eq!(trade.exit_price_sol, 1.5);
+    }
+
+    #[test]
+    fn unexited_trade_closes_at_last_candle_net_of_costs() {
+        let tokens = vec![("HOLD".to_string(), vec![candle(0, 1.0, 1.1, 0.9, 1.0), candle(1, 1.0, 1.1, 0.9, 1.0)])];
+        let report = run(&strategy(), &tokens, 100).unwrap();
+        let trade = &report.trades[0];
+        assert_eq!(trade.exit_reason, BacktestExit::EndOfData);
+        assert!((trade.exit_value_sol - 0.99 * 0.99).abs() < 1e-9);
+    }
+}
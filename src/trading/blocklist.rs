@@ -0,0 +1,156 @@
+//! External scam blocklist
+//!
+//! Periodically loads known-scam mints and creator wallets from a URL or a local
+//! file (updated out-of-band) so newly identified scams are rejected without a
+//! restart. A failed refresh keeps the last-known list.
+//!
+//! Accepted formats: a JSON object `{"mints": [...], "creators": [...]}`, or plain
+//! text with one address per line (`#` comments allowed) matched as either.
+//!
+//! The global mint/creator blacklists and whitelists from Config are checked here
+//! too, so every buy path that consults the blocklist honours them.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::time::{interval, Duration};
+use tracing::{info, warn};
+
+use crate::config::Config;
+use crate::trading::strategy::TokenLists;
+
+#[derive(Debug, Default, Deserialize)]
+struct BlocklistFile {
+    #[serde(default)]
+    mints: Vec<String>,
+    #[serde(default)]
+    creators: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct BlocklistEntries {
+    mints: HashSet<String>,
+    creators: HashSet<String>,
+}
+
+fn parse_blocklist(body: &str) -> Result<BlocklistEntries> {
+    if body.trim_start().starts_with('{') {
+        let file: BlocklistFile = serde_json::from_str(body).context("Failed to parse blocklist JSON")?;
+        return Ok(BlocklistEntries {
+            mints: file.mints.into_iter().map(|m| m.trim().to_string()).collect(),
+            creators: file.creators.into_iter().map(|c| c.trim().to_string()).collect(),
+        });
+    }
+    let addresses: HashSet<String> = body.lines()
+        .map(|l| l.split('#').next().unwrap_or("").trim())
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect();
+    Ok(BlocklistEntries { mints: addresses.clone(), creators: addresses })
+}
+
+pub struct Blocklist {
+    config: Arc<Config>,
+    configured: TokenLists, // Global lists from Config
+    http_client: reqwest::Client,
+    entries: RwLock<BlocklistEntries>,
+    refreshing: AtomicBool,
+}
+
+impl Blocklist {
+    pub fn new(config: Arc<Config>) -> Self {
+        let configured = TokenLists {
+            mint_blacklist: config.mint_blacklist.clone(),
+            mint_whitelist: config.mint_whitelist.clone(),
+            creator_blacklist: config.creator_blacklist.clone(),
+            creator_whitelist: config.creator_whitelist.clone(),
+        };
+        Self {
+            config,
+            configured,
+            http_client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .expect("Failed to create HTTP client"),
+            entries: RwLock::new(BlocklistEntries::default()),
+            refreshing: AtomicBool::new(false),
+        }
+    }
+
+    /// Why a token is blocklisted, or None if it isn't
+    pub fn block_reason(&self, mint: &str, creator: Option<&str>) -> Option<String> {
+        if let Some(reason) = self.configured.rejection_reason(mint, creator) {
+            return Some(reason);
+        }
+        let entries = self.entries.read().unwrap();
+        if entries.mints.contains(mint) {
+            return Some("mint is blocklisted".to_string());
+        }
+        match creator {
+            Some(c) if entries.creators.contains(c) => Some(format!("creator {} is blocklisted", c)),
+            _ => None,
+        }
+    }
+
+    async fn fetch(&self, source: &str) -> Result<BlocklistEntries> {
+        let body = if source.starts_with("http://") || source.starts_with("https://") {
+            let response = self.http_client.get(source).send().await
+                .context("Failed to fetch blocklist")?
+                .error_for_status()
+                .context("Blocklist source returned an error")?;
+            response.text().await.context("Failed to read blocklist body")?
+        } else {
+            tokio::fs::read_to_string(source).await
+                .context(format!("Failed to read blocklist file {}", source))?
+        };
+        parse_blocklist(&body)
+    }
+
+    /// Reload the list from the configured source, keeping the old list on failure
+    pub async fn refresh(&self) {
+        let Some(source) = self.config.blocklist_source.as_deref() else {
+            return;
+        };
+        match self.fetch(source).await {
+            Ok(entries) => {
+                info!("Blocklist refreshed: {} mints, {} creators", entries.mints.len(), entries.creators.len());
+                *self.entries.write().unwrap() = entries;
+            }
+            Err(e) => warn!("Blocklist refresh failed, keeping last-known list: {:?}", e),
+        }
+    }
+
+    /// Start the background refresh task (once; later calls are no-ops)
+    pub fn spawn_refresh(self: Arc<Self>) {
+        if self.config.blocklist_source.is_none() || self.refreshing.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let period = Duration::from_secs(self.config.blocklist_refresh_minutes.max(1) * 60);
+        tokio::spawn(async move {
+            let mut timer = interval(period);
+            loop {
+                timer.tick().await;
+                self.refresh().await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_json_and_plain_text() {
+        let json = parse_blocklist(r#"{"mints": ["MintA"], "creators": ["DevB"]}"#).unwrap();
+        assert!(json.mints.contains("MintA"));
+        assert!(json.creators.contains("DevB"));
+        assert!(!json.mints.contains("DevB"));
+
+        let text = parse_blocklist("# scam feed\nMintA\n\n  DevB  # rugged 3x\n").unwrap();
+        assert_eq!(text.mints.len(), 2);
+        assert!(text.creators.contains("DevB"));
+    }
+}
@@ -0,0 +1,240 @@
+//! Deployer reputation for the risk analyzer
+//!
+//! Serial ruggers launch token after token from the same wallet. The mint's creator
+//! (or its update authority when no creator is listed) is looked up through Helius:
+//! how many other tokens it has launched, how many of the most recent ones are now
+//! dead (liquidity gone), how old the wallet is and which wallet funded it. The
+//! result is scored and added to the token's `risk_level`.
+
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::{EncodedTransaction, UiMessage};
+use tracing::debug;
+
+use crate::api::helius::{DasAsset, HeliusClient};
+use crate::api::price_cache::PriceCache;
+use crate::solana::client::SolanaClient;
+
+/// Update authorities shared by every token of a launchpad; they say nothing about the deployer
+const SHARED_AUTHORITIES: &[&str] = &[
+    "TSLvdd1pWpHVjahSpsvCXUbgwsL3JAcvokwaKt1eokM", // pump.fun
+];
+
+/// Earlier launches fetched per deployer
+const MAX_LAUNCHES_FETCHED: u32 = 50;
+
+/// A previous launch with less liquidity than this (USD) is counted as dead
+const DEAD_LIQUIDITY_USD: f64 = 1_000.0;
+
+/// Signatures per page when walking back to a wallet's first transaction
+const SIGNATURE_PAGE: usize = 1000;
+
+/// Pages walked before giving up on finding the first transaction (the wallet is old)
+const MAX_SIGNATURE_PAGES: usize = 3;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeployerReport {
+    pub wallet: String,
+    pub prior_launches: usize,            // Other tokens this wallet created (up to MAX_LAUNCHES_FETCHED)
+    pub checked_launches: usize,          // Most recent of those whose liquidity was checked
+    pub dead_launches: usize,             // Checked launches whose liquidity is gone
+    pub first_seen: Option<DateTime<Utc>>, // Wallet's first transaction (None if older than the walk reaches)
+    pub funded_by: Option<String>,        // Fee payer of that first transaction, if not the wallet itself
+}
+
+impl DeployerReport {
+    pub fn wallet_age_days(&self, now: DateTime<Utc>) -> Option<f64> {
+        self.first_seen.map(|t| (now - t).num_seconds().max(0) as f64 / 86_400.0)
+    }
+}
+
+/// Risk points and detail lines for a deployer report
+pub fn deployer_score(report: &DeployerReport, now: DateTime<Utc>) -> (u32, Vec<String>) {
+    let mut score = 0;
+    let mut details = Vec::new();
+    let short: String = report.wallet.chars().take(8).collect();
+
+    if report.dead_launches > 0 {
+        score += (report.dead_launches as u32 * 10).min(40);
+        details.push(format!(
+            "🔴 Deployer {} has {} dead launch(es) among its last {} tokens.",
+            short, report.dead_launches, report.checked_launches
+        ));
+    }
+    if report.prior_launches >= 20 {
+        score += 15;
+        details.push(format!("🟠 Deployer {} has launched {}+ tokens (token factory).", short, report.prior_launches));
+    } else if report.prior_launches >= 5 {
+        score += 5;
+        details.push(format!("🟠 Deployer {} has launched {} other tokens.", short, report.prior_launches));
+    } else if report.dead_launches == 0 {
+        details.push(format!("✅ Deployer {} has {} other launch(es), none dead.", short, report.prior_launches));
+    }
+
+    match report.wallet_age_days(now) {
+        Some(days) if days < 1.0 => {
+            score += 15;
+            details.push(format!("🔴 Deployer wallet is fresh ({:.1}h old).", days * 24.0));
+        }
+        Some(days) if days < 7.0 => {
+            score += 5;
+            details.push(format!("🟠 Deployer wallet is {:.1} days old.", days));
+        }
+        Some(days) => details.push(format!("✅ Deployer wallet is {:.0} days old.", days)),
+        None => details.push("✅ Deployer wallet has a long history.".to_string()),
+    }
+    if let Some(funder) = &report.funded_by {
+        details.push(format!("ℹ️ Deployer wallet funded by {}.", funder));
+    }
+
+    (score, details)
+}
+
+/// The wallet behind a mint: its first creator, else a non-launchpad update authority
+pub fn deployer_wallet(asset: &DasAsset) -> Option<String> {
+    asset.creators.first()
+        .map(|c| c.address.clone())
+        .or_else(|| {
+            asset.authorities.iter()
+                .map(|a| a.address.clone())
+                .find(|a| !SHARED_AUTHORITIES.contains(&a.as_str()))
+        })
+}
+
+/// Look up the deployer of the mint `asset` describes. Ok(None) when no deployer
+/// wallet can be identified.
+pub async fn deployer_report(
+    helius_client: &HeliusClient,
+    solana_client: &SolanaClient,
+    price_cache: &PriceCache,
+    asset: &DasAsset,
+    max_launches_checked: usize,
+) -> Result<Option<DeployerReport>> {
+    let mint = asset.id.as_str();
+    let Some(wallet) = deployer_wallet(asset) else {
+        return Ok(None);
+    };
+
+    let launches: Vec<String> = helius_client.search_assets_by_creator(&wallet, MAX_LAUNCHES_FETCHED).await?
+        .into_iter()
+        .map(|a| a.id)
+        .filter(|id| id != mint)
+        .collect();
+
+    let mut checked_launches = 0;
+    let mut dead_launches = 0;
+    for launch in launches.iter().take(max_launches_checked) {
+        match price_cache.token_overview(launch).await {
+            Ok(overview) => {
+                checked_launches += 1;
+                let liquidity = overview.and_then(|o| o.liquidity).unwrap_or(0.0);
+                if liquidity < DEAD_LIQUIDITY_USD {
+                    dead_launches += 1;
+                }
+            }
+            Err(e) => debug!("Skipping liquidity check of {}'s launch {}: {}", wallet, launch, e),
+        }
+    }
+
+    let (first_seen, funded_by) = match first_transaction(solana_client, &wallet).await {
+        Ok(Some((signature, block_time))) => (block_time, fee_payer(solana_client, &signature, &wallet).await),
+        Ok(None) => (None, None),
+        Err(e) => {
+            debug!("Failed to read deployer {} history: {}", wallet, e);
+            (None, None)
+        }
+    };
+
+    Ok(Some(DeployerReport {
+        wallet,
+        prior_launches: launches.len(),
+        checked_launches,
+        dead_launches,
+        first_seen,
+        funded_by,
+    }))
+}
+
+/// The wallet's oldest transaction and its time, or None if it is older than the walk reaches
+async fn first_transaction(solana_client: &SolanaClient, wallet: &str) -> Result<Option<(String, Option<DateTime<Utc>>)>> {
+    let pubkey = Pubkey::from_str(wallet).context("Invalid deployer wallet address")?;
+    let mut before = None;
+    for _ in 0..MAX_SIGNATURE_PAGES {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until: None,
+            limit: Some(SIGNATURE_PAGE),
+            commitment: Some(CommitmentConfig::confirmed()),
+        };
+        let page = solana_client.get_rpc()
+            .get_signatures_for_address_with_config(&pubkey, config)
+            .await
+            .context("Failed to fetch deployer signatures")?;
+        let Some(oldest) = page.last() else {
+            return Ok(None);
+        };
+        if page.len() < SIGNATURE_PAGE {
+            let block_time = oldest.block_time.and_then(|t| DateTime::from_timestamp(t, 0));
+            return Ok(Some((oldest.signature.clone(), block_time)));
+        }
+        before = Some(Signature::from_str(&oldest.signature).context("Invalid signature from RPC")?);
+    }
+    Ok(None)
+}
+
+/// Fee payer of a transaction, unless it is the wallet itself
+async fn fee_payer(solana_client: &SolanaClient, signature: &str, wallet: &str) -> Option<String> {
+    let signature = Signature::from_str(signature).ok()?;
+    let tx = solana_client.get_transaction(&signature, CommitmentConfig::confirmed()).await.ok()?;
+    let EncodedTransaction::Json(ui_tx) = &tx.transaction.transaction else {
+        return None;
+    };
+    let payer = match &ui_tx.message {
+        UiMessage::Raw(raw) => raw.account_keys.first().cloned(),
+        UiMessage::Parsed(parsed) => parsed.account_keys.first().map(|a| a.pubkey.clone()),
+    }?;
+    (payer != wallet).then_some(payer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn serial_rugger_scores_high() {
+        let now = Utc::now();
+        let rugger = DeployerReport {
+            wallet: "Rugger1111".to_string(),
+            prior_launches: 30,
+            checked_launches: 5,
+            dead_launches: 5,
+            first_seen: Some(now - Duration::hours(6)),
+            funded_by: Some("Funder".to_string()),
+        };
+        let (score, details) = deployer_score(&rugger, now);
+        assert_eq!(score, 40 + 15 + 15);
+        assert!(details.iter().any(|d| d.contains("Funder")));
+
+        let clean = DeployerReport {
+            wallet: "Dev1111111".to_string(),
+            prior_launches: 1,
+            checked_launches: 1,
+            dead_launches: 0,
+            first_seen: Some(now - Duration::days(200)),
+            funded_by: None,
+        };
+        assert_eq!(deployer_score(&clean, now).0, 0);
+
+        // Too much history to find the first transaction: treated as an old wallet
+        let veteran = DeployerReport { first_seen: None, ..clean };
+        assert_eq!(deployer_score(&veteran, now).0, 0);
+    }
+}
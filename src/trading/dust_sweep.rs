@@ -0,0 +1,351 @@
+//! Periodic conversion of leftover token dust back to SOL
+//!
+//! Partial fills and failed closes leave small balances in many tokens. When enabled,
+//! every trading wallet's token balances are quoted against SOL on an interval; any
+//! holding worth less than the dust ceiling (but more than a swap costs) is sold.
+//! Tokens with an open position or a buy/sell in flight are left alone, and tokens
+//! whose quote or sell fails (no route, honeypots) are skipped for a few hours.
+//!
+//! The same sweep cleans up orphaned token accounts that no position tracks (failed
+//! entries, airdrops): optionally selling ones above the dust ceiling (up to a cap),
+//! burning ones not worth the swap fee, and closing empty accounts to reclaim their
+//! rent. It also runs on demand via `POST /api/wallet/sweep`. A sale leaves the
+//! account empty, so it's closed by the next sweep.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tokio::time::{interval, Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::api::jupiter::{JupiterClient, SwapRoute, SOL_MINT};
+use crate::config::Config;
+use crate::solana::client::TokenHolding;
+use crate::solana::wallet::WalletManager;
+use crate::solana::wallet_pool::WalletPool;
+use crate::trading::position::PositionManager;
+
+/// Token accounts closed (and burned, when needed) per cleanup transaction
+const CLEANUP_ACCOUNTS_PER_TX: usize = 8;
+
+/// How long a mint whose quote or sell failed is skipped before trying again
+/// (a missing route may just be a pool that doesn't exist yet)
+const UNSELLABLE_RECHECK_SECS: u64 = 6 * 3600;
+
+/// Outcome of one sweep, sent to notification subscribers when anything was sold or closed
+#[derive(Debug, Clone, Serialize)]
+pub struct DustSweepReport {
+    pub swept_tokens: usize,
+    pub reclaimed_sol: f64,
+    pub skipped_tokens: usize,
+    pub burned_tokens: usize,
+    pub closed_accounts: usize,
+    pub reclaimed_rent_sol: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl DustSweepReport {
+    pub fn changed_anything(&self) -> bool {
+        self.swept_tokens > 0 || self.closed_accounts > 0
+    }
+}
+
+/// What to do with one token holding, given its SOL value (None if it can't be quoted)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DustDecision {
+    Sweep,
+    /// Worth more than the dust ceiling; a real holding
+    NotDust,
+    /// Worth less than the swap would cost
+    NotWorthFee,
+    /// No route back to SOL
+    Unsellable,
+}
+
+/// With `orphan_max_value_sol`, holdings above the dust ceiling are sold too, up to that value
+pub fn dust_decision(value_sol: Option<f64>, max_value_sol: f64, min_value_sol: f64, orphan_max_value_sol: Option<f64>) -> DustDecision {
+    match value_sol {
+        None => DustDecision::Unsellable,
+        Some(v) if v >= orphan_max_value_sol.unwrap_or(max_value_sol).max(max_value_sol) => DustDecision::NotDust,
+        Some(v) if v < min_value_sol => DustDecision::NotWorthFee,
+        Some(_) => DustDecision::Sweep,
+    }
+}
+
+/// What the sweep did with one token account
+enum HoldingOutcome {
+    Sold(f64),
+    /// Empty (or about to be burned) and safe to close
+    Close { burn: bool },
+    Skipped,
+}
+
+/// Burn `holding`'s balance (if `burn`) and close its account, rent to `owner`.
+/// spl-token-2022's builders accept either token program id.
+fn cleanup_instructions(holding: &TokenHolding, owner: &Pubkey, burn: bool) -> anyhow::Result<Vec<Instruction>> {
+    let mut instructions = Vec::new();
+    if burn && holding.raw_amount > 0 {
+        let mint = holding.mint.parse::<Pubkey>()?;
+        instructions.push(spl_token_2022::instruction::burn(
+            &holding.program_id, &holding.account, &mint, owner, &[], holding.raw_amount,
+        )?);
+    }
+    instructions.push(spl_token_2022::instruction::close_account(
+        &holding.program_id, &holding.account, owner, owner, &[],
+    )?);
+    Ok(instructions)
+}
+
+pub struct DustSweeper {
+    wallet_pool: Arc<WalletPool>,
+    jupiter_client: Arc<JupiterClient>,
+    position_manager: Arc<PositionManager>,
+    config: Arc<Config>,
+    unsellable: RwLock<HashMap<String, Instant>>, // Mints whose quote or sell failed, and when
+    running: AtomicBool,
+    sweep_lock: Mutex<()>, // The periodic and on-demand sweeps never overlap
+    report_tx: broadcast::Sender<DustSweepReport>,
+}
+
+impl DustSweeper {
+    pub fn new(
+        wallet_pool: Arc<WalletPool>,
+        jupiter_client: Arc<JupiterClient>,
+        position_manager: Arc<PositionManager>,
+        config: Arc<Config>,
+    ) -> Self {
+        Self {
+            wallet_pool,
+            jupiter_client,
+            position_manager,
+            config,
+            unsellable: RwLock::new(HashMap::new()),
+            running: AtomicBool::new(false),
+            sweep_lock: Mutex::new(()),
+            report_tx: broadcast::channel(16).0,
+        }
+    }
+
+    /// Receive a report after each sweep that sold something
+    pub fn subscribe(&self) -> broadcast::Receiver<DustSweepReport> {
+        self.report_tx.subscribe()
+    }
+
+    /// Start the periodic sweep (no-op when disabled or already started)
+    pub fn spawn(self: Arc<Self>) {
+        if !self.config.dust_sweep_enabled || self.config.demo_mode || self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        info!(
+            "🧹 Dust sweep enabled: every {} min, holdings worth {:.4}-{:.4} SOL are sold to SOL",
+            self.config.dust_sweep_interval_minutes, self.config.dust_sweep_min_value_sol, self.config.dust_sweep_max_value_sol
+        );
+        let period = Duration::from_secs(self.config.dust_sweep_interval_minutes.max(1) * 60);
+        tokio::spawn(async move {
+            let mut timer = interval(period);
+            timer.tick().await; // Don't sweep the moment trading starts
+            loop {
+                timer.tick().await;
+                let report = self.sweep().await;
+                if report.changed_anything() {
+                    let _ = self.report_tx.send(report);
+                }
+            }
+        });
+    }
+
+    /// Sell every dust holding in every wallet once, then burn and close what's configured
+    pub async fn sweep(&self) -> DustSweepReport {
+        let _guard = self.sweep_lock.lock().await;
+        let mut report = DustSweepReport {
+            swept_tokens: 0,
+            reclaimed_sol: 0.0,
+            skipped_tokens: 0,
+            burned_tokens: 0,
+            closed_accounts: 0,
+            reclaimed_rent_sol: 0.0,
+            timestamp: Utc::now(),
+        };
+        for wallet in self.wallet_pool.all() {
+            let owner = wallet.get_public_key();
+            let accounts = match wallet.solana_client().get_token_accounts(&owner).await {
+                Ok(accounts) => accounts,
+                Err(e) => {
+                    warn!("Dust sweep: failed to list token accounts of {}: {:?}", owner, e);
+                    continue;
+                }
+            };
+            let mut cleanup = Vec::new();
+            for holding in accounts {
+                match self.sweep_holding(wallet, &holding).await {
+                    HoldingOutcome::Sold(sol) => {
+                        report.swept_tokens += 1;
+                        report.reclaimed_sol += sol;
+                    }
+                    HoldingOutcome::Close { burn } => cleanup.push((holding, burn)),
+                    HoldingOutcome::Skipped if holding.raw_amount > 0 => report.skipped_tokens += 1,
+                    HoldingOutcome::Skipped => {}
+                }
+            }
+            for batch in cleanup.chunks(CLEANUP_ACCOUNTS_PER_TX) {
+                self.close_accounts(wallet, batch, &mut report).await;
+            }
+        }
+
+        if report.changed_anything() {
+            info!(
+                "🧹 Dust sweep reclaimed {:.6} SOL from {} tokens and {:.6} SOL rent from {} closed accounts ({} burned, {} skipped)",
+                report.reclaimed_sol, report.swept_tokens, report.reclaimed_rent_sol,
+                report.closed_accounts, report.burned_tokens, report.skipped_tokens
+            );
+        } else {
+            debug!("Dust sweep found nothing to sell or close ({} holdings skipped)", report.skipped_tokens);
+        }
+        report
+    }
+
+    /// Burn and close one batch of token accounts in a single transaction
+    async fn close_accounts(&self, wallet: &Arc<WalletManager>, batch: &[(TokenHolding, bool)], report: &mut DustSweepReport) {
+        let owner = wallet.get_public_key();
+        let mut instructions = Vec::new();
+        for (holding, burn) in batch {
+            match cleanup_instructions(holding, &owner, *burn) {
+                Ok(ixs) => instructions.extend(ixs),
+                Err(e) => {
+                    warn!("Dust sweep: can't close token account {}: {:?}", holding.account, e);
+                    return;
+                }
+            }
+        }
+        match wallet.send_instructions(&instructions, self.config.confirm_timeout_secs).await {
+            Ok(signature) => {
+                let rent: u64 = batch.iter().map(|(h, _)| h.lamports).sum();
+                let burned = batch.iter().filter(|(h, burn)| *burn && h.raw_amount > 0).count();
+                info!("🧹 Closed {} token accounts ({} burned): {}", batch.len(), burned, signature);
+                report.closed_accounts += batch.len();
+                report.burned_tokens += burned;
+                report.reclaimed_rent_sol += rent as f64 / 1_000_000_000.0;
+            }
+            // e.g. a Token-2022 account still holding withheld transfer fees
+            Err(e) => warn!("Dust sweep: closing {} token accounts of {} failed: {:?}", batch.len(), owner, e),
+        }
+    }
+
+    /// Whether the mint's quote or sell failed within the recheck window
+    async fn recently_unsellable(&self, mint: &str) -> bool {
+        let mut unsellable = self.unsellable.write().await;
+        match unsellable.get(mint) {
+            Some(failed_at) if failed_at.elapsed() < Duration::from_secs(UNSELLABLE_RECHECK_SECS) => true,
+            Some(_) => {
+                unsellable.remove(mint);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Sell one holding if it is dust, or mark its account for closing
+    async fn sweep_holding(&self, wallet: &Arc<WalletManager>, holding: &TokenHolding) -> HoldingOutcome {
+        if self.config.dust_sweep_keep_mints.contains(&holding.mint)
+            || self.position_manager.has_active_position(&holding.mint).await
+            || self.position_manager.has_trade_in_flight(&holding.mint).await
+        {
+            return HoldingOutcome::Skipped;
+        }
+        if holding.raw_amount == 0 {
+            return match self.config.dust_sweep_close_empty_accounts {
+                true => HoldingOutcome::Close { burn: false },
+                false => HoldingOutcome::Skipped,
+            };
+        }
+        if holding.mint == SOL_MINT || self.recently_unsellable(&holding.mint).await {
+            return HoldingOutcome::Skipped;
+        }
+
+        let slippage_bps = self.config.live().default_slippage_bps;
+        let value_sol = self.jupiter_client
+            .get_quote(&holding.mint, SOL_MINT, holding.raw_amount, slippage_bps)
+            .await
+            .ok()
+            .and_then(|q| q.out_amount.parse::<u64>().ok())
+            .map(|lamports| lamports as f64 / 1_000_000_000.0);
+
+        match dust_decision(
+            value_sol,
+            self.config.dust_sweep_max_value_sol,
+            self.config.dust_sweep_min_value_sol,
+            self.config.dust_sweep_sell_orphans.then_some(self.config.dust_sweep_orphan_max_value_sol),
+        ) {
+            DustDecision::Sweep => {}
+            DustDecision::Unsellable => {
+                // A failed quote may be transient, so these are never burned, only retried later
+                debug!("Dust sweep: no route to SOL for {}, retrying in {}h", holding.mint, UNSELLABLE_RECHECK_SECS / 3600);
+                self.unsellable.write().await.insert(holding.mint.clone(), Instant::now());
+                return HoldingOutcome::Skipped;
+            }
+            DustDecision::NotWorthFee if self.config.dust_sweep_burn_worthless => {
+                debug!("Dust sweep: burning {} (~{:.6} SOL, not worth the swap fee)", holding.mint, value_sol.unwrap_or(0.0));
+                return HoldingOutcome::Close { burn: true };
+            }
+            decision => {
+                debug!("Dust sweep: skipping {} ({:?}, ~{:.6} SOL)", holding.mint, decision, value_sol.unwrap_or(0.0));
+                return HoldingOutcome::Skipped;
+            }
+        }
+
+        match self.jupiter_client.swap_token_to_sol(
+            &holding.mint,
+            holding.decimals,
+            holding.ui_amount,
+            slippage_bps,
+            Some(self.config.live().default_priority_fee_micro_lamports),
+            SwapRoute::default(),
+            wallet.clone(),
+        ).await {
+            Ok(result) => {
+                let sol = result.actual_out_amount_ui.unwrap_or(result.out_amount_ui);
+                info!("🧹 Swept {} {} to {:.6} SOL: {}", holding.ui_amount, holding.mint, sol, result.transaction_signature);
+                HoldingOutcome::Sold(sol)
+            }
+            Err(e) => {
+                warn!("Dust sweep: selling {} failed (possible honeypot), retrying in {}h: {}", holding.mint, UNSELLABLE_RECHECK_SECS / 3600, e);
+                self.unsellable.write().await.insert(holding.mint.clone(), Instant::now());
+                HoldingOutcome::Skipped
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_sells_dust_worth_the_fee() {
+        assert_eq!(dust_decision(Some(0.005), 0.01, 0.0005, None), DustDecision::Sweep);
+        assert_eq!(dust_decision(Some(0.5), 0.01, 0.0005, None), DustDecision::NotDust);
+        assert_eq!(dust_decision(Some(0.0001), 0.01, 0.0005, None), DustDecision::NotWorthFee);
+        assert_eq!(dust_decision(None, 0.01, 0.0005, None), DustDecision::Unsellable);
+    }
+
+    #[test]
+    fn sells_orphans_above_the_ceiling_when_enabled() {
+        assert_eq!(dust_decision(Some(0.4), 0.01, 0.0005, Some(0.5)), DustDecision::Sweep);
+        assert_eq!(dust_decision(Some(0.0001), 0.01, 0.0005, Some(0.5)), DustDecision::NotWorthFee);
+        assert_eq!(dust_decision(None, 0.01, 0.0005, Some(0.5)), DustDecision::Unsellable);
+    }
+
+    #[test]
+    fn never_sells_orphans_above_their_cap() {
+        assert_eq!(dust_decision(Some(0.5), 0.01, 0.0005, Some(0.5)), DustDecision::NotDust);
+        assert_eq!(dust_decision(Some(3.0), 0.01, 0.0005, Some(0.5)), DustDecision::NotDust);
+        // A cap below the dust ceiling still lets dust through
+        assert_eq!(dust_decision(Some(0.005), 0.01, 0.0005, Some(0.001)), DustDecision::Sweep);
+    }
+}
@@ -0,0 +1,341 @@
+//! Escalation Module
+//!
+//! Raises high-priority incidents for situations that need a human: positions
+//! that can't be sold (swap failures or a collapsed pool), buys whose tokens never
+//! reached the wallet, an RPC that has been unreachable for too long, an RPC
+//! lagging too many slots behind, and buys paused by a run of failed swaps.
+//! Each incident is deduplicated by key and notified once, then re-notified every
+//! `escalation_repeat_minutes` until it is acknowledged or resolved.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+use tokio::time::{interval, Duration};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::api::swap_breaker::SwapCircuitBreaker;
+use crate::config::Config;
+use crate::solana::client::SolanaClient;
+
+/// How often the monitor checks RPC health and due re-notifications
+const MONITOR_INTERVAL_SECS: u64 = 30;
+
+/// Dedupe key for the RPC-unreachable incident
+const RPC_INCIDENT_KEY: &str = "rpc_unreachable";
+
+/// Dedupe key for the RPC slot-lag incident
+const SLOT_LAG_INCIDENT_KEY: &str = "rpc_slot_lag";
+
+/// Dedupe key for the swap circuit breaker incident
+const SWAP_BREAKER_INCIDENT_KEY: &str = "swap_failures";
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentKind {
+    /// A position's exit swap failed and the tokens are still held
+    UnsellablePosition,
+    /// An exit was held back because pool liquidity is below the configured floor
+    IlliquidExit,
+    /// A buy confirmed but the wallet doesn't hold the tokens; no position was opened
+    MissingBuyTokens,
+    /// The Solana RPC has failed health checks for longer than the threshold
+    RpcUnreachable,
+    /// The active RPC is more than `max_rpc_slot_lag` slots behind
+    RpcSlotLag,
+    /// Consecutive swap failures tripped the circuit breaker; new buys are paused
+    SwapFailures,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Incident {
+    pub id: String,
+    pub key: String,
+    pub kind: IncidentKind,
+    pub message: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_notified: DateTime<Utc>,
+    pub notify_count: u32,
+    pub acknowledged: bool,
+    pub resolved: bool,
+}
+
+pub struct EscalationManager {
+    /// Incidents keyed by dedupe key (e.g. "unsellable:<position_id>")
+    incidents: Arc<RwLock<HashMap<String, Incident>>>,
+    /// Every (re-)notification is published here for the web layer to forward
+    notify_tx: broadcast::Sender<Incident>,
+    config: Arc<Config>,
+    rpc_down_since: Arc<RwLock<Option<DateTime<Utc>>>>,
+}
+
+impl EscalationManager {
+    pub fn new(config: Arc<Config>) -> Self {
+        let (notify_tx, _) = broadcast::channel(32);
+        Self {
+            incidents: Arc::new(RwLock::new(HashMap::new())),
+            notify_tx,
+            config,
+            rpc_down_since: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Receive incident notifications (first escalation and each repeat)
+    pub fn subscribe(&self) -> broadcast::Receiver<Incident> {
+        self.notify_tx.subscribe()
+    }
+
+    /// Raise an incident. Does nothing if an unresolved incident with the same key exists.
+    pub async fn raise(&self, key: &str, kind: IncidentKind, message: String) {
+        if !self.config.escalation_enabled {
+            return;
+        }
+
+        let mut incidents = self.incidents.write().await;
+        if incidents.get(key).is_some_and(|i| !i.resolved) {
+            return;
+        }
+
+        let now = Utc::now();
+        let incident = Incident {
+            id: Uuid::new_v4().to_string(),
+            key: key.to_string(),
+            kind,
+            message,
+            first_seen: now,
+            last_notified: now,
+            notify_count: 1,
+            acknowledged: false,
+            resolved: false,
+        };
+        incidents.insert(key.to_string(), incident.clone());
+        drop(incidents);
+
+        self.notify(&incident);
+    }
+
+    /// Mark the incident for `key` as resolved so it stops repeating
+    pub async fn resolve(&self, key: &str) {
+        if let Some(incident) = self.incidents.write().await.get_mut(key) {
+            if !incident.resolved {
+                incident.resolved = true;
+                info!("Incident resolved: {} ({})", incident.key, incident.id);
+            }
+        }
+    }
+
+    /// Acknowledge an incident by id. Returns false if no such incident exists.
+    pub async fn acknowledge(&self, id: &str) -> bool {
+        let mut incidents = self.incidents.write().await;
+        match incidents.values_mut().find(|i| i.id == id) {
+            Some(incident) => {
+                incident.acknowledged = true;
+                info!("Incident acknowledged: {} ({})", incident.key, incident.id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// All known incidents, newest first
+    pub async fn list(&self) -> Vec<Incident> {
+        let mut incidents: Vec<Incident> = self.incidents.read().await.values().cloned().collect();
+        incidents.sort_by(|a, b| b.first_seen.cmp(&a.first_seen));
+        incidents
+    }
+
+    fn notify(&self, incident: &Incident) {
+        error!(
+            "🚨 ESCALATION [{:?}] {} (incident {}, notice #{})",
+            incident.kind, incident.message, incident.id, incident.notify_count
+        );
+        // Ignore errors (no subscribers)
+        let _ = self.notify_tx.send(incident.clone());
+    }
+
+    /// Re-notify open, unacknowledged incidents whose repeat interval has elapsed
+    async fn repeat_due(&self, now: DateTime<Utc>) {
+        let repeat_minutes = self.config.escalation_repeat_minutes;
+        if repeat_minutes == 0 {
+            return;
+        }
+
+        let repeat_after = ChronoDuration::minutes(repeat_minutes as i64);
+        let mut due = Vec::new();
+        {
+            let mut incidents = self.incidents.write().await;
+            for incident in incidents.values_mut() {
+                if !incident.resolved && !incident.acknowledged && now - incident.last_notified >= repeat_after {
+                    incident.last_notified = now;
+                    incident.notify_count += 1;
+                    due.push(incident.clone());
+                }
+            }
+        }
+
+        for incident in &due {
+            self.notify(incident);
+        }
+    }
+
+    /// Track RPC health, escalating once it has been down longer than the threshold
+    async fn check_rpc(&self, solana_client: &SolanaClient) {
+        match solana_client.get_rpc().get_slot().await {
+            Ok(_) => {
+                if self.rpc_down_since.write().await.take().is_some() {
+                    info!("Solana RPC reachable again");
+                    self.resolve(RPC_INCIDENT_KEY).await;
+                }
+            }
+            Err(e) => {
+                let now = Utc::now();
+                let down_since = *self.rpc_down_since.write().await.get_or_insert(now);
+                let down_for = now - down_since;
+                if down_for >= ChronoDuration::seconds(self.config.escalation_rpc_down_secs as i64) {
+                    self.raise(
+                        RPC_INCIDENT_KEY,
+                        IncidentKind::RpcUnreachable,
+                        format!("Solana RPC unreachable for {}s: {}", down_for.num_seconds(), e),
+                    ).await;
+                }
+            }
+        }
+    }
+
+    /// Escalate while the slot-lag monitor reports the RPC as lagging
+    async fn check_slot_lag(&self, solana_client: &SolanaClient) {
+        match solana_client.slot_lag_status() {
+            Some(status) if status.lagging => {
+                self.raise(
+                    SLOT_LAG_INCIDENT_KEY,
+                    IncidentKind::RpcSlotLag,
+                    format!(
+                        "Solana RPC is {} slots behind (limit {}); new buys are paused",
+                        status.lag_slots, self.config.max_rpc_slot_lag
+                    ),
+                ).await;
+            }
+            Some(_) => self.resolve(SLOT_LAG_INCIDENT_KEY).await,
+            None => {}
+        }
+    }
+
+    /// Escalate while the swap circuit breaker is holding back buys
+    async fn check_swap_breaker(&self, swap_breaker: &SwapCircuitBreaker) {
+        let status = swap_breaker.status();
+        if status.paused {
+            self.raise(
+                SWAP_BREAKER_INCIDENT_KEY,
+                IncidentKind::SwapFailures,
+                format!(
+                    "{} swaps failed in a row (last: {}); new buys paused for {}s",
+                    status.consecutive_failures,
+                    status.last_error.map_or("unknown".to_string(), |e| e.to_string()),
+                    status.paused_secs_remaining.unwrap_or(0)
+                ),
+            ).await;
+        } else {
+            self.resolve(SWAP_BREAKER_INCIDENT_KEY).await;
+        }
+    }
+
+    /// Spawn the background loop that checks RPC health and repeats open incidents
+    pub fn start_monitoring(self: Arc<Self>, solana_client: Arc<SolanaClient>, swap_breaker: Arc<SwapCircuitBreaker>) {
+        if !self.config.escalation_enabled {
+            info!("Escalation disabled");
+            return;
+        }
+
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(MONITOR_INTERVAL_SECS));
+            loop {
+                ticker.tick().await;
+                self.check_rpc(&solana_client).await;
+                self.check_slot_lag(&solana_client).await;
+                self.check_swap_breaker(&swap_breaker).await;
+                self.repeat_due(Utc::now()).await;
+            }
+        });
+    }
+}
+
+/// Dedupe key for an unsellable-position incident
+pub fn unsellable_key(position_id: &str) -> String {
+    format!("unsellable:{}", position_id)
+}
+
+/// Dedupe key for a held-back (illiquid) exit incident
+pub fn illiquid_key(position_id: &str) -> String {
+    format!("illiquid:{}", position_id)
+}
+
+/// Dedupe key for a buy whose tokens never arrived
+pub fn missing_tokens_key(signature: &str) -> String {
+    format!("missing_tokens:{}", signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(extra: &[(&str, &str)]) -> EscalationManager {
+        EscalationManager::new(Arc::new(Config::for_tests(extra)))
+    }
+
+    #[tokio::test]
+    async fn incident_repeats_until_acknowledged_and_reopens_after_resolve() {
+        let escalation = manager(&[("ESCALATION_REPEAT_MINUTES", "15")]);
+        let mut notices = escalation.subscribe();
+        let key = unsellable_key("p1");
+
+        escalation.raise(&key, IncidentKind::UnsellablePosition, "exit failed".to_string()).await;
+        let first = notices.try_recv().unwrap();
+        assert_eq!(first.notify_count, 1);
+
+        // A second raise for the same open incident is deduplicated
+        escalation.raise(&key, IncidentKind::UnsellablePosition, "exit failed again".to_string()).await;
+        assert!(notices.try_recv().is_err());
+        assert_eq!(escalation.list().await.len(), 1);
+
+        // Re-notified only once the repeat interval has passed
+        escalation.repeat_due(first.last_notified + ChronoDuration::minutes(14)).await;
+        assert!(notices.try_recv().is_err());
+        escalation.repeat_due(first.last_notified + ChronoDuration::minutes(15)).await;
+        let repeat = notices.try_recv().unwrap();
+        assert_eq!(repeat.id, first.id);
+        assert_eq!(repeat.notify_count, 2);
+
+        // Acknowledged incidents stay open but stop repeating
+        assert!(!escalation.acknowledge("unknown").await);
+        assert!(escalation.acknowledge(&first.id).await);
+        escalation.repeat_due(first.last_notified + ChronoDuration::minutes(60)).await;
+        assert!(notices.try_recv().is_err());
+        escalation.raise(&key, IncidentKind::UnsellablePosition, "still failing".to_string()).await;
+        assert!(notices.try_recv().is_err());
+
+        // Once resolved, the same key raises a fresh incident
+        escalation.resolve(&key).await;
+        assert!(escalation.list().await[0].resolved);
+        escalation.raise(&key, IncidentKind::UnsellablePosition, "failed after recovery".to_string()).await;
+        let reopened = notices.try_recv().unwrap();
+        assert_ne!(reopened.id, first.id);
+        assert_eq!(reopened.notify_count, 1);
+        assert!(!reopened.acknowledged);
+    }
+
+    #[tokio::test]
+    async fn disabled_escalation_and_zero_repeat_interval_stay_quiet() {
+        let disabled = manager(&[("ESCALATION_ENABLED", "false")]);
+        disabled.raise(RPC_INCIDENT_KEY, IncidentKind::RpcUnreachable, "down".to_string()).await;
+        assert!(disabled.list().await.is_empty());
+
+        let notify_once = manager(&[("ESCALATION_REPEAT_MINUTES", "0")]);
+        let mut notices = notify_once.subscribe();
+        notify_once.raise(RPC_INCIDENT_KEY, IncidentKind::RpcUnreachable, "down".to_string()).await;
+        let first = notices.try_recv().unwrap();
+        notify_once.repeat_due(first.last_notified + ChronoDuration::days(1)).await;
+        assert!(notices.try_recv().is_err());
+    }
+}
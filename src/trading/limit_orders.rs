@@ -0,0 +1,284 @@
+//! Limit-style entries that wait for a token's price to fall to a target
+//!
+//! Orders come from the API (manual buys) or from strategies with `limit_entry` set,
+//! which turn a matching token into an order below its current price instead of buying
+//! at market. The AutoTrader's order monitor prices each pending order through Jupiter
+//! and buys once the price is at or below the target; orders still open at expiry are
+//! cancelled. Every order that leaves Pending is sent to subscribers for notification.
+//! Orders are persisted through the configured [`Store`].
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, RwLock};
+use tracing::{info, warn};
+
+use crate::storage::Store;
+
+/// Finished orders kept for the order list; older ones are dropped on save
+const MAX_FINISHED_ORDERS: usize = 200;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitOrderStatus {
+    Pending,
+    Filled,
+    /// Target never reached before `expires_at`
+    Expired,
+    Cancelled,
+    /// Target reached but the buy failed
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitOrder {
+    pub id: String,
+    pub token_address: String,
+    pub token_name: String,
+    pub token_symbol: String,
+    pub token_decimals: u8,
+    pub target_price_sol: f64,      // Buy once the price is at or below this
+    pub amount_sol: f64,
+    pub strategy_id: Option<String>, // Strategy that placed the order (None = manual, uses the default strategy)
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub status: LimitOrderStatus,
+    pub last_price_sol: Option<f64>, // Most recent price seen by the monitor
+    pub closed_at: Option<DateTime<Utc>>,
+    pub signature: Option<String>,  // Buy transaction, once filled
+    pub error: Option<String>,
+}
+
+/// What the monitor should do with a pending order this check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderAction {
+    Wait,
+    Fill,
+    Expire,
+}
+
+/// An expired order never fills, even if its target was reached on the same check
+pub fn order_action(order: &LimitOrder, price_sol: Option<f64>, now: DateTime<Utc>) -> OrderAction {
+    if now >= order.expires_at {
+        return OrderAction::Expire;
+    }
+    match price_sol {
+        Some(price) if price > 0.0 && price <= order.target_price_sol => OrderAction::Fill,
+        _ => OrderAction::Wait,
+    }
+}
+
+pub struct LimitOrderBook {
+    orders: RwLock<HashMap<String, LimitOrder>>,
+    store: Arc<dyn Store>,
+    monitoring: AtomicBool,
+    update_tx: broadcast::Sender<LimitOrder>,
+}
+
+impl LimitOrderBook {
+    pub fn new(store: Arc<dyn Store>) -> Self {
+        Self {
+            orders: RwLock::new(HashMap::new()),
+            store,
+            monitoring: AtomicBool::new(false),
+            update_tx: broadcast::channel(32).0,
+        }
+    }
+
+    /// Receive each order as it fills, expires, fails or is cancelled
+    pub fn subscribe(&self) -> broadcast::Receiver<LimitOrder> {
+        self.update_tx.subscribe()
+    }
+
+    /// Claim the monitor; false if it is already running
+    pub fn claim_monitor(&self) -> bool {
+        !self.monitoring.swap(true, Ordering::SeqCst)
+    }
+
+    pub async fn place(&self, order: LimitOrder) -> Result<LimitOrder> {
+        if !order.target_price_sol.is_finite() || order.target_price_sol <= 0.0 {
+            return Err(anyhow!("Target price must be positive"));
+        }
+        if !order.amount_sol.is_finite() || order.amount_sol <= 0.0 {
+            return Err(anyhow!("Order amount must be positive"));
+        }
+        if order.expires_at <= order.created_at {
+            return Err(anyhow!("Order must expire after it is placed"));
+        }
+
+        let mut orders = self.orders.write().await;
+        if orders.values().any(|o| o.status == LimitOrderStatus::Pending && o.token_address == order.token_address) {
+            return Err(anyhow!("A limit order for {} is already pending", order.token_address));
+        }
+        info!(
+            "📌 Limit order {} placed: {} SOL of {} at <= {:.9} SOL, expires {}",
+            order.id, order.amount_sol, order.token_symbol, order.target_price_sol, order.expires_at
+        );
+        orders.insert(order.id.clone(), order.clone());
+        drop(orders);
+
+        self.save().await?;
+        Ok(order)
+    }
+
+    /// All orders, newest first
+    pub async fn list(&self) -> Vec<LimitOrder> {
+        let mut orders: Vec<LimitOrder> = self.orders.read().await.values().cloned().collect();
+        orders.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        orders
+    }
+
+    pub async fn pending(&self) -> Vec<LimitOrder> {
+        self.orders.read().await.values()
+            .filter(|o| o.status == LimitOrderStatus::Pending)
+            .cloned()
+            .collect()
+    }
+
+    pub async fn has_pending(&self, token_address: &str) -> bool {
+        self.orders.read().await.values()
+            .any(|o| o.status == LimitOrderStatus::Pending && o.token_address == token_address)
+    }
+
+    pub async fn record_price(&self, id: &str, price_sol: f64) {
+        if let Some(order) = self.orders.write().await.get_mut(id) {
+            order.last_price_sol = Some(price_sol);
+        }
+    }
+
+    pub async fn cancel(&self, id: &str) -> Result<LimitOrder> {
+        self.finish(id, LimitOrderStatus::Cancelled, None, None).await
+    }
+
+    /// Move a pending order to its final status and notify subscribers
+    pub async fn finish(
+        &self,
+        id: &str,
+        status: LimitOrderStatus,
+        signature: Option<String>,
+        error: Option<String>,
+    ) -> Result<LimitOrder> {
+        let mut orders = self.orders.write().await;
+        let order = orders.get_mut(id).ok_or_else(|| anyhow!("Limit order {} not found", id))?;
+        if order.status != LimitOrderStatus::Pending {
+            return Err(anyhow!("Limit order {} is already {:?}", id, order.status));
+        }
+        order.status = status;
+        order.closed_at = Some(Utc::now());
+        order.signature = signature;
+        order.error = error;
+        let finished = order.clone();
+        drop(orders);
+
+        match status {
+            LimitOrderStatus::Failed => warn!("Limit order {} for {} failed: {}", id, finished.token_symbol, finished.error.as_deref().unwrap_or("unknown error")),
+            _ => info!("📌 Limit order {} for {} {:?}", id, finished.token_symbol, status),
+        }
+        if let Err(e) = self.save().await {
+            warn!("Failed to save limit orders: {:?}", e);
+        }
+        let _ = self.update_tx.send(finished.clone());
+        Ok(finished)
+    }
+
+    /// Replace every order with `orders`, e.g. from an imported state bundle
+    pub async fn replace(&self, orders: Vec<LimitOrder>) -> Result<()> {
+        let mut current = self.orders.write().await;
+        *current = orders.into_iter().map(|o| (o.id.clone(), o)).collect();
+        info!("Replaced limit orders with {} imported orders", current.len());
+        drop(current);
+        self.save().await
+    }
+
+    /// Load orders from the store
+    pub async fn load(&self) -> Result<()> {
+        let loaded = self.store.load_limit_orders().await?;
+        let mut orders = self.orders.write().await;
+        *orders = loaded.into_iter().map(|o| (o.id.clone(), o)).collect();
+
+        let pending = orders.values().filter(|o| o.status == LimitOrderStatus::Pending).count();
+        info!("📂 Loaded {} limit orders ({} pending)", orders.len(), pending);
+        Ok(())
+    }
+
+    /// Save orders to the store, keeping only the most recent finished ones
+    pub async fn save(&self) -> Result<()> {
+        let mut orders = self.orders.write().await;
+        let mut finished: Vec<(String, DateTime<Utc>)> = orders.values()
+            .filter(|o| o.status != LimitOrderStatus::Pending)
+            .map(|o| (o.id.clone(), o.closed_at.unwrap_or(o.created_at)))
+            .collect();
+        if finished.len() > MAX_FINISHED_ORDERS {
+            finished.sort_by(|a, b| b.1.cmp(&a.1));
+            for (id, _) in finished.drain(MAX_FINISHED_ORDERS..) {
+                orders.remove(&id);
+            }
+        }
+
+        self.store.save_limit_orders(&orders.values().collect::<Vec<_>>()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::JsonStore;
+    use chrono::Duration;
+
+    fn order(target: f64) -> LimitOrder {
+        let now = Utc::now();
+        LimitOrder {
+            id: "o1".to_string(),
+            token_address: "mint".to_string(),
+            token_name: "Token".to_string(),
+            token_symbol: "TKN".to_string(),
+            token_decimals: 6,
+            target_price_sol: target,
+            amount_sol: 0.1,
+            strategy_id: None,
+            created_at: now,
+            expires_at: now + Duration::minutes(30),
+            status: LimitOrderStatus::Pending,
+            last_price_sol: None,
+            closed_at: None,
+            signature: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn fills_at_or_below_target_until_expiry() {
+        let order = order(0.001);
+        let now = Utc::now();
+        assert_eq!(order_action(&order, Some(0.002), now), OrderAction::Wait);
+        assert_eq!(order_action(&order, None, now), OrderAction::Wait);
+        assert_eq!(order_action(&order, Some(0.001), now), OrderAction::Fill);
+        assert_eq!(order_action(&order, Some(0.0005), now), OrderAction::Fill);
+        assert_eq!(order_action(&order, Some(0.0005), now + Duration::minutes(31)), OrderAction::Expire);
+    }
+
+    #[tokio::test]
+    async fn one_pending_order_per_token() {
+        let dir = std::env::temp_dir().join(format!("limit_orders_{}", uuid::Uuid::new_v4()));
+        let store = Arc::new(JsonStore::with_paths(dir.join("positions.json"), dir.join("strategies.json")));
+        let book = LimitOrderBook::new(store);
+        book.place(order(0.001)).await.unwrap();
+        assert!(book.place(LimitOrder { id: "o2".to_string(), ..order(0.002) }).await.is_err());
+
+        book.cancel("o1").await.unwrap();
+        assert!(book.cancel("o1").await.is_err());
+        assert!(!book.has_pending("mint").await);
+        assert!(book.place(LimitOrder { id: "o2".to_string(), ..order(0.002) }).await.is_ok());
+
+        // Saved orders come back on the next start
+        let reloaded = LimitOrderBook::new(Arc::new(JsonStore::with_paths(dir.join("positions.json"), dir.join("strategies.json"))));
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.list().await.len(), 2);
+        assert!(reloaded.has_pending("mint").await);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
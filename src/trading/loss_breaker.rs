@@ -0,0 +1,358 @@
+//! Circuit breaker for losing streaks
+//!
+//! In a bad market window a strategy can keep buying into tokens that all dump.
+//! After `consecutive_losses` losing closes in a row a strategy's new buys are
+//! paused for `cooldown`; once the day's realized PnL (UTC) across all strategies
+//! falls to `-daily_drawdown_sol`, every strategy is paused for the same cooldown.
+//! A winning close ends a strategy's streak. Buys resume on their own when the
+//! cooldown runs out, or when an operator resets the breaker. Trips and resumes
+//! are broadcast so the web layer can notify.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::config::Config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LossBreakerReason {
+    ConsecutiveLosses,
+    DailyDrawdown,
+}
+
+/// A pause starting or ending. `strategy_id` is None for the global (daily drawdown) pause.
+#[derive(Debug, Clone, Serialize)]
+pub struct LossBreakerEvent {
+    pub strategy_id: Option<String>,
+    pub reason: LossBreakerReason,
+    pub tripped: bool,                       // false = buys resumed
+    pub consecutive_losses: u32,
+    pub daily_pnl_sol: f64,
+    pub paused_until: Option<DateTime<Utc>>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Why buys are paused and until when
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LossPause {
+    pub strategy_id: Option<String>,
+    pub reason: LossBreakerReason,
+    pub until: DateTime<Utc>,
+}
+
+/// Breaker state for the status endpoints
+#[derive(Debug, Clone, Serialize)]
+pub struct LossBreakerStatus {
+    pub enabled: bool,
+    pub consecutive_loss_limit: u32,
+    pub daily_drawdown_limit_sol: Option<f64>,
+    pub daily_pnl_sol: f64,
+    pub paused: Vec<LossPause>,
+    pub losing_streaks: HashMap<String, u32>, // Strategy ID -> consecutive losing closes
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    streaks: HashMap<String, u32>,
+    strategy_pauses: HashMap<String, DateTime<Utc>>,
+    global_pause: Option<DateTime<Utc>>,
+    day: Option<NaiveDate>,
+    daily_pnl_sol: f64,
+}
+
+impl BreakerState {
+    /// Start a fresh daily tally at UTC midnight
+    fn roll_day(&mut self, now: DateTime<Utc>) {
+        let today = now.date_naive();
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.daily_pnl_sol = 0.0;
+        }
+    }
+}
+
+pub struct LossCircuitBreaker {
+    consecutive_losses: u32,
+    daily_drawdown_sol: Option<f64>,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+    events_tx: broadcast::Sender<LossBreakerEvent>,
+}
+
+impl LossCircuitBreaker {
+    /// `consecutive_losses` of 0 and no `daily_drawdown_sol` disable the breaker
+    pub fn new(consecutive_losses: u32, daily_drawdown_sol: Option<f64>, cooldown_minutes: u64) -> Self {
+        Self {
+            consecutive_losses,
+            daily_drawdown_sol: daily_drawdown_sol.filter(|d| *d > 0.0),
+            cooldown: Duration::minutes(cooldown_minutes.max(1) as i64),
+            state: Mutex::new(BreakerState::default()),
+            events_tx: broadcast::channel(16).0,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.loss_breaker_consecutive_losses, config.loss_breaker_daily_drawdown_sol, config.loss_breaker_cooldown_minutes)
+    }
+
+    /// Receive every trip and automatic resume
+    pub fn subscribe(&self) -> broadcast::Receiver<LossBreakerEvent> {
+        self.events_tx.subscribe()
+    }
+
+    fn send(&self, event: LossBreakerEvent) {
+        // Ignore errors (no subscribers)
+        let _ = self.events_tx.send(event);
+    }
+
+    /// Count a closed trade; returns the pause it tripped, if any
+    pub fn record_close(&self, strategy_id: &str, pnl_sol: f64, now: DateTime<Utc>) -> Option<LossPause> {
+        let mut state = self.state.lock().unwrap();
+        let (pause, streak) = self.apply_close(&mut state, strategy_id, pnl_sol, now)?;
+        let daily_pnl_sol = state.daily_pnl_sol;
+        match &pause.strategy_id {
+            None => warn!(
+                "🛑 Daily realized PnL {:.4} SOL hit the -{:.4} SOL drawdown limit; pausing all buys for {} min",
+                daily_pnl_sol, self.daily_drawdown_sol.unwrap_or_default(), self.cooldown.num_minutes()
+            ),
+            Some(id) => warn!(
+                "🛑 Strategy {} closed {} losing trades in a row; pausing its buys for {} min",
+                id, streak, self.cooldown.num_minutes()
+            ),
+        }
+        self.send(LossBreakerEvent {
+            strategy_id: pause.strategy_id.clone(),
+            reason: pause.reason,
+            tripped: true,
+            consecutive_losses: streak,
+            daily_pnl_sol,
+            paused_until: Some(pause.until),
+            timestamp: now,
+        });
+        Some(pause)
+    }
+
+    /// Rebuild today's PnL, the losing streaks and any pause still running from
+    /// past closes (strategy, PnL, exit time), so a restart doesn't wipe them.
+    /// Nothing is broadcast: these trips were already announced.
+    pub fn restore(&self, closes: impl IntoIterator<Item = (String, f64, DateTime<Utc>)>, now: DateTime<Utc>) {
+        let mut closes: Vec<_> = closes.into_iter().collect();
+        closes.sort_by_key(|(_, _, at)| *at);
+        let mut state = self.state.lock().unwrap();
+        *state = BreakerState::default();
+        for (strategy_id, pnl_sol, at) in &closes {
+            self.apply_close(&mut state, strategy_id, *pnl_sol, *at);
+        }
+        state.roll_day(now);
+        state.global_pause = state.global_pause.filter(|u| *u > now);
+        state.strategy_pauses.retain(|_, until| *until > now);
+        if !closes.is_empty() {
+            info!(
+                "Loss circuit breaker restored from {} closed trades (today's PnL {:.4} SOL, {} pauses active)",
+                closes.len(), state.daily_pnl_sol, state.strategy_pauses.len() + usize::from(state.global_pause.is_some())
+            );
+        }
+    }
+
+    /// Update the tally for one close; returns the pause it tripped and the streak that tripped it
+    fn apply_close(&self, state: &mut BreakerState, strategy_id: &str, pnl_sol: f64, now: DateTime<Utc>) -> Option<(LossPause, u32)> {
+        state.roll_day(now);
+        state.daily_pnl_sol += pnl_sol;
+
+        let streak = state.streaks.entry(strategy_id.to_string()).or_insert(0);
+        if pnl_sol < 0.0 {
+            *streak += 1;
+        } else {
+            *streak = 0;
+        }
+        let streak = *streak;
+        let until = now + self.cooldown;
+
+        let global_active = state.global_pause.is_some_and(|u| u > now);
+        if let Some(limit) = self.daily_drawdown_sol {
+            if state.daily_pnl_sol <= -limit && !global_active {
+                state.global_pause = Some(until);
+                return Some((LossPause { strategy_id: None, reason: LossBreakerReason::DailyDrawdown, until }, streak));
+            }
+        }
+
+        let strategy_active = state.strategy_pauses.get(strategy_id).is_some_and(|u| *u > now);
+        if self.consecutive_losses > 0 && streak >= self.consecutive_losses && !strategy_active {
+            state.strategy_pauses.insert(strategy_id.to_string(), until);
+            state.streaks.insert(strategy_id.to_string(), 0);
+            return Some((LossPause { strategy_id: Some(strategy_id.to_string()), reason: LossBreakerReason::ConsecutiveLosses, until }, streak));
+        }
+        None
+    }
+
+    /// The pause holding back a strategy's buys, if any. Expired pauses are cleared
+    /// here and their resume is broadcast.
+    pub fn buy_paused(&self, strategy_id: &str, now: DateTime<Utc>) -> Option<LossPause> {
+        let mut state = self.state.lock().unwrap();
+        state.roll_day(now);
+        let daily_pnl_sol = state.daily_pnl_sol;
+
+        match state.global_pause {
+            Some(until) if until > now => {
+                return Some(LossPause { strategy_id: None, reason: LossBreakerReason::DailyDrawdown, until });
+            }
+            Some(_) => {
+                state.global_pause = None;
+                info!("Loss circuit breaker cooldown over, buys resumed");
+                self.send(LossBreakerEvent {
+                    strategy_id: None,
+                    reason: LossBreakerReason::DailyDrawdown,
+                    tripped: false,
+                    consecutive_losses: 0,
+                    daily_pnl_sol,
+                    paused_until: None,
+                    timestamp: now,
+                });
+            }
+            None => {}
+        }
+
+        match state.strategy_pauses.get(strategy_id).copied() {
+            Some(until) if until > now => Some(LossPause {
+                strategy_id: Some(strategy_id.to_string()),
+                reason: LossBreakerReason::ConsecutiveLosses,
+                until,
+            }),
+            Some(_) => {
+                state.strategy_pauses.remove(strategy_id);
+                info!("Loss circuit breaker cooldown over for strategy {}, buys resumed", strategy_id);
+                self.send(LossBreakerEvent {
+                    strategy_id: Some(strategy_id.to_string()),
+                    reason: LossBreakerReason::ConsecutiveLosses,
+                    tripped: false,
+                    consecutive_losses: 0,
+                    daily_pnl_sol,
+                    paused_until: None,
+                    timestamp: now,
+                });
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Lift every pause now; returns false if nothing was paused
+    pub fn reset(&self) -> bool {
+        let now = Utc::now();
+        let mut state = self.state.lock().unwrap();
+        let was_paused = state.global_pause.is_some_and(|u| u > now) || state.strategy_pauses.values().any(|u| *u > now);
+        state.global_pause = None;
+        state.strategy_pauses.clear();
+        state.streaks.clear();
+        if was_paused {
+            info!("Loss circuit breaker reset, buys resumed");
+        }
+        was_paused
+    }
+
+    pub fn status(&self) -> LossBreakerStatus {
+        let now = Utc::now();
+        let mut state = self.state.lock().unwrap();
+        state.roll_day(now);
+        let mut paused: Vec<LossPause> = state.strategy_pauses.iter()
+            .filter(|(_, until)| **until > now)
+            .map(|(id, until)| LossPause { strategy_id: Some(id.clone()), reason: LossBreakerReason::ConsecutiveLosses, until: *until })
+            .collect();
+        if let Some(until) = state.global_pause.filter(|u| *u > now) {
+            paused.insert(0, LossPause { strategy_id: None, reason: LossBreakerReason::DailyDrawdown, until });
+        }
+        LossBreakerStatus {
+            enabled: self.consecutive_losses > 0 || self.daily_drawdown_sol.is_some(),
+            consecutive_loss_limit: self.consecutive_losses,
+            daily_drawdown_limit_sol: self.daily_drawdown_sol,
+            daily_pnl_sol: state.daily_pnl_sol,
+            paused,
+            losing_streaks: state.streaks.iter().filter(|(_, n)| **n > 0).map(|(id, n)| (id.clone(), *n)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn losing_streak_pauses_only_that_strategy_then_resumes() {
+        let breaker = LossCircuitBreaker::new(3, None, 60);
+        let mut events = breaker.subscribe();
+        assert!(breaker.record_close("a", -0.1, at(0)).is_none());
+        assert!(breaker.record_close("a", -0.1, at(1)).is_none());
+        // A win ends the streak
+        assert!(breaker.record_close("a", 0.2, at(2)).is_none());
+        assert!(breaker.record_close("a", -0.1, at(3)).is_none());
+        assert!(breaker.record_close("a", -0.1, at(4)).is_none());
+        let pause = breaker.record_close("a", -0.1, at(5)).unwrap();
+        assert_eq!(pause.reason, LossBreakerReason::ConsecutiveLosses);
+        assert!(events.try_recv().unwrap().tripped);
+
+        assert!(breaker.buy_paused("a", at(30)).is_some());
+        assert!(breaker.buy_paused("b", at(30)).is_none());
+        assert!(breaker.buy_paused("a", at(66)).is_none());
+        assert!(!events.try_recv().unwrap().tripped);
+    }
+
+    #[test]
+    fn daily_drawdown_pauses_everything_and_resets_at_midnight() {
+        let breaker = LossCircuitBreaker::new(0, Some(1.0), 30);
+        assert!(breaker.record_close("a", -0.6, at(0)).is_none());
+        let pause = breaker.record_close("b", -0.5, at(1)).unwrap();
+        assert_eq!(pause.strategy_id, None);
+        assert!(breaker.buy_paused("c", at(10)).is_some());
+        assert!(breaker.buy_paused("c", at(40)).is_none());
+
+        // A new UTC day starts a new tally
+        let next_day = at(0) + Duration::days(1);
+        assert!(breaker.record_close("a", -0.6, next_day).is_none());
+    }
+
+    #[test]
+    fn restore_rebuilds_todays_pnl_streaks_and_pauses() {
+        let breaker = LossCircuitBreaker::new(3, Some(1.0), 60);
+        let mut events = breaker.subscribe();
+        let yesterday = at(0) - Duration::days(1);
+        breaker.restore(vec![
+            ("a".to_string(), -0.1, at(2)),
+            ("c".to_string(), -5.0, yesterday),
+            ("a".to_string(), -0.1, at(1)),
+            ("b".to_string(), -0.1, at(3)),
+            ("b".to_string(), -0.1, at(4)),
+            ("b".to_string(), -0.1, at(5)),
+        ], at(10));
+        // Yesterday's drawdown pause has expired and its loss no longer counts
+        assert!(breaker.buy_paused("a", at(10)).is_none());
+        assert!(breaker.buy_paused("b", at(10)).is_some());
+        assert!(events.try_recv().is_err());
+
+        // One more loss completes a's streak
+        let pause = breaker.record_close("a", -0.1, at(11)).unwrap();
+        assert_eq!(pause.reason, LossBreakerReason::ConsecutiveLosses);
+        // Today is at -0.6 SOL; another -0.4 reaches the daily limit
+        let pause = breaker.record_close("c", -0.4, at(12)).unwrap();
+        assert_eq!(pause.reason, LossBreakerReason::DailyDrawdown);
+    }
+
+    #[test]
+    fn reset_lifts_pauses() {
+        let breaker = LossCircuitBreaker::new(1, None, 60);
+        breaker.record_close("a", -0.1, Utc::now());
+        assert!(breaker.buy_paused("a", Utc::now()).is_some());
+        assert!(breaker.reset());
+        assert!(breaker.buy_paused("a", Utc::now()).is_none());
+        assert!(!breaker.reset());
+        assert!(!LossCircuitBreaker::new(0, None, 60).status().enabled);
+    }
+}
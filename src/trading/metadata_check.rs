@@ -0,0 +1,256 @@
+//! Token metadata and social-link checks for the risk analyzer
+//!
+//! Honeypots and throwaway launches tend to ship empty or copy-pasted metadata: no
+//! image, no description, no socials, or a "Twitter" that is just someone else's
+//! tweet. The mint's DAS asset from Helius gives the on-chain metadata and the
+//! off-chain JSON URI; the JSON (pump.fun style `website`/`twitter`/`telegram`
+//! fields, or the same under `extensions`) is fetched and the links are scored.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::api::helius::DasAsset;
+
+/// Descriptions shorter than this say nothing about the project
+const MIN_DESCRIPTION_LEN: usize = 20;
+
+/// Off-chain metadata fetches slower than this are treated as missing
+const METADATA_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The parts of an off-chain metadata JSON that matter here
+#[derive(Debug, Default, Deserialize)]
+pub struct OffchainMetadata {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default)]
+    pub website: Option<String>,
+    #[serde(default)]
+    pub twitter: Option<String>,
+    #[serde(default)]
+    pub telegram: Option<String>,
+    #[serde(default)]
+    pub extensions: Option<Value>,
+}
+
+impl OffchainMetadata {
+    /// A top-level field, else the same key under `extensions`
+    fn link(&self, top: &Option<String>, key: &str) -> Option<String> {
+        top.clone()
+            .or_else(|| self.extensions.as_ref()?.get(key)?.as_str().map(String::from))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+/// What a token's metadata says about it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetadataFindings {
+    pub has_offchain_metadata: bool,
+    pub name: String,
+    pub symbol: String,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub website: Option<String>,
+    pub twitter: Option<String>,
+    pub telegram: Option<String>,
+}
+
+impl MetadataFindings {
+    pub fn from_asset(asset: &DasAsset, offchain: Option<&OffchainMetadata>) -> Self {
+        let content = asset.content.as_ref();
+        let onchain = content.and_then(|c| c.metadata.as_ref());
+        let non_empty = |v: Option<String>| v.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        Self {
+            has_offchain_metadata: offchain.is_some(),
+            name: onchain.and_then(|m| m.name.clone()).unwrap_or_default(),
+            symbol: onchain.and_then(|m| m.symbol.clone()).unwrap_or_default(),
+            description: non_empty(offchain.and_then(|o| o.description.clone()))
+                .or_else(|| non_empty(onchain.and_then(|m| m.description.clone()))),
+            image: non_empty(offchain.and_then(|o| o.image.clone()))
+                .or_else(|| non_empty(content.and_then(|c| c.links.as_ref()).and_then(|l| l.image.clone()))),
+            website: offchain.and_then(|o| o.link(&o.website, "website"))
+                .or_else(|| non_empty(content.and_then(|c| c.links.as_ref()).and_then(|l| l.external_url.clone()))),
+            twitter: offchain.and_then(|o| o.link(&o.twitter, "twitter")),
+            telegram: offchain.and_then(|o| o.link(&o.telegram, "telegram")),
+        }
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.host_str().is_some())
+}
+
+fn host_of(url: &str) -> Option<String> {
+    let host = reqwest::Url::parse(url).ok()?.host_str()?.to_lowercase();
+    Some(host.trim_start_matches("www.").to_string())
+}
+
+/// Problem with a Twitter/X link, or None if it points at an account
+fn twitter_problem(url: &str) -> Option<&'static str> {
+    if !matches!(host_of(url).as_deref(), Some("twitter.com" | "x.com" | "mobile.twitter.com")) {
+        return Some("is not a Twitter/X link");
+    }
+    let path = reqwest::Url::parse(url).ok()?.path().trim_matches('/').to_string();
+    if path.is_empty() || path == "home" {
+        return Some("has no account");
+    }
+    // Borrowing someone else's viral tweet is the usual copy-paste pattern; communities are fine
+    if path.contains("/status/") {
+        return Some("links to a tweet rather than a project account");
+    }
+    None
+}
+
+/// Problem with a Telegram link, or None if it points at a chat
+fn telegram_problem(url: &str) -> Option<&'static str> {
+    if !matches!(host_of(url).as_deref(), Some("t.me" | "telegram.me")) {
+        return Some("is not a t.me link");
+    }
+    let path = reqwest::Url::parse(url).ok()?.path().trim_matches('/').to_string();
+    path.is_empty().then_some("has no chat")
+}
+
+/// Risk points and detail lines for a token's metadata
+pub fn metadata_score(findings: &MetadataFindings) -> (u32, Vec<String>) {
+    let mut score = 0;
+    let mut details = Vec::new();
+
+    if !findings.has_offchain_metadata {
+        score += 10;
+        details.push("🟠 No off-chain metadata (URI missing or unreachable).".to_string());
+    }
+    if findings.image.as_deref().is_some_and(is_http_url) {
+        details.push("✅ Token has an image.".to_string());
+    } else {
+        score += 5;
+        details.push("🟠 Token has no image.".to_string());
+    }
+    match findings.description.as_deref() {
+        Some(d) if d.eq_ignore_ascii_case(&findings.name) || d.eq_ignore_ascii_case(&findings.symbol) => {
+            score += 5;
+            details.push("🟠 Description just repeats the token name.".to_string());
+        }
+        Some(d) if d.chars().count() < MIN_DESCRIPTION_LEN => {
+            score += 5;
+            details.push("🟠 Description is too short to say anything.".to_string());
+        }
+        Some(_) => details.push("✅ Token has a description.".to_string()),
+        None => {
+            score += 5;
+            details.push("🟠 Token has no description.".to_string());
+        }
+    }
+
+    let mut valid_socials = 0;
+    if let Some(website) = &findings.website {
+        if is_http_url(website) {
+            valid_socials += 1;
+        } else {
+            score += 5;
+            details.push(format!("🟠 Website '{}' is not a valid URL.", website));
+        }
+    }
+    if let Some(twitter) = &findings.twitter {
+        match twitter_problem(twitter) {
+            None => valid_socials += 1,
+            Some(problem) => {
+                score += 5;
+                details.push(format!("🟠 Twitter '{}' {}.", twitter, problem));
+            }
+        }
+    }
+    if let Some(telegram) = &findings.telegram {
+        match telegram_problem(telegram) {
+            None => valid_socials += 1,
+            Some(problem) => {
+                score += 5;
+                details.push(format!("🟠 Telegram '{}' {}.", telegram, problem));
+            }
+        }
+    }
+    match valid_socials {
+        0 => {
+            score += 15;
+            details.push("🔴 No valid socials (website, Twitter, Telegram).".to_string());
+        }
+        1 => {
+            score += 5;
+            details.push("🟠 Only one valid social link.".to_string());
+        }
+        n => details.push(format!("✅ {} valid social links.", n)),
+    }
+
+    (score, details)
+}
+
+/// Fetch the off-chain metadata JSON an asset points to (None if it has no URI)
+pub async fn fetch_offchain_metadata(http_client: &reqwest::Client, asset: &DasAsset) -> Result<Option<OffchainMetadata>> {
+    let Some(uri) = asset.content.as_ref().and_then(|c| c.json_uri.as_deref()).filter(|u| is_http_url(u)) else {
+        return Ok(None);
+    };
+    let metadata = http_client.get(uri)
+        .timeout(METADATA_FETCH_TIMEOUT)
+        .send().await
+        .context("Failed to fetch token metadata JSON")?
+        .error_for_status()
+        .context("Token metadata URI returned an error")?
+        .json::<OffchainMetadata>().await
+        .context("Failed to parse token metadata JSON")?;
+    Ok(Some(metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn findings() -> MetadataFindings {
+        MetadataFindings {
+            has_offchain_metadata: true,
+            name: "Good Token".to_string(),
+            symbol: "GOOD".to_string(),
+            description: Some("A community token with an actual roadmap.".to_string()),
+            image: Some("https://ipfs.io/ipfs/abc".to_string()),
+            website: Some("https://good.example".to_string()),
+            twitter: Some("https://x.com/goodtoken".to_string()),
+            telegram: Some("https://t.me/goodtoken".to_string()),
+        }
+    }
+
+    #[test]
+    fn complete_metadata_scores_zero() {
+        let (score, details) = metadata_score(&findings());
+        assert_eq!(score, 0);
+        assert!(details.iter().any(|d| d.contains("3 valid social links")));
+    }
+
+    #[test]
+    fn empty_and_copy_pasted_metadata_is_penalized() {
+        let empty = MetadataFindings { name: "X".to_string(), symbol: "X".to_string(), ..MetadataFindings::default() };
+        assert_eq!(metadata_score(&empty).0, 10 + 5 + 5 + 15);
+
+        let copied = MetadataFindings {
+            description: Some("good token".to_string()),
+            twitter: Some("https://twitter.com/elonmusk/status/123".to_string()),
+            telegram: Some("https://example.com/chat".to_string()),
+            ..findings()
+        };
+        let (score, details) = metadata_score(&copied);
+        // Name-only description, tweet link, bad Telegram, and only the website left valid
+        assert_eq!(score, 5 + 5 + 5 + 5);
+        assert!(details.iter().any(|d| d.contains("links to a tweet")));
+    }
+
+    #[test]
+    fn reads_socials_from_extensions() {
+        let offchain: OffchainMetadata = serde_json::from_str(
+            r#"{"description": "d", "extensions": {"twitter": "https://x.com/a", "telegram": " "}}"#,
+        ).unwrap();
+        assert_eq!(offchain.link(&offchain.twitter, "twitter").as_deref(), Some("https://x.com/a"));
+        assert_eq!(offchain.link(&offchain.telegram, "telegram"), None);
+    }
+}
@@ -0,0 +1,147 @@
+//! Post-buy checks that a swap delivered the mint we asked for
+//!
+//! A spoofed pool or route can hand back a look-alike token (same symbol, different
+//! mint). After a buy confirms, the wallet's token balance changes in the confirmed
+//! transaction are compared against the intended mint so a position is never opened
+//! for the wrong token. The wallet's live balance of the mint is then compared with
+//! the swap's reported output, so a routing quirk that left the tokens elsewhere
+//! doesn't produce a position that can never be sold.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::UiTransactionTokenBalance;
+
+use crate::api::jupiter::SOL_MINT;
+use crate::solana::client::SolanaClient;
+
+/// A token the wallet gained in a transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceivedToken {
+    pub mint: String,
+    pub amount_ui: f64,
+    pub decimals: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReceivedMint {
+    /// The intended mint arrived (possibly alongside others)
+    Matches { amount_ui: f64 },
+    /// The intended mint did not arrive, but these did
+    Mismatch { received: Vec<ReceivedToken> },
+    /// No token balance increase to judge by (e.g. the RPC omitted token balances)
+    Unknown,
+}
+
+/// Per-mint (amount, decimals) a wallet holds in a transaction's balance list
+fn owner_balances(balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>, owner: &str) -> HashMap<String, (f64, u8)> {
+    let mut totals: HashMap<String, (f64, u8)> = HashMap::new();
+    if let OptionSerializer::Some(balances) = balances {
+        for balance in balances {
+            if matches!(&balance.owner, OptionSerializer::Some(o) if o == owner) {
+                let entry = totals.entry(balance.mint.clone()).or_insert((0.0, balance.ui_token_amount.decimals));
+                entry.0 += balance.ui_token_amount.ui_amount.unwrap_or(0.0);
+            }
+        }
+    }
+    totals
+}
+
+/// Tokens (other than wrapped SOL) whose balance went up between `pre` and `post`
+pub fn token_increases(pre: &HashMap<String, (f64, u8)>, post: &HashMap<String, (f64, u8)>) -> Vec<ReceivedToken> {
+    let mut received: Vec<ReceivedToken> = post.iter()
+        .filter(|(mint, _)| mint.as_str() != SOL_MINT)
+        .filter_map(|(mint, (after, decimals))| {
+            let gained = after - pre.get(mint).map_or(0.0, |(before, _)| *before);
+            (gained > 0.0).then(|| ReceivedToken { mint: mint.clone(), amount_ui: gained, decimals: *decimals })
+        })
+        .collect();
+    received.sort_by(|a, b| a.mint.cmp(&b.mint));
+    received
+}
+
+/// Judge the tokens a buy delivered against the mint it was meant to buy
+pub fn classify_received(intended_mint: &str, received: Vec<ReceivedToken>) -> ReceivedMint {
+    if let Some(token) = received.iter().find(|t| t.mint == intended_mint) {
+        return ReceivedMint::Matches { amount_ui: token.amount_ui };
+    }
+    if received.is_empty() {
+        ReceivedMint::Unknown
+    } else {
+        ReceivedMint::Mismatch { received }
+    }
+}
+
+/// Whether a post-buy balance is too far below what the swap reported receiving
+pub fn balance_shortfall(expected_ui: f64, held_ui: f64, min_percent: f64) -> bool {
+    expected_ui > 0.0 && held_ui < expected_ui * min_percent / 100.0
+}
+
+/// Fetch a confirmed buy and check which mint `owner` actually received
+pub async fn verify_received_mint(
+    solana_client: &SolanaClient,
+    signature: &Signature,
+    owner: &str,
+    intended_mint: &str,
+) -> Result<ReceivedMint> {
+    let tx = solana_client.get_transaction(signature, CommitmentConfig::confirmed()).await
+        .context("Failed to fetch confirmed buy transaction")?;
+    let Some(meta) = tx.transaction.meta.as_ref() else {
+        return Ok(ReceivedMint::Unknown);
+    };
+
+    let pre = owner_balances(&meta.pre_token_balances, owner);
+    let post = owner_balances(&meta.post_token_balances, owner);
+    Ok(classify_received(intended_mint, token_increases(&pre, &post)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balances(entries: &[(&str, f64)]) -> HashMap<String, (f64, u8)> {
+        entries.iter().map(|(m, a)| (m.to_string(), (*a, 6))).collect()
+    }
+
+    #[test]
+    fn intended_mint_received() {
+        let pre = balances(&[(SOL_MINT, 1.0)]);
+        let post = balances(&[(SOL_MINT, 0.0), ("Real", 500.0)]);
+        assert_eq!(
+            classify_received("Real", token_increases(&pre, &post)),
+            ReceivedMint::Matches { amount_ui: 500.0 }
+        );
+    }
+
+    #[test]
+    fn look_alike_mint_is_a_mismatch() {
+        let pre = balances(&[("Real", 10.0)]);
+        let post = balances(&[("Real", 10.0), ("Fake", 500.0)]);
+        match classify_received("Real", token_increases(&pre, &post)) {
+            ReceivedMint::Mismatch { received } => {
+                assert_eq!(received.len(), 1);
+                assert_eq!(received[0].mint, "Fake");
+                assert_eq!(received[0].amount_ui, 500.0);
+            }
+            other => panic!("expected mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn held_balance_far_below_output_is_a_shortfall() {
+        assert!(!balance_shortfall(1000.0, 1000.0, 50.0));
+        assert!(!balance_shortfall(1000.0, 600.0, 50.0));
+        assert!(balance_shortfall(1000.0, 400.0, 50.0));
+        assert!(balance_shortfall(1000.0, 0.0, 50.0));
+        assert!(!balance_shortfall(0.0, 0.0, 50.0));
+    }
+
+    #[test]
+    fn no_increase_is_unknown() {
+        let pre = balances(&[("Real", 10.0)]);
+        assert_eq!(classify_received("Real", token_increases(&pre, &pre)), ReceivedMint::Unknown);
+    }
+}
@@ -1,14 +1,38 @@
+pub mod accounting;
 pub mod autotrader;
+pub mod backtest;
+pub mod deployer;
 pub mod position;
+pub mod position_history;
+pub mod position_archive;
 pub mod risk;
+pub mod rug_monitor;
 pub mod strategy;
 pub mod simulation;
 pub mod pumpfun;
 pub mod pumpfun_monitor;
+pub mod pumpfun_swap;
+pub mod pool_discovery;
+pub mod pool_liquidity;
+pub mod pool_monitor;
 pub mod graduation_monitor;
 pub mod watchlist;
 pub mod scanner;
 pub mod sniper;
+pub mod swap_retry;
+pub mod escalation;
+pub mod sol_trend;
+pub mod blocklist;
+pub mod dust_sweep;
+pub mod limit_orders;
+pub mod loss_breaker;
+pub mod metadata_check;
+pub mod mint_check;
+pub mod pnl_report;
+pub mod startup_digest;
+pub mod test_swap;
+pub mod strategy_changelog;
+pub mod strategy_stats;
 // Potentially add order types, execution logic, etc. here later
 
 pub use simulation::SimulationManager;
@@ -0,0 +1,209 @@
+//! Daily and weekly PnL reports
+//!
+//! At `pnl_report_time` (UTC) a summary of the trades closed over the past day is
+//! sent to notification relays; on `pnl_report_weekday` a summary of the past week
+//! follows it. Both are built from the closed-trade history, archived positions
+//! included, so demo trades never show up.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::trading::accounting::ClosedTrade;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportPeriod {
+    Daily,
+    Weekly,
+}
+
+impl ReportPeriod {
+    pub fn length(self) -> Duration {
+        match self {
+            ReportPeriod::Daily => Duration::days(1),
+            ReportPeriod::Weekly => Duration::days(7),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ReportPeriod::Daily => "Daily",
+            ReportPeriod::Weekly => "Weekly",
+        }
+    }
+}
+
+/// One trade as shown in a report (best/worst)
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportTrade {
+    pub position_id: String,
+    pub token_symbol: String,
+    pub pnl_sol: f64,
+    pub pnl_percent: f64,
+}
+
+impl From<&ClosedTrade> for ReportTrade {
+    fn from(t: &ClosedTrade) -> Self {
+        Self {
+            position_id: t.position_id.clone(),
+            token_symbol: t.token_symbol.clone(),
+            pnl_sol: t.pnl_sol.unwrap_or(0.0),
+            pnl_percent: t.pnl_percent.unwrap_or(0.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PnlReport {
+    pub period: ReportPeriod,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub trades: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub win_rate_percent: f64,
+    pub invested_sol: f64,               // Entry value of the trades closed in the period
+    pub pnl_sol: f64,
+    pub pnl_usd: Option<f64>,            // Only when every trade has a USD PnL
+    pub profit_fees_sol: f64,            // Profit share paid to the fee wallet
+    pub transfer_tax_sol: f64,           // Token-2022 transfer fees withheld on sells (estimated)
+    pub best_trade: Option<ReportTrade>,
+    pub worst_trade: Option<ReportTrade>,
+}
+
+impl PnlReport {
+    /// Summarize `trades` (already filtered to exits in `from`..`to`)
+    pub fn compile(period: ReportPeriod, from: DateTime<Utc>, to: DateTime<Utc>, trades: &[ClosedTrade]) -> Self {
+        let pnl = |t: &&ClosedTrade| t.pnl_sol.unwrap_or(0.0);
+        let wins = trades.iter().filter(|t| pnl(t) > 0.0).count();
+        let losses = trades.iter().filter(|t| pnl(t) < 0.0).count();
+        let transfer_tax_sol = trades.iter()
+            .filter(|t| t.transfer_tax_percent > 0.0 && t.transfer_tax_percent < 100.0)
+            .map(|t| t.exit_value_sol * t.transfer_tax_percent / (100.0 - t.transfer_tax_percent))
+            .sum();
+
+        Self {
+            period,
+            from,
+            to,
+            trades: trades.len(),
+            wins,
+            losses,
+            win_rate_percent: if trades.is_empty() { 0.0 } else { wins as f64 / trades.len() as f64 * 100.0 },
+            invested_sol: trades.iter().map(|t| t.entry_value_sol).sum(),
+            pnl_sol: trades.iter().map(|t| pnl(&t)).sum(),
+            pnl_usd: trades.iter().map(|t| t.pnl_usd).sum(),
+            profit_fees_sol: trades.iter().filter_map(|t| t.profit_fee_sol).sum(),
+            transfer_tax_sol,
+            best_trade: trades.iter().filter(|t| pnl(t) > 0.0).max_by(|a, b| pnl(a).total_cmp(&pnl(b))).map(ReportTrade::from),
+            worst_trade: trades.iter().filter(|t| pnl(t) < 0.0).min_by(|a, b| pnl(a).total_cmp(&pnl(b))).map(ReportTrade::from),
+        }
+    }
+
+    /// Multi-line text for notification relays
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!(
+            "📊 {} PnL report ({} - {} UTC)",
+            self.period.label(), self.from.format("%Y-%m-%d %H:%M"), self.to.format("%Y-%m-%d %H:%M")
+        )];
+        if self.trades == 0 {
+            lines.push("No trades closed.".to_string());
+            return lines.join("\n");
+        }
+        lines.push(format!(
+            "{} PnL: {:+.4} SOL{}",
+            if self.pnl_sol >= 0.0 { "🟢" } else { "🔴" },
+            self.pnl_sol,
+            self.pnl_usd.map(|usd| format!(" (${:+.2})", usd)).unwrap_or_default()
+        ));
+        lines.push(format!(
+            "Trades: {} ({} won, {} lost) | Win rate: {:.1}%",
+            self.trades, self.wins, self.losses, self.win_rate_percent
+        ));
+        lines.push(format!("Invested: {:.4} SOL", self.invested_sol));
+        if let Some(best) = &self.best_trade {
+            lines.push(format!("Best: {} {:+.4} SOL ({:+.1}%)", best.token_symbol, best.pnl_sol, best.pnl_percent));
+        }
+        if let Some(worst) = &self.worst_trade {
+            lines.push(format!("Worst: {} {:+.4} SOL ({:+.1}%)", worst.token_symbol, worst.pnl_sol, worst.pnl_percent));
+        }
+        lines.push(format!(
+            "Fees: {:.4} SOL profit share, {:.4} SOL transfer tax",
+            self.profit_fees_sol, self.transfer_tax_sol
+        ));
+        lines.join("\n")
+    }
+}
+
+/// Bounds of the UTC calendar day `date`
+pub fn day_bounds(date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let from = date.and_time(NaiveTime::MIN).and_utc();
+    (from, from + Duration::days(1))
+}
+
+/// First occurrence of the UTC clock time `at` strictly after `after`
+pub fn next_report_time(after: DateTime<Utc>, at: NaiveTime) -> DateTime<Utc> {
+    let today = after.date_naive().and_time(at).and_utc();
+    if today > after {
+        today
+    } else {
+        today + Duration::days(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(symbol: &str, pnl_sol: f64, profit_fee_sol: Option<f64>) -> ClosedTrade {
+        let at = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        ClosedTrade {
+            position_id: symbol.to_lowercase(),
+            token_address: format!("{}Mint", symbol),
+            token_symbol: symbol.to_string(),
+            strategy_id: "s".to_string(),
+            entry_time: at,
+            exit_time: at,
+            entry_price_sol: 0.001,
+            exit_price_sol: Some(0.001),
+            token_amount: 1000.0,
+            entry_value_sol: 1.0,
+            exit_value_sol: 1.0 + pnl_sol,
+            transfer_tax_percent: 0.0,
+            profit_fee_sol,
+            pnl_sol: Some(pnl_sol),
+            pnl_percent: Some(pnl_sol * 100.0),
+            pnl_usd: Some(pnl_sol * 150.0),
+            exit_reason: "Closed".to_string(),
+            entry_tx: "sig".to_string(),
+            exit_tx: None,
+        }
+    }
+
+    #[test]
+    fn compiles_wins_losses_and_extremes() {
+        let (from, to) = day_bounds(NaiveDate::from_ymd_opt(2024, 5, 1).unwrap());
+        let trades = vec![trade("AAA", 0.5, Some(0.05)), trade("BBB", -0.2, None), trade("CCC", 0.1, Some(0.01))];
+        let report = PnlReport::compile(ReportPeriod::Daily, from, to, &trades);
+
+        assert_eq!((report.trades, report.wins, report.losses), (3, 2, 1));
+        assert!((report.pnl_sol - 0.4).abs() < 1e-9);
+        assert!((report.profit_fees_sol - 0.06).abs() < 1e-9);
+        assert_eq!(report.best_trade.as_ref().unwrap().token_symbol, "AAA");
+        assert_eq!(report.worst_trade.as_ref().unwrap().token_symbol, "BBB");
+        assert!(report.summary().contains("Win rate: 66.7%"));
+
+        let empty = PnlReport::compile(ReportPeriod::Weekly, from, to, &[]);
+        assert!(empty.best_trade.is_none());
+        assert!(empty.summary().contains("No trades closed."));
+    }
+
+    #[test]
+    fn next_report_time_rolls_over() {
+        let at = NaiveTime::from_hms_opt(21, 0, 0).unwrap();
+        let before = DateTime::parse_from_rfc3339("2024-05-01T20:59:00Z").unwrap().with_timezone(&Utc);
+        let on_time = DateTime::parse_from_rfc3339("2024-05-01T21:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(next_report_time(before, at).to_rfc3339(), "2024-05-01T21:00:00+00:00");
+        assert_eq!(next_report_time(on_time, at).to_rfc3339(), "2024-05-02T21:00:00+00:00");
+    }
+}
@@ -0,0 +1,218 @@
+//! Finding a token's deepest SOL pool on-chain
+//!
+//! Both the on-chain liquidity fallback and direct Raydium swaps need the token's
+//! pool before Birdeye or Jupiter know about it. Discovery is a program-account
+//! scan per pool program for accounts pairing the token with SOL (in either
+//! order); the candidate whose SOL vault holds the most lamports wins. Results are
+//! cached per token, and "no pool" is only remembered for the caller's recheck
+//! window, since a pool may be created at any moment.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use crate::api::jupiter::SOL_MINT;
+use crate::solana::client::SolanaClient;
+
+pub(crate) const RAYDIUM_AMM_V4_PROGRAM_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+pub(crate) const RAYDIUM_AMM_V4_SIZE: u64 = 752;
+pub(crate) const RAYDIUM_BASE_VAULT_OFFSET: usize = 336;
+pub(crate) const RAYDIUM_QUOTE_VAULT_OFFSET: usize = 368;
+pub(crate) const RAYDIUM_BASE_MINT_OFFSET: usize = 400;
+pub(crate) const RAYDIUM_QUOTE_MINT_OFFSET: usize = 432;
+
+const WHIRLPOOL_PROGRAM_ID: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+const WHIRLPOOL_SIZE: u64 = 653;
+const WHIRLPOOL_MINT_A_OFFSET: usize = 101;
+const WHIRLPOOL_VAULT_A_OFFSET: usize = 133;
+const WHIRLPOOL_MINT_B_OFFSET: usize = 181;
+const WHIRLPOOL_VAULT_B_OFFSET: usize = 213;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolKind {
+    RaydiumAmm,
+    Whirlpool,
+}
+
+/// A pool pairing a token with SOL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolPool {
+    pub kind: PoolKind,
+    pub address: Pubkey,
+    pub sol_vault: Pubkey,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CachedDiscovery {
+    pool: Option<SolPool>,
+    discovered_at: Instant,
+}
+
+pub(crate) fn read_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    data.get(offset..offset + 32).and_then(|bytes| Pubkey::try_from(bytes).ok())
+}
+
+/// The SOL-side vault of a pool account pairing `mint` with SOL, given the layout's
+/// mint and vault offsets for each side
+fn sol_vault_from_pool(data: &[u8], mint: &Pubkey, sides: [(usize, usize); 2]) -> Option<Pubkey> {
+    let sol = Pubkey::from_str(SOL_MINT).ok()?;
+    let [(mint_a, vault_a), (mint_b, vault_b)] = sides;
+    let (a, b) = (read_pubkey(data, mint_a)?, read_pubkey(data, mint_b)?);
+    if a == *mint && b == sol {
+        read_pubkey(data, vault_b)
+    } else if a == sol && b == *mint {
+        read_pubkey(data, vault_a)
+    } else {
+        None
+    }
+}
+
+fn raydium_sol_vault(data: &[u8], mint: &Pubkey) -> Option<Pubkey> {
+    sol_vault_from_pool(data, mint, [
+        (RAYDIUM_BASE_MINT_OFFSET, RAYDIUM_BASE_VAULT_OFFSET),
+        (RAYDIUM_QUOTE_MINT_OFFSET, RAYDIUM_QUOTE_VAULT_OFFSET),
+    ])
+}
+
+fn whirlpool_sol_vault(data: &[u8], mint: &Pubkey) -> Option<Pubkey> {
+    sol_vault_from_pool(data, mint, [
+        (WHIRLPOOL_MINT_A_OFFSET, WHIRLPOOL_VAULT_A_OFFSET),
+        (WHIRLPOOL_MINT_B_OFFSET, WHIRLPOOL_VAULT_B_OFFSET),
+    ])
+}
+
+/// Finds and caches each token's deepest SOL pool among `kinds`
+#[derive(Debug)]
+pub struct PoolDiscovery {
+    kinds: &'static [PoolKind],
+    no_pool_recheck: Duration, // How long "no pool found" is remembered
+    discoveries: RwLock<HashMap<Pubkey, CachedDiscovery>>,
+}
+
+impl PoolDiscovery {
+    pub fn new(kinds: &'static [PoolKind], no_pool_recheck_secs: u64) -> Self {
+        Self {
+            kinds,
+            no_pool_recheck: Duration::from_secs(no_pool_recheck_secs),
+            discoveries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// The token's deepest SOL pool, from the cache or a fresh scan
+    pub async fn deepest_sol_pool(&self, solana_client: &SolanaClient, mint: &Pubkey) -> Result<Option<SolPool>> {
+        if let Some(cached) = self.discoveries.read().await.get(mint) {
+            if cached.pool.is_some() || cached.discovered_at.elapsed() < self.no_pool_recheck {
+                return Ok(cached.pool);
+            }
+        }
+
+        let mut candidates = Vec::new();
+        for kind in self.kinds {
+            candidates.extend(scan_pools(solana_client, mint, *kind).await?);
+        }
+        let mut deepest: Option<(SolPool, u64)> = None;
+        for candidate in candidates {
+            let Ok((lamports, _)) = solana_client.get_token_balance(&candidate.sol_vault).await else {
+                continue;
+            };
+            if !deepest.is_some_and(|(_, best)| best >= lamports) {
+                deepest = Some((candidate, lamports));
+            }
+        }
+        match deepest {
+            Some((pool, lamports)) => info!(
+                "Found {:?} SOL pool {} for {} ({:.2} SOL)", pool.kind, pool.address, mint, lamports as f64 / 1e9
+            ),
+            None => debug!("No {:?} SOL pool found on-chain for {}", self.kinds, mint),
+        }
+        let pool = deepest.map(|(pool, _)| pool);
+        self.discoveries.write().await.insert(*mint, CachedDiscovery { pool, discovered_at: Instant::now() });
+        Ok(pool)
+    }
+
+    /// Drop the cached pool (e.g. it was closed) so the next lookup scans again
+    pub async fn forget(&self, mint: &Pubkey) {
+        self.discoveries.write().await.remove(mint);
+    }
+}
+
+/// Pools of one program pairing the token with SOL, in either order
+async fn scan_pools(solana_client: &SolanaClient, mint: &Pubkey, kind: PoolKind) -> Result<Vec<SolPool>> {
+    let (program, size, mint_offsets) = match kind {
+        PoolKind::RaydiumAmm => (RAYDIUM_AMM_V4_PROGRAM_ID, RAYDIUM_AMM_V4_SIZE, [RAYDIUM_BASE_MINT_OFFSET, RAYDIUM_QUOTE_MINT_OFFSET]),
+        PoolKind::Whirlpool => (WHIRLPOOL_PROGRAM_ID, WHIRLPOOL_SIZE, [WHIRLPOOL_MINT_A_OFFSET, WHIRLPOOL_MINT_B_OFFSET]),
+    };
+    let program = Pubkey::from_str(program).context("Invalid pool program id")?;
+    let sol = Pubkey::from_str(SOL_MINT).context("Invalid SOL mint")?;
+    let rpc = solana_client.get_rpc();
+
+    let mut pools = Vec::new();
+    for (first, second) in [(*mint, sol), (sol, *mint)] {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(size),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(mint_offsets[0], first.as_ref())),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(mint_offsets[1], second.as_ref())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let accounts = rpc.get_program_accounts_with_config(&program, config).await
+            .with_context(|| format!("Failed to scan {:?} pools for {}", kind, mint))?;
+        pools.extend(accounts.iter().filter_map(|(address, account)| {
+            let sol_vault = match kind {
+                PoolKind::RaydiumAmm => raydium_sol_vault(&account.data, mint),
+                PoolKind::Whirlpool => whirlpool_sol_vault(&account.data, mint),
+            }?;
+            Some(SolPool { kind, address: *address, sol_vault })
+        }));
+    }
+    Ok(pools)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool_data(size: usize, fields: &[(usize, Pubkey)]) -> Vec<u8> {
+        let mut data = vec![0u8; size];
+        for (offset, key) in fields {
+            data[*offset..*offset + 32].copy_from_slice(key.as_ref());
+        }
+        data
+    }
+
+    #[test]
+    fn finds_sol_vault_on_either_side() {
+        let mint = Pubkey::new_unique();
+        let sol = Pubkey::from_str(SOL_MINT).unwrap();
+        let (base_vault, quote_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let token_base = pool_data(RAYDIUM_AMM_V4_SIZE as usize, &[
+            (RAYDIUM_BASE_MINT_OFFSET, mint), (RAYDIUM_QUOTE_MINT_OFFSET, sol),
+            (RAYDIUM_BASE_VAULT_OFFSET, base_vault), (RAYDIUM_QUOTE_VAULT_OFFSET, quote_vault),
+        ]);
+        assert_eq!(raydium_sol_vault(&token_base, &mint), Some(quote_vault));
+
+        let sol_a = pool_data(WHIRLPOOL_SIZE as usize, &[
+            (WHIRLPOOL_MINT_A_OFFSET, sol), (WHIRLPOOL_MINT_B_OFFSET, mint),
+            (WHIRLPOOL_VAULT_A_OFFSET, base_vault), (WHIRLPOOL_VAULT_B_OFFSET, quote_vault),
+        ]);
+        assert_eq!(whirlpool_sol_vault(&sol_a, &mint), Some(base_vault));
+
+        // Not paired with SOL, or too short to hold the fields
+        assert_eq!(raydium_sol_vault(&token_base, &Pubkey::new_unique()), None);
+        assert_eq!(whirlpool_sol_vault(&[0u8; 100], &mint), None);
+    }
+}
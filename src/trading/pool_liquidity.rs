@@ -0,0 +1,69 @@
+//! On-chain pool liquidity, for when Birdeye has no data for a token
+//!
+//! The risk analyzer prices liquidity from Birdeye's overview. New and thin tokens
+//! often have no overview yet, and treating that as zero liquidity gave perfectly
+//! tradable tokens the "very low liquidity" penalty. This reads the SOL side of the
+//! token's pool straight from the chain instead: the pump.fun bonding curve while
+//! the token is still on it, otherwise the deepest Raydium AMM v4 or Orca
+//! Whirlpool SOL pool. Pool discovery is a program-account scan, so its result is
+//! cached per token; only the vault balance is read on later checks.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+use tracing::{debug, warn};
+
+use crate::solana::client::SolanaClient;
+use crate::trading::pool_discovery::{PoolDiscovery, PoolKind};
+use crate::trading::pumpfun::{derive_bonding_curve_pda, parse_bonding_curve_account};
+
+/// How long "no pool found" is remembered before scanning again (a pool may appear)
+const NO_POOL_RECHECK_SECS: u64 = 600;
+
+pub struct PoolLiquidity {
+    solana_client: Arc<SolanaClient>,
+    discovery: PoolDiscovery,
+}
+
+impl PoolLiquidity {
+    pub fn new(solana_client: Arc<SolanaClient>) -> Self {
+        Self {
+            solana_client,
+            discovery: PoolDiscovery::new(&[PoolKind::RaydiumAmm, PoolKind::Whirlpool], NO_POOL_RECHECK_SECS),
+        }
+    }
+
+    /// Liquidity in SOL from the chain, counted like Birdeye's (both sides of the
+    /// pool, so twice the SOL reserve; a bonding curve's real SOL reserve as is).
+    /// None if the token has no curve or SOL pool we can read.
+    pub async fn liquidity_sol(&self, mint: &Pubkey) -> Result<Option<f64>> {
+        if let Some(curve_sol) = self.bonding_curve_sol(mint).await {
+            debug!("On-chain liquidity for {}: {:.2} SOL in its bonding curve", mint, curve_sol);
+            return Ok(Some(curve_sol));
+        }
+        let Some(pool) = self.discovery.deepest_sol_pool(&self.solana_client, mint).await? else {
+            return Ok(None);
+        };
+        let (lamports, _) = match self.solana_client.get_token_balance(&pool.sol_vault).await {
+            Ok(balance) => balance,
+            Err(e) => {
+                // The pool may have been closed; rediscover next time
+                warn!("Failed to read {:?} SOL vault {} for {}: {:?}", pool.kind, pool.sol_vault, mint, e);
+                self.discovery.forget(mint).await;
+                return Ok(None);
+            }
+        };
+        let liquidity_sol = 2.0 * lamports as f64 / 1_000_000_000.0;
+        debug!("On-chain liquidity for {}: {:.2} SOL ({:?} vault {})", mint, liquidity_sol, pool.kind, pool.sol_vault);
+        Ok(Some(liquidity_sol))
+    }
+
+    /// SOL in the token's pump.fun bonding curve, if it's still trading on one
+    async fn bonding_curve_sol(&self, mint: &Pubkey) -> Option<f64> {
+        let (curve, _) = derive_bonding_curve_pda(mint);
+        let data = self.solana_client.get_account_data(&curve).await.ok()?;
+        let (state, _) = parse_bonding_curve_account(&data)?;
+        (!state.is_ready_to_graduate()).then(|| state.get_liquidity_sol())
+    }
+}
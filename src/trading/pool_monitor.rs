@@ -0,0 +1,380 @@
+// src/trading/pool_monitor.rs
+//
+// Real-time detection of new Raydium pools using WebSocket logsSubscribe.
+//
+// The Helius scan finds new pairs up to a scan interval late. This subscribes to the
+// logs of the Raydium AMM v4 and CPMM programs (one subscription each: a logs
+// filter can only mention one address) and, for every transaction that initializes
+// a pool, reads the pool's mints from the transaction. New SOL pairs are sent
+// through a channel the AutoTrader consumes, next to the pump.fun create stream.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use solana_client::{
+    nonblocking::pubsub_client::PubsubClient,
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+    rpc_response::{Response, RpcLogsResponse},
+};
+use solana_sdk::signature::Signature;
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedTransaction, UiCompiledInstruction, UiInstruction, UiMessage,
+};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tracing::{debug, error, info, warn};
+
+use crate::api::jupiter::SOL_MINT;
+use crate::solana::client::SolanaClient;
+use crate::trading::pumpfun_monitor::{MonitorStats, PumpfunMonitorConfig};
+
+/// Anchor discriminator of the CPMM `initialize` instruction
+const CPMM_INITIALIZE: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+
+/// Transaction lookups per detected pool; the RPC can trail the log stream slightly
+const FETCH_ATTEMPTS: u32 = 3;
+const FETCH_RETRY_DELAY_MS: u64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolProgram {
+    RaydiumAmmV4,
+    RaydiumCpmm,
+}
+
+impl PoolProgram {
+    pub const ALL: [PoolProgram; 2] = [PoolProgram::RaydiumAmmV4, PoolProgram::RaydiumCpmm];
+
+    pub fn program_id(self) -> &'static str {
+        match self {
+            PoolProgram::RaydiumAmmV4 => crate::trading::pool_discovery::RAYDIUM_AMM_V4_PROGRAM_ID,
+            PoolProgram::RaydiumCpmm => "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C",
+        }
+    }
+
+    /// Log line printed by the pool's initialize instruction
+    fn init_log(self) -> &'static str {
+        match self {
+            PoolProgram::RaydiumAmmV4 => "initialize2",
+            PoolProgram::RaydiumCpmm => "Instruction: Initialize",
+        }
+    }
+
+    fn is_init_data(self, data: &[u8]) -> bool {
+        match self {
+            PoolProgram::RaydiumAmmV4 => data.first() == Some(&1), // initialize2 tag
+            PoolProgram::RaydiumCpmm => data.starts_with(&CPMM_INITIALIZE),
+        }
+    }
+
+    /// Positions of the pool and its two mints in the initialize instruction's accounts
+    fn init_accounts(self) -> (usize, usize, usize) {
+        match self {
+            PoolProgram::RaydiumAmmV4 => (4, 8, 9),
+            PoolProgram::RaydiumCpmm => (3, 4, 5),
+        }
+    }
+}
+
+/// A pool pairing a token with SOL, seen being initialized
+#[derive(Debug, Clone)]
+pub struct NewPool {
+    pub program: PoolProgram,
+    pub pool: String,
+    pub token_mint: String,
+    pub token_decimals: Option<u8>, // From the transaction's token balances, when present
+    pub signature: String,
+    pub discovered_at: DateTime<Utc>,
+}
+
+/// The pool and mints initialized by `program` in a transaction, from its account
+/// keys and compiled instructions (top-level and inner)
+fn find_pool_init(program: PoolProgram, keys: &[String], instructions: &[&UiCompiledInstruction]) -> Option<(String, String, String)> {
+    let (pool_at, mint_a_at, mint_b_at) = program.init_accounts();
+    instructions.iter()
+        .filter(|ix| keys.get(ix.program_id_index as usize).map(String::as_str) == Some(program.program_id()))
+        .find(|ix| bs58::decode(&ix.data).into_vec().is_ok_and(|data| program.is_init_data(&data)))
+        .and_then(|ix| {
+            let key = |at: usize| ix.accounts.get(at).and_then(|i| keys.get(*i as usize)).cloned();
+            Some((key(pool_at)?, key(mint_a_at)?, key(mint_b_at)?))
+        })
+}
+
+/// Real-time Raydium pool discovery monitor using WebSocket logsSubscribe
+pub struct PoolMonitor {
+    config: PumpfunMonitorConfig, // Same connection settings as the pump.fun stream
+    solana_client: Arc<SolanaClient>,
+    pool_sender: mpsc::Sender<NewPool>,
+    running: Arc<RwLock<bool>>,
+    shutdown_tx: broadcast::Sender<()>,
+    stats: Arc<RwLock<MonitorStats>>,
+}
+
+impl PoolMonitor {
+    pub fn new(websocket_url: &str, solana_client: Arc<SolanaClient>, pool_sender: mpsc::Sender<NewPool>) -> Self {
+        Self {
+            config: PumpfunMonitorConfig {
+                websocket_url: websocket_url.to_string(),
+                ..Default::default()
+            },
+            solana_client,
+            pool_sender,
+            running: Arc::new(RwLock::new(false)),
+            shutdown_tx: broadcast::channel(1).0,
+            stats: Arc::new(RwLock::new(MonitorStats::default())),
+        }
+    }
+
+    /// Start the monitor (runs in background)
+    pub async fn start(&self) -> Result<()> {
+        let mut running = self.running.write().await;
+        if *running {
+            return Err(anyhow!("Pool monitor is already running"));
+        }
+        *running = true;
+        drop(running);
+
+        info!("🚀 Starting Raydium pool discovery monitor...");
+
+        let config = self.config.clone();
+        let solana_client = self.solana_client.clone();
+        let pool_sender = self.pool_sender.clone();
+        let running = self.running.clone();
+        let stats = self.stats.clone();
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
+
+        tokio::spawn(async move {
+            let mut reconnect_attempts = 0u32;
+            loop {
+                if !*running.read().await {
+                    info!("Pool monitor stopped by request");
+                    break;
+                }
+
+                match Self::run_subscription(&config, &solana_client, &pool_sender, &stats, &mut shutdown_rx).await {
+                    Ok(_) => {
+                        info!("Pool WebSocket subscription ended normally");
+                        break;
+                    }
+                    Err(e) => {
+                        error!("Pool WebSocket error: {:?}", e);
+                        reconnect_attempts += 1;
+                        stats.write().await.reconnect_attempts = reconnect_attempts;
+
+                        if reconnect_attempts >= config.max_reconnect_attempts {
+                            error!("Max reconnection attempts reached. Stopping pool monitor.");
+                            *running.write().await = false;
+                            break;
+                        }
+                        warn!(
+                            "Reconnecting pool monitor in {}ms (attempt {}/{})",
+                            config.reconnect_delay_ms, reconnect_attempts, config.max_reconnect_attempts
+                        );
+                        tokio::time::sleep(tokio::time::Duration::from_millis(config.reconnect_delay_ms)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        info!("Stopping pool monitor...");
+        *self.running.write().await = false;
+        let _ = self.shutdown_tx.send(());
+        Ok(())
+    }
+
+    pub async fn is_running(&self) -> bool {
+        *self.running.read().await
+    }
+
+    async fn run_subscription(
+        config: &PumpfunMonitorConfig,
+        solana_client: &Arc<SolanaClient>,
+        pool_sender: &mpsc::Sender<NewPool>,
+        stats: &Arc<RwLock<MonitorStats>>,
+        shutdown_rx: &mut broadcast::Receiver<()>,
+    ) -> Result<()> {
+        let pubsub_client = PubsubClient::new(&config.websocket_url).await?;
+        let logs_config = || RpcTransactionLogsConfig { commitment: Some(config.commitment) };
+        let mention = |program: PoolProgram| RpcTransactionLogsFilter::Mentions(vec![program.program_id().to_string()]);
+
+        let (amm_stream, amm_unsubscribe) = pubsub_client
+            .logs_subscribe(mention(PoolProgram::RaydiumAmmV4), logs_config())
+            .await?;
+        let (cpmm_stream, cpmm_unsubscribe) = pubsub_client
+            .logs_subscribe(mention(PoolProgram::RaydiumCpmm), logs_config())
+            .await?;
+        info!("✅ Subscribed to {} Raydium programs, listening for new pools...", PoolProgram::ALL.len());
+
+        let mut logs = futures::stream::select(
+            amm_stream.map(|r| (PoolProgram::RaydiumAmmV4, r)),
+            cpmm_stream.map(|r| (PoolProgram::RaydiumCpmm, r)),
+        );
+
+        let mut stream_ended = false;
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.recv() => {
+                    info!("Pool monitor received shutdown signal");
+                    break;
+                }
+                item = logs.next() => {
+                    let Some((program, response)) = item else {
+                        warn!("Pool log stream ended unexpectedly");
+                        stream_ended = true;
+                        break;
+                    };
+                    stats.write().await.logs_received += 1;
+                    Self::process_log_response(program, response, solana_client, pool_sender, stats);
+                }
+            }
+        }
+
+        drop(logs);
+        amm_unsubscribe().await;
+        cpmm_unsubscribe().await;
+
+        // A dropped stream is a disconnect, not a shutdown: report it so the caller reconnects
+        if stream_ended {
+            return Err(anyhow!("Pool log stream ended unexpectedly"));
+        }
+        Ok(())
+    }
+
+    /// Hand a pool initialization off to be resolved, without holding up the stream
+    fn process_log_response(
+        program: PoolProgram,
+        response: Response<RpcLogsResponse>,
+        solana_client: &Arc<SolanaClient>,
+        pool_sender: &mpsc::Sender<NewPool>,
+        stats: &Arc<RwLock<MonitorStats>>,
+    ) {
+        let logs = response.value;
+        if logs.err.is_some() || !logs.logs.iter().any(|l| l.contains(program.init_log())) {
+            return;
+        }
+        debug!("🔎 Pool initialization ({:?}) in tx {}", program, logs.signature);
+
+        let (solana_client, pool_sender, stats) = (solana_client.clone(), pool_sender.clone(), stats.clone());
+        tokio::spawn(async move {
+            match Self::resolve_pool(&solana_client, program, &logs.signature).await {
+                Ok(Some(pool)) => {
+                    info!("🚀 NEW {:?} POOL: {} (pool {}, tx {})", program, pool.token_mint, pool.pool, pool.signature);
+                    stats.write().await.tokens_discovered += 1;
+                    if let Err(e) = pool_sender.send(pool).await {
+                        error!("Failed to send new pool to channel: {:?}", e);
+                    }
+                }
+                Ok(None) => debug!("Tx {} did not initialize a SOL pool", logs.signature),
+                Err(e) => {
+                    stats.write().await.parse_failures += 1;
+                    debug!("Failed to resolve pool from tx {}: {:?}", logs.signature, e);
+                }
+            }
+        });
+    }
+
+    /// Read the pool initialized by `signature`; None unless it pairs a token with SOL
+    async fn resolve_pool(solana_client: &SolanaClient, program: PoolProgram, signature: &str) -> Result<Option<NewPool>> {
+        let sig = Signature::from_str(signature).context("Invalid signature from log stream")?;
+        let mut attempt = 0;
+        let tx = loop {
+            attempt += 1;
+            match solana_client.get_transaction(&sig, solana_sdk::commitment_config::CommitmentConfig::confirmed()).await {
+                Ok(tx) => break tx,
+                Err(_) if attempt < FETCH_ATTEMPTS => {
+                    tokio::time::sleep(tokio::time::Duration::from_millis(FETCH_RETRY_DELAY_MS)).await;
+                }
+                Err(e) => return Err(e).context("Failed to fetch pool transaction"),
+            }
+        };
+
+        let EncodedTransaction::Json(ui_tx) = &tx.transaction.transaction else {
+            return Ok(None);
+        };
+        let UiMessage::Raw(message) = &ui_tx.message else {
+            return Ok(None);
+        };
+        let meta = tx.transaction.meta.as_ref();
+
+        let mut keys = message.account_keys.clone();
+        if let Some(OptionSerializer::Some(loaded)) = meta.map(|m| &m.loaded_addresses) {
+            keys.extend(loaded.writable.iter().cloned());
+            keys.extend(loaded.readonly.iter().cloned());
+        }
+        let mut instructions: Vec<&UiCompiledInstruction> = message.instructions.iter().collect();
+        if let Some(OptionSerializer::Some(inner)) = meta.map(|m| &m.inner_instructions) {
+            for set in inner {
+                instructions.extend(set.instructions.iter().filter_map(|ix| match ix {
+                    UiInstruction::Compiled(compiled) => Some(compiled),
+                    _ => None,
+                }));
+            }
+        }
+
+        let Some((pool, mint_a, mint_b)) = find_pool_init(program, &keys, &instructions) else {
+            return Ok(None);
+        };
+        let token_mint = match (mint_a.as_str(), mint_b.as_str()) {
+            (SOL_MINT, token) | (token, SOL_MINT) if token != SOL_MINT => token.to_string(),
+            _ => return Ok(None),
+        };
+        let token_decimals = match meta.map(|m| &m.post_token_balances) {
+            Some(OptionSerializer::Some(balances)) => balances.iter()
+                .find(|b| b.mint == token_mint)
+                .map(|b| b.ui_token_amount.decimals),
+            _ => None,
+        };
+
+        Ok(Some(NewPool {
+            program,
+            pool,
+            token_mint,
+            token_decimals,
+            signature: signature.to_string(),
+            discovered_at: Utc::now(),
+        }))
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ix(program_id_index: u8, accounts: Vec<u8>, data: &[u8]) -> UiCompiledInstruction {
+        UiCompiledInstruction {
+            program_id_index,
+            accounts,
+            data: bs58::encode(data).into_string(),
+            stack_height: None,
+        }
+    }
+
+    #[test]
+    fn finds_cpmm_pool_mints() {
+        let mut keys: Vec<String> = (0..7).map(|i| format!("Key{}", i)).collect();
+        keys[4] = "TokenMint".to_string();
+        keys[5] = SOL_MINT.to_string();
+        keys.push(PoolProgram::RaydiumCpmm.program_id().to_string());
+
+        let mut init_data = CPMM_INITIALIZE.to_vec();
+        init_data.extend([0u8; 24]);
+        let swap = ix(7, vec![0, 1, 2, 3, 4, 5, 6], &[9u8; 16]);
+        let init = ix(7, vec![0, 1, 2, 3, 4, 5, 6], &init_data);
+
+        assert_eq!(find_pool_init(PoolProgram::RaydiumCpmm, &keys, &[&swap]), None);
+        assert_eq!(
+            find_pool_init(PoolProgram::RaydiumCpmm, &keys, &[&swap, &init]),
+            Some(("Key3".to_string(), "TokenMint".to_string(), SOL_MINT.to_string()))
+        );
+        // Another program's instruction with the same accounts doesn't count
+        assert_eq!(find_pool_init(PoolProgram::RaydiumAmmV4, &keys, &[&init]), None);
+    }
+}
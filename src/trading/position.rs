@@ -1,23 +1,41 @@
 use anyhow::{anyhow, Context, Result};
-use chrono::{DateTime, Duration as ChronoDuration, Utc}; // Added ChronoDuration
+use chrono::{DateTime, Duration as ChronoDuration, NaiveTime, Utc}; // Added ChronoDuration
 use rand::Rng; // For demo mode price updates
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Arc}; // Added PathBuf, FromStr
+use std::{collections::{HashMap, HashSet, VecDeque}, str::FromStr, sync::Arc}; // Added FromStr
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::{
-    fs, // Added tokio::fs for async file operations
-    sync::{Mutex, RwLock},
+    sync::{broadcast, mpsc, Mutex, RwLock},
     time::{interval, Duration},
 };
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
+use solana_sdk::pubkey::Pubkey;
 
-use crate::api::jupiter::JupiterClient;
-use crate::config::Config;
+use crate::api::jupiter::{JupiterClient, SellPreview, SwapRoute};
+use crate::api::swap_error::SwapError;
+use crate::config::{Config, LossRebuyGuard};
 use crate::error::TraderbotError;
 use crate::solana::client::SolanaClient;
+use crate::solana::fees::{FeeUrgency, PriorityFeeEstimator};
+use crate::storage::Store;
 use crate::solana::wallet::WalletManager;
+use crate::solana::wallet_pool::WalletPool;
+use crate::trading::accounting::{self, FillSide, TradeFill};
+use crate::trading::escalation::{self, EscalationManager, IncidentKind};
+use crate::trading::loss_breaker::{LossBreakerEvent, LossCircuitBreaker};
+use crate::trading::pnl_report::{PnlReport, ReportPeriod};
+use crate::trading::position_archive::{is_archivable, PositionArchive};
+use crate::trading::position_history::{
+    push_event, push_price_sample, PositionEvent, PositionEventKind, PriceSample, MAX_POSITION_EVENTS, MAX_PRICE_SAMPLES,
+};
+use crate::trading::risk::{fetch_transfer_tax_percent, net_of_transfer_tax, RiskAnalyzer};
+use crate::trading::rug_monitor::{self, RugSnapshot, RugThresholds};
+use crate::trading::strategy::{next_force_close, ExecutionVenue, MomentumTpSettings, ScaleInSettings, TradeNotify, VolatilityStopSettings};
+use crate::trading::swap_retry::SwapRetryLadder;
+use crate::trading::strategy_stats::{StrategyStatsHistory, StrategyStatsSnapshot};
 
-const POSITIONS_FILE: &str = "data/positions.json"; // Define persistence file path
+const ARCHIVE_INTERVAL_SECS: u64 = 3600; // How often old closed positions are moved to the archive
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)] // Added Eq
 pub enum PositionStatus {
@@ -27,12 +45,244 @@ pub enum PositionStatus {
     StopLossHit,
     TrailingStopHit,
     MaxHoldTimeReached,
+    ScheduledClose, // Strategy's force_close_at time reached
     ManualClose,
     EmergencyClose, // e.g., Rug pull detected
     Failed,         // e.g., Sell transaction failed
     Closed,         // Successfully sold and recorded
     ClosedManually, // Closed manually by user command
     Liquidated,     // Liquidated (not applicable for spot)
+    Unsellable,     // No sell route at all (liquidity pulled); tokens still held, written off
+}
+
+/// Price change per minute, in percent, between the oldest and newest sample.
+/// None until the samples span at least 30 seconds.
+fn velocity_percent_per_min(samples: &VecDeque<(DateTime<Utc>, f64)>) -> Option<f64> {
+    let (first_time, first_price) = *samples.front()?;
+    let (last_time, last_price) = *samples.back()?;
+    let minutes = (last_time - first_time).num_seconds() as f64 / 60.0;
+    if minutes < 0.5 || first_price <= 0.0 {
+        return None;
+    }
+    Some((last_price - first_price) / first_price * 100.0 / minutes)
+}
+
+/// Fewest samples in the window before a volatility stop is sized
+const MIN_VOLATILITY_SAMPLES: usize = 5;
+
+/// Standard deviation of the prices sampled over the last `window_minutes`, as a
+/// percent of their mean. None until the history spans half the window.
+fn price_volatility_percent(samples: &[PriceSample], now: DateTime<Utc>, window_minutes: u32) -> Option<f64> {
+    let window = ChronoDuration::minutes(window_minutes as i64);
+    if samples.first()?.timestamp > now - window / 2 {
+        return None;
+    }
+    let prices: Vec<f64> = samples.iter()
+        .filter(|s| s.timestamp >= now - window && s.price_sol > 0.0)
+        .map(|s| s.price_sol)
+        .collect();
+    if prices.len() < MIN_VOLATILITY_SAMPLES {
+        return None;
+    }
+    let mean = prices.iter().sum::<f64>() / prices.len() as f64;
+    let variance = prices.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / prices.len() as f64;
+    Some(variance.sqrt() / mean * 100.0)
+}
+
+/// New stop distance (percent below entry) for a position with a volatility stop, or
+/// None if it shouldn't move: not enough history yet, already sized and not
+/// re-evaluated, within half a point of the current distance, or tightened past its
+/// sized level by the max-hold deadline.
+fn volatility_stop_percent(position: &Position, now: DateTime<Utc>) -> Option<f64> {
+    let settings = position.volatility_stop?;
+    let current = position.stop_loss_distance_percent();
+    if position.volatility_stop_percent.is_some() && !settings.reevaluate {
+        return None;
+    }
+    let sized_level = current.map(|c| position.entry_price_sol * (1.0 - c / 100.0));
+    if let (Some(sl), Some(level)) = (position.stop_loss_price, sized_level) {
+        if sl > level * (1.0 + 1e-9) {
+            return None;
+        }
+    }
+    let volatility = price_volatility_percent(&position.price_history, now, settings.window_minutes)?;
+    let percent = settings.stop_percent(volatility);
+    if position.volatility_stop_percent.is_some() && current.is_some_and(|c| (c - percent).abs() < 0.5) {
+        return None;
+    }
+    Some(percent)
+}
+
+/// Distance to the nearest price trigger (percent of price) at which a position is
+/// checked at the slowest rate
+const QUIET_TRIGGER_DISTANCE_PERCENT: f64 = 10.0;
+
+/// Checks a position should get before its recent velocity could carry it to a trigger
+const CHECKS_BEFORE_TRIGGER: f64 = 3.0;
+
+/// Window of recent price history used to measure velocity for scheduling
+const SCHEDULING_VELOCITY_WINDOW_SECS: i64 = 120;
+
+/// Smallest profit fee worth a transfer (roughly its own network fee)
+const MIN_PROFIT_FEE_SOL: f64 = 0.00001;
+
+/// Absolute price change per minute, in percent, over the recent window
+fn recent_velocity_percent_per_min(samples: &[PriceSample], now: DateTime<Utc>) -> Option<f64> {
+    let cutoff = now - ChronoDuration::seconds(SCHEDULING_VELOCITY_WINDOW_SECS);
+    let last = samples.last()?;
+    let first = samples.iter().find(|s| s.timestamp >= cutoff)?;
+    let secs = (last.timestamp - first.timestamp).num_seconds();
+    if secs < 5 || first.price_sol <= 0.0 {
+        return None;
+    }
+    let change = (last.price_sol - first.price_sol) / first.price_sol * 100.0;
+    Some(change.abs() / (secs as f64 / 60.0))
+}
+
+/// Seconds until a position should be checked again. The interval shrinks with the
+/// distance to the nearest SL/TP/trailing level, and is capped so that at its recent
+/// velocity the price gets a few checks before it could reach that level. Max-hold
+/// and scheduled-close deadlines are never overshot.
+fn next_check_interval_secs(position: &Position, now: DateTime<Utc>, min_secs: u64, max_secs: u64) -> u64 {
+    if position.pending_exit_reason.is_some() {
+        return min_secs;
+    }
+    let max_secs = max_secs.max(min_secs);
+    let price = position.current_price_sol;
+    let mut interval = max_secs as f64;
+
+    if price > 0.0 {
+        let distance = [position.stop_loss_price, position.trailing_stop_price]
+            .into_iter()
+            .flatten()
+            .map(|level| (price - level) / price * 100.0)
+            .chain(position.monitored_take_profit().map(|tp| (tp - price) / price * 100.0))
+            .fold(f64::INFINITY, f64::min);
+        if distance <= 0.0 {
+            return min_secs;
+        }
+        if distance.is_finite() {
+            interval = interval.min(max_secs as f64 * distance / QUIET_TRIGGER_DISTANCE_PERCENT);
+            if let Some(velocity) = recent_velocity_percent_per_min(&position.price_history, now).filter(|v| *v > 0.0) {
+                interval = interval.min(distance / velocity * 60.0 / CHECKS_BEFORE_TRIGGER);
+            }
+        }
+    }
+
+    let max_hold_end = position.max_hold_time_minutes
+        .map(|m| position.entry_time + ChronoDuration::minutes(m as i64));
+    for deadline in [max_hold_end, position.force_close_at].into_iter().flatten() {
+        interval = interval.min((deadline - now).num_seconds().max(0) as f64);
+    }
+
+    (interval.round() as u64).clamp(min_secs, max_secs)
+}
+
+/// Stop-loss for a position nearing its max hold deadline, or None if it shouldn't move.
+/// Over the last `window_percent` of the hold window the SL rises from its original
+/// level (its stop distance below entry, or zero without one) toward `final_gap_percent`
+/// below the current price, following `progress^curve`. Never lowers the current SL.
+fn hold_deadline_stop_loss(
+    position: &Position,
+    now: DateTime<Utc>,
+    window_percent: f64,
+    curve: f64,
+    final_gap_percent: f64,
+) -> Option<f64> {
+    let max_minutes = position.max_hold_time_minutes.filter(|m| *m > 0)?;
+    if window_percent <= 0.0 {
+        return None;
+    }
+    let window = window_percent.min(100.0) / 100.0;
+    let held = (now - position.entry_time).num_milliseconds() as f64 / (max_minutes as f64 * 60_000.0);
+    let progress = ((held - (1.0 - window)) / window).clamp(0.0, 1.0);
+    if progress <= 0.0 {
+        return None;
+    }
+
+    let base = position.stop_loss_distance_percent()
+        .map_or(0.0, |sl| position.entry_price_sol * (1.0 - sl / 100.0));
+    let target = position.current_price_sol * (1.0 - final_gap_percent / 100.0);
+    if target <= base {
+        return None;
+    }
+    let level = base + (target - base) * progress.powf(curve.max(0.01));
+    (level > position.stop_loss_price.unwrap_or(0.0)).then_some(level)
+}
+
+/// What to do with a position after a failed exit sell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitFailureOutcome {
+    /// Back to Active with the exit pending; the next cycle sells again
+    Retry,
+    /// Retries or grace period used up: mark Failed
+    GiveUp,
+    /// Several consecutive no-route failures: the token can't be sold at all
+    Unroutable,
+}
+
+/// Counts a failed exit sell. Consecutive no-route failures (`no_route`) reaching
+/// `unroutable_after` mean the token is delisted/unroutable; otherwise the position is
+/// given up once the retries or the grace period are used up, or put back to Active
+/// with the exit still pending.
+#[allow(clippy::too_many_arguments)]
+fn register_exit_failure(
+    position: &mut Position,
+    reason: PositionStatus,
+    now: DateTime<Utc>,
+    max_retries: u32,
+    grace_minutes: u64,
+    unroutable_after: u32,
+    no_route: bool,
+    error: &str,
+) -> ExitFailureOutcome {
+    position.exit_failures += 1;
+    position.no_route_exit_failures = if no_route { position.no_route_exit_failures + 1 } else { 0 };
+    let first_failure = *position.first_exit_failure_at.get_or_insert(now);
+    position.record_event(
+        PositionEventKind::ExitFailed,
+        position.current_price_sol,
+        format!("attempt {}: {}", position.exit_failures, error),
+    );
+    if unroutable_after > 0 && position.no_route_exit_failures >= unroutable_after {
+        return ExitFailureOutcome::Unroutable;
+    }
+    if position.exit_failures > max_retries
+        || now - first_failure >= ChronoDuration::minutes(grace_minutes as i64)
+    {
+        return ExitFailureOutcome::GiveUp;
+    }
+    position.pending_exit_reason = Some(reason);
+    position.status = PositionStatus::Active;
+    ExitFailureOutcome::Retry
+}
+
+/// Profit fee owed on a closed position: `percent` of its net profit, or None for
+/// losing/break-even exits and fees too small to be worth a transfer
+fn profit_fee_sol(pnl_sol: Option<f64>, percent: f64) -> Option<f64> {
+    let profit = pnl_sol.filter(|p| *p > 0.0)?;
+    let fee = profit * percent / 100.0;
+    (fee >= MIN_PROFIT_FEE_SOL).then_some(fee)
+}
+
+/// Drawdown from the high (percent) if it has crossed the position's alert level
+/// and the alert hasn't fired since the last high; marks it fired.
+fn claim_drawdown_alert(position: &mut Position) -> Option<f64> {
+    let alert_percent = position.peak_drawdown_alert_percent?;
+    if position.drawdown_alert_fired || position.highest_price <= 0.0 {
+        return None;
+    }
+    let drawdown = (position.highest_price - position.current_price_sol) / position.highest_price * 100.0;
+    if drawdown < alert_percent {
+        return None;
+    }
+    position.drawdown_alert_fired = true;
+    position.record_event(
+        PositionEventKind::DrawdownAlert,
+        position.current_price_sol,
+        format!("{:.1}% below high of {:.9}", drawdown, position.highest_price),
+    );
+    Some(drawdown)
 }
 
 impl std::fmt::Display for PositionStatus {
@@ -44,16 +294,178 @@ impl std::fmt::Display for PositionStatus {
             Self::StopLossHit => write!(f, "SL Hit"),
             Self::TrailingStopHit => write!(f, "Trailing SL Hit"),
             Self::MaxHoldTimeReached => write!(f, "Max Hold Time"),
+            Self::ScheduledClose => write!(f, "Scheduled Close"),
             Self::ManualClose => write!(f, "Manual Close"),
             Self::EmergencyClose => write!(f, "Emergency Close"),
             Self::Failed => write!(f, "Failed"),
             Self::Closed => write!(f, "Closed"),
             Self::ClosedManually => write!(f, "Closed Manually"),
             Self::Liquidated => write!(f, "Liquidated"),
+            Self::Unsellable => write!(f, "Unsellable"),
+        }
+    }
+}
+
+/// Progress of a scale-in entry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScaleInState {
+    pub settings: ScaleInSettings,
+    pub base_price_sol: f64,      // First buy's price; tranche triggers are measured from it
+    pub initial_value_sol: f64,   // SOL spent on the first buy
+    pub filled_tranches: usize,   // Tranches bought (or skipped after a failure) so far
+}
+
+impl ScaleInState {
+    /// Index and SOL size of the next tranche, if its trigger is met
+    pub fn due_tranche(&self, current_price_sol: f64, minutes_held: i64) -> Option<(usize, f64)> {
+        let tranche = self.settings.tranches.get(self.filled_tranches)?;
+        if !tranche.trigger.is_met(self.base_price_sol, current_price_sol, minutes_held) {
+            return None;
+        }
+        let full_size_sol = self.initial_value_sol * 100.0 / self.settings.initial_percent;
+        Some((self.filled_tranches, full_size_sol * tranche.size_percent / 100.0))
+    }
+}
+
+/// A position opened or closed, for trade notifications, with its strategy's
+/// `notify_trades` so listeners don't have to look the strategy up
+#[derive(Debug, Clone)]
+pub enum PositionLifecycle {
+    Opened(Position, TradeNotify),
+    Closed(Position, TradeNotify),
+}
+
+impl PositionLifecycle {
+    /// Failed and emergency exits are alerts that go out whatever the strategy's preference
+    pub fn is_alert(&self) -> bool {
+        match self {
+            Self::Opened(..) => false,
+            Self::Closed(p, _) => {
+                matches!(p.status, PositionStatus::Failed | PositionStatus::EmergencyClose | PositionStatus::Unsellable)
+                    || p.exit_reason() == PositionStatus::EmergencyClose.to_string()
+            }
+        }
+    }
+
+    /// Whether this event should be sent: the strategy wants it, or it's an alert
+    pub fn should_notify(&self) -> bool {
+        self.is_alert() || match self {
+            Self::Opened(_, notify) => notify.on_open(),
+            Self::Closed(_, notify) => notify.on_close(),
         }
     }
 }
 
+/// What closing a position now would realize, from a live sell quote
+#[derive(Debug, Clone, Serialize)]
+pub struct ExitPreview {
+    pub position_id: String,
+    pub token_symbol: String,
+    pub token_amount: f64,
+    pub sellable: bool,              // False when no sell route exists (the position can't be exited now)
+    pub error: Option<String>,       // Why the quote failed
+    pub quote: Option<SellPreview>,
+    pub net_sol_out: Option<f64>,    // Expected SOL after transfer tax and fees
+    pub min_net_sol_out: Option<f64>, // Same, at worst-case slippage
+    pub realized_pnl_sol: Option<f64>,
+    pub realized_pnl_percent: Option<f64>,
+    pub worst_case_pnl_sol: Option<f64>,
+}
+
+/// A token the bot stopped out of at a loss, for the loss rebuy guard
+#[derive(Debug, Clone, Serialize)]
+pub struct LosingExit {
+    pub token_address: String,
+    pub token_symbol: String,
+    pub strategy_id: String,
+    pub exited_at: DateTime<Utc>,
+    pub pnl_sol: f64,
+    pub losses: u32,                 // Losing stop-outs on this token this session
+}
+
+/// Whether the loss rebuy guard still blocks a token stopped out at `exited_at`
+fn loss_rebuy_blocked(guard: LossRebuyGuard, cooldown_minutes: u64, exited_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    match guard {
+        LossRebuyGuard::Off => false,
+        LossRebuyGuard::Cooldown => now < exited_at + ChronoDuration::minutes(cooldown_minutes as i64),
+        LossRebuyGuard::Session => true,
+    }
+}
+
+/// Early warning that a position has fallen `peak_drawdown_alert_percent` from its high
+#[derive(Debug, Clone, Serialize)]
+pub struct DrawdownAlert {
+    pub position_id: String,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub drawdown_percent: f64,
+    pub highest_price_sol: f64,
+    pub price_sol: f64,
+    pub sold_percent: Option<f64>,   // Share of the holding de-risked, if a partial sell went through
+    pub sold_value_sol: Option<f64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Live price and unrealized PnL of an active position, streamed to the dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionPriceTick {
+    pub position_id: String,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub price_sol: f64,
+    pub entry_price_sol: f64,
+    pub highest_price_sol: f64,
+    pub pnl_sol: f64,
+    pub pnl_percent: f64,
+}
+
+impl From<&Position> for PositionPriceTick {
+    fn from(position: &Position) -> Self {
+        Self {
+            position_id: position.id.clone(),
+            token_address: position.token_address.clone(),
+            token_symbol: position.token_symbol.clone(),
+            price_sol: position.current_price_sol,
+            entry_price_sol: position.entry_price_sol,
+            highest_price_sol: position.highest_price,
+            pnl_sol: position.pnl_sol.unwrap_or(0.0),
+            pnl_percent: position.pnl_percent.unwrap_or(0.0),
+        }
+    }
+}
+
+/// Whether a price stream every `interval_secs` (0 = off) is due, given the last send
+fn price_stream_due(interval_secs: u64, last_sent: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    interval_secs > 0 && !last_sent.is_some_and(|t| now < t + ChronoDuration::seconds(interval_secs as i64))
+}
+
+/// Add `sol` valued at `sol_price_usd` to a USD total. An unknown price leaves the
+/// total unknown: a USD basis with a gap in it would be wrong, not approximate.
+fn add_usd(total_usd: Option<f64>, sol: f64, sol_price_usd: Option<f64>) -> Option<f64> {
+    Some(total_usd? + sol * sol_price_usd?)
+}
+
+/// Which strategy limit is holding back new buys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapacityLimit {
+    MaxConcurrentPositions,
+    Budget,
+}
+
+/// A strategy has stopped taking new trades because it's at its budget or position limit
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyCapacityAlert {
+    pub strategy_id: String,
+    pub strategy_name: String,
+    pub limit: CapacityLimit,
+    pub open_positions: usize,
+    pub max_concurrent_positions: u32,
+    pub deployed_sol: f64,
+    pub budget_sol: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
     pub id: String,                          // Unique position ID
@@ -78,135 +490,496 @@ pub struct Position {
     pub take_profit_price: Option<f64>,      // Take profit price (SOL per Token)
     pub trailing_stop_price: Option<f64>,    // Trailing stop price (SOL per Token)
     pub trailing_stop_percent: Option<u32>,  // Trailing stop percentage (used to update price)
+    #[serde(default)]
+    pub peak_drawdown_alert_percent: Option<f64>, // Drawdown from the high that raises an early warning
+    #[serde(default)]
+    pub peak_drawdown_sell_percent: Option<f64>,  // Share of the holding sold when the warning fires
+    #[serde(default)]
+    pub drawdown_alert_fired: bool,          // Warning already sent for the current high
+    #[serde(default)]
+    pub realized_value_sol: f64,             // SOL already taken out by partial sells
+    #[serde(default)]
+    pub pending_exit_reason: Option<PositionStatus>, // Exit still owed after a failed sell; retried each cycle
+    #[serde(default)]
+    pub exit_failures: u32,                  // Failed sell attempts for the pending exit
+    #[serde(default)]
+    pub no_route_exit_failures: u32,         // Consecutive failed sells that found no route
+    #[serde(default)]
+    pub first_exit_failure_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub unconfirmed_exit_tx: Option<String>, // Sell that timed out unconfirmed; checked before selling again
+    #[serde(default)]
+    pub unconfirmed_exit_value_sol: Option<f64>, // Expected SOL out of that sell
+    #[serde(default)]
+    pub price_history: Vec<PriceSample>,     // Bounded price series for replay
+    #[serde(default)]
+    pub events: Vec<PositionEvent>,          // Levels set/moved, exit trigger, close (for replay)
+    #[serde(default)]
+    pub fills: Vec<TradeFill>,               // Confirmed buys and sells, for lot accounting
     pub highest_price: f64,                  // Highest price seen since entry
     pub status: PositionStatus,              // Position status
     pub entry_tx_signature: String,          // Entry transaction signature
     pub exit_tx_signature: Option<String>,   // Exit transaction signature
     pub is_demo: bool,                       // Whether position is demo
     pub max_hold_time_minutes: Option<u32>,  // Maximum hold time in minutes (optional)
+    #[serde(default)]
+    pub force_close_at: Option<DateTime<Utc>>, // Scheduled exit time from the strategy (optional)
+    #[serde(default)]
+    pub momentum_tp: Option<MomentumTpSettings>, // Momentum-aware TP settings from the strategy (optional)
+    #[serde(default)]
+    pub effective_take_profit_percent: Option<f64>, // Current TP after momentum adjustment
+    #[serde(default)]
+    pub volatility_stop: Option<VolatilityStopSettings>, // Volatility-sized SL settings from the strategy (None = fixed percent)
+    #[serde(default)]
+    pub volatility_stop_percent: Option<f64>, // SL distance from the last volatility sizing (None until measured)
+    #[serde(default)]
+    pub scale_in: Option<ScaleInState>,      // Remaining tranches of a scale-in entry (optional)
     pub stop_loss_percent: Option<u32>,
     pub take_profit_percent: Option<u32>,
+    #[serde(default)]
+    pub wallet_address: Option<String>,      // Wallet holding the tokens (None = primary wallet)
+    #[serde(default)]
+    pub transfer_tax_percent: f64,           // Token-2022 transfer fee withheld on sell
+    #[serde(default)]
+    pub profit_fee_sol: Option<f64>,         // Profit share sent to the fee wallet on close
+    #[serde(default)]
+    pub profit_fee_tx: Option<String>,       // Transfer that paid the profit fee
+    #[serde(default)]
+    pub exit_slippage_bps: Option<u32>,      // Sell slippage calibrated to this token at entry (None = global default)
+    #[serde(default)]
+    pub entry_value_usd: Option<f64>,        // USD cost of the buys at the SOL price of each (None = not tracked)
+    #[serde(default)]
+    pub realized_value_usd: Option<f64>,     // USD value of partial sells so far
+    #[serde(default)]
+    pub exit_value_usd: Option<f64>,         // USD value of all sell proceeds at close
+    #[serde(default)]
+    pub pnl_usd: Option<f64>,                // Profit/loss in USD (includes SOL's own move)
+    #[serde(default)]
+    pub jito_tip_lamports: Option<u64>,      // Exits and scale-ins go out as Jito bundles with this tip (None = RPC)
+    #[serde(default)]
+    pub execution_venue: ExecutionVenue,     // Venue the entry was routed to; exits try it first
+    #[serde(default)]
+    pub bonding_curve_route: bool,           // Exits go to the pump.fun curve while the token is still on it
+    #[serde(default)]
+    pub take_profit_order: Option<TakeProfitOrder>, // Take-profit placed on-chain as a Jupiter limit sell
+    #[serde(default)]
+    pub notify_trades: TradeNotify,          // Strategy's trade alert preference when the position opened
+}
+
+impl Position {
+    /// Venue and Jito tip for this position's exits and scale-ins, as on entry
+    pub fn swap_route(&self) -> SwapRoute {
+        SwapRoute::new(self.execution_venue, self.jito_tip_lamports).with_bonding_curve(self.bonding_curve_route)
+    }
+
+    /// Take-profit the bot watches itself; None while it sits on-chain as a limit order
+    pub fn monitored_take_profit(&self) -> Option<f64> {
+        self.take_profit_price.filter(|_| self.take_profit_order.is_none())
+    }
+
+    /// Whether the position is too small to be worth showing (`threshold_sol` of 0 disables)
+    pub fn is_dust(&self, threshold_sol: f64) -> bool {
+        threshold_sol > 0.0 && self.entry_value_sol < threshold_sol
+    }
+
+    /// Current stop-loss distance below entry, in percent: the volatility-sized one once
+    /// measured, else the strategy's fixed percent (or a volatility stop's widest bound)
+    fn stop_loss_distance_percent(&self) -> Option<f64> {
+        self.volatility_stop_percent
+            .or(self.stop_loss_percent.map(f64::from))
+            .or(self.volatility_stop.map(|v| v.max_percent))
+    }
+
+    /// Why the position was exited: the last triggered exit, else its final status
+    pub fn exit_reason(&self) -> String {
+        self.events.iter().rev()
+            .find(|e| e.kind == PositionEventKind::ExitTriggered)
+            .map(|e| e.detail.clone())
+            .unwrap_or_else(|| self.status.to_string())
+    }
+
+    /// Realized (PnL SOL, PnL %) if the remaining tokens were sold for `net_sol_out`,
+    /// counting proceeds of earlier partial sells
+    pub fn pnl_if_sold_for(&self, net_sol_out: f64) -> (f64, f64) {
+        let pnl_sol = net_sol_out + self.realized_value_sol - self.entry_value_sol;
+        let pnl_percent = if self.entry_value_sol > 0.0 { pnl_sol / self.entry_value_sol * 100.0 } else { 0.0 };
+        (pnl_sol, pnl_percent)
+    }
+
+    /// Record a confirmed buy or sell for lot accounting
+    fn record_fill(&mut self, side: FillSide, token_amount: f64, sol_amount: f64, signature: &str) {
+        self.fills.push(TradeFill { timestamp: Utc::now(), side, token_amount, sol_amount, signature: signature.to_string() });
+    }
+
+    /// Add a decision point to the replay timeline
+    pub fn record_event(&mut self, kind: PositionEventKind, price_sol: f64, detail: String) {
+        let event = PositionEvent { timestamp: Utc::now(), kind, price_sol, detail };
+        push_event(&mut self.events, event, MAX_POSITION_EVENTS);
+    }
+
+    /// Record the stop-loss/take-profit/trailing levels currently in force
+    fn record_levels(&mut self) {
+        let detail = format!(
+            "entry {:.9} | SL {:?} | TP {:?} | trailing {:?}",
+            self.entry_price_sol, self.stop_loss_price, self.take_profit_price, self.trailing_stop_price
+        );
+        self.record_event(PositionEventKind::LevelsSet, self.current_price_sol, detail);
+    }
+}
+
+/// A take-profit resting on-chain as a limit sell of the whole position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TakeProfitOrder {
+    pub order: String,             // Trigger order account
+    pub min_sol_out: f64,          // SOL the fill pays at least
+    pub placed_at: DateTime<Utc>,
+}
+
+/// On-chain take-profit work that waits for confirmation, done off the buy and exit paths
+#[derive(Debug)]
+enum TakeProfitJob {
+    Place(String),  // Position id
+    Cancel(String),
+}
+
+/// Whether a position's tokens are free to sell, as far as its on-chain take-profit goes
+#[derive(Debug, PartialEq)]
+enum TakeProfitRelease {
+    Free,
+    Filled,  // The order filled; the position is closed
+    Pending, // A placement or cancel is still landing; try the sell again later
+}
+
+/// Marks a token's buy as in flight from before the swap is sent until its position
+/// exists (or the buy fails); dropping the guard clears the mark
+pub struct BuyInFlight {
+    token_address: String,
+    buys: Arc<std::sync::Mutex<HashMap<String, usize>>>,
+}
+
+impl Drop for BuyInFlight {
+    fn drop(&mut self) {
+        let mut buys = self.buys.lock().unwrap();
+        if let Some(count) = buys.get_mut(&self.token_address) {
+            *count -= 1;
+            if *count == 0 {
+                buys.remove(&self.token_address);
+            }
+        }
+    }
 }
 
 // Removed Debug derive as SolanaClient doesn't implement it
 pub struct PositionManager {
-    wallet_manager: Arc<WalletManager>,
+    wallet_pool: Arc<WalletPool>,
     jupiter_client: Arc<JupiterClient>,
     solana_client: Arc<SolanaClient>,
     // Use HashMap for efficient lookups by position ID
     positions: Arc<RwLock<HashMap<String, Position>>>,
+    // Closing positions whose sell swap has been handed to Jupiter (may already be broadcast)
+    exits_in_flight: Arc<RwLock<HashSet<String>>>,
+    // Tokens with a buy sent but no position recorded yet (count of concurrent buys)
+    buys_in_flight: Arc<std::sync::Mutex<HashMap<String, usize>>>,
     monitoring: Arc<RwLock<bool>>,
     config: Arc<Config>,
     task_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
-    persistence_path: PathBuf,
+    store: Arc<dyn Store>, // Where positions are persisted (JSON file or SQLite)
+    escalation: Arc<EscalationManager>,
+    risk_analyzer: Arc<RiskAnalyzer>, // Pool liquidity checks before exits
+    stats_history: Arc<StrategyStatsHistory>, // Per-strategy performance snapshots over time
+    price_samples: Arc<RwLock<HashMap<String, VecDeque<(DateTime<Utc>, f64)>>>>, // Recent prices per position, for momentum
+    drawdown_alert_tx: broadcast::Sender<DrawdownAlert>, // Peak-drawdown warnings for the web layer to forward
+    price_tick_tx: broadcast::Sender<Vec<PositionPriceTick>>, // Live prices of all active positions, for the dashboard
+    last_price_stream: Arc<RwLock<Option<DateTime<Utc>>>>,     // When prices were last streamed
+    next_checks: Arc<RwLock<HashMap<String, DateTime<Utc>>>>, // When each active position is next due a price check
+    rug_checks: Arc<RwLock<HashMap<String, (RugSnapshot, DateTime<Utc>, bool)>>>, // Rug monitor baseline, last check and unconfirmed liquidity pull per active position
+    lifecycle_tx: broadcast::Sender<PositionLifecycle>, // Opens and closes, for trade notifications
+    exit_slippage_hints: Arc<RwLock<HashMap<String, u32>>>, // Per-token sell slippage from risk analysis, taken by the next position opened
+    losing_exits: Arc<RwLock<HashMap<String, LosingExit>>>, // Tokens stopped out at a loss this session, for the rebuy guard
+    capacity_alert_tx: broadcast::Sender<StrategyCapacityAlert>, // Strategies that hit their budget/position limit
+    maxed_strategies: Arc<RwLock<HashSet<String>>>, // Strategies already alerted as maxed out, until a buy gets through
+    archive: Arc<PositionArchive>, // Old closed positions moved out of the live set
+    archiving: Arc<AtomicBool>,    // Archival task started
+    loss_breaker: Arc<LossCircuitBreaker>, // Pauses buys after losing streaks / daily drawdown
+    fee_estimator: Arc<PriorityFeeEstimator>, // Adaptive priority fees from recent blocks
+    take_profit_tx: mpsc::UnboundedSender<TakeProfitJob>, // On-chain take-profits to place or cancel
+    take_profit_rx: std::sync::Mutex<Option<mpsc::UnboundedReceiver<TakeProfitJob>>>, // Taken by the worker on start
+    take_profit_busy: Arc<std::sync::Mutex<HashSet<String>>>, // Positions with a take-profit job still landing
 }
 
 impl PositionManager {
     pub fn new(
-        wallet_manager: Arc<WalletManager>,
+        wallet_pool: Arc<WalletPool>,
         jupiter_client: Arc<JupiterClient>,
         solana_client: Arc<SolanaClient>,
         config: Arc<Config>,
+        escalation: Arc<EscalationManager>,
+        risk_analyzer: Arc<RiskAnalyzer>,
+        store: Arc<dyn Store>,
     ) -> Self {
-        let persistence_path = PathBuf::from(POSITIONS_FILE);
+        let archive = Arc::new(PositionArchive::new(store.clone()));
+        let (take_profit_tx, take_profit_rx) = mpsc::unbounded_channel();
         Self {
-            wallet_manager,
+            wallet_pool,
             jupiter_client,
-            solana_client,
             positions: Arc::new(RwLock::new(HashMap::new())),
+            exits_in_flight: Arc::new(RwLock::new(HashSet::new())),
+            buys_in_flight: Arc::new(std::sync::Mutex::new(HashMap::new())),
             monitoring: Arc::new(RwLock::new(false)),
-            config,
             task_handle: Arc::new(Mutex::new(None)),
-            persistence_path,
+            store,
+            escalation,
+            risk_analyzer,
+            stats_history: Arc::new(StrategyStatsHistory::new()),
+            price_samples: Arc::new(RwLock::new(HashMap::new())),
+            drawdown_alert_tx: broadcast::channel(32).0,
+            price_tick_tx: broadcast::channel(16).0,
+            last_price_stream: Arc::new(RwLock::new(None)),
+            next_checks: Arc::new(RwLock::new(HashMap::new())),
+            rug_checks: Arc::new(RwLock::new(HashMap::new())),
+            lifecycle_tx: broadcast::channel(64).0,
+            exit_slippage_hints: Arc::new(RwLock::new(HashMap::new())),
+            losing_exits: Arc::new(RwLock::new(HashMap::new())),
+            capacity_alert_tx: broadcast::channel(16).0,
+            maxed_strategies: Arc::new(RwLock::new(HashSet::new())),
+            archive,
+            archiving: Arc::new(AtomicBool::new(false)),
+            loss_breaker: Arc::new(LossCircuitBreaker::from_config(&config)),
+            fee_estimator: Arc::new(PriorityFeeEstimator::new(solana_client.clone(), config.clone())),
+            take_profit_tx,
+            take_profit_rx: std::sync::Mutex::new(Some(take_profit_rx)),
+            take_profit_busy: Arc::new(std::sync::Mutex::new(HashSet::new())),
+            solana_client,
+            config,
         }
     }
 
-    // --- Persistence ---
+    /// Priority fee for a swap of `token_address` at `urgency`; `fallback` when it
+    /// can't be estimated (or PRIORITY_FEE_AUTO is off)
+    pub async fn priority_fee(&self, token_address: &str, urgency: FeeUrgency, fallback: u64) -> u64 {
+        let accounts: Vec<Pubkey> = Pubkey::from_str(token_address).into_iter().collect();
+        self.fee_estimator.estimate(urgency, &accounts, fallback).await
+    }
 
-    // Loads positions from the JSON file into the in-memory HashMap.
-    async fn load_positions(&self) -> Result<()> {
-        // Ensure the data directory exists, create if not.
-        if let Some(dir) = self.persistence_path.parent() {
-            if !dir.exists() {
-                info!("Data directory not found, creating at: {:?}", dir);
-                fs::create_dir_all(dir).await.context("Failed to create data directory")?;
-            }
+    /// Exits compete at high urgency; without an estimate they pay double the default fee
+    async fn exit_priority_fee(&self, position: &Position) -> u64 {
+        let fallback = self.config.live().default_priority_fee_micro_lamports * 2;
+        self.priority_fee(&position.token_address, FeeUrgency::High, fallback).await
+    }
+
+    /// The losing exit that keeps a token from being bought again, if the loss rebuy
+    /// guard is on and still covers it
+    pub async fn rebuy_blocked_by_loss(&self, token_address: &str) -> Option<LosingExit> {
+        let exit = self.losing_exits.read().await.get(token_address).cloned()?;
+        loss_rebuy_blocked(self.config.loss_rebuy_guard, self.config.loss_rebuy_cooldown_minutes, exit.exited_at, Utc::now())
+            .then_some(exit)
+    }
+
+    /// Tokens currently blocked by the loss rebuy guard, most recent first
+    pub async fn loss_blacklist(&self) -> Vec<LosingExit> {
+        let now = Utc::now();
+        let mut exits: Vec<LosingExit> = self.losing_exits.read().await.values()
+            .filter(|e| loss_rebuy_blocked(self.config.loss_rebuy_guard, self.config.loss_rebuy_cooldown_minutes, e.exited_at, now))
+            .cloned()
+            .collect();
+        exits.sort_by(|a, b| b.exited_at.cmp(&a.exited_at));
+        exits
+    }
+
+    /// Let a token be bought again; returns false if it wasn't on the list
+    pub async fn clear_losing_exit(&self, token_address: &str) -> bool {
+        self.losing_exits.write().await.remove(token_address).is_some()
+    }
+
+    /// Remember a stop-out at a loss so the rebuy guard can skip the token
+    async fn record_losing_exit(&self, position: &Position) {
+        let is_stop = matches!(position.status, PositionStatus::StopLossHit | PositionStatus::TrailingStopHit);
+        let pnl_sol = position.pnl_sol.unwrap_or(0.0);
+        if !is_stop || pnl_sol >= 0.0 {
+            return;
         }
+        let mut exits = self.losing_exits.write().await;
+        let losses = exits.get(&position.token_address).map_or(0, |e| e.losses) + 1;
+        if self.config.loss_rebuy_guard != LossRebuyGuard::Off {
+            info!(
+                "{} stopped out at a loss ({:.4} SOL, {} this session); rebuys blocked ({:?})",
+                position.token_symbol, pnl_sol, losses, self.config.loss_rebuy_guard
+            );
+        }
+        exits.insert(position.token_address.clone(), LosingExit {
+            token_address: position.token_address.clone(),
+            token_symbol: position.token_symbol.clone(),
+            strategy_id: position.strategy_id.clone(),
+            exited_at: position.exit_time.unwrap_or_else(Utc::now),
+            pnl_sol,
+            losses,
+        });
+    }
 
-        // Check if the positions file exists. If not, it's okay, start fresh.
-        if !self.persistence_path.exists() {
-            info!("Positions file not found at {:?}, starting with empty state.", self.persistence_path);
-            return Ok(());
+    /// Sell slippage calibrated for a token about to be bought; the position opened
+    /// for it stores the value and uses it for its exits
+    pub async fn set_exit_slippage_hint(&self, token_address: &str, slippage_bps: u32) {
+        self.exit_slippage_hints.write().await.insert(token_address.to_string(), slippage_bps);
+    }
+
+    /// Drop an unused hint once the buy attempt is over
+    pub async fn clear_exit_slippage_hint(&self, token_address: &str) {
+        self.exit_slippage_hints.write().await.remove(token_address);
+    }
+
+    /// Slippage for selling a position: its per-token value capped at
+    /// `token_slippage_max_bps`, or the global default without one (or when disabled)
+    fn exit_slippage_bps(&self, position: &Position) -> u32 {
+        match position.exit_slippage_bps {
+            Some(bps) if self.config.token_slippage_max_bps > 0 => bps.min(self.config.token_slippage_max_bps),
+            _ => self.config.live().default_slippage_bps,
+        }
+    }
+
+    /// SOL/USD price for USD cost basis, if tracking is on and a live price is available
+    async fn sol_price_usd(&self) -> Option<f64> {
+        if !self.config.usd_pnl_tracking {
+            return None;
         }
+        self.risk_analyzer.live_sol_price_usd().await
+    }
 
-        info!("Loading positions from {:?}...", self.persistence_path);
-        let data = match fs::read_to_string(&self.persistence_path).await {
-            Ok(d) => d,
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                 info!("Positions file not found (race condition?), starting fresh.");
-                 return Ok(());
-            }
-            Err(e) => {
-                return Err(e).context(format!("Failed to read positions file: {:?}", self.persistence_path));
+    /// Receive peak-drawdown warnings
+    pub fn subscribe_drawdown_alerts(&self) -> broadcast::Receiver<DrawdownAlert> {
+        self.drawdown_alert_tx.subscribe()
+    }
+
+    /// Receive live prices and PnL of every active position, every
+    /// `position_price_stream_secs`
+    pub fn subscribe_price_ticks(&self) -> broadcast::Receiver<Vec<PositionPriceTick>> {
+        self.price_tick_tx.subscribe()
+    }
+
+    /// Send the current price and PnL of every active position if the stream
+    /// interval has passed. Positions are re-checked on their own schedules, so each
+    /// batch carries whatever price each one last got.
+    async fn stream_position_prices(&self) {
+        let now = Utc::now();
+        {
+            let mut last = self.last_price_stream.write().await;
+            if !price_stream_due(self.config.position_price_stream_secs, *last, now) {
+                return;
             }
-        };
+            *last = Some(now);
+        }
+        let ticks: Vec<PositionPriceTick> = self.positions.read().await.values()
+            .filter(|p| p.status == PositionStatus::Active)
+            .map(PositionPriceTick::from)
+            .collect();
+        if !ticks.is_empty() {
+            // Ignore errors (no subscribers)
+            let _ = self.price_tick_tx.send(ticks);
+        }
+    }
 
+    /// Receive alerts for strategies that stopped buying at their budget/position limit
+    pub fn subscribe_capacity_alerts(&self) -> broadcast::Receiver<StrategyCapacityAlert> {
+        self.capacity_alert_tx.subscribe()
+    }
 
-        if data.trim().is_empty() {
-             info!("Positions file is empty.");
-             return Ok(());
+    /// A buy was skipped because the strategy is at a limit. Alerts the first time
+    /// only; later skips stay quiet until `strategy_has_capacity` re-arms it.
+    pub async fn strategy_maxed_out(&self, alert: StrategyCapacityAlert) {
+        if !self.maxed_strategies.write().await.insert(alert.strategy_id.clone()) {
+            return;
         }
+        warn!(
+            "Strategy '{}' is maxed out ({:?}): {} open / {} max, {:.4} of {:.4} SOL deployed. New buys are skipped until capacity frees.",
+            alert.strategy_name, alert.limit, alert.open_positions, alert.max_concurrent_positions, alert.deployed_sol, alert.budget_sol
+        );
+        if self.config.strategy_capacity_alerts {
+            // Ignore errors (no subscribers)
+            let _ = self.capacity_alert_tx.send(alert);
+        }
+    }
 
-        // Deserialize from JSON into a Vec<Position>
-        let loaded_positions: Vec<Position> = match serde_json::from_str(&data) {
-             Ok(p) => p,
-             Err(e) => {
-                  error!("Failed to deserialize positions data from {:?}: {}. Starting with empty state.", self.persistence_path, e);
-                  // Optionally back up the corrupted file here
-                  return Ok(()); // Don't crash, just start fresh
-             }
+    /// The strategy has room for a buy again; re-arm its capacity alert
+    pub async fn strategy_has_capacity(&self, strategy_id: &str) {
+        if self.maxed_strategies.write().await.remove(strategy_id) {
+            info!("Strategy {} has capacity for new buys again", strategy_id);
+        }
+    }
+
+    /// Circuit breaker that pauses buys after losing trades
+    pub fn loss_breaker(&self) -> &Arc<LossCircuitBreaker> {
+        &self.loss_breaker
+    }
+
+    /// Receive loss circuit breaker trips and resumes
+    pub fn subscribe_loss_breaker(&self) -> broadcast::Receiver<LossBreakerEvent> {
+        self.loss_breaker.subscribe()
+    }
+
+    /// Incident escalation shared with the AutoTrader
+    pub fn escalation(&self) -> &EscalationManager {
+        &self.escalation
+    }
+
+    /// Receive every position open and close
+    pub fn subscribe_lifecycle(&self) -> broadcast::Receiver<PositionLifecycle> {
+        self.lifecycle_tx.subscribe()
+    }
+
+    // --- Persistence ---
+
+    // Loads positions from the store into the in-memory HashMap.
+    async fn load_positions(&self) -> Result<()> {
+        // Missing storage is fine (start fresh). Unreadable storage is not: starting with
+        // empty state would forget tokens we hold and could buy them again, so transient
+        // read errors are retried with backoff and a persistent one refuses to start.
+        info!("Loading positions from {}...", self.store.describe());
+        let mut attempt = 0;
+        let loaded_positions = loop {
+            match self.store.load_positions().await {
+                Ok(p) => break p,
+                Err(e) if attempt < self.config.position_load_retries => {
+                    attempt += 1;
+                    let delay = Duration::from_millis(self.config.position_load_retry_delay_ms * (1 << (attempt - 1)));
+                    warn!(
+                        "Failed to load positions from {} ({:#}), retrying in {}ms ({}/{})",
+                        self.store.describe(), e, delay.as_millis(), attempt, self.config.position_load_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => {
+                    error!("Positions in {} are unreadable after {} retries - refusing to start trading", self.store.describe(), attempt);
+                    return Err(e).context(format!(
+                        "Failed to load positions from {}; refusing to start with empty positions", self.store.describe()
+                    ));
+                }
+            }
         };
 
         // Populate the in-memory HashMap
         let mut positions_map = self.positions.write().await;
         positions_map.clear(); // Clear existing in-memory positions first
         for pos in loaded_positions {
-            // Filter out positions that shouldn't be loaded (e.g., already closed/failed long ago?)
-            // For now, load all states. Consider filtering later if needed.
             positions_map.insert(pos.id.clone(), pos);
         }
-        info!("Loaded {} positions from file.", positions_map.len());
+        info!("Loaded {} positions.", positions_map.len());
+        // Pick up today's PnL and losing streaks where the last run left them
+        self.loss_breaker.restore(
+            positions_map.values()
+                .filter(|p| p.status != PositionStatus::Failed)
+                .filter_map(|p| Some((p.strategy_id.clone(), p.pnl_sol?, p.exit_time?))),
+            Utc::now(),
+        );
         Ok(())
     }
 
-    // Saves the current in-memory positions HashMap to the JSON file.
+    // Saves the current in-memory positions HashMap to the store.
     async fn save_positions(&self) -> Result<()> {
         debug!("Saving positions state...");
         let positions_map = self.positions.read().await;
         // No need to filter here, save the complete current state
         let positions_vec: Vec<&Position> = positions_map.values().collect(); // Collect references
-
-        // Ensure the directory exists
-        if let Some(dir) = self.persistence_path.parent() {
-             // No need to check existence again if load_positions already did,
-             // but create_dir_all is idempotent.
-            fs::create_dir_all(dir).await.context("Failed to create data directory")?;
-        }
-
-        // Serialize Vec<&Position> to JSON string
-        let data = serde_json::to_string_pretty(&positions_vec)
-            .context("Failed to serialize positions")?;
-
-        // Write data to the file atomically (optional but safer)
-        // Using a temporary file and rename can prevent data loss if write fails mid-way.
-        let temp_path = self.persistence_path.with_extension("json.tmp");
-        fs::write(&temp_path, data).await
-            .context(format!("Failed to write temporary positions file: {:?}", temp_path))?;
-        fs::rename(&temp_path, &self.persistence_path).await
-             .context(format!("Failed to rename temporary positions file to {:?}", self.persistence_path))?;
-
-
-        debug!("Saved {} positions to file: {:?}", positions_vec.len(), self.persistence_path);
+        self.store.save_positions(&positions_vec).await?;
+        debug!("Saved {} positions to {}", positions_vec.len(), self.store.describe());
         Ok(())
     }
 
@@ -229,7 +1002,17 @@ impl PositionManager {
         stop_loss_percent: Option<u32>,
         take_profit_percent: Option<u32>,
         trailing_stop_percent: Option<u32>,
+        peak_drawdown_alert_percent: Option<f64>,
+        peak_drawdown_sell_percent: Option<f64>,
         max_hold_time_minutes: Option<u32>, // Changed to Option<u32>
+        force_close_at: Option<NaiveTime>, // Strategy's daily UTC close time
+        momentum_tp: Option<MomentumTpSettings>,
+        volatility_stop: Option<VolatilityStopSettings>, // Size the SL from price volatility once measured
+        scale_in: Option<ScaleInSettings>, // Tranches still to buy after this first one
+        wallet_address: Option<&str>, // Wallet that bought the tokens (None = primary)
+        route: SwapRoute, // The strategy's venue and Jito tip, reused for this position's sells
+        transfer_tax_percent: Option<f64>, // From the buy's risk analysis; read from the mint when None
+        notify_trades: TradeNotify, // The strategy's trade alert preference, sent with lifecycle events
     ) -> Result<Position> {
         let now = Utc::now();
 
@@ -255,13 +1038,34 @@ impl PositionManager {
             );
         }
 
-        let stop_loss_price = stop_loss_percent.map(|sl| entry_price_sol * (1.0 - (sl as f64 / 100.0)));
+        // A volatility stop starts at the fixed percent (or its widest bound) until there's history to size it
+        let stop_loss_price = stop_loss_percent.map(f64::from)
+            .or(volatility_stop.map(|v| v.max_percent))
+            .map(|sl| entry_price_sol * (1.0 - sl / 100.0));
         let take_profit_price = take_profit_percent.map(|tp| entry_price_sol * (1.0 + (tp as f64 / 100.0)));
         // Initial trailing stop is based on entry price and percentage
         let trailing_stop_price = trailing_stop_percent.map(|ts| entry_price_sol * (1.0 - (ts as f64 / 100.0)));
 
+        // Taxed tokens return less SOL on sell than the quote suggests; remember the tax for PnL
+        let transfer_tax_percent = match transfer_tax_percent {
+            Some(tax) => tax,
+            None if self.config.demo_mode => 0.0,
+            None => match Pubkey::from_str(token_address) {
+                Ok(mint) => fetch_transfer_tax_percent(&self.solana_client, &mint).await.unwrap_or(0.0),
+                Err(_) => 0.0,
+            },
+        };
+
+        let exit_slippage_bps = self.exit_slippage_hints.write().await.remove(token_address);
+        let entry_value_usd = self.sol_price_usd().await.map(|p| entry_value_sol * p);
+        if let Some(bps) = exit_slippage_bps {
+            info!(
+                "Exit slippage for {} calibrated to {} bps from risk analysis (default {} bps, cap {} bps)",
+                token_symbol, bps, self.config.live().default_slippage_bps, self.config.token_slippage_max_bps
+            );
+        }
 
-        let position = Position {
+        let mut position = Position {
             id: Uuid::new_v4().to_string(),
             token_address: token_address.to_string(),
             token_name: token_name.to_string(),
@@ -284,15 +1088,60 @@ impl PositionManager {
             take_profit_price,
             trailing_stop_price,
             trailing_stop_percent, // Store the percentage
+            peak_drawdown_alert_percent,
+            peak_drawdown_sell_percent: peak_drawdown_sell_percent.filter(|_| peak_drawdown_alert_percent.is_some()),
+            drawdown_alert_fired: false,
+            realized_value_sol: 0.0,
+            pending_exit_reason: None,
+            exit_failures: 0,
+            no_route_exit_failures: 0,
+            first_exit_failure_at: None,
+            unconfirmed_exit_tx: None,
+            unconfirmed_exit_value_sol: None,
+            price_history: vec![PriceSample { timestamp: now, price_sol: entry_price_sol }],
+            events: Vec::new(),
+            fills: Vec::new(),
             highest_price: entry_price_sol, // Initial highest price is entry price
             status: PositionStatus::Active,
             entry_tx_signature: entry_tx_sig.to_string(),
             exit_tx_signature: None,
             is_demo: self.config.demo_mode,
             max_hold_time_minutes,
+            force_close_at: force_close_at.map(|at| next_force_close(now, at)),
+            momentum_tp: momentum_tp.filter(|_| take_profit_percent.is_some()),
+            effective_take_profit_percent: take_profit_percent.map(|tp| tp as f64),
+            volatility_stop,
+            volatility_stop_percent: None,
+            scale_in: scale_in.map(|settings| ScaleInState {
+                settings,
+                base_price_sol: entry_price_sol,
+                initial_value_sol: entry_value_sol,
+                filled_tranches: 0,
+            }),
             stop_loss_percent,
             take_profit_percent,
+            wallet_address: wallet_address.map(|a| a.to_string()),
+            transfer_tax_percent,
+            profit_fee_sol: None,
+            profit_fee_tx: None,
+            exit_slippage_bps,
+            entry_value_usd,
+            realized_value_usd: entry_value_usd.map(|_| 0.0),
+            exit_value_usd: None,
+            pnl_usd: None,
+            jito_tip_lamports: route.jito_tip_lamports,
+            execution_venue: route.venue,
+            bonding_curve_route: route.bonding_curve,
+            take_profit_order: None,
+            notify_trades,
         };
+        position.record_fill(FillSide::Buy, entry_token_amount, entry_value_sol, entry_tx_sig);
+        position.record_event(
+            PositionEventKind::Opened,
+            entry_price_sol,
+            format!("bought {:.4} tokens for {:.4} SOL (tx {})", entry_token_amount, entry_value_sol, entry_tx_sig),
+        );
+        position.record_levels();
 
         info!(
             "Creating new position (ID: {}): {} ({}) | Entry SOL: {:.4} | Entry Tokens: {:.4}/{:.4} ({:.1}%) | Entry Price: {:.6} SOL/Token | SL: {:?} | TP: {:?} | Trail: {:?}",
@@ -314,6 +1163,15 @@ impl PositionManager {
         drop(positions); // Release lock before saving
 
         self.save_positions().await?;
+        let _ = self.lifecycle_tx.send(PositionLifecycle::Opened(position.clone(), position.notify_trades));
+
+        // Scale-ins add tokens and momentum take-profits move after entry, so those
+        // keep the monitored take-profit. Placing waits for confirmation, so the buy doesn't.
+        if self.config.onchain_take_profit && !position.is_demo && position.take_profit_price.is_some()
+            && position.scale_in.is_none() && position.momentum_tp.is_none()
+        {
+            self.queue_take_profit_job(TakeProfitJob::Place(position.id.clone()));
+        }
 
         Ok(position)
     }
@@ -364,13 +1222,16 @@ impl PositionManager {
         
         // Update position
         position.entry_token_amount = actual_token_amount;
+        if let Some(entry_fill) = position.fills.iter_mut().find(|f| f.side == FillSide::Buy) {
+            entry_fill.token_amount = actual_token_amount;
+        }
         position.fill_percent = fill_percent;
         position.entry_price_sol = entry_price_sol;
         position.current_price_sol = entry_price_sol; // Also update current price
         
         // Recalculate stop loss and take profit prices
-        if let Some(sl_percent) = position.stop_loss_percent {
-            position.stop_loss_price = Some(entry_price_sol * (1.0 - (sl_percent as f64 / 100.0)));
+        if let Some(sl_percent) = position.stop_loss_distance_percent() {
+            position.stop_loss_price = Some(entry_price_sol * (1.0 - sl_percent / 100.0));
         }
         
         if let Some(tp_percent) = position.take_profit_percent {
@@ -386,6 +1247,7 @@ impl PositionManager {
         if position.highest_price < entry_price_sol {
             position.highest_price = entry_price_sol;
         }
+        position.record_levels();
         
         let updated_position = position.clone();
         drop(positions); // Release lock before saving
@@ -395,6 +1257,74 @@ impl PositionManager {
         Ok(updated_position)
     }
 
+    /// Add a scale-in tranche to an open position, averaging the entry price over all
+    /// buys and moving the stop-loss/take-profit with it. The trailing stop follows the
+    /// highest price, not the entry, so it is left alone.
+    pub async fn add_to_position(
+        &self,
+        position_id: &str,
+        added_value_sol: f64,
+        added_token_amount: f64,
+        tx_sig: &str,
+    ) -> Result<Position> {
+        if added_value_sol <= 0.0 || added_token_amount <= 0.0 {
+            return Err(anyhow!("Invalid scale-in amounts: SOL={}, Token={}", added_value_sol, added_token_amount));
+        }
+
+        let sol_price_usd = self.sol_price_usd().await;
+        let mut positions = self.positions.write().await;
+        let position = positions.get_mut(position_id)
+            .ok_or_else(|| TraderbotError::PositionError(format!("Position ID {} not found for scale-in", position_id)))?;
+        if position.status != PositionStatus::Active {
+            return Err(anyhow!("Cannot add to non-active position: {}", position_id));
+        }
+
+        // Average by cost per token held, which stays right after partial sells
+        let previous_price = position.entry_price_sol;
+        let held_cost_sol = previous_price * position.entry_token_amount;
+        position.entry_value_sol += added_value_sol;
+        position.entry_value_usd = add_usd(position.entry_value_usd, added_value_sol, sol_price_usd);
+        position.entry_token_amount += added_token_amount;
+        position.expected_token_amount += added_token_amount;
+        position.fill_percent = if position.expected_token_amount > 0.0 {
+            position.entry_token_amount / position.expected_token_amount
+        } else {
+            1.0
+        };
+        position.entry_price_sol = (held_cost_sol + added_value_sol) / position.entry_token_amount;
+
+        if let Some(sl_percent) = position.stop_loss_distance_percent() {
+            position.stop_loss_price = Some(position.entry_price_sol * (1.0 - sl_percent / 100.0));
+        }
+        if let Some(tp_percent) = position.effective_take_profit_percent {
+            position.take_profit_price = Some(position.entry_price_sol * (1.0 + tp_percent / 100.0));
+        }
+        let exit_value = net_of_transfer_tax(position.entry_token_amount * position.current_price_sol, position.transfer_tax_percent)
+            + position.realized_value_sol;
+        position.pnl_sol = Some(exit_value - position.entry_value_sol);
+        position.pnl_percent = Some(exit_value / position.entry_value_sol * 100.0 - 100.0);
+
+        position.record_fill(FillSide::Buy, added_token_amount, added_value_sol, tx_sig);
+        position.record_event(
+            PositionEventKind::ScaledIn,
+            position.current_price_sol,
+            format!("+{:.4} SOL for {:.4} tokens (tx {})", added_value_sol, added_token_amount, tx_sig),
+        );
+        position.record_levels();
+
+        info!(
+            "Scaled into {} (ID: {}): +{:.4} SOL for {:.4} tokens (tx {}) | Avg entry {:.6} -> {:.6} SOL/Token | Total {:.4} SOL",
+            position.token_symbol, position_id, added_value_sol, added_token_amount, tx_sig,
+            previous_price, position.entry_price_sol, position.entry_value_sol
+        );
+
+        let updated_position = position.clone();
+        drop(positions);
+
+        self.save_positions().await?;
+        Ok(updated_position)
+    }
+
     pub async fn create_demo_position(
         &self,
         token_address: &str,
@@ -422,7 +1352,17 @@ impl PositionManager {
             Some(15), // 15% SL
             Some(50), // 50% TP
             Some(5),  // 5% Trailing SL
+            None, // No drawdown warning for demo positions
+            None,
             Some(240),      // 4 hours max hold (Wrapped in Some)
+            None, // No scheduled close for demo positions
+            None,
+            None,
+            None,
+            None,
+            SwapRoute::default(),
+            None,
+            TradeNotify::default(),
         ).await
     }
 
@@ -434,6 +1374,7 @@ impl PositionManager {
         exit_value_sol: f64,
         exit_tx_sig: &str,
     ) -> Result<Position> {
+        let sol_price_usd = self.sol_price_usd().await;
         let mut positions = self.positions.write().await;
         let position = positions.get_mut(position_id)
             .ok_or_else(|| TraderbotError::PositionError(format!("Position ID {} not found for closing", position_id)))?;
@@ -447,7 +1388,18 @@ impl PositionManager {
         let now = Utc::now();
         position.exit_time = Some(now);
         position.status = status; // Use the provided final status (Closed, Failed, etc.)
+        position.pending_exit_reason = None;
+        position.unconfirmed_exit_tx = None;
         position.exit_price_sol = Some(exit_price_sol);
+        // A Failed/Unsellable close sold nothing; the tokens stay in open lots
+        if !matches!(position.status, PositionStatus::Failed | PositionStatus::Unsellable) {
+            let sold_tokens = position.entry_token_amount;
+            position.record_fill(FillSide::Sell, sold_tokens, exit_value_sol, exit_tx_sig);
+        }
+        position.exit_value_usd = add_usd(position.realized_value_usd, exit_value_sol, sol_price_usd);
+        position.pnl_usd = position.exit_value_usd.zip(position.entry_value_usd).map(|(exit, entry)| exit - entry);
+        // Proceeds of earlier partial sells count toward the exit
+        let exit_value_sol = exit_value_sol + position.realized_value_sol;
         position.exit_value_sol = Some(exit_value_sol);
         position.exit_tx_signature = Some(exit_tx_sig.to_string());
 
@@ -460,19 +1412,392 @@ impl PositionManager {
             position.pnl_percent = Some(0.0);
         }
 
+        position.record_event(
+            PositionEventKind::Closed,
+            exit_price_sol,
+            format!("{} | PnL {:.4} SOL ({:.2}%) | tx {}", position.status, pnl_sol, position.pnl_percent.unwrap_or(0.0), exit_tx_sig),
+        );
+
         info!(
-            "Closed position {} ({}) | Status: {} | PnL: {:.4} SOL ({:.2}%) | Exit Sig: {}",
+            "Closed position {} ({}) | Status: {} | PnL: {:.4} SOL ({:.2}%){} | Exit Sig: {}",
             position.token_symbol, position_id, position.status,
-            pnl_sol, position.pnl_percent.unwrap_or(0.0), exit_tx_sig
+            pnl_sol, position.pnl_percent.unwrap_or(0.0),
+            position.pnl_usd.map(|usd| format!(" / ${:.2}", usd)).unwrap_or_default(),
+            exit_tx_sig
         );
 
         let closed_position = position.clone();
         drop(positions); // Release lock before saving
+        self.price_samples.write().await.remove(position_id);
+        self.next_checks.write().await.remove(position_id);
+        self.record_losing_exit(&closed_position).await;
+        // A Failed close never got a sell through; its PnL says nothing about the strategy
+        if closed_position.status != PositionStatus::Failed {
+            self.loss_breaker.record_close(&closed_position.strategy_id, pnl_sol, now);
+        }
 
         self.save_positions().await?;
+        self.snapshot_strategy_stats(Some(&closed_position.strategy_id)).await;
+        let _ = self.lifecycle_tx.send(PositionLifecycle::Closed(closed_position.clone(), closed_position.notify_trades));
         Ok(closed_position)
     }
 
+    // --- Strategy Stats History ---
+
+    /// Append a performance snapshot for one strategy, or for every strategy with positions
+    pub async fn snapshot_strategy_stats(&self, strategy_id: Option<&str>) {
+        let now = Utc::now();
+        let dust_threshold = self.config.hide_positions_below_sol;
+        // Archived trades still count toward a strategy's totals
+        let archived = self.archive.trades().await;
+        let snapshots: Vec<StrategyStatsSnapshot> = {
+            let positions = self.positions.read().await;
+            let strategy_ids: HashSet<&str> = match strategy_id {
+                Some(id) => HashSet::from([id]),
+                None => positions.values().map(|p| p.strategy_id.as_str())
+                    .chain(archived.iter().map(|t| t.strategy_id.as_str()))
+                    .collect(),
+            };
+            strategy_ids.into_iter()
+                .map(|id| {
+                    let trades = positions.values()
+                        .filter(|p| p.strategy_id == id && !p.is_dust(dust_threshold))
+                        .filter_map(|p| p.exit_value_sol.map(|exit| (p.entry_value_sol, exit)))
+                        .chain(archived.iter()
+                            .filter(|t| t.strategy_id == id && !(dust_threshold > 0.0 && t.entry_value_sol < dust_threshold))
+                            .map(|t| (t.entry_value_sol, t.exit_value_sol)));
+                    StrategyStatsSnapshot::from_closed_trades(id, trades, now)
+                })
+                .collect()
+        };
+
+        if let Err(e) = self.stats_history.record(snapshots).await {
+            warn!("Failed to record strategy stats snapshot: {:?}", e);
+        }
+    }
+
+    /// Move closed positions older than `position_archive_after_days` from the live
+    /// positions to the store's archive. Returns how many were moved.
+    pub async fn archive_closed_positions(&self) -> Result<usize> {
+        if self.config.position_archive_after_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = Utc::now() - ChronoDuration::days(self.config.position_archive_after_days as i64);
+        let mut positions = self.positions.write().await;
+        let archived: Vec<Position> = positions.values().filter(|p| is_archivable(p, cutoff)).cloned().collect();
+        if archived.is_empty() {
+            return Ok(0);
+        }
+        // Archive first: if that fails, everything stays live
+        self.archive.append(&archived).await?;
+        for position in &archived {
+            positions.remove(&position.id);
+        }
+        drop(positions); // Release lock before saving
+        self.save_positions().await?;
+
+        info!(
+            "🗄️ Archived {} closed positions older than {} days",
+            archived.len(), self.config.position_archive_after_days
+        );
+        Ok(archived.len())
+    }
+
+    /// Closed positions moved to the archive, read from the store
+    pub async fn get_archived_positions(&self) -> Result<Vec<Position>> {
+        self.archive.read_all().await
+    }
+
+    /// Current positions plus archived ones, for historical stats and exports
+    pub async fn get_all_positions_with_archive(&self) -> Result<Vec<Position>> {
+        let mut all = self.get_all_positions().await;
+        let live: HashSet<String> = all.iter().map(|p| p.id.clone()).collect();
+        all.extend(self.archive.read_all().await?.into_iter().filter(|p| !live.contains(&p.id)));
+        Ok(all)
+    }
+
+    /// PnL summary of the trades closed in `from`..`to`, archived ones included
+    pub async fn pnl_report(&self, period: ReportPeriod, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<PnlReport> {
+        let positions = self.get_all_positions_with_archive().await?;
+        let trades = accounting::closed_trades(&positions, Some(from), Some(to));
+        Ok(PnlReport::compile(period, from, to, &trades))
+    }
+
+    /// Start the hourly archival of old closed positions (no-op when disabled or already started)
+    fn spawn_archiver(self: Arc<Self>) {
+        if self.config.position_archive_after_days == 0 || self.archiving.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        tokio::spawn(async move {
+            let mut timer = interval(Duration::from_secs(ARCHIVE_INTERVAL_SECS));
+            loop {
+                timer.tick().await;
+                if let Err(e) = self.archive_closed_positions().await {
+                    warn!("Failed to archive closed positions: {:?}", e);
+                }
+            }
+        });
+    }
+
+    /// Recorded performance series for a strategy, oldest first
+    pub async fn strategy_stats_history(&self, strategy_id: &str) -> Vec<StrategyStatsSnapshot> {
+        self.stats_history.history(strategy_id).await
+    }
+
+    /// Best-effort abort of a pending exit: reverts a `Closing` position back to `Active`
+    /// as long as its sell swap hasn't been started yet. A sell transaction that has
+    /// already been sent cannot be recalled, so this fails once the swap is underway.
+    pub async fn cancel_exit(&self, position_id: &str) -> Result<Position> {
+        let mut positions = self.positions.write().await;
+        let position = positions.get_mut(position_id)
+            .ok_or_else(|| TraderbotError::PositionError(format!("Position ID {} not found", position_id)))?;
+
+        if position.status != PositionStatus::Closing && position.pending_exit_reason.is_none() {
+            return Err(anyhow!("Position {} is not pending exit (status: {})", position_id, position.status));
+        }
+        if self.exits_in_flight.read().await.contains(position_id) {
+            return Err(anyhow!("Sell for position {} has already been sent and cannot be cancelled", position_id));
+        }
+
+        position.status = PositionStatus::Active;
+        // Also drops an exit awaiting retry after a failed sell
+        position.pending_exit_reason = None;
+        position.exit_failures = 0;
+        position.first_exit_failure_at = None;
+        info!("Cancelled pending exit for position {} ({}), back to Active", position.token_symbol, position_id);
+
+        let reverted = position.clone();
+        drop(positions);
+
+        self.save_positions().await?;
+        Ok(reverted)
+    }
+
+    /// Sell an active position on the next management cycle, cancelling any on-chain
+    /// take-profit first
+    pub async fn request_close(&self, position_id: &str) -> Result<Position> {
+        let mut positions = self.positions.write().await;
+        let position = positions.get_mut(position_id)
+            .ok_or_else(|| TraderbotError::PositionError(format!("Position ID {} not found", position_id)))?;
+
+        if position.status != PositionStatus::Active {
+            return Err(anyhow!("Position {} is not active (status: {})", position_id, position.status));
+        }
+        position.pending_exit_reason = Some(PositionStatus::ManualClose);
+        info!("Manual close requested for position {} ({})", position.token_symbol, position_id);
+
+        let requested = position.clone();
+        drop(positions);
+
+        self.next_checks.write().await.remove(position_id);
+        self.save_positions().await?;
+        Ok(requested)
+    }
+
+    /// Puts a Closing position back to Active without selling (the exit condition
+    /// will fire again on a later cycle)
+    async fn hold_exit(&self, position_id: &str) -> Result<()> {
+        {
+            let mut positions = self.positions.write().await;
+            if let Some(position) = positions.get_mut(position_id) {
+                if position.status == PositionStatus::Closing {
+                    position.status = PositionStatus::Active;
+                }
+            }
+        }
+        self.save_positions().await
+    }
+
+    /// Where profit fees go, if profit sharing is configured
+    fn profit_fee_destination(&self) -> Option<Pubkey> {
+        if self.config.profit_fee_percent <= 0.0 {
+            return None;
+        }
+        self.config.profit_fee_wallet.as_deref().and_then(|w| Pubkey::from_str(w).ok())
+    }
+
+    /// Sends the configured share of a profitable close to the fee wallet and records
+    /// it on the position. Failures are logged only; the close itself already stands.
+    async fn collect_profit_fee(&self, position: &Position) {
+        if position.is_demo {
+            return;
+        }
+        let Some(destination) = self.profit_fee_destination() else {
+            return;
+        };
+        let Some(fee_sol) = profit_fee_sol(position.pnl_sol, self.config.profit_fee_percent) else {
+            debug!("No profit fee for position {} (PnL {:?} SOL)", position.id, position.pnl_sol);
+            return;
+        };
+
+        let result = match self.wallet_for_position(position) {
+            Ok(wallet) => wallet.transfer_sol(&destination, fee_sol, self.config.confirm_timeout_secs).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(signature) => {
+                info!(
+                    "💸 Profit fee of {:.6} SOL ({:.2}% of {:.6} SOL profit) for position {} sent to {}: {}",
+                    fee_sol, self.config.profit_fee_percent, position.pnl_sol.unwrap_or(0.0), position.id, destination, signature
+                );
+                if let Some(p) = self.positions.write().await.get_mut(&position.id) {
+                    p.profit_fee_sol = Some(fee_sol);
+                    p.profit_fee_tx = Some(signature.to_string());
+                }
+                if let Err(e) = self.save_positions().await {
+                    error!("Failed to save profit fee for position {}: {:?}", position.id, e);
+                }
+            }
+            Err(e) => error!(
+                "Profit fee transfer of {:.6} SOL for position {} failed; the position stays closed without a fee: {:?}",
+                fee_sol, position.id, e
+            ),
+        }
+    }
+
+    /// Records a failed exit sell and decides whether to retry it next cycle, mark the
+    /// position Failed, or mark it Unsellable
+    async fn record_exit_failure(&self, position_id: &str, reason: PositionStatus, error: &anyhow::Error) -> ExitFailureOutcome {
+        let mut positions = self.positions.write().await;
+        let Some(position) = positions.get_mut(position_id) else {
+            return ExitFailureOutcome::GiveUp;
+        };
+        let kind = SwapError::classify(error);
+        let outcome = register_exit_failure(
+            position,
+            reason,
+            Utc::now(),
+            self.config.exit_retry_attempts,
+            self.config.exit_retry_grace_minutes,
+            self.config.unroutable_exit_attempts,
+            kind == SwapError::NoRoute,
+            &format!("[{}] {}", kind, error),
+        );
+        if outcome == ExitFailureOutcome::Retry {
+            warn!(
+                "Exit for position {} ({}) failed (attempt {}/{}), retrying next cycle: {}",
+                position_id, position.token_symbol, position.exit_failures, self.config.exit_retry_attempts + 1, error
+            );
+        }
+        outcome
+    }
+
+    /// Stop trying to sell a token with no route left. The position is closed as
+    /// Unsellable at its last known price with nothing realized; the tokens stay in the
+    /// wallet. The close is announced once through the lifecycle notifications.
+    async fn mark_unsellable(&self, position: &Position) {
+        let last_known_value = position.entry_token_amount * position.current_price_sol;
+        error!(
+            "🪦 {} (position {}) has no sell route after {} attempts; marking Unsellable (last known value {:.6} SOL)",
+            position.token_symbol, position.id, self.config.unroutable_exit_attempts, last_known_value
+        );
+        if let Err(e) = self.close_position(
+            &position.id,
+            PositionStatus::Unsellable,
+            position.current_price_sol,
+            0.0,
+            "UNROUTABLE",
+        ).await {
+            error!("Critical: Failed to mark position {} as Unsellable: {:?}", position.id, e);
+        }
+    }
+
+    /// If an earlier sell for this position timed out unconfirmed, check whether it
+    /// landed after all and, if so, close the position with that sell's proceeds.
+    /// Returns true when the position was closed this way.
+    async fn settle_unconfirmed_exit(&self, position: &Position) -> bool {
+        let Some(tx) = &position.unconfirmed_exit_tx else {
+            return false;
+        };
+        let Ok(signature) = solana_sdk::signature::Signature::from_str(tx) else {
+            return false;
+        };
+        match self.solana_client.get_signature_outcome(&signature).await {
+            Ok(Some(true)) => {
+                let exit_value_sol = position.unconfirmed_exit_value_sol.unwrap_or(0.0);
+                let exit_price_sol = if position.entry_token_amount > 0.0 {
+                    exit_value_sol / position.entry_token_amount
+                } else {
+                    0.0
+                };
+                match self.close_position(&position.id, PositionStatus::Closed, exit_price_sol, exit_value_sol, tx).await {
+                    Ok(closed) => {
+                        info!("Earlier sell {} for position {} landed late; closed it", tx, position.id);
+                        self.collect_profit_fee(&closed).await;
+                        true
+                    }
+                    Err(e) => {
+                        error!("Earlier sell {} for position {} landed but closing failed: {:?}", tx, position.id, e);
+                        false
+                    }
+                }
+            }
+            Ok(Some(false)) => {
+                debug!("Earlier sell {} for position {} failed on-chain; selling again", tx, position.id);
+                false
+            }
+            Ok(None) => {
+                debug!("Earlier sell {} for position {} not found; selling again", tx, position.id);
+                false
+            }
+            Err(e) => {
+                warn!("Status check of earlier sell {} for position {} failed ({}); selling again", tx, position.id, e);
+                false
+            }
+        }
+    }
+
+    /// Marks a Closing position's exit as in flight so it can no longer be cancelled.
+    /// Returns false if the position is no longer Closing (e.g. the exit was cancelled).
+    async fn claim_exit(&self, position_id: &str) -> bool {
+        let positions = self.positions.read().await;
+        match positions.get(position_id) {
+            Some(p) if p.status == PositionStatus::Closing => {
+                self.exits_in_flight.write().await.insert(position_id.to_string());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Record a price sample and, for positions with a momentum TP, move the take-profit
+    /// target with the price velocity over the lookback window. The stop-loss is untouched.
+    async fn apply_momentum_take_profit(&self, position: &mut Position, price: f64) {
+        let (Some(momentum), Some(base_tp)) = (position.momentum_tp, position.take_profit_percent) else {
+            return;
+        };
+        let now = Utc::now();
+        let velocity = {
+            let mut samples = self.price_samples.write().await;
+            let series = samples.entry(position.id.clone()).or_default();
+            series.push_back((now, price));
+            let cutoff = now - ChronoDuration::minutes(momentum.lookback_minutes as i64);
+            while series.len() > 2 && series.front().is_some_and(|(t, _)| *t < cutoff) {
+                series.pop_front();
+            }
+            velocity_percent_per_min(series)
+        };
+        let Some(velocity) = velocity else {
+            return;
+        };
+
+        let new_tp = momentum.take_profit_percent(base_tp as f64, velocity);
+        let current_tp = position.effective_take_profit_percent.unwrap_or(base_tp as f64);
+        if (new_tp - current_tp).abs() >= 1.0 {
+            info!(
+                "Momentum TP for {}: {:.0}% -> {:.0}% (velocity {:+.2}%/min)",
+                position.token_symbol, current_tp, new_tp, velocity
+            );
+            position.effective_take_profit_percent = Some(new_tp);
+            position.take_profit_price = Some(position.entry_price_sol * (1.0 + new_tp / 100.0));
+            position.record_event(
+                PositionEventKind::TakeProfitMoved,
+                price,
+                format!("momentum TP {:.0}% -> {:.0}% ({:.9})", current_tp, new_tp, position.entry_price_sol * (1.0 + new_tp / 100.0)),
+            );
+        }
+    }
+
     // Updates price and checks exit conditions, but doesn't save immediately
     // Returns true if an exit condition was met
     async fn update_and_check_position(&self, position_id: &str, current_price_sol: f64) -> Result<Option<PositionStatus>> {
@@ -520,8 +1845,8 @@ impl PositionManager {
 
      // Internal check, assumes position is mutable and lock is held
      fn check_exit_conditions_internal(&self, position: &Position) -> Option<PositionStatus> {
-        // Check take profit
-        if let Some(tp_price) = position.take_profit_price {
+        // Check take profit (an on-chain order fills by itself)
+        if let Some(tp_price) = position.monitored_take_profit() {
             if position.current_price_sol >= tp_price {
                 info!("TP hit for {}: Current {:.6} >= TP {:.6}", position.token_symbol, position.current_price_sol, tp_price);
                 return Some(PositionStatus::TakeProfitHit);
@@ -553,10 +1878,67 @@ impl PositionManager {
             }
         }
 
+        // Check scheduled close (absolute clock time, independent of PnL)
+        if let Some(close_at) = position.force_close_at {
+            if Utc::now() >= close_at {
+                info!("Scheduled close reached for {}: {} UTC", position.token_symbol, close_at.format("%Y-%m-%d %H:%M"));
+                return Some(PositionStatus::ScheduledClose);
+            }
+        }
+
         None // No exit condition met
     }
 
 
+    /// Quote selling a position's remaining tokens and the PnL that would realize, without
+    /// sending anything. A failed quote is reported in the preview rather than as an error.
+    pub async fn preview_exit(&self, id: &str) -> Result<ExitPreview> {
+        let position = self.get_position(id).await
+            .ok_or_else(|| anyhow!("Position {} not found", id))?;
+        if position.status != PositionStatus::Active {
+            return Err(anyhow!("Position {} is {} and has nothing left to sell", id, position.status));
+        }
+
+        let mut preview = ExitPreview {
+            position_id: position.id.clone(),
+            token_symbol: position.token_symbol.clone(),
+            token_amount: position.entry_token_amount,
+            sellable: true,
+            error: None,
+            quote: None,
+            net_sol_out: None,
+            min_net_sol_out: None,
+            realized_pnl_sol: None,
+            realized_pnl_percent: None,
+            worst_case_pnl_sol: None,
+        };
+        let quote = self.jupiter_client.preview_sell(
+            &position.token_address,
+            position.token_decimals,
+            position.entry_token_amount,
+            self.exit_slippage_bps(position),
+            Some(self.exit_priority_fee(position).await),
+        ).await;
+        match quote {
+            Ok(quote) => {
+                let net = |sol: f64| (net_of_transfer_tax(sol, position.transfer_tax_percent) - quote.estimated_fee_sol).max(0.0);
+                let (net_sol_out, min_net_sol_out) = (net(quote.expected_sol), net(quote.min_sol));
+                let (pnl_sol, pnl_percent) = position.pnl_if_sold_for(net_sol_out);
+                preview.net_sol_out = Some(net_sol_out);
+                preview.min_net_sol_out = Some(min_net_sol_out);
+                preview.realized_pnl_sol = Some(pnl_sol);
+                preview.realized_pnl_percent = Some(pnl_percent);
+                preview.worst_case_pnl_sol = Some(position.pnl_if_sold_for(min_net_sol_out).0);
+                preview.quote = Some(quote);
+            }
+            Err(e) => {
+                preview.sellable = SwapError::classify(&e) != SwapError::NoRoute;
+                preview.error = Some(format!("{:#}", e));
+            }
+        }
+        Ok(preview)
+    }
+
     // --- Getters ---
 
     pub async fn get_position(&self, id: &str) -> Option<Position> {
@@ -575,6 +1957,32 @@ impl PositionManager {
         Ok(matching_positions)
     }
 
+    /// Total entry value (SOL) currently held in a token across all strategies and manual buys
+    pub async fn token_allocation_sol(&self, token_address: &str) -> Result<f64> {
+        Ok(self.get_positions_by_token(token_address).await?
+            .iter()
+            .filter(|p| p.status == PositionStatus::Active || p.status == PositionStatus::Closing)
+            .map(|p| p.entry_value_sol)
+            .sum())
+    }
+
+    /// Rejects a buy of `amount_sol` if it would push the token's open allocation
+    /// past `max_allocation_per_token_sol`
+    pub async fn check_token_allocation(&self, token_address: &str, amount_sol: f64) -> Result<()> {
+        let Some(max_allocation) = self.config.max_allocation_per_token_sol else {
+            return Ok(());
+        };
+
+        let current = self.token_allocation_sol(token_address).await?;
+        if current + amount_sol > max_allocation {
+            return Err(TraderbotError::PositionError(format!(
+                "Buying {:.4} SOL of {} would exceed the per-token cap of {:.4} SOL (currently allocated: {:.4} SOL)",
+                amount_sol, token_address, max_allocation, current
+            )).into());
+        }
+        Ok(())
+    }
+
     /// Gets all active positions
     pub async fn get_active_positions(&self) -> Vec<Position> {
         let positions = self.positions.read().await;
@@ -590,6 +1998,19 @@ impl PositionManager {
         positions.values().cloned().collect()
     }
 
+    /// Replaces every position with `positions` (state import) and persists them.
+    /// Active ones are picked up by the monitor on its next cycle.
+    pub async fn replace_positions(&self, positions: Vec<Position>) -> Result<()> {
+        let mut current = self.positions.write().await;
+        *current = positions.into_iter().map(|p| (p.id.clone(), p)).collect();
+        info!("Replaced positions with {} imported positions", current.len());
+        drop(current); // Release lock before saving
+
+        self.price_samples.write().await.clear();
+        self.next_checks.write().await.clear();
+        self.save_positions().await
+    }
+
     /// Gets all active positions for a specific strategy
     pub async fn get_active_positions_by_strategy(&self, strategy_id: &str) -> Vec<Position> {
         let positions = self.positions.read().await;
@@ -608,11 +2029,35 @@ impl PositionManager {
         )
     }
 
+    /// Mark a buy of the token as in flight until the returned guard is dropped
+    pub fn begin_buy(&self, token_address: &str) -> BuyInFlight {
+        *self.buys_in_flight.lock().unwrap().entry(token_address.to_string()).or_insert(0) += 1;
+        BuyInFlight { token_address: token_address.to_string(), buys: self.buys_in_flight.clone() }
+    }
+
+    /// Whether the token has a buy not yet recorded as a position, or a sell already sent
+    pub async fn has_trade_in_flight(&self, token_address: &str) -> bool {
+        if self.buys_in_flight.lock().unwrap().contains_key(token_address) {
+            return true;
+        }
+        let positions = self.positions.read().await;
+        let exits = self.exits_in_flight.read().await;
+        exits.iter().any(|id| positions.get(id).is_some_and(|p| p.token_address == token_address))
+    }
+
     // --- Monitoring Task ---
 
     pub async fn start_monitoring(self: Arc<Self>) -> Result<()> { // Take Arc<Self>
         // Load existing positions first
         self.load_positions().await?;
+        if let Err(e) = self.stats_history.load().await {
+            warn!("Failed to load strategy stats history: {:?}", e);
+        }
+        if let Err(e) = self.archive.load().await {
+            warn!("Failed to load positions archive: {:?}", e);
+        }
+        self.clone().spawn_archiver();
+        self.clone().spawn_take_profit_worker();
 
         let mut monitoring_guard = self.monitoring.write().await;
         if *monitoring_guard {
@@ -622,13 +2067,28 @@ impl PositionManager {
         *monitoring_guard = true;
         drop(monitoring_guard); // Release lock
 
+        match self.profit_fee_destination() {
+            Some(wallet) => info!(
+                "💸 Profit fee enabled: {:.2}% of net profit on each profitable exit is sent to {}",
+                self.config.profit_fee_percent, wallet
+            ),
+            None if self.config.profit_fee_percent > 0.0 => warn!(
+                "PROFIT_FEE_PERCENT is {:.2}% but PROFIT_FEE_WALLET is missing or invalid; no profit fee will be taken",
+                self.config.profit_fee_percent
+            ),
+            None => {}
+        }
+
         info!("Starting position monitoring task...");
 
         let self_clone = self.clone(); // Clone Arc<Self>
         let handle = tokio::spawn(async move {
-            let monitor_interval = Duration::from_secs(15); // Check more frequently? Configurable?
+            // Tick at the fastest rate; each position is only re-checked once it's due
+            let monitor_interval = Duration::from_secs(self_clone.config.position_monitor_min_secs);
             let mut interval_timer = interval(monitor_interval);
             interval_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            let snapshot_interval = ChronoDuration::minutes(self_clone.config.strategy_stats_snapshot_minutes as i64);
+            let mut last_snapshot = Utc::now();
 
             info!("Position monitoring task started.");
             loop {
@@ -643,6 +2103,11 @@ impl PositionManager {
                     error!("Error during position management cycle: {:?}", e);
                     // Decide if error is fatal or recoverable
                 }
+
+                if self_clone.config.strategy_stats_snapshot_minutes > 0 && Utc::now() - last_snapshot >= snapshot_interval {
+                    self_clone.snapshot_strategy_stats(None).await;
+                    last_snapshot = Utc::now();
+                }
             }
              info!("Position monitoring task finished.");
         });
@@ -694,12 +2159,35 @@ impl PositionManager {
 
         if active_ids.is_empty() {
             debug!("No active positions to manage.");
+            self.rug_checks.write().await.clear();
+            return Ok(());
+        }
+
+        // Filled on-chain take-profits close straight away too
+        let filled = self.settle_take_profit_orders(&active_ids).await;
+        let active_ids: Vec<String> = active_ids.into_iter().filter(|id| !filled.contains(id)).collect();
+
+        // Rugs are sold straight away, whatever the price check schedule says
+        let rugged = self.check_for_rugs(&active_ids).await;
+
+        // Only positions whose adaptive check time has come
+        let now = Utc::now();
+        let active_ids: Vec<String> = {
+            let next_checks = self.next_checks.read().await;
+            active_ids.into_iter()
+                .filter(|id| !rugged.iter().any(|(rugged_id, _)| rugged_id == id))
+                .filter(|id| !next_checks.get(id).is_some_and(|due| *due > now))
+                .collect()
+        };
+        if active_ids.is_empty() && rugged.is_empty() {
             return Ok(());
         }
 
         debug!("Managing {} active positions...", active_ids.len());
 
-        let mut exits_to_execute = Vec::new();
+        let mut exits_to_execute = rugged;
+        let mut scale_ins_to_execute = Vec::new();
+        let mut drawdown_alerts = Vec::new();
 
         // Process each active position individually to avoid holding lock for too long
         for position_id in active_ids {
@@ -730,11 +2218,8 @@ impl PositionManager {
                     debug!("[DEMO] Position {}: Simulated price update to {}", position.id, current_price_sol_opt.unwrap());
                 } else {
                     // Fetch real price for non-demo positions
-                    match self.jupiter_client.get_price(
-                        &crate::api::jupiter::SOL_MINT.to_string(), // Price relative to SOL
-                        &position.token_address,
-                        position.token_decimals
-                    ).await {
+                    // Through the cache shared with risk checks, so one token isn't priced twice at once
+                    match self.risk_analyzer.price_cache().price_sol(&position.token_address, position.token_decimals).await {
                         Ok(price) => {
                             current_price_sol_opt = Some(price);
                             debug!("Position {}: Fetched price {:.6}", position.id, price);
@@ -751,14 +2236,23 @@ impl PositionManager {
             if let (Some(current_price_sol), Some(_position)) = (current_price_sol_opt, position_snapshot) {
                  // Re-acquire write lock briefly to update and check
                  let mut exit_reason_opt: Option<PositionStatus> = None;
+                 let mut scale_in_opt: Option<(usize, f64)> = None;
+                 let mut drawdown_alert_opt: Option<f64> = None;
                  { // Scope for write lock
                      let mut positions_map = self.positions.write().await;
                      if let Some(pos_mut) = positions_map.get_mut(&position_id) {
                          // Ensure it's still active before updating
                          if pos_mut.status == PositionStatus::Active {
                              pos_mut.current_price_sol = current_price_sol;
+                             push_price_sample(
+                                 &mut pos_mut.price_history,
+                                 PriceSample { timestamp: Utc::now(), price_sol: current_price_sol },
+                                 MAX_PRICE_SAMPLES,
+                             );
                              // Recalculate PnL (optional here, can be done just before closing)
-                             pos_mut.pnl_sol = Some(pos_mut.entry_token_amount * current_price_sol - pos_mut.entry_value_sol);
+                             let exit_value = net_of_transfer_tax(pos_mut.entry_token_amount * current_price_sol, pos_mut.transfer_tax_percent)
+                                 + pos_mut.realized_value_sol;
+                             pos_mut.pnl_sol = Some(exit_value - pos_mut.entry_value_sol);
                              if pos_mut.entry_value_sol > 0.0 {
                                  pos_mut.pnl_percent = Some(pos_mut.pnl_sol.unwrap_or(0.0) / pos_mut.entry_value_sol * 100.0);
                              }
@@ -766,19 +2260,71 @@ impl PositionManager {
                              // Update highest price and trailing stop
                              if current_price_sol > pos_mut.highest_price {
                                  pos_mut.highest_price = current_price_sol;
+                                 if pos_mut.drawdown_alert_fired {
+                                     debug!("New high for {}; re-arming peak drawdown alert", pos_mut.token_symbol);
+                                     pos_mut.drawdown_alert_fired = false;
+                                 }
                                  if let Some(ts_percent) = pos_mut.trailing_stop_percent {
                                      let new_trailing_stop = current_price_sol * (1.0 - (ts_percent as f64 / 100.0));
                                      if pos_mut.trailing_stop_price.map_or(true, |current_ts| new_trailing_stop > current_ts) {
                                          debug!("Updating trailing stop for {}: {:.6} -> {:.6}", pos_mut.token_symbol, pos_mut.trailing_stop_price.unwrap_or(0.0), new_trailing_stop);
                                          pos_mut.trailing_stop_price = Some(new_trailing_stop);
+                                         pos_mut.record_event(
+                                             PositionEventKind::TrailingStopMoved,
+                                             current_price_sol,
+                                             format!("trailing stop -> {:.9}", new_trailing_stop),
+                                         );
                                      }
                                  }
                              }
+                             self.apply_momentum_take_profit(pos_mut, current_price_sol).await;
+                             if let Some(percent) = volatility_stop_percent(pos_mut, Utc::now()) {
+                                 let new_sl = pos_mut.entry_price_sol * (1.0 - percent / 100.0);
+                                 info!(
+                                     "Volatility SL for {}: {:.1}% below entry ({:.9} -> {:.9})",
+                                     pos_mut.token_symbol, percent, pos_mut.stop_loss_price.unwrap_or(0.0), new_sl
+                                 );
+                                 pos_mut.volatility_stop_percent = Some(percent);
+                                 pos_mut.stop_loss_price = Some(new_sl);
+                                 pos_mut.record_event(
+                                     PositionEventKind::StopLossMoved,
+                                     current_price_sol,
+                                     format!("volatility SL {:.1}% -> {:.9}", percent, new_sl),
+                                 );
+                             }
+                             if let Some(new_sl) = hold_deadline_stop_loss(
+                                 pos_mut,
+                                 Utc::now(),
+                                 self.config.hold_sl_tighten_window_percent,
+                                 self.config.hold_sl_tighten_curve,
+                                 self.config.hold_sl_tighten_final_gap_percent,
+                             ) {
+                                 debug!("Tightening SL for {} near max hold: {:.9} -> {:.9}", pos_mut.token_symbol, pos_mut.stop_loss_price.unwrap_or(0.0), new_sl);
+                                 pos_mut.stop_loss_price = Some(new_sl);
+                                 pos_mut.record_event(
+                                     PositionEventKind::StopLossMoved,
+                                     current_price_sol,
+                                     format!("max hold approaching, SL -> {:.9}", new_sl),
+                                 );
+                             }
+
+                             let interval = next_check_interval_secs(
+                                 pos_mut, Utc::now(), self.config.position_monitor_min_secs, self.config.position_monitor_max_secs,
+                             );
+                             debug!("Position {} next check in {}s", position_id, interval);
+                             self.next_checks.write().await.insert(position_id.clone(), Utc::now() + ChronoDuration::seconds(interval as i64));
+
                              // Check exit conditions based on the updated state
-                             exit_reason_opt = self.check_exit_conditions_internal(pos_mut);
-                             if exit_reason_opt.is_some() {
+                             // An exit whose sell failed earlier is retried before anything else
+                             exit_reason_opt = pos_mut.pending_exit_reason.clone()
+                                 .or_else(|| self.check_exit_conditions_internal(pos_mut));
+                             if let Some(reason) = &exit_reason_opt {
+                                 pos_mut.record_event(PositionEventKind::ExitTriggered, current_price_sol, reason.to_string());
                                  pos_mut.status = PositionStatus::Closing; // Mark for exit
                                  info!("Position {} marked for closing due to: {:?}", position_id, exit_reason_opt.as_ref().unwrap());
+                             } else {
+                                 drawdown_alert_opt = claim_drawdown_alert(pos_mut);
+                                 scale_in_opt = self.claim_scale_in_tranche(pos_mut, current_price_sol);
                              }
                          } else {
                               debug!("Position {} status changed to {} before update could be applied.", position_id, pos_mut.status);
@@ -790,10 +2336,19 @@ impl PositionManager {
                  if let Some(exit_reason) = exit_reason_opt {
                      exits_to_execute.push((position_id.clone(), exit_reason));
                  }
+                 if let Some(drawdown) = drawdown_alert_opt {
+                     drawdown_alerts.push((position_id.clone(), drawdown));
+                 }
+                 if let Some((tranche, amount_sol)) = scale_in_opt {
+                     scale_ins_to_execute.push((position_id.clone(), tranche, amount_sol));
+                 }
             }
         } // End loop through active_ids
 
 
+        // --- Step 2b: Stream live prices to the dashboard ---
+        self.stream_position_prices().await;
+
         // --- Step 3: Execute Exits ---
         for (position_id, exit_reason) in exits_to_execute { // Use the collected exits
              // Re-fetch position to ensure it's still marked for closing and get latest state
@@ -809,10 +2364,32 @@ impl PositionManager {
                  }
              };
 
-            // Borrow position_to_exit when calling execute_exit
-            if let Err(e) = self.execute_exit(&position_to_exit, exit_reason).await {
-                error!("Failed to execute exit for position {}: {:?}", position_id, e);
-                // Attempt to mark as Failed status
+            if !self.claim_exit(&position_id).await {
+                info!("Exit for position {} was cancelled before the sell was sent.", position_id);
+                continue;
+            }
+
+            // A sell that timed out last time may have landed since; don't sell twice
+            let exit_result = if self.settle_unconfirmed_exit(&position_to_exit).await {
+                Ok(())
+            } else {
+                self.execute_exit(&position_to_exit, exit_reason.clone()).await
+            };
+            if let Err(e) = exit_result {
+                match self.record_exit_failure(&position_id, exit_reason, &e).await {
+                    ExitFailureOutcome::Retry => {
+                        self.exits_in_flight.write().await.remove(&position_id);
+                        continue;
+                    }
+                    ExitFailureOutcome::Unroutable => {
+                        self.mark_unsellable(&position_to_exit).await;
+                        self.exits_in_flight.write().await.remove(&position_id);
+                        continue;
+                    }
+                    ExitFailureOutcome::GiveUp => {}
+                }
+                error!("Failed to execute exit for position {}, giving up: {:?}", position_id, e);
+                // Retries exhausted: mark as Failed
                  if let Err(close_err) = self.close_position(
                      &position_id,
                      PositionStatus::Failed,
@@ -822,6 +2399,28 @@ impl PositionManager {
                  ).await {
                      error!("Critical: Failed to even mark position {} as Failed: {:?}", position_id, close_err);
                  }
+                 self.escalation.raise(
+                     &escalation::unsellable_key(&position_id),
+                     IncidentKind::UnsellablePosition,
+                     format!(
+                         "Could not sell {} {} (position {}) [{}]: {}",
+                         position_to_exit.entry_token_amount, position_to_exit.token_symbol, position_id,
+                         SwapError::classify(&e), e
+                     ),
+                 ).await;
+            }
+            self.exits_in_flight.write().await.remove(&position_id);
+        }
+
+        // --- Step 3b: Peak-drawdown warnings (and optional partial de-risk) ---
+        for (position_id, drawdown) in drawdown_alerts {
+            self.handle_drawdown_alert(&position_id, drawdown).await;
+        }
+
+        // --- Step 3c: Buy due scale-in tranches ---
+        for (position_id, tranche, amount_sol) in scale_ins_to_execute {
+            if let Err(e) = self.execute_scale_in(&position_id, tranche, amount_sol).await {
+                warn!("Scale-in tranche {} for position {} failed and is skipped: {:?}", tranche + 1, position_id, e);
             }
         }
 
@@ -835,6 +2434,441 @@ impl PositionManager {
         Ok(())
     }
 
+    /// Hand an on-chain take-profit placement or cancel to the worker. The position
+    /// counts as busy until it lands, so sells wait rather than race it.
+    fn queue_take_profit_job(&self, job: TakeProfitJob) {
+        let position_id = match &job {
+            TakeProfitJob::Place(id) | TakeProfitJob::Cancel(id) => id.clone(),
+        };
+        if !self.take_profit_busy.lock().unwrap().insert(position_id.clone()) {
+            return; // Already being placed or cancelled
+        }
+        if self.take_profit_tx.send(job).is_err() {
+            self.take_profit_busy.lock().unwrap().remove(&position_id);
+        }
+    }
+
+    /// Start running queued take-profit jobs, each in its own task (no-op once started).
+    /// Jobs queued before monitoring starts wait for it.
+    fn spawn_take_profit_worker(self: Arc<Self>) {
+        let Some(mut jobs) = self.take_profit_rx.lock().unwrap().take() else {
+            return;
+        };
+        tokio::spawn(async move {
+            while let Some(job) = jobs.recv().await {
+                let manager = self.clone();
+                tokio::spawn(async move {
+                    let (position_id, result) = match &job {
+                        TakeProfitJob::Place(id) => (id, manager.place_take_profit_order(id).await),
+                        TakeProfitJob::Cancel(id) => (id, manager.cancel_take_profit_order(id).await),
+                    };
+                    manager.take_profit_busy.lock().unwrap().remove(position_id);
+                    if let Err(e) = result {
+                        match &job {
+                            TakeProfitJob::Place(_) => warn!("On-chain take-profit for {} not placed, monitoring it instead: {:?}", position_id, e),
+                            TakeProfitJob::Cancel(_) => warn!("Failed to cancel on-chain take-profit for {}, retrying on the next exit: {:?}", position_id, e),
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    /// Place a position's take-profit on-chain as a limit sell of all its tokens, net
+    /// of transfer tax, and record it once it lands. A placement that fails
+    /// confirmation is kept if its order account exists anyway; one that lands after
+    /// the position started exiting is cancelled again.
+    async fn place_take_profit_order(&self, position_id: &str) -> Result<()> {
+        let position = self.get_position(position_id).await
+            .ok_or_else(|| TraderbotError::PositionError(format!("Position ID {} not found", position_id)))?;
+        if position.status != PositionStatus::Active {
+            return Err(anyhow!("Position {} is {} and no longer needs a take-profit order", position_id, position.status));
+        }
+        let tp_price = position.take_profit_price
+            .ok_or_else(|| anyhow!("Position {} has no take-profit", position_id))?;
+        let min_sol_out = net_of_transfer_tax(position.entry_token_amount * tp_price, position.transfer_tax_percent);
+        let wallet = self.wallet_for_position(&position)?;
+        let placed = self.jupiter_client.place_limit_sell(
+            &position.token_address,
+            position.token_decimals,
+            position.entry_token_amount,
+            min_sol_out,
+            wallet.clone(),
+        ).await?;
+        if let Err(e) = self.solana_client.confirm_transaction(&placed.signature, solana_sdk::commitment_config::CommitmentLevel::Confirmed, 60).await {
+            if !self.trigger_order_open(&placed.order).await.unwrap_or(false) {
+                return Err(e).context(format!("Limit order transaction {} failed confirmation", placed.signature));
+            }
+        }
+
+        let order = TakeProfitOrder { order: placed.order, min_sol_out, placed_at: Utc::now() };
+        let recorded = match self.positions.write().await.get_mut(position_id) {
+            Some(p) if p.status == PositionStatus::Active => {
+                p.record_event(
+                    PositionEventKind::TakeProfitMoved,
+                    tp_price,
+                    format!("on-chain limit order {} for >= {:.6} SOL", order.order, min_sol_out),
+                );
+                p.take_profit_order = Some(order.clone());
+                true
+            }
+            _ => false,
+        };
+        if !recorded {
+            // An exit got in first; give it the tokens back
+            self.cancel_and_confirm_trigger_order(&order, wallet).await?;
+            return Err(anyhow!("Position {} started exiting while its limit order {} landed; cancelled it", position_id, order.order));
+        }
+        self.save_positions().await
+    }
+
+    /// Cancel a trigger order and wait for it to land. A cancel that fails confirmation
+    /// still counts if the order account is gone.
+    async fn cancel_and_confirm_trigger_order(&self, order: &TakeProfitOrder, wallet: Arc<WalletManager>) -> Result<()> {
+        let signature = self.jupiter_client.cancel_trigger_order(&order.order, wallet).await?;
+        if let Err(e) = self.solana_client.confirm_transaction(&signature, solana_sdk::commitment_config::CommitmentLevel::Confirmed, 60).await {
+            if self.trigger_order_open(&order.order).await.unwrap_or(true) {
+                return Err(e).context(format!("Cancel of limit order {} failed confirmation", order.order));
+            }
+        }
+        Ok(())
+    }
+
+    /// Cancel a position's on-chain take-profit and forget it, so the next exit can sell
+    async fn cancel_take_profit_order(&self, position_id: &str) -> Result<()> {
+        let position = self.get_position(position_id).await
+            .ok_or_else(|| TraderbotError::PositionError(format!("Position ID {} not found", position_id)))?;
+        let Some(order) = position.take_profit_order.clone() else {
+            return Ok(());
+        };
+        // Already gone: the monitor settles it (filled, or cancelled elsewhere)
+        if !self.trigger_order_open(&order.order).await? {
+            return Ok(());
+        }
+        let wallet = self.wallet_for_position(&position)?;
+        self.cancel_and_confirm_trigger_order(&order, wallet).await?;
+        if let Some(p) = self.positions.write().await.get_mut(position_id) {
+            p.take_profit_order = None;
+            p.record_event(
+                PositionEventKind::TakeProfitMoved,
+                p.current_price_sol,
+                format!("cancelled on-chain limit order {}", order.order),
+            );
+        }
+        info!("Cancelled on-chain take-profit {} for {} ({})", order.order, position.token_symbol, position_id);
+        self.save_positions().await
+    }
+
+    /// Whether a trigger order's account still exists (open or partly filled)
+    async fn trigger_order_open(&self, order: &str) -> Result<bool> {
+        let key = Pubkey::from_str(order).context("Invalid trigger order account")?;
+        let response = self.solana_client.get_rpc()
+            .get_account_with_commitment(&key, solana_sdk::commitment_config::CommitmentConfig::confirmed()).await?;
+        Ok(response.value.is_some())
+    }
+
+    /// Close the positions whose on-chain take-profit order has gone. Returns their ids.
+    async fn settle_take_profit_orders(&self, active_ids: &[String]) -> Vec<String> {
+        let mut closed = Vec::new();
+        for id in active_ids {
+            let Some(position) = self.get_position(id).await else { continue };
+            let Some(order) = position.take_profit_order.clone() else { continue };
+            // A cancel still landing clears the order itself
+            if self.take_profit_busy.lock().unwrap().contains(id) {
+                continue;
+            }
+            match self.trigger_order_open(&order.order).await {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => {
+                    debug!("Could not check limit order {} for {}: {}", order.order, id, e);
+                    continue;
+                }
+            }
+            match self.settle_take_profit_fill(&position, &order).await {
+                Ok(true) => closed.push(id.clone()),
+                Ok(false) => {}
+                Err(e) => warn!("Failed to settle limit order {} for position {}: {:?}", order.order, id, e),
+            }
+        }
+        closed
+    }
+
+    /// A position's limit order is gone: close the position at the order's price, or,
+    /// if the tokens are back in the wallet (cancelled elsewhere), forget the order
+    /// and monitor the take-profit again. Returns whether the position closed.
+    async fn settle_take_profit_fill(&self, position: &Position, order: &TakeProfitOrder) -> Result<bool> {
+        let wallet = self.wallet_for_position(position)?;
+        let mint = Pubkey::from_str(&position.token_address).context("Invalid token address")?;
+        let held = self.solana_client.get_mint_balance_ui(&wallet.get_public_key(), &mint).await?;
+        if held >= position.entry_token_amount * 0.5 {
+            warn!(
+                "Limit order {} for {} ({}) is gone but the tokens are still held, monitoring the take-profit again",
+                order.order, position.token_symbol, position.id
+            );
+            if let Some(p) = self.positions.write().await.get_mut(&position.id) {
+                p.take_profit_order = None;
+            }
+            self.save_positions().await?;
+            return Ok(false);
+        }
+
+        info!("🎯 Limit take-profit {} for {} ({}) filled", order.order, position.token_symbol, position.id);
+        let exit_price = if position.entry_token_amount > 0.0 { order.min_sol_out / position.entry_token_amount } else { 0.0 };
+        if let Some(p) = self.positions.write().await.get_mut(&position.id) {
+            p.record_event(PositionEventKind::ExitTriggered, exit_price, PositionStatus::TakeProfitHit.to_string());
+        }
+        let closed = self.close_position(&position.id, PositionStatus::Closed, exit_price, order.min_sol_out, &order.order).await?;
+        self.collect_profit_fee(&closed).await;
+        Ok(true)
+    }
+
+    /// Free a position's tokens from its on-chain take-profit so they can be sold another
+    /// way. An open order is cancelled in the background (the cancel waits for
+    /// confirmation) and the sell is retried once it lands.
+    async fn release_take_profit_order(&self, position: &Position) -> Result<TakeProfitRelease> {
+        if self.take_profit_busy.lock().unwrap().contains(&position.id) {
+            return Ok(TakeProfitRelease::Pending);
+        }
+        let Some(order) = position.take_profit_order.clone() else {
+            return Ok(TakeProfitRelease::Free);
+        };
+        if !self.trigger_order_open(&order.order).await? {
+            return Ok(if self.settle_take_profit_fill(position, &order).await? {
+                TakeProfitRelease::Filled
+            } else {
+                TakeProfitRelease::Free
+            });
+        }
+        self.queue_take_profit_job(TakeProfitJob::Cancel(position.id.clone()));
+        Ok(TakeProfitRelease::Pending)
+    }
+
+    /// Compare each active position's token with its first rug-monitor snapshot,
+    /// every `rug_check_interval_secs`, and mark rugged positions for an emergency
+    /// close. Returns the exits to execute.
+    async fn check_for_rugs(&self, active_ids: &[String]) -> Vec<(String, PositionStatus)> {
+        if !self.config.rug_monitor {
+            return Vec::new();
+        }
+        let now = Utc::now();
+        let interval = ChronoDuration::seconds(self.config.rug_check_interval_secs as i64);
+        let thresholds = RugThresholds::from_config(&self.config);
+        self.rug_checks.write().await.retain(|id, _| active_ids.contains(id));
+
+        let mut rugged = Vec::new();
+        for position_id in active_ids {
+            let (baseline, mut pull_pending) = match self.rug_checks.read().await.get(position_id) {
+                Some((_, checked, _)) if now - *checked < interval => continue,
+                Some((baseline, _, pull_pending)) => (Some(baseline.clone()), *pull_pending),
+                None => (None, false),
+            };
+            let Some(position) = self.get_position(position_id).await.filter(|p| !p.is_demo) else {
+                continue;
+            };
+            let Ok(wallet) = self.wallet_for_position(&position) else {
+                continue;
+            };
+            let snapshot = match rug_monitor::snapshot(
+                &self.solana_client,
+                &self.risk_analyzer,
+                &position.token_address,
+                &wallet.get_public_key(),
+                thresholds.whale_min_percent,
+                baseline.as_ref(),
+            ).await {
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    debug!("Rug check for {} failed: {:?}", position.token_symbol, e);
+                    continue;
+                }
+            };
+
+            let Some(baseline) = baseline else {
+                debug!(
+                    "Rug monitor baseline for {}: liquidity {:?} SOL, {} large holders tracked",
+                    position.token_symbol, snapshot.liquidity_sol, snapshot.whales.len()
+                );
+                self.rug_checks.write().await.insert(position_id.clone(), (snapshot, now, false));
+                continue;
+            };
+            let signal = rug_monitor::confirm(rug_monitor::detect(&baseline, &snapshot, &thresholds), &mut pull_pending);
+            if pull_pending {
+                warn!("Rug monitor: {} liquidity looks pulled ({:?} SOL), confirming on the next check", position.token_symbol, snapshot.liquidity_sol);
+            }
+            self.rug_checks.write().await.insert(position_id.clone(), (baseline, now, pull_pending));
+            let Some(signal) = signal else {
+                continue;
+            };
+
+            error!("🚨 Rug detected on {} ({}): {} - emergency selling", position.token_symbol, position_id, signal);
+            if let Some(p) = self.positions.write().await.get_mut(position_id) {
+                if p.status != PositionStatus::Active {
+                    continue;
+                }
+                p.record_event(PositionEventKind::RugDetected, p.current_price_sol, signal.to_string());
+                p.record_event(PositionEventKind::ExitTriggered, p.current_price_sol, PositionStatus::EmergencyClose.to_string());
+                p.status = PositionStatus::Closing;
+                rugged.push((position_id.clone(), PositionStatus::EmergencyClose));
+            }
+        }
+        rugged
+    }
+
+    /// The wallet that holds a position's tokens
+    fn wallet_for_position(&self, position: &Position) -> Result<Arc<WalletManager>> {
+        match &position.wallet_address {
+            Some(address) => self.wallet_pool.get(address)
+                .ok_or_else(|| anyhow!("Wallet {} holding position {} is not configured", address, position.id)),
+            None => Ok(self.wallet_pool.primary()),
+        }
+    }
+
+    /// Notify a peak-drawdown warning, first selling `peak_drawdown_sell_percent` of the
+    /// holding if configured. A failed sell still sends the warning.
+    async fn handle_drawdown_alert(&self, position_id: &str, drawdown_percent: f64) {
+        let Some(position) = self.get_position(position_id).await.filter(|p| p.status == PositionStatus::Active) else {
+            return;
+        };
+        warn!(
+            "📉 {} ({}) is {:.1}% below its high of {:.6} SOL (alert at {:.1}%)",
+            position.token_symbol, position.id, drawdown_percent, position.highest_price,
+            position.peak_drawdown_alert_percent.unwrap_or(0.0)
+        );
+
+        let mut sold = None;
+        if let Some(sell_percent) = position.peak_drawdown_sell_percent {
+            match self.sell_fraction(&position, sell_percent).await {
+                Ok(value) => sold = Some((sell_percent, value)),
+                Err(e) => warn!("Partial de-risk sell for position {} failed: {:?}", position.id, e),
+            }
+        }
+
+        let alert = DrawdownAlert {
+            position_id: position.id.clone(),
+            token_address: position.token_address.clone(),
+            token_symbol: position.token_symbol.clone(),
+            drawdown_percent,
+            highest_price_sol: position.highest_price,
+            price_sol: position.current_price_sol,
+            sold_percent: sold.map(|(percent, _)| percent),
+            sold_value_sol: sold.map(|(_, value)| value),
+            timestamp: Utc::now(),
+        };
+        // Ignore errors (no subscribers)
+        let _ = self.drawdown_alert_tx.send(alert);
+    }
+
+    /// Sell `percent` of a position's tokens, keeping the position open with the rest.
+    /// Returns the SOL received, which is added to the position's realized value.
+    async fn sell_fraction(&self, position: &Position, percent: f64) -> Result<f64> {
+        let token_amount = position.entry_token_amount * percent / 100.0;
+        match self.release_take_profit_order(position).await? {
+            TakeProfitRelease::Free => {}
+            TakeProfitRelease::Filled => {
+                return Err(anyhow!("Take-profit order for position {} filled before the partial sell", position.id));
+            }
+            TakeProfitRelease::Pending => {
+                return Err(anyhow!("Take-profit order for position {} is still being cancelled", position.id));
+            }
+        }
+        let (value_sol, tx_sig) = if position.is_demo {
+            (token_amount * position.current_price_sol, format!("DEMO_PARTIAL_{}", Uuid::new_v4()))
+        } else {
+            let wallet = self.wallet_for_position(position)?;
+            let swap_result = self.jupiter_client.swap_token_to_sol(
+                &position.token_address,
+                position.token_decimals,
+                token_amount,
+                self.exit_slippage_bps(position),
+                Some(self.exit_priority_fee(position).await),
+                position.swap_route(),
+                wallet,
+            ).await.context(format!("Failed to execute partial sell for position {}", position.id))?;
+            let signature = solana_sdk::signature::Signature::from_str(&swap_result.transaction_signature)
+                .context("Failed to parse partial sell transaction signature")?;
+            self.solana_client.confirm_transaction(&signature, solana_sdk::commitment_config::CommitmentLevel::Confirmed, 60).await
+                .context(format!("Partial sell transaction {} failed confirmation", signature))?;
+            let value = swap_result.actual_out_amount_ui
+                .unwrap_or_else(|| net_of_transfer_tax(swap_result.out_amount_ui, position.transfer_tax_percent));
+            (value, swap_result.transaction_signature)
+        };
+
+        let sol_price_usd = self.sol_price_usd().await;
+        let mut positions = self.positions.write().await;
+        if let Some(pos) = positions.get_mut(&position.id) {
+            pos.entry_token_amount -= token_amount;
+            pos.expected_token_amount = (pos.expected_token_amount - token_amount).max(pos.entry_token_amount);
+            pos.realized_value_sol += value_sol;
+            pos.realized_value_usd = add_usd(pos.realized_value_usd, value_sol, sol_price_usd);
+            pos.record_fill(FillSide::Sell, token_amount, value_sol, &tx_sig);
+            pos.record_event(
+                PositionEventKind::PartialSell,
+                position.current_price_sol,
+                format!("sold {:.0}% ({:.4} tokens) for {:.4} SOL (tx {})", percent, token_amount, value_sol, tx_sig),
+            );
+        }
+        drop(positions);
+        info!(
+            "Sold {:.0}% of {} ({}): {:.4} tokens for {:.4} SOL (tx {})",
+            percent, position.token_symbol, position.id, token_amount, value_sol, tx_sig
+        );
+        self.save_positions().await?;
+        Ok(value_sol)
+    }
+
+    /// Claim the next scale-in tranche if its trigger is met. It is marked filled up
+    /// front, so a failed buy is skipped rather than retried into a bad route.
+    fn claim_scale_in_tranche(&self, position: &mut Position, price: f64) -> Option<(usize, f64)> {
+        let minutes_held = (Utc::now() - position.entry_time).num_minutes();
+        let state = position.scale_in.as_mut()?;
+        let due = state.due_tranche(price, minutes_held)?;
+        if !position.is_demo && self.solana_client.is_lagging() {
+            debug!("Scale-in tranche for {} is due but the RPC is lagging; waiting", position.token_symbol);
+            return None;
+        }
+        state.filled_tranches += 1;
+        Some(due)
+    }
+
+    async fn execute_scale_in(&self, position_id: &str, tranche: usize, amount_sol: f64) -> Result<()> {
+        let position = match self.get_position(position_id).await {
+            Some(p) if p.status == PositionStatus::Active => p,
+            _ => return Ok(()), // Closed or closing since the tranche was claimed
+        };
+        info!(
+            "Scale-in tranche {} for {} ({}): buying {:.4} SOL at {:.6} SOL/Token",
+            tranche + 1, position.token_symbol, position.id, amount_sol, position.current_price_sol
+        );
+
+        if position.is_demo {
+            let tokens = amount_sol / position.current_price_sol;
+            self.add_to_position(position_id, amount_sol, tokens, &format!("DEMO_SCALE_IN_{}", Uuid::new_v4())).await?;
+            return Ok(());
+        }
+
+        self.check_token_allocation(&position.token_address, amount_sol).await?;
+        let wallet = self.wallet_for_position(&position)?;
+        let swap_result = self.jupiter_client.swap_sol_to_token(
+            &position.token_address,
+            position.token_decimals,
+            amount_sol,
+            self.config.live().default_slippage_bps,
+            Some(self.priority_fee(&position.token_address, FeeUrgency::default(), self.config.live().default_priority_fee_micro_lamports).await),
+            position.swap_route(),
+            wallet,
+        ).await.context(format!("Failed to execute scale-in swap for position {}", position_id))?;
+
+        let signature = solana_sdk::signature::Signature::from_str(&swap_result.transaction_signature)
+            .context("Failed to parse scale-in transaction signature")?;
+        self.solana_client.confirm_transaction(&signature, solana_sdk::commitment_config::CommitmentLevel::Confirmed, 60).await
+            .context(format!("Scale-in transaction {} failed confirmation", signature))?;
+
+        let tokens = swap_result.actual_out_amount_ui.unwrap_or(swap_result.out_amount_ui);
+        self.add_to_position(position_id, amount_sol, tokens, &swap_result.transaction_signature).await?;
+        Ok(())
+    }
+
     // Changed to take &Position to avoid moving the value
     async fn execute_exit(&self, position: &Position, reason: PositionStatus) -> Result<()> {
         info!(
@@ -857,14 +2891,83 @@ impl PositionManager {
             return Ok(());
         }
 
+        // Tokens escrowed by an on-chain take-profit come back first; while that lands the
+        // exit is held and tried again on a later check
+        match self.release_take_profit_order(position).await? {
+            TakeProfitRelease::Free => {}
+            TakeProfitRelease::Filled => return Ok(()),
+            TakeProfitRelease::Pending => return self.hold_exit(&position.id).await,
+        }
+
+        // --- Liquidity floor: hold rather than dump into a dead pool ---
+        // (except on a rug, where waiting only makes it worse)
+        let emergency = reason == PositionStatus::EmergencyClose;
+        if let Some(min_liquidity) = self.config.min_exit_liquidity_sol.filter(|_| !emergency) {
+            let key = escalation::illiquid_key(&position.id);
+            match self.risk_analyzer.current_liquidity_sol(&position.token_address).await {
+                Ok(Some(liquidity)) if liquidity < min_liquidity => {
+                    warn!(
+                        "Holding exit for {} ({}): pool liquidity {:.2} SOL is below the {:.2} SOL floor",
+                        position.token_symbol, position.id, liquidity, min_liquidity
+                    );
+                    self.escalation.raise(
+                        &key,
+                        IncidentKind::IlliquidExit,
+                        format!(
+                            "Exit ({}) for {} (position {}) held: liquidity {:.2} SOL < {:.2} SOL floor",
+                            reason, position.token_symbol, position.id, liquidity, min_liquidity
+                        ),
+                    ).await;
+                    return self.hold_exit(&position.id).await;
+                }
+                Ok(Some(_)) => self.escalation.resolve(&key).await,
+                Ok(None) => debug!("Liquidity for {} unknown, proceeding with exit", position.token_symbol),
+                Err(e) => warn!("Liquidity check for {} failed ({}), proceeding with exit", position.token_symbol, e),
+            }
+        }
+
         // --- Real Exit ---
+        // A rug is sold at any price, ahead of everyone else trying to get out
+        let live = self.config.live();
+        let (mut slippage_bps, mut priority_fee) = if emergency {
+            (live.emergency_exit_slippage_bps, live.emergency_priority_fee_micro_lamports)
+        } else {
+            // Calibrated per token at entry, else the default; higher priority fee for closing
+            (self.exit_slippage_bps(position), self.exit_priority_fee(position).await)
+        };
+
+        // Re-quoted with more slippage and a higher fee if it fails on slippage or an expired blockhash
+        let ladder = SwapRetryLadder::from_config(&self.config);
+        let mut attempt = 0;
+        loop {
+            let err = match self.send_exit(position, slippage_bps, priority_fee).await {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+            let kind = SwapError::classify(&err);
+            if !SwapRetryLadder::retries(kind) || attempt >= ladder.attempts {
+                return Err(err);
+            }
+            attempt += 1;
+            (slippage_bps, priority_fee) = ladder.escalate(slippage_bps, priority_fee);
+            warn!(
+                "Exit for {} ({}) failed [{}], re-quoting at {} bps slippage, {} μlamports priority ({}/{})",
+                position.token_symbol, position.id, kind, slippage_bps, priority_fee, attempt, ladder.attempts
+            );
+        }
+    }
+
+    /// Sends the sell of everything `position` holds, confirms it and closes the position
+    async fn send_exit(&self, position: &Position, slippage_bps: u32, priority_fee: u64) -> Result<()> {
+        let wallet = self.wallet_for_position(position)?;
         let swap_result = match self.jupiter_client.swap_token_to_sol(
             &position.token_address,
             position.token_decimals,
             position.entry_token_amount, // Sell the full amount held
-            self.config.default_slippage_bps, // Use default slippage for closing? Or strategy specific?
-            Some(self.config.default_priority_fee_micro_lamports * 2), // Higher priority fee for closing?
-            self.wallet_manager.clone(),
+            slippage_bps,
+            Some(priority_fee),
+            position.swap_route(), // Frontrun protection and venue as on entry
+            wallet,
         ).await {
              Ok(result) => result,
              Err(e) => {
@@ -885,21 +2988,23 @@ impl PositionManager {
         let signature = solana_sdk::signature::Signature::from_str(&swap_result.transaction_signature)
             .context("Failed to parse exit transaction signature")?;
 
+        // Fall back to the quote, less any transfer tax, when the tx amount can't be read
+        let actual_exit_value_sol = swap_result.actual_out_amount_ui
+            .unwrap_or_else(|| net_of_transfer_tax(swap_result.out_amount_ui, position.transfer_tax_percent));
+
         // TODO: Make confirmation timeout configurable
         match self.solana_client.confirm_transaction(&signature, solana_sdk::commitment_config::CommitmentLevel::Confirmed, 60).await {
             Ok(_) => {
                 info!("Exit transaction {} confirmed successfully.", signature);
 
                 // --- Close Position (Only after confirmation) ---
-                // TODO: Get actual SOL received after confirmation if possible (requires parsing tx details)
-                let actual_exit_value_sol = swap_result.actual_out_amount_ui.unwrap_or(swap_result.out_amount_ui); // Use estimate for now
                 let actual_exit_price_sol = if position.entry_token_amount > 0.0 {
                     actual_exit_value_sol / position.entry_token_amount // Calculate effective exit price
                 } else {
                     0.0 // Avoid division by zero if entry amount was somehow zero
                 };
 
-                self.close_position(
+                let closed = self.close_position(
                     &position.id,
                     PositionStatus::Closed, // Mark as successfully closed
                     actual_exit_price_sol,
@@ -908,17 +3013,355 @@ impl PositionManager {
                 ).await?;
 
                 info!("Successfully executed exit and closed position {}", position.id);
+                self.collect_profit_fee(&closed).await;
                 // TODO: Send notification
                 Ok(())
             }
             Err(e) => {
                 error!("Failed to confirm exit transaction {}: {:?}", signature, e);
-                // Don't close the position as Closed if confirmation fails.
-                // Mark as Failed instead? Or leave as Closing for retry?
-                // For now, return error to indicate confirmation failure.
-                // The caller (manage_positions_cycle) will mark as Failed.
+                // Don't close the position as Closed if confirmation fails. The sell may
+                // still land, so remember it; the caller's retry checks it before selling again.
+                // A sell that failed on slippage or an expired blockhash never will.
+                if !SwapRetryLadder::retries(SwapError::classify(&e)) {
+                    if let Some(p) = self.positions.write().await.get_mut(&position.id) {
+                        p.unconfirmed_exit_tx = Some(swap_result.transaction_signature.clone());
+                        p.unconfirmed_exit_value_sol = Some(actual_exit_value_sol);
+                    }
+                }
                 Err(e).context(format!("Exit transaction {} failed confirmation", signature))
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position_at(price: f64, stop_loss: Option<f64>, take_profit: Option<f64>) -> Position {
+        let now = Utc::now();
+        Position {
+            id: "p1".to_string(),
+            token_address: "mint".to_string(),
+            token_name: "Token".to_string(),
+            token_symbol: "TKN".to_string(),
+            token_decimals: 6,
+            strategy_id: "s1".to_string(),
+            entry_time: now,
+            exit_time: None,
+            entry_value_sol: 1.0,
+            entry_token_amount: 1.0,
+            expected_token_amount: 1.0,
+            fill_percent: 1.0,
+            exit_value_sol: None,
+            entry_price_sol: price,
+            current_price_sol: price,
+            exit_price_sol: None,
+            pnl_sol: None,
+            pnl_percent: None,
+            stop_loss_price: stop_loss,
+            take_profit_price: take_profit,
+            trailing_stop_price: None,
+            trailing_stop_percent: None,
+            peak_drawdown_alert_percent: None,
+            peak_drawdown_sell_percent: None,
+            drawdown_alert_fired: false,
+            realized_value_sol: 0.0,
+            pending_exit_reason: None,
+            exit_failures: 0,
+            no_route_exit_failures: 0,
+            first_exit_failure_at: None,
+            unconfirmed_exit_tx: None,
+            unconfirmed_exit_value_sol: None,
+            highest_price: price,
+            status: PositionStatus::Active,
+            entry_tx_signature: String::new(),
+            exit_tx_signature: None,
+            is_demo: true,
+            max_hold_time_minutes: None,
+            force_close_at: None,
+            momentum_tp: None,
+            effective_take_profit_percent: None,
+            volatility_stop: None,
+            volatility_stop_percent: None,
+            scale_in: None,
+            stop_loss_percent: None,
+            take_profit_percent: None,
+            wallet_address: None,
+            transfer_tax_percent: 0.0,
+            profit_fee_sol: None,
+            profit_fee_tx: None,
+            exit_slippage_bps: None,
+            entry_value_usd: None,
+            realized_value_usd: None,
+            exit_value_usd: None,
+            pnl_usd: None,
+            jito_tip_lamports: None,
+            execution_venue: ExecutionVenue::Jupiter,
+            bonding_curve_route: false,
+            take_profit_order: None,
+            notify_trades: TradeNotify::default(),
+            price_history: Vec::new(),
+            events: Vec::new(),
+            fills: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn check_interval_tightens_near_triggers_and_with_velocity() {
+        let now = Utc::now();
+        // No triggers: slowest rate
+        assert_eq!(next_check_interval_secs(&position_at(1.0, None, None), now, 5, 30), 30);
+        // Far from the stop: slowest rate; 1% away: fastest
+        assert_eq!(next_check_interval_secs(&position_at(1.0, Some(0.5), None), now, 5, 30), 30);
+        assert_eq!(next_check_interval_secs(&position_at(1.0, Some(0.99), None), now, 5, 30), 5);
+        // 5% from TP: halfway
+        assert_eq!(next_check_interval_secs(&position_at(1.0, None, Some(1.05)), now, 5, 30), 15);
+
+        // 20% from the stop alone allows the slowest rate, but moving 40%/min it
+        // could get there in 30s, so it's checked every 10s
+        let mut fast = position_at(1.0, Some(0.8), None);
+        fast.price_history = vec![
+            PriceSample { timestamp: now - ChronoDuration::seconds(60), price_sol: 1.0 / 1.4 },
+            PriceSample { timestamp: now, price_sol: 1.0 },
+        ];
+        assert_eq!(next_check_interval_secs(&fast, now, 5, 30), 10);
+    }
+
+    #[test]
+    fn check_interval_never_overshoots_max_hold() {
+        let now = Utc::now();
+        let mut position = position_at(1.0, None, None);
+        position.max_hold_time_minutes = Some(1);
+        position.entry_time = now - ChronoDuration::seconds(50);
+        assert_eq!(next_check_interval_secs(&position, now, 5, 30), 10);
+    }
+
+    #[test]
+    fn failed_exit_is_retried_until_attempts_or_grace_run_out() {
+        let now = Utc::now();
+        let mut position = position_at(1.0, Some(0.9), None);
+        position.status = PositionStatus::Closing;
+
+        for attempt in 1..=2 {
+            assert_eq!(register_exit_failure(&mut position, PositionStatus::StopLossHit, now, 2, 10, 0, true, "no route"), ExitFailureOutcome::Retry);
+            assert_eq!(position.exit_failures, attempt);
+            assert_eq!(position.status, PositionStatus::Active);
+            assert_eq!(position.pending_exit_reason, Some(PositionStatus::StopLossHit));
+            assert_eq!(next_check_interval_secs(&position, now, 5, 30), 5);
+            position.status = PositionStatus::Closing;
+        }
+        assert_eq!(register_exit_failure(&mut position, PositionStatus::StopLossHit, now, 2, 10, 0, true, "no route"), ExitFailureOutcome::GiveUp);
+        assert_eq!(position.status, PositionStatus::Closing);
+
+        // Grace period elapsed before the retries ran out
+        let mut position = position_at(1.0, Some(0.9), None);
+        assert_eq!(register_exit_failure(&mut position, PositionStatus::StopLossHit, now, 5, 10, 0, false, "timeout"), ExitFailureOutcome::Retry);
+        let later = now + ChronoDuration::minutes(10);
+        assert_eq!(register_exit_failure(&mut position, PositionStatus::StopLossHit, later, 5, 10, 0, false, "timeout"), ExitFailureOutcome::GiveUp);
+    }
+
+    #[test]
+    fn stop_loss_tightens_toward_price_near_max_hold() {
+        let mut position = position_at(1.0, Some(0.8), None);
+        position.stop_loss_percent = Some(20);
+        position.max_hold_time_minutes = Some(100);
+        position.current_price_sol = 1.2;
+        let entry = position.entry_time;
+        let at = |minutes| entry + ChronoDuration::minutes(minutes);
+
+        // Outside the last 20% of the window: untouched
+        assert_eq!(hold_deadline_stop_loss(&position, at(70), 20.0, 1.0, 0.0), None);
+        // Halfway through the window: halfway from 0.8 to 1.2
+        let sl = hold_deadline_stop_loss(&position, at(90), 20.0, 1.0, 0.0).unwrap();
+        assert!((sl - 1.0).abs() < 1e-9);
+        // A steeper curve tightens later
+        let late = hold_deadline_stop_loss(&position, at(90), 20.0, 2.0, 0.0).unwrap();
+        assert!((late - 0.9).abs() < 1e-9);
+        // At the deadline it sits the final gap below the price
+        let end = hold_deadline_stop_loss(&position, at(100), 20.0, 1.0, 5.0).unwrap();
+        assert!((end - 1.14).abs() < 1e-9);
+
+        // Never lowered, and off when disabled
+        position.stop_loss_price = Some(1.1);
+        assert_eq!(hold_deadline_stop_loss(&position, at(90), 20.0, 1.0, 0.0), None);
+        assert_eq!(hold_deadline_stop_loss(&position, at(99), 0.0, 1.0, 0.0), None);
+    }
+
+    #[test]
+    fn volatility_stop_sizes_from_recent_prices() {
+        let mut position = position_at(1.0, Some(0.7), None);
+        position.volatility_stop = Some(VolatilityStopSettings {
+            window_minutes: 10, multiplier: 2.0, min_percent: 5.0, max_percent: 30.0, reevaluate: false,
+        });
+        let entry = position.entry_time;
+        let at = |minutes| entry + ChronoDuration::minutes(minutes);
+        position.price_history = [1.0, 1.1, 0.9, 1.1, 0.9, 1.0]
+            .iter()
+            .enumerate()
+            .map(|(i, p)| PriceSample { timestamp: at(i as i64), price_sol: *p })
+            .collect();
+
+        // History shorter than half the window: not sized yet
+        assert_eq!(volatility_stop_percent(&position, at(4)), None);
+        // Std dev of the prices is ~8.2% of their mean, doubled
+        let percent = volatility_stop_percent(&position, at(6)).unwrap();
+        assert!((percent - 16.33).abs() < 0.01);
+
+        // Sized once unless re-evaluated
+        position.volatility_stop_percent = Some(percent);
+        position.stop_loss_price = Some(1.0 - percent / 100.0);
+        assert_eq!(volatility_stop_percent(&position, at(6)), None);
+        position.volatility_stop = position.volatility_stop.map(|v| VolatilityStopSettings { reevaluate: true, ..v });
+        assert_eq!(volatility_stop_percent(&position, at(6)), None); // unchanged within half a point
+        position.price_history.iter_mut().for_each(|s| s.price_sol = 1.0);
+        assert_eq!(volatility_stop_percent(&position, at(6)), Some(5.0)); // calm: clamped to the minimum
+
+        // Left alone once the max-hold tightening has moved it above the sized level
+        position.stop_loss_price = Some(0.95);
+        assert_eq!(volatility_stop_percent(&position, at(6)), None);
+    }
+
+    #[test]
+    fn loss_rebuy_guard_modes() {
+        let exited = Utc::now();
+        let later = exited + ChronoDuration::minutes(90);
+        assert!(!loss_rebuy_blocked(LossRebuyGuard::Off, 60, exited, exited));
+        assert!(loss_rebuy_blocked(LossRebuyGuard::Cooldown, 60, exited, exited + ChronoDuration::minutes(30)));
+        assert!(!loss_rebuy_blocked(LossRebuyGuard::Cooldown, 60, exited, later));
+        assert!(loss_rebuy_blocked(LossRebuyGuard::Session, 60, exited, later));
+        assert_eq!("session".parse::<LossRebuyGuard>(), Ok(LossRebuyGuard::Session));
+        assert!("sometimes".parse::<LossRebuyGuard>().is_err());
+    }
+
+    #[test]
+    fn consecutive_no_route_exits_mark_unroutable() {
+        let now = Utc::now();
+        let mut position = position_at(1.0, Some(0.9), None);
+        let mut fail = |no_route| register_exit_failure(&mut position, PositionStatus::StopLossHit, now, 10, 60, 3, no_route, "err");
+        assert_eq!(fail(true), ExitFailureOutcome::Retry);
+        assert_eq!(fail(true), ExitFailureOutcome::Retry);
+        // Any other failure breaks the streak
+        assert_eq!(fail(false), ExitFailureOutcome::Retry);
+        assert_eq!(fail(true), ExitFailureOutcome::Retry);
+        assert_eq!(fail(true), ExitFailureOutcome::Retry);
+        assert_eq!(fail(true), ExitFailureOutcome::Unroutable);
+    }
+
+    #[test]
+    fn profit_fee_only_on_net_profit() {
+        assert_eq!(profit_fee_sol(Some(2.0), 10.0), Some(0.2));
+        assert_eq!(profit_fee_sol(Some(-1.0), 10.0), None);
+        assert_eq!(profit_fee_sol(Some(0.0), 10.0), None);
+        assert_eq!(profit_fee_sol(None, 10.0), None);
+        assert_eq!(profit_fee_sol(Some(0.00005), 10.0), None);
+    }
+
+    #[test]
+    fn emergency_and_failed_closes_are_alerts() {
+        let mut position = position_at(1.0, None, None);
+        position.status = PositionStatus::Closed;
+        position.record_event(PositionEventKind::ExitTriggered, 1.0, PositionStatus::TakeProfitHit.to_string());
+        assert!(!PositionLifecycle::Closed(position.clone(), TradeNotify::Open).is_alert());
+        assert!(!PositionLifecycle::Closed(position.clone(), TradeNotify::Open).should_notify());
+        assert!(PositionLifecycle::Closed(position.clone(), TradeNotify::Close).should_notify());
+        assert_eq!(position.exit_reason(), "TP Hit");
+
+        position.record_event(PositionEventKind::ExitTriggered, 1.0, PositionStatus::EmergencyClose.to_string());
+        assert!(PositionLifecycle::Closed(position.clone(), TradeNotify::None).is_alert());
+        assert!(PositionLifecycle::Closed(position.clone(), TradeNotify::None).should_notify());
+
+        position.events.clear();
+        position.status = PositionStatus::Failed;
+        assert!(PositionLifecycle::Closed(position.clone(), TradeNotify::None).is_alert());
+        assert!(!PositionLifecycle::Opened(position, TradeNotify::Close).is_alert());
+    }
+
+    #[test]
+    fn exit_pnl_counts_partial_sell_proceeds() {
+        let mut position = position_at(1.0, None, None);
+        position.entry_value_sol = 2.0;
+        assert_eq!(position.pnl_if_sold_for(3.0), (1.0, 50.0));
+        position.realized_value_sol = 0.5;
+        assert_eq!(position.pnl_if_sold_for(1.0), (-0.5, -25.0));
+    }
+
+    #[tokio::test]
+    async fn archives_only_positions_closed_before_cutoff() {
+        use crate::storage::JsonStore;
+        use crate::trading::position_archive::{is_archivable, PositionArchive};
+
+        let cutoff = Utc::now() - ChronoDuration::days(30);
+        let mut old = position_at(1.0, None, None);
+        old.status = PositionStatus::StopLossHit;
+        old.exit_time = Some(cutoff - ChronoDuration::days(1));
+        old.exit_value_sol = Some(0.8);
+        let mut recent = old.clone();
+        recent.id = "p2".to_string();
+        recent.exit_time = Some(Utc::now());
+        let mut stuck = old.clone();
+        stuck.id = "p3".to_string();
+        stuck.status = PositionStatus::Closing;
+        assert!(is_archivable(&old, cutoff));
+        assert!(!is_archivable(&recent, cutoff));
+        assert!(!is_archivable(&stuck, cutoff));
+
+        let dir = std::env::temp_dir().join(format!("positions_archive_{}", Uuid::new_v4()));
+        let store: Arc<dyn Store> = Arc::new(JsonStore::with_paths(dir.join("positions.json"), dir.join("strategies.json")));
+        let archive = PositionArchive::new(store.clone());
+        archive.append(std::slice::from_ref(&old)).await.unwrap();
+        // A retry after a failed positions save archives the same position again
+        archive.append(std::slice::from_ref(&old)).await.unwrap();
+        assert_eq!(archive.trades().await.len(), 1);
+
+        let reloaded = PositionArchive::new(store);
+        reloaded.load().await.unwrap();
+        let positions = reloaded.read_all().await.unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].id, old.id);
+        assert_eq!(reloaded.trades().await[0].exit_value_sol, 0.8);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn usd_basis_is_unknown_once_a_price_is_missing() {
+        // 1 SOL bought at $200, sold for 1.25 SOL after SOL fell to $144: up in SOL, down in USD
+        let entry = add_usd(Some(0.0), 1.0, Some(200.0));
+        let exit = add_usd(Some(0.0), 1.25, Some(144.0));
+        assert_eq!(exit.zip(entry).map(|(exit, entry)| exit - entry), Some(-20.0));
+
+        assert_eq!(add_usd(entry, 0.5, None), None);
+        assert_eq!(add_usd(None, 0.5, Some(150.0)), None);
+    }
+
+    #[test]
+    fn price_stream_is_throttled_to_its_interval() {
+        let now = Utc::now();
+        assert!(price_stream_due(5, None, now));
+        assert!(!price_stream_due(5, Some(now - ChronoDuration::seconds(3)), now));
+        assert!(price_stream_due(5, Some(now - ChronoDuration::seconds(5)), now));
+        assert!(!price_stream_due(0, None, now));
+
+        let mut position = position_at(1.0, None, None);
+        position.current_price_sol = 1.5;
+        position.pnl_sol = Some(0.5);
+        position.pnl_percent = Some(50.0);
+        let tick = PositionPriceTick::from(&position);
+        assert_eq!((tick.price_sol, tick.pnl_sol, tick.pnl_percent), (1.5, 0.5, 50.0));
+    }
+
+    #[test]
+    fn onchain_take_profit_is_not_monitored() {
+        let now = Utc::now();
+        let mut position = position_at(1.0, Some(0.5), Some(2.0));
+        position.current_price_sol = 1.9;
+        assert_eq!(position.monitored_take_profit(), Some(2.0));
+        let near_tp = next_check_interval_secs(&position, now, 1, 600);
+
+        position.take_profit_order = Some(TakeProfitOrder { order: "order".to_string(), min_sol_out: 2.0, placed_at: now });
+        assert_eq!(position.monitored_take_profit(), None);
+        // Only the distant stop-loss sets the check interval now
+        assert!(next_check_interval_secs(&position, now, 1, 600) > near_tp);
+    }
+}
@@ -0,0 +1,98 @@
+//! Archive of old closed positions
+//!
+//! The live positions are rewritten on every change, so a bot with thousands of
+//! trades spends longer and longer loading and saving them. Closed positions older
+//! than `position_archive_after_days` are moved to the store's archive instead
+//! (an append-only JSON-lines file, or a table in SQLite). The archive is read on
+//! demand (historical stats, FIFO export, the archive listing); only each archived
+//! trade's strategy and SOL in/out are kept in memory, so strategy stats snapshots
+//! still count them.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use crate::storage::Store;
+use crate::trading::position::{Position, PositionStatus};
+
+/// What strategy stats need from an archived trade
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchivedTrade {
+    pub position_id: String,
+    pub strategy_id: String,
+    pub entry_value_sol: f64,
+    pub exit_value_sol: f64,
+}
+
+impl ArchivedTrade {
+    fn from_position(position: &Position) -> Option<Self> {
+        Some(Self {
+            position_id: position.id.clone(),
+            strategy_id: position.strategy_id.clone(),
+            entry_value_sol: position.entry_value_sol,
+            exit_value_sol: position.exit_value_sol?,
+        })
+    }
+}
+
+/// Whether a position is finished and closed before `cutoff`
+pub fn is_archivable(position: &Position, cutoff: DateTime<Utc>) -> bool {
+    !matches!(position.status, PositionStatus::Active | PositionStatus::Closing)
+        && position.exit_time.is_some_and(|t| t < cutoff)
+}
+
+pub struct PositionArchive {
+    store: Arc<dyn Store>,
+    trades: RwLock<Vec<ArchivedTrade>>,
+}
+
+impl PositionArchive {
+    pub fn new(store: Arc<dyn Store>) -> Self {
+        Self {
+            store,
+            trades: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Every archived position, oldest archived first
+    pub async fn read_all(&self) -> Result<Vec<Position>> {
+        self.store.load_archived_positions().await
+    }
+
+    /// Load the archived trades' stats summary
+    pub async fn load(&self) -> Result<()> {
+        let positions = self.read_all().await?;
+        let trades: Vec<ArchivedTrade> = positions.iter().filter_map(ArchivedTrade::from_position).collect();
+        if !positions.is_empty() {
+            info!("Positions archive in {} holds {} closed positions", self.store.describe(), positions.len());
+        }
+        *self.trades.write().await = trades;
+        Ok(())
+    }
+
+    /// Add positions to the archive
+    pub async fn append(&self, positions: &[Position]) -> Result<()> {
+        if positions.is_empty() {
+            return Ok(());
+        }
+        self.store.archive_positions(positions).await?;
+
+        debug!("Archived {} positions to {}", positions.len(), self.store.describe());
+        let mut trades = self.trades.write().await;
+        let known: HashSet<String> = trades.iter().map(|t| t.position_id.clone()).collect();
+        let new_trades: Vec<ArchivedTrade> = positions.iter()
+            .filter(|p| !known.contains(&p.id))
+            .filter_map(ArchivedTrade::from_position)
+            .collect();
+        trades.extend(new_trades);
+        Ok(())
+    }
+
+    /// Closed trades in the archive, for strategy stats
+    pub async fn trades(&self) -> Vec<ArchivedTrade> {
+        self.trades.read().await.clone()
+    }
+}
@@ -0,0 +1,116 @@
+//! Per-position price history and decision timeline
+//!
+//! The monitor appends the current price to each open position every cycle and
+//! records an event whenever a level is set or moved, an exit triggers, or the
+//! position is scaled or closed, so a closed trade can be replayed afterwards.
+//! Both series are bounded so positions.json doesn't grow without limit: older
+//! price samples are thinned out, and trailing-stop moves are dropped first.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Most price samples kept per position
+pub const MAX_PRICE_SAMPLES: usize = 500;
+
+/// Most timeline events kept per position
+pub const MAX_POSITION_EVENTS: usize = 100;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PriceSample {
+    pub timestamp: DateTime<Utc>,
+    pub price_sol: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionEventKind {
+    Opened,
+    /// Stop-loss / take-profit / trailing levels (re)computed from the entry price
+    LevelsSet,
+    TakeProfitMoved,
+    TrailingStopMoved,
+    /// Stop-loss ratcheted toward the price as the max hold deadline nears
+    StopLossMoved,
+    ScaledIn,
+    PartialSell,
+    DrawdownAlert,
+    /// The rug monitor saw liquidity pulled, a large holder dump or new supply
+    RugDetected,
+    ExitTriggered,
+    /// A sell failed or went unconfirmed; the exit will be retried
+    ExitFailed,
+    Closed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PositionEvent {
+    pub timestamp: DateTime<Utc>,
+    pub kind: PositionEventKind,
+    pub price_sol: f64,
+    pub detail: String,
+}
+
+/// Append a sample, thinning the older half of the series to every other sample
+/// once it exceeds `max`, so recent prices stay dense and old ones get sparser.
+pub fn push_price_sample(samples: &mut Vec<PriceSample>, sample: PriceSample, max: usize) {
+    samples.push(sample);
+    if samples.len() <= max {
+        return;
+    }
+    let older = samples.len() / 2;
+    let mut index = 0;
+    samples.retain(|_| {
+        let keep = index >= older || index % 2 == 0;
+        index += 1;
+        keep
+    });
+}
+
+/// Append an event, dropping the oldest trailing-stop move (or failing that, the
+/// oldest event after the first) once there are more than `max`.
+pub fn push_event(events: &mut Vec<PositionEvent>, event: PositionEvent, max: usize) {
+    events.push(event);
+    if events.len() <= max {
+        return;
+    }
+    let drop_index = events.iter()
+        .position(|e| e.kind == PositionEventKind::TrailingStopMoved)
+        .unwrap_or(1.min(events.len() - 1));
+    events.remove(drop_index);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn price_history_stays_bounded_and_keeps_latest() {
+        let start = Utc::now();
+        let mut samples = Vec::new();
+        for i in 0..2_000 {
+            let sample = PriceSample { timestamp: start + Duration::seconds(i), price_sol: i as f64 };
+            push_price_sample(&mut samples, sample, 100);
+        }
+        assert!(samples.len() <= 100);
+        assert_eq!(samples.first().unwrap().price_sol, 0.0);
+        assert_eq!(samples.last().unwrap().price_sol, 1_999.0);
+        assert!(samples.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+    }
+
+    #[test]
+    fn events_drop_trailing_moves_first() {
+        let event = |kind| PositionEvent { timestamp: Utc::now(), kind, price_sol: 1.0, detail: String::new() };
+        let mut events = vec![event(PositionEventKind::Opened), event(PositionEventKind::TrailingStopMoved)];
+        push_event(&mut events, event(PositionEventKind::ExitTriggered), 2);
+        assert_eq!(
+            events.iter().map(|e| e.kind).collect::<Vec<_>>(),
+            vec![PositionEventKind::Opened, PositionEventKind::ExitTriggered]
+        );
+        push_event(&mut events, event(PositionEventKind::Closed), 2);
+        assert_eq!(
+            events.iter().map(|e| e.kind).collect::<Vec<_>>(),
+            vec![PositionEventKind::Opened, PositionEventKind::Closed]
+        );
+    }
+}
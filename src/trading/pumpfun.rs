@@ -10,6 +10,7 @@
 
 use borsh::BorshDeserialize;
 use serde::{Deserialize, Serialize};
+use solana_sdk::instruction::{AccountMeta, Instruction};
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 
@@ -53,6 +54,31 @@ pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
 /// Graduation threshold - approximately 85 SOL in lamports
 pub const GRADUATION_THRESHOLD_LAMPORTS: u64 = 85_000_000_000;
 
+/// Pump.fun global config account
+pub const PUMP_GLOBAL: &str = "4wTV1YmiEkRvAtNtsSGPtUrqRYQMe5SKy2uB4Jjaxnjf";
+
+/// Protocol fee recipient passed to buy/sell
+pub const PUMP_FEE_RECIPIENT: &str = "CebN5WGQ4jvEPvsVU4EoHEpgzq1VV7AbicfhtW4xC9iM";
+
+/// Anchor event authority PDA of the Pump.fun program
+pub const PUMP_EVENT_AUTHORITY: &str = "Ce6TQqeHC9p8KetsN6JsjHK7UTZk7nasjjnr7XxXp9F1";
+
+/// Creator fee vault seed for PDA derivation
+pub const CREATOR_VAULT_SEED: &[u8] = b"creator-vault";
+
+/// Buy instruction discriminator - sha256("global:buy")[0..8]
+pub const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+
+/// Sell instruction discriminator - sha256("global:sell")[0..8]
+pub const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+/// Trading fee charged on the SOL side of curve trades (protocol + creator), in bps
+pub const CURVE_FEE_BPS: u64 = 125;
+
+/// Offset of the creator pubkey in bonding curve account data
+/// (8 discriminator + 5 u64 reserves/supply + 1 bool)
+const CURVE_CREATOR_OFFSET: usize = 49;
+
 // ============================================================================
 // EVENT STRUCTURES
 // ============================================================================
@@ -173,6 +199,44 @@ impl BondingCurveState {
         let supply = self.token_total_supply as f64 / 1_000_000.0; // 6 decimals
         price * supply
     }
+
+    /// Raw tokens received for `lamports_in`, after the trading fee.
+    /// Constant product on the virtual reserves, capped at the real tokens left.
+    pub fn buy_quote(&self, lamports_in: u64) -> u64 {
+        let sol_in = lamports_in as u128 * (10_000 - CURVE_FEE_BPS) as u128 / 10_000;
+        let (vsol, vtok) = (self.virtual_sol_reserves as u128, self.virtual_token_reserves as u128);
+        if vsol == 0 || vtok == 0 {
+            return 0;
+        }
+        let out = vtok * sol_in / (vsol + sol_in);
+        out.min(self.real_token_reserves as u128) as u64
+    }
+
+    /// Lamports received for selling `tokens_in` raw tokens, after the trading fee.
+    /// Capped at the real SOL held by the curve.
+    pub fn sell_quote(&self, tokens_in: u64) -> u64 {
+        let (vsol, vtok) = (self.virtual_sol_reserves as u128, self.virtual_token_reserves as u128);
+        if vsol == 0 || vtok == 0 {
+            return 0;
+        }
+        let gross = vsol * tokens_in as u128 / (vtok + tokens_in as u128);
+        let net = gross * (10_000 - CURVE_FEE_BPS) as u128 / 10_000;
+        net.min(self.real_sol_reserves as u128) as u64
+    }
+}
+
+/// Parse a bonding curve account (including its 8-byte discriminator).
+/// Returns the state and, on accounts new enough to carry it, the creator.
+pub fn parse_bonding_curve_account(data: &[u8]) -> Option<(BondingCurveState, Option<Pubkey>)> {
+    if data.len() <= 8 {
+        return None;
+    }
+    // Newer accounts have trailing fields, so don't require the slice to be consumed
+    let state = BondingCurveState::deserialize(&mut &data[8..]).ok()?;
+    let creator = data.get(CURVE_CREATOR_OFFSET..CURVE_CREATOR_OFFSET + 32)
+        .and_then(|bytes| Pubkey::try_from(bytes).ok())
+        .filter(|creator| *creator != Pubkey::default());
+    Some((state, creator))
 }
 
 // ============================================================================
@@ -232,6 +296,91 @@ pub fn get_pump_program_id() -> Pubkey {
     Pubkey::from_str(PUMP_PROGRAM_ID).expect("Invalid PUMP_PROGRAM_ID")
 }
 
+/// Derive the creator fee vault for a token creator.
+pub fn derive_creator_vault_pda(creator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CREATOR_VAULT_SEED, creator.as_ref()], &get_pump_program_id()).0
+}
+
+// ============================================================================
+// TRADE INSTRUCTIONS
+// ============================================================================
+
+/// Accounts shared by curve buys and sells
+#[derive(Debug, Clone)]
+pub struct CurveTradeAccounts {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub user: Pubkey,
+    pub creator: Pubkey,
+    /// Token program owning the mint (SPL Token or Token-2022)
+    pub token_program: Pubkey,
+}
+
+impl CurveTradeAccounts {
+    fn associated_bonding_curve(&self) -> Pubkey {
+        spl_associated_token_account::get_associated_token_address_with_program_id(&self.bonding_curve, &self.mint, &self.token_program)
+    }
+
+    pub fn associated_user(&self) -> Pubkey {
+        spl_associated_token_account::get_associated_token_address_with_program_id(&self.user, &self.mint, &self.token_program)
+    }
+}
+
+fn pump_pubkey(address: &str) -> Pubkey {
+    Pubkey::from_str(address).expect("Invalid Pump.fun constant address")
+}
+
+fn trade_data(discriminator: [u8; 8], amount: u64, sol_limit: u64) -> Vec<u8> {
+    let mut data = discriminator.to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&sol_limit.to_le_bytes());
+    data
+}
+
+/// Buy exactly `token_amount` raw tokens, paying at most `max_sol_cost` lamports.
+pub fn build_buy_instruction(accounts: &CurveTradeAccounts, token_amount: u64, max_sol_cost: u64) -> Instruction {
+    Instruction {
+        program_id: get_pump_program_id(),
+        accounts: vec![
+            AccountMeta::new_readonly(pump_pubkey(PUMP_GLOBAL), false),
+            AccountMeta::new(pump_pubkey(PUMP_FEE_RECIPIENT), false),
+            AccountMeta::new_readonly(accounts.mint, false),
+            AccountMeta::new(accounts.bonding_curve, false),
+            AccountMeta::new(accounts.associated_bonding_curve(), false),
+            AccountMeta::new(accounts.associated_user(), false),
+            AccountMeta::new(accounts.user, true),
+            AccountMeta::new_readonly(solana_sdk::system_program::id(), false),
+            AccountMeta::new_readonly(accounts.token_program, false),
+            AccountMeta::new(derive_creator_vault_pda(&accounts.creator), false),
+            AccountMeta::new_readonly(pump_pubkey(PUMP_EVENT_AUTHORITY), false),
+            AccountMeta::new_readonly(get_pump_program_id(), false),
+        ],
+        data: trade_data(BUY_DISCRIMINATOR, token_amount, max_sol_cost),
+    }
+}
+
+/// Sell `token_amount` raw tokens, receiving at least `min_sol_output` lamports.
+pub fn build_sell_instruction(accounts: &CurveTradeAccounts, token_amount: u64, min_sol_output: u64) -> Instruction {
+    Instruction {
+        program_id: get_pump_program_id(),
+        accounts: vec![
+            AccountMeta::new_readonly(pump_pubkey(PUMP_GLOBAL), false),
+            AccountMeta::new(pump_pubkey(PUMP_FEE_RECIPIENT), false),
+            AccountMeta::new_readonly(accounts.mint, false),
+            AccountMeta::new(accounts.bonding_curve, false),
+            AccountMeta::new(accounts.associated_bonding_curve(), false),
+            AccountMeta::new(accounts.associated_user(), false),
+            AccountMeta::new(accounts.user, true),
+            AccountMeta::new_readonly(solana_sdk::system_program::id(), false),
+            AccountMeta::new(derive_creator_vault_pda(&accounts.creator), false),
+            AccountMeta::new_readonly(accounts.token_program, false),
+            AccountMeta::new_readonly(pump_pubkey(PUMP_EVENT_AUTHORITY), false),
+            AccountMeta::new_readonly(get_pump_program_id(), false),
+        ],
+        data: trade_data(SELL_DISCRIMINATOR, token_amount, min_sol_output),
+    }
+}
+
 /// Get the PumpSwap program ID as a Pubkey.
 pub fn get_pumpswap_program_id() -> Pubkey {
     Pubkey::from_str(PUMPSWAP_PROGRAM_ID).expect("Invalid PUMPSWAP_PROGRAM_ID")
@@ -497,5 +646,86 @@ mod tests {
         assert!(Pubkey::from_str(PUMP_PROGRAM_ID).is_ok());
         assert!(Pubkey::from_str(PUMPSWAP_PROGRAM_ID).is_ok());
         assert!(Pubkey::from_str(RAYDIUM_AMM_V4).is_ok());
+        assert!(Pubkey::from_str(PUMP_GLOBAL).is_ok());
+        assert!(Pubkey::from_str(PUMP_FEE_RECIPIENT).is_ok());
+        assert!(Pubkey::from_str(PUMP_EVENT_AUTHORITY).is_ok());
+    }
+
+    fn initial_curve() -> BondingCurveState {
+        BondingCurveState {
+            virtual_token_reserves: INITIAL_VIRTUAL_TOKEN_RESERVES,
+            virtual_sol_reserves: INITIAL_VIRTUAL_SOL_RESERVES,
+            real_token_reserves: INITIAL_REAL_TOKEN_RESERVES,
+            real_sol_reserves: 0,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+        }
+    }
+
+    #[test]
+    fn test_curve_buy_and_sell_quotes() {
+        let mut curve = initial_curve();
+
+        // 1 SOL in at the initial price buys a bit under 1/30 of the virtual tokens
+        let tokens = curve.buy_quote(1_000_000_000);
+        let spot_tokens = 1_000_000_000u128 * INITIAL_VIRTUAL_TOKEN_RESERVES as u128 / INITIAL_VIRTUAL_SOL_RESERVES as u128;
+        assert!((tokens as u128) < spot_tokens);
+        assert!(tokens > 30_000_000_000_000);
+
+        // Apply the buy, then selling the same tokens returns less than went in (fees both ways)
+        let sol_in = 1_000_000_000 * (10_000 - CURVE_FEE_BPS) / 10_000;
+        curve.virtual_sol_reserves += sol_in;
+        curve.virtual_token_reserves -= tokens;
+        curve.real_sol_reserves += sol_in;
+        curve.real_token_reserves -= tokens;
+        let back = curve.sell_quote(tokens);
+        assert!(back < 1_000_000_000);
+        assert!(back > 970_000_000);
+
+        // Can't buy more than the curve holds
+        let mut nearly_done = initial_curve();
+        nearly_done.real_token_reserves = 1_000;
+        assert_eq!(nearly_done.buy_quote(10_000_000_000), 1_000);
+    }
+
+    #[test]
+    fn test_parse_curve_account_with_creator() {
+        let creator = Pubkey::new_unique();
+        let mut data = vec![0u8; 8];
+        for value in [INITIAL_VIRTUAL_TOKEN_RESERVES, INITIAL_VIRTUAL_SOL_RESERVES, INITIAL_REAL_TOKEN_RESERVES, 0, 1_000_000_000_000_000] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.push(0);
+        data.extend_from_slice(creator.as_ref());
+        data.extend_from_slice(&[0u8; 16]); // Trailing padding
+
+        let (state, parsed_creator) = parse_bonding_curve_account(&data).expect("Should parse curve");
+        assert_eq!(state.real_token_reserves, INITIAL_REAL_TOKEN_RESERVES);
+        assert!(!state.complete);
+        assert_eq!(parsed_creator, Some(creator));
+
+        // Older accounts without a creator still parse
+        let (_, no_creator) = parse_bonding_curve_account(&data[..CURVE_CREATOR_OFFSET]).unwrap();
+        assert_eq!(no_creator, None);
+    }
+
+    #[test]
+    fn test_trade_instruction_data() {
+        let accounts = CurveTradeAccounts {
+            mint: Pubkey::new_unique(),
+            bonding_curve: Pubkey::new_unique(),
+            user: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            token_program: spl_token::id(),
+        };
+        let buy = build_buy_instruction(&accounts, 5, 7);
+        assert_eq!(&buy.data[..8], &BUY_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(buy.data[8..16].try_into().unwrap()), 5);
+        assert_eq!(u64::from_le_bytes(buy.data[16..24].try_into().unwrap()), 7);
+        assert!(buy.accounts.iter().any(|a| a.pubkey == accounts.user && a.is_signer));
+
+        let sell = build_sell_instruction(&accounts, 5, 3);
+        assert_eq!(&sell.data[..8], &SELL_DISCRIMINATOR);
+        assert_eq!(sell.accounts.len(), buy.accounts.len());
     }
 }
@@ -92,12 +92,17 @@ impl PumpfunMonitor {
     /// * `token_sender` - Channel to send discovered tokens
     pub fn new(helius_api_key: &str, token_sender: mpsc::Sender<PumpfunToken>) -> Self {
         let websocket_url = format!("wss://mainnet.helius-rpc.com/?api-key={}", helius_api_key);
+        Self::with_websocket_url(&websocket_url, token_sender)
+    }
 
+    /// Create a monitor against any Solana WebSocket endpoint that supports
+    /// `logsSubscribe` (credentials, if any, go in the URL).
+    pub fn with_websocket_url(websocket_url: &str, token_sender: mpsc::Sender<PumpfunToken>) -> Self {
         let (shutdown_tx, _) = broadcast::channel(1);
 
         Self {
             config: PumpfunMonitorConfig {
-                websocket_url,
+                websocket_url: websocket_url.to_string(),
                 ..Default::default()
             },
             token_sender,
@@ -224,6 +229,7 @@ impl PumpfunMonitor {
 
         info!("✅ Subscribed! Listening for new Pump.fun tokens...");
 
+        let mut stream_ended = false;
         loop {
             tokio::select! {
                 // Check for shutdown signal
@@ -251,6 +257,7 @@ impl PumpfunMonitor {
                         }
                         None => {
                             warn!("Log stream ended unexpectedly");
+                            stream_ended = true;
                             break;
                         }
                     }
@@ -261,6 +268,10 @@ impl PumpfunMonitor {
         // Unsubscribe
         unsubscribe().await;
 
+        // A dropped stream is a disconnect, not a shutdown: report it so the caller reconnects
+        if stream_ended {
+            return Err(anyhow!("Log stream ended unexpectedly"));
+        }
         Ok(())
     }
 
@@ -0,0 +1,216 @@
+//! Buys and sells on a pump.fun bonding curve
+//!
+//! Until a pump.fun token graduates it only trades on its bonding curve, which
+//! Jupiter can't route. These swaps talk to the pump.fun program directly: the
+//! expected output comes from the curve's constant-product math on its virtual
+//! reserves, and the instruction's SOL limit (buys) or minimum SOL out (sells)
+//! carries the slippage protection. Once the curve completes the token is traded through Jupiter again.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::message::{Message, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use tracing::{debug, info};
+
+use crate::api::jupiter::{SwapResult, SOL_MINT};
+use crate::solana::client::SolanaClient;
+use crate::solana::jito::JitoTip;
+use crate::solana::wallet::WalletManager;
+use crate::trading::pumpfun::{
+    build_buy_instruction, build_sell_instruction, derive_bonding_curve_pda, parse_bonding_curve_account,
+    BondingCurveState, CurveTradeAccounts,
+};
+
+/// A token still trading on its bonding curve
+#[derive(Debug, Clone)]
+pub struct ActiveCurve {
+    pub mint: Pubkey,
+    pub address: Pubkey,
+    pub state: BondingCurveState,
+    pub creator: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl ActiveCurve {
+    fn trade_accounts(&self, user: Pubkey) -> CurveTradeAccounts {
+        CurveTradeAccounts {
+            mint: self.mint,
+            bonding_curve: self.address,
+            user,
+            creator: self.creator,
+            token_program: self.token_program,
+        }
+    }
+}
+
+/// The token's bonding curve if it hasn't graduated yet. None for tokens that
+/// aren't pump.fun tokens, have graduated, or whose accounts couldn't be read -
+/// all of which go through Jupiter instead.
+pub async fn active_curve(solana_client: &SolanaClient, token_mint: &str) -> Option<ActiveCurve> {
+    let mint = Pubkey::from_str(token_mint).ok()?;
+    let (address, _) = derive_bonding_curve_pda(&mint);
+    let rpc = solana_client.get_rpc();
+    let curve_account = rpc.get_account(&address).await.ok()?;
+    let (state, creator) = parse_bonding_curve_account(&curve_account.data)?;
+    if state.is_ready_to_graduate() {
+        debug!("{} has graduated from its bonding curve", token_mint);
+        return None;
+    }
+    let Some(creator) = creator else {
+        debug!("Bonding curve of {} has no creator field; leaving it to Jupiter", token_mint);
+        return None;
+    };
+    let token_program = rpc.get_account(&mint).await.ok()?.owner;
+    Some(ActiveCurve { mint, address, state, creator, token_program })
+}
+
+/// Price impact of a trade against the curve's spot price, in percent
+fn price_impact_pct(state: &BondingCurveState, lamports: u64, tokens: u64) -> f64 {
+    if tokens == 0 || state.virtual_token_reserves == 0 {
+        return 0.0;
+    }
+    let spot = state.virtual_sol_reserves as f64 / state.virtual_token_reserves as f64;
+    let execution = lamports as f64 / tokens as f64;
+    ((execution - spot).abs() / spot) * 100.0
+}
+
+/// Reduce an amount by `slippage_bps`
+pub(crate) fn less_slippage(amount: u64, slippage_bps: u32) -> u64 {
+    (amount as u128 * 10_000u128.saturating_sub(slippage_bps as u128) / 10_000) as u64
+}
+
+/// Raise an amount by `slippage_bps`
+fn plus_slippage(amount: u64, slippage_bps: u32) -> u64 {
+    (amount as u128 * (10_000 + slippage_bps as u128) / 10_000).min(u64::MAX as u128) as u64
+}
+
+async fn send_curve_transaction(
+    wallet_manager: &WalletManager,
+    mut instructions: Vec<solana_sdk::instruction::Instruction>,
+    priority_fee_micro_lamports: Option<u64>,
+    jito: Option<&JitoTip>,
+) -> Result<Signature> {
+    if let Some(fee) = priority_fee_micro_lamports.filter(|f| *f > 0) {
+        instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_price(fee));
+    }
+    let message = Message::new(&instructions, Some(&wallet_manager.get_public_key()));
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+        message: VersionedMessage::Legacy(message),
+    };
+    // The blockhash is set when signing
+    wallet_manager.send_swap_transaction(transaction, 0, jito).await
+        .context("Failed to send bonding curve transaction")
+}
+
+/// Buy the tokens `amount_sol` gets at the curve's current price. The program buys
+/// an exact token amount, so the SOL cost is capped at `amount_sol` plus
+/// `slippage_bps`: a price move beyond the slippage makes it reject the buy instead
+/// of overpaying.
+pub async fn buy_on_curve(
+    curve: &ActiveCurve,
+    token_decimals: u8,
+    amount_sol: f64,
+    slippage_bps: u32,
+    priority_fee_micro_lamports: Option<u64>,
+    jito: Option<&JitoTip>,
+    wallet_manager: Arc<WalletManager>,
+) -> Result<SwapResult> {
+    let lamports_in = (amount_sol * 1_000_000_000.0) as u64;
+    let expected_tokens = curve.state.buy_quote(lamports_in);
+    if expected_tokens == 0 {
+        return Err(anyhow!("Bonding curve quote for {} SOL of {} is zero", amount_sol, curve.mint));
+    }
+    let max_sol_cost = plus_slippage(lamports_in, slippage_bps);
+    let scale = 10f64.powi(token_decimals as i32);
+    info!(
+        "Bonding curve buy: {:.6} SOL (max {:.6}) -> {:.6} {} (curve {:.1}% complete)",
+        amount_sol, max_sol_cost as f64 / 1e9, expected_tokens as f64 / scale, curve.mint, curve.state.get_progress_percent()
+    );
+
+    let user = wallet_manager.get_public_key();
+    let accounts = curve.trade_accounts(user);
+    let create_ata = spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+        &user, &user, &curve.mint, &curve.token_program,
+    );
+    let signature = send_curve_transaction(
+        &wallet_manager,
+        vec![create_ata, build_buy_instruction(&accounts, expected_tokens, max_sol_cost)],
+        priority_fee_micro_lamports,
+        jito,
+    ).await?;
+    info!("Bonding curve buy sent: {}", signature);
+
+    Ok(SwapResult {
+        input_mint: SOL_MINT.to_string(),
+        output_mint: curve.mint.to_string(),
+        in_amount_ui: amount_sol,
+        out_amount_ui: expected_tokens as f64 / scale,
+        actual_out_amount_ui: None,
+        price_impact_pct: price_impact_pct(&curve.state, lamports_in, expected_tokens),
+        transaction_signature: signature.to_string(),
+    })
+}
+
+/// Sell `token_amount_ui` tokens, requiring at least the curve quote less `slippage_bps`
+pub async fn sell_on_curve(
+    curve: &ActiveCurve,
+    token_decimals: u8,
+    token_amount_ui: f64,
+    slippage_bps: u32,
+    priority_fee_micro_lamports: Option<u64>,
+    jito: Option<&JitoTip>,
+    wallet_manager: Arc<WalletManager>,
+) -> Result<SwapResult> {
+    let raw_tokens = (token_amount_ui * 10f64.powi(token_decimals as i32)) as u64;
+    if raw_tokens == 0 {
+        return Err(anyhow!("Input token amount is too small or zero"));
+    }
+    let expected_lamports = curve.state.sell_quote(raw_tokens);
+    let min_lamports = less_slippage(expected_lamports, slippage_bps);
+    if min_lamports == 0 {
+        return Err(anyhow!("Bonding curve quote for {} {} is zero", token_amount_ui, curve.mint));
+    }
+    info!(
+        "Bonding curve sell: {:.6} {} -> {:.6} SOL (min {:.6})",
+        token_amount_ui, curve.mint, expected_lamports as f64 / 1e9, min_lamports as f64 / 1e9
+    );
+
+    let accounts = curve.trade_accounts(wallet_manager.get_public_key());
+    let signature = send_curve_transaction(
+        &wallet_manager,
+        vec![build_sell_instruction(&accounts, raw_tokens, min_lamports)],
+        priority_fee_micro_lamports,
+        jito,
+    ).await?;
+    info!("Bonding curve sell sent: {}", signature);
+
+    Ok(SwapResult {
+        input_mint: curve.mint.to_string(),
+        output_mint: SOL_MINT.to_string(),
+        in_amount_ui: token_amount_ui,
+        out_amount_ui: expected_lamports as f64 / 1_000_000_000.0,
+        actual_out_amount_ui: None,
+        price_impact_pct: price_impact_pct(&curve.state, expected_lamports, raw_tokens),
+        transaction_signature: signature.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slippage_reduces_limits() {
+        assert_eq!(less_slippage(10_000, 100), 9_900);
+        assert_eq!(less_slippage(10_000, 0), 10_000);
+        assert_eq!(less_slippage(10_000, 20_000), 0);
+        assert_eq!(plus_slippage(10_000, 100), 10_100);
+        assert_eq!(plus_slippage(u64::MAX, 100), u64::MAX);
+    }
+}
@@ -8,7 +8,11 @@ use serde_json::Value; // Added for Raydium API parsing
 use crate::api::birdeye::{BirdeyeClient, TokenOverviewData};
 use crate::api::helius::HeliusClient;
 use crate::api::jupiter::JupiterClient;
+use crate::api::price_cache::PriceCache;
 use crate::solana::client::SolanaClient;
+use crate::trading::deployer::{deployer_report, deployer_score, DeployerReport};
+use crate::trading::metadata_check::{fetch_offchain_metadata, metadata_score, MetadataFindings};
+use crate::trading::pool_liquidity::PoolLiquidity;
 use crate::error::TraderbotError;
 use crate::solana::wallet::WalletManager;
 use base64::{engine::general_purpose::STANDARD, Engine as _};
@@ -32,6 +36,10 @@ pub struct RiskAnalysis {
     pub transfer_tax_percent: f64,
     pub can_sell: bool,
     pub concentration_percent: f64,
+    #[serde(default)]
+    pub recommended_slippage_bps: u32, // Sell slippage likely to fill on this token's pool
+    #[serde(default)]
+    pub deployer: Option<DeployerReport>, // Creator wallet's launch history (None if unchecked or unknown)
 }
 
 
@@ -44,6 +52,14 @@ pub struct RiskAnalyzer {
     wallet_manager: Arc<WalletManager>,
     // Add http client for Raydium API call
     http_client: reqwest::Client,
+    // On-chain reserves, used when Birdeye has no liquidity figure
+    pool_liquidity: Option<Arc<PoolLiquidity>>,
+    // Token prices and Birdeye overviews, shared with the position manager
+    price_cache: Arc<PriceCache>,
+    // Earlier launches of the deployer whose liquidity is checked (None = no deployer check)
+    deployer_launches_checked: Option<usize>,
+    // Score image, description and social links from the token's metadata
+    metadata_check: bool,
 }
 
 impl RiskAnalyzer {
@@ -54,6 +70,8 @@ impl RiskAnalyzer {
         birdeye_client: Arc<BirdeyeClient>,
         wallet_manager: Arc<WalletManager>,
     ) -> Self {
+        // Uncached until a shared cache is attached
+        let price_cache = Arc::new(PriceCache::new(jupiter_client.clone(), birdeye_client.clone(), Duration::ZERO));
         Self {
             solana_client,
             helius_client,
@@ -65,9 +83,52 @@ impl RiskAnalyzer {
                 .timeout(Duration::from_secs(15)) // Shorter timeout for external API
                 .build()
                 .expect("Failed to create HTTP client for RiskAnalyzer"),
+            pool_liquidity: None,
+            price_cache,
+            deployer_launches_checked: None,
+            metadata_check: false,
         }
     }
 
+    /// Look tokens up through a cache shared with other components
+    pub fn with_price_cache(mut self, price_cache: Arc<PriceCache>) -> Self {
+        self.price_cache = price_cache;
+        self
+    }
+
+    /// Shared token price and overview cache
+    pub fn price_cache(&self) -> Arc<PriceCache> {
+        self.price_cache.clone()
+    }
+
+    /// Fall back to on-chain pool reserves when Birdeye can't price a token's liquidity
+    pub fn with_onchain_liquidity_fallback(mut self, enabled: bool) -> Self {
+        self.pool_liquidity = enabled.then(|| Arc::new(PoolLiquidity::new(self.solana_client.clone())));
+        self
+    }
+
+    /// Score the mint's deployer wallet, checking up to `launches_checked` of its earlier tokens
+    pub fn with_deployer_reputation(mut self, enabled: bool, launches_checked: usize) -> Self {
+        self.deployer_launches_checked = enabled.then_some(launches_checked);
+        self
+    }
+
+    /// Score the token's image, description and social links
+    pub fn with_metadata_check(mut self, enabled: bool) -> Self {
+        self.metadata_check = enabled;
+        self
+    }
+
+    /// Live SOL/USD price (None if Birdeye can't provide one right now)
+    pub async fn live_sol_price_usd(&self) -> Option<f64> {
+        self.birdeye_client.get_live_sol_price_usd().await
+    }
+
+    /// Cooldown shared by the analysis APIs after rate-limit responses
+    pub fn rate_limit_backoff(&self) -> &crate::api::rate_limit::RateLimitBackoff {
+        self.birdeye_client.rate_limit_backoff()
+    }
+
     // Main analysis function
     pub async fn analyze_token(&self, token_address_str: &str) -> Result<RiskAnalysis> {
         info!("Starting risk analysis for token: {}", token_address_str);
@@ -79,7 +140,7 @@ impl RiskAnalyzer {
         let mut details = Vec::new();
 
         // --- Fetch Data Upfront ---
-        let birdeye_overview = match self.birdeye_client.get_token_overview(token_address_str).await {
+        let birdeye_overview = match self.price_cache.token_overview(token_address_str).await {
             Ok(Some(data)) => {
                 debug!("Successfully fetched Birdeye overview for {}", token_address_str);
                 Some(data)
@@ -147,8 +208,13 @@ impl RiskAnalyzer {
         };
 
         // 2. Liquidity Check - Now using our improved implementation
-        let liquidity_sol = match self.check_liquidity(birdeye_overview.as_ref(), sol_price_usd).await {
-            Ok(liq) => {
+        let liquidity_sol = match self.check_liquidity(&token_pubkey, birdeye_overview.as_ref(), sol_price_usd).await {
+            Ok(None) => {
+                risk_score += 30; // Unpriced pools are as risky as empty ones to buy into
+                details.push("❓ Liquidity unknown (no Birdeye or on-chain data).".to_string());
+                0.0
+            }
+            Ok(Some(liq)) => {
                 // Adjusted thresholds based on feedback
                 if liq < 1.0 { risk_score += 30; details.push(format!("🔴 Very low liquidity ({:.2} SOL).", liq)); }
                 else if liq < 5.0 { risk_score += 20; details.push(format!("🟠 Low liquidity ({:.2} SOL).", liq)); }
@@ -211,6 +277,57 @@ impl RiskAnalyzer {
         else if transfer_tax_percent > 0.0 { details.push(format!("✅ Low transfer tax ({:.1}%).", transfer_tax_percent)); }
         else { details.push("✅ No transfer tax detected.".to_string()); }
 
+        // The DAS asset feeds both the deployer and the metadata checks
+        let asset = if self.deployer_launches_checked.is_some() || self.metadata_check {
+            match self.helius_client.get_asset(token_address_str).await {
+                Ok(asset) => Some(asset),
+                Err(e) => {
+                    warn!("Failed to fetch DAS asset for {}: {:?}", token_address_str, e);
+                    details.push("❓ Failed to fetch token metadata from Helius.".to_string());
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // 7. Deployer Reputation
+        let deployer = if let (Some(launches_checked), Some(asset)) = (self.deployer_launches_checked, asset.as_ref()) {
+            match deployer_report(&self.helius_client, &self.solana_client, &self.price_cache, asset, launches_checked).await {
+                Ok(Some(report)) => {
+                    let (score, deployer_details) = deployer_score(&report, chrono::Utc::now());
+                    risk_score += score;
+                    details.extend(deployer_details);
+                    Some(report)
+                }
+                Ok(None) => {
+                    details.push("❓ Deployer wallet unknown.".to_string());
+                    None
+                }
+                Err(e) => {
+                    warn!("Deployer check failed for {}: {:?}", token_address_str, e);
+                    details.push("❓ Failed to check deployer history.".to_string());
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // 8. Metadata & Socials
+        if let Some(asset) = asset.as_ref().filter(|_| self.metadata_check) {
+            let offchain = match fetch_offchain_metadata(&self.http_client, asset).await {
+                Ok(offchain) => offchain,
+                Err(e) => {
+                    debug!("Off-chain metadata unavailable for {}: {:?}", token_address_str, e);
+                    None
+                }
+            };
+            let (score, metadata_details) = metadata_score(&MetadataFindings::from_asset(asset, offchain.as_ref()));
+            risk_score += score;
+            details.extend(metadata_details);
+        }
+
         // --- Final Score Calculation ---
         let final_risk_level = risk_score.min(100);
 
@@ -232,6 +349,8 @@ impl RiskAnalyzer {
             transfer_tax_percent,
             can_sell,
             concentration_percent,
+            recommended_slippage_bps: recommended_slippage_bps(liquidity_sol, concentration_percent, transfer_tax_percent),
+            deployer,
         })
     }
 
@@ -251,32 +370,53 @@ impl RiskAnalyzer {
 
     /// Calculates liquidity in SOL for a token using multiple methods:
     /// 1. Birdeye data (if available)
-    /// 2. Direct DEX liquidity assessment via primary pair info (Placeholder/Not Implemented)
+    /// 2. On-chain bonding curve / pool reserves (if the fallback is enabled)
     /// Returns estimated SOL liquidity value, or 0.0 if unable to calculate
     async fn check_liquidity(
         &self,
+        token_pubkey: &Pubkey,
         overview_data: Option<&TokenOverviewData>,
         sol_price_usd: Option<f64>,
-    ) -> Result<f64> {
+    ) -> Result<Option<f64>> {
         debug!("Calculating SOL liquidity");
 
         // Method 1: Try to use the Birdeye data if available for quick calculation
-        if let (Some(overview), Some(sol_price)) = (overview_data, sol_price_usd) {
-            let usd_liquidity = overview.liquidity.unwrap_or(0.0);
-            if usd_liquidity > 0.0 && sol_price > 0.0 {
-                let calculated_liquidity_sol = usd_liquidity / sol_price;
-                debug!(
-                    "Used Birdeye data for liquidity calculation: {:.2} SOL (USD Liq: {:.2}, SOL Price: {:.2})",
-                    calculated_liquidity_sol, usd_liquidity, sol_price
-                );
-                return Ok(calculated_liquidity_sol);
+        if let Some(calculated_liquidity_sol) = birdeye_liquidity_sol(overview_data, sol_price_usd) {
+            debug!("Used Birdeye data for liquidity calculation: {:.2} SOL", calculated_liquidity_sol);
+            return Ok(Some(calculated_liquidity_sol));
+        }
+        debug!("Birdeye data insufficient for liquidity calculation, falling back.");
+
+        // Method 2: Read the reserves from the chain
+        if let Some(liquidity_sol) = self.onchain_liquidity_sol(token_pubkey).await {
+            return Ok(Some(liquidity_sol));
+        }
+
+        warn!("Could not calculate liquidity for {} from Birdeye or on-chain data.", token_pubkey);
+        Ok(None) // No source could price it
+    }
+
+    /// Liquidity from on-chain reserves, if the fallback is enabled and a pool was found
+    async fn onchain_liquidity_sol(&self, token_pubkey: &Pubkey) -> Option<f64> {
+        let pool_liquidity = self.pool_liquidity.as_ref()?;
+        match pool_liquidity.liquidity_sol(token_pubkey).await {
+            Ok(liquidity_sol) => liquidity_sol,
+            Err(e) => {
+                warn!("On-chain liquidity lookup failed for {}: {:?}", token_pubkey, e);
+                None
             }
-            debug!("Birdeye data insufficient for liquidity calculation, falling back.");
         }
+    }
 
-        // Fallback or alternative method if needed (e.g., using find_primary_pair_info if implemented)
-        warn!("Could not calculate liquidity from Birdeye data. Returning 0.");
-        Ok(0.0) // Return 0 if Birdeye data is insufficient/unavailable
+    /// Current liquidity in SOL for a held token, or None if neither Birdeye nor the
+    /// chain has data for it (so callers can tell "unknown" apart from "liquidity has collapsed").
+    pub async fn current_liquidity_sol(&self, token_address: &str) -> Result<Option<f64>> {
+        let token_pubkey = Pubkey::from_str(token_address).context("Invalid token address")?;
+        let Some(overview) = self.price_cache.token_overview(token_address).await? else {
+            return Ok(self.onchain_liquidity_sol(&token_pubkey).await);
+        };
+        let sol_price_usd = self.birdeye_client.get_sol_price_usd().await?;
+        self.check_liquidity(&token_pubkey, Some(&overview), Some(sol_price_usd)).await
     }
 
     // Removed PrimaryPairInfo struct as find_primary_pair_info is not implemented here
@@ -692,45 +832,113 @@ impl RiskAnalyzer {
     }
 
     async fn check_transfer_tax(&self, token_address: &Pubkey) -> Result<f64> {
-        debug!("Checking transfer tax for {}", token_address);
-        let mint_account = match self.solana_client.get_rpc().get_account(token_address).await {
-             Ok(account) => account,
-             Err(e) => {
-                 warn!("Failed to get mint account for tax check {}: {:?}", token_address, e);
-                 return Ok(0.0);
-             }
-        };
-        if mint_account.owner == spl_token_2022::id() {
-            debug!("Token {} belongs to Token-2022 program. Checking for transfer fee extension.", token_address);
-            match StateWithExtensions::<Token2022Mint>::unpack(&mint_account.data) {
-                Ok(mint_state) => {
-                    match mint_state.get_extension::<TransferFeeConfig>() {
-                        Ok(transfer_fee_config) => {
-                            let fee_basis_points_pod = transfer_fee_config.get_epoch_fee(0).transfer_fee_basis_points;
-                            let fee_basis_points: u16 = fee_basis_points_pod.into();
-                            let tax_percent = fee_basis_points as f64 / 100.0;
-                            info!("Token {} has Token-2022 transfer tax: {}% ({} basis points)", token_address, tax_percent, fee_basis_points);
-                            Ok(tax_percent)
-                        }
-                        Err(_) => {
-                            debug!("Token {} is Token-2022 but has no TransferFeeConfig extension.", token_address);
-                            Ok(0.0)
-                        }
+        fetch_transfer_tax_percent(&self.solana_client, token_address).await
+    }
+}
+
+/// Fetches a mint account and returns its transfer tax percent (0.0 if it can't be determined)
+pub async fn fetch_transfer_tax_percent(solana_client: &SolanaClient, token_address: &Pubkey) -> Result<f64> {
+    debug!("Checking transfer tax for {}", token_address);
+    let mint_account = match solana_client.get_rpc().get_account(token_address).await {
+         Ok(account) => account,
+         Err(e) => {
+             warn!("Failed to get mint account for tax check {}: {:?}", token_address, e);
+             return Ok(0.0);
+         }
+    };
+    Ok(transfer_tax_from_mint_account(token_address, &mint_account.owner, &mint_account.data))
+}
+
+/// Transfer tax percent encoded in a mint account. 0.0 for standard SPL mints and
+/// Token-2022 mints without a `TransferFeeConfig` extension.
+pub fn transfer_tax_from_mint_account(token_address: &Pubkey, owner: &Pubkey, data: &[u8]) -> f64 {
+    if *owner == spl_token_2022::id() {
+        debug!("Token {} belongs to Token-2022 program. Checking for transfer fee extension.", token_address);
+        match StateWithExtensions::<Token2022Mint>::unpack(data) {
+            Ok(mint_state) => {
+                match mint_state.get_extension::<TransferFeeConfig>() {
+                    Ok(transfer_fee_config) => {
+                        let fee_basis_points_pod = transfer_fee_config.get_epoch_fee(0).transfer_fee_basis_points;
+                        let fee_basis_points: u16 = fee_basis_points_pod.into();
+                        let tax_percent = fee_basis_points as f64 / 100.0;
+                        info!("Token {} has Token-2022 transfer tax: {}% ({} basis points)", token_address, tax_percent, fee_basis_points);
+                        tax_percent
+                    }
+                    Err(_) => {
+                        debug!("Token {} is Token-2022 but has no TransferFeeConfig extension.", token_address);
+                        0.0
                     }
-                }
-                Err(e) => {
-                    warn!("Failed to unpack Token-2022 mint extensions for {}: {:?}. Assuming no tax.", token_address, e);
-                    Ok(0.0)
                 }
             }
-        } else if mint_account.owner == spl_token::id() {
-             debug!("Token {} belongs to standard SPL Token program. Assuming no transfer tax.", token_address);
-             Ok(0.0)
-        } else {
-             warn!("Token {} has an unknown owner program: {}. Cannot determine transfer tax.", token_address, mint_account.owner);
-             Ok(0.0)
+            Err(e) => {
+                warn!("Failed to unpack Token-2022 mint extensions for {}: {:?}. Assuming no tax.", token_address, e);
+                0.0
+            }
         }
+    } else if *owner == spl_token::id() {
+         debug!("Token {} belongs to standard SPL Token program. Assuming no transfer tax.", token_address);
+         0.0
+    } else {
+         warn!("Token {} has an unknown owner program: {}. Cannot determine transfer tax.", token_address, owner);
+         0.0
+    }
+}
+
+/// Amount left after a transfer tax of `tax_percent` is withheld
+pub fn net_of_transfer_tax(amount: f64, tax_percent: f64) -> f64 {
+    amount * (1.0 - tax_percent.clamp(0.0, 100.0) / 100.0)
+}
+
+/// Price gain (percent) needed to break even after paying the transfer tax on both
+/// the buy and the sell
+pub fn break_even_gain_percent(tax_percent: f64) -> f64 {
+    let kept = net_of_transfer_tax(net_of_transfer_tax(1.0, tax_percent), tax_percent);
+    if kept <= 0.0 {
+        f64::INFINITY
+    } else {
+        (1.0 / kept - 1.0) * 100.0
+    }
+}
+
+/// Largest buy that keeps the position within `max_fraction` of the pool's SOL
+/// liquidity. Zero (unknown) liquidity leaves the size unchanged.
+pub fn liquidity_capped_size(size_sol: f64, liquidity_sol: f64, max_fraction: f64) -> f64 {
+    if liquidity_sol <= 0.0 || max_fraction <= 0.0 {
+        return size_sol;
+    }
+    size_sol.min(liquidity_sol * max_fraction)
+}
+
+/// Share of `sol_in` lost buying a token and selling it straight back, in percent.
+/// `sol_back` is the sell quote for the bought amount; Jupiter quotes ignore a
+/// Token-2022 transfer fee, which is withheld on the way in and again on the way out.
+pub fn round_trip_loss_percent(sol_in: f64, sol_back: f64, transfer_tax_percent: f64) -> f64 {
+    if sol_in <= 0.0 {
+        return 0.0;
     }
+    let net_back = net_of_transfer_tax(net_of_transfer_tax(sol_back, transfer_tax_percent), transfer_tax_percent);
+    ((1.0 - net_back / sol_in) * 100.0).max(0.0)
+}
+
+/// Sell slippage (bps) sized to a token's pool: thin pools move further per sell,
+/// concentrated supply means other large holders may be selling into the same pool,
+/// and a transfer tax comes straight off the output, so each widens the tolerance.
+/// Unknown (zero) liquidity is treated as thin.
+pub fn recommended_slippage_bps(liquidity_sol: f64, concentration_percent: f64, transfer_tax_percent: f64) -> u32 {
+    let base = if liquidity_sol >= 500.0 {
+        50.0
+    } else if liquidity_sol >= 100.0 {
+        150.0
+    } else if liquidity_sol >= 30.0 {
+        400.0
+    } else if liquidity_sol >= 10.0 {
+        800.0
+    } else {
+        1500.0
+    };
+    let concentration = (concentration_percent - 30.0).max(0.0) * 10.0;
+    let tax = transfer_tax_percent.max(0.0) * 100.0;
+    (base + concentration + tax).round().min(5000.0) as u32
 }
 
 /* 
@@ -823,3 +1031,104 @@ impl RiskAnalyzer {
  *    
  *    Then run with: `cargo run -- --test-risk`
  */
+
+/// Liquidity in SOL from a Birdeye overview, or None if it has no USD liquidity
+/// figure (or SOL has no price) to convert
+fn birdeye_liquidity_sol(overview: Option<&TokenOverviewData>, sol_price_usd: Option<f64>) -> Option<f64> {
+    let usd_liquidity = overview?.liquidity?;
+    let sol_price = sol_price_usd?;
+    (usd_liquidity > 0.0 && sol_price > 0.0).then(|| usd_liquidity / sol_price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_token_2022::{
+        extension::{transfer_fee::TransferFee, ExtensionType, StateWithExtensionsMut},
+        pod::{PodU16, PodU64},
+    };
+
+    /// Builds raw Token-2022 mint account data with a TransferFeeConfig of `basis_points`
+    fn taxed_mint_data(basis_points: u16) -> Vec<u8> {
+        let len = ExtensionType::get_account_len::<Token2022Mint>(&[ExtensionType::TransferFeeConfig]);
+        let mut data = vec![0u8; len];
+        let mut state = StateWithExtensionsMut::<Token2022Mint>::unpack_uninitialized(&mut data).unwrap();
+        let fee = TransferFee {
+            epoch: PodU64::from(0),
+            maximum_fee: PodU64::from(u64::MAX),
+            transfer_fee_basis_points: PodU16::from(basis_points),
+        };
+        let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        config.older_transfer_fee = fee;
+        config.newer_transfer_fee = fee;
+        state.base = Token2022Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    #[test]
+    fn detects_token_2022_transfer_tax() {
+        let mint = Pubkey::new_unique();
+        let data = taxed_mint_data(1000); // 10%
+        let tax = transfer_tax_from_mint_account(&mint, &spl_token_2022::id(), &data);
+        assert!((tax - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn standard_spl_mint_has_no_tax() {
+        let mint = Pubkey::new_unique();
+        let data = taxed_mint_data(1000);
+        assert_eq!(transfer_tax_from_mint_account(&mint, &spl_token::id(), &data), 0.0);
+    }
+
+    #[test]
+    fn taxed_exit_value_and_break_even() {
+        assert!((net_of_transfer_tax(2.0, 10.0) - 1.8).abs() < 1e-9);
+        assert_eq!(net_of_transfer_tax(2.0, 0.0), 2.0);
+        // 10% in and 10% out keeps 81%, so price must rise ~23.46% to break even
+        assert!((break_even_gain_percent(10.0) - 23.456790).abs() < 1e-4);
+        assert!(break_even_gain_percent(100.0).is_infinite());
+    }
+
+    #[test]
+    fn position_capped_to_liquidity_fraction() {
+        assert!((liquidity_capped_size(1.0, 2.0, 0.1) - 0.2).abs() < 1e-12);
+        assert_eq!(liquidity_capped_size(0.05, 100.0, 0.1), 0.05);
+        assert_eq!(liquidity_capped_size(1.0, 0.0, 0.1), 1.0);
+    }
+
+    #[test]
+    fn round_trip_loss_includes_tax_both_ways() {
+        assert!((round_trip_loss_percent(1.0, 0.97, 0.0) - 3.0).abs() < 1e-9);
+        // 10% withheld twice on top of the quoted 0.97 back
+        assert!((round_trip_loss_percent(1.0, 0.97, 10.0) - 21.43).abs() < 1e-9);
+        assert_eq!(round_trip_loss_percent(1.0, 1.2, 0.0), 0.0);
+        assert_eq!(round_trip_loss_percent(0.0, 0.5, 0.0), 0.0);
+    }
+
+    #[test]
+    fn slippage_widens_for_thin_concentrated_taxed_pools() {
+        assert_eq!(recommended_slippage_bps(1000.0, 10.0, 0.0), 50);
+        assert_eq!(recommended_slippage_bps(50.0, 10.0, 0.0), 400);
+        // 40% concentration adds 100 bps, a 5% tax adds 500
+        assert_eq!(recommended_slippage_bps(50.0, 40.0, 5.0), 1000);
+        assert_eq!(recommended_slippage_bps(0.0, 100.0, 50.0), 5000);
+    }
+
+    #[test]
+    fn overview_without_liquidity_is_unpriced_not_zero() {
+        let overview = |liquidity: Option<f64>| -> TokenOverviewData {
+            serde_json::from_value(serde_json::json!({ "address": "mint", "price": 0.01, "liquidity": liquidity })).unwrap()
+        };
+        assert_eq!(birdeye_liquidity_sol(Some(&overview(None)), Some(150.0)), None);
+        assert_eq!(birdeye_liquidity_sol(Some(&overview(Some(0.0))), Some(150.0)), None);
+        assert_eq!(birdeye_liquidity_sol(Some(&overview(Some(3000.0))), None), None);
+        assert_eq!(birdeye_liquidity_sol(None, Some(150.0)), None);
+        assert_eq!(birdeye_liquidity_sol(Some(&overview(Some(3000.0))), Some(150.0)), Some(20.0));
+    }
+}
@@ -0,0 +1,222 @@
+//! Rug detection for tokens we hold
+//!
+//! Stop-losses react to price, which on a rug is too late: once the LP is pulled
+//! or the deployer dumps, there is nothing left to sell into. Each held token is
+//! snapshotted on its first check (pool liquidity, supply, and the balances of the
+//! wallets holding a large share of supply) and later snapshots are compared with
+//! that baseline. Program-owned accounts (pool vaults, the bonding curve, lockers)
+//! are not tracked as holders: their balances move with ordinary trading.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::config::Config;
+use crate::solana::client::SolanaClient;
+use crate::trading::risk::RiskAnalyzer;
+
+// Offsets shared by SPL Token and Token-2022 base layouts
+const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+const MINT_SUPPLY_OFFSET: usize = 36;
+
+/// How far a token has to move from its baseline to count as a rug
+#[derive(Debug, Clone, Copy)]
+pub struct RugThresholds {
+    pub liquidity_drop_percent: f64,
+    pub holder_dump_percent: f64,
+    pub whale_min_percent: f64,
+}
+
+impl RugThresholds {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            liquidity_drop_percent: config.rug_liquidity_drop_percent,
+            holder_dump_percent: config.rug_holder_dump_percent,
+            whale_min_percent: config.rug_whale_min_percent,
+        }
+    }
+}
+
+/// What a token looked like at one check. None fields couldn't be read and are
+/// not judged.
+#[derive(Debug, Clone, Default)]
+pub struct RugSnapshot {
+    pub liquidity_sol: Option<f64>,
+    pub supply: Option<u64>,
+    pub whales: HashMap<Pubkey, u64>, // Token account -> raw balance
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RugSignal {
+    LiquidityPulled { from_sol: f64, to_sol: f64 },
+    HolderDump { account: Pubkey, from: u64, to: u64 },
+    SupplyMinted { from: u64, to: u64 },
+}
+
+impl fmt::Display for RugSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LiquidityPulled { from_sol, to_sol } => write!(f, "liquidity pulled ({:.2} -> {:.2} SOL)", from_sol, to_sol),
+            Self::HolderDump { account, from, to } => write!(f, "large holder {} dumped ({} -> {} raw)", account, from, to),
+            Self::SupplyMinted { from, to } => write!(f, "supply minted ({} -> {} raw)", from, to),
+        }
+    }
+}
+
+/// The first way `current` has rugged relative to `baseline`, if any
+pub fn detect(baseline: &RugSnapshot, current: &RugSnapshot, thresholds: &RugThresholds) -> Option<RugSignal> {
+    if let (Some(from), Some(to)) = (baseline.supply, current.supply) {
+        if to > from {
+            return Some(RugSignal::SupplyMinted { from, to });
+        }
+    }
+    if let (Some(from_sol), Some(to_sol)) = (baseline.liquidity_sol, current.liquidity_sol) {
+        if from_sol > 0.0 && (from_sol - to_sol) / from_sol * 100.0 >= thresholds.liquidity_drop_percent {
+            return Some(RugSignal::LiquidityPulled { from_sol, to_sol });
+        }
+    }
+    baseline.whales.iter().find_map(|(account, &from)| {
+        let to = *current.whales.get(account)?;
+        let sold_percent = from.saturating_sub(to) as f64 / from as f64 * 100.0;
+        (from > 0 && sold_percent >= thresholds.holder_dump_percent).then_some(RugSignal::HolderDump { account: *account, from, to })
+    })
+}
+
+/// Confirms a signal from `detect`. A liquidity pull only counts once two checks in
+/// a row see it (`pull_pending` carries the first sighting between checks): one bad
+/// liquidity read would otherwise dump the position at emergency slippage. The
+/// other signals are read straight from the chain and fire at once.
+pub fn confirm(signal: Option<RugSignal>, pull_pending: &mut bool) -> Option<RugSignal> {
+    let pulled = matches!(signal, Some(RugSignal::LiquidityPulled { .. }));
+    let first_sighting = pulled && !*pull_pending;
+    *pull_pending = pulled;
+    if first_sighting {
+        None
+    } else {
+        signal
+    }
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8).map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap_or_default()))
+}
+
+/// Snapshot `token_address`. With a `baseline`, the whales it found are re-read
+/// (a closed account counts as empty); without one, wallets other than
+/// `own_wallet` holding at least `whale_min_percent` of supply are picked.
+pub async fn snapshot(
+    solana_client: &SolanaClient,
+    risk_analyzer: &RiskAnalyzer,
+    token_address: &str,
+    own_wallet: &Pubkey,
+    whale_min_percent: f64,
+    baseline: Option<&RugSnapshot>,
+) -> Result<RugSnapshot> {
+    let mint = Pubkey::from_str(token_address).context("Invalid token address")?;
+    let rpc = solana_client.get_rpc();
+    let supply = rpc.get_account(&mint).await.ok().and_then(|account| read_u64(&account.data, MINT_SUPPLY_OFFSET));
+    let liquidity_sol = risk_analyzer.current_liquidity_sol(token_address).await.ok().flatten();
+
+    let whales = match baseline {
+        Some(baseline) => {
+            let accounts: Vec<Pubkey> = baseline.whales.keys().copied().collect();
+            let balances = rpc.get_multiple_accounts(&accounts).await.context("Failed to read tracked holders")?;
+            accounts.into_iter().zip(balances)
+                .map(|(address, account)| {
+                    let amount = account.and_then(|a| read_u64(&a.data, TOKEN_ACCOUNT_AMOUNT_OFFSET)).unwrap_or(0);
+                    (address, amount)
+                })
+                .collect()
+        }
+        None => find_whales(solana_client, &mint, own_wallet, supply, whale_min_percent).await?,
+    };
+    Ok(RugSnapshot { liquidity_sol, supply, whales })
+}
+
+/// Wallet-owned token accounts among the largest holders with at least
+/// `whale_min_percent` of supply
+async fn find_whales(
+    solana_client: &SolanaClient,
+    mint: &Pubkey,
+    own_wallet: &Pubkey,
+    supply: Option<u64>,
+    whale_min_percent: f64,
+) -> Result<HashMap<Pubkey, u64>> {
+    let Some(supply) = supply.filter(|s| *s > 0) else {
+        return Ok(HashMap::new());
+    };
+    let min_amount = (supply as f64 * whale_min_percent / 100.0) as u64;
+    let largest: Vec<(Pubkey, u64)> = solana_client.get_token_largest_accounts(mint).await?
+        .iter()
+        .filter_map(|a| Some((Pubkey::from_str(&a.address).ok()?, a.amount.amount.parse::<u64>().ok()?)))
+        .filter(|(_, amount)| *amount >= min_amount.max(1))
+        .collect();
+    if largest.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let addresses: Vec<Pubkey> = largest.iter().map(|(address, _)| *address).collect();
+    let accounts = solana_client.get_rpc().get_multiple_accounts(&addresses).await
+        .context("Failed to read largest holder accounts")?;
+    Ok(largest.into_iter().zip(accounts)
+        .filter_map(|((address, amount), account)| {
+            let owner = Pubkey::try_from(account?.data.get(TOKEN_ACCOUNT_OWNER_OFFSET..TOKEN_ACCOUNT_OWNER_OFFSET + 32)?).ok()?;
+            // PDAs are program vaults; a wallet's key is always on the curve
+            (owner.is_on_curve() && owner != *own_wallet).then_some((address, amount))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thresholds() -> RugThresholds {
+        RugThresholds { liquidity_drop_percent: 80.0, holder_dump_percent: 50.0, whale_min_percent: 5.0 }
+    }
+
+    #[test]
+    fn detects_each_rug_signal() {
+        let whale = Pubkey::new_unique();
+        let baseline = RugSnapshot {
+            liquidity_sol: Some(100.0),
+            supply: Some(1_000),
+            whales: HashMap::from([(whale, 200)]),
+        };
+        let mut current = baseline.clone();
+        assert_eq!(detect(&baseline, &current, &thresholds()), None);
+
+        current.liquidity_sol = Some(30.0); // 70% drop: a price move, not a pull
+        assert_eq!(detect(&baseline, &current, &thresholds()), None);
+        current.liquidity_sol = Some(10.0);
+        assert_eq!(detect(&baseline, &current, &thresholds()), Some(RugSignal::LiquidityPulled { from_sol: 100.0, to_sol: 10.0 }));
+
+        current.liquidity_sol = None; // Unknown liquidity isn't judged
+        current.whales.insert(whale, 90);
+        assert_eq!(detect(&baseline, &current, &thresholds()), Some(RugSignal::HolderDump { account: whale, from: 200, to: 90 }));
+
+        current.supply = Some(2_000);
+        assert_eq!(detect(&baseline, &current, &thresholds()), Some(RugSignal::SupplyMinted { from: 1_000, to: 2_000 }));
+    }
+
+    #[test]
+    fn liquidity_pull_needs_two_checks_in_a_row() {
+        let pulled = Some(RugSignal::LiquidityPulled { from_sol: 100.0, to_sol: 0.0 });
+        let mut pending = false;
+        assert_eq!(confirm(pulled.clone(), &mut pending), None);
+        assert_eq!(confirm(pulled.clone(), &mut pending), pulled);
+
+        // A recovered reading in between starts over
+        let mut pending = false;
+        assert_eq!(confirm(pulled.clone(), &mut pending), None);
+        assert_eq!(confirm(None, &mut pending), None);
+        assert_eq!(confirm(pulled.clone(), &mut pending), None);
+
+        let minted = Some(RugSignal::SupplyMinted { from: 1, to: 2 });
+        assert_eq!(confirm(minted.clone(), &mut false), minted);
+    }
+}
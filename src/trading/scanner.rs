@@ -176,7 +176,8 @@ impl Scanner {
                 debug!("NewPairs strategy uses WebSocket discovery, not scanner");
                 Ok(vec![])
             }
-            StrategyType::FinalStretch => {
+            // Both look for bonding tokens; the curve strategy just buys them differently
+            StrategyType::FinalStretch | StrategyType::PumpfunBondingCurve => {
                 self.scan_final_stretch(strategy).await
             }
             StrategyType::Migrated => {
@@ -198,7 +199,7 @@ impl Scanner {
         let min_market_cap = strategy.min_market_cap_usd.unwrap_or(20_000.0);
         let min_holders = strategy.min_holders as u64;
         let min_volume = strategy.min_volume_usd.unwrap_or(20_000.0);
-        let max_age_minutes = strategy.max_token_age_minutes as u64; // IMPORTANT: Filter by age!
+        let max_age_minutes = strategy.max_token_age_secs().div_ceil(60); // IMPORTANT: Filter by age!
 
         // Use Moralis client to scan and filter (now includes age filter)
         let candidates = self.moralis_client
@@ -266,7 +267,7 @@ impl Scanner {
                     holders: candidate.holders,
                     bonding_progress: candidate.token.bonding_progress(),
                     graduated_at: None,
-                    strategy_type: StrategyType::FinalStretch,
+                    strategy_type: strategy.strategy_type.clone(),
                 });
 
                 info!("✅ [CANDIDATE] {} ({}) - Progress: {:.1}%, MCap: ${:.0}, Vol: ${:.0}, Holders: {}",
@@ -293,7 +294,7 @@ impl Scanner {
         // Get filter criteria from strategy
         let min_market_cap = strategy.min_market_cap_usd.unwrap_or(40_000.0);
         let min_holders = strategy.min_holders as u64;
-        let max_age_hours = (strategy.max_token_age_minutes / 60) as u64; // Convert minutes to hours
+        let max_age_hours = strategy.max_token_age_secs() / 3600; // Convert seconds to hours
         let min_volume = strategy.min_volume_usd.unwrap_or(40_000.0);
 
         // Use Moralis client to scan and filter
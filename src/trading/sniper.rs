@@ -153,8 +153,9 @@ impl Sniper {
     async fn execute_snipe(self: Arc<Self>, signal: CallSignal) -> Result<()> {
         let mint = &signal.mint;
         let amount_sol = self.config.snipe_amount_sol;
-        let slippage_bps = self.config.snipe_slippage_bps;
-        let priority_fee = Some(self.config.snipe_priority_fee_micro_lamports);
+        let slippage_bps = self.config.live().snipe_slippage_bps;
+        let priority_fee = Some(self.config.live().snipe_priority_fee_micro_lamports);
+        let route = self.strategy.swap_route(self.config.jito_tip_lamports);
         let symbol_for_log = signal.ticker.as_deref().unwrap_or("?");
 
         // DRY-RUN SIMULATION: fetch real read-only Jupiter quotes + market cap
@@ -248,12 +249,19 @@ impl Sniper {
             return Ok(());
         }
 
+        self.position_manager
+            .check_token_allocation(mint, amount_sol)
+            .await
+            .context("Snipe rejected")?;
+
         info!(
             "🚨 SNIPE FIRING: trigger={} ticker={} mint={} amount={} SOL slippage={}bps",
             signal.trigger, symbol_for_log, mint, amount_sol, slippage_bps
         );
 
         // --- BUY ---
+        // Held until the moonbag is recorded, so the dust sweep leaves the tokens alone
+        let _buy_in_flight = self.position_manager.begin_buy(mint);
         let buy_start = std::time::Instant::now();
         let buy_result = self
             .jupiter
@@ -263,6 +271,7 @@ impl Sniper {
                 amount_sol,
                 slippage_bps,
                 priority_fee,
+                route,
                 self.wallet.clone(),
             )
             .await
@@ -307,6 +316,7 @@ impl Sniper {
                 dump_amount,
                 slippage_bps,
                 priority_fee,
+                route,
                 self.wallet.clone(),
             )
             .await;
@@ -342,6 +352,7 @@ impl Sniper {
             (tokens_acquired, amount_sol)
         };
 
+        // Tracked even when tiny so SL/TP still apply; listings hide dust positions
         if final_token_amount > 0.0 {
             if let Err(e) = self
                 .position_manager
@@ -359,7 +370,17 @@ impl Sniper {
                     self.strategy.stop_loss_percent,
                     self.strategy.take_profit_percent,
                     self.strategy.trailing_stop_percent,
+                    self.strategy.peak_drawdown_alert_percent,
+                    self.strategy.peak_drawdown_sell_percent,
                     Some(self.strategy.max_hold_time_minutes),
+                    self.strategy.force_close_at,
+                    self.strategy.momentum_tp,
+                    self.strategy.stop_loss_type.volatility(),
+                    None, // The moonbag is what's left after the dump, not a fresh entry
+                    Some(&self.wallet.get_public_key().to_string()),
+                    route,
+                    None,
+                    self.strategy.notify_trades,
                 )
                 .await
             {
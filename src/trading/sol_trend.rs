@@ -0,0 +1,65 @@
+//! SOL market trend filter
+//!
+//! Launches perform badly while SOL itself is dumping. When enabled, new buys are
+//! paused while SOL's price change over the lookback window is below
+//! `-sol_downtrend_pause_percent`, and resume automatically once it recovers.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::api::birdeye::BirdeyeClient;
+use crate::api::jupiter::SOL_MINT;
+use crate::config::Config;
+
+pub struct SolTrendFilter {
+    birdeye_client: Arc<BirdeyeClient>,
+    config: Arc<Config>,
+    /// Whether buys are currently paused (tracked to log pause/resume transitions once)
+    paused: AtomicBool,
+}
+
+impl SolTrendFilter {
+    pub fn new(birdeye_client: Arc<BirdeyeClient>, config: Arc<Config>) -> Self {
+        Self {
+            birdeye_client,
+            config,
+            paused: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether new buys are allowed given the current SOL trend.
+    /// Fails open: if the trend can't be fetched, buying continues.
+    pub async fn allows_buys(&self) -> bool {
+        let Some(max_drop) = self.config.sol_downtrend_pause_percent else {
+            return true;
+        };
+        let lookback = self.config.sol_trend_lookback_minutes;
+
+        let change = match self.birdeye_client.get_price_change_percent(SOL_MINT, lookback).await {
+            Ok(Some(change)) => change,
+            Ok(None) => {
+                debug!("SOL trend unavailable; not pausing buys");
+                return true;
+            }
+            Err(e) => {
+                warn!("Failed to fetch SOL trend ({}); not pausing buys", e);
+                return true;
+            }
+        };
+
+        let downtrend = change <= -max_drop;
+        let was_paused = self.paused.swap(downtrend, Ordering::Relaxed);
+        if downtrend && !was_paused {
+            warn!(
+                "📉 SOL down {:.2}% over {}m (limit -{:.2}%) - pausing new buys",
+                change.abs(), lookback, max_drop
+            );
+        } else if !downtrend && was_paused {
+            info!("📈 SOL trend recovered ({:+.2}% over {}m) - resuming buys", change, lookback);
+        } else if downtrend {
+            debug!("SOL still down {:.2}% over {}m - buys paused", change.abs(), lookback);
+        }
+        !downtrend
+    }
+}
@@ -0,0 +1,114 @@
+//! Summary of the state the bot came up in, sent once after startup
+//!
+//! After a restart operators want to see that recovery went as expected: how many
+//! positions were reloaded and what they are worth, whether trading auto-resumed,
+//! which strategies are live, the wallet balance and the trading mode. Coming up in
+//! REAL mode is called out explicitly.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::solana::wallet_pool::WalletPool;
+use crate::trading::autotrader::AutoTrader;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupDigest {
+    pub mode: String,                     // "REAL", "DRY RUN" or "DEMO"
+    pub open_positions: usize,
+    pub open_value_sol: f64,              // At the last known price of each position
+    pub trading_running: bool,
+    pub enabled_strategies: Vec<String>,
+    pub wallet_balance_sol: Option<f64>,  // None if the RPC balance check failed
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Trading mode label; dry run takes precedence over demo as it scans real tokens
+pub fn trading_mode(config: &Config) -> &'static str {
+    if config.dry_run_mode {
+        "DRY RUN"
+    } else if config.demo_mode {
+        "DEMO"
+    } else {
+        "REAL"
+    }
+}
+
+impl StartupDigest {
+    pub async fn collect(auto_trader: &AutoTrader, wallet_pool: &WalletPool, config: &Config) -> Self {
+        let positions = auto_trader.position_manager.get_active_positions().await;
+        let mut enabled_strategies: Vec<String> = auto_trader.list_strategies().await
+            .into_iter()
+            .filter(|s| s.enabled)
+            .map(|s| s.name)
+            .collect();
+        enabled_strategies.sort();
+
+        Self {
+            mode: trading_mode(config).to_string(),
+            open_positions: positions.len(),
+            open_value_sol: positions.iter().map(|p| p.current_price_sol * p.entry_token_amount).sum(),
+            trading_running: auto_trader.get_status().await,
+            enabled_strategies,
+            wallet_balance_sol: wallet_pool.total_sol_balance().await.ok(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn is_real_mode(&self) -> bool {
+        self.mode == "REAL"
+    }
+
+    /// Multi-line text for notification relays
+    pub fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        if self.is_real_mode() {
+            lines.push("⚠️ Started in REAL mode - trades use real funds".to_string());
+        }
+        lines.push(format!("🚀 TraderTony started ({} mode)", self.mode));
+        lines.push(format!(
+            "Trading: {}",
+            if self.trading_running { "auto-resumed" } else { "stopped (start it from the dashboard)" }
+        ));
+        lines.push(format!(
+            "Open positions: {} worth {:.4} SOL",
+            self.open_positions, self.open_value_sol
+        ));
+        lines.push(match self.wallet_balance_sol {
+            Some(balance) => format!("Wallet balance: {:.4} SOL", balance),
+            None => "Wallet balance: unavailable".to_string(),
+        });
+        lines.push(if self.enabled_strategies.is_empty() {
+            "Strategies: none enabled".to_string()
+        } else {
+            format!("Strategies: {}", self.enabled_strategies.join(", "))
+        });
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_warns_in_real_mode() {
+        let digest = StartupDigest {
+            mode: "REAL".to_string(),
+            open_positions: 5,
+            open_value_sol: 2.3,
+            trading_running: true,
+            enabled_strategies: vec!["Migrated Scout".to_string()],
+            wallet_balance_sol: Some(1.5),
+            timestamp: Utc::now(),
+        };
+        let text = digest.summary();
+        assert!(text.starts_with("⚠️ Started in REAL mode"));
+        assert!(text.contains("Open positions: 5 worth 2.3000 SOL"));
+        assert!(text.contains("auto-resumed"));
+
+        let demo = StartupDigest { mode: "DEMO".to_string(), wallet_balance_sol: None, ..digest };
+        assert!(!demo.summary().contains("REAL"));
+        assert!(demo.summary().contains("Wallet balance: unavailable"));
+    }
+}
@@ -1,9 +1,307 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::api::jupiter::SwapRoute;
+use crate::solana::fees::FeeUrgency;
+
 fn default_min_buy_ratio() -> f64 { 0.0 }
 
+/// Wait between entry retries when a strategy doesn't set its own
+pub const DEFAULT_ENTRY_RETRY_DELAY_MS: u64 = 1000;
+fn default_entry_retry_delay_ms() -> u64 { DEFAULT_ENTRY_RETRY_DELAY_MS }
+
+/// First occurrence of the UTC clock time `at` strictly after `entry`.
+/// A position opened after today's scheduled close is closed at the same time tomorrow.
+pub fn next_force_close(entry: DateTime<Utc>, at: NaiveTime) -> DateTime<Utc> {
+    let today = entry.date_naive().and_time(at).and_utc();
+    if today > entry {
+        today
+    } else {
+        today + Duration::days(1)
+    }
+}
+
+/// Momentum-aware take-profit: the target is widened above the strategy's
+/// `take_profit_percent` while recent price velocity is high, and falls back
+/// toward it as momentum fades. The stop-loss is never moved.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct MomentumTpSettings {
+    pub max_extra_percent: u32,             // Most the TP can be widened, in percentage points
+    pub full_velocity_percent_per_min: f64, // Price velocity at which the full widening applies
+    pub lookback_minutes: u32,              // Window the velocity is measured over
+}
+
+impl MomentumTpSettings {
+    /// Effective TP percent for a base TP and a price velocity (% per minute)
+    pub fn take_profit_percent(&self, base_percent: f64, velocity_percent_per_min: f64) -> f64 {
+        if self.full_velocity_percent_per_min <= 0.0 {
+            return base_percent;
+        }
+        let strength = (velocity_percent_per_min / self.full_velocity_percent_per_min).clamp(0.0, 1.0);
+        base_percent + strength * self.max_extra_percent as f64
+    }
+}
+
+/// How a strategy sizes its stop-loss distance below entry
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StopLossType {
+    /// `stop_loss_percent` below entry, for every token alike
+    #[default]
+    FixedPercent,
+    /// A multiple of the token's recent price volatility: volatile tokens get wider
+    /// stops, calm ones tighter
+    Volatility(VolatilityStopSettings),
+}
+
+impl StopLossType {
+    pub fn volatility(&self) -> Option<VolatilityStopSettings> {
+        match self {
+            StopLossType::FixedPercent => None,
+            StopLossType::Volatility(settings) => Some(*settings),
+        }
+    }
+}
+
+/// Volatility-sized stop-loss. Until the position's price samples span half the
+/// window the stop sits at `stop_loss_percent` (or `max_percent` without one); it is
+/// then set to `multiplier` x the price's standard deviation over the window, as a
+/// percent of its mean, clamped to `min_percent..=max_percent`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct VolatilityStopSettings {
+    pub window_minutes: u32,                // Window of price samples the volatility is measured over
+    pub multiplier: f64,                    // Stop distance in standard deviations
+    pub min_percent: f64,                   // Tightest the stop can be, in percent below entry
+    pub max_percent: f64,                   // Widest the stop can be, in percent below entry
+    #[serde(default)]
+    pub reevaluate: bool,                   // Keep resizing the stop as volatility changes (false = size it once)
+}
+
+impl VolatilityStopSettings {
+    /// Stop distance below entry, in percent, for a volatility in percent
+    pub fn stop_percent(&self, volatility_percent: f64) -> f64 {
+        (volatility_percent * self.multiplier).clamp(self.min_percent, self.max_percent)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window_minutes == 0 {
+            return Err("Volatility stop window must be at least 1 minute".to_string());
+        }
+        if self.multiplier <= 0.0 {
+            return Err("Volatility stop multiplier must be greater than 0".to_string());
+        }
+        if self.min_percent <= 0.0 || self.max_percent >= 100.0 || self.min_percent > self.max_percent {
+            return Err("Volatility stop bounds must satisfy 0 < min <= max < 100 percent".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Scale-in (DCA) entry: the first buy takes `initial_percent` of
+/// `max_position_size_sol` and each tranche adds its share once its trigger fires.
+/// Triggers are measured against the first buy's price and fill in order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScaleInSettings {
+    pub initial_percent: f64,               // Share of the position bought up front
+    pub tranches: Vec<ScaleInTranche>,      // Follow-up buys, in order
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ScaleInTranche {
+    pub size_percent: f64,                  // Share of max_position_size_sol
+    pub trigger: ScaleInTrigger,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScaleInTrigger {
+    /// Price is still at or above the first buy after this many minutes
+    HoldAboveEntry { after_minutes: u32 },
+    /// Price has broken out this many percent above the first buy
+    Breakout { above_entry_percent: f64 },
+    /// This many minutes after the first buy, whatever the price (DCA)
+    AfterMinutes { after_minutes: u32 },
+}
+
+impl ScaleInTrigger {
+    pub fn is_met(&self, base_price: f64, current_price: f64, minutes_held: i64) -> bool {
+        match *self {
+            ScaleInTrigger::HoldAboveEntry { after_minutes } => {
+                minutes_held >= after_minutes as i64 && current_price >= base_price
+            }
+            ScaleInTrigger::Breakout { above_entry_percent } => {
+                current_price >= base_price * (1.0 + above_entry_percent / 100.0)
+            }
+            ScaleInTrigger::AfterMinutes { after_minutes } => minutes_held >= after_minutes as i64,
+        }
+    }
+}
+
+impl ScaleInSettings {
+    /// SOL spent on the first buy of a `full_size_sol` position
+    pub fn initial_size_sol(&self, full_size_sol: f64) -> f64 {
+        full_size_sol * self.initial_percent / 100.0
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.initial_percent <= 0.0 || self.initial_percent > 100.0 {
+            return Err("Scale-in initial percent must be between 0 and 100".to_string());
+        }
+        if self.tranches.is_empty() {
+            return Err("Scale-in needs at least one tranche after the initial buy".to_string());
+        }
+        if self.tranches.iter().any(|t| t.size_percent <= 0.0) {
+            return Err("Scale-in tranche sizes must be greater than 0".to_string());
+        }
+        let total: f64 = self.initial_percent + self.tranches.iter().map(|t| t.size_percent).sum::<f64>();
+        if total > 100.0 + 1e-9 {
+            return Err(format!("Scale-in sizes add up to {:.1}% of the position, more than 100%", total));
+        }
+        for tranche in &self.tranches {
+            match tranche.trigger {
+                ScaleInTrigger::HoldAboveEntry { after_minutes: 0 } | ScaleInTrigger::AfterMinutes { after_minutes: 0 } => {
+                    return Err("Scale-in hold trigger needs a wait of at least 1 minute".to_string());
+                }
+                ScaleInTrigger::Breakout { above_entry_percent } if above_entry_percent <= 0.0 => {
+                    return Err("Scale-in breakout trigger must be above the entry price".to_string());
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Dollar-cost-averaged entry: the position is bought in `tranches` equal buys,
+/// one every `interval_minutes`, regardless of price. Runs as a scale-in with
+/// time-only triggers, so the position's entry price is the weighted average.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct DcaSettings {
+    pub tranches: u32,
+    pub interval_minutes: u32,
+}
+
+impl DcaSettings {
+    /// The scale-in schedule that carries out this DCA
+    pub fn to_scale_in(&self) -> ScaleInSettings {
+        let size_percent = 100.0 / self.tranches.max(1) as f64;
+        ScaleInSettings {
+            initial_percent: size_percent,
+            tranches: (1..self.tranches)
+                .map(|i| ScaleInTranche {
+                    size_percent,
+                    trigger: ScaleInTrigger::AfterMinutes { after_minutes: i * self.interval_minutes },
+                })
+                .collect(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tranches < 2 {
+            return Err("DCA needs at least 2 tranches".to_string());
+        }
+        if self.interval_minutes == 0 {
+            return Err("DCA interval must be at least 1 minute".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Buy-the-dip entry: instead of buying a matching token at market, place a limit
+/// order `discount_percent` below its current price that lapses after `expiry_minutes`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct LimitEntrySettings {
+    pub discount_percent: f64,
+    pub expiry_minutes: u32,
+}
+
+impl LimitEntrySettings {
+    /// Target price for a token currently at `price_sol`
+    pub fn target_price(&self, price_sol: f64) -> f64 {
+        price_sol * (1.0 - self.discount_percent / 100.0)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.discount_percent <= 0.0 || self.discount_percent >= 100.0 {
+            return Err("Limit entry discount must be between 0 and 100 percent".to_string());
+        }
+        if self.expiry_minutes == 0 {
+            return Err("Limit entry expiry must be at least 1 minute".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Mint and creator-wallet allow/deny lists. A blacklisted mint or creator is always
+/// rejected; a non-empty whitelist admits only its entries (a token whose creator is
+/// unknown fails a creator whitelist).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TokenLists {
+    #[serde(default)]
+    pub mint_blacklist: Vec<String>,
+    #[serde(default)]
+    pub mint_whitelist: Vec<String>,
+    #[serde(default)]
+    pub creator_blacklist: Vec<String>,
+    #[serde(default)]
+    pub creator_whitelist: Vec<String>,
+}
+
+impl TokenLists {
+    /// Why a token fails these lists, or None if it passes
+    pub fn rejection_reason(&self, mint: &str, creator: Option<&str>) -> Option<String> {
+        if self.mint_blacklist.iter().any(|m| m == mint) {
+            return Some("mint is blacklisted".to_string());
+        }
+        if !self.mint_whitelist.is_empty() && !self.mint_whitelist.iter().any(|m| m == mint) {
+            return Some("mint is not whitelisted".to_string());
+        }
+        if let Some(c) = creator.filter(|c| self.creator_blacklist.iter().any(|b| b == c)) {
+            return Some(format!("creator {} is blacklisted", c));
+        }
+        if !self.creator_whitelist.is_empty() {
+            match creator {
+                Some(c) if self.creator_whitelist.iter().any(|w| w == c) => {}
+                Some(c) => return Some(format!("creator {} is not whitelisted", c)),
+                None => return Some("creator unknown and only whitelisted creators are accepted".to_string()),
+            }
+        }
+        None
+    }
+}
+
+/// Which trade alerts a strategy sends. Error and emergency alerts are sent regardless.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeNotify {
+    #[default]
+    Both,
+    Open,
+    Close,
+    None,
+}
+
+impl TradeNotify {
+    pub fn on_open(self) -> bool {
+        matches!(self, TradeNotify::Both | TradeNotify::Open)
+    }
+
+    pub fn on_close(self) -> bool {
+        matches!(self, TradeNotify::Both | TradeNotify::Close)
+    }
+}
+
+/// Where a strategy's swaps are built. Raydium skips Jupiter's routing for tokens
+/// with a Raydium AMM v4 SOL pool; tokens without one still go through Jupiter.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionVenue {
+    #[default]
+    Jupiter,
+    Raydium,
+}
+
 /// Strategy type determines which discovery/evaluation method is used
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "snake_case")]
@@ -22,15 +320,24 @@ pub enum StrategyType {
     /// "Gamboled"/"Gamboling" messages containing a pump.fun mint, dumps
     /// 90% after a short hold.
     TelegramCall,
+    /// Buys pre-graduation pump.fun tokens directly on their bonding curve once
+    /// it passes `min_bonding_progress`. Uses the same scanner as Final Stretch.
+    PumpfunBondingCurve,
 }
 
 impl StrategyType {
+    /// Whether the scanner looks for tokens still on their bonding curve
+    pub fn scans_bonding_tokens(&self) -> bool {
+        matches!(self, StrategyType::FinalStretch | StrategyType::PumpfunBondingCurve)
+    }
+
     pub fn display_name(&self) -> &'static str {
         match self {
             StrategyType::NewPairs => "New Pairs",
             StrategyType::FinalStretch => "Final Stretch",
             StrategyType::Migrated => "Migrated",
             StrategyType::TelegramCall => "Telegram Call",
+            StrategyType::PumpfunBondingCurve => "Pump.fun Bonding Curve",
         }
     }
 
@@ -40,6 +347,7 @@ impl StrategyType {
             StrategyType::FinalStretch => "Tokens on bonding curve with proven traction (20-80% progress)",
             StrategyType::Migrated => "Tokens graduated to PumpSwap/Raydium with established liquidity",
             StrategyType::TelegramCall => "Snipes tokens called out by a monitored Telegram channel",
+            StrategyType::PumpfunBondingCurve => "Buys on the pump.fun bonding curve before migration, past a progress threshold",
         }
     }
 }
@@ -61,15 +369,39 @@ pub struct Strategy {
     
     // Exit Conditions
     pub stop_loss_percent: Option<u32>,      // Stop loss percentage (optional)
+    #[serde(default)]
+    pub stop_loss_type: StopLossType,        // Fixed percent or volatility-sized stop
     pub take_profit_percent: Option<u32>,    // Take profit percentage (optional)
     pub trailing_stop_percent: Option<u32>,  // Trailing stop percentage (optional)
+    #[serde(default)]
+    pub peak_drawdown_alert_percent: Option<f64>, // Warn once price is this far below its high (optional)
+    #[serde(default)]
+    pub peak_drawdown_sell_percent: Option<f64>,  // Share of the holding to sell when that warning fires
     pub max_hold_time_minutes: u32,          // Max time to hold a position before forced exit
+    #[serde(default)]
+    pub force_close_at: Option<NaiveTime>,   // UTC clock time at which all positions are closed (e.g. session end)
+    #[serde(default)]
+    pub momentum_tp: Option<MomentumTpSettings>, // Widen take-profit while price is running (None = fixed TP)
+
+    // Entry Mode
+    #[serde(default)]
+    pub scale_in: Option<ScaleInSettings>,   // Split the entry into tranches (None = single buy)
+    #[serde(default)]
+    pub dca: Option<DcaSettings>,            // Equal buys on a fixed interval (None = single buy)
+    #[serde(default)]
+    pub limit_entry: Option<LimitEntrySettings>, // Wait for a dip below the signal price (None = buy at market)
     
     // Entry Filters (Token Selection Criteria)
     pub min_liquidity_sol: u32,              // Minimum liquidity required in SOL
     pub max_risk_level: u32,                 // Maximum acceptable risk score (0-100) from RiskAnalyzer
     pub min_holders: u32,                    // Minimum number of token holders
     pub max_token_age_minutes: u32,          // Maximum age of token since creation
+    #[serde(default)]
+    pub max_token_age_seconds: Option<u32>,  // Finer age cap; when set, replaces max_token_age_minutes entirely
+    #[serde(default)]
+    pub scan_interval_secs: Option<u32>,     // Scan for new tokens at least this often while enabled (None = global setting)
+    #[serde(default)]
+    pub scan_token_age_minutes: Option<u32>, // Look back at least this far for new tokens (None = global setting)
     // Add more specific risk filters based on RiskAnalysis fields
     pub require_lp_burned: bool,             // Require LP tokens to be burned/locked
     pub reject_if_mint_authority: bool,      // Reject if mint authority exists
@@ -89,10 +421,30 @@ pub struct Strategy {
     pub min_buy_ratio_percent: f64,          // Minimum buy/sell ratio (60.0 = 60% buys, reject if sells dominate)
     #[serde(default)]
     pub min_unique_wallets_24h: Option<u64>, // Minimum unique wallets trading in 24h (filters out wash trading)
+    #[serde(default)]
+    pub token_lists: TokenLists,             // Mints/creators always rejected or exclusively accepted
 
     // Transaction Parameters (Optional overrides for config defaults)
     pub slippage_bps: Option<u32>,           // Slippage basis points for swaps (overrides config)
     pub priority_fee_micro_lamports: Option<u64>, // Priority fee for swaps (overrides config)
+    #[serde(default)]
+    pub fee_urgency: FeeUrgency,             // Estimated priority fee level when no fixed fee is set
+    #[serde(default)]
+    pub use_jito: bool,                      // Send buys and exits as Jito bundles (frontrun protection)
+    #[serde(default)]
+    pub jito_tip_lamports: Option<u64>,      // Bundle tip (overrides config)
+    #[serde(default)]
+    pub execution_venue: ExecutionVenue,     // Build swaps via Jupiter or directly on Raydium
+
+    // Entry Retry (for just-launched tokens whose pool isn't routable yet)
+    #[serde(default)]
+    pub entry_retry_attempts: u32,           // Extra buy attempts after a no-route/simulation failure (0 = none)
+    #[serde(default = "default_entry_retry_delay_ms")]
+    pub entry_retry_delay_ms: u64,           // Wait between entry attempts
+
+    // Notifications
+    #[serde(default)]
+    pub notify_trades: TradeNotify,          // Which trade alerts to send (errors/emergencies always are)
 
     // Metadata
     pub created_at: DateTime<Utc>,           // Strategy creation time
@@ -112,13 +464,25 @@ impl Strategy {
             max_position_size_sol: 0.05, // Default smaller size
             total_budget_sol: 0.2,      // Default smaller budget
             stop_loss_percent: Some(15), // Default 15% SL
+            stop_loss_type: StopLossType::FixedPercent,
             take_profit_percent: Some(50), // Default 50% TP
             trailing_stop_percent: Some(5), // Default 5% Trailing SL
+            peak_drawdown_alert_percent: None,
+            peak_drawdown_sell_percent: None,
             max_hold_time_minutes: 240, // 4 hours
+            force_close_at: None,
+            momentum_tp: None,
+            scale_in: None,
+            dca: None,
+            scan_interval_secs: None,
+            scan_token_age_minutes: None,
+            limit_entry: None,
+            notify_trades: TradeNotify::Both,
             min_liquidity_sol: 10,      // Min 10 SOL liquidity
             max_risk_level: 60,         // Max risk score 60
             min_holders: 50,            // Min 50 holders
             max_token_age_minutes: 120, // Max 2 hours old
+            max_token_age_seconds: None,
             require_lp_burned: true,
             reject_if_mint_authority: true,
             reject_if_freeze_authority: true,
@@ -133,8 +497,15 @@ impl Strategy {
             // Advanced filters (not used for NewPairs)
             min_buy_ratio_percent: 0.0,
             min_unique_wallets_24h: None,
+            token_lists: TokenLists::default(),
             slippage_bps: None, // Use global default
             priority_fee_micro_lamports: None, // Use global default
+            fee_urgency: FeeUrgency::Medium,
+            use_jito: false,
+            jito_tip_lamports: None, // Use JITO_TIP_LAMPORTS
+            execution_venue: ExecutionVenue::Jupiter,
+            entry_retry_attempts: 2, // Pools often aren't routable for a second or two after launch
+            entry_retry_delay_ms: DEFAULT_ENTRY_RETRY_DELAY_MS,
             created_at: now,
             updated_at: now,
         }
@@ -152,13 +523,25 @@ impl Strategy {
             max_position_size_sol: 0.1,
             total_budget_sol: 1.0,
             stop_loss_percent: Some(20),
+            stop_loss_type: StopLossType::FixedPercent,
             take_profit_percent: Some(50),
             trailing_stop_percent: Some(10),
+            peak_drawdown_alert_percent: None,
+            peak_drawdown_sell_percent: None,
             max_hold_time_minutes: 60,
+            force_close_at: None,
+            momentum_tp: None,
+            scale_in: None,
+            dca: None,
+            scan_interval_secs: None,
+            scan_token_age_minutes: None,
+            limit_entry: None,
+            notify_trades: TradeNotify::Both,
             min_liquidity_sol: 1,       // Virtual liquidity for bonding curve
             max_risk_level: 70,
             min_holders: 50,            // Minimum 50 holders
             max_token_age_minutes: 60,  // 0-60 minutes old
+            max_token_age_seconds: None,
             require_lp_burned: false,   // N/A for bonding curve (still on pump.fun)
             reject_if_mint_authority: true,
             reject_if_freeze_authority: true,
@@ -173,8 +556,15 @@ impl Strategy {
             // Advanced filters
             min_buy_ratio_percent: 55.0,         // At least 55% buys (healthy demand)
             min_unique_wallets_24h: Some(20),    // At least 20 unique wallets (organic activity)
+            token_lists: TokenLists::default(),
             slippage_bps: None,
             priority_fee_micro_lamports: None,
+            fee_urgency: FeeUrgency::Medium,
+            use_jito: false,
+            jito_tip_lamports: None,
+            execution_venue: ExecutionVenue::Jupiter,
+            entry_retry_attempts: 0,
+            entry_retry_delay_ms: DEFAULT_ENTRY_RETRY_DELAY_MS,
             created_at: now,
             updated_at: now,
         }
@@ -192,13 +582,25 @@ impl Strategy {
             max_position_size_sol: 0.1,
             total_budget_sol: 1.0,
             stop_loss_percent: Some(15),
+            stop_loss_type: StopLossType::FixedPercent,
             take_profit_percent: Some(40),
             trailing_stop_percent: Some(8),
+            peak_drawdown_alert_percent: None,
+            peak_drawdown_sell_percent: None,
             max_hold_time_minutes: 1440, // 24 hours
+            force_close_at: None,
+            momentum_tp: None,
+            scale_in: None,
+            dca: None,
+            scan_interval_secs: None,
+            scan_token_age_minutes: None,
+            limit_entry: None,
+            notify_trades: TradeNotify::Both,
             min_liquidity_sol: 10,       // Real DEX liquidity
             max_risk_level: 50,          // Lower risk tolerance for established tokens
             min_holders: 75,             // Minimum 75 holders
             max_token_age_minutes: 1440, // 0-24 hours old
+            max_token_age_seconds: None,
             require_lp_burned: false,
             reject_if_mint_authority: true,
             reject_if_freeze_authority: true,
@@ -213,8 +615,15 @@ impl Strategy {
             // Advanced filters
             min_buy_ratio_percent: 55.0,         // At least 55% buys
             min_unique_wallets_24h: Some(30),    // At least 30 unique wallets (more established)
+            token_lists: TokenLists::default(),
             slippage_bps: None,
             priority_fee_micro_lamports: None,
+            fee_urgency: FeeUrgency::Medium,
+            use_jito: false,
+            jito_tip_lamports: None,
+            execution_venue: ExecutionVenue::Jupiter,
+            entry_retry_attempts: 0,
+            entry_retry_delay_ms: DEFAULT_ENTRY_RETRY_DELAY_MS,
             created_at: now,
             updated_at: now,
         }
@@ -235,14 +644,26 @@ impl Strategy {
             total_budget_sol: 2.0,
             // Moonbag (10% remainder) exit rules:
             stop_loss_percent: Some(50),    // very loose — moonbag is meant to ride
+            stop_loss_type: StopLossType::FixedPercent,
             take_profit_percent: Some(500), // 5x on moonbag triggers full close
             trailing_stop_percent: Some(30),
+            peak_drawdown_alert_percent: None,
+            peak_drawdown_sell_percent: None,
             max_hold_time_minutes: 60,
+            force_close_at: None,
+            momentum_tp: None,
+            scale_in: None,
+            dca: None,
+            scan_interval_secs: None,
+            scan_token_age_minutes: None,
+            limit_entry: None,
+            notify_trades: TradeNotify::Both,
             // No discovery filters apply — TG signal is the filter.
             min_liquidity_sol: 0,
             max_risk_level: 100,
             min_holders: 0,
             max_token_age_minutes: 1440,
+            max_token_age_seconds: None,
             require_lp_burned: false,
             reject_if_mint_authority: false,
             reject_if_freeze_authority: false,
@@ -255,8 +676,15 @@ impl Strategy {
             require_migrated: None,
             min_buy_ratio_percent: 0.0,
             min_unique_wallets_24h: None,
+            token_lists: TokenLists::default(),
             slippage_bps: Some(1500),       // mirrors SNIPE_SLIPPAGE_BPS default
             priority_fee_micro_lamports: Some(1_000_000),
+            fee_urgency: FeeUrgency::Medium,
+            use_jito: false,
+            jito_tip_lamports: None,
+            execution_venue: ExecutionVenue::Jupiter,
+            entry_retry_attempts: 0,
+            entry_retry_delay_ms: DEFAULT_ENTRY_RETRY_DELAY_MS,
             created_at: now,
             updated_at: now,
         }
@@ -266,6 +694,23 @@ impl Strategy {
     pub fn touch(&mut self) {
         self.updated_at = Utc::now();
     }
+
+    /// The tranche schedule for this strategy's entries: its scale-in, or its DCA
+    /// expressed as one (None = single buy)
+    pub fn entry_tranches(&self) -> Option<ScaleInSettings> {
+        self.scale_in.clone().or_else(|| self.dca.map(|dca| dca.to_scale_in()))
+    }
+
+    /// The Jito tip for this strategy's swaps, or None to send them through the RPC
+    pub fn jito_tip(&self, default_tip_lamports: u64) -> Option<u64> {
+        self.use_jito.then(|| self.jito_tip_lamports.unwrap_or(default_tip_lamports))
+    }
+
+    /// How this strategy's swaps are built and sent
+    pub fn swap_route(&self, default_tip_lamports: u64) -> SwapRoute {
+        SwapRoute::new(self.execution_venue, self.jito_tip(default_tip_lamports))
+            .with_bonding_curve(self.strategy_type == StrategyType::PumpfunBondingCurve)
+    }
     
     // Create a basic strategy with more conservative parameters
     pub fn conservative(name: &str) -> Self {
@@ -297,6 +742,74 @@ impl Strategy {
         strategy
     }
     
+    /// Bonding-curve buyer: Final Stretch filters, but entries go straight to the
+    /// pump.fun curve instead of waiting for Jupiter to route them
+    pub fn pumpfun_bonding_curve(name: &str) -> Self {
+        let mut strategy = Self::final_stretch(name);
+        strategy.strategy_type = StrategyType::PumpfunBondingCurve;
+        strategy
+    }
+
+    /// Graduation play: late bonding-curve tokens about to migrate, taking
+    /// profit into the graduation pump.
+    pub fn graduation_play(name: &str) -> Self {
+        let mut strategy = Self::final_stretch(name);
+        strategy.max_position_size_sol = 0.1;
+        strategy.total_budget_sol = 0.5;
+        strategy.min_bonding_progress = Some(70.0);
+        strategy.min_holders = 100;
+        strategy.min_volume_usd = Some(25_000.0);
+        strategy.min_market_cap_usd = Some(25_000.0);
+        strategy.stop_loss_percent = Some(15);
+        strategy.take_profit_percent = Some(80);
+        strategy.trailing_stop_percent = Some(12);
+        strategy.max_hold_time_minutes = 90;
+        strategy
+    }
+
+    /// Blue-chip only: established migrated tokens with deep liquidity and a
+    /// broad holder base. Small targets, tight stops, long holds.
+    pub fn blue_chip(name: &str) -> Self {
+        let mut strategy = Self::migrated(name);
+        strategy.max_position_size_sol = 0.2;
+        strategy.total_budget_sol = 1.0;
+        strategy.max_risk_level = 35;
+        strategy.min_liquidity_sol = 100;
+        strategy.min_holders = 500;
+        strategy.max_token_age_minutes = 43_200; // 30 days
+        strategy.require_lp_burned = true;
+        strategy.max_concentration_percent = Some(30.0);
+        strategy.min_volume_usd = Some(250_000.0);
+        strategy.min_market_cap_usd = Some(1_000_000.0);
+        strategy.min_unique_wallets_24h = Some(200);
+        strategy.stop_loss_percent = Some(10);
+        strategy.take_profit_percent = Some(25);
+        strategy.trailing_stop_percent = Some(5);
+        strategy.max_hold_time_minutes = 2880; // 48 hours
+        strategy
+    }
+
+    /// Build a strategy from a named template (see `STRATEGY_TEMPLATES`).
+    /// Returns None if the template ID is unknown.
+    pub fn from_template(template_id: &str, name: &str) -> Option<Self> {
+        STRATEGY_TEMPLATES
+            .iter()
+            .find(|t| t.id == template_id)
+            .map(|t| (t.build)(name))
+    }
+
+    /// Maximum token age in seconds used to filter entries.
+    ///
+    /// `max_token_age_seconds` takes precedence: when it is set, `max_token_age_minutes`
+    /// is ignored, so exactly one of the two fields decides. Otherwise the minute limit
+    /// applies.
+    pub fn max_token_age_secs(&self) -> u64 {
+        match self.max_token_age_seconds {
+            Some(secs) => secs as u64,
+            None => self.max_token_age_minutes as u64 * 60,
+        }
+    }
+
     // Validates the strategy parameters to ensure they're coherent
     pub fn validate(&self) -> Result<(), String> {
         // Check for logical parameter relationships
@@ -315,12 +828,101 @@ impl Strategy {
         if self.max_concurrent_positions == 0 {
             return Err("Maximum concurrent positions must be at least 1".to_string());
         }
+
+        if let Some(momentum) = &self.momentum_tp {
+            if self.take_profit_percent.is_none() {
+                return Err("Momentum take-profit requires a base take-profit percent".to_string());
+            }
+            if momentum.full_velocity_percent_per_min <= 0.0 || momentum.lookback_minutes == 0 {
+                return Err("Momentum take-profit velocity and lookback must be greater than 0".to_string());
+            }
+        }
+
+        if let Some(alert) = self.peak_drawdown_alert_percent {
+            if alert <= 0.0 || alert >= 100.0 {
+                return Err("Peak drawdown alert percent must be between 0 and 100".to_string());
+            }
+            if self.trailing_stop_percent.is_some_and(|ts| alert >= ts as f64) {
+                return Err("Peak drawdown alert must fire before the trailing stop".to_string());
+            }
+        }
+        if let Some(sell) = self.peak_drawdown_sell_percent {
+            if self.peak_drawdown_alert_percent.is_none() {
+                return Err("Peak drawdown sell percent requires a peak drawdown alert percent".to_string());
+            }
+            if sell <= 0.0 || sell >= 100.0 {
+                return Err("Peak drawdown sell percent must be between 0 and 100".to_string());
+            }
+        }
+
+        if let Some(volatility) = self.stop_loss_type.volatility() {
+            volatility.validate()?;
+        }
+
+        if let Some(scale_in) = &self.scale_in {
+            scale_in.validate()?;
+        }
+        if let Some(dca) = &self.dca {
+            if self.scale_in.is_some() {
+                return Err("A strategy can use scale-in or DCA, not both".to_string());
+            }
+            dca.validate()?;
+        }
+        if let Some(limit_entry) = &self.limit_entry {
+            limit_entry.validate()?;
+        }
+
+        if self.max_token_age_seconds == Some(0) {
+            return Err("Maximum token age in seconds must be greater than 0 (unset it to use the minute limit)".to_string());
+        }
+        if self.scan_interval_secs == Some(0) || self.scan_token_age_minutes == Some(0) {
+            return Err("Scan interval and scan token age overrides must be greater than 0".to_string());
+        }
+        if self.min_bonding_progress.is_some_and(|p| !(0.0..100.0).contains(&p)) {
+            return Err("Minimum bonding progress must be between 0 and 100 percent".to_string());
+        }
         
         // All conditions met
         Ok(())
     }
 }
 
+/// A named preset with pre-filled strategy fields for a common trading style.
+pub struct StrategyTemplate {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub build: fn(&str) -> Strategy,
+}
+
+/// Presets offered by `GET /api/strategies/templates`.
+pub const STRATEGY_TEMPLATES: &[StrategyTemplate] = &[
+    StrategyTemplate {
+        id: "conservative",
+        name: "Conservative",
+        description: "Small positions in low-risk new pairs with tight stops",
+        build: Strategy::conservative,
+    },
+    StrategyTemplate {
+        id: "aggressive_sniper",
+        name: "Aggressive Sniper",
+        description: "Larger positions in fresh pairs, tolerating higher risk for bigger targets",
+        build: Strategy::aggressive,
+    },
+    StrategyTemplate {
+        id: "graduation_play",
+        name: "Graduation Play",
+        description: "Bonding-curve tokens past 70% progress, riding the migration pump",
+        build: Strategy::graduation_play,
+    },
+    StrategyTemplate {
+        id: "blue_chip",
+        name: "Blue-chip Only",
+        description: "Established migrated tokens with deep liquidity and broad holder base",
+        build: Strategy::blue_chip,
+    },
+];
+
 /// Ensure the strategy map contains an ENABLED strategy of the given type.
 /// Creates one from the factory defaults if missing, or re-enables a disabled one.
 /// Returns true if the map was modified (caller should persist to disk).
@@ -351,6 +953,7 @@ pub fn ensure_enabled_strategy(
         StrategyType::FinalStretch => Strategy::final_stretch("Final Stretch Scout"),
         StrategyType::Migrated => Strategy::migrated("Migrated Scout"),
         StrategyType::TelegramCall => Strategy::telegram_call("Telegram Call Sniper"),
+        StrategyType::PumpfunBondingCurve => Strategy::pumpfun_bonding_curve("Bonding Curve Buyer"),
     };
     strategies.insert(strategy.id.clone(), strategy);
     true
@@ -475,6 +1078,117 @@ mod tests {
         assert!(s.validate().is_ok());
     }
 
+    #[test]
+    fn all_templates_build_valid_strategies() {
+        for template in STRATEGY_TEMPLATES {
+            let s = Strategy::from_template(template.id, "test")
+                .unwrap_or_else(|| panic!("template {} should resolve", template.id));
+            assert!(s.validate().is_ok(), "template {} should validate", template.id);
+        }
+        assert!(Strategy::from_template("nope", "test").is_none());
+    }
+
+    #[test]
+    fn next_force_close_rolls_over_to_next_day() {
+        let at = NaiveTime::from_hms_opt(20, 0, 0).unwrap();
+        let morning = DateTime::parse_from_rfc3339("2024-05-01T09:30:00Z").unwrap().with_timezone(&Utc);
+        let evening = DateTime::parse_from_rfc3339("2024-05-01T21:15:00Z").unwrap().with_timezone(&Utc);
+
+        assert_eq!(next_force_close(morning, at).to_rfc3339(), "2024-05-01T20:00:00+00:00");
+        assert_eq!(next_force_close(evening, at).to_rfc3339(), "2024-05-02T20:00:00+00:00");
+    }
+
+    #[test]
+    fn momentum_tp_widens_with_velocity_and_caps() {
+        let m = MomentumTpSettings { max_extra_percent: 100, full_velocity_percent_per_min: 10.0, lookback_minutes: 5 };
+        assert_eq!(m.take_profit_percent(50.0, 0.0), 50.0);
+        assert_eq!(m.take_profit_percent(50.0, -4.0), 50.0);
+        assert_eq!(m.take_profit_percent(50.0, 5.0), 100.0);
+        assert_eq!(m.take_profit_percent(50.0, 25.0), 150.0);
+    }
+
+    #[test]
+    fn volatility_stop_scales_and_clamps() {
+        let v = VolatilityStopSettings { window_minutes: 10, multiplier: 2.0, min_percent: 5.0, max_percent: 30.0, reevaluate: false };
+        assert_eq!(v.stop_percent(1.0), 5.0);
+        assert_eq!(v.stop_percent(6.0), 12.0);
+        assert_eq!(v.stop_percent(40.0), 30.0);
+        assert!(v.validate().is_ok());
+        assert!(VolatilityStopSettings { min_percent: 40.0, ..v }.validate().is_err());
+        assert!(VolatilityStopSettings { window_minutes: 0, ..v }.validate().is_err());
+
+        let mut s = Strategy::default("Vol");
+        s.stop_loss_type = StopLossType::Volatility(v);
+        assert!(s.validate().is_ok());
+        assert_eq!(s.stop_loss_type.volatility(), Some(v));
+    }
+
+    #[test]
+    fn scale_in_triggers_and_validation() {
+        let hold = ScaleInTrigger::HoldAboveEntry { after_minutes: 10 };
+        assert!(!hold.is_met(1.0, 1.2, 5));
+        assert!(!hold.is_met(1.0, 0.9, 15));
+        assert!(hold.is_met(1.0, 1.0, 10));
+        let breakout = ScaleInTrigger::Breakout { above_entry_percent: 50.0 };
+        assert!(!breakout.is_met(1.0, 1.4, 0));
+        assert!(breakout.is_met(1.0, 1.5, 0));
+
+        let mut s = ScaleInSettings {
+            initial_percent: 34.0,
+            tranches: vec![
+                ScaleInTranche { size_percent: 33.0, trigger: hold },
+                ScaleInTranche { size_percent: 33.0, trigger: breakout },
+            ],
+        };
+        assert!(s.validate().is_ok());
+        assert_eq!(s.initial_size_sol(0.3), 0.3 * 0.34);
+        s.tranches[1].size_percent = 40.0;
+        assert!(s.validate().is_err());
+    }
+
+    #[test]
+    fn dca_runs_as_equal_timed_tranches() {
+        let dca = DcaSettings { tranches: 4, interval_minutes: 5 };
+        assert!(dca.validate().is_ok());
+        let schedule = dca.to_scale_in();
+        assert!(schedule.validate().is_ok());
+        assert_eq!(schedule.initial_size_sol(1.0), 0.25);
+        assert_eq!(schedule.tranches.len(), 3);
+        assert_eq!(schedule.tranches[2].trigger, ScaleInTrigger::AfterMinutes { after_minutes: 15 });
+        // Time alone triggers a DCA tranche, even below the first buy's price
+        assert!(schedule.tranches[0].trigger.is_met(1.0, 0.5, 5));
+        assert!(!schedule.tranches[1].trigger.is_met(1.0, 2.0, 9));
+
+        let mut s = Strategy::default("DCA");
+        s.dca = Some(dca);
+        assert_eq!(s.entry_tranches(), Some(schedule.clone()));
+        assert!(s.validate().is_ok());
+        s.scale_in = Some(schedule);
+        assert!(s.validate().is_err());
+        assert!(DcaSettings { tranches: 1, interval_minutes: 5 }.validate().is_err());
+    }
+
+    #[test]
+    fn limit_entry_target_and_validation() {
+        let entry = LimitEntrySettings { discount_percent: 20.0, expiry_minutes: 30 };
+        assert!((entry.target_price(0.001) - 0.0008).abs() < 1e-12);
+        assert!(entry.validate().is_ok());
+        assert!(LimitEntrySettings { discount_percent: 0.0, ..entry }.validate().is_err());
+        assert!(LimitEntrySettings { expiry_minutes: 0, ..entry }.validate().is_err());
+    }
+
+    #[test]
+    fn age_seconds_override_takes_precedence() {
+        let mut s = Strategy::default("Age");
+        s.max_token_age_minutes = 10;
+        assert_eq!(s.max_token_age_secs(), 600);
+        s.max_token_age_seconds = Some(90);
+        assert_eq!(s.max_token_age_secs(), 90);
+        assert!(s.validate().is_ok());
+        s.max_token_age_seconds = Some(0);
+        assert!(s.validate().is_err());
+    }
+
     #[test]
     fn telegram_call_display_name() {
         assert_eq!(StrategyType::TelegramCall.display_name(), "Telegram Call");
@@ -526,4 +1240,50 @@ mod tests {
         assert_eq!(strategies.len(), 1);
         assert!(strategies[&mig_id].enabled);
     }
+
+    #[test]
+    fn jito_tip_only_when_strategy_opts_in() {
+        let mut strategy = Strategy::default("Jito");
+        assert_eq!(strategy.jito_tip(100_000), None);
+        strategy.use_jito = true;
+        assert_eq!(strategy.jito_tip(100_000), Some(100_000));
+        strategy.jito_tip_lamports = Some(250_000);
+        assert_eq!(strategy.jito_tip(100_000), Some(250_000));
+    }
+
+    #[test]
+    fn bonding_curve_strategy_forces_curve_route() {
+        let strategy = Strategy::pumpfun_bonding_curve("Curve");
+        assert!(strategy.swap_route(0).bonding_curve);
+        assert_eq!(strategy.min_bonding_progress, Some(20.0));
+        assert!(!Strategy::final_stretch("Stretch").swap_route(0).bonding_curve);
+
+        let mut invalid = strategy.clone();
+        invalid.min_bonding_progress = Some(100.0);
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn token_lists_blacklist_and_whitelist() {
+        let open = TokenLists::default();
+        assert_eq!(open.rejection_reason("Mint", None), None);
+
+        let lists = TokenLists {
+            mint_blacklist: vec!["BadMint".to_string()],
+            creator_blacklist: vec!["Rugger".to_string()],
+            ..TokenLists::default()
+        };
+        assert!(lists.rejection_reason("BadMint", None).is_some());
+        assert!(lists.rejection_reason("Mint", Some("Rugger")).unwrap().contains("Rugger"));
+        assert_eq!(lists.rejection_reason("Mint", Some("Dev")), None);
+
+        let trusted = TokenLists { creator_whitelist: vec!["Dev".to_string()], ..TokenLists::default() };
+        assert_eq!(trusted.rejection_reason("Mint", Some("Dev")), None);
+        assert!(trusted.rejection_reason("Mint", Some("Other")).is_some());
+        assert!(trusted.rejection_reason("Mint", None).is_some());
+
+        let only = TokenLists { mint_whitelist: vec!["Mint".to_string()], ..TokenLists::default() };
+        assert_eq!(only.rejection_reason("Mint", None), None);
+        assert!(only.rejection_reason("Other", None).is_some());
+    }
 }
@@ -0,0 +1,176 @@
+//! Per-strategy change history
+//!
+//! Edits otherwise overwrite a strategy in place, leaving no way to tell why the
+//! bot's behaviour changed. Every update or toggle records who made it and each
+//! field's old and new value. The log is persisted to disk and bounded per strategy;
+//! the oldest entries are dropped once a strategy exceeds the limit.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::trading::strategy::Strategy;
+
+const CHANGELOG_FILE: &str = "data/strategy_changelog.json";
+
+/// Bookkeeping fields that change on every edit and aren't worth recording
+const IGNORED_FIELDS: &[&str] = &["updated_at"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyChange {
+    pub strategy_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub actor: String,           // Who made the change, e.g. "api:admin"
+    pub action: String,          // "update" or "toggle"
+    pub changes: Vec<FieldChange>,
+}
+
+/// Fields that differ between two versions of a strategy, in field-name order
+pub fn diff_strategies(old: &Strategy, new: &Strategy) -> Vec<FieldChange> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) = (serde_json::to_value(old), serde_json::to_value(new)) else {
+        return Vec::new();
+    };
+    let mut changes: Vec<FieldChange> = new.iter()
+        .filter(|(field, _)| !IGNORED_FIELDS.contains(&field.as_str()))
+        .filter_map(|(field, value)| {
+            let before = old.get(field).cloned().unwrap_or(Value::Null);
+            (before != *value).then(|| FieldChange { field: field.clone(), old: before, new: value.clone() })
+        })
+        .collect();
+    changes.sort_by(|a, b| a.field.cmp(&b.field));
+    changes
+}
+
+pub struct StrategyChangelog {
+    path: PathBuf,
+    max_per_strategy: usize,
+    entries: RwLock<HashMap<String, Vec<StrategyChange>>>,
+}
+
+impl StrategyChangelog {
+    pub fn new(max_per_strategy: usize) -> Self {
+        Self {
+            path: PathBuf::from(CHANGELOG_FILE),
+            max_per_strategy: max_per_strategy.max(1),
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Load the log from disk
+    pub async fn load(&self) -> Result<()> {
+        let data = match tokio::fs::read_to_string(&self.path).await {
+            Ok(d) => d,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).context(format!("Failed to read strategy changelog: {:?}", self.path)),
+        };
+        if data.trim().is_empty() {
+            return Ok(());
+        }
+        let loaded: HashMap<String, Vec<StrategyChange>> = serde_json::from_str(&data)
+            .context("Failed to parse strategy changelog")?;
+        info!("📂 Loaded change history for {} strategies", loaded.len());
+        *self.entries.write().await = loaded;
+        Ok(())
+    }
+
+    /// Record the difference between `old` and `new`; nothing is written if no field changed
+    pub async fn record(&self, old: &Strategy, new: &Strategy, actor: &str, action: &str) {
+        let changes = diff_strategies(old, new);
+        if changes.is_empty() {
+            debug!("Strategy {} {} by {} changed nothing", new.id, action, actor);
+            return;
+        }
+        info!(
+            "📝 Strategy '{}' {} by {}: {}",
+            new.name, action, actor,
+            changes.iter().map(|c| format!("{} {} -> {}", c.field, c.old, c.new)).collect::<Vec<_>>().join(", ")
+        );
+
+        let mut entries = self.entries.write().await;
+        let log = entries.entry(new.id.clone()).or_default();
+        log.push(StrategyChange {
+            strategy_id: new.id.clone(),
+            timestamp: Utc::now(),
+            actor: actor.to_string(),
+            action: action.to_string(),
+            changes,
+        });
+        if log.len() > self.max_per_strategy {
+            let excess = log.len() - self.max_per_strategy;
+            log.drain(..excess);
+        }
+        drop(entries);
+
+        if let Err(e) = self.save().await {
+            warn!("Failed to save strategy changelog: {:?}", e);
+        }
+    }
+
+    /// A strategy's changes, oldest first
+    pub async fn history(&self, strategy_id: &str) -> Vec<StrategyChange> {
+        self.entries.read().await.get(strategy_id).cloned().unwrap_or_default()
+    }
+
+    async fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            tokio::fs::create_dir_all(dir).await.context("Failed to create data directory")?;
+        }
+        let data = serde_json::to_string_pretty(&*self.entries.read().await)?;
+        tokio::fs::write(&self.path, data).await
+            .context(format!("Failed to write strategy changelog: {:?}", self.path))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_lists_changed_fields_only() {
+        let old = Strategy::default("Scout");
+        let mut new = old.clone();
+        new.enabled = false;
+        new.stop_loss_percent = Some(20);
+        new.updated_at = Utc::now() + chrono::Duration::seconds(5);
+
+        let changes = diff_strategies(&old, &new);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0], FieldChange { field: "enabled".to_string(), old: json!(true), new: json!(false) });
+        assert_eq!(changes[1].field, "stop_loss_percent");
+        assert_eq!(changes[1].new, json!(20));
+        assert!(diff_strategies(&old, &old).is_empty());
+    }
+
+    #[tokio::test]
+    async fn log_is_bounded_per_strategy() {
+        let log = StrategyChangelog {
+            path: std::env::temp_dir().join(format!("strategy_changelog_{}.json", uuid::Uuid::new_v4())),
+            ..StrategyChangelog::new(2)
+        };
+        let mut strategy = Strategy::default("Scout");
+        for sl in [10, 11, 12] {
+            let old = strategy.clone();
+            strategy.stop_loss_percent = Some(sl);
+            log.record(&old, &strategy, "api:admin", "update").await;
+        }
+        let history = log.history(&strategy.id).await;
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].changes[0].new, json!(11));
+        let _ = std::fs::remove_file(&log.path);
+    }
+}
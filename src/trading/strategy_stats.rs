@@ -0,0 +1,178 @@
+//! Per-strategy performance history
+//!
+//! Strategy stats are otherwise recomputed from whatever positions are still in
+//! memory, so history disappears once old positions are pruned. Snapshots of each
+//! strategy's win rate, PnL and trade count are appended to a JSON-lines file on a
+//! timer and whenever one of its positions closes, for trend charts over time.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+const STATS_HISTORY_FILE: &str = "data/strategy_stats.jsonl";
+
+/// One point in a strategy's performance series
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StrategyStatsSnapshot {
+    pub strategy_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub total_trades: u32,
+    pub winning_trades: u32,
+    pub win_rate: f64,
+    pub total_pnl_sol: f64,
+}
+
+impl StrategyStatsSnapshot {
+    /// Build a snapshot from closed trades given as (entry SOL, exit SOL) pairs
+    pub fn from_closed_trades(
+        strategy_id: &str,
+        trades: impl IntoIterator<Item = (f64, f64)>,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        let mut total_trades = 0;
+        let mut winning_trades = 0;
+        let mut total_pnl_sol = 0.0;
+        for (entry, exit) in trades {
+            let pnl = exit - entry;
+            total_pnl_sol += pnl;
+            total_trades += 1;
+            if pnl > 0.0 {
+                winning_trades += 1;
+            }
+        }
+        let win_rate = if total_trades > 0 {
+            (winning_trades as f64 / total_trades as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        Self {
+            strategy_id: strategy_id.to_string(),
+            timestamp,
+            total_trades,
+            winning_trades,
+            win_rate,
+            total_pnl_sol,
+        }
+    }
+}
+
+pub struct StrategyStatsHistory {
+    path: PathBuf,
+    snapshots: RwLock<Vec<StrategyStatsSnapshot>>,
+}
+
+impl StrategyStatsHistory {
+    pub fn new() -> Self {
+        Self::with_path(PathBuf::from(STATS_HISTORY_FILE))
+    }
+
+    pub fn with_path(path: PathBuf) -> Self {
+        Self {
+            path,
+            snapshots: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Load the series from disk. Malformed lines are skipped, not fatal.
+    pub async fn load(&self) -> Result<()> {
+        let data = match fs::read_to_string(&self.path).await {
+            Ok(d) => d,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).context(format!("Failed to read strategy stats file: {:?}", self.path)),
+        };
+
+        let mut loaded = Vec::new();
+        for line in data.lines().filter(|l| !l.trim().is_empty()) {
+            match serde_json::from_str::<StrategyStatsSnapshot>(line) {
+                Ok(snapshot) => loaded.push(snapshot),
+                Err(e) => warn!("Skipping malformed strategy stats line: {}", e),
+            }
+        }
+        info!("Loaded {} strategy stats snapshots from {:?}", loaded.len(), self.path);
+        *self.snapshots.write().await = loaded;
+        Ok(())
+    }
+
+    /// Append snapshots to the series and the file
+    pub async fn record(&self, snapshots: Vec<StrategyStatsSnapshot>) -> Result<()> {
+        if snapshots.is_empty() {
+            return Ok(());
+        }
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).await.context("Failed to create data directory")?;
+        }
+
+        let mut lines = String::new();
+        for snapshot in &snapshots {
+            lines.push_str(&serde_json::to_string(snapshot).context("Failed to serialize strategy stats")?);
+            lines.push('\n');
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .context(format!("Failed to open strategy stats file: {:?}", self.path))?;
+        file.write_all(lines.as_bytes()).await
+            .context(format!("Failed to append to strategy stats file: {:?}", self.path))?;
+
+        debug!("Recorded {} strategy stats snapshots", snapshots.len());
+        self.snapshots.write().await.extend(snapshots);
+        Ok(())
+    }
+
+    /// A strategy's series, oldest first
+    pub async fn history(&self, strategy_id: &str) -> Vec<StrategyStatsSnapshot> {
+        self.snapshots.read().await
+            .iter()
+            .filter(|s| s.strategy_id == strategy_id)
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for StrategyStatsHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_counts_wins_and_pnl() {
+        let s = StrategyStatsSnapshot::from_closed_trades("s1", [(1.0, 1.5), (1.0, 0.8), (0.5, 0.6), (1.0, 1.0)], Utc::now());
+        assert_eq!(s.total_trades, 4);
+        assert_eq!(s.winning_trades, 2);
+        assert_eq!(s.win_rate, 50.0);
+        assert!((s.total_pnl_sol - 0.4).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn record_then_reload_filters_by_strategy() {
+        let path = std::env::temp_dir().join(format!("strategy_stats_{}.jsonl", uuid::Uuid::new_v4()));
+        let history = StrategyStatsHistory::with_path(path.clone());
+        history.record(vec![
+            StrategyStatsSnapshot::from_closed_trades("a", [(1.0, 2.0)], Utc::now()),
+            StrategyStatsSnapshot::from_closed_trades("b", [(1.0, 0.5)], Utc::now()),
+        ]).await.unwrap();
+        history.record(vec![StrategyStatsSnapshot::from_closed_trades("a", [(1.0, 2.0), (1.0, 0.5)], Utc::now())]).await.unwrap();
+
+        let reloaded = StrategyStatsHistory::with_path(path.clone());
+        reloaded.load().await.unwrap();
+        let series = reloaded.history("a").await;
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[1].total_trades, 2);
+        assert_eq!(reloaded.history("b").await.len(), 1);
+
+        let _ = std::fs::remove_file(path);
+    }
+}
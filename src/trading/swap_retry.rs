@@ -0,0 +1,76 @@
+//! Slippage retry ladder
+//!
+//! A buy or sell that fails on slippage, or whose blockhash expires before it
+//! lands, never executed, so it's safe to send again. Each retry re-quotes with
+//! more slippage and a higher priority fee, up to the configured caps. Timeouts
+//! aren't retried here: the swap may still land, and exits already track those
+//! as unconfirmed.
+
+use crate::api::swap_error::SwapError;
+use crate::config::Config;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwapRetryLadder {
+    pub attempts: u32,                    // Retries after the first attempt (0 = off)
+    pub slippage_step_bps: u32,           // Added to the slippage on each retry
+    pub max_slippage_bps: u32,
+    pub fee_multiplier: f64,              // Applied to the priority fee on each retry
+    pub max_priority_fee_micro_lamports: u64,
+}
+
+impl SwapRetryLadder {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            attempts: config.swap_retry_attempts,
+            slippage_step_bps: config.swap_retry_slippage_step_bps,
+            max_slippage_bps: config.swap_retry_max_slippage_bps,
+            fee_multiplier: config.swap_retry_fee_multiplier,
+            max_priority_fee_micro_lamports: config.swap_retry_max_priority_fee_micro_lamports,
+        }
+    }
+
+    /// Whether a swap that failed with `kind` can be sent again
+    pub fn retries(kind: SwapError) -> bool {
+        matches!(kind, SwapError::SlippageExceeded | SwapError::BlockhashExpired)
+    }
+
+    /// Slippage and priority fee for the next rung. Neither is lowered, so a
+    /// swap that already starts above a cap (e.g. an emergency exit) keeps its values.
+    pub fn escalate(&self, slippage_bps: u32, priority_fee: u64) -> (u32, u64) {
+        let slippage = slippage_bps.saturating_add(self.slippage_step_bps).min(self.max_slippage_bps);
+        let fee = ((priority_fee as f64 * self.fee_multiplier).round() as u64).min(self.max_priority_fee_micro_lamports);
+        (slippage.max(slippage_bps), fee.max(priority_fee))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ladder() -> SwapRetryLadder {
+        SwapRetryLadder {
+            attempts: 2,
+            slippage_step_bps: 300,
+            max_slippage_bps: 2500,
+            fee_multiplier: 2.0,
+            max_priority_fee_micro_lamports: 3_000_000,
+        }
+    }
+
+    #[test]
+    fn escalates_up_to_the_caps() {
+        let ladder = ladder();
+        assert_eq!(ladder.escalate(100, 50_000), (400, 100_000));
+        assert_eq!(ladder.escalate(2400, 2_000_000), (2500, 3_000_000));
+        // Already past the caps: held, never lowered
+        assert_eq!(ladder.escalate(5000, 5_000_000), (5000, 5_000_000));
+    }
+
+    #[test]
+    fn retries_only_swaps_that_never_executed() {
+        assert!(SwapRetryLadder::retries(SwapError::SlippageExceeded));
+        assert!(SwapRetryLadder::retries(SwapError::BlockhashExpired));
+        assert!(!SwapRetryLadder::retries(SwapError::RpcTimeout));
+        assert!(!SwapRetryLadder::retries(SwapError::InsufficientFunds));
+    }
+}
@@ -0,0 +1,137 @@
+//! Tiny real round-trip swap that proves the live execution path works
+//!
+//! Demo and dry-run modes never touch the chain, so a misconfigured wallet, RPC or
+//! Jupiter setup only shows up on the first real trade. This buys a small amount of
+//! a liquid token (USDC by default) and sells it straight back, recording each step
+//! (quote, send, confirm) so the report shows exactly where the path breaks.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::signature::Signature;
+use tracing::{info, warn};
+
+use crate::api::jupiter::{JupiterClient, SwapRoute, SOL_MINT};
+use crate::solana::wallet::WalletManager;
+
+/// Seconds to wait for each leg to confirm
+const CONFIRM_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestSwapStep {
+    pub name: String,         // e.g. "buy_quote", "buy_send", "buy_confirm"
+    pub ok: bool,
+    pub detail: String,
+    pub signature: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestSwapReport {
+    pub ok: bool,
+    pub wallet: String,
+    pub token_mint: String,
+    pub amount_sol: f64,
+    pub sol_returned: Option<f64>,
+    pub steps: Vec<TestSwapStep>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl TestSwapReport {
+    /// Record a step's outcome; returns the value if it succeeded
+    fn step<T>(&mut self, name: &str, started: Instant, result: Result<T>, describe: impl FnOnce(&T) -> (String, Option<String>)) -> Option<T> {
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        match result {
+            Ok(value) => {
+                let (detail, signature) = describe(&value);
+                info!("🧪 Test swap {}: {}", name, detail);
+                self.steps.push(TestSwapStep { name: name.to_string(), ok: true, detail, signature, elapsed_ms });
+                Some(value)
+            }
+            Err(e) => {
+                warn!("🧪 Test swap {} failed: {:?}", name, e);
+                self.ok = false;
+                self.steps.push(TestSwapStep { name: name.to_string(), ok: false, detail: format!("{:#}", e), signature: None, elapsed_ms });
+                None
+            }
+        }
+    }
+}
+
+/// Buy `amount_sol` of `token_mint` and sell everything received back to SOL.
+/// Stops at the first failed step; a failure after the buy leaves the token in the wallet.
+pub async fn run_test_swap(
+    jupiter_client: &JupiterClient,
+    wallet: Arc<WalletManager>,
+    token_mint: &str,
+    token_decimals: u8,
+    amount_sol: f64,
+    slippage_bps: u32,
+    priority_fee_micro_lamports: u64,
+) -> TestSwapReport {
+    let mut report = TestSwapReport {
+        ok: true,
+        wallet: wallet.get_public_key().to_string(),
+        token_mint: token_mint.to_string(),
+        amount_sol,
+        sol_returned: None,
+        steps: Vec::new(),
+        timestamp: Utc::now(),
+    };
+    let solana_client = wallet.solana_client();
+
+    // Buy leg
+    let started = Instant::now();
+    let lamports = (amount_sol * 1_000_000_000.0) as u64;
+    let quote = jupiter_client.get_quote(SOL_MINT, token_mint, lamports, slippage_bps).await;
+    if report.step("buy_quote", started, quote, |q| (format!("{} SOL -> {} raw units", amount_sol, q.out_amount), None)).is_none() {
+        return report;
+    }
+
+    let started = Instant::now();
+    let buy = jupiter_client.swap_sol_to_token(
+        token_mint, token_decimals, amount_sol, slippage_bps, Some(priority_fee_micro_lamports), SwapRoute::default(), wallet.clone(),
+    ).await;
+    let Some(buy) = report.step("buy_send", started, buy, |r| (format!("sent, expecting {:.6} tokens", r.out_amount_ui), Some(r.transaction_signature.clone()))) else {
+        return report;
+    };
+
+    let started = Instant::now();
+    let confirmed = confirm(&solana_client, &buy.transaction_signature).await;
+    if report.step("buy_confirm", started, confirmed, |_| ("confirmed".to_string(), Some(buy.transaction_signature.clone()))).is_none() {
+        return report;
+    }
+
+    // Sell leg: everything the buy delivered
+    let tokens = buy.actual_out_amount_ui.unwrap_or(buy.out_amount_ui);
+    let started = Instant::now();
+    let raw_tokens = (tokens * 10f64.powi(token_decimals as i32)) as u64;
+    let quote = jupiter_client.get_quote(token_mint, SOL_MINT, raw_tokens, slippage_bps).await;
+    if report.step("sell_quote", started, quote, |q| (format!("{:.6} tokens -> {} lamports", tokens, q.out_amount), None)).is_none() {
+        return report;
+    }
+
+    let started = Instant::now();
+    let sell = jupiter_client.swap_token_to_sol(
+        token_mint, token_decimals, tokens, slippage_bps, Some(priority_fee_micro_lamports), SwapRoute::default(), wallet.clone(),
+    ).await;
+    let Some(sell) = report.step("sell_send", started, sell, |r| (format!("sent, expecting {:.6} SOL", r.out_amount_ui), Some(r.transaction_signature.clone()))) else {
+        return report;
+    };
+
+    let started = Instant::now();
+    let confirmed = confirm(&solana_client, &sell.transaction_signature).await;
+    if report.step("sell_confirm", started, confirmed, |_| ("confirmed".to_string(), Some(sell.transaction_signature.clone()))).is_some() {
+        report.sol_returned = Some(sell.actual_out_amount_ui.unwrap_or(sell.out_amount_ui));
+    }
+    report
+}
+
+async fn confirm(solana_client: &crate::solana::client::SolanaClient, signature: &str) -> Result<()> {
+    let signature: Signature = signature.parse()?;
+    solana_client.confirm_transaction(&signature, CommitmentLevel::Confirmed, CONFIRM_TIMEOUT_SECS).await
+}
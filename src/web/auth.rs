@@ -0,0 +1,238 @@
+//! Bearer-token authentication with admin / observer roles
+//!
+//! Admin tokens can call every endpoint. Observer tokens can only call read-only
+//! endpoints and get 403 on anything that changes trading state. Besides the two
+//! static API keys, HS256 JWTs signed with `api_jwt_secret` are accepted, their
+//! `scope` claim ("trade" or "read") picking the role. When neither an admin token
+//! nor a JWT secret is configured, authentication is disabled entirely.
+
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+
+use crate::config::Config;
+use super::models::ErrorResponse;
+use super::AppState;
+
+/// Access level granted by an API token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    Observer,
+    Admin,
+}
+
+impl Role {
+    /// Role granted by a JWT `scope` claim
+    fn from_scope(scope: &str) -> Option<Self> {
+        match scope {
+            "trade" | "admin" => Some(Role::Admin),
+            "read" | "observer" => Some(Role::Observer),
+            _ => None,
+        }
+    }
+}
+
+/// The `?token=` query parameter, percent-decoded
+#[derive(Debug, Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+/// Claims read from a JWT. `exp` is required and checked by the decoder.
+#[derive(Debug, Deserialize)]
+struct JwtClaims {
+    scope: String,
+}
+
+/// Endpoints that never require a token
+const PUBLIC_PATHS: &[&str] = &["/api/health"];
+
+/// POST endpoints that don't change any state and are safe for observers
+const READ_ONLY_POSTS: &[&str] = &["/api/analyze", "/api/strategies/match", "/api/backtest"];
+
+/// GET endpoints observers can't call (full state dumps)
+const ADMIN_ONLY_GETS: &[&str] = &["/api/state/export"];
+
+/// Endpoints that authenticate the caller by wallet signature instead of an API
+/// token: outside copy traders fetching a nonce, then registering or leaving
+const WALLET_SIGNED: &[(&str, &str)] = &[
+    ("GET", "/api/copy/nonce"),
+    ("POST", "/api/copy/register"),
+    ("DELETE", "/api/copy/register"),
+];
+
+/// Role required to call `method path`, or None if the endpoint is public (or
+/// checks a wallet signature itself)
+pub fn required_role(method: &Method, path: &str) -> Option<Role> {
+    if PUBLIC_PATHS.contains(&path) || WALLET_SIGNED.iter().any(|&(m, p)| m == method.as_str() && p == path) {
+        return None;
+    }
+    if ADMIN_ONLY_GETS.contains(&path) {
+        return Some(Role::Admin);
+    }
+    if *method == Method::GET || *method == Method::HEAD || *method == Method::OPTIONS {
+        return Some(Role::Observer);
+    }
+    if *method == Method::POST && READ_ONLY_POSTS.contains(&path) {
+        return Some(Role::Observer);
+    }
+    Some(Role::Admin)
+}
+
+/// Extract the token from `Authorization: Bearer <token>`, falling back to a
+/// `token` query parameter (browsers can't set headers on WebSocket upgrades).
+fn extract_token(req: &Request) -> Option<String> {
+    if let Some(value) = req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()) {
+        if let Some(token) = value.strip_prefix("Bearer ") {
+            return Some(token.trim().to_string());
+        }
+    }
+    Query::<TokenQuery>::try_from_uri(req.uri()).ok()?.0.token
+}
+
+/// Who made a change, for audit logs. Without configured tokens there is no role.
+pub fn actor_name(role: Option<Role>) -> String {
+    match role {
+        Some(Role::Admin) => "api:admin".to_string(),
+        Some(Role::Observer) => "api:observer".to_string(),
+        None => "api".to_string(),
+    }
+}
+
+/// Compares a presented token to a configured key without an early exit on the
+/// first differing byte, so response timing doesn't reveal how much of it matched
+fn key_matches(key: Option<&str>, token: &str) -> bool {
+    let Some(key) = key else {
+        return false;
+    };
+    let (key, token) = (key.as_bytes(), token.as_bytes());
+    key.len() == token.len() && key.iter().zip(token).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Role for a bearer token: one of the static API keys, or a valid unexpired JWT
+/// when a secret is configured. None for anything else.
+fn token_role(token: &str, config: &Config) -> Option<Role> {
+    let live = config.live();
+    if key_matches(live.api_admin_token.as_deref(), token) {
+        return Some(Role::Admin);
+    }
+    if key_matches(live.api_observer_token.as_deref(), token) {
+        return Some(Role::Observer);
+    }
+    jwt_role(token, live.api_jwt_secret.as_deref()?)
+}
+
+/// Role from a JWT signed with `secret`, or None if it's invalid, expired or has
+/// an unknown scope
+fn jwt_role(token: &str, secret: &str) -> Option<Role> {
+    let claims = decode::<JwtClaims>(token, &DecodingKey::from_secret(secret.as_bytes()), &Validation::new(Algorithm::HS256))
+        .ok()?
+        .claims;
+    Role::from_scope(&claims.scope)
+}
+
+fn reject(status: StatusCode, error: &str) -> Response {
+    (status, Json(ErrorResponse { error: error.to_string(), details: None })).into_response()
+}
+
+/// Middleware that resolves the caller's role and enforces it for the route.
+/// The resolved role is stored in the request extensions for handlers that need it.
+pub async fn require_role(State(state): State<AppState>, mut req: Request, next: Next) -> Response {
+    let live = state.config.live();
+    if live.api_admin_token.is_none() && live.api_jwt_secret.is_none() {
+        return next.run(req).await;
+    }
+
+    let Some(required) = required_role(req.method(), req.uri().path()) else {
+        return next.run(req).await;
+    };
+
+    let role = match extract_token(&req) {
+        Some(token) => match token_role(&token, &state.config) {
+            Some(role) => role,
+            None => return reject(StatusCode::UNAUTHORIZED, "Invalid API token"),
+        },
+        None => return reject(StatusCode::UNAUTHORIZED, "Missing API token"),
+    };
+
+    if role < required {
+        return reject(StatusCode::FORBIDDEN, "Observer tokens cannot modify trading state");
+    }
+
+    req.extensions_mut().insert(role);
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    fn get(uri: &str) -> Request {
+        Request::builder().uri(uri).body(axum::body::Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn routes_require_the_expected_role() {
+        let cases = [
+            (Method::GET, "/api/health", None),
+            (Method::POST, "/api/health", None),
+            (Method::GET, "/api/positions", Some(Role::Observer)),
+            (Method::HEAD, "/api/positions", Some(Role::Observer)),
+            (Method::OPTIONS, "/api/strategies", Some(Role::Observer)),
+            (Method::GET, "/api/state/export", Some(Role::Admin)),
+            (Method::POST, "/api/analyze", Some(Role::Observer)),
+            (Method::POST, "/api/strategies/match", Some(Role::Observer)),
+            (Method::POST, "/api/backtest", Some(Role::Observer)),
+            (Method::PUT, "/api/analyze", Some(Role::Admin)), // Only POST is read-only
+            (Method::POST, "/api/strategies", Some(Role::Admin)),
+            (Method::POST, "/api/wallet/sweep", Some(Role::Admin)),
+            (Method::DELETE, "/api/strategies/abc", Some(Role::Admin)),
+            (Method::PATCH, "/api/autotrader/settings", Some(Role::Admin)),
+            (Method::GET, "/api/copy/nonce", None),
+            (Method::POST, "/api/copy/register", None),
+            (Method::DELETE, "/api/copy/register", None),
+            (Method::PUT, "/api/copy/settings", Some(Role::Admin)),
+        ];
+        for (method, path, role) in cases {
+            assert_eq!(required_role(&method, path), role, "{} {}", method, path);
+        }
+    }
+
+    #[test]
+    fn query_token_is_percent_decoded() {
+        assert_eq!(extract_token(&get("/ws?token=a%2Bb%3D%3D&x=1")).as_deref(), Some("a+b=="));
+        assert_eq!(extract_token(&get("/ws?x=1")), None);
+    }
+
+    #[test]
+    fn static_keys_only_match_exactly() {
+        assert!(key_matches(Some("secret"), "secret"));
+        assert!(!key_matches(Some("secret"), "secreT"));
+        assert!(!key_matches(Some("secret"), "secret2"));
+        assert!(!key_matches(Some("secret"), ""));
+        assert!(!key_matches(None, "secret"));
+    }
+
+    fn jwt(secret: &str, scope: &str, exp: i64) -> String {
+        encode(&Header::default(), &json!({ "sub": "dashboard", "scope": scope, "exp": exp }), &EncodingKey::from_secret(secret.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn jwt_scope_picks_role_and_bad_tokens_are_rejected() {
+        let later = chrono::Utc::now().timestamp() + 3600;
+        assert_eq!(jwt_role(&jwt("secret", "trade", later), "secret"), Some(Role::Admin));
+        assert_eq!(jwt_role(&jwt("secret", "read", later), "secret"), Some(Role::Observer));
+        assert_eq!(jwt_role(&jwt("secret", "superuser", later), "secret"), None);
+        assert_eq!(jwt_role(&jwt("other", "trade", later), "secret"), None);
+        assert_eq!(jwt_role(&jwt("secret", "trade", later - 7200), "secret"), None);
+        assert_eq!(jwt_role("not-a-jwt", "secret"), None);
+    }
+}
@@ -0,0 +1,219 @@
+//! Source wallet watcher for copy trading
+//!
+//! Polls the transaction history of registered external "source" wallets, picks
+//! out their swaps by comparing the wallet's SOL and token balances before and
+//! after each transaction, and turns them into `TradeSignal`s that followers can
+//! copy like the bot's own signals.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, UiMessage, UiTransactionTokenBalance,
+};
+use tokio::time::{interval, Duration};
+use tracing::{debug, info, warn};
+
+use crate::api::jupiter::SOL_MINT;
+use crate::models::copy_trade::TradeAction;
+use crate::solana::client::SolanaClient;
+
+use super::copy_trade::CopyTradeManager;
+
+/// Most signatures fetched per wallet per poll
+const MAX_SIGNATURES_PER_POLL: usize = 25;
+
+/// Token balance changes smaller than this are rounding noise, not trades
+const MIN_TOKEN_CHANGE: f64 = 1e-9;
+
+/// A swap made by a source wallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedSwap {
+    pub token_address: String,
+    pub action: TradeAction,
+    pub sol_amount: f64,
+    pub token_amount: f64,
+}
+
+impl DetectedSwap {
+    pub fn price_sol(&self) -> f64 {
+        if self.token_amount > 0.0 { self.sol_amount / self.token_amount } else { 0.0 }
+    }
+}
+
+/// Classify a wallet's balance changes as a swap. Only transactions that move
+/// exactly one non-SOL token against SOL in the opposite direction count; transfers,
+/// airdrops and token-to-token routes are ignored. `sol_change` includes wrapped SOL.
+pub fn classify_swap(sol_change: f64, token_changes: &[(String, f64)], min_sol: f64) -> Option<DetectedSwap> {
+    let changed: Vec<&(String, f64)> = token_changes.iter()
+        .filter(|(mint, change)| mint != SOL_MINT && change.abs() > MIN_TOKEN_CHANGE)
+        .collect();
+    let [(mint, token_change)] = changed.as_slice() else {
+        return None;
+    };
+    if sol_change.abs() < min_sol {
+        return None;
+    }
+
+    let action = match (*token_change > 0.0, sol_change < 0.0) {
+        (true, true) => TradeAction::Buy,
+        (false, false) => TradeAction::Sell,
+        _ => return None,
+    };
+    Some(DetectedSwap {
+        token_address: mint.clone(),
+        action,
+        sol_amount: sol_change.abs(),
+        token_amount: token_change.abs(),
+    })
+}
+
+/// Sum a wallet's token balances per mint
+fn owner_balances(balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>, owner: &str) -> HashMap<String, f64> {
+    let mut totals = HashMap::new();
+    if let OptionSerializer::Some(balances) = balances {
+        for balance in balances {
+            if matches!(&balance.owner, OptionSerializer::Some(o) if o == owner) {
+                *totals.entry(balance.mint.clone()).or_insert(0.0) += balance.ui_token_amount.ui_amount.unwrap_or(0.0);
+            }
+        }
+    }
+    totals
+}
+
+/// Extract the swap (if any) a wallet made in a transaction
+pub fn extract_swap(wallet: &str, tx: &EncodedConfirmedTransactionWithStatusMeta, min_sol: f64) -> Option<DetectedSwap> {
+    let meta = tx.transaction.meta.as_ref()?;
+    if meta.err.is_some() {
+        return None;
+    }
+    let EncodedTransaction::Json(ui_tx) = &tx.transaction.transaction else {
+        return None;
+    };
+    let account_keys: Vec<&str> = match &ui_tx.message {
+        UiMessage::Raw(raw) => raw.account_keys.iter().map(String::as_str).collect(),
+        UiMessage::Parsed(parsed) => parsed.account_keys.iter().map(|a| a.pubkey.as_str()).collect(),
+    };
+    let index = account_keys.iter().position(|k| *k == wallet)?;
+
+    // The fee payer's SOL change includes the fee; exclude it so the amount is the swap itself
+    let fee = if index == 0 { meta.fee as i64 } else { 0 };
+    let lamport_change = *meta.post_balances.get(index)? as i64 - *meta.pre_balances.get(index)? as i64 + fee;
+
+    let pre = owner_balances(&meta.pre_token_balances, wallet);
+    let post = owner_balances(&meta.post_token_balances, wallet);
+    let mut token_changes: Vec<(String, f64)> = post.iter()
+        .map(|(mint, after)| (mint.clone(), after - pre.get(mint).copied().unwrap_or(0.0)))
+        .collect();
+    for (mint, before) in &pre {
+        if !post.contains_key(mint) {
+            token_changes.push((mint.clone(), -before));
+        }
+    }
+    let wsol_change: f64 = token_changes.iter().filter(|(m, _)| m == SOL_MINT).map(|(_, c)| c).sum();
+
+    classify_swap(lamport_change as f64 / 1_000_000_000.0 + wsol_change, &token_changes, min_sol)
+}
+
+/// Signatures newer than the wallet's cursor, oldest first. The first poll of a
+/// wallet only sets the cursor so its history isn't replayed as fresh signals.
+async fn new_signatures(solana_client: &SolanaClient, wallet: &str, cursor: Option<&str>) -> Result<Vec<String>> {
+    let pubkey = Pubkey::from_str(wallet).context("Invalid source wallet address")?;
+    let config = GetConfirmedSignaturesForAddress2Config {
+        before: None,
+        until: cursor.map(Signature::from_str).transpose().context("Invalid cursor signature")?,
+        limit: Some(if cursor.is_some() { MAX_SIGNATURES_PER_POLL } else { 1 }),
+        commitment: Some(CommitmentConfig::confirmed()),
+    };
+    let statuses = solana_client.get_rpc()
+        .get_signatures_for_address_with_config(&pubkey, config)
+        .await
+        .context("Failed to fetch source wallet signatures")?;
+
+    Ok(statuses.into_iter()
+        .rev()
+        .filter(|s| s.err.is_none())
+        .map(|s| s.signature)
+        .collect())
+}
+
+async fn poll_wallet(manager: &CopyTradeManager, solana_client: &SolanaClient, wallet: &str, cursor: Option<String>, min_sol: f64) -> Result<()> {
+    let signatures = new_signatures(solana_client, wallet, cursor.as_deref()).await?;
+    let Some(newest) = signatures.last().cloned() else {
+        return Ok(());
+    };
+
+    if cursor.is_some() {
+        for signature in &signatures {
+            let sig = Signature::from_str(signature).context("Invalid signature from RPC")?;
+            let tx = match solana_client.get_transaction(&sig, CommitmentConfig::confirmed()).await {
+                Ok(tx) => tx,
+                Err(e) => {
+                    warn!("Failed to fetch source wallet transaction {}: {}", signature, e);
+                    continue;
+                }
+            };
+            if let Some(swap) = extract_swap(wallet, &tx, min_sol) {
+                manager.record_source_trade(wallet, &swap).await;
+            } else {
+                debug!("Source wallet {} tx {} is not a swap", wallet, signature);
+            }
+        }
+    }
+
+    manager.set_source_cursor(wallet, &newest).await
+}
+
+/// Poll every source wallet on an interval until the process exits
+pub fn spawn_source_wallet_watcher(manager: Arc<CopyTradeManager>, solana_client: Arc<SolanaClient>, poll_secs: u64, min_sol: f64) {
+    info!("Starting copy-trade source wallet watcher (every {}s)", poll_secs);
+    tokio::spawn(async move {
+        let mut timer = interval(Duration::from_secs(poll_secs));
+        timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            timer.tick().await;
+            for source in manager.list_source_wallets().await {
+                if let Err(e) = poll_wallet(&manager, &solana_client, &source.address, source.last_seen_signature, min_sol).await {
+                    warn!("Failed to poll source wallet {}: {:?}", source.address, e);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINT: &str = "TokenMint111111111111111111111111111111111";
+
+    #[test]
+    fn classifies_buy_and_sell() {
+        let buy = classify_swap(-0.5, &[(MINT.to_string(), 1000.0)], 0.01).unwrap();
+        assert_eq!(buy.action, TradeAction::Buy);
+        assert_eq!(buy.sol_amount, 0.5);
+        assert_eq!(buy.price_sol(), 0.0005);
+
+        let sell = classify_swap(0.7, &[(MINT.to_string(), -1000.0)], 0.01).unwrap();
+        assert_eq!(sell.action, TradeAction::Sell);
+    }
+
+    #[test]
+    fn ignores_transfers_dust_and_multi_token_moves() {
+        // Tokens received without paying SOL (airdrop/transfer)
+        assert!(classify_swap(0.0, &[(MINT.to_string(), 1000.0)], 0.01).is_none());
+        // Below the minimum SOL size
+        assert!(classify_swap(-0.001, &[(MINT.to_string(), 10.0)], 0.01).is_none());
+        // Token-to-token route
+        assert!(classify_swap(-0.5, &[(MINT.to_string(), 10.0), ("Other".to_string(), -5.0)], 0.01).is_none());
+        // Wrapped SOL alone isn't a token trade
+        assert!(classify_swap(-0.5, &[(SOL_MINT.to_string(), 0.5)], 0.01).is_none());
+    }
+}
@@ -7,25 +7,60 @@
 //! - Fee calculation and collection
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use anyhow::{anyhow, Context, Result};
-use chrono::Utc;
-use tokio::fs;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
 use tokio::sync::RwLock;
+use uuid::Uuid;
 use tracing::{debug, error, info, warn};
 
 use crate::config::Config;
 use crate::models::copy_trade::{
-    CopyPosition, CopyPositionStatus, CopyTradeSettings, CopyTradeStats, CopyTrader,
-    TradeAction, TradeSignal,
+    CopyPosition, CopyPositionStatus, CopyTradeSettings, CopyTradeState, CopyTradeStats, CopyTrader,
+    SourceWallet, TradeAction, TradeSignal,
 };
+use crate::storage::Store;
 use crate::trading::position::Position;
 
-const COPY_TRADERS_FILE: &str = "data/copy_traders.json";
-const SIGNALS_FILE: &str = "data/signals.json";
-const COPY_POSITIONS_FILE: &str = "data/copy_positions.json";
+use super::copy_sources::DetectedSwap;
+
+/// Signals kept when saving, to prevent unbounded growth
+const MAX_SAVED_SIGNALS: usize = 1000;
+/// How long an issued nonce can be signed and used
+const NONCE_TTL_SECS: i64 = 300;
+
+/// A one-time message a wallet signs to prove it owns the address
+#[derive(Debug, Clone)]
+pub struct WalletNonce {
+    pub nonce: String,
+    pub message: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+fn nonce_message(wallet_address: &str, nonce: &str, issued_at: DateTime<Utc>) -> String {
+    format!(
+        "TraderTony V4 Copy Trade Registration\nWallet: {}\nNonce: {}\nIssued: {}",
+        wallet_address, nonce, issued_at.to_rfc3339()
+    )
+}
+
+/// Check that `signature` (base58 or base64) is `wallet_address`'s ed25519
+/// signature of `message`
+pub fn verify_wallet_signature(wallet_address: &str, message: &str, signature: &str) -> Result<()> {
+    let pubkey = Pubkey::from_str(wallet_address).map_err(|_| anyhow!("Invalid wallet address"))?;
+    let signature = Signature::from_str(signature).ok()
+        .or_else(|| STANDARD.decode(signature).ok().and_then(|bytes| Signature::try_from(bytes.as_slice()).ok()))
+        .ok_or_else(|| anyhow!("Signature is not a base58 or base64 ed25519 signature"))?;
+    if !signature.verify(pubkey.as_ref(), message.as_bytes()) {
+        return Err(anyhow!("Signature was not made by wallet {}", wallet_address));
+    }
+    Ok(())
+}
 
 /// Manages all copy trading functionality
 pub struct CopyTradeManager {
@@ -35,8 +70,14 @@ pub struct CopyTradeManager {
     signals: Arc<RwLock<Vec<TradeSignal>>>,
     /// Copy positions by copier wallet
     copy_positions: Arc<RwLock<HashMap<String, Vec<CopyPosition>>>>,
+    /// External wallets whose trades generate signals, by address
+    source_wallets: Arc<RwLock<HashMap<String, SourceWallet>>>,
     /// Configuration
     config: Arc<Config>,
+    /// Where copy-trade state is persisted (JSON files or SQLite, as positions)
+    store: Arc<dyn Store>,
+    /// Unused registration nonces by wallet address
+    nonces: Arc<RwLock<HashMap<String, WalletNonce>>>,
     /// Treasury wallet for fee collection
     treasury_wallet: String,
     /// Fee percentage (e.g., 10.0 for 10%)
@@ -44,7 +85,7 @@ pub struct CopyTradeManager {
 }
 
 impl CopyTradeManager {
-    pub fn new(config: Arc<Config>) -> Self {
+    pub fn new(config: Arc<Config>, store: Arc<dyn Store>) -> Self {
         let treasury_wallet = config
             .treasury_wallet
             .clone()
@@ -55,22 +96,31 @@ impl CopyTradeManager {
             traders: Arc::new(RwLock::new(HashMap::new())),
             signals: Arc::new(RwLock::new(Vec::new())),
             copy_positions: Arc::new(RwLock::new(HashMap::new())),
+            source_wallets: Arc::new(RwLock::new(HashMap::new())),
             config,
+            store,
+            nonces: Arc::new(RwLock::new(HashMap::new())),
             treasury_wallet,
             fee_percent,
         }
     }
 
-    /// Initialize and load data from disk
+    /// Initialize and load persisted state, so copiers' open positions and fee
+    /// accounting survive a restart
     pub async fn init(&self) -> Result<()> {
-        info!("Initializing CopyTradeManager...");
-        self.load_traders().await?;
-        self.load_signals().await?;
-        self.load_copy_positions().await?;
+        info!("Initializing CopyTradeManager from {}...", self.store.describe());
+        let state = self.store.load_copy_trade_state().await
+            .context("Failed to load copy-trade state")?;
+        self.replace_state(state).await;
+        let open_copy_positions = self.copy_positions.read().await.values()
+            .flatten()
+            .filter(|p| p.status == CopyPositionStatus::Open)
+            .count();
         info!(
-            "CopyTradeManager initialized: {} traders, {} signals",
+            "CopyTradeManager initialized: {} traders, {} signals, {} open copy positions",
             self.traders.read().await.len(),
-            self.signals.read().await.len()
+            self.signals.read().await.len(),
+            open_copy_positions
         );
         Ok(())
     }
@@ -79,15 +129,49 @@ impl CopyTradeManager {
     // Trader Management
     // ==========================================================================
 
-    /// Register a new copy trader
+    /// Issue a message for `wallet_address` to sign, replacing any earlier one
+    pub async fn issue_nonce(&self, wallet_address: &str) -> Result<WalletNonce> {
+        Pubkey::from_str(wallet_address).map_err(|_| anyhow!("Invalid wallet address"))?;
+        let now = Utc::now();
+        let nonce = Uuid::new_v4().simple().to_string();
+        let issued = WalletNonce {
+            message: nonce_message(wallet_address, &nonce, now),
+            nonce,
+            expires_at: now + Duration::seconds(NONCE_TTL_SECS),
+        };
+
+        let mut nonces = self.nonces.write().await;
+        nonces.retain(|_, n| n.expires_at > now);
+        nonces.insert(wallet_address.to_string(), issued.clone());
+        Ok(issued)
+    }
+
+    /// Check that `message` is the nonce message issued to `wallet_address` and
+    /// that the wallet signed it. The nonce is used up on success.
+    async fn verify_ownership(&self, wallet_address: &str, signature: &str, message: &str) -> Result<()> {
+        let mut nonces = self.nonces.write().await;
+        let issued = nonces.get(wallet_address)
+            .filter(|n| n.expires_at > Utc::now())
+            .ok_or_else(|| anyhow!("No unexpired nonce for this wallet, request one from /api/copy/nonce"))?;
+        if issued.message != message {
+            return Err(anyhow!("Signed message is not the nonce message issued to this wallet"));
+        }
+        verify_wallet_signature(wallet_address, message, signature)?;
+        nonces.remove(wallet_address);
+        Ok(())
+    }
+
+    /// Register a new copy trader, who must have signed a nonce from `issue_nonce`
     pub async fn register_trader(
         &self,
         wallet_address: &str,
-        _signature: &str,
-        _message: &str,
+        signature: &str,
+        message: &str,
     ) -> Result<CopyTrader> {
-        // TODO: Verify the signature matches the message and wallet
-        // For now, we trust the frontend verification
+        if self.traders.read().await.contains_key(wallet_address) {
+            return Err(anyhow!("Wallet already registered"));
+        }
+        self.verify_ownership(wallet_address, signature, message).await?;
 
         let mut traders = self.traders.write().await;
 
@@ -99,14 +183,19 @@ impl CopyTradeManager {
         traders.insert(wallet_address.to_string(), trader.clone());
         drop(traders);
 
-        self.save_traders().await?;
+        self.save_state().await?;
         info!("Registered new copy trader: {}", wallet_address);
 
         Ok(trader)
     }
 
-    /// Unregister a copy trader
-    pub async fn unregister_trader(&self, wallet_address: &str) -> Result<()> {
+    /// Unregister a copy trader, who must have signed a nonce from `issue_nonce`
+    pub async fn unregister_trader(&self, wallet_address: &str, signature: &str, message: &str) -> Result<()> {
+        if !self.traders.read().await.contains_key(wallet_address) {
+            return Err(anyhow!("Wallet not registered"));
+        }
+        self.verify_ownership(wallet_address, signature, message).await?;
+
         let mut traders = self.traders.write().await;
 
         if traders.remove(wallet_address).is_none() {
@@ -114,7 +203,7 @@ impl CopyTradeManager {
         }
         drop(traders);
 
-        self.save_traders().await?;
+        self.save_state().await?;
         info!("Unregistered copy trader: {}", wallet_address);
 
         Ok(())
@@ -147,7 +236,7 @@ impl CopyTradeManager {
         let updated_trader = trader.clone();
         drop(traders);
 
-        self.save_traders().await?;
+        self.save_state().await?;
         info!(
             "Updated settings for trader {}: auto_copy={}, amount={}",
             wallet_address, settings.auto_copy_enabled, settings.copy_amount_sol
@@ -166,6 +255,95 @@ impl CopyTradeManager {
             .collect()
     }
 
+    // ==========================================================================
+    // Source Wallets
+    // ==========================================================================
+
+    /// Start watching an external wallet's trades
+    pub async fn add_source_wallet(&self, address: &str, label: Option<String>) -> Result<SourceWallet> {
+        Pubkey::from_str(address).map_err(|_| anyhow!("Invalid wallet address"))?;
+
+        let mut sources = self.source_wallets.write().await;
+        if sources.contains_key(address) {
+            return Err(anyhow!("Source wallet already registered"));
+        }
+        let source = SourceWallet::new(address, label);
+        sources.insert(address.to_string(), source.clone());
+        drop(sources);
+
+        self.save_state().await?;
+        info!("Added copy-trade source wallet: {}", address);
+        Ok(source)
+    }
+
+    /// Stop watching an external wallet
+    pub async fn remove_source_wallet(&self, address: &str) -> Result<()> {
+        if self.source_wallets.write().await.remove(address).is_none() {
+            return Err(anyhow!("Source wallet not registered"));
+        }
+        self.save_state().await?;
+        info!("Removed copy-trade source wallet: {}", address);
+        Ok(())
+    }
+
+    pub async fn list_source_wallets(&self) -> Vec<SourceWallet> {
+        self.source_wallets.read().await.values().cloned().collect()
+    }
+
+    /// Remember the newest processed transaction for a source wallet
+    pub async fn set_source_cursor(&self, address: &str, signature: &str) -> Result<()> {
+        {
+            let mut sources = self.source_wallets.write().await;
+            let Some(source) = sources.get_mut(address) else {
+                return Ok(()); // Removed while being polled
+            };
+            if source.last_seen_signature.as_deref() == Some(signature) {
+                return Ok(());
+            }
+            source.last_seen_signature = Some(signature.to_string());
+        }
+        self.save_state().await
+    }
+
+    /// Turn a source wallet's swap into a trade signal
+    pub async fn record_source_trade(&self, address: &str, swap: &DetectedSwap) -> TradeSignal {
+        let position_key = TradeSignal::source_position_key(address, &swap.token_address);
+        let symbol: String = swap.token_address.chars().take(6).collect();
+        let mut signal = match swap.action {
+            TradeAction::Buy => TradeSignal::new_buy(
+                &swap.token_address, &symbol, &symbol, swap.sol_amount, swap.price_sol(), &position_key,
+            ),
+            TradeAction::Sell => TradeSignal::new_sell(
+                &swap.token_address, &symbol, &symbol, swap.sol_amount, swap.price_sol(), 0.0, &position_key,
+            ),
+        };
+        signal.source_wallet = Some(address.to_string());
+
+        {
+            let mut signals = self.signals.write().await;
+            if swap.action == TradeAction::Sell {
+                for s in signals.iter_mut() {
+                    if s.bot_position_id == position_key && s.action == TradeAction::Buy {
+                        s.is_active = false;
+                    }
+                }
+            }
+            signals.push(signal.clone());
+        }
+        if let Some(source) = self.source_wallets.write().await.get_mut(address) {
+            source.signals_generated += 1;
+        }
+
+        if let Err(e) = self.save_state().await {
+            error!("Failed to save signals: {}", e);
+        }
+        info!(
+            "Created {} signal from source wallet {} for {} ({:.4} SOL)",
+            swap.action, address, swap.token_address, swap.sol_amount
+        );
+        signal
+    }
+
     // ==========================================================================
     // Signal Management
     // ==========================================================================
@@ -185,7 +363,7 @@ impl CopyTradeManager {
         signals.push(signal.clone());
         drop(signals);
 
-        if let Err(e) = self.save_signals().await {
+        if let Err(e) = self.save_state().await {
             error!("Failed to save signals: {}", e);
         }
 
@@ -225,7 +403,7 @@ impl CopyTradeManager {
             signals.push(signal.clone());
         }
 
-        if let Err(e) = self.save_signals().await {
+        if let Err(e) = self.save_state().await {
             error!("Failed to save signals: {}", e);
         }
 
@@ -319,8 +497,7 @@ impl CopyTradeManager {
             }
         }
 
-        self.save_copy_positions().await?;
-        self.save_traders().await?;
+        self.save_state().await?;
 
         info!(
             "Created copy position {} for {} copying {}",
@@ -379,8 +556,7 @@ impl CopyTradeManager {
                     }
                 }
 
-                self.save_copy_positions().await?;
-                self.save_traders().await?;
+                self.save_state().await?;
 
                 info!(
                     "Closed copy position {} - PnL: {:?} SOL, Fee: {:?} SOL",
@@ -529,131 +705,65 @@ impl CopyTradeManager {
     // Persistence
     // ==========================================================================
 
-    async fn ensure_data_dir(&self) -> Result<()> {
-        let path = PathBuf::from("data");
-        if !path.exists() {
-            fs::create_dir_all(&path).await?;
-        }
-        Ok(())
-    }
-
-    async fn load_traders(&self) -> Result<()> {
-        let path = PathBuf::from(COPY_TRADERS_FILE);
-        if !path.exists() {
-            debug!("No traders file found, starting fresh");
-            return Ok(());
-        }
-
-        let data = fs::read_to_string(&path).await?;
-        if data.trim().is_empty() {
-            return Ok(());
-        }
-
-        let traders: Vec<CopyTrader> = serde_json::from_str(&data)
-            .context("Failed to parse traders file")?;
-
-        let mut traders_map = self.traders.write().await;
-        for trader in traders {
-            traders_map.insert(trader.wallet_address.clone(), trader);
+    /// Snapshot all copy-trade data for a state export
+    pub async fn export_state(&self) -> CopyTradeState {
+        CopyTradeState {
+            traders: self.traders.read().await.values().cloned().collect(),
+            signals: self.signals.read().await.clone(),
+            copy_positions: self.copy_positions.read().await.clone(),
+            source_wallets: self.source_wallets.read().await.values().cloned().collect(),
         }
-
-        info!("Loaded {} copy traders", traders_map.len());
-        Ok(())
     }
 
-    async fn save_traders(&self) -> Result<()> {
-        self.ensure_data_dir().await?;
-
-        let traders = self.traders.read().await;
-        let traders_vec: Vec<&CopyTrader> = traders.values().collect();
-        let data = serde_json::to_string_pretty(&traders_vec)?;
-
-        let temp_path = PathBuf::from(COPY_TRADERS_FILE).with_extension("json.tmp");
-        fs::write(&temp_path, data).await?;
-        fs::rename(&temp_path, COPY_TRADERS_FILE).await?;
-
-        debug!("Saved {} copy traders", traders.len());
-        Ok(())
-    }
-
-    async fn load_signals(&self) -> Result<()> {
-        let path = PathBuf::from(SIGNALS_FILE);
-        if !path.exists() {
-            debug!("No signals file found, starting fresh");
-            return Ok(());
-        }
-
-        let data = fs::read_to_string(&path).await?;
-        if data.trim().is_empty() {
-            return Ok(());
-        }
-
-        let signals: Vec<TradeSignal> = serde_json::from_str(&data)
-            .context("Failed to parse signals file")?;
-
-        let mut signals_vec = self.signals.write().await;
-        *signals_vec = signals;
-
-        info!("Loaded {} trade signals", signals_vec.len());
+    /// Replace all copy-trade data with an imported snapshot and persist it
+    pub async fn import_state(&self, state: CopyTradeState) -> Result<()> {
+        self.replace_state(state).await;
+        self.save_state().await?;
+        info!("Imported copy-trade state");
         Ok(())
     }
 
-    async fn save_signals(&self) -> Result<()> {
-        self.ensure_data_dir().await?;
-
-        let signals = self.signals.read().await;
-        // Only keep last 1000 signals to prevent unbounded growth
-        let signals_to_save: Vec<&TradeSignal> = if signals.len() > 1000 {
-            signals.iter().rev().take(1000).collect()
-        } else {
-            signals.iter().collect()
-        };
-
-        let data = serde_json::to_string_pretty(&signals_to_save)?;
-
-        let temp_path = PathBuf::from(SIGNALS_FILE).with_extension("json.tmp");
-        fs::write(&temp_path, data).await?;
-        fs::rename(&temp_path, SIGNALS_FILE).await?;
-
-        debug!("Saved {} trade signals", signals_to_save.len());
-        Ok(())
+    async fn replace_state(&self, state: CopyTradeState) {
+        *self.traders.write().await = state.traders.into_iter()
+            .map(|t| (t.wallet_address.clone(), t))
+            .collect();
+        *self.signals.write().await = state.signals;
+        *self.copy_positions.write().await = state.copy_positions;
+        *self.source_wallets.write().await = state.source_wallets.into_iter()
+            .map(|s| (s.address.clone(), s))
+            .collect();
     }
 
-    async fn load_copy_positions(&self) -> Result<()> {
-        let path = PathBuf::from(COPY_POSITIONS_FILE);
-        if !path.exists() {
-            debug!("No copy positions file found, starting fresh");
-            return Ok(());
-        }
-
-        let data = fs::read_to_string(&path).await?;
-        if data.trim().is_empty() {
-            return Ok(());
-        }
-
-        let positions: HashMap<String, Vec<CopyPosition>> = serde_json::from_str(&data)
-            .context("Failed to parse copy positions file")?;
-
-        let mut positions_map = self.copy_positions.write().await;
-        *positions_map = positions;
-
-        let total: usize = positions_map.values().map(|v| v.len()).sum();
-        info!("Loaded {} copy positions", total);
+    /// Persist all copy-trade data, keeping only the latest signals
+    async fn save_state(&self) -> Result<()> {
+        let mut state = self.export_state().await;
+        let excess = state.signals.len().saturating_sub(MAX_SAVED_SIGNALS);
+        state.signals.drain(..excess);
+        self.store.save_copy_trade_state(&state).await?;
+        debug!("Saved copy-trade state to {}", self.store.describe());
         Ok(())
     }
+}
 
-    async fn save_copy_positions(&self) -> Result<()> {
-        self.ensure_data_dir().await?;
-
-        let positions = self.copy_positions.read().await;
-        let data = serde_json::to_string_pretty(&*positions)?;
-
-        let temp_path = PathBuf::from(COPY_POSITIONS_FILE).with_extension("json.tmp");
-        fs::write(&temp_path, data).await?;
-        fs::rename(&temp_path, COPY_POSITIONS_FILE).await?;
-
-        let total: usize = positions.values().map(|v| v.len()).sum();
-        debug!("Saved {} copy positions", total);
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    #[test]
+    fn only_the_wallets_own_signature_verifies() {
+        let wallet = Keypair::new();
+        let address = wallet.pubkey().to_string();
+        let message = nonce_message(&address, "abc123", Utc::now());
+        let signature = wallet.sign_message(message.as_bytes());
+
+        // Phantom's signMessage result arrives base64 encoded; CLI tools use base58
+        assert!(verify_wallet_signature(&address, &message, &signature.to_string()).is_ok());
+        assert!(verify_wallet_signature(&address, &message, &STANDARD.encode(signature.as_ref())).is_ok());
+
+        let forged = Keypair::new().sign_message(message.as_bytes()).to_string();
+        assert!(verify_wallet_signature(&address, &message, &forged).is_err());
+        assert!(verify_wallet_signature(&address, "another message", &signature.to_string()).is_err());
+        assert!(verify_wallet_signature(&address, &message, "garbage").is_err());
     }
 }
@@ -1,27 +1,65 @@
 //! Request handlers for all API endpoints
 
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    Extension,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use chrono::Utc;
-use tracing::{error, info, warn};
+use solana_sdk::pubkey::Pubkey;
+use tracing::{debug, error, info, warn};
 
+use super::auth::{actor_name, Role};
 use super::models::*;
+use super::state_bundle::{self, StateBundle, STATE_BUNDLE_VERSION};
 use super::websocket::WsMessage;
 use super::AppState;
+use crate::config::ConfigReload;
 use crate::models::copy_trade::CopyTradeSettings;
-use crate::trading::strategy::Strategy;
+use crate::trading::accounting;
+use crate::trading::autotrader::{ScanSettings, StrategyMatchReport};
+use crate::trading::backtest::{self, BacktestReport};
+use crate::trading::dust_sweep::DustSweepReport;
+use crate::trading::limit_orders::{LimitOrder, LimitOrderStatus};
+use crate::trading::pnl_report::{self, PnlReport, ReportPeriod};
+use crate::trading::position::{ExitPreview, PositionStatus};
+use crate::trading::position_history::PositionEventKind;
+use crate::trading::risk::{net_of_transfer_tax, round_trip_loss_percent};
+use crate::trading::strategy::{Strategy, DEFAULT_ENTRY_RETRY_DELAY_MS, STRATEGY_TEMPLATES};
+use crate::trading::test_swap::TestSwapReport;
 
 // ============================================================================
 // Health Check
 // ============================================================================
 
-pub async fn health_check() -> Json<HealthResponse> {
+/// Liveness plus the latest RPC slot-lag check and per-endpoint probe results;
+/// "degraded" while the RPC lags
+pub async fn health_check(State(state): State<AppState>) -> Json<HealthResponse> {
+    let slot_lag = state.solana_client.slot_lag_status();
+    let rpc_lagging = slot_lag.is_some_and(|s| s.lagging);
     Json(HealthResponse {
-        status: "ok".to_string(),
+        status: if rpc_lagging { "degraded" } else { "ok" }.to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
+        rpc_slot: slot_lag.map(|s| s.slot),
+        rpc_slot_lag: slot_lag.map(|s| s.lag_slots),
+        rpc_lagging,
+        rpc_endpoints: state.solana_client.endpoint_health().into_iter()
+            .map(|e| RpcEndpointHealth {
+                host: e.host,
+                active: e.active,
+                latency_ms: e.latency_ms,
+                probes: e.probes,
+                errors: e.errors,
+                consecutive_errors: e.consecutive_errors,
+                last_slot: e.last_slot,
+                last_error: e.last_error,
+            })
+            .collect(),
         timestamp: Utc::now(),
     })
 }
@@ -35,9 +73,9 @@ pub async fn get_wallet(
 ) -> Result<Json<WalletResponse>, (StatusCode, Json<ErrorResponse>)> {
     let address = state.wallet_manager.get_public_key().to_string();
 
-    // Get SOL balance
-    let balance_sol = match state.solana_client.get_sol_balance(&state.wallet_manager.get_public_key()).await {
-        Ok(balance) => balance,
+    // Get SOL balance of every wallet in the pool
+    let balances = match state.wallet_pool.balances().await {
+        Ok(balances) => balances,
         Err(e) => {
             error!("Failed to get wallet balance: {}", e);
             return Err((
@@ -50,18 +88,140 @@ pub async fn get_wallet(
         }
     };
 
-    Ok(Json(WalletResponse { address, balance_sol }))
+    let balance_sol: f64 = balances.iter().map(|(_, b)| b).sum();
+    let wallets = balances
+        .into_iter()
+        .map(|(address, balance_sol)| WalletBalanceResponse { address, balance_sol })
+        .collect();
+
+    Ok(Json(WalletResponse { address, balance_sol, wallets }))
+}
+
+/// Sweep orphaned tokens now: sell dust, burn/close per the dust sweep settings
+pub async fn sweep_wallet(
+    State(state): State<AppState>,
+) -> Result<Json<DustSweepReport>, (StatusCode, Json<ErrorResponse>)> {
+    if state.config.demo_mode {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Wallet sweep not run".to_string(),
+                details: Some("Demo mode has no on-chain token accounts to sweep".to_string()),
+            }),
+        ));
+    }
+    // Don't hold the trader lock for the whole sweep
+    let dust_sweeper = state.auto_trader.lock().await.dust_sweeper.clone();
+    Ok(Json(dust_sweeper.sweep().await))
+}
+
+/// Wallet SOL, open positions and total equity in SOL and USD
+pub async fn get_portfolio(
+    State(state): State<AppState>,
+) -> Result<Json<PortfolioResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let balances = state.wallet_pool.balances().await.map_err(|e| {
+        error!("Failed to get wallet balance: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Failed to get wallet balance".to_string(),
+                details: Some(e.to_string()),
+            }),
+        )
+    })?;
+    let (position_manager, risk_analyzer) = {
+        let auto_trader = state.auto_trader.lock().await;
+        (auto_trader.position_manager.clone(), auto_trader.risk_analyzer.clone())
+    };
+    let open_positions: Vec<_> = position_manager.get_all_positions().await
+        .into_iter()
+        .filter(|p| matches!(p.status, PositionStatus::Active | PositionStatus::Closing))
+        .collect();
+
+    // One token-account listing per wallet holding a real position
+    let primary = state.wallet_manager.get_public_key().to_string();
+    let owners: HashSet<String> = open_positions.iter()
+        .filter(|p| !p.is_demo)
+        .map(|p| p.wallet_address.clone().unwrap_or_else(|| primary.clone()))
+        .collect();
+    let mut token_balances: HashMap<(String, String), f64> = HashMap::new();
+    for owner in owners {
+        let Ok(owner_key) = Pubkey::from_str(&owner) else { continue };
+        match state.solana_client.get_token_holdings(&owner_key).await {
+            Ok(holdings) => {
+                for holding in holdings {
+                    *token_balances.entry((owner.clone(), holding.mint)).or_default() += holding.ui_amount;
+                }
+            }
+            Err(e) => warn!("Failed to list token balances of {}: {}", owner, e),
+        }
+    }
+
+    let positions: Vec<PortfolioPositionResponse> = open_positions.iter()
+        .map(|p| {
+            let wallet_address = p.wallet_address.clone().unwrap_or_else(|| primary.clone());
+            let value_sol = net_of_transfer_tax(p.entry_token_amount * p.current_price_sol, p.transfer_tax_percent);
+            let unrealized_pnl_sol = value_sol + p.realized_value_sol - p.entry_value_sol;
+            PortfolioPositionResponse {
+                id: p.id.clone(),
+                token_address: p.token_address.clone(),
+                token_symbol: p.token_symbol.clone(),
+                wallet_token_balance: (!p.is_demo)
+                    .then(|| token_balances.get(&(wallet_address.clone(), p.token_address.clone())).copied().unwrap_or(0.0)),
+                wallet_address,
+                token_amount: p.entry_token_amount,
+                price_sol: p.current_price_sol,
+                entry_value_sol: p.entry_value_sol,
+                value_sol,
+                unrealized_pnl_sol,
+                unrealized_pnl_percent: if p.entry_value_sol > 0.0 { unrealized_pnl_sol / p.entry_value_sol * 100.0 } else { 0.0 },
+            }
+        })
+        .collect();
+
+    let sol_balance: f64 = balances.iter().map(|(_, b)| b).sum();
+    let positions_value_sol: f64 = positions.iter().map(|p| p.value_sol).sum();
+    let total_equity_sol = sol_balance + positions_value_sol;
+    let sol_price_usd = risk_analyzer.live_sol_price_usd().await;
+    Ok(Json(PortfolioResponse {
+        sol_balance,
+        wallets: balances
+            .into_iter()
+            .map(|(address, balance_sol)| WalletBalanceResponse { address, balance_sol })
+            .collect(),
+        unrealized_pnl_sol: positions.iter().map(|p| p.unrealized_pnl_sol).sum(),
+        positions,
+        positions_value_sol,
+        total_equity_sol,
+        sol_price_usd,
+        total_equity_usd: sol_price_usd.map(|price| total_equity_sol * price),
+        timestamp: Utc::now(),
+    }))
 }
 
 // ============================================================================
 // Positions
 // ============================================================================
 
+/// Entry-value threshold below which positions are hidden (0.0 when `include_dust` is set)
+fn dust_threshold(state: &AppState, include_dust: Option<bool>) -> f64 {
+    if include_dust.unwrap_or(false) {
+        0.0
+    } else {
+        state.config.hide_positions_below_sol
+    }
+}
+
 pub async fn get_positions(
     State(state): State<AppState>,
+    Query(query): Query<DustQuery>,
 ) -> Result<Json<PositionsListResponse>, (StatusCode, Json<ErrorResponse>)> {
     let auto_trader = state.auto_trader.lock().await;
-    let positions = auto_trader.position_manager.get_all_positions().await;
+    let dust_threshold = dust_threshold(&state, query.include_dust);
+    let positions: Vec<_> = auto_trader.position_manager.get_all_positions().await
+        .into_iter()
+        .filter(|p| !p.is_dust(dust_threshold))
+        .collect();
 
     let position_responses: Vec<PositionResponse> = positions
         .iter()
@@ -86,6 +246,10 @@ pub async fn get_positions(
                 opened_at: p.entry_time,
                 closed_at: p.exit_time,
                 exit_reason: Some(format!("{}", p.status)),
+                profit_fee_sol: p.profit_fee_sol,
+                entry_value_usd: p.entry_value_usd,
+                exit_value_usd: p.exit_value_usd,
+                pnl_usd: p.pnl_usd,
             }
         })
         .collect();
@@ -98,11 +262,249 @@ pub async fn get_positions(
     }))
 }
 
+/// Quote closing a position now: net SOL out and the PnL it would realize
+pub async fn preview_position_exit(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ExitPreview>, (StatusCode, Json<ErrorResponse>)> {
+    // The sell quote can be slow; release the AutoTrader lock before it
+    let position_manager = state.auto_trader.lock().await.position_manager.clone();
+
+    if position_manager.get_position(&id).await.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Position not found".to_string(),
+                details: Some(format!("No position with ID {}", id)),
+            }),
+        ));
+    }
+
+    match position_manager.preview_exit(&id).await {
+        Ok(preview) => Ok(Json(preview)),
+        Err(e) => Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Cannot preview exit".to_string(),
+                details: Some(e.to_string()),
+            }),
+        )),
+    }
+}
+
+/// Closed positions moved to the archive (`POSITION_ARCHIVE_AFTER_DAYS`), latest exit first
+pub async fn get_archived_positions(
+    State(state): State<AppState>,
+    Query(query): Query<DustQuery>,
+) -> Result<Json<PositionsListResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+    let dust_threshold = dust_threshold(&state, query.include_dust);
+    let mut positions: Vec<_> = match auto_trader.position_manager.get_archived_positions().await {
+        Ok(positions) => positions.into_iter().filter(|p| !p.is_dust(dust_threshold)).collect(),
+        Err(e) => {
+            error!("Failed to read positions archive: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Failed to read positions archive".to_string(),
+                    details: Some(e.to_string()),
+                }),
+            ));
+        }
+    };
+    positions.sort_by(|a, b| b.exit_time.cmp(&a.exit_time));
+
+    // Archived positions are closed: their value is what the exit returned
+    let position_responses: Vec<PositionResponse> = positions
+        .iter()
+        .map(|p| PositionResponse {
+            id: p.id.clone(),
+            token_address: p.token_address.clone(),
+            token_name: p.token_name.clone(),
+            token_symbol: p.token_symbol.clone(),
+            strategy_id: p.strategy_id.clone(),
+            entry_value_sol: p.entry_value_sol,
+            current_value_sol: p.exit_value_sol,
+            token_amount: p.entry_token_amount,
+            entry_price: p.entry_price_sol,
+            current_price: p.exit_price_sol,
+            pnl_percent: p.pnl_percent,
+            pnl_sol: p.pnl_sol,
+            status: format!("{}", p.status),
+            opened_at: p.entry_time,
+            closed_at: p.exit_time,
+            exit_reason: Some(p.exit_reason()),
+            profit_fee_sol: p.profit_fee_sol,
+            entry_value_usd: p.entry_value_usd,
+            exit_value_usd: p.exit_value_usd,
+            pnl_usd: p.pnl_usd,
+        })
+        .collect();
+
+    let total = position_responses.len();
+
+    Ok(Json(PositionsListResponse {
+        positions: position_responses,
+        total,
+    }))
+}
+
+/// Tokens the loss rebuy guard currently keeps the bot from buying
+pub async fn get_loss_blacklist(
+    State(state): State<AppState>,
+) -> Result<Json<LossBlacklistResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+    let tokens = auto_trader.position_manager.loss_blacklist().await;
+
+    Ok(Json(LossBlacklistResponse {
+        mode: state.config.loss_rebuy_guard,
+        cooldown_minutes: state.config.loss_rebuy_cooldown_minutes,
+        tokens,
+    }))
+}
+
+/// Take a token off the loss blacklist so it can be bought again
+pub async fn clear_loss_blacklist_entry(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+
+    if auto_trader.position_manager.clear_losing_exit(&token).await {
+        info!("Loss blacklist entry for {} cleared via API", token);
+        Ok(Json(SuccessResponse {
+            success: true,
+            message: format!("{} can be bought again", token),
+        }))
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Token not on the loss blacklist".to_string(),
+                details: Some(format!("No losing exit recorded for {}", token)),
+            }),
+        ))
+    }
+}
+
+/// Sell a position on the next management cycle
+pub async fn close_position(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+
+    if auto_trader.position_manager.get_position(&id).await.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Position not found".to_string(),
+                details: Some(format!("No position with ID {}", id)),
+            }),
+        ));
+    }
+
+    match auto_trader.position_manager.request_close(&id).await {
+        Ok(position) => {
+            info!("Close requested for position {} via API", id);
+            Ok(Json(SuccessResponse {
+                success: true,
+                message: format!("Closing {} on the next management cycle", position.token_symbol),
+            }))
+        }
+        Err(e) => Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Cannot close position".to_string(),
+                details: Some(e.to_string()),
+            }),
+        )),
+    }
+}
+
+/// Best-effort cancel of a pending exit. Only works while the position is marked
+/// Closing and its sell hasn't been sent; a broadcast transaction can't be recalled.
+pub async fn cancel_position_exit(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+
+    if auto_trader.position_manager.get_position(&id).await.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Position not found".to_string(),
+                details: Some(format!("No position with ID {}", id)),
+            }),
+        ));
+    }
+
+    match auto_trader.position_manager.cancel_exit(&id).await {
+        Ok(position) => {
+            info!("Exit cancelled for position {} via API", id);
+            Ok(Json(SuccessResponse {
+                success: true,
+                message: format!("Exit cancelled, {} is Active again", position.token_symbol),
+            }))
+        }
+        Err(e) => Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Cannot cancel exit".to_string(),
+                details: Some(e.to_string()),
+            }),
+        )),
+    }
+}
+
+/// Price history and decision timeline of a position, to replay how it played out
+pub async fn get_position_replay(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<PositionReplayResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let position = state.auto_trader.lock().await.position_manager.get_position(&id).await;
+    let Some(position) = position else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Position not found".to_string(),
+                details: Some(format!("No position with ID {}", id)),
+            }),
+        ));
+    };
+
+    let exit_reason = position.events.iter()
+        .rev()
+        .find(|e| e.kind == PositionEventKind::ExitTriggered)
+        .map(|e| e.detail.clone())
+        .or_else(|| position.exit_time.map(|_| position.status.to_string()));
+
+    Ok(Json(PositionReplayResponse {
+        position_id: position.id,
+        token_symbol: position.token_symbol,
+        status: position.status.to_string(),
+        entry_time: position.entry_time,
+        exit_time: position.exit_time,
+        entry_price_sol: position.entry_price_sol,
+        exit_price_sol: position.exit_price_sol,
+        exit_reason,
+        pnl_percent: position.pnl_percent,
+        samples: position.price_history,
+        events: position.events,
+    }))
+}
+
 pub async fn get_active_positions(
     State(state): State<AppState>,
+    Query(query): Query<DustQuery>,
 ) -> Result<Json<PositionsListResponse>, (StatusCode, Json<ErrorResponse>)> {
     let auto_trader = state.auto_trader.lock().await;
-    let positions = auto_trader.position_manager.get_active_positions().await;
+    let dust_threshold = dust_threshold(&state, query.include_dust);
+    let positions: Vec<_> = auto_trader.position_manager.get_active_positions().await
+        .into_iter()
+        .filter(|p| !p.is_dust(dust_threshold))
+        .collect();
 
     let position_responses: Vec<PositionResponse> = positions
         .iter()
@@ -126,6 +528,10 @@ pub async fn get_active_positions(
                 opened_at: p.entry_time,
                 closed_at: p.exit_time,
                 exit_reason: Some(format!("{}", p.status)),
+                profit_fee_sol: p.profit_fee_sol,
+                entry_value_usd: p.entry_value_usd,
+                exit_value_usd: p.exit_value_usd,
+                pnl_usd: p.pnl_usd,
             }
         })
         .collect();
@@ -151,11 +557,12 @@ pub async fn get_trades(
 
     let auto_trader = state.auto_trader.lock().await;
     let positions = auto_trader.position_manager.get_all_positions().await;
+    let dust_threshold = dust_threshold(&state, query.include_dust);
 
     // Convert closed positions to trades
     let mut trades: Vec<TradeResponse> = positions
         .iter()
-        .filter(|p| p.exit_time.is_some())
+        .filter(|p| p.exit_time.is_some() && !p.is_dust(dust_threshold))
         .map(|p| TradeResponse {
             id: p.id.clone(),
             token_address: p.token_address.clone(),
@@ -166,6 +573,7 @@ pub async fn get_trades(
             price: p.exit_price_sol.unwrap_or(0.0),
             pnl_sol: p.pnl_sol,
             pnl_percent: p.pnl_percent,
+            pnl_usd: p.pnl_usd,
             transaction_signature: p.exit_tx_signature.clone().unwrap_or_default(),
             timestamp: p.exit_time.unwrap_or(p.entry_time),
         })
@@ -194,10 +602,11 @@ pub async fn get_trades(
 
 pub async fn get_stats(
     State(state): State<AppState>,
+    Query(query): Query<StatsQuery>,
 ) -> Result<Json<StatsResponse>, (StatusCode, Json<ErrorResponse>)> {
     let auto_trader = state.auto_trader.lock().await;
 
-    match auto_trader.get_performance_stats().await {
+    match auto_trader.get_performance_stats(query.include_dust.unwrap_or(false), query.include_archived.unwrap_or(false)).await {
         Ok(stats) => {
             let losing_trades = stats.total_trades.saturating_sub(stats.winning_trades);
 
@@ -207,6 +616,7 @@ pub async fn get_stats(
                 losing_trades,
                 win_rate: stats.win_rate,
                 total_pnl_sol: stats.total_pnl,
+                total_pnl_usd: stats.total_pnl_usd,
                 avg_roi_percent: stats.avg_roi,
                 total_volume_sol: stats.total_entry_value,
                 best_trade_pnl: 0.0,  // TODO: Calculate from positions
@@ -314,13 +724,25 @@ pub async fn create_strategy(
         max_position_size_sol: req.max_position_size_sol.unwrap_or(0.1),
         total_budget_sol: req.total_budget_sol.unwrap_or(1.0),
         stop_loss_percent: req.stop_loss_percent,
+        stop_loss_type: req.stop_loss_type.unwrap_or_default(),
         take_profit_percent: req.take_profit_percent,
         trailing_stop_percent: req.trailing_stop_percent,
+        peak_drawdown_alert_percent: req.peak_drawdown_alert_percent,
+        peak_drawdown_sell_percent: req.peak_drawdown_sell_percent,
         max_hold_time_minutes: req.max_hold_time_minutes.unwrap_or(240),
+        force_close_at: req.force_close_at,
+        momentum_tp: req.momentum_tp,
+        scale_in: req.scale_in,
+        dca: req.dca,
+        limit_entry: req.limit_entry,
+        notify_trades: req.notify_trades.unwrap_or_default(),
         min_liquidity_sol: req.min_liquidity_sol.unwrap_or(10),
         max_risk_level: req.max_risk_level.unwrap_or(50),
         min_holders: req.min_holders.unwrap_or(50),
         max_token_age_minutes: 60,
+        max_token_age_seconds: req.max_token_age_seconds,
+        scan_interval_secs: req.scan_interval_secs,
+        scan_token_age_minutes: req.scan_token_age_minutes,
         require_lp_burned: false,
         reject_if_mint_authority: true,
         reject_if_freeze_authority: true,
@@ -333,8 +755,15 @@ pub async fn create_strategy(
         require_migrated: None,
         min_buy_ratio_percent: 0.0,
         min_unique_wallets_24h: None,
+        token_lists: req.token_lists.unwrap_or_default(),
         slippage_bps: None,
         priority_fee_micro_lamports: None,
+        fee_urgency: req.fee_urgency.unwrap_or_default(),
+        use_jito: req.use_jito.unwrap_or(false),
+        jito_tip_lamports: req.jito_tip_lamports,
+        execution_venue: req.execution_venue.unwrap_or_default(),
+        entry_retry_attempts: req.entry_retry_attempts.unwrap_or(0),
+        entry_retry_delay_ms: req.entry_retry_delay_ms.unwrap_or(DEFAULT_ENTRY_RETRY_DELAY_MS),
         created_at: now,
         updated_at: now,
     };
@@ -378,6 +807,7 @@ pub async fn create_strategy(
 pub async fn update_strategy(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    role: Option<Extension<Role>>,
     Json(req): Json<UpdateStrategyRequest>,
 ) -> Result<Json<StrategyResponse>, (StatusCode, Json<ErrorResponse>)> {
     let auto_trader = state.auto_trader.lock().await;
@@ -406,13 +836,25 @@ pub async fn update_strategy(
         max_position_size_sol: req.max_position_size_sol.unwrap_or(existing.max_position_size_sol),
         total_budget_sol: req.total_budget_sol.unwrap_or(existing.total_budget_sol),
         stop_loss_percent: req.stop_loss_percent.or(existing.stop_loss_percent),
+        stop_loss_type: req.stop_loss_type.unwrap_or(existing.stop_loss_type),
         take_profit_percent: req.take_profit_percent.or(existing.take_profit_percent),
         trailing_stop_percent: req.trailing_stop_percent.or(existing.trailing_stop_percent),
+        peak_drawdown_alert_percent: req.peak_drawdown_alert_percent.or(existing.peak_drawdown_alert_percent),
+        peak_drawdown_sell_percent: req.peak_drawdown_sell_percent.or(existing.peak_drawdown_sell_percent),
         max_hold_time_minutes: req.max_hold_time_minutes.unwrap_or(existing.max_hold_time_minutes),
+        force_close_at: req.force_close_at.or(existing.force_close_at),
+        momentum_tp: req.momentum_tp.or(existing.momentum_tp),
+        scale_in: req.scale_in.or(existing.scale_in),
+        dca: req.dca.or(existing.dca),
+        limit_entry: req.limit_entry.or(existing.limit_entry),
+        notify_trades: req.notify_trades.unwrap_or(existing.notify_trades),
         min_liquidity_sol: req.min_liquidity_sol.unwrap_or(existing.min_liquidity_sol),
         max_risk_level: req.max_risk_level.unwrap_or(existing.max_risk_level),
         min_holders: req.min_holders.unwrap_or(existing.min_holders),
         max_token_age_minutes: existing.max_token_age_minutes,
+        max_token_age_seconds: req.max_token_age_seconds.or(existing.max_token_age_seconds),
+        scan_interval_secs: req.scan_interval_secs.or(existing.scan_interval_secs),
+        scan_token_age_minutes: req.scan_token_age_minutes.or(existing.scan_token_age_minutes),
         require_lp_burned: existing.require_lp_burned,
         reject_if_mint_authority: existing.reject_if_mint_authority,
         reject_if_freeze_authority: existing.reject_if_freeze_authority,
@@ -425,13 +867,20 @@ pub async fn update_strategy(
         require_migrated: existing.require_migrated,
         min_buy_ratio_percent: existing.min_buy_ratio_percent,
         min_unique_wallets_24h: existing.min_unique_wallets_24h,
+        token_lists: req.token_lists.unwrap_or(existing.token_lists),
         slippage_bps: existing.slippage_bps,
         priority_fee_micro_lamports: existing.priority_fee_micro_lamports,
+        fee_urgency: req.fee_urgency.unwrap_or(existing.fee_urgency),
+        use_jito: req.use_jito.unwrap_or(existing.use_jito),
+        jito_tip_lamports: req.jito_tip_lamports.or(existing.jito_tip_lamports),
+        execution_venue: req.execution_venue.unwrap_or(existing.execution_venue),
+        entry_retry_attempts: req.entry_retry_attempts.unwrap_or(existing.entry_retry_attempts),
+        entry_retry_delay_ms: req.entry_retry_delay_ms.unwrap_or(existing.entry_retry_delay_ms),
         created_at: existing.created_at,
         updated_at: Utc::now(),
     };
 
-    match auto_trader.update_strategy(updated.clone()).await {
+    match auto_trader.update_strategy(updated.clone(), &actor_name(role.map(|Extension(r)| r))).await {
         Ok(_) => {
             info!("Updated strategy: {} ({})", updated.name, updated.id);
             Ok(Json(StrategyResponse {
@@ -492,27 +941,61 @@ pub async fn delete_strategy(
     }
 }
 
-pub async fn toggle_strategy(
+/// List the named strategy presets with their pre-filled values
+pub async fn list_strategy_templates() -> Json<StrategyTemplatesResponse> {
+    let templates = STRATEGY_TEMPLATES
+        .iter()
+        .map(|t| {
+            let s = (t.build)(t.name);
+            StrategyTemplateResponse {
+                id: t.id.to_string(),
+                name: t.name.to_string(),
+                description: t.description.to_string(),
+                strategy_type: format!("{:?}", s.strategy_type),
+                defaults: strategy_response(&s),
+            }
+        })
+        .collect();
+
+    Json(StrategyTemplatesResponse { templates })
+}
+
+/// Create a strategy from a named template. The result can be tweaked
+/// afterwards via PUT /api/strategies/:id.
+pub async fn create_strategy_from_template(
     State(state): State<AppState>,
-    Path(id): Path<String>,
-) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    Json(req): Json<CreateFromTemplateRequest>,
+) -> Result<Json<StrategyResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let template = match STRATEGY_TEMPLATES.iter().find(|t| t.id == req.template) {
+        Some(t) => t,
+        None => {
+            let valid: Vec<&str> = STRATEGY_TEMPLATES.iter().map(|t| t.id).collect();
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Unknown strategy template".to_string(),
+                    details: Some(format!("Valid templates: {}. Got: {}", valid.join(", "), req.template)),
+                }),
+            ));
+        }
+    };
+
+    let name = req.name.unwrap_or_else(|| template.name.to_string());
+    let strategy = (template.build)(&name);
+
     let auto_trader = state.auto_trader.lock().await;
 
-    match auto_trader.toggle_strategy(&id).await {
-        Ok(new_status) => {
-            let status_str = if new_status { "enabled" } else { "disabled" };
-            info!("Toggled strategy {}: now {}", id, status_str);
-            Ok(Json(SuccessResponse {
-                success: true,
-                message: format!("Strategy {} is now {}", id, status_str),
-            }))
+    match auto_trader.add_strategy(strategy.clone()).await {
+        Ok(_) => {
+            info!("Created strategy {} ({}) from template '{}'", strategy.name, strategy.id, template.id);
+            Ok(Json(strategy_response(&strategy)))
         }
         Err(e) => {
-            error!("Failed to toggle strategy {}: {}", id, e);
+            error!("Failed to create strategy from template {}: {}", template.id, e);
             Err((
-                StatusCode::NOT_FOUND,
+                StatusCode::BAD_REQUEST,
                 Json(ErrorResponse {
-                    error: "Failed to toggle strategy".to_string(),
+                    error: "Failed to create strategy".to_string(),
                     details: Some(e.to_string()),
                 }),
             ))
@@ -520,88 +1003,889 @@ pub async fn toggle_strategy(
     }
 }
 
-// ============================================================================
-// AutoTrader Control
-// ============================================================================
+/// Performance snapshots recorded for a strategy over time
+pub async fn get_strategy_history(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<StrategyHistoryResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
 
-pub async fn get_autotrader_status(
+    if auto_trader.get_strategy(&id).await.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Strategy not found".to_string(),
+                details: None,
+            }),
+        ));
+    }
+
+    let snapshots = auto_trader.position_manager.strategy_stats_history(&id).await;
+    let total = snapshots.len();
+    Ok(Json(StrategyHistoryResponse {
+        strategy_id: id,
+        snapshots,
+        total,
+    }))
+}
+
+/// Who changed a strategy's fields and when, oldest first
+pub async fn get_strategy_changelog(
     State(state): State<AppState>,
-) -> Result<Json<AutoTraderStatus>, (StatusCode, Json<ErrorResponse>)> {
+    Path(id): Path<String>,
+) -> Result<Json<StrategyChangelogResponse>, (StatusCode, Json<ErrorResponse>)> {
     let auto_trader = state.auto_trader.lock().await;
 
-    let running = auto_trader.get_status().await;
-    let strategies = auto_trader.list_strategies().await;
-    let active_strategies = strategies.iter().filter(|s| s.enabled).count();
-    let positions = auto_trader.position_manager.get_active_positions().await;
+    let changes = auto_trader.strategy_changelog.history(&id).await;
+    if changes.is_empty() && auto_trader.get_strategy(&id).await.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Strategy not found".to_string(),
+                details: None,
+            }),
+        ));
+    }
 
-    Ok(Json(AutoTraderStatus {
-        running,
-        demo_mode: state.config.demo_mode,
-        dry_run_mode: state.config.dry_run_mode,
-        active_strategies,
-        active_positions: positions.len(),
+    let total = changes.len();
+    Ok(Json(StrategyChangelogResponse {
+        strategy_id: id,
+        changes,
+        total,
     }))
 }
 
-pub async fn start_autotrader(
+fn strategy_response(s: &Strategy) -> StrategyResponse {
+    StrategyResponse {
+        id: s.id.clone(),
+        name: s.name.clone(),
+        enabled: s.enabled,
+        max_concurrent_positions: s.max_concurrent_positions,
+        max_position_size_sol: s.max_position_size_sol,
+        total_budget_sol: s.total_budget_sol,
+        stop_loss_percent: s.stop_loss_percent,
+        take_profit_percent: s.take_profit_percent,
+        trailing_stop_percent: s.trailing_stop_percent,
+        max_hold_time_minutes: s.max_hold_time_minutes,
+        min_liquidity_sol: s.min_liquidity_sol,
+        max_risk_level: s.max_risk_level,
+        min_holders: s.min_holders,
+        created_at: s.created_at,
+        updated_at: s.updated_at,
+    }
+}
+
+/// Show which enabled strategies match a token and which one would claim the buy
+pub async fn match_strategies(
+    State(state): State<AppState>,
+    Json(req): Json<StrategyMatchRequest>,
+) -> Result<Json<StrategyMatchReport>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+
+    match auto_trader.match_strategies(&req.token_address).await {
+        Ok(report) => Ok(Json(report)),
+        Err(e) => {
+            error!("Failed to match strategies for {}: {}", req.token_address, e);
+            Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Failed to match strategies".to_string(),
+                    details: Some(e.to_string()),
+                }),
+            ))
+        }
+    }
+}
+
+/// Most tokens one backtest request may replay (one Birdeye call each)
+const MAX_BACKTEST_TOKENS: usize = 20;
+
+/// Replay historical Birdeye candles for a set of tokens against a strategy's exits
+pub async fn run_backtest(
+    State(state): State<AppState>,
+    Json(req): Json<BacktestRequest>,
+) -> Result<Json<BacktestReport>, (StatusCode, Json<ErrorResponse>)> {
+    let bad_request = |error: &str, details: Option<String>| {
+        (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: error.to_string(), details }))
+    };
+
+    // Candle fetches are slow; don't hold the AutoTrader lock through them
+    let (strategy, birdeye_client) = {
+        let auto_trader = state.auto_trader.lock().await;
+        (auto_trader.get_strategy(&req.strategy_id).await, auto_trader.birdeye_client())
+    };
+    let Some(mut strategy) = strategy else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Strategy not found".to_string(),
+                details: Some(format!("No strategy with ID {}", req.strategy_id)),
+            }),
+        ));
+    };
+    if req.token_addresses.is_empty() || req.token_addresses.len() > MAX_BACKTEST_TOKENS {
+        return Err(bad_request(
+            "Invalid token list",
+            Some(format!("Give between 1 and {} token addresses", MAX_BACKTEST_TOKENS)),
+        ));
+    }
+    let to = req.to.unwrap_or_else(Utc::now);
+    if req.from >= to {
+        return Err(bad_request("Invalid time range", Some("`from` must be before `to`".to_string())));
+    }
+
+    if let Some(sl) = req.stop_loss_percent {
+        strategy.stop_loss_percent = Some(sl);
+    }
+    if let Some(tp) = req.take_profit_percent {
+        strategy.take_profit_percent = Some(tp);
+    }
+    if let Some(ts) = req.trailing_stop_percent {
+        strategy.trailing_stop_percent = Some(ts);
+    }
+    if let Some(minutes) = req.max_hold_time_minutes {
+        strategy.max_hold_time_minutes = minutes;
+    }
+
+    let interval = req.interval.as_deref().unwrap_or("5m");
+    let mut tokens = Vec::with_capacity(req.token_addresses.len());
+    for token_address in &req.token_addresses {
+        match birdeye_client.get_ohlcv(token_address, interval, req.from.timestamp(), to.timestamp()).await {
+            Ok(candles) => tokens.push((token_address.clone(), candles)),
+            Err(e) => {
+                error!("Failed to fetch candles for backtest of {}: {}", token_address, e);
+                return Err((
+                    StatusCode::BAD_GATEWAY,
+                    Json(ErrorResponse {
+                        error: "Failed to fetch historical candles".to_string(),
+                        details: Some(e.to_string()),
+                    }),
+                ));
+            }
+        }
+    }
+
+    backtest::run(&strategy, &tokens, req.cost_bps.unwrap_or(backtest::DEFAULT_COST_BPS))
+        .map(Json)
+        .map_err(|e| bad_request("Backtest failed", Some(e.to_string())))
+}
+
+pub async fn toggle_strategy(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    role: Option<Extension<Role>>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+
+    match auto_trader.toggle_strategy(&id, &actor_name(role.map(|Extension(r)| r))).await {
+        Ok(new_status) => {
+            let status_str = if new_status { "enabled" } else { "disabled" };
+            info!("Toggled strategy {}: now {}", id, status_str);
+            Ok(Json(SuccessResponse {
+                success: true,
+                message: format!("Strategy {} is now {}", id, status_str),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to toggle strategy {}: {}", id, e);
+            Err((
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "Failed to toggle strategy".to_string(),
+                    details: Some(e.to_string()),
+                }),
+            ))
+        }
+    }
+}
+
+// ============================================================================
+// AutoTrader Control
+// ============================================================================
+
+/// Everything an operator checks first, in one call: running state, mode, balance,
+/// open positions, today's realized PnL, strategies and RPC health
+pub async fn get_system_status(
+    State(state): State<AppState>,
+) -> Result<Json<SystemStatusResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (running, strategies, positions) = {
+        let auto_trader = state.auto_trader.lock().await;
+        (
+            auto_trader.get_status().await,
+            auto_trader.list_strategies().await,
+            auto_trader.position_manager.get_all_positions().await,
+        )
+    };
+
+    let mode = if state.config.demo_mode {
+        "demo"
+    } else if state.config.dry_run_mode {
+        "dry_run"
+    } else {
+        "real"
+    };
+
+    let wallet_balance_sol = match state.wallet_pool.total_sol_balance().await {
+        Ok(balance) => Some(balance),
+        Err(e) => {
+            warn!("Status: failed to get wallet balance: {}", e);
+            None
+        }
+    };
+
+    let open: Vec<_> = positions.iter().filter(|p| p.exit_time.is_none()).collect();
+    let open_positions_value_sol: f64 = open.iter().map(|p| p.current_price_sol * p.entry_token_amount).sum();
+
+    let today = Utc::now().date_naive();
+    let realized_pnl_today_sol: f64 = positions
+        .iter()
+        .filter(|p| p.exit_time.is_some_and(|t| t.date_naive() == today))
+        .filter_map(|p| p.pnl_sol)
+        .sum();
+
+    let rpc_start = std::time::Instant::now();
+    let (rpc_healthy, rpc_latency_ms, rpc_slot) = match state.solana_client.get_rpc().get_slot().await {
+        Ok(slot) => (true, Some(rpc_start.elapsed().as_millis() as u64), Some(slot)),
+        Err(e) => {
+            warn!("Status: RPC health check failed: {}", e);
+            (false, None, None)
+        }
+    };
+
+    Ok(Json(SystemStatusResponse {
+        running,
+        mode: mode.to_string(),
+        wallet_balance_sol,
+        open_positions: open.len(),
+        open_positions_value_sol,
+        realized_pnl_today_sol,
+        enabled_strategies: strategies.iter().filter(|s| s.enabled).count(),
+        total_strategies: strategies.len(),
+        rpc_healthy,
+        rpc_latency_ms,
+        rpc_slot,
+        timestamp: Utc::now(),
+    }))
+}
+
+/// Swap failure counts by category, so operators can see which failure mode
+/// dominates (e.g. expired blockhashes point at priority fees or the RPC)
+pub async fn get_metrics(
+    State(state): State<AppState>,
+) -> Result<Json<MetricsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (counts, swap_breaker, loss_breaker) = {
+        let auto_trader = state.auto_trader.lock().await;
+        (
+            auto_trader.swap_failure_counts(),
+            auto_trader.swap_breaker_status(),
+            auto_trader.position_manager.loss_breaker().status(),
+        )
+    };
+    let total: u64 = counts.iter().map(|(_, count)| count).sum();
+
+    let swap_failures = counts
+        .into_iter()
+        .map(|(kind, count)| SwapFailureCount {
+            category: kind.to_string(),
+            count,
+            percent: if total > 0 { count as f64 / total as f64 * 100.0 } else { 0.0 },
+        })
+        .collect();
+
+    Ok(Json(MetricsResponse {
+        swap_failures_total: total,
+        swap_failures,
+        swap_breaker,
+        loss_breaker,
+        timestamp: Utc::now(),
+    }))
+}
+
+/// Resume buys paused by the swap circuit breaker without waiting out its cooldown
+pub async fn reset_swap_breaker(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+
+    if auto_trader.swap_breaker().reset() {
+        info!("Swap circuit breaker reset via API");
+        Ok(Json(SuccessResponse {
+            success: true,
+            message: "Swap circuit breaker reset, buys resumed".to_string(),
+        }))
+    } else {
+        Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Swap circuit breaker is not tripped".to_string(),
+                details: None,
+            }),
+        ))
+    }
+}
+
+/// Resume buys paused by the loss circuit breaker without waiting out its cooldown
+pub async fn reset_loss_breaker(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+
+    if auto_trader.position_manager.loss_breaker().reset() {
+        info!("Loss circuit breaker reset via API");
+        Ok(Json(SuccessResponse {
+            success: true,
+            message: "Loss circuit breaker reset, buys resumed".to_string(),
+        }))
+    } else {
+        Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Loss circuit breaker is not tripped".to_string(),
+                details: None,
+            }),
+        ))
+    }
+}
+
+/// Re-read `.env` and apply slippage, priority fee, API credential and scan
+/// settings without restarting
+pub async fn reload_config(
+    State(state): State<AppState>,
+) -> Result<Json<ConfigReload>, (StatusCode, Json<ErrorResponse>)> {
+    match state.reload_config().await {
+        Ok(reload) => Ok(Json(reload)),
+        Err(e) => {
+            error!("Config reload failed: {:#}", e);
+            Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Failed to reload config".to_string(),
+                    details: Some(format!("{:#}", e)),
+                }),
+            ))
+        }
+    }
+}
+
+/// Change the Helius scan interval and lookback without restarting
+pub async fn update_autotrader_settings(
+    State(state): State<AppState>,
+    Json(req): Json<UpdateScanSettingsRequest>,
+) -> Result<Json<ScanSettings>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+
+    match auto_trader.update_scan_settings(req.scan_interval_secs, req.token_age_minutes).await {
+        Ok(settings) => Ok(Json(settings)),
+        Err(e) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid scan settings".to_string(),
+                details: Some(e.to_string()),
+            }),
+        )),
+    }
+}
+
+pub async fn get_autotrader_status(
+    State(state): State<AppState>,
+) -> Result<Json<AutoTraderStatus>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+
+    let running = auto_trader.get_status().await;
+    let strategies = auto_trader.list_strategies().await;
+    let active_strategies = strategies.iter().filter(|s| s.enabled).count();
+    let positions = auto_trader.position_manager.get_active_positions().await;
+
+    Ok(Json(AutoTraderStatus {
+        running,
+        demo_mode: state.config.demo_mode,
+        dry_run_mode: state.config.dry_run_mode,
+        active_strategies,
+        active_positions: positions.len(),
+    }))
+}
+
+pub async fn start_autotrader(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+
+    match auto_trader.start().await {
+        Ok(_) => {
+            info!("AutoTrader started via API");
+
+            // Broadcast status change
+            state.broadcast(WsMessage::StatusChange {
+                running: true,
+                timestamp: Utc::now(),
+            });
+
+            Ok(Json(SuccessResponse {
+                success: true,
+                message: "AutoTrader started".to_string(),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to start AutoTrader: {}", e);
+            Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Failed to start AutoTrader".to_string(),
+                    details: Some(e.to_string()),
+                }),
+            ))
+        }
+    }
+}
+
+pub async fn stop_autotrader(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+
+    match auto_trader.stop().await {
+        Ok(_) => {
+            info!("AutoTrader stopped via API");
+
+            // Broadcast status change
+            state.broadcast(WsMessage::StatusChange {
+                running: false,
+                timestamp: Utc::now(),
+            });
+
+            Ok(Json(SuccessResponse {
+                success: true,
+                message: "AutoTrader stopped".to_string(),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to stop AutoTrader: {}", e);
+            Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Failed to stop AutoTrader".to_string(),
+                    details: Some(e.to_string()),
+                }),
+            ))
+        }
+    }
+}
+
+// ============================================================================
+// Token Analysis
+// ============================================================================
+
+pub async fn analyze_token(
+    State(state): State<AppState>,
+    Json(req): Json<AnalyzeRequest>,
+) -> Result<Json<AnalyzeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Analysis makes several slow API calls; don't hold the AutoTrader lock through them
+    let (risk_analyzer, jupiter_client, slippage_bps) = {
+        let auto_trader = state.auto_trader.lock().await;
+        (auto_trader.risk_analyzer.clone(), auto_trader.jupiter_client(), auto_trader.manual_slippage_bps().await)
+    };
+
+    match risk_analyzer.analyze_token(&req.address).await {
+        Ok(mut analysis) => {
+            let amount_sol = req.amount_sol.filter(|a| *a > 0.0).unwrap_or(state.config.snipe_amount_sol);
+            let roundtrip_loss_percent = match jupiter_client.quote_round_trip(&req.address, amount_sol, slippage_bps).await {
+                Ok(quote) => Some(round_trip_loss_percent(quote.sol_in, quote.sol_back, analysis.transfer_tax_percent)),
+                Err(e) => {
+                    debug!("No round-trip quote for {}: {:#}", req.address, e);
+                    None
+                }
+            };
+            let roundtrip_trap = match (roundtrip_loss_percent, state.config.max_roundtrip_loss_percent) {
+                (Some(loss), Some(max)) => loss > max,
+                _ => false,
+            };
+            if let Some(loss) = roundtrip_loss_percent {
+                let marker = if roundtrip_trap { "🔴" } else { "🟢" };
+                analysis.details.push(format!("{} Round trip at {:.4} SOL loses {:.1}%", marker, amount_sol, loss));
+            }
+
+            let risk_rating = match analysis.risk_level {
+                0..=25 => "Low",
+                26..=50 => "Medium",
+                51..=75 => "High",
+                _ => "Very High",
+            };
+
+            let recommendation = if roundtrip_trap {
+                "DO NOT TRADE - Selling back loses too much (soft honeypot)"
+            } else if analysis.risk_level <= 30 && analysis.can_sell && analysis.liquidity_sol >= 10.0 {
+                "Consider trading with caution"
+            } else if analysis.risk_level <= 50 && analysis.can_sell {
+                "High risk - small position only"
+            } else if !analysis.can_sell {
+                "DO NOT TRADE - Cannot sell (honeypot)"
+            } else {
+                "Avoid - Too risky"
+            };
+
+            Ok(Json(AnalyzeResponse {
+                token_address: analysis.token_address,
+                risk_level: analysis.risk_level,
+                risk_rating: risk_rating.to_string(),
+                liquidity_sol: analysis.liquidity_sol,
+                holder_count: analysis.holder_count,
+                has_mint_authority: analysis.has_mint_authority,
+                has_freeze_authority: analysis.has_freeze_authority,
+                lp_tokens_burned: analysis.lp_tokens_burned,
+                transfer_tax_percent: analysis.transfer_tax_percent,
+                can_sell: analysis.can_sell,
+                concentration_percent: analysis.concentration_percent,
+                roundtrip_loss_percent,
+                details: analysis.details,
+                recommendation: recommendation.to_string(),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to analyze token {}: {}", req.address, e);
+            Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Failed to analyze token".to_string(),
+                    details: Some(e.to_string()),
+                }),
+            ))
+        }
+    }
+}
+
+// ============================================================================
+// Manual Snipe
+// ============================================================================
+
+/// How long a large-snipe confirmation id stays valid
+const SNIPE_CONFIRMATION_TTL_SECS: i64 = 60;
+
+/// Buy a token manually. Amounts above `require_confirmation_above_sol` are not
+/// executed; a confirmation id is returned for `confirm_manual_snipe` instead.
+pub async fn manual_snipe(
+    State(state): State<AppState>,
+    Json(req): Json<ManualSnipeRequest>,
+) -> Result<Json<ManualSnipeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if !req.amount_sol.is_finite() || req.amount_sol <= 0.0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "amount_sol must be positive".to_string(),
+                details: None,
+            }),
+        ));
+    }
+
+    check_manual_snipe_risk(&state, &req.token_address).await?;
+
+    if let Some(threshold) = state.config.require_confirmation_above_sol {
+        if req.amount_sol > threshold {
+            let confirmation_id = uuid::Uuid::new_v4().to_string();
+            let expires_at = Utc::now() + chrono::Duration::seconds(SNIPE_CONFIRMATION_TTL_SECS);
+
+            let mut pending = state.pending_snipes.lock().await;
+            pending.retain(|_, p| p.expires_at > Utc::now());
+            pending.insert(confirmation_id.clone(), super::PendingSnipe {
+                token_address: req.token_address.clone(),
+                amount_sol: req.amount_sol,
+                expires_at,
+            });
+
+            info!("Manual snipe of {} SOL for {} exceeds {} SOL - awaiting confirmation {}",
+                  req.amount_sol, req.token_address, threshold, confirmation_id);
+            drop(pending);
+
+            // Show what the buy would get before it's confirmed (quoted without holding the trader lock)
+            let previewer = state.auto_trader.lock().await.manual_buy_previewer().await;
+            let preview = previewer.preview(&req.token_address, req.amount_sol).await;
+            let mut message = format!(
+                "{} SOL exceeds the {} SOL confirmation threshold. POST the confirmation_id to /api/snipe/confirm within {}s to execute.",
+                req.amount_sol, threshold, SNIPE_CONFIRMATION_TTL_SECS
+            );
+            match &preview {
+                Ok(p) => message.push_str(&format!(
+                    " Expected ~{:.2} tokens (min {:.2}), price impact {:.2}%, est. fees {:.6} SOL.",
+                    p.expected_tokens, p.min_tokens, p.price_impact_pct, p.estimated_fee_sol
+                )),
+                Err(e) => {
+                    warn!("Quote preview failed for {}: {}", req.token_address, e);
+                    message.push_str(" WARNING: no quote available - the token may not be tradable yet.");
+                }
+            }
+
+            return Ok(Json(ManualSnipeResponse {
+                status: "confirmation_required".to_string(),
+                token_address: req.token_address,
+                amount_sol: req.amount_sol,
+                signature: None,
+                confirmation_id: Some(confirmation_id),
+                threshold_sol: Some(threshold),
+                expires_at: Some(expires_at),
+                preview: preview.ok(),
+                message,
+            }));
+        }
+    }
+
+    execute_snipe(&state, req.token_address, req.amount_sol).await
+}
+
+/// Execute a manual snipe previously held back for confirmation
+pub async fn confirm_manual_snipe(
+    State(state): State<AppState>,
+    Json(req): Json<ConfirmSnipeRequest>,
+) -> Result<Json<ManualSnipeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let pending = state.pending_snipes.lock().await.remove(&req.confirmation_id);
+
+    match pending {
+        Some(p) if p.expires_at > Utc::now() => {
+            info!("Manual snipe confirmed: {} SOL for {}", p.amount_sol, p.token_address);
+            // Risk may have changed while the snipe waited for confirmation
+            check_manual_snipe_risk(&state, &p.token_address).await?;
+            execute_snipe(&state, p.token_address, p.amount_sol).await
+        }
+        Some(_) => Err((
+            StatusCode::GONE,
+            Json(ErrorResponse {
+                error: "Confirmation expired".to_string(),
+                details: Some("Submit the snipe again to get a new confirmation id".to_string()),
+            }),
+        )),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Unknown confirmation id".to_string(),
+                details: None,
+            }),
+        )),
+    }
+}
+
+/// Refuse a manual snipe of a token riskier than `manual_snipe_max_risk_level`
+async fn check_manual_snipe_risk(
+    state: &AppState,
+    token_address: &str,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let max_risk = state.config.manual_snipe_max_risk_level;
+    if max_risk >= 100 {
+        return Ok(());
+    }
+
+    let risk_analyzer = state.auto_trader.lock().await.risk_analyzer.clone();
+    match risk_analyzer.analyze_token(token_address).await {
+        Ok(analysis) if analysis.risk_level > max_risk => {
+            warn!("Manual snipe of {} refused: risk {} > {}", token_address, analysis.risk_level, max_risk);
+            Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ErrorResponse {
+                    error: format!(
+                        "Token risk level {} exceeds the manual snipe limit of {} (MANUAL_SNIPE_MAX_RISK_LEVEL)",
+                        analysis.risk_level, max_risk
+                    ),
+                    details: Some(analysis.details.join("; ")),
+                }),
+            ))
+        }
+        Ok(_) => Ok(()),
+        Err(e) => Err((
+            StatusCode::BAD_GATEWAY,
+            Json(ErrorResponse {
+                error: format!("Risk analysis failed; manual snipes are limited to risk level {}", max_risk),
+                details: Some(e.to_string()),
+            }),
+        )),
+    }
+}
+
+async fn execute_snipe(
+    state: &AppState,
+    token_address: String,
+    amount_sol: f64,
+) -> Result<Json<ManualSnipeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+
+    let dry_run = state.config.dry_run_mode;
+    match auto_trader.execute_manual_buy(&token_address, amount_sol).await {
+        Ok(result) => Ok(Json(ManualSnipeResponse {
+            status: if dry_run { "simulated" } else { "executed" }.to_string(),
+            token_address,
+            amount_sol,
+            signature: Some(result.transaction_signature),
+            confirmation_id: None,
+            threshold_sol: None,
+            expires_at: None,
+            preview: None,
+            message: if dry_run { "Manual snipe simulated (dry run)" } else { "Manual snipe executed" }.to_string(),
+        })),
+        Err(e) => {
+            error!("Manual snipe failed for {}: {}", token_address, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Manual snipe failed".to_string(),
+                    details: Some(e.to_string()),
+                }),
+            ))
+        }
+    }
+}
+
+// ============================================================================
+// Incidents (Escalation)
+// ============================================================================
+
+/// List escalated incidents (unsellable positions, RPC outages)
+pub async fn get_incidents(
+    State(state): State<AppState>,
+) -> Result<Json<IncidentsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+    let incidents = auto_trader.escalation_manager.list().await;
+    let open = incidents.iter().filter(|i| !i.resolved && !i.acknowledged).count();
+
+    Ok(Json(IncidentsResponse { incidents, open }))
+}
+
+/// Acknowledge an incident so it stops re-notifying
+pub async fn acknowledge_incident(
     State(state): State<AppState>,
+    Path(id): Path<String>,
 ) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
     let auto_trader = state.auto_trader.lock().await;
 
-    match auto_trader.start().await {
-        Ok(_) => {
-            info!("AutoTrader started via API");
+    if auto_trader.escalation_manager.acknowledge(&id).await {
+        Ok(Json(SuccessResponse {
+            success: true,
+            message: format!("Incident {} acknowledged", id),
+        }))
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Incident not found".to_string(),
+                details: Some(format!("No incident with ID {}", id)),
+            }),
+        ))
+    }
+}
 
-            // Broadcast status change
-            state.broadcast(WsMessage::StatusChange {
-                running: true,
-                timestamp: Utc::now(),
-            });
+// ============================================================================
+// Accounting
+// ============================================================================
 
-            Ok(Json(SuccessResponse {
-                success: true,
-                message: "AutoTrader started".to_string(),
-            }))
+/// FIFO lot accounting of realized gains, as JSON or as a CSV for tax software
+pub async fn get_fifo_accounting(
+    State(state): State<AppState>,
+    Query(query): Query<FifoQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let csv = match query.format.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("json") => false,
+        Some("csv") => true,
+        Some(other) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown format '{}'", other),
+                    details: Some("Use json or csv".to_string()),
+                }),
+            ));
         }
+    };
+
+    // Lots opened or closed in archived positions still count toward the report
+    let positions = match state.auto_trader.lock().await.position_manager.get_all_positions_with_archive().await {
+        Ok(positions) => positions,
         Err(e) => {
-            error!("Failed to start AutoTrader: {}", e);
-            Err((
-                StatusCode::BAD_REQUEST,
+            error!("Failed to read positions archive for FIFO report: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
-                    error: "Failed to start AutoTrader".to_string(),
+                    error: "Failed to read positions archive".to_string(),
                     details: Some(e.to_string()),
                 }),
-            ))
+            ));
         }
+    };
+    let report = accounting::fifo_report(&positions, query.token.as_deref(), query.from, query.to);
+
+    if csv {
+        let headers = [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"fifo_realized.csv\""),
+        ];
+        Ok((headers, accounting::realized_csv(&report)).into_response())
+    } else {
+        Ok(Json(report).into_response())
     }
 }
 
-pub async fn stop_autotrader(
+/// Closed positions with entry/exit times, prices, fees and PnL, as CSV or JSON
+pub async fn export_trades(
     State(state): State<AppState>,
-) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let auto_trader = state.auto_trader.lock().await;
-
-    match auto_trader.stop().await {
-        Ok(_) => {
-            info!("AutoTrader stopped via API");
+    Query(query): Query<TradeExportQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let csv = match query.format.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("csv") => true,
+        Some("json") => false,
+        Some(other) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Unknown format '{}'", other),
+                    details: Some("Use csv or json".to_string()),
+                }),
+            ));
+        }
+    };
 
-            // Broadcast status change
-            state.broadcast(WsMessage::StatusChange {
-                running: false,
-                timestamp: Utc::now(),
-            });
+    let position_manager = state.auto_trader.lock().await.position_manager.clone();
+    let positions = match position_manager.get_all_positions_with_archive().await {
+        Ok(positions) => positions,
+        Err(e) => {
+            error!("Failed to read positions archive for trade export: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Failed to read positions archive".to_string(),
+                    details: Some(e.to_string()),
+                }),
+            ));
+        }
+    };
+    let trades = accounting::closed_trades(&positions, query.from, query.to);
+
+    if csv {
+        let headers = [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"trades.csv\""),
+        ];
+        Ok((headers, accounting::closed_trades_csv(&trades)).into_response())
+    } else {
+        Ok(Json(trades).into_response())
+    }
+}
 
-            Ok(Json(SuccessResponse {
-                success: true,
-                message: "AutoTrader stopped".to_string(),
-            }))
+/// PnL summary of one UTC day's closed trades, or of the last 24 hours without `?date=`
+pub async fn get_daily_report(
+    State(state): State<AppState>,
+    Query(query): Query<DailyReportQuery>,
+) -> Result<Json<PnlReport>, (StatusCode, Json<ErrorResponse>)> {
+    let (from, to) = match query.date {
+        Some(date) => pnl_report::day_bounds(date),
+        None => {
+            let now = Utc::now();
+            (now - ReportPeriod::Daily.length(), now)
         }
+    };
+
+    let position_manager = state.auto_trader.lock().await.position_manager.clone();
+    match position_manager.pnl_report(ReportPeriod::Daily, from, to).await {
+        Ok(report) => Ok(Json(report)),
         Err(e) => {
-            error!("Failed to stop AutoTrader: {}", e);
+            error!("Failed to compile daily PnL report: {}", e);
             Err((
-                StatusCode::BAD_REQUEST,
+                StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
-                    error: "Failed to stop AutoTrader".to_string(),
+                    error: "Failed to compile PnL report".to_string(),
                     details: Some(e.to_string()),
                 }),
             ))
@@ -610,61 +1894,83 @@ pub async fn stop_autotrader(
 }
 
 // ============================================================================
-// Token Analysis
+// Limit Orders
 // ============================================================================
 
-pub async fn analyze_token(
+/// Place a limit buy that waits for the price to fall to a target
+pub async fn place_limit_order(
     State(state): State<AppState>,
-    Json(req): Json<AnalyzeRequest>,
-) -> Result<Json<AnalyzeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    Json(req): Json<LimitOrderRequest>,
+) -> Result<Json<LimitOrder>, (StatusCode, Json<ErrorResponse>)> {
+    let expires_in_minutes = req.expires_in_minutes.unwrap_or(state.config.limit_order_default_expiry_minutes);
+    if expires_in_minutes == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "expires_in_minutes must be at least 1".to_string(),
+                details: None,
+            }),
+        ));
+    }
+
     let auto_trader = state.auto_trader.lock().await;
+    match auto_trader.place_limit_order(&req.token_address, req.target_price_sol, req.amount_sol, expires_in_minutes).await {
+        Ok(order) => Ok(Json(order)),
+        Err(e) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Failed to place limit order".to_string(),
+                details: Some(e.to_string()),
+            }),
+        )),
+    }
+}
 
-    match auto_trader.risk_analyzer.analyze_token(&req.address).await {
-        Ok(analysis) => {
-            let risk_rating = match analysis.risk_level {
-                0..=25 => "Low",
-                26..=50 => "Medium",
-                51..=75 => "High",
-                _ => "Very High",
-            };
+/// Buy and sell back a tiny amount of a liquid token to check the live execution path
+pub async fn run_test_swap(
+    State(state): State<AppState>,
+) -> Result<Json<TestSwapReport>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+    match auto_trader.run_test_swap().await {
+        Ok(report) => Ok(Json(report)),
+        Err(e) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Test swap not run".to_string(),
+                details: Some(e.to_string()),
+            }),
+        )),
+    }
+}
 
-            let recommendation = if analysis.risk_level <= 30 && analysis.can_sell && analysis.liquidity_sol >= 10.0 {
-                "Consider trading with caution"
-            } else if analysis.risk_level <= 50 && analysis.can_sell {
-                "High risk - small position only"
-            } else if !analysis.can_sell {
-                "DO NOT TRADE - Cannot sell (honeypot)"
-            } else {
-                "Avoid - Too risky"
-            };
+/// List limit orders, newest first
+pub async fn list_limit_orders(
+    State(state): State<AppState>,
+) -> Result<Json<LimitOrdersResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+    let orders = auto_trader.limit_orders.list().await;
+    let pending = orders.iter()
+        .filter(|o| o.status == LimitOrderStatus::Pending)
+        .count();
 
-            Ok(Json(AnalyzeResponse {
-                token_address: analysis.token_address,
-                risk_level: analysis.risk_level,
-                risk_rating: risk_rating.to_string(),
-                liquidity_sol: analysis.liquidity_sol,
-                holder_count: analysis.holder_count,
-                has_mint_authority: analysis.has_mint_authority,
-                has_freeze_authority: analysis.has_freeze_authority,
-                lp_tokens_burned: analysis.lp_tokens_burned,
-                transfer_tax_percent: analysis.transfer_tax_percent,
-                can_sell: analysis.can_sell,
-                concentration_percent: analysis.concentration_percent,
-                details: analysis.details,
-                recommendation: recommendation.to_string(),
-            }))
-        }
-        Err(e) => {
-            error!("Failed to analyze token {}: {}", req.address, e);
-            Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Failed to analyze token".to_string(),
-                    details: Some(e.to_string()),
-                }),
-            ))
-        }
-    }
+    Ok(Json(LimitOrdersResponse { orders, pending }))
+}
+
+/// Cancel a pending limit order
+pub async fn cancel_limit_order(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<LimitOrder>, (StatusCode, Json<ErrorResponse>)> {
+    let auto_trader = state.auto_trader.lock().await;
+    auto_trader.limit_orders.cancel(&id).await
+        .map(Json)
+        .map_err(|e| (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Cannot cancel limit order".to_string(),
+                details: Some(e.to_string()),
+            }),
+        ))
 }
 
 // ============================================================================
@@ -692,6 +1998,7 @@ pub async fn get_signals(
             is_active: s.is_active,
             current_price_sol: s.current_price_sol,
             current_pnl_percent: s.current_pnl_percent,
+            source_wallet: s.source_wallet.clone(),
         })
         .collect();
 
@@ -724,6 +2031,7 @@ pub async fn get_active_signals(
             is_active: s.is_active,
             current_price_sol: s.current_price_sol,
             current_pnl_percent: s.current_pnl_percent,
+            source_wallet: s.source_wallet.clone(),
         })
         .collect();
 
@@ -735,10 +2043,88 @@ pub async fn get_active_signals(
     }))
 }
 
+// ============================================================================
+// Copy Trade - Source Wallets
+// ============================================================================
+
+/// List external wallets watched for copy-trade signals
+pub async fn list_source_wallets(
+    State(state): State<AppState>,
+) -> Result<Json<SourceWalletsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let sources = state.copy_trade_manager.list_source_wallets().await;
+    let total = sources.len();
+    Ok(Json(SourceWalletsResponse { sources, total }))
+}
+
+/// Start watching an external wallet; its swaps become trade signals
+pub async fn add_source_wallet(
+    State(state): State<AppState>,
+    Json(req): Json<AddSourceWalletRequest>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    match state.copy_trade_manager.add_source_wallet(&req.address, req.label).await {
+        Ok(_) => Ok(Json(SuccessResponse {
+            success: true,
+            message: format!("Watching {} for copy-trade signals", req.address),
+        })),
+        Err(e) => {
+            warn!("Failed to add source wallet: {}", e);
+            Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Failed to add source wallet".to_string(),
+                    details: Some(e.to_string()),
+                }),
+            ))
+        }
+    }
+}
+
+/// Stop watching an external wallet
+pub async fn remove_source_wallet(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    match state.copy_trade_manager.remove_source_wallet(&address).await {
+        Ok(_) => Ok(Json(SuccessResponse {
+            success: true,
+            message: format!("Stopped watching {}", address),
+        })),
+        Err(e) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Failed to remove source wallet".to_string(),
+                details: Some(e.to_string()),
+            }),
+        )),
+    }
+}
+
 // ============================================================================
 // Copy Trade - Registration
 // ============================================================================
 
+/// Issue the message a wallet signs to register or unregister
+pub async fn get_copy_trade_nonce(
+    State(state): State<AppState>,
+    Query(query): Query<CopyTradeNonceQuery>,
+) -> Result<Json<CopyTradeNonceResponse>, (StatusCode, Json<ErrorResponse>)> {
+    match state.copy_trade_manager.issue_nonce(&query.wallet).await {
+        Ok(issued) => Ok(Json(CopyTradeNonceResponse {
+            wallet_address: query.wallet,
+            nonce: issued.nonce,
+            message: issued.message,
+            expires_at: issued.expires_at,
+        })),
+        Err(e) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Failed to issue nonce".to_string(),
+                details: Some(e.to_string()),
+            }),
+        )),
+    }
+}
+
 /// Register a wallet for copy trading
 pub async fn register_copy_trader(
     State(state): State<AppState>,
@@ -774,9 +2160,19 @@ pub async fn unregister_copy_trader(
     State(state): State<AppState>,
     Json(req): Json<CopyTradeRegisterRequest>,
 ) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if state.copy_trade_manager.get_trader(&req.wallet_address).await.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Failed to unregister".to_string(),
+                details: Some("Wallet not registered".to_string()),
+            }),
+        ));
+    }
+
     match state
         .copy_trade_manager
-        .unregister_trader(&req.wallet_address)
+        .unregister_trader(&req.wallet_address, &req.signature, &req.message)
         .await
     {
         Ok(_) => {
@@ -789,7 +2185,7 @@ pub async fn unregister_copy_trader(
         Err(e) => {
             warn!("Failed to unregister copy trader: {}", e);
             Err((
-                StatusCode::NOT_FOUND,
+                StatusCode::UNAUTHORIZED,
                 Json(ErrorResponse {
                     error: "Failed to unregister".to_string(),
                     details: Some(e.to_string()),
@@ -1267,13 +2663,14 @@ pub async fn set_active_strategy_type(
         "finalstretch" | "final_stretch" | "bonding" => StrategyType::FinalStretch,
         "migrated" | "graduated" => StrategyType::Migrated,
         "telegramcall" | "telegram_call" | "telegram" => StrategyType::TelegramCall,
+        "pumpfunbondingcurve" | "pumpfun_bonding_curve" | "bonding_curve" | "curve" => StrategyType::PumpfunBondingCurve,
         _ => {
             return Err((
                 StatusCode::BAD_REQUEST,
                 Json(ErrorResponse {
                     error: "Invalid strategy type".to_string(),
                     details: Some(format!(
-                        "Valid types: NewPairs, FinalStretch, Migrated, TelegramCall. Got: {}",
+                        "Valid types: NewPairs, FinalStretch, Migrated, TelegramCall, PumpfunBondingCurve. Got: {}",
                         req.strategy_type
                     )),
                 }),
@@ -1353,3 +2750,123 @@ pub async fn get_watchlist_stats(
         max_capacity: stats.max_capacity,
     }))
 }
+
+// =============================================================================
+// State Export / Import
+// =============================================================================
+
+/// Export strategies, positions, limit orders and copy-trade state as one bundle.
+/// See `state_bundle` for what is and isn't included.
+pub async fn export_state(
+    State(state): State<AppState>,
+) -> Result<Json<StateBundle>, (StatusCode, Json<ErrorResponse>)> {
+    let config = state_bundle::redacted_config(&state.config).map_err(|e| {
+        error!("Failed to build state export: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Failed to export state".to_string(),
+                details: Some(e.to_string()),
+            }),
+        )
+    })?;
+
+    let auto_trader = state.auto_trader.lock().await;
+    let bundle = StateBundle {
+        version: STATE_BUNDLE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: Utc::now(),
+        strategies: auto_trader.list_strategies().await,
+        positions: auto_trader.position_manager.get_all_positions().await,
+        limit_orders: auto_trader.limit_orders.list().await,
+        copy_trade: state.copy_trade_manager.export_state().await,
+        config,
+    };
+
+    info!(
+        "Exported state: {} strategies, {} positions, {} limit orders",
+        bundle.strategies.len(),
+        bundle.positions.len(),
+        bundle.limit_orders.len()
+    );
+    Ok(Json(bundle))
+}
+
+/// Restore a bundle from `export_state`, replacing this instance's strategies,
+/// positions, limit orders and copy-trade state. Refuses to overwrite an instance that is
+/// trading or already holds positions unless `force=true`.
+pub async fn import_state(
+    State(state): State<AppState>,
+    Query(query): Query<ImportStateQuery>,
+    Json(bundle): Json<StateBundle>,
+) -> Result<Json<ImportStateResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if let Err(e) = state_bundle::check_compatible(&bundle) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Incompatible state bundle".to_string(),
+                details: Some(e.to_string()),
+            }),
+        ));
+    }
+
+    let auto_trader = state.auto_trader.lock().await;
+    if !query.force.unwrap_or(false) {
+        let running = auto_trader.get_status().await;
+        let existing_positions = auto_trader.position_manager.get_all_positions().await.len();
+        if running || existing_positions > 0 {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(ErrorResponse {
+                    error: "Instance already has state".to_string(),
+                    details: Some(format!(
+                        "AutoTrader running: {}, existing positions: {}. Stop the AutoTrader and retry with force=true to overwrite.",
+                        running, existing_positions
+                    )),
+                }),
+            ));
+        }
+    }
+
+    let internal_error = |e: anyhow::Error| {
+        error!("State import failed: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Failed to import state".to_string(),
+                details: Some(e.to_string()),
+            }),
+        )
+    };
+
+    let strategies = bundle.strategies.len();
+    let positions = bundle.positions.len();
+    let active_positions = bundle.positions.iter()
+        .filter(|p| p.status == PositionStatus::Active)
+        .count();
+    let limit_orders = bundle.limit_orders.len();
+    let copy_traders = bundle.copy_trade.traders.len();
+    let source_wallets = bundle.copy_trade.source_wallets.len();
+
+    auto_trader.replace_strategies(bundle.strategies).await.map_err(internal_error)?;
+    auto_trader.position_manager.replace_positions(bundle.positions).await.map_err(internal_error)?;
+    auto_trader.limit_orders.replace(bundle.limit_orders).await.map_err(internal_error)?;
+    state.copy_trade_manager.import_state(bundle.copy_trade).await.map_err(internal_error)?;
+
+    warn!(
+        "Imported state bundle v{} exported at {}: {} strategies, {} positions ({} active), {} limit orders",
+        bundle.version, bundle.exported_at, strategies, positions, active_positions, limit_orders
+    );
+
+    Ok(Json(ImportStateResponse {
+        success: true,
+        bundle_version: bundle.version,
+        exported_at: bundle.exported_at,
+        strategies,
+        positions,
+        active_positions,
+        limit_orders,
+        copy_traders,
+        source_wallets,
+    }))
+}
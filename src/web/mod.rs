@@ -3,31 +3,53 @@
 //! This module provides the REST API and WebSocket server for the trading bot,
 //! replacing the previous Telegram bot interface.
 
+pub mod auth;
+pub mod timeout;
 pub mod server;
 pub mod routes;
 pub mod handlers;
 pub mod websocket;
 pub mod models;
 pub mod copy_trade;
+pub mod copy_sources;
+pub mod state_bundle;
 
+use std::collections::HashMap;
 use std::sync::Arc;
+use chrono::{DateTime, Datelike, NaiveTime, Utc};
 use tokio::sync::{broadcast, Mutex};
+use tracing::{info, warn};
 
-use crate::config::Config;
+use crate::config::{Config, ConfigReload};
 use crate::solana::client::SolanaClient;
 use crate::solana::wallet::WalletManager;
-use crate::trading::autotrader::AutoTrader;
+use crate::solana::wallet_pool::WalletPool;
+use crate::storage::Store;
+use crate::trading::autotrader::{AutoTrader, ScanSettings};
+use crate::trading::pnl_report::{next_report_time, ReportPeriod};
+use crate::trading::startup_digest::StartupDigest;
 
 use self::copy_trade::CopyTradeManager;
-use self::websocket::WsMessage;
+use self::websocket::{NotificationQueue, WsMessage};
+
+/// A manual snipe waiting for explicit confirmation because it exceeded
+/// `require_confirmation_above_sol`
+#[derive(Debug, Clone)]
+pub struct PendingSnipe {
+    pub token_address: String,
+    pub amount_sol: f64,
+    pub expires_at: DateTime<Utc>,
+}
 
 /// Shared application state for all API handlers
 #[derive(Clone)]
 pub struct AppState {
     /// The AutoTrader instance for managing trading operations
     pub auto_trader: Arc<Mutex<AutoTrader>>,
-    /// Wallet manager for transaction signing
+    /// Wallet manager for transaction signing (primary wallet)
     pub wallet_manager: Arc<WalletManager>,
+    /// All trading wallets (primary first)
+    pub wallet_pool: Arc<WalletPool>,
     /// Solana RPC client
     pub solana_client: Arc<SolanaClient>,
     /// Application configuration
@@ -36,35 +58,69 @@ pub struct AppState {
     pub ws_tx: broadcast::Sender<WsMessage>,
     /// Copy trade manager for handling copy trading functionality
     pub copy_trade_manager: Arc<CopyTradeManager>,
+    /// Large manual snipes awaiting confirmation, keyed by confirmation id
+    pub pending_snipes: Arc<Mutex<HashMap<String, PendingSnipe>>>,
+    /// Digest sent after startup, replayed to clients that connect later
+    pub startup_digest: Arc<Mutex<Option<WsMessage>>>,
+    /// Alerts broadcast while no client was connected
+    pub notification_queue: Arc<NotificationQueue>,
+    /// Scan settings as last read from `.env`; a reload only applies the ones that
+    /// changed there, leaving changes made through the API alone
+    pub env_scan_settings: Arc<Mutex<ScanSettings>>,
 }
 
 impl AppState {
     /// Create a new AppState instance
     pub fn new(
         auto_trader: Arc<Mutex<AutoTrader>>,
-        wallet_manager: Arc<WalletManager>,
+        wallet_pool: Arc<WalletPool>,
         solana_client: Arc<SolanaClient>,
+        store: Arc<dyn Store>,
         config: Arc<Config>,
     ) -> Self {
-        // Create broadcast channel for WebSocket messages (capacity of 100 messages)
-        let (ws_tx, _) = broadcast::channel(100);
+        // Create broadcast channel for WebSocket messages; clients that fall further behind get a resync
+        let (ws_tx, _) = broadcast::channel(config.ws_channel_capacity.max(1));
 
         // Create copy trade manager
-        let copy_trade_manager = Arc::new(CopyTradeManager::new(config.clone()));
+        let copy_trade_manager = Arc::new(CopyTradeManager::new(config.clone(), store));
+
+        // Holds alerts while no WebSocket client is connected
+        let notification_queue = Arc::new(NotificationQueue::new(config.notification_queue_max));
+
+        let env_scan_settings = Arc::new(Mutex::new(ScanSettings {
+            scan_interval_secs: config.scan_interval_secs,
+            token_age_minutes: config.scan_token_age_minutes,
+        }));
 
         Self {
             auto_trader,
-            wallet_manager,
+            wallet_manager: wallet_pool.primary(),
+            wallet_pool,
             solana_client,
             config,
             ws_tx,
             copy_trade_manager,
+            pending_snipes: Arc::new(Mutex::new(HashMap::new())),
+            startup_digest: Arc::new(Mutex::new(None)),
+            notification_queue,
+            env_scan_settings,
         }
     }
 
     /// Initialize async components (call after creation)
     pub async fn init(&self) -> anyhow::Result<()> {
         self.copy_trade_manager.init().await?;
+        if self.config.copy_source_poll_secs > 0 {
+            copy_sources::spawn_source_wallet_watcher(
+                self.copy_trade_manager.clone(),
+                self.solana_client.clone(),
+                self.config.copy_source_poll_secs,
+                self.config.copy_source_min_sol,
+            );
+        }
+        if let Some(at) = self.config.pnl_report_time {
+            self.spawn_pnl_reports(at);
+        }
         Ok(())
     }
 
@@ -73,9 +129,123 @@ impl AppState {
         self.ws_tx.subscribe()
     }
 
-    /// Broadcast a message to all WebSocket clients
+    /// Summarise the state the bot came up in and send it to clients
+    pub async fn send_startup_digest(&self) {
+        let digest = {
+            let trader = self.auto_trader.lock().await;
+            StartupDigest::collect(&trader, &self.wallet_pool, &self.config).await
+        };
+        let message = digest.summary();
+        if digest.is_real_mode() {
+            warn!("{}", message);
+        } else {
+            info!("{}", message);
+        }
+
+        let msg = WsMessage::StartupDigest {
+            message,
+            real_mode: digest.is_real_mode(),
+            mode: digest.mode,
+            open_positions: digest.open_positions,
+            open_value_sol: digest.open_value_sol,
+            trading_running: digest.trading_running,
+            enabled_strategies: digest.enabled_strategies,
+            wallet_balance_sol: digest.wallet_balance_sol,
+            timestamp: digest.timestamp,
+        }.with_capped_text(self.config.notification_max_chars);
+        *self.startup_digest.lock().await = Some(msg.clone());
+        self.broadcast(msg);
+    }
+
+    /// Re-read `.env` and the environment (SIGHUP or POST /api/config/reload).
+    /// Slippage, priority fees, API credentials and scan settings take effect at
+    /// once; position monitoring carries on. Other changes need a restart.
+    pub async fn reload_config(&self) -> anyhow::Result<ConfigReload> {
+        let (fresh, mut reload) = self.config.reload()?;
+
+        // Scan settings live in the AutoTrader, which may also have had them changed via
+        // the API. Only a value that changed in `.env` itself overrides those.
+        reload.restart_required.retain(|name| name != "scan_interval_secs" && name != "scan_token_age_minutes");
+        let mut env_scan = self.env_scan_settings.lock().await;
+        let scan_interval_secs = (fresh.scan_interval_secs != env_scan.scan_interval_secs)
+            .then_some(fresh.scan_interval_secs);
+        let token_age_minutes = (fresh.scan_token_age_minutes != env_scan.token_age_minutes)
+            .then_some(fresh.scan_token_age_minutes);
+        if scan_interval_secs.is_some() {
+            reload.applied.push("scan_interval_secs".to_string());
+        }
+        if token_age_minutes.is_some() {
+            reload.applied.push("scan_token_age_minutes".to_string());
+        }
+        if scan_interval_secs.is_some() || token_age_minutes.is_some() {
+            self.auto_trader.lock().await.update_scan_settings(scan_interval_secs, token_age_minutes).await?;
+        }
+        *env_scan = ScanSettings {
+            scan_interval_secs: fresh.scan_interval_secs,
+            token_age_minutes: fresh.scan_token_age_minutes,
+        };
+
+        info!("Config reloaded; applied: [{}]", reload.applied.join(", "));
+        if !reload.restart_required.is_empty() {
+            warn!("Config changes that need a restart: [{}]", reload.restart_required.join(", "));
+        }
+        Ok(reload)
+    }
+
+    /// Send the daily PnL report at `at` (UTC) every day, followed by the weekly one on
+    /// `pnl_report_weekday`
+    fn spawn_pnl_reports(&self, at: NaiveTime) {
+        let state = self.clone();
+        tokio::spawn(async move {
+            info!("PnL reports scheduled daily at {} UTC (weekly on {:?})", at.format("%H:%M"), state.config.pnl_report_weekday);
+            loop {
+                let due = next_report_time(Utc::now(), at);
+                let wait = (due - Utc::now()).to_std().unwrap_or_default();
+                tokio::time::sleep(wait).await;
+
+                state.send_pnl_report(ReportPeriod::Daily, due).await;
+                if state.config.pnl_report_weekday == Some(due.weekday()) {
+                    state.send_pnl_report(ReportPeriod::Weekly, due).await;
+                }
+            }
+        });
+    }
+
+    /// Summarise the trades closed in the period ending at `to` and send it to clients
+    pub async fn send_pnl_report(&self, period: ReportPeriod, to: DateTime<Utc>) {
+        let position_manager = self.auto_trader.lock().await.position_manager.clone();
+        let report = match position_manager.pnl_report(period, to - period.length(), to).await {
+            Ok(report) => report,
+            Err(e) => {
+                warn!("Failed to compile {:?} PnL report: {}", period, e);
+                return;
+            }
+        };
+        let message = report.summary();
+        info!("{}", message);
+
+        self.broadcast(WsMessage::PnlReport {
+            message,
+            period: report.period,
+            trades: report.trades,
+            win_rate_percent: report.win_rate_percent,
+            pnl_sol: report.pnl_sol,
+            pnl_usd: report.pnl_usd,
+            from: report.from,
+            to: report.to,
+        });
+    }
+
+    /// Broadcast a message to all WebSocket clients. With no client connected, alerts
+    /// are queued for the next one instead of being lost.
     pub fn broadcast(&self, msg: WsMessage) {
-        // Ignore errors (no subscribers)
-        let _ = self.ws_tx.send(msg);
+        let msg = msg.with_capped_text(self.config.notification_max_chars);
+        if let Err(broadcast::error::SendError(msg)) = self.ws_tx.send(msg) {
+            if msg.should_queue() {
+                if let Some(dropped) = self.notification_queue.push(msg) {
+                    warn!("Notification queue full, dropping oldest undelivered notification: {:?}", dropped);
+                }
+            }
+        }
     }
 }
@@ -1,7 +1,7 @@
 //! Request and Response DTOs for the Web API
 
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveTime, Utc};
 
 // ============================================================================
 // Health & Status
@@ -9,11 +9,28 @@ use chrono::{DateTime, Utc};
 
 #[derive(Debug, Serialize)]
 pub struct HealthResponse {
-    pub status: String,
+    pub status: String, // "ok", or "degraded" while the RPC lags
     pub version: String,
+    pub rpc_slot: Option<u64>,
+    pub rpc_slot_lag: Option<u64>, // slots behind at the last check (None until one runs)
+    pub rpc_lagging: bool,
+    pub rpc_endpoints: Vec<RpcEndpointHealth>,
     pub timestamp: DateTime<Utc>,
 }
 
+/// One RPC endpoint's health-probe record
+#[derive(Debug, Serialize)]
+pub struct RpcEndpointHealth {
+    pub host: String,
+    pub active: bool,
+    pub latency_ms: Option<f64>,
+    pub probes: u64,
+    pub errors: u64,
+    pub consecutive_errors: u32,
+    pub last_slot: Option<u64>,
+    pub last_error: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct AutoTraderStatus {
     pub running: bool,
@@ -23,16 +40,86 @@ pub struct AutoTraderStatus {
     pub active_positions: usize,
 }
 
+/// One-shot overview of the whole system (GET /api/status)
+#[derive(Debug, Serialize)]
+pub struct SystemStatusResponse {
+    pub running: bool,
+    pub mode: String, // "demo", "dry_run" or "real"
+    pub wallet_balance_sol: Option<f64>,
+    pub open_positions: usize,
+    pub open_positions_value_sol: f64,
+    pub realized_pnl_today_sol: f64,
+    pub enabled_strategies: usize,
+    pub total_strategies: usize,
+    pub rpc_healthy: bool,
+    pub rpc_latency_ms: Option<u64>,
+    pub rpc_slot: Option<u64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Operational metrics (GET /api/metrics)
+#[derive(Debug, Serialize)]
+pub struct MetricsResponse {
+    pub swap_failures_total: u64,
+    pub swap_failures: Vec<SwapFailureCount>,
+    pub swap_breaker: crate::api::swap_breaker::SwapBreakerStatus,
+    pub loss_breaker: crate::trading::loss_breaker::LossBreakerStatus,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SwapFailureCount {
+    pub category: String,
+    pub count: u64,
+    pub percent: f64, // share of all swap failures
+}
+
 // ============================================================================
 // Wallet
 // ============================================================================
 
 #[derive(Debug, Serialize)]
 pub struct WalletResponse {
+    pub address: String,     // Primary wallet
+    pub balance_sol: f64,    // Combined balance across all wallets
+    pub wallets: Vec<WalletBalanceResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WalletBalanceResponse {
     pub address: String,
     pub balance_sol: f64,
 }
 
+/// Wallet SOL plus open positions marked to their last price
+#[derive(Debug, Serialize)]
+pub struct PortfolioResponse {
+    pub sol_balance: f64,                  // Across all wallets
+    pub wallets: Vec<WalletBalanceResponse>,
+    pub positions: Vec<PortfolioPositionResponse>,
+    pub positions_value_sol: f64,
+    pub unrealized_pnl_sol: f64,
+    pub total_equity_sol: f64,
+    pub sol_price_usd: Option<f64>,        // Birdeye; None when unavailable
+    pub total_equity_usd: Option<f64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortfolioPositionResponse {
+    pub id: String,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub wallet_address: String,
+    pub token_amount: f64,                 // Tokens the position holds
+    pub wallet_token_balance: Option<f64>, // On-chain balance of the token in that wallet (None for demo or if unreadable)
+    pub price_sol: f64,
+    pub entry_value_sol: f64,
+    pub value_sol: f64,                    // Net of transfer tax
+    pub unrealized_pnl_sol: f64,           // Including any partial sells
+    pub unrealized_pnl_percent: f64,
+}
+
 // ============================================================================
 // Positions
 // ============================================================================
@@ -55,6 +142,10 @@ pub struct PositionResponse {
     pub opened_at: DateTime<Utc>,
     pub closed_at: Option<DateTime<Utc>>,
     pub exit_reason: Option<String>,
+    pub profit_fee_sol: Option<f64>,
+    pub entry_value_usd: Option<f64>,
+    pub exit_value_usd: Option<f64>,
+    pub pnl_usd: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -63,6 +154,22 @@ pub struct PositionsListResponse {
     pub total: usize,
 }
 
+/// A position's recorded prices and decision points, for post-trade analysis
+#[derive(Debug, Serialize)]
+pub struct PositionReplayResponse {
+    pub position_id: String,
+    pub token_symbol: String,
+    pub status: String,
+    pub entry_time: DateTime<Utc>,
+    pub exit_time: Option<DateTime<Utc>>,
+    pub entry_price_sol: f64,
+    pub exit_price_sol: Option<f64>,
+    pub exit_reason: Option<String>, // What triggered the exit (e.g. "SL Hit"), from the timeline
+    pub pnl_percent: Option<f64>,
+    pub samples: Vec<crate::trading::position_history::PriceSample>,
+    pub events: Vec<crate::trading::position_history::PositionEvent>,
+}
+
 // ============================================================================
 // Trades
 // ============================================================================
@@ -78,6 +185,7 @@ pub struct TradeResponse {
     pub price: f64,
     pub pnl_sol: Option<f64>,
     pub pnl_percent: Option<f64>,
+    pub pnl_usd: Option<f64>,
     pub transaction_signature: String,
     pub timestamp: DateTime<Utc>,
 }
@@ -86,6 +194,41 @@ pub struct TradeResponse {
 pub struct TradesQuery {
     pub page: Option<u32>,
     pub limit: Option<u32>,
+    pub include_dust: Option<bool>,
+}
+
+/// Query for `POST /api/state/import`
+#[derive(Debug, Deserialize)]
+pub struct ImportStateQuery {
+    /// Import even though this instance already has positions or is trading
+    pub force: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportStateResponse {
+    pub success: bool,
+    pub bundle_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub strategies: usize,
+    pub positions: usize,
+    pub active_positions: usize,
+    pub limit_orders: usize,
+    pub copy_traders: usize,
+    pub source_wallets: usize,
+}
+
+/// Query for listings that hide dust positions by default
+#[derive(Debug, Deserialize)]
+pub struct DustQuery {
+    pub include_dust: Option<bool>,
+}
+
+/// Query for `GET /api/stats`
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    pub include_dust: Option<bool>,
+    /// Also count positions moved to the archive (read from the store)
+    pub include_archived: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -107,6 +250,7 @@ pub struct StatsResponse {
     pub losing_trades: u32,
     pub win_rate: f64,
     pub total_pnl_sol: f64,
+    pub total_pnl_usd: Option<f64>, // Over closed trades with a USD basis (None if none have one)
     pub avg_roi_percent: f64,
     pub total_volume_sol: f64,
     pub best_trade_pnl: f64,
@@ -143,12 +287,31 @@ pub struct CreateStrategyRequest {
     pub max_position_size_sol: Option<f64>,
     pub total_budget_sol: Option<f64>,
     pub stop_loss_percent: Option<u32>,
+    pub stop_loss_type: Option<crate::trading::strategy::StopLossType>,
     pub take_profit_percent: Option<u32>,
     pub trailing_stop_percent: Option<u32>,
+    pub peak_drawdown_alert_percent: Option<f64>,
+    pub peak_drawdown_sell_percent: Option<f64>,
     pub max_hold_time_minutes: Option<u32>,
+    pub force_close_at: Option<NaiveTime>,
+    pub momentum_tp: Option<crate::trading::strategy::MomentumTpSettings>,
+    pub scale_in: Option<crate::trading::strategy::ScaleInSettings>,
+    pub dca: Option<crate::trading::strategy::DcaSettings>,
+    pub limit_entry: Option<crate::trading::strategy::LimitEntrySettings>,
+    pub notify_trades: Option<crate::trading::strategy::TradeNotify>,
     pub min_liquidity_sol: Option<u32>,
     pub max_risk_level: Option<u32>,
     pub min_holders: Option<u32>,
+    pub max_token_age_seconds: Option<u32>,
+    pub scan_interval_secs: Option<u32>,
+    pub scan_token_age_minutes: Option<u32>,
+    pub entry_retry_attempts: Option<u32>,
+    pub entry_retry_delay_ms: Option<u64>,
+    pub use_jito: Option<bool>,
+    pub jito_tip_lamports: Option<u64>,
+    pub execution_venue: Option<crate::trading::strategy::ExecutionVenue>,
+    pub fee_urgency: Option<crate::solana::fees::FeeUrgency>,
+    pub token_lists: Option<crate::trading::strategy::TokenLists>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -159,12 +322,47 @@ pub struct UpdateStrategyRequest {
     pub max_position_size_sol: Option<f64>,
     pub total_budget_sol: Option<f64>,
     pub stop_loss_percent: Option<u32>,
+    pub stop_loss_type: Option<crate::trading::strategy::StopLossType>,
     pub take_profit_percent: Option<u32>,
     pub trailing_stop_percent: Option<u32>,
+    pub peak_drawdown_alert_percent: Option<f64>,
+    pub peak_drawdown_sell_percent: Option<f64>,
     pub max_hold_time_minutes: Option<u32>,
+    pub force_close_at: Option<NaiveTime>,
+    pub momentum_tp: Option<crate::trading::strategy::MomentumTpSettings>,
+    pub scale_in: Option<crate::trading::strategy::ScaleInSettings>,
+    pub dca: Option<crate::trading::strategy::DcaSettings>,
+    pub limit_entry: Option<crate::trading::strategy::LimitEntrySettings>,
+    pub notify_trades: Option<crate::trading::strategy::TradeNotify>,
     pub min_liquidity_sol: Option<u32>,
     pub max_risk_level: Option<u32>,
     pub min_holders: Option<u32>,
+    pub max_token_age_seconds: Option<u32>,
+    pub scan_interval_secs: Option<u32>,
+    pub scan_token_age_minutes: Option<u32>,
+    pub entry_retry_attempts: Option<u32>,
+    pub entry_retry_delay_ms: Option<u64>,
+    pub use_jito: Option<bool>,
+    pub jito_tip_lamports: Option<u64>,
+    pub execution_venue: Option<crate::trading::strategy::ExecutionVenue>,
+    pub fee_urgency: Option<crate::solana::fees::FeeUrgency>,
+    pub token_lists: Option<crate::trading::strategy::TokenLists>, // Replaces all four lists
+}
+
+/// A strategy's recorded performance series
+#[derive(Debug, Serialize)]
+pub struct StrategyHistoryResponse {
+    pub strategy_id: String,
+    pub snapshots: Vec<crate::trading::strategy_stats::StrategyStatsSnapshot>,
+    pub total: usize,
+}
+
+/// A strategy's recorded edits
+#[derive(Debug, Serialize)]
+pub struct StrategyChangelogResponse {
+    pub strategy_id: String,
+    pub changes: Vec<crate::trading::strategy_changelog::StrategyChange>,
+    pub total: usize,
 }
 
 #[derive(Debug, Serialize)]
@@ -173,6 +371,57 @@ pub struct StrategiesListResponse {
     pub total: usize,
 }
 
+/// A named strategy preset with its pre-filled fields
+#[derive(Debug, Serialize)]
+pub struct StrategyTemplateResponse {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub strategy_type: String,
+    pub defaults: StrategyResponse,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StrategyTemplatesResponse {
+    pub templates: Vec<StrategyTemplateResponse>,
+}
+
+/// New scan settings; omitted fields keep their current value
+#[derive(Debug, Deserialize)]
+pub struct UpdateScanSettingsRequest {
+    pub scan_interval_secs: Option<u64>,
+    pub token_age_minutes: Option<u64>,
+}
+
+/// Token to evaluate against the enabled strategies
+#[derive(Debug, Deserialize)]
+pub struct StrategyMatchRequest {
+    pub token_address: String,
+}
+
+/// Strategy and tokens to replay historical candles for. The exit fields override
+/// the strategy's own for this run only, to compare settings without saving them.
+#[derive(Debug, Deserialize)]
+pub struct BacktestRequest {
+    pub strategy_id: String,
+    pub token_addresses: Vec<String>,
+    pub from: DateTime<Utc>,
+    pub to: Option<DateTime<Utc>>,        // default now
+    pub interval: Option<String>,         // Birdeye candle size, default "5m"
+    pub cost_bps: Option<u32>,            // Fees + price impact per side, default 100
+    pub stop_loss_percent: Option<u32>,
+    pub take_profit_percent: Option<u32>,
+    pub trailing_stop_percent: Option<u32>,
+    pub max_hold_time_minutes: Option<u32>,
+}
+
+/// Request to create a strategy from a named template
+#[derive(Debug, Deserialize)]
+pub struct CreateFromTemplateRequest {
+    pub template: String,
+    pub name: Option<String>,
+}
+
 // ============================================================================
 // Token Analysis
 // ============================================================================
@@ -180,6 +429,7 @@ pub struct StrategiesListResponse {
 #[derive(Debug, Deserialize)]
 pub struct AnalyzeRequest {
     pub address: String,
+    pub amount_sol: Option<f64>,         // Size for the round-trip quote (default: snipe amount)
 }
 
 #[derive(Debug, Serialize)]
@@ -195,10 +445,104 @@ pub struct AnalyzeResponse {
     pub transfer_tax_percent: f64,
     pub can_sell: bool,
     pub concentration_percent: f64,
+    pub roundtrip_loss_percent: Option<f64>, // Lost buying and selling straight back (None if not quotable)
     pub details: Vec<String>,
     pub recommendation: String,
 }
 
+// ============================================================================
+// Manual Snipe
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ManualSnipeRequest {
+    pub token_address: String,
+    pub amount_sol: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmSnipeRequest {
+    pub confirmation_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManualSnipeResponse {
+    pub status: String, // "executed" or "confirmation_required"
+    pub token_address: String,
+    pub amount_sol: f64,
+    pub signature: Option<String>,
+    pub confirmation_id: Option<String>,
+    pub threshold_sol: Option<f64>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Quote and fee estimate shown before confirming (None if the quote failed)
+    pub preview: Option<crate::api::jupiter::BuyPreview>,
+    pub message: String,
+}
+
+// ============================================================================
+// Incidents (Escalation)
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct IncidentsResponse {
+    pub incidents: Vec<crate::trading::escalation::Incident>,
+    pub open: usize,
+}
+
+// ============================================================================
+// Loss Rebuy Guard
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct LossBlacklistResponse {
+    pub mode: crate::config::LossRebuyGuard,
+    pub cooldown_minutes: u64,
+    pub tokens: Vec<crate::trading::position::LosingExit>,
+}
+
+// ============================================================================
+// Accounting
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct FifoQuery {
+    pub token: Option<String>,           // Limit to one mint
+    pub from: Option<DateTime<Utc>>,     // Disposals at or after this time
+    pub to: Option<DateTime<Utc>>,       // Disposals before this time
+    pub format: Option<String>,          // "json" (default) or "csv" (realized lots only)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TradeExportQuery {
+    pub format: Option<String>,          // "csv" (default) or "json"
+    pub from: Option<DateTime<Utc>>,     // Exits at or after this time
+    pub to: Option<DateTime<Utc>>,       // Exits before this time
+}
+
+/// Query for GET /api/reports/daily
+#[derive(Debug, Deserialize)]
+pub struct DailyReportQuery {
+    pub date: Option<chrono::NaiveDate>, // UTC calendar day; default the 24 hours up to now
+}
+
+// ============================================================================
+// Limit Orders
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct LimitOrderRequest {
+    pub token_address: String,
+    pub target_price_sol: f64,      // Buy once the price is at or below this
+    pub amount_sol: f64,
+    pub expires_in_minutes: Option<u64>, // Defaults to LIMIT_ORDER_DEFAULT_EXPIRY_MINUTES
+}
+
+#[derive(Debug, Serialize)]
+pub struct LimitOrdersResponse {
+    pub orders: Vec<crate::trading::limit_orders::LimitOrder>,
+    pub pending: usize,
+}
+
 // ============================================================================
 // Generic Responses
 // ============================================================================
@@ -234,6 +578,7 @@ pub struct SignalResponse {
     pub is_active: bool,
     pub current_price_sol: Option<f64>,
     pub current_pnl_percent: Option<f64>,
+    pub source_wallet: Option<String>,
 }
 
 /// Response for signals list
@@ -243,11 +588,41 @@ pub struct SignalsListResponse {
     pub total: usize,
 }
 
-/// Request to register for copy trading
+/// Request to watch an external wallet for copy-trade signals
+#[derive(Debug, Deserialize)]
+pub struct AddSourceWalletRequest {
+    pub address: String,
+    pub label: Option<String>,
+}
+
+/// Response for source wallets list
+#[derive(Debug, Serialize)]
+pub struct SourceWalletsResponse {
+    pub sources: Vec<crate::models::copy_trade::SourceWallet>,
+    pub total: usize,
+}
+
+/// Query params for `/api/copy/nonce`
+#[derive(Debug, Deserialize)]
+pub struct CopyTradeNonceQuery {
+    pub wallet: String,
+}
+
+/// Message for a wallet to sign before registering or unregistering
+#[derive(Debug, Serialize)]
+pub struct CopyTradeNonceResponse {
+    pub wallet_address: String,
+    pub nonce: String,
+    pub message: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Request to register for (or unregister from) copy trading, signed with the
+/// wallet over the message from `/api/copy/nonce`
 #[derive(Debug, Deserialize)]
 pub struct CopyTradeRegisterRequest {
     pub wallet_address: String,
-    pub signature: String,
+    pub signature: String, // base58 or base64 ed25519 signature
     pub message: String,
 }
 
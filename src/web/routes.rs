@@ -1,11 +1,14 @@
 //! API route definitions
 
 use axum::{
-    routing::{get, post, put, delete},
+    middleware,
+    routing::{get, post, put, patch, delete},
     Router,
 };
 
+use super::auth;
 use super::handlers;
+use super::timeout;
 use super::websocket::ws_handler;
 use super::AppState;
 
@@ -15,15 +18,32 @@ pub fn create_routes(state: AppState) -> Router {
         // Health check
         .route("/api/health", get(handlers::health_check))
 
+        // System overview (running state, mode, balance, positions, PnL, RPC health)
+        .route("/api/status", get(handlers::get_system_status))
+
+        // Operational metrics (swap failures by category)
+        .route("/api/metrics", get(handlers::get_metrics))
+
         // Wallet
         .route("/api/wallet", get(handlers::get_wallet))
+        .route("/api/wallet/sweep", post(handlers::sweep_wallet))
+        .route("/api/portfolio", get(handlers::get_portfolio))
 
         // Positions
         .route("/api/positions", get(handlers::get_positions))
         .route("/api/positions/active", get(handlers::get_active_positions))
+        .route("/api/positions/archive", get(handlers::get_archived_positions))
+        .route("/api/positions/loss-blacklist", get(handlers::get_loss_blacklist))
+        .route("/api/positions/loss-blacklist/:token", delete(handlers::clear_loss_blacklist_entry))
+        .route("/api/positions/:id/close", post(handlers::close_position))
+        .route("/api/positions/:id/cancel-exit", post(handlers::cancel_position_exit))
+        .route("/api/positions/:id/replay", get(handlers::get_position_replay))
+        .route("/api/positions/:id/exit-preview", post(handlers::preview_position_exit))
 
         // Trades
         .route("/api/trades", get(handlers::get_trades))
+        .route("/api/trades/export", get(handlers::export_trades))
+        .route("/api/reports/daily", get(handlers::get_daily_report))
 
         // Statistics
         .route("/api/stats", get(handlers::get_stats))
@@ -31,10 +51,16 @@ pub fn create_routes(state: AppState) -> Router {
         // Strategies
         .route("/api/strategies", get(handlers::list_strategies))
         .route("/api/strategies", post(handlers::create_strategy))
+        .route("/api/strategies/templates", get(handlers::list_strategy_templates))
+        .route("/api/strategies/templates", post(handlers::create_strategy_from_template))
+        .route("/api/strategies/match", post(handlers::match_strategies))
+        .route("/api/backtest", post(handlers::run_backtest))
         .route("/api/strategies/:id", get(handlers::get_strategy))
         .route("/api/strategies/:id", put(handlers::update_strategy))
         .route("/api/strategies/:id", delete(handlers::delete_strategy))
         .route("/api/strategies/:id/toggle", post(handlers::toggle_strategy))
+        .route("/api/strategies/:id/history", get(handlers::get_strategy_history))
+        .route("/api/strategies/:id/changelog", get(handlers::get_strategy_changelog))
 
         // Active Strategy Type (for multi-strategy support)
         .route("/api/strategy/active", get(handlers::get_active_strategy_type))
@@ -48,15 +74,44 @@ pub fn create_routes(state: AppState) -> Router {
         .route("/api/autotrader/status", get(handlers::get_autotrader_status))
         .route("/api/autotrader/start", post(handlers::start_autotrader))
         .route("/api/autotrader/stop", post(handlers::stop_autotrader))
+        .route("/api/autotrader/swap-breaker/reset", post(handlers::reset_swap_breaker))
+        .route("/api/config/reload", post(handlers::reload_config))
+        .route("/api/autotrader/loss-breaker/reset", post(handlers::reset_loss_breaker))
+        .route("/api/autotrader/settings", patch(handlers::update_autotrader_settings))
 
         // Token analysis
         .route("/api/analyze", post(handlers::analyze_token))
 
+        // Manual snipe (large amounts require a confirm step)
+        .route("/api/snipe", post(handlers::manual_snipe))
+        .route("/api/snipe/confirm", post(handlers::confirm_manual_snipe))
+
+        // Incidents (escalation of stuck positions / RPC outages)
+        .route("/api/incidents", get(handlers::get_incidents))
+        .route("/api/incidents/:id/ack", post(handlers::acknowledge_incident))
+
+        // Accounting
+        .route("/api/accounting/fifo", get(handlers::get_fifo_accounting))
+
+        // Diagnostics: tiny real round-trip swap (TEST_SWAP_ENABLED)
+        .route("/api/test/swap", post(handlers::run_test_swap))
+
+        // Limit orders
+        .route("/api/orders/limit", get(handlers::list_limit_orders))
+        .route("/api/orders/limit", post(handlers::place_limit_order))
+        .route("/api/orders/limit/:id", delete(handlers::cancel_limit_order))
+
         // Copy Trade - Signals
         .route("/api/signals", get(handlers::get_signals))
         .route("/api/signals/active", get(handlers::get_active_signals))
 
+        // Copy Trade - Source wallets (external traders whose swaps become signals)
+        .route("/api/copy/sources", get(handlers::list_source_wallets))
+        .route("/api/copy/sources", post(handlers::add_source_wallet))
+        .route("/api/copy/sources/:address", delete(handlers::remove_source_wallet))
+
         // Copy Trade - Registration
+        .route("/api/copy/nonce", get(handlers::get_copy_trade_nonce))
         .route("/api/copy/register", post(handlers::register_copy_trader))
         .route("/api/copy/register", delete(handlers::unregister_copy_trader))
 
@@ -78,9 +133,19 @@ pub fn create_routes(state: AppState) -> Router {
         .route("/api/simulation/clear", post(handlers::clear_simulation))
         .route("/api/simulation/close/:id", post(handlers::close_simulated_position))
 
+        // Full state export/import (migrating to a new instance)
+        .route("/api/state/export", get(handlers::export_state))
+        .route("/api/state/import", post(handlers::import_state))
+
         // WebSocket
         .route("/ws", get(ws_handler))
 
+        // 504 for read-only requests stuck on a slow upstream call
+        .layer(middleware::from_fn_with_state(state.clone(), timeout::request_timeout))
+
+        // Token/role check for every route above
+        .layer(middleware::from_fn_with_state(state.clone(), auth::require_role))
+
         // Add state to all routes
         .with_state(state)
 }
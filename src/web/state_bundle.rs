@@ -0,0 +1,121 @@
+//! Full bot state export/import for moving an instance to new hardware
+//!
+//! A bundle carries strategies, positions (open and closed), limit orders and
+//! copy-trade state (traders, signals, copy positions, source wallets) in one JSON
+//! document, plus the exporting instance's config with secrets redacted for reference.
+//!
+//! Not included: the wallet private keys, API keys/tokens and RPC URLs (which often
+//! embed keys), the Telegram session, and derived data such as strategy stats history,
+//! the watchlist and simulated positions. Config is never applied on import; the new
+//! instance is configured through its own environment.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::models::copy_trade::CopyTradeState;
+use crate::trading::limit_orders::LimitOrder;
+use crate::trading::position::Position;
+use crate::trading::strategy::Strategy;
+
+/// Bumped whenever the bundle layout changes incompatibly
+pub const STATE_BUNDLE_VERSION: u32 = 1;
+
+/// Placeholder written over secret config values
+const REDACTED: &str = "[REDACTED]";
+
+/// Config fields that hold keys, tokens, or URLs that may embed them
+const SECRET_CONFIG_FIELDS: &[&str] = &[
+    "solana_rpc_url",
+    "solana_ws_url",
+    "solana_rpc_headers",
+    "solana_rpc_auth_token",
+    "solana_rpc_failover_urls",
+    "solana_private_key",
+    "additional_wallet_private_keys",
+    "helius_api_key",
+    "jupiter_api_key",
+    "birdeye_api_key",
+    "moralis_api_key",
+    "tg_api_id",
+    "tg_api_hash",
+    "tg_phone",
+    "api_admin_token",
+    "api_observer_token",
+    "api_jwt_secret",
+    "realtime_discovery_ws_url",
+    "blocklist_source",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateBundle {
+    pub version: u32,
+    pub app_version: String,
+    pub exported_at: DateTime<Utc>,
+    pub strategies: Vec<Strategy>,
+    pub positions: Vec<Position>,
+    #[serde(default)] // Absent from bundles exported before limit orders were included
+    pub limit_orders: Vec<LimitOrder>,
+    pub copy_trade: CopyTradeState,
+    /// Exporting instance's config, secrets redacted. Informational only.
+    pub config: serde_json::Value,
+}
+
+/// The config as JSON with every secret field that is set replaced by a placeholder
+pub fn redacted_config(config: &Config) -> Result<serde_json::Value> {
+    let mut value = serde_json::to_value(config).context("Failed to serialize config")?;
+    if let Some(fields) = value.as_object_mut() {
+        for name in SECRET_CONFIG_FIELDS {
+            if let Some(field) = fields.get_mut(*name) {
+                let is_set = match field {
+                    serde_json::Value::Null => false,
+                    serde_json::Value::String(s) => !s.is_empty(),
+                    serde_json::Value::Array(a) => !a.is_empty(),
+                    _ => true,
+                };
+                if is_set {
+                    *field = serde_json::Value::String(REDACTED.to_string());
+                }
+            }
+        }
+    }
+    Ok(value)
+}
+
+/// Check a bundle can be imported by this build
+pub fn check_compatible(bundle: &StateBundle) -> Result<()> {
+    if bundle.version != STATE_BUNDLE_VERSION {
+        bail!(
+            "State bundle version {} (from v{}) is not supported; this build reads version {}",
+            bundle.version, bundle.app_version, STATE_BUNDLE_VERSION
+        );
+    }
+    for strategy in &bundle.strategies {
+        strategy.validate()
+            .map_err(|e| anyhow::anyhow!("Strategy '{}' ({}) in bundle is invalid: {}", strategy.name, strategy.id, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_other_versions() {
+        let bundle = StateBundle {
+            version: STATE_BUNDLE_VERSION + 1,
+            app_version: "9.9.9".to_string(),
+            exported_at: Utc::now(),
+            strategies: vec![Strategy::default("s")],
+            positions: Vec::new(),
+            limit_orders: Vec::new(),
+            copy_trade: CopyTradeState::default(),
+            config: serde_json::Value::Null,
+        };
+        assert!(check_compatible(&bundle).is_err());
+        let bundle = StateBundle { version: STATE_BUNDLE_VERSION, ..bundle };
+        assert!(check_compatible(&bundle).is_ok());
+    }
+}
@@ -0,0 +1,44 @@
+//! Per-request timeout for read-only endpoints
+//!
+//! A hung RPC or Birdeye call used to leave the request, and any lock its handler
+//! held, waiting forever. Read-only requests (the ones observers may call) that take
+//! longer than `api_request_timeout_secs` are dropped with 504. Requests that change
+//! state are left to finish: dropping a handler mid-swap could leave a transaction
+//! on chain that the bot never records.
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tokio::time::{timeout, Duration};
+use tracing::warn;
+
+use super::auth::{required_role, Role};
+use super::models::ErrorResponse;
+use super::AppState;
+
+pub async fn request_timeout(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let limit_secs = state.config.api_request_timeout_secs;
+    let read_only = matches!(required_role(req.method(), req.uri().path()), None | Some(Role::Observer));
+    if limit_secs == 0 || !read_only {
+        return next.run(req).await;
+    }
+
+    let route = format!("{} {}", req.method(), req.uri().path());
+    match timeout(Duration::from_secs(limit_secs), next.run(req)).await {
+        Ok(response) => response,
+        Err(_) => {
+            warn!("{} timed out after {}s", route, limit_secs);
+            (
+                StatusCode::GATEWAY_TIMEOUT,
+                Json(ErrorResponse {
+                    error: "Request timed out".to_string(),
+                    details: Some(format!("No response within {}s (API_REQUEST_TIMEOUT_SECS)", limit_secs)),
+                }),
+            ).into_response()
+        }
+    }
+}
@@ -10,9 +10,14 @@ use axum::{
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::broadcast::error::RecvError;
 use tracing::{debug, error, info, warn};
 
 use super::AppState;
+use crate::trading::pnl_report::ReportPeriod;
+use crate::trading::position::PositionPriceTick;
 
 /// WebSocket message types broadcast to clients
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +55,13 @@ pub enum WsMessage {
         timestamp: DateTime<Utc>,
     },
 
+    /// Live price and unrealized PnL of every active position, sent every
+    /// `position_price_stream_secs` from the position monitor
+    PositionPrices {
+        positions: Vec<PositionPriceTick>,
+        timestamp: DateTime<Utc>,
+    },
+
     /// AutoTrader status changed
     StatusChange {
         running: bool,
@@ -75,12 +87,233 @@ pub enum WsMessage {
         timestamp: DateTime<Utc>,
     },
 
+    /// High-priority incident needing operator attention (repeats until acknowledged)
+    Escalation {
+        incident_id: String,
+        kind: String,
+        message: String,
+        notify_count: u32,
+        first_seen: DateTime<Utc>,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// A limit order filled, expired, failed or was cancelled
+    LimitOrderUpdate {
+        id: String,
+        token_address: String,
+        token_symbol: String,
+        status: String,
+        target_price_sol: f64,
+        amount_sol: f64,
+        signature: Option<String>,
+        error: Option<String>,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// The periodic dust sweep sold leftover token balances back to SOL and/or
+    /// closed orphaned token accounts
+    DustSwept {
+        swept_tokens: usize,
+        reclaimed_sol: f64,
+        skipped_tokens: usize,
+        burned_tokens: usize,
+        closed_accounts: usize,
+        reclaimed_rent_sol: f64,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// A position fell `peak_drawdown_alert_percent` from its high (early warning,
+    /// separate from the trailing stop exit)
+    DrawdownAlert {
+        position_id: String,
+        token_address: String,
+        token_symbol: String,
+        drawdown_percent: f64,
+        highest_price_sol: f64,
+        price_sol: f64,
+        sold_percent: Option<f64>,
+        sold_value_sol: Option<f64>,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// A strategy stopped taking new trades: its budget is fully deployed or it has
+    /// `max_concurrent_positions` open. Sent once until a buy gets through again.
+    StrategyCapacity {
+        strategy_id: String,
+        strategy_name: String,
+        limit: String, // "max_concurrent_positions" or "budget"
+        open_positions: usize,
+        max_concurrent_positions: u32,
+        deployed_sol: f64,
+        budget_sol: f64,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// The loss circuit breaker paused buys (`tripped`) or its cooldown ran out.
+    /// `strategy_id` is None for the daily drawdown pause covering every strategy.
+    LossBreaker {
+        strategy_id: Option<String>,
+        reason: String, // "consecutive_losses" or "daily_drawdown"
+        tripped: bool,
+        consecutive_losses: u32,
+        daily_pnl_sol: f64,
+        paused_until: Option<DateTime<Utc>>,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// State the bot came up in after a start/restart
+    StartupDigest {
+        message: String,
+        mode: String,
+        real_mode: bool,
+        open_positions: usize,
+        open_value_sol: f64,
+        trading_running: bool,
+        enabled_strategies: Vec<String>,
+        wallet_balance_sol: Option<f64>,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// Scheduled daily/weekly PnL summary of closed trades
+    PnlReport {
+        message: String,
+        period: ReportPeriod,
+        trades: usize,
+        win_rate_percent: f64,
+        pnl_sol: f64,
+        pnl_usd: Option<f64>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+
+    /// This client fell behind and `missed` updates were dropped; it should
+    /// refetch current state (positions, status) over the REST API
+    Resync {
+        missed: u64,
+        timestamp: DateTime<Utc>,
+    },
+
     /// Heartbeat/ping message
     Ping {
         timestamp: DateTime<Utc>,
     },
 }
 
+impl WsMessage {
+    /// Alerts worth holding for the next client when nobody is connected. Price ticks
+    /// and pings are stale by then; the startup digest is replayed separately.
+    pub fn should_queue(&self) -> bool {
+        !matches!(
+            self,
+            WsMessage::Ping { .. } | WsMessage::PriceUpdate { .. } | WsMessage::PositionPrices { .. } | WsMessage::StartupDigest { .. } | WsMessage::Resync { .. }
+        )
+    }
+
+    /// Cap the free-text fields of notification messages at `max_chars` (0 = no cap)
+    pub fn with_capped_text(self, max_chars: usize) -> Self {
+        match self {
+            WsMessage::Error { message, details, timestamp } => WsMessage::Error {
+                message: cap_message_length(&message, max_chars),
+                details: details.map(|d| cap_message_length(&d, max_chars)),
+                timestamp,
+            },
+            WsMessage::StartupDigest { message, mode, real_mode, open_positions, open_value_sol, trading_running, enabled_strategies, wallet_balance_sol, timestamp } => WsMessage::StartupDigest {
+                message: cap_message_length(&message, max_chars),
+                mode,
+                real_mode,
+                open_positions,
+                open_value_sol,
+                trading_running,
+                enabled_strategies,
+                wallet_balance_sol,
+                timestamp,
+            },
+            WsMessage::PnlReport { message, period, trades, win_rate_percent, pnl_sol, pnl_usd, from, to } => WsMessage::PnlReport {
+                message: cap_message_length(&message, max_chars),
+                period,
+                trades,
+                win_rate_percent,
+                pnl_sol,
+                pnl_usd,
+                from,
+                to,
+            },
+            WsMessage::Escalation { incident_id, kind, message, notify_count, first_seen, timestamp } => WsMessage::Escalation {
+                incident_id,
+                kind,
+                message: cap_message_length(&message, max_chars),
+                notify_count,
+                first_seen,
+                timestamp,
+            },
+            other => other,
+        }
+    }
+}
+
+/// Notifications sent while no client was connected (relay down, dashboard closed),
+/// delivered to the next client that connects. Bounded: once full, the oldest are
+/// dropped and logged.
+pub struct NotificationQueue {
+    max: usize,
+    queue: Mutex<VecDeque<WsMessage>>,
+}
+
+impl NotificationQueue {
+    pub fn new(max: usize) -> Self {
+        Self { max, queue: Mutex::new(VecDeque::new()) }
+    }
+
+    /// Hold a message for later delivery; returns the message evicted to make room
+    pub fn push(&self, msg: WsMessage) -> Option<WsMessage> {
+        if self.max == 0 {
+            return Some(msg);
+        }
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        let evicted = if queue.len() >= self.max { queue.pop_front() } else { None };
+        queue.push_back(msg);
+        evicted
+    }
+
+    /// Take every held message, oldest first
+    pub fn drain(&self) -> Vec<WsMessage> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner()).drain(..).collect()
+    }
+}
+
+/// Shorten `text` to at most `max_chars` characters. Multi-line text is cut at a
+/// line boundary with an "...and N more" footer so list-style messages stay readable;
+/// a single overlong line is cut mid-line with an ellipsis.
+pub fn cap_message_length(text: &str, max_chars: usize) -> String {
+    if max_chars == 0 || text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let lines: Vec<&str> = text.lines().collect();
+    let mut out = String::new();
+    let mut used = 0;
+    for (i, line) in lines.iter().enumerate() {
+        let footer = format!("\n...and {} more", lines.len() - i);
+        let line_len = line.chars().count() + usize::from(!out.is_empty());
+        if used + line_len + footer.chars().count() > max_chars {
+            if !out.is_empty() {
+                out.push_str(&footer);
+                return out;
+            }
+            break;
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(line);
+        used += line_len;
+    }
+
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
 /// WebSocket upgrade handler
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
@@ -106,9 +339,38 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
         let _ = sender.send(Message::Text(json.into())).await;
     }
 
+    // Clients that connect after startup still get the startup digest
+    if let Some(digest) = state.startup_digest.lock().await.clone() {
+        if let Ok(json) = serde_json::to_string(&digest) {
+            let _ = sender.send(Message::Text(json.into())).await;
+        }
+    }
+
+    // Deliver alerts sent while no client was connected
+    let queued = state.notification_queue.drain();
+    if !queued.is_empty() {
+        info!("Delivering {} notifications queued while no client was connected", queued.len());
+    }
+    for msg in queued {
+        if let Ok(json) = serde_json::to_string(&msg) {
+            if sender.send(Message::Text(json.into())).await.is_err() {
+                break;
+            }
+        }
+    }
+
     // Spawn task to forward broadcast messages to this client
     let mut send_task = tokio::spawn(async move {
-        while let Ok(msg) = rx.recv().await {
+        loop {
+            let msg = match rx.recv().await {
+                Ok(msg) => msg,
+                // A slow client overran the channel; tell it to refetch instead of silently missing updates
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("WebSocket client lagged and missed {} messages; asking it to resync", missed);
+                    WsMessage::Resync { missed, timestamp: Utc::now() }
+                }
+                Err(RecvError::Closed) => break,
+            };
             match serde_json::to_string(&msg) {
                 Ok(json) => {
                     if sender.send(Message::Text(json.into())).await.is_err() {
@@ -161,3 +423,49 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
 
     info!("WebSocket connection closed");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(message: &str) -> WsMessage {
+        WsMessage::Error { message: message.to_string(), details: None, timestamp: Utc::now() }
+    }
+
+    #[test]
+    fn queue_drops_oldest_when_full() {
+        let queue = NotificationQueue::new(2);
+        assert!(queue.push(error("a")).is_none());
+        assert!(queue.push(error("b")).is_none());
+        assert!(matches!(queue.push(error("c")), Some(WsMessage::Error { message, .. }) if message == "a"));
+
+        let held: Vec<String> = queue.drain().into_iter().map(|m| match m {
+            WsMessage::Error { message, .. } => message,
+            _ => unreachable!(),
+        }).collect();
+        assert_eq!(held, vec!["b", "c"]);
+        assert!(queue.drain().is_empty());
+    }
+
+    #[test]
+    fn long_lists_are_cut_at_a_line_with_a_footer() {
+        let text = "line one\nline two\nline three\nline four";
+        assert_eq!(cap_message_length(text, 33), "line one\nline two\n...and 2 more");
+        // The footer counts the line that didn't fit too
+        assert_eq!(cap_message_length(text, 30), "line one\n...and 3 more");
+        assert!(cap_message_length(text, 30).chars().count() <= 30);
+        assert_eq!(cap_message_length(text, 100), text);
+    }
+
+    #[test]
+    fn an_overlong_line_is_cut_mid_line() {
+        assert_eq!(cap_message_length("abcdefghij", 5), "abcd…");
+        // Also when it's the first of several lines, leaving nothing to put a footer under
+        assert_eq!(cap_message_length("abcdefghij\nk", 8), "abcdefg…");
+    }
+
+    #[test]
+    fn zero_max_chars_leaves_text_uncapped() {
+        assert_eq!(cap_message_length("abcdefghij\nk", 0), "abcdefghij\nk");
+    }
+}
@@ -7,6 +7,7 @@ const API = {
     // Configuration
     baseUrl: null,
     demoMode: false,
+    tokenStorageKey: 'trader-tony-api-token',
 
     /**
      * Initialize the API client
@@ -27,6 +28,45 @@ const API = {
         console.log(`[API] Initialized with base URL: ${this.baseUrl}`);
         console.log(`[API] Window API_BASE_URL: ${window.API_BASE_URL}`);
         console.log(`[API] Hostname: ${window.location.hostname}`);
+
+        this.loadTokenFromUrl();
+    },
+
+    /**
+     * Save a token passed as ?token= in the dashboard URL (API_ADMIN_TOKEN,
+     * API_OBSERVER_TOKEN or a JWT), then drop it from the address bar
+     */
+    loadTokenFromUrl() {
+        const params = new URLSearchParams(window.location.search);
+        const token = params.get('token');
+        if (!token) {
+            return;
+        }
+        this.setToken(token);
+        params.delete('token');
+        const query = params.toString();
+        window.history.replaceState(null, '', `${window.location.pathname}${query ? `?${query}` : ''}${window.location.hash}`);
+        console.log('[API] Stored API token from URL');
+    },
+
+    /**
+     * API token sent with every request, if one has been set
+     * @returns {string|null}
+     */
+    getToken() {
+        return localStorage.getItem(this.tokenStorageKey);
+    },
+
+    /**
+     * Set (or with an empty value, clear) the API token
+     * @param {string|null} token
+     */
+    setToken(token) {
+        if (token) {
+            localStorage.setItem(this.tokenStorageKey, token);
+        } else {
+            localStorage.removeItem(this.tokenStorageKey);
+        }
     },
 
     /**
@@ -58,9 +98,11 @@ const API = {
         }
 
         const url = `${this.baseUrl}${endpoint}`;
+        const token = this.getToken();
         const defaultOptions = {
             headers: {
                 'Content-Type': 'application/json',
+                ...(token ? { 'Authorization': `Bearer ${token}` } : {}),
             },
         };
 
@@ -253,11 +295,19 @@ const API = {
         return this.get('/api/signals/active');
     },
 
+    /**
+     * Get the one-time message a wallet signs to register or unregister
+     * @param {string} walletAddress - User's wallet address
+     */
+    async getCopyTradeNonce(walletAddress) {
+        return this.get(`/api/copy/nonce?wallet=${walletAddress}`);
+    },
+
     /**
      * Register wallet for copy trading
      * @param {string} walletAddress - User's wallet address
      * @param {string} signature - Signed message for verification
-     * @param {string} message - Original signed message
+     * @param {string} message - Original signed message (from getCopyTradeNonce)
      */
     async registerCopyTrader(walletAddress, signature, message) {
         return this.post('/api/copy/register', {
@@ -270,10 +320,14 @@ const API = {
     /**
      * Unregister from copy trading
      * @param {string} walletAddress - User's wallet address
+     * @param {string} signature - Signed message for verification
+     * @param {string} message - Original signed message (from getCopyTradeNonce)
      */
-    async unregisterCopyTrader(walletAddress) {
+    async unregisterCopyTrader(walletAddress, signature, message) {
         return this.delete('/api/copy/register', {
             wallet_address: walletAddress,
+            signature,
+            message,
         });
     },
 
@@ -660,6 +714,17 @@ const API = {
             };
         }
 
+        // Copy trade nonce
+        if (endpoint.startsWith('/api/copy/nonce')) {
+            const wallet = new URLSearchParams(endpoint.split('?')[1]).get('wallet');
+            return {
+                wallet_address: wallet,
+                nonce: 'demo',
+                message: `TraderTony V4 Copy Trade Registration\nWallet: ${wallet}\nNonce: demo\nIssued: ${new Date().toISOString()}`,
+                expires_at: new Date(Date.now() + 300000).toISOString(),
+            };
+        }
+
         // Copy trade status
         if (endpoint.startsWith('/api/copy/status')) {
             return {
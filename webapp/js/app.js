@@ -948,7 +948,7 @@ const App = {
             const verification = await WalletManager.generateVerification();
 
             // Register with backend
-            await API.registerCopyTrader(verification.publicKey, verification.signature);
+            await API.registerCopyTrader(verification.publicKey, verification.signature, verification.message);
 
             this.showToast('Copy trading enabled!', 'success');
         } catch (error) {
@@ -356,24 +356,21 @@ const WalletManager = {
     // ==========================================
 
     /**
-     * Generate and sign a verification message for copy trade registration
-     * @returns {Promise<object>} Wallet address and signature
+     * Sign the server's one-time message for copy trade registration
+     * @returns {Promise<object>} Wallet address, signed message and signature
      */
     async generateVerification() {
         if (!this.connected) {
             throw new Error('Wallet not connected');
         }
 
-        const timestamp = Date.now();
-        const message = `TraderTony V4 Copy Trade Registration\nWallet: ${this.publicKey}\nTimestamp: ${timestamp}`;
-
+        const { message } = await API.getCopyTradeNonce(this.publicKey);
         const signature = await this.signMessage(message);
 
         return {
             publicKey: this.publicKey,
             message,
             signature,
-            timestamp,
         };
     },
 };
@@ -71,7 +71,11 @@ const WebSocketClient = {
         console.log(`[WebSocket] Connecting to ${this.url}...`);
 
         try {
-            this.socket = new WebSocket(this.url);
+            // Browsers can't set headers on the upgrade, so the API token goes in the query
+            const token = typeof API !== 'undefined' ? API.getToken() : null;
+            const separator = this.url.includes('?') ? '&' : '?';
+            const url = token ? `${this.url}${separator}token=${encodeURIComponent(token)}` : this.url;
+            this.socket = new WebSocket(url);
             this.setupEventListeners();
         } catch (error) {
             console.error('[WebSocket] Connection error:', error);